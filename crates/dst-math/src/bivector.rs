@@ -100,6 +100,13 @@ impl BivectorRotation {
     pub fn new(i: f64, j: f64, k: f64) -> Self {
         Self { i, j, k }
     }
+
+    /// Returns the rotation angle, the Euclidean magnitude of the rotation bivector.
+    pub fn norm(&self) -> f64 {
+        self.i
+            .mul_add(self.i, self.j.mul_add(self.j, self.k * self.k))
+            .sqrt()
+    }
 }
 
 impl ExpBoost {
@@ -107,6 +114,20 @@ impl ExpBoost {
     pub fn new(scalar: f64, i: f64, j: f64, k: f64) -> Self {
         Self { scalar, i, j, k }
     }
+
+    /// Recovers the boost bivector this versor is the exponential of; inverse of
+    /// [`BivectorBoost::exp`].
+    pub fn log(&self) -> BivectorBoost {
+        let sinh = self
+            .i
+            .mul_add(self.i, self.j.mul_add(self.j, self.k * self.k))
+            .sqrt();
+        if sinh == 0.0 {
+            return BivectorBoost::new(0.0, 0.0, 0.0);
+        }
+        let ratio = sinh.asinh() / sinh;
+        BivectorBoost::new(self.i * ratio, self.j * ratio, self.k * ratio)
+    }
 }
 
 impl ExpRotation {
@@ -129,3 +150,147 @@ impl VersorRotation {
         Self { theta, vx, vy, vz }
     }
 }
+
+/// Spinor form `a + b·σ` of a proper Lorentz transformation, with complex `a` and `b` in the
+/// Pauli algebra. Real vector parts generate boosts and imaginary vector parts rotations;
+/// products compose transformations, the right factor acting first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LorentzRotor {
+    /// Real parts of the scalar and σx, σy, σz coefficients.
+    pub re: [f64; 4],
+    /// Imaginary parts of the scalar and σx, σy, σz coefficients.
+    pub im: [f64; 4],
+}
+
+impl LorentzRotor {
+    pub const IDENTITY: Self = Self {
+        re: [1.0, 0.0, 0.0, 0.0],
+        im: [0.0; 4],
+    };
+
+    /// Pure boost by the rapidity bivector `boost`.
+    pub fn from_boost(boost: BivectorBoost) -> Self {
+        let phi = boost.norm();
+        if phi == 0.0 {
+            return Self::IDENTITY;
+        }
+        let half = 0.5 * phi;
+        let ratio = half.sinh() / phi;
+        Self {
+            re: [
+                half.cosh(),
+                boost.i * ratio,
+                boost.j * ratio,
+                boost.k * ratio,
+            ],
+            im: [0.0; 4],
+        }
+    }
+
+    /// Pure rotation by `rotation.norm()` radians about its direction, right-handed.
+    pub fn from_rotation(rotation: BivectorRotation) -> Self {
+        let theta = rotation.norm();
+        if theta == 0.0 {
+            return Self::IDENTITY;
+        }
+        let half = 0.5 * theta;
+        let ratio = -half.sin() / theta;
+        Self {
+            re: [half.cos(), 0.0, 0.0, 0.0],
+            im: [
+                0.0,
+                rotation.i * ratio,
+                rotation.j * ratio,
+                rotation.k * ratio,
+            ],
+        }
+    }
+
+    /// Inverse transformation, `a − b·σ`; exact for rotors of unit determinant.
+    pub fn inverse(&self) -> Self {
+        Self {
+            re: [self.re[0], -self.re[1], -self.re[2], -self.re[3]],
+            im: [self.im[0], -self.im[1], -self.im[2], -self.im[3]],
+        }
+    }
+
+    /// Hermitian conjugate: every coefficient conjugated.
+    fn dagger(&self) -> Self {
+        Self {
+            re: self.re,
+            im: self.im.map(|c| -c),
+        }
+    }
+
+    /// Splits the transformation into a boost applied after a rotation,
+    /// `self = from_boost(b) * from_rotation(r)`. The rotation angle is in `[0, π]`.
+    pub fn decompose(&self) -> (BivectorBoost, BivectorRotation) {
+        // The boost is the square root of self·self†, which has no rotation part.
+        let h = *self * self.dagger();
+        let c = (0.5 * (h.re[0] + 1.0)).sqrt();
+        let s = [h.re[1], h.re[2], h.re[3]].map(|x| x / (2.0 * c));
+        let sinh = s[0].mul_add(s[0], s[1].mul_add(s[1], s[2] * s[2])).sqrt();
+        let boost_rotor = Self {
+            re: [c, s[0], s[1], s[2]],
+            im: [0.0; 4],
+        };
+        let boost = if sinh == 0.0 {
+            BivectorBoost::new(0.0, 0.0, 0.0)
+        } else {
+            let ratio = 2.0 * sinh.asinh() / sinh;
+            BivectorBoost::new(s[0] * ratio, s[1] * ratio, s[2] * ratio)
+        };
+        let mut rotation_rotor = boost_rotor.inverse() * *self;
+        // A rotor and its negative describe the same rotation; keep the shorter angle.
+        if rotation_rotor.re[0] < 0.0 {
+            rotation_rotor.re = rotation_rotor.re.map(|x| -x);
+            rotation_rotor.im = rotation_rotor.im.map(|x| -x);
+        }
+        let axis = [
+            -rotation_rotor.im[1],
+            -rotation_rotor.im[2],
+            -rotation_rotor.im[3],
+        ];
+        let sin = axis[0]
+            .mul_add(axis[0], axis[1].mul_add(axis[1], axis[2] * axis[2]))
+            .sqrt();
+        let rotation = if sin == 0.0 {
+            BivectorRotation::new(0.0, 0.0, 0.0)
+        } else {
+            let ratio = 2.0 * sin.atan2(rotation_rotor.re[0]) / sin;
+            BivectorRotation::new(axis[0] * ratio, axis[1] * ratio, axis[2] * ratio)
+        };
+        (boost, rotation)
+    }
+}
+
+impl std::ops::Mul for LorentzRotor {
+    type Output = Self;
+    /// Composes two transformations: `rhs` first, then `self`.
+    fn mul(self, rhs: Self) -> Self {
+        let mul =
+            |(ar, ai): (f64, f64), (br, bi): (f64, f64)| (ar * br - ai * bi, ar * bi + ai * br);
+        let a = |n: usize| (self.re[n], self.im[n]);
+        let b = |n: usize| (rhs.re[n], rhs.im[n]);
+        let mut out = Self {
+            re: [0.0; 4],
+            im: [0.0; 4],
+        };
+        for n in 0..4 {
+            let (re, im) = mul(a(n), b(n));
+            out.re[0] += re;
+            out.im[0] += im;
+        }
+        for n in 1..4 {
+            let (p, q) = (n % 3 + 1, (n + 1) % 3 + 1);
+            let (re0, im0) = mul(a(0), b(n));
+            let (re1, im1) = mul(a(n), b(0));
+            let (pq_re, pq_im) = mul(a(p), b(q));
+            let (qp_re, qp_im) = mul(a(q), b(p));
+            // The cross-product term carries a factor of i.
+            out.re[n] = re0 + re1 - (pq_im - qp_im);
+            out.im[n] = im0 + im1 + (pq_re - qp_re);
+        }
+        out
+    }
+}
@@ -1,5 +1,6 @@
 //! DST gravity: Newtonian potential drives oscillating time delay via cos(λ_eff).
 
+use crate::summation::{ScalarSum, Summation, Vec3Sum};
 use glam::DVec3;
 
 /// Scaling constant k = 2/c² in simulation units (c_sim = c/scale).
@@ -28,6 +29,30 @@ pub fn newtonian_gravity_pair(
     (phi_j, acceleration)
 }
 
+/// Weak-field gravitoelectromagnetic velocity change of `i` toward `j`: the Newtonian pull
+/// plus the gravitomagnetic term `(4/c²) v_i × (v_j × r̂)`, which weakens the pull between
+/// co-moving masses and strengthens it between counter-moving ones.
+#[allow(clippy::too_many_arguments)]
+pub fn gravitomagnetic_pair(
+    pos_i: DVec3,
+    vel_i: DVec3,
+    pos_j: DVec3,
+    vel_j: DVec3,
+    mass_j: f64,
+    time_g: f64,
+    inverse_light_speed_sq: f64,
+    epsilon: f64,
+) -> DVec3 {
+    let diff = pos_j - pos_i;
+    let distance_sq = diff.length_squared();
+    if distance_sq < epsilon {
+        return DVec3::ZERO;
+    }
+    let direction = diff / distance_sq.sqrt();
+    let magnetic = vel_i.cross(vel_j.cross(direction)) * (4.0 * inverse_light_speed_sq);
+    time_g * mass_j / distance_sq * (direction + magnetic)
+}
+
 /// Newtonian gravitational potential at particle `i`: Φ = -Σ G m_j / (|r_ij| + ε).
 pub fn gravitational_potential_at(
     i: usize,
@@ -71,19 +96,46 @@ pub fn dst_gravity_step_at(
     k_scale: f64,
     epsilon: f64,
     delta_seconds: f64,
+) -> (DVec3, f64, f64) {
+    dst_gravity_step_at_with(
+        i,
+        positions,
+        masses,
+        g,
+        time_g,
+        k_scale,
+        epsilon,
+        delta_seconds,
+        Summation::Naive,
+    )
+}
+
+/// [`dst_gravity_step_at`] with the given accumulation strategy for Φ and the acceleration.
+#[allow(clippy::too_many_arguments)]
+pub fn dst_gravity_step_at_with(
+    i: usize,
+    positions: &[DVec3],
+    masses: &[f64],
+    g: f64,
+    time_g: f64,
+    k_scale: f64,
+    epsilon: f64,
+    delta_seconds: f64,
+    summation: Summation,
 ) -> (DVec3, f64, f64) {
     let pos_i = positions[i];
-    let mut phi = 0.0;
-    let mut acceleration = DVec3::ZERO;
+    let mut phi = ScalarSum::new(summation);
+    let mut acceleration = Vec3Sum::new(summation);
     for (j, &pos_j) in positions.iter().enumerate() {
         if j == i {
             continue;
         }
         let (phi_j, accel_j) = newtonian_gravity_pair(pos_i, pos_j, masses[j], g, time_g, epsilon);
-        phi += phi_j;
-        acceleration += accel_j;
+        phi.add(phi_j);
+        acceleration.add(accel_j);
     }
-    let lambda_eff = k_scale * phi;
+    let acceleration = acceleration.value();
+    let lambda_eff = k_scale * phi.value();
     let dilation = time_dilation(lambda_eff);
     (
         gravity_sign_from_time_dilation(dilation) * acceleration,
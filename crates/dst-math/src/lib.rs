@@ -6,3 +6,4 @@ pub mod gravity;
 pub mod pga;
 pub mod s3_galaxy;
 pub mod spacetime;
+pub mod summation;
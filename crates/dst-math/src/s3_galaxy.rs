@@ -1,5 +1,6 @@
 //! S³ galaxy gravity via Ln (log map) on unit quaternions.
 
+use crate::summation::{Summation, Vec3Sum};
 use glam::{DQuat, DVec3};
 
 /// Fixed galaxy radius in light years (observation-derived constant).
@@ -112,16 +113,38 @@ pub fn galaxy_gravity_step_at_orientations(
     r_galaxy: f64,
     time_g: f64,
     epsilon: f64,
+) -> DVec3 {
+    galaxy_gravity_step_at_orientations_with(
+        i,
+        orientations,
+        masses,
+        r_galaxy,
+        time_g,
+        epsilon,
+        Summation::Naive,
+    )
+}
+
+/// [`galaxy_gravity_step_at_orientations`] with the given accumulation strategy.
+pub fn galaxy_gravity_step_at_orientations_with(
+    i: usize,
+    orientations: &[DQuat],
+    masses: &[f64],
+    r_galaxy: f64,
+    time_g: f64,
+    epsilon: f64,
+    summation: Summation,
 ) -> DVec3 {
     let q_i = orientations[i];
-    orientations
-        .iter()
-        .enumerate()
-        .filter(|(j, _)| *j != i)
-        .map(|(j, &q_j)| {
-            galaxy_gravity_pair_ln(q_i, q_j, masses[j], r_galaxy, time_g, epsilon)
-        })
-        .sum()
+    let mut total = Vec3Sum::new(summation);
+    for (j, &q_j) in orientations.iter().enumerate() {
+        if j != i {
+            total.add(galaxy_gravity_pair_ln(
+                q_i, q_j, masses[j], r_galaxy, time_g, epsilon,
+            ));
+        }
+    }
+    total.value()
 }
 
 /// Total chart-space gravity at particle i from all neighbors.
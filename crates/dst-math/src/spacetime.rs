@@ -1,5 +1,6 @@
 use glam::{DMat4, DVec3, DVec4};
 use std::f64;
+use std::ops::{Div, Mul, Neg};
 
 const EPSILON: f64 = 1e-10;
 
@@ -163,6 +164,17 @@ impl Spacetime {
         self.x * self.x + self.y * self.y + self.z * self.z - self.t * self.t
     }
 
+    /// Minkowski inner product with the same (-,+,+,+) signature as [`Self::norm`].
+    pub fn dot(&self, other: Spacetime) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z - self.t * other.t
+    }
+
+    /// Returns this value scaled to unit [`Self::abs`], or unchanged when it is null.
+    pub fn normalized(&self) -> Self {
+        let a = self.abs();
+        if a == 0.0 { *self } else { *self / a }
+    }
+
     /// Returns the spacetime conjugate that negates only the temporal component.
     pub fn conjugated(&self) -> Self {
         Self::new(-self.t, self.x, self.y, self.z)
@@ -202,7 +214,10 @@ impl Spacetime {
         DVec3::new(v * versor_angle.x, v * versor_angle.y, v * versor_angle.z)
     }
 
-    /// Applies a Lorentz transformation represented as a spacetime versor.
+    /// Applies a Lorentz transformation represented as a spacetime versor. For the unit
+    /// versor `(cosh(a/2), n sinh(a/2))` this is the boost of rapidity `a` along `n`,
+    /// `t' = γ(t + β n·x)` and `x' = x + ((γ - 1)(n·x) + γβt) n`; the velocity and rapidity
+    /// helpers below build it for a boost by `+v`.
     #[inline(always)]
     pub fn apply_lorentz_transform(&mut self, boost_versor: Spacetime) {
         let p = boost_versor.t;
@@ -226,9 +241,9 @@ impl Spacetime {
         let s_z = s * z;
 
         self.t = (pp + qq + rr + ss) * w + 2.0 * p * (q_x + r_y + s_z);
-        self.x = (pp + qq - rr - ss) * x + 2.0 * q * (p_w - r_y - s_z);
-        self.y = (pp - qq + rr - ss) * y + 2.0 * r * (p_w - q_x - s_z);
-        self.z = (pp - qq - rr + ss) * z + 2.0 * s * (p_w - q_x - r_y);
+        self.x = (pp + qq - rr - ss) * x + 2.0 * q * (p_w + r_y + s_z);
+        self.y = (pp - qq + rr - ss) * y + 2.0 * r * (p_w + q_x + s_z);
+        self.z = (pp - qq - rr + ss) * z + 2.0 * s * (p_w + q_x + r_y);
     }
 
     /// Applies a Lorentz transformation from velocity and inverse light speed.
@@ -274,6 +289,44 @@ impl std::fmt::Display for Spacetime {
     }
 }
 
+impl Mul<f64> for Spacetime {
+    type Output = Self;
+    /// Scales every component by `rhs`.
+    fn mul(self, rhs: f64) -> Self {
+        Self::new(self.t * rhs, self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl Div<f64> for Spacetime {
+    type Output = Self;
+    /// Divides every component by `rhs`.
+    fn div(self, rhs: f64) -> Self {
+        Self::new(self.t / rhs, self.x / rhs, self.y / rhs, self.z / rhs)
+    }
+}
+
+impl Neg for Spacetime {
+    type Output = Self;
+    /// Negates every component.
+    fn neg(self) -> Self {
+        Self::new(-self.t, -self.x, -self.y, -self.z)
+    }
+}
+
+impl From<DVec3> for Spacetime {
+    /// Same as [`Spacetime::from_vector3`].
+    fn from(v: DVec3) -> Self {
+        Self::from_vector3(v)
+    }
+}
+
+impl From<Spacetime> for [f64; 4] {
+    /// Components in t, x, y, z order.
+    fn from(s: Spacetime) -> Self {
+        [s.t, s.x, s.y, s.z]
+    }
+}
+
 /// Lorentz boost as a 4×4 matrix acting on `(t, x, y, z)` column vectors.
 ///
 /// `inverse_light_speed` is `1/c`. Returns an error when `|v|/c >= 1` or `γ` is non-finite.
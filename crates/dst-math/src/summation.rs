@@ -0,0 +1,98 @@
+//! Pairwise-force accumulation with optional Kahan–Babuška (Neumaier) compensation.
+
+use glam::DVec3;
+
+/// How per-particle force sums are accumulated.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Summation {
+    /// Plain `+=` accumulation.
+    #[default]
+    Naive,
+    /// Neumaier-compensated accumulation: rounding error of each addition is carried in a
+    /// separate term, so a few heavy neighbors do not swamp many light ones.
+    Compensated,
+}
+
+impl Summation {
+    pub const ALL: [Self; 2] = [Self::Naive, Self::Compensated];
+}
+
+impl std::fmt::Display for Summation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Summation::Naive => "Naive",
+            Summation::Compensated => "Compensated",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// Running scalar sum.
+#[derive(Clone, Copy, Debug)]
+pub struct ScalarSum {
+    sum: f64,
+    compensation: f64,
+    compensated: bool,
+}
+
+impl ScalarSum {
+    pub fn new(summation: Summation) -> Self {
+        Self {
+            sum: 0.0,
+            compensation: 0.0,
+            compensated: summation == Summation::Compensated,
+        }
+    }
+
+    pub fn add(&mut self, value: f64) {
+        if !self.compensated {
+            self.sum += value;
+            return;
+        }
+        let t = self.sum + value;
+        self.compensation += if self.sum.abs() >= value.abs() {
+            (self.sum - t) + value
+        } else {
+            (value - t) + self.sum
+        };
+        self.sum = t;
+    }
+
+    pub fn value(&self) -> f64 {
+        self.sum + self.compensation
+    }
+}
+
+/// Running vector sum, compensated per component.
+#[derive(Clone, Copy, Debug)]
+pub struct Vec3Sum {
+    sum: DVec3,
+    compensation: DVec3,
+    compensated: bool,
+}
+
+impl Vec3Sum {
+    pub fn new(summation: Summation) -> Self {
+        Self {
+            sum: DVec3::ZERO,
+            compensation: DVec3::ZERO,
+            compensated: summation == Summation::Compensated,
+        }
+    }
+
+    pub fn add(&mut self, value: DVec3) {
+        if !self.compensated {
+            self.sum += value;
+            return;
+        }
+        let t = self.sum + value;
+        let sum_larger = self.sum.abs().cmpge(value.abs());
+        self.compensation +=
+            DVec3::select(sum_larger, (self.sum - t) + value, (value - t) + self.sum);
+        self.sum = t;
+    }
+
+    pub fn value(&self) -> DVec3 {
+        self.sum + self.compensation
+    }
+}
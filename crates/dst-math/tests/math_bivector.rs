@@ -1,4 +1,4 @@
-use dst_math::bivector::BivectorBoost;
+use dst_math::bivector::{BivectorBoost, BivectorRotation, LorentzRotor};
 
 #[test]
 fn norm_squared_matches_norm_squared() {
@@ -42,3 +42,39 @@ fn from_velocity_consistent_with_manual_phi() {
     assert!((b.j - inv * vy).abs() < 1e-9);
     assert!((b.k - inv * vz).abs() < 1e-9);
 }
+
+#[test]
+fn rotor_decompose_recovers_boost_and_rotation() {
+    let boost = BivectorBoost::new(0.3, -0.2, 0.5);
+    let rotation = BivectorRotation::new(0.1, 0.7, -0.4);
+    let rotor = LorentzRotor::from_boost(boost) * LorentzRotor::from_rotation(rotation);
+    let (b, r) = rotor.decompose();
+    assert!((b.i - boost.i).abs() < 1e-12);
+    assert!((b.j - boost.j).abs() < 1e-12);
+    assert!((b.k - boost.k).abs() < 1e-12);
+    assert!((r.i - rotation.i).abs() < 1e-12);
+    assert!((r.j - rotation.j).abs() < 1e-12);
+    assert!((r.k - rotation.k).abs() < 1e-12);
+}
+
+#[test]
+fn collinear_boosts_compose_without_rotation() {
+    let a = LorentzRotor::from_boost(BivectorBoost::new(0.4, 0.0, 0.0));
+    let b = LorentzRotor::from_boost(BivectorBoost::new(0.7, 0.0, 0.0));
+    let (boost, rotation) = (a * b).decompose();
+    assert!((boost.i - 1.1).abs() < 1e-12);
+    assert!(rotation.norm() < 1e-12);
+}
+
+#[test]
+fn perpendicular_boosts_compose_to_wigner_rotation() {
+    // Boosting along x, then along y, rotates the frame about z; the angle follows from
+    // tan(θ/2) = sinh(a/2) sinh(b/2) / (cosh(a/2) cosh(b/2)).
+    let (a, b) = (0.8, 1.2);
+    let x = LorentzRotor::from_boost(BivectorBoost::new(a, 0.0, 0.0));
+    let y = LorentzRotor::from_boost(BivectorBoost::new(0.0, b, 0.0));
+    let (_, rotation) = (y * x).decompose();
+    let expected = 2.0 * ((0.5 * a).tanh() * (0.5 * b).tanh()).atan();
+    assert!(rotation.i.abs() < 1e-12 && rotation.j.abs() < 1e-12);
+    assert!((rotation.k.abs() - expected).abs() < 1e-12);
+}
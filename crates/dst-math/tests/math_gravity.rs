@@ -1,7 +1,7 @@
 use dst_math::gravity::{
-    dst_gravity_step_at, gravitational_potential_at, gravity_sign_from_time_dilation,
-    k_scale_from_light_speed, newtonian_gravity_pair, time_dilation,
-    update_time_delay_for_particle,
+    dst_gravity_step_at, gravitational_potential_at, gravitomagnetic_pair,
+    gravity_sign_from_time_dilation, k_scale_from_light_speed, newtonian_gravity_pair,
+    time_dilation, update_time_delay_for_particle,
 };
 use glam::DVec3;
 
@@ -61,6 +61,21 @@ fn newtonian_gravity_pair_matches_separate_formulas() {
     assert!((accel - expected_accel).length() < 1e-6 * expected_accel.length().max(1.0));
 }
 
+#[test]
+fn gravitomagnetic_pair_weakens_co_moving_and_strengthens_counter_moving_pull() {
+    let pos_j = DVec3::new(0.0, 1.0, 0.0);
+    let v = DVec3::new(0.1, 0.0, 0.0);
+    let pull = |vel_i: DVec3, vel_j: DVec3| {
+        gravitomagnetic_pair(DVec3::ZERO, vel_i, pos_j, vel_j, 1.0, 1.0, 1.0, EPSILON)
+    };
+    let (_, newtonian) = newtonian_gravity_pair(DVec3::ZERO, pos_j, 1.0, 1.0, 1.0, EPSILON);
+
+    assert!((pull(DVec3::ZERO, v) - newtonian).length() < 1e-15);
+    assert!((pull(v, v).y - (1.0 - 4.0 * 0.01)).abs() < 1e-12);
+    assert!((pull(v, -v).y - (1.0 + 4.0 * 0.01)).abs() < 1e-12);
+    assert!(pull(v, v).x.abs() < 1e-15 && pull(v, -v).x.abs() < 1e-15);
+}
+
 #[test]
 fn dst_gravity_step_at_single_pass_matches_potential_and_sign() {
     let scale = 1e10_f64;
@@ -0,0 +1,70 @@
+//! Property-based invariants across the math modules.
+
+use dst_math::biquaternion::Biquaternion;
+use dst_math::bivector::BivectorBoost;
+use dst_math::spacetime::Spacetime;
+use glam::DVec3;
+use proptest::prelude::*;
+
+fn event() -> impl Strategy<Value = Spacetime> {
+    (-10.0..10.0, -10.0..10.0, -10.0..10.0, -10.0..10.0)
+        .prop_map(|(t, x, y, z)| Spacetime::new(t, x, y, z))
+}
+
+/// Velocities below 0.9 c for c = 1.
+fn velocity() -> impl Strategy<Value = DVec3> {
+    (-1.0..1.0, -1.0..1.0, -1.0..1.0, 0.0..0.9)
+        .prop_map(|(x, y, z, speed)| DVec3::new(x, y, z).normalize_or(DVec3::X) * speed)
+}
+
+fn biquaternion() -> impl Strategy<Value = Biquaternion> {
+    (-2.0..2.0, prop::array::uniform15(-2.0..2.0))
+        .prop_map(|(real, bases)| Biquaternion::new(real, bases))
+}
+
+proptest! {
+    #[test]
+    fn lorentz_transform_preserves_minkowski_norm(st in event(), v in velocity()) {
+        let mut boosted = st;
+        boosted.apply_lorentz_transform_by_velocity(v, 1.0);
+        let scale = st.t * st.t + st.x * st.x + st.y * st.y + st.z * st.z;
+        prop_assert!((boosted.norm() - st.norm()).abs() <= 1e-9 * (1.0 + scale));
+    }
+
+    #[test]
+    fn parallel_boosts_add_rapidities(
+        st in event(),
+        b1 in -0.9f64..0.9,
+        b2 in -0.9f64..0.9,
+        axis in (-1.0f64..1.0, -1.0f64..1.0, -1.0f64..1.0),
+    ) {
+        let dir = DVec3::new(axis.0, axis.1, axis.2).normalize_or(DVec3::X);
+        let mut twice = st;
+        twice.apply_lorentz_transform_by_velocity(dir * b1, 1.0);
+        twice.apply_lorentz_transform_by_velocity(dir * b2, 1.0);
+        let combined = (b1.atanh() + b2.atanh()).tanh();
+        let mut once = st;
+        once.apply_lorentz_transform_by_velocity(dir * combined, 1.0);
+        for i in 0..4 {
+            prop_assert!((once.get(i) - twice.get(i)).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn boost_bivector_exp_log_round_trips(i in -3.0..3.0, j in -3.0..3.0, k in -3.0..3.0) {
+        let b = BivectorBoost::new(i, j, k);
+        let back = b.exp().log();
+        prop_assert!((back.i - i).abs() < 1e-9);
+        prop_assert!((back.j - j).abs() < 1e-9);
+        prop_assert!((back.k - k).abs() < 1e-9);
+    }
+
+    #[test]
+    fn biquaternion_multiplication_is_associative(
+        a in biquaternion(),
+        b in biquaternion(),
+        c in biquaternion(),
+    ) {
+        prop_assert!(((a * b) * c).max_abs_diff(&(a * (b * c))) < 1e-9);
+    }
+}
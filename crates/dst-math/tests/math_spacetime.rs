@@ -2,8 +2,7 @@
 
 use dst_math::spacetime::{
     Spacetime, lorentz_boost_matrix_from_velocity, momentum_from_velocity,
-    position_delta_from_momentum, rapidity_from_momentum, rapidity_vector,
-    velocity_from_momentum,
+    position_delta_from_momentum, rapidity_from_momentum, rapidity_vector, velocity_from_momentum,
 };
 use glam::{DMat4, DVec3, DVec4};
 
@@ -115,6 +114,27 @@ fn apply_lorentz_transform_with_identity_spacetime_is_noop() {
     assert!(st.fuzzy_compare(original));
 }
 
+#[test]
+fn apply_lorentz_transform_by_velocity_boosts_along_an_oblique_direction() {
+    let v = DVec3::new(0.0, -0.357, -0.306);
+    let mut st = Spacetime::new(0.0, 0.0, 6.66, 7.81);
+    let original = st;
+    st.apply_lorentz_transform_by_velocity(v, 1.0);
+
+    let beta = v.length();
+    let n = v / beta;
+    let gamma = 1.0 / (1.0 - beta * beta).sqrt();
+    let x = DVec3::new(original.x, original.y, original.z);
+    let n_x = n.dot(x);
+    let t = gamma * (original.t + beta * n_x);
+    let boosted = x + ((gamma - 1.0) * n_x + gamma * beta * original.t) * n;
+    assert!((st.t - t).abs() < 1e-12);
+    assert!((st.x - boosted.x).abs() < 1e-12);
+    assert!((st.y - boosted.y).abs() < 1e-12);
+    assert!((st.z - boosted.z).abs() < 1e-12);
+    assert!((st.norm() - original.norm()).abs() < 1e-12);
+}
+
 #[test]
 fn exp_versor_matches_exp_vector_form() {
     let a = 0.37;
@@ -123,3 +143,28 @@ fn exp_versor_matches_exp_vector_form() {
     let e2 = Spacetime::exp(a, v);
     assert!(e1.fuzzy_compare(e2));
 }
+
+#[test]
+fn scalar_ops_and_conversions() {
+    let st = Spacetime::new(1.0, -2.0, 3.0, -4.0);
+    assert_eq!(st * 2.0, Spacetime::new(2.0, -4.0, 6.0, -8.0));
+    assert_eq!(st / 2.0, Spacetime::new(0.5, -1.0, 1.5, -2.0));
+    assert_eq!(-st, Spacetime::new(-1.0, 2.0, -3.0, 4.0));
+    assert_eq!(
+        Spacetime::from(DVec3::new(1.0, 2.0, 3.0)),
+        Spacetime::new(0.0, 1.0, 2.0, 3.0)
+    );
+    let a: [f64; 4] = st.into();
+    assert_eq!(a, [1.0, -2.0, 3.0, -4.0]);
+}
+
+#[test]
+fn dot_matches_norm_and_normalized_has_unit_abs() {
+    let st = Spacetime::new(5.0, 1.0, 2.0, 3.0);
+    assert_eq!(st.dot(st), st.norm());
+    assert!((st.normalized().abs() - 1.0).abs() < 1e-12);
+    assert_eq!(
+        Spacetime::new(1.0, 1.0, 0.0, 0.0).normalized(),
+        Spacetime::new(1.0, 1.0, 0.0, 0.0)
+    );
+}
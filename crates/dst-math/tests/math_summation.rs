@@ -0,0 +1,64 @@
+use dst_math::gravity::{dst_gravity_step_at, dst_gravity_step_at_with};
+use dst_math::summation::{ScalarSum, Summation, Vec3Sum};
+use glam::DVec3;
+
+#[test]
+fn compensated_scalar_sum_recovers_cancelled_term() {
+    let values = [1.0e16, 1.0, -1.0e16];
+    let mut naive = ScalarSum::new(Summation::Naive);
+    let mut compensated = ScalarSum::new(Summation::Compensated);
+    for v in values {
+        naive.add(v);
+        compensated.add(v);
+    }
+    assert_eq!(naive.value(), 0.0);
+    assert_eq!(compensated.value(), 1.0);
+}
+
+#[test]
+fn compensated_vector_sum_works_per_component() {
+    let mut sum = Vec3Sum::new(Summation::Compensated);
+    sum.add(DVec3::new(1.0e16, 1.0, -3.0));
+    sum.add(DVec3::new(1.0, 1.0e16, 0.5));
+    sum.add(DVec3::new(-1.0e16, -1.0e16, 0.5));
+    assert_eq!(sum.value(), DVec3::new(1.0, 1.0, -2.0));
+}
+
+#[test]
+fn dst_step_with_naive_summation_matches_default() {
+    let positions = [
+        DVec3::ZERO,
+        DVec3::new(1.0e11, 0.0, 0.0),
+        DVec3::new(0.0, 3.0e11, 0.0),
+    ];
+    let masses = [1.0e30, 2.0e24, 5.0e27];
+    let args = (6.6743e-11, 6.6743e-11 * 60.0, 1.0e-17, 1e-10, 60.0);
+    let default = dst_gravity_step_at(
+        0, &positions, &masses, args.0, args.1, args.2, args.3, args.4,
+    );
+    let naive = dst_gravity_step_at_with(
+        0,
+        &positions,
+        &masses,
+        args.0,
+        args.1,
+        args.2,
+        args.3,
+        args.4,
+        Summation::Naive,
+    );
+    let compensated = dst_gravity_step_at_with(
+        0,
+        &positions,
+        &masses,
+        args.0,
+        args.1,
+        args.2,
+        args.3,
+        args.4,
+        Summation::Compensated,
+    );
+    assert_eq!(default, naive);
+    assert!((compensated.0 - naive.0).length() <= 1e-12 * naive.0.length());
+    assert!((compensated.1 - naive.1).abs() <= 1e-12 * naive.1.abs());
+}
@@ -4,6 +4,10 @@ use std::path::Path;
 use std::process::Command;
 
 fn main() {
+    // Only the renderer of the `gui` feature loads SPIR-V.
+    if env::var_os("CARGO_FEATURE_GUI").is_none() {
+        return;
+    }
     let shader_dir = Path::new("src/shaders");
     let out_dir = env::var("OUT_DIR").unwrap();
     let spv_dir = Path::new(&out_dir).join("shaders");
@@ -15,7 +19,16 @@ fn main() {
         "particles_vertex_ssbo.vert",
         "particles_fragment.frag",
         "particles_sphere_fragment.frag",
+        "particles_sprite_fragment.frag",
         "particles_compute.comp",
+        "particles_cull.comp",
+        "particles_depth_keys.comp",
+        "particles_sort_histogram.comp",
+        "particles_sort_scan.comp",
+        "particles_sort_scatter.comp",
+        "fullscreen_triangle.vert",
+        "accumulation_fade.frag",
+        "accumulation_composite.frag",
         "egui_vertex.vert",
         "egui_fragment.frag",
         "selection_marker_vertex.vert",
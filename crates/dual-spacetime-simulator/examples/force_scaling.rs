@@ -0,0 +1,96 @@
+//! Benchmark of the direct force loop's thread scaling: times one velocity update of the
+//! untiled loop, the tiled loop, and the tiled loop on threads pinned node by node, at
+//! thread counts doubling up to every CPU, and reports each one's speedup over its own
+//! single-thread time. Past about 16 threads the untiled loop tends to stall on shared
+//! cache and cross-socket bandwidth, which the tiles and the pinning are there to relieve.
+//!
+//! cargo run --release -p dual-spacetime-simulator --example force_scaling -- [N]
+
+use dst_math::summation::Summation;
+use dual_spacetime_simulator::force_tiles::{
+    direct_velocity_deltas, force_thread_pool, numa_node_count, tiled_velocity_deltas,
+};
+use glam::DVec3;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::time::Instant;
+
+const DEFAULT_SIZE: usize = 65_536;
+/// Runs per configuration; the fastest is reported.
+const REPEATS: u32 = 3;
+
+type Kernel = fn(&[DVec3], &[f64], f64, Summation) -> Vec<DVec3>;
+
+/// Thread counts 1, 2, 4, … up to `cpus`, which is always included.
+fn thread_counts(cpus: usize) -> Vec<usize> {
+    let mut counts: Vec<usize> = (0..)
+        .map(|power| 1usize << power)
+        .take_while(|&count| count < cpus)
+        .collect();
+    counts.push(cpus);
+    counts
+}
+
+/// Fastest of [`REPEATS`] runs of `kernel` on `threads` workers, in milliseconds.
+fn best_time(
+    positions: &[DVec3],
+    masses: &[f64],
+    threads: usize,
+    pin: bool,
+    kernel: Kernel,
+) -> f64 {
+    let pool = force_thread_pool(threads, pin);
+    (0..REPEATS)
+        .map(|_| {
+            let started = Instant::now();
+            pool.install(|| kernel(positions, masses, 1.0, Summation::Naive));
+            started.elapsed().as_secs_f64() * 1e3
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+fn main() {
+    let n = std::env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(DEFAULT_SIZE);
+    let mut rng = StdRng::seed_from_u64(1);
+    let positions: Vec<DVec3> = (0..n)
+        .map(|_| {
+            DVec3::new(
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+            )
+        })
+        .collect();
+    let masses = vec![1e10; n];
+    let cpus = num_cpus::get();
+    println!(
+        "N = {}, {} CPUs on {} NUMA node(s)",
+        n,
+        cpus,
+        numa_node_count()
+    );
+    println!(
+        "{:>8}  {:>18} {:>18} {:>18}",
+        "threads", "untiled ms (×)", "tiled ms (×)", "tiled+pinned ms (×)"
+    );
+    let configurations: [(bool, Kernel); 3] = [
+        (false, direct_velocity_deltas),
+        (false, tiled_velocity_deltas),
+        (true, tiled_velocity_deltas),
+    ];
+    let mut single = [0.0; 3];
+    for threads in thread_counts(cpus) {
+        let mut row = format!("{:>8}", threads);
+        for (k, &(pin, kernel)) in configurations.iter().enumerate() {
+            let ms = best_time(&positions, &masses, threads, pin, kernel);
+            if threads == 1 {
+                single[k] = ms;
+            }
+            row += &format!("  {:>10.1} ({:>4.1})", ms, single[k] / ms);
+        }
+        println!("{}", row);
+    }
+}
@@ -0,0 +1,89 @@
+//! Benchmark of the approximate force solvers: times one velocity update of the
+//! Barnes–Hut tree and of the FMM at several expansion orders on uniform balls of growing
+//! size, and reports each one's RMS relative force error against direct summation.
+//!
+//! cargo run --release -p dual-spacetime-simulator --example force_solvers -- [N ...]
+
+use dual_spacetime_simulator::fast_multipole::{fmm_relative_force_error, fmm_velocity_update};
+use dual_spacetime_simulator::simulation::{Particle, Summation};
+use dual_spacetime_simulator::tree_gravity::{
+    DEFAULT_OPENING_ANGLE, FORCE_ERROR_SAMPLE_SIZE, relative_force_error, sample_indices,
+    tree_velocity_update,
+};
+use glam::DVec3;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::time::Instant;
+
+const DEFAULT_SIZES: [usize; 4] = [2_000, 8_000, 32_000, 128_000];
+const ORDERS: [u32; 4] = [2, 4, 6, 8];
+/// Runs per solver; the fastest is reported.
+const REPEATS: u32 = 3;
+
+/// Equal-mass particles spread uniformly through a unit ball, the same on every run.
+fn ball(count: usize) -> Vec<Particle> {
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut particles = Vec::with_capacity(count);
+    while particles.len() < count {
+        let position = DVec3::new(
+            rng.random_range(-1.0..1.0),
+            rng.random_range(-1.0..1.0),
+            rng.random_range(-1.0..1.0),
+        );
+        if position.length_squared() <= 1.0 {
+            particles.push(Particle::from_kinematics(
+                position,
+                DVec3::ZERO,
+                1e10,
+                [1.0; 4],
+            ));
+        }
+    }
+    particles
+}
+
+/// Fastest of [`REPEATS`] runs of `step` on fresh copies of `particles`, in milliseconds.
+fn best_time(particles: &[Particle], step: impl Fn(&mut [Particle])) -> f64 {
+    (0..REPEATS)
+        .map(|_| {
+            let mut copy = particles.to_vec();
+            let started = Instant::now();
+            step(&mut copy);
+            started.elapsed().as_secs_f64() * 1e3
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+fn main() {
+    let sizes: Vec<usize> = std::env::args()
+        .skip(1)
+        .filter_map(|arg| arg.parse().ok())
+        .collect();
+    let sizes = if sizes.is_empty() {
+        DEFAULT_SIZES.to_vec()
+    } else {
+        sizes
+    };
+    println!(
+        "{:>8}  {:<12} {:>10} {:>10}",
+        "N", "solver", "ms/step", "error"
+    );
+    for n in sizes {
+        let particles = ball(n);
+        let sample = sample_indices(n, FORCE_ERROR_SAMPLE_SIZE, 1);
+        let ms = best_time(&particles, |p| {
+            tree_velocity_update(p, 1.0, DEFAULT_OPENING_ANGLE, Summation::Naive)
+        });
+        let error = relative_force_error(&particles, DEFAULT_OPENING_ANGLE, &sample);
+        let name = format!("tree θ={}", DEFAULT_OPENING_ANGLE);
+        println!("{:>8}  {:<12} {:>10.2} {:>10.2e}", n, name, ms, error);
+        for order in ORDERS {
+            let ms = best_time(&particles, |p| {
+                fmm_velocity_update(p, 1.0, order, Summation::Naive)
+            });
+            let error = fmm_relative_force_error(&particles, order, &sample);
+            let name = format!("fmm p={}", order);
+            println!("{:>8}  {:<12} {:>10.2} {:>10.2e}", n, name, ms, error);
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use glam::DVec3;
+use serde::{Deserialize, Serialize};
+
+/// Offset in points from an annotated point to the bottom-left corner of its label; the
+/// leader line spans it.
+pub const LEADER_OFFSET: [f32; 2] = [24.0, -24.0];
+/// Vertical gap in points kept between labels stacked to avoid overlapping.
+const LABEL_SPACING: f32 = 2.0;
+
+/// What an annotation is pinned to.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum AnnotationTarget {
+    /// Follows the particle with this ID.
+    Particle(u64),
+    /// Stays at a fixed position in simulation units.
+    Position(DVec3),
+}
+
+impl AnnotationTarget {
+    pub fn is_particle(self) -> bool {
+        matches!(self, AnnotationTarget::Particle(_))
+    }
+}
+
+/// A user-placed text label, such as "Earth" on a particle or "L4" at a point.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Annotation {
+    pub text: String,
+    pub target: AnnotationTarget,
+    /// Index the target particle was last found at, checked first on the next lookup.
+    #[serde(skip)]
+    pub index_hint: usize,
+}
+
+impl Annotation {
+    pub fn new(text: String, target: AnnotationTarget) -> Self {
+        Self {
+            text,
+            target,
+            index_hint: 0,
+        }
+    }
+}
+
+/// Places each label of `size` above and to the right of its `anchor`, moving it further
+/// up while it would overlap a label placed before it. Returns the top-left corners in
+/// the order of `labels`; all values are screen coordinates in points.
+pub fn place_labels(labels: &[([f32; 2], [f32; 2])]) -> Vec<[f32; 2]> {
+    let mut placed: Vec<([f32; 2], [f32; 2])> = Vec::with_capacity(labels.len());
+    for &(anchor, size) in labels {
+        let mut corner = [
+            anchor[0] + LEADER_OFFSET[0],
+            anchor[1] + LEADER_OFFSET[1] - size[1],
+        ];
+        // Each move lands strictly above the label it cleared, so this terminates.
+        while let Some(&(other, _)) = placed
+            .iter()
+            .find(|(other, other_size)| overlaps(corner, size, *other, *other_size))
+        {
+            corner[1] = other[1] - size[1] - LABEL_SPACING;
+        }
+        placed.push((corner, size));
+    }
+    placed.into_iter().map(|(corner, _)| corner).collect()
+}
+
+/// Returns whether two rectangles given by top-left corner and size overlap.
+fn overlaps(a: [f32; 2], a_size: [f32; 2], b: [f32; 2], b_size: [f32; 2]) -> bool {
+    a[0] < b[0] + b_size[0]
+        && b[0] < a[0] + a_size[0]
+        && a[1] < b[1] + b_size[1]
+        && b[1] < a[1] + a_size[1]
+}
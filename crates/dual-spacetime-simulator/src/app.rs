@@ -0,0 +1,1454 @@
+use crate::crash_report::{install_panic_hook, take_crash_report};
+#[cfg(feature = "gamepad")]
+use crate::gamepad::{GamepadAction, Gamepads, apply_gamepad_camera};
+use crate::gpu_simulation::{ExternalForces, GpuAdvance};
+use crate::integration::Gui;
+use crate::object_input::ObjectInput;
+use crate::pipeline::ParticleRenderPipeline;
+use crate::repaint::{RepaintSchedule, schedule_repaint};
+use crate::settings::AppSettings;
+use crate::simulation::SimulationManager;
+use crate::simulation_worker::{SimulationWorker, WorkerHandles};
+use crate::taskbar::Taskbar;
+use crate::trace_follow::compute_trace_follow_distance_limits;
+use crate::tutorial::TutorialAction;
+use crate::ui::{
+    draw_ui, process_batch_job, process_checkpoint, process_due_maneuvers, process_energy_monitor,
+    process_event_triggers, process_grid_alignment, process_light_cone_update,
+    process_mass_profile_update, process_memory_budget, process_metric_sampling,
+    process_minimap_update, process_orbit_preview_update, process_pending_batch_export,
+    process_pending_determinism_audit, process_pending_display_mode, process_pending_engine_switch,
+    process_pending_fit_view, process_pending_font_dialog, process_pending_group_finder,
+    process_pending_group_recolor, process_pending_live_rescale, process_pending_metrics_export,
+    process_pending_particle_delete, process_pending_plot_export, process_pending_power_spectrum,
+    process_pending_power_spectrum_export, process_pending_region_action,
+    process_pending_snapshot_dialog, process_pending_still_render,
+    process_pending_trajectory_start, process_pending_undo, process_pending_verification,
+    process_phase_space_update, process_run_end_snapshot, process_thomas_precession,
+    process_trajectory_recording, process_verification_job, process_worldline_recording,
+    resolve_observer_view, resolve_trace_particle_for_camera,
+};
+use crate::ui_state::{DragOwner, PendingSnapshotDialog, SimulationType, UiState};
+use crate::undo_history::UndoDirection;
+use crate::window_icon::{WINDOW_ICON_SIZE, window_icon_rgba};
+use crate::{GALAXY_CULL_INTERVAL, GpuParticleSync};
+use ash::vk;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use vulkanvil::{
+    InputState, VulkanBase, apply_camera_mouse_wheel, spacecraft_scene_wheel_allowed,
+    tick_orbit_camera, tick_spacecraft_steer_and_motion_from_anchors,
+    toggle_spacecraft_steer_anchor as toggle_steer_anchor,
+};
+use winit::{
+    application::ApplicationHandler,
+    dpi::PhysicalPosition,
+    error::EventLoopError,
+    event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent},
+    event_loop::{ActiveEventLoop, ControlFlow, EventLoop},
+    keyboard::{KeyCode, PhysicalKey},
+    window::Window,
+};
+
+const DOUBLE_CLICK_MILLIS: u64 = 400;
+const DOUBLE_CLICK_DIST: f64 = 25.0;
+const DEFAULT_WINDOW_WIDTH: f32 = 1280.0;
+const DEFAULT_WINDOW_HEIGHT: f32 = 800.0;
+/// DST Galaxy, GPU path: minimum dead slots before threshold-gated compaction.
+/// Compacting stalls the GPU pipeline (device-idle wait), so it only pays off once
+/// enough slots can be reclaimed; below this, dead particles stay parked in their
+/// slots (massless and invisible) until the forced interval fires.
+const GALAXY_COMPACT_MIN_DEAD: usize = 64;
+/// DST Galaxy, GPU path: advancing frames between unconditional dead-slot
+/// compactions, so stragglers are reclaimed even when the threshold is never met.
+const GALAXY_COMPACT_INTERVAL: u32 = 10_000;
+
+/// Run the desktop application (window + Vulkan + UI loop).
+pub fn run() -> Result<(), EventLoopError> {
+    if let Ok(dir) = AppSettings::app_dir() {
+        install_panic_hook(dir);
+    }
+    let event_loop = EventLoop::new()?;
+    let mut app = App::default();
+    app.simulation_worker = Some(SimulationWorker::start(app.worker_handles()));
+    event_loop.run_app(&mut app)
+}
+
+/// Builds the window title from crate name and version metadata.
+fn generate_window_title() -> String {
+    let package_name = env!("CARGO_PKG_NAME");
+    let package_version = env!("CARGO_PKG_VERSION");
+    format!("{} v{}", package_name, package_version)
+}
+
+/// Builds the window and taskbar icon.
+fn window_icon() -> Option<winit::window::Icon> {
+    let rgba = window_icon_rgba(WINDOW_ICON_SIZE);
+    winit::window::Icon::from_rgba(rgba, WINDOW_ICON_SIZE, WINDOW_ICON_SIZE)
+        .map_err(|e| eprintln!("Failed to create the window icon: {}", e))
+        .ok()
+}
+
+/// Returns whether `event` is a press, wheel turn or touch by the user; cursor motion alone
+/// does not count, so a nudged mouse does not end kiosk mode.
+fn is_user_input(event: &WindowEvent) -> bool {
+    match event {
+        WindowEvent::KeyboardInput { event, .. } => event.state == ElementState::Pressed,
+        WindowEvent::MouseInput { state, .. } => *state == ElementState::Pressed,
+        WindowEvent::MouseWheel { .. } | WindowEvent::Touch(_) => true,
+        _ => false,
+    }
+}
+
+pub struct App {
+    // Drop order matters: gui and pipeline must be dropped before vulkan_base
+    gui: Option<Gui>,
+    render_pipeline: Option<ParticleRenderPipeline>,
+    vulkan_base: Option<VulkanBase>,
+    window: Option<Arc<Window>>,
+    ui_state: Arc<RwLock<UiState>>,
+    simulation_manager: Arc<RwLock<SimulationManager>>,
+    need_redraw: Arc<RwLock<bool>>,
+    skip_redraw: Arc<RwLock<u32>>,
+    gpu_particle_sync: GpuParticleSync,
+    simulation_worker: Option<SimulationWorker>,
+    mouse_left_down: bool,
+    mouse_right_down: bool,
+    mouse_middle_down: bool,
+    last_cursor_position: Option<(f64, f64)>,
+    last_right_click_time: Option<Instant>,
+    last_right_click_pos: Option<(f64, f64)>,
+    settings: AppSettings,
+    drag_owner: DragOwner,
+    input: InputState,
+    last_camera_tick: Option<Instant>,
+    last_lock_camera_up: Option<bool>,
+    /// When the last frame was drawn; paces idle redraws.
+    last_redraw: Option<Instant>,
+    #[cfg(feature = "gamepad")]
+    gamepads: Option<Gamepads>,
+    taskbar: Option<Taskbar>,
+    /// Accumulated GPU advance steps since the last DST Galaxy dead-slot scan.
+    gpu_cull_accumulated_steps: u32,
+    /// Accumulated GPU advance steps since the last DST Galaxy compaction; drives
+    /// the unconditional garbage-collect interval.
+    gpu_forced_compact_steps: u32,
+}
+
+impl Drop for App {
+    /// Waits for all pending GPU work to finish before dropping GUI and pipeline resources.
+    ///
+    /// The last frame's command buffer may still be executing on the GPU when the event loop
+    /// exits (e.g. via CloseRequested or request_exit). The gui (egui renderer) and
+    /// render_pipeline own Vulkan resources (vertex buffers, textures, descriptor sets,
+    /// pipelines, etc.) that are referenced by in-flight command buffers.
+    ///
+    /// Destroying them before the work completes violates Vulkan's rules and commonly
+    /// results in ERROR_DEVICE_LOST (visible in the Drop impls of ParticleRenderPipeline
+    /// and VulkanBase).
+    ///
+    /// We perform the wait here, while all resources are still alive. The waits inside
+    /// the individual drops will then be fast and succeed.
+    fn drop(&mut self) {
+        if let Some(vb) = &self.vulkan_base {
+            // Ignore error: on shutdown we just want to be as clean as possible.
+            // Real device loss from a bad submit would have been observable earlier too.
+            let _ = unsafe { vb.device.device_wait_idle() };
+        }
+    }
+}
+
+impl Default for App {
+    /// Creates application state with loaded settings and default runtime resources.
+    fn default() -> Self {
+        let settings = AppSettings::load();
+        let mut ui_state = UiState::default();
+        ui_state.apply_settings(&settings);
+        ui_state.crash_report = AppSettings::app_dir()
+            .ok()
+            .and_then(|dir| take_crash_report(&dir));
+        Self {
+            window: None,
+            vulkan_base: None,
+            render_pipeline: None,
+            gui: None,
+            ui_state: Arc::new(RwLock::new(ui_state)),
+            simulation_manager: Arc::new(RwLock::new(SimulationManager::default())),
+            need_redraw: Arc::new(RwLock::new(true)),
+            skip_redraw: Arc::new(RwLock::new(0)),
+            gpu_particle_sync: GpuParticleSync::new(true),
+            simulation_worker: None,
+            mouse_left_down: false,
+            mouse_right_down: false,
+            mouse_middle_down: false,
+            last_cursor_position: None,
+            last_right_click_time: None,
+            last_right_click_pos: None,
+            settings,
+            drag_owner: DragOwner::None,
+            input: InputState::default(),
+            last_camera_tick: None,
+            last_lock_camera_up: None,
+            last_redraw: None,
+            #[cfg(feature = "gamepad")]
+            gamepads: Gamepads::new().map_err(|e| eprintln!("{}", e)).ok(),
+            taskbar: None,
+            gpu_cull_accumulated_steps: 0,
+            gpu_forced_compact_steps: 0,
+        }
+    }
+}
+
+impl ApplicationHandler for App {
+    /// Creates window and graphics resources when the app is resumed by the event loop.
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        let ui_state = self.ui_state.write().unwrap();
+
+        let window_attrs = Window::default_attributes()
+            .with_title(generate_window_title())
+            .with_inner_size(winit::dpi::LogicalSize::new(
+                DEFAULT_WINDOW_WIDTH,
+                DEFAULT_WINDOW_HEIGHT,
+            ))
+            .with_min_inner_size(winit::dpi::LogicalSize::new(
+                ui_state.min_window_width,
+                ui_state.min_window_height,
+            ))
+            .with_window_icon(window_icon());
+        #[cfg(windows)]
+        let window_attrs = {
+            use winit::platform::windows::WindowAttributesExtWindows;
+            window_attrs.with_taskbar_icon(window_icon())
+        };
+        let window = Arc::new(event_loop.create_window(window_attrs).unwrap());
+
+        if self.settings.start_maximized {
+            window.set_maximized(true);
+        }
+
+        let vulkan_base = VulkanBase::new(
+            &window,
+            self.settings.mailbox_present_mode,
+            c"DualSpacetimeSimulator",
+            vk::make_api_version(0, 0, 2, 0),
+        );
+        let mut render_pipeline = ParticleRenderPipeline::new(&vulkan_base);
+        render_pipeline.connect_staging(self.gpu_particle_sync.staging().clone());
+
+        let gui = Gui::new(
+            event_loop,
+            &window,
+            &vulkan_base.instance,
+            vulkan_base.physical_device,
+            vulkan_base.device.clone(),
+            vulkan_base.graphics_queue,
+            vulkan_base.command_pool,
+            render_pipeline.render_pass(),
+            vulkan_base.swapchain_format,
+        );
+
+        self.taskbar = Some(Taskbar::new(&window));
+        self.window = Some(window);
+        self.render_pipeline = Some(render_pipeline);
+        self.vulkan_base = Some(vulkan_base);
+        self.gui = Some(gui);
+
+        let object_input = ObjectInput::default();
+        let add_particle_count = ui_state.add_particle_count;
+        let scale = ui_state.scale;
+        let sim_type = ui_state.active_simulation_type();
+        self.simulation_manager.write().unwrap().reset(
+            object_input,
+            sim_type,
+            add_particle_count,
+            scale,
+        );
+        self.skip_redraw.write().unwrap().clone_from(&ui_state.skip);
+        self.gpu_particle_sync.clear_advance_steps();
+    }
+
+    /// Stops the simulation worker and flushes pending exports before the event loop exits.
+    fn exiting(&mut self, _event_loop: &ActiveEventLoop) {
+        self.shutdown();
+    }
+
+    /// Handles window, input, rendering, and camera events for each platform event.
+    fn window_event(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        _window_id: winit::window::WindowId,
+        event: WindowEvent,
+    ) {
+        let Some(window) = self.window.as_ref() else {
+            return;
+        };
+        let Some(vb) = self.vulkan_base.as_mut() else {
+            return;
+        };
+        let gui = self.gui.as_mut().unwrap();
+        let Some(pipeline) = self.render_pipeline.as_mut() else {
+            return;
+        };
+
+        {
+            let mut ui_state = self.ui_state.write().unwrap();
+            if ui_state.request_exit {
+                ui_state.request_exit = false;
+                event_loop.exit();
+                return;
+            }
+        }
+
+        let lock_camera_up = {
+            let ui_state = self.ui_state.read().unwrap();
+            ui_state.lock_camera_up
+        };
+        pipeline.set_lock_camera_up(lock_camera_up);
+
+        // Any key, button, wheel or touch ends kiosk mode and is not passed on, so the
+        // visitor's first press does not also act on the scene or a panel.
+        if is_user_input(&event) && self.ui_state.write().unwrap().stop_kiosk() {
+            window.request_redraw();
+            return;
+        }
+
+        match &event {
+            WindowEvent::KeyboardInput { event, .. } => {
+                if let PhysicalKey::Code(code) = event.physical_key {
+                    self.input.key_event(code, event.state);
+                    // Pause/Escape shortcuts stay reachable even when heavy draw-skipping
+                    // makes the egui controls hard to click.
+                    if code == KeyCode::Escape && event.state == ElementState::Pressed {
+                        let cleared_anchor = self.ui_state.write().unwrap().apply_escape_shortcut();
+                        if cleared_anchor {
+                            window.request_redraw();
+                        }
+                    } else if code == KeyCode::Pause
+                        && event.state == ElementState::Pressed
+                        && !event.repeat
+                    {
+                        self.ui_state.write().unwrap().is_running ^= true;
+                    }
+                }
+            }
+            WindowEvent::Resized(size) => {
+                if size.width > 0 && size.height > 0 {
+                    vb.recreate_swapchain(window);
+                    pipeline.recreate_framebuffers(vb);
+                }
+            }
+            // Releases that happen while a file dialog has focus never arrive, so forget
+            // held keys instead of letting WASD keep moving the camera.
+            WindowEvent::Focused(false) => self.input = InputState::default(),
+            WindowEvent::ScaleFactorChanged { .. } => {
+                vb.recreate_swapchain(window);
+                pipeline.recreate_framebuffers(vb);
+            }
+            WindowEvent::CloseRequested => {
+                event_loop.exit();
+            }
+            WindowEvent::RedrawRequested => {
+                self.last_redraw = Some(Instant::now());
+                gui.immediate_ui(window, |gui| {
+                    let ctx = gui.context();
+                    draw_ui(
+                        &self.ui_state,
+                        &self.simulation_manager,
+                        Some(pipeline),
+                        &self.gpu_particle_sync,
+                        &mut self.settings,
+                        &ctx,
+                    );
+                });
+                let desired_mailbox_present_mode = {
+                    let ui_state = self.ui_state.read().unwrap();
+                    pipeline.sync_add_center_marker(&ui_state);
+                    pipeline.sync_region_marker(&ui_state);
+                    pipeline.sync_mass_profile_marker(&ui_state);
+                    pipeline.sync_escaper_marker(&ui_state);
+                    pipeline.sync_light_cone(&ui_state);
+                    pipeline.sync_light_speed_sphere(&ui_state);
+                    pipeline.sync_thomas_precession(&ui_state);
+                    pipeline.sync_container_walls(&ui_state);
+                    pipeline.sync_orbit_preview(&ui_state);
+                    pipeline.sync_selection_marker(&ui_state);
+                    ui_state.mailbox_present_mode
+                };
+                if vb.mailbox_present_mode != desired_mailbox_present_mode {
+                    vb.mailbox_present_mode = desired_mailbox_present_mode;
+                    vb.recreate_swapchain(window);
+                    pipeline.recreate_framebuffers(vb);
+                }
+                gui.prepare_frame(window);
+
+                vb.wait_for_fence();
+                pipeline.retire_staging(vb.current_frame);
+
+                let image_index = match vb.acquire_next_image() {
+                    Ok((idx, _)) => idx,
+                    Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => {
+                        vb.recreate_swapchain(window);
+                        pipeline.recreate_framebuffers(vb);
+                        return;
+                    }
+                    Err(e) => panic!("Failed to acquire swapchain image: {:?}", e),
+                };
+
+                vb.reset_fence();
+
+                let cb = vb.current_command_buffer();
+                let begin_ci = vk::CommandBufferBeginInfo::default()
+                    .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT);
+                unsafe {
+                    vb.device
+                        .reset_command_buffer(cb, vk::CommandBufferResetFlags::empty())
+                        .unwrap();
+                    vb.device.begin_command_buffer(cb, &begin_ci).unwrap();
+                }
+                let staged_slot = pipeline.record_staged_upload(cb);
+
+                let ui_state = self.ui_state.read().unwrap();
+                let scale = ui_state.scale_gauge;
+                let link_point_size_to_scale = ui_state.link_point_size_to_scale;
+                let show_grid = ui_state.show_grid;
+                let grid_rotation = ui_state.grid_orientation.shown().as_quat();
+                let minimap_bounds = ui_state.minimap_bounds.filter(|_| ui_state.show_minimap);
+                let split_view = ui_state.split_view;
+                let particle_display_mode = ui_state.particle_display_mode;
+                let gpu_frustum_culling = ui_state.gpu_frustum_culling;
+                let point_budget = ui_state.point_budget;
+                let motion_blur_frames = ui_state.motion_blur_frames;
+                let uses_gpu = ui_state.uses_gpu_simulation();
+                let time_per_frame = ui_state.time_per_frame;
+                let simulation_type = ui_state.active_simulation_type();
+                let sim_scale = ui_state.scale;
+                let engine_config = ui_state.engine_config();
+                let galaxy_cull_enabled = ui_state.galaxy_cull_enabled;
+                let galaxy_cull_max_angle = ui_state.galaxy_cull_max_angle;
+                let forces = ExternalForces {
+                    walls: ui_state.container_walls(),
+                    drag: ui_state.drag_force(),
+                    noise: ui_state.langevin_noise(),
+                };
+                let frame = ui_state.frame as u64;
+                drop(ui_state);
+                pipeline.set_engine_config(engine_config);
+                let observer_view = resolve_observer_view(
+                    &self.ui_state,
+                    &self.simulation_manager,
+                    Some(&*pipeline),
+                );
+
+                let pending_steps = if uses_gpu {
+                    self.gpu_particle_sync.take_advance_steps()
+                } else {
+                    0
+                };
+                // The compute shader marks particles beyond the S³ cull angle dead
+                // in-place (mass 0, invisible) with no CPU-GPU sync. Slot reclamation
+                // below is the only stalling step, so it runs rarely: a periodic
+                // stall-free scan of the mapped SSBO triggers compaction once enough
+                // dead slots accumulated, and a long unconditional interval sweeps up
+                // stragglers that never reach the threshold.
+                if uses_gpu && simulation_type == SimulationType::DstGalaxy && pending_steps > 0 {
+                    self.gpu_cull_accumulated_steps += pending_steps;
+                    self.gpu_forced_compact_steps += pending_steps;
+                    if self.gpu_cull_accumulated_steps >= GALAXY_CULL_INTERVAL {
+                        self.gpu_cull_accumulated_steps = 0;
+                        let forced = self.gpu_forced_compact_steps >= GALAXY_COMPACT_INTERVAL;
+                        let dead = pipeline.count_dead_galaxy_particles();
+                        let total = pipeline.gpu_particle_count() as usize;
+                        let threshold_hit = dead >= GALAXY_COMPACT_MIN_DEAD && dead * 8 >= total;
+                        if threshold_hit || (forced && dead > 0) {
+                            let removed = pipeline.compact_dead_galaxy_particles();
+                            if !removed.is_empty() {
+                                // Mirror onto the CPU list so index correspondence holds
+                                // and culled particles are not resurrected on the next add.
+                                self.simulation_manager
+                                    .write()
+                                    .unwrap()
+                                    .remove_particles_at_sorted(&removed);
+                                self.ui_state
+                                    .write()
+                                    .unwrap()
+                                    .adjust_selection_after_removal(&removed);
+                            }
+                        }
+                        // Any compaction (or a forced pass finding nothing) restarts
+                        // the forced-GC clock.
+                        if threshold_hit || forced {
+                            self.gpu_forced_compact_steps = 0;
+                        }
+                    }
+                }
+                if pending_steps > 0 {
+                    let cull_max_angle =
+                        if galaxy_cull_enabled && simulation_type == SimulationType::DstGalaxy {
+                            galaxy_cull_max_angle as f32
+                        } else {
+                            0.0
+                        };
+                    // The worker already counted the pending steps as frames.
+                    let advance = GpuAdvance {
+                        delta_seconds: time_per_frame,
+                        steps: pending_steps,
+                        first_frame: frame.saturating_sub(u64::from(pending_steps)),
+                    };
+                    pipeline.record_gpu_advance(
+                        cb,
+                        simulation_type,
+                        sim_scale,
+                        cull_max_angle,
+                        advance,
+                        forces,
+                    );
+                }
+
+                pipeline.set_gpu_culling(gpu_frustum_culling);
+                pipeline.set_point_budget(point_budget);
+                pipeline.set_motion_blur_frames(motion_blur_frames);
+                pipeline.set_observer_view(observer_view);
+                pipeline.set_grid_rotation(grid_rotation);
+                pipeline.set_minimap_bounds(minimap_bounds);
+                pipeline.set_split_view(split_view);
+                pipeline.render(
+                    cb,
+                    image_index as usize,
+                    vb.swapchain_extent,
+                    gui,
+                    scale,
+                    link_point_size_to_scale,
+                    show_grid,
+                    particle_display_mode,
+                );
+
+                unsafe {
+                    vb.device.end_command_buffer(cb).unwrap();
+                }
+
+                let presented = vb.submit_and_present(image_index);
+                if let Some(slot) = staged_slot {
+                    pipeline.staged_upload_submitted(slot, vb.current_frame);
+                }
+                match presented {
+                    Ok(true) | Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => {
+                        vb.recreate_swapchain(window);
+                        pipeline.recreate_framebuffers(vb);
+                    }
+                    Ok(false) => {}
+                    Err(e) => panic!("Failed to present: {:?}", e),
+                }
+
+                gui.finish_frame();
+                vb.advance_frame();
+                if uses_gpu {
+                    self.need_redraw.write().unwrap().clone_from(&false);
+                }
+            }
+            _ => (),
+        }
+
+        let window_clone = window.clone();
+        let ui_consumed = gui.update(&window_clone, &event);
+        let ui_wants_pointer = gui.pointer_wants_input();
+
+        match &event {
+            WindowEvent::MouseInput { state, button, .. } => {
+                let pressed = *state == ElementState::Pressed;
+                if pressed {
+                    if ui_wants_pointer || ui_consumed {
+                        self.drag_owner = DragOwner::Ui;
+                        self.clear_mouse_drag_flags();
+                    } else {
+                        if let Some((x, _)) = self.last_cursor_position {
+                            pipeline.focus_view_at(x, window.inner_size().width);
+                        }
+                        match button {
+                            MouseButton::Left => {
+                                self.drag_owner = DragOwner::PendingSceneLeft;
+                                if !self.ui_state.read().unwrap().lock_camera_up {
+                                    if let Some(pos) = self.last_cursor_position {
+                                        Self::toggle_spacecraft_steer_anchor(
+                                            window,
+                                            &self.ui_state,
+                                            pos,
+                                        );
+                                    }
+                                }
+                                self.left_button(state);
+                            }
+                            MouseButton::Right => {
+                                self.drag_owner = DragOwner::PendingSceneRight;
+                                if !self.ui_state.read().unwrap().lock_camera_up {
+                                    if let Some(pos) = self.last_cursor_position {
+                                        Self::sync_spacecraft_yaw_steer_anchor(
+                                            window,
+                                            &self.ui_state,
+                                            Some(pos),
+                                        );
+                                    }
+                                }
+                                self.right_button(state);
+                            }
+                            MouseButton::Middle => {
+                                self.drag_owner = DragOwner::PendingSceneMiddle;
+                                self.middle_button(state);
+                            }
+                            _ => {}
+                        }
+                    }
+                } else {
+                    match button {
+                        MouseButton::Left => {
+                            let lock_camera_up = self.ui_state.read().unwrap().lock_camera_up;
+                            let is_scene_click =
+                                matches!(self.drag_owner, DragOwner::PendingSceneLeft);
+                            self.left_button(state);
+                            if is_scene_click && lock_camera_up {
+                                self.try_pick_particle();
+                            }
+                        }
+                        MouseButton::Right => {
+                            let lock_camera_up = self.ui_state.read().unwrap().lock_camera_up;
+                            if !lock_camera_up {
+                                Self::sync_spacecraft_yaw_steer_anchor(
+                                    window,
+                                    &self.ui_state,
+                                    None,
+                                );
+                            }
+                            self.right_button(state);
+                        }
+                        MouseButton::Middle => {
+                            let is_scene_click =
+                                matches!(self.drag_owner, DragOwner::PendingSceneMiddle);
+                            self.middle_button(state);
+                            if is_scene_click {
+                                let mut ui = self.ui_state.write().unwrap();
+                                ui.lock_camera_up = !ui.lock_camera_up;
+                            }
+                        }
+                        _ => {}
+                    }
+                    self.drag_owner = DragOwner::None;
+                }
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                let (x, y) = (position.x, position.y);
+                let ui_blocks = ui_wants_pointer || ui_consumed;
+                if let Some(new_owner) = self.drag_owner.promote_from_pending(ui_blocks) {
+                    if new_owner == DragOwner::Ui {
+                        self.mouse_left_down = false;
+                        self.mouse_right_down = false;
+                        self.mouse_middle_down = false;
+                    }
+                    self.drag_owner = new_owner;
+                }
+                let lock_camera_up = self.ui_state.read().unwrap().lock_camera_up;
+                if let Some((lx, ly)) = self.last_cursor_position {
+                    match self.drag_owner {
+                        DragOwner::SceneLeft if lock_camera_up => {
+                            pipeline.revolve_camera(x - lx, y - ly);
+                        }
+                        DragOwner::SceneLeft => {}
+                        DragOwner::SceneRight if lock_camera_up => {
+                            pipeline.look_around(x - lx, y - ly);
+                        }
+                        DragOwner::SceneMiddle if lock_camera_up => {
+                            let window_size = window.inner_size();
+                            let (center_x, center_y) = pipeline
+                                .focused_view_rect(window_size.width, window_size.height)
+                                .center();
+                            pipeline.rotate_camera(x, lx, y, ly, center_x, center_y);
+                        }
+                        DragOwner::None
+                        | DragOwner::Ui
+                        | DragOwner::SceneMiddle
+                        | DragOwner::SceneRight
+                        | DragOwner::PendingSceneLeft
+                        | DragOwner::PendingSceneRight
+                        | DragOwner::PendingSceneMiddle => {}
+                    }
+                    let camera_dragged = lock_camera_up
+                        && matches!(
+                            self.drag_owner,
+                            DragOwner::SceneLeft | DragOwner::SceneRight | DragOwner::SceneMiddle
+                        );
+                    if camera_dragged {
+                        let mut ui = self.ui_state.write().unwrap();
+                        ui.tutorial.notify(TutorialAction::CameraMoved);
+                    }
+                }
+                self.last_cursor_position = Some((x, y));
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                let (lock_camera_up, steer_anchor_active, trace_may_be_active) = {
+                    let ui = self.ui_state.read().unwrap();
+                    (
+                        ui.lock_camera_up,
+                        ui.spacecraft_steer_anchor.is_some(),
+                        ui.is_trace_enabled
+                            && ui.is_particle_info_panel_open
+                            && ui.selected_particle.is_some(),
+                    )
+                };
+                let trace_active = trace_may_be_active
+                    && resolve_trace_particle_for_camera(
+                        &self.ui_state,
+                        &self.simulation_manager,
+                        Some(&*pipeline),
+                    )
+                    .1;
+                if spacecraft_scene_wheel_allowed(
+                    lock_camera_up,
+                    steer_anchor_active,
+                    ui_wants_pointer || ui_consumed,
+                ) {
+                    let scroll_y = match delta {
+                        MouseScrollDelta::LineDelta(_, y) => *y,
+                        MouseScrollDelta::PixelDelta(PhysicalPosition { y, .. }) => *y as f32,
+                    };
+                    if let Some((x, _)) = self.last_cursor_position {
+                        pipeline.focus_view_at(x, window.inner_size().width);
+                    }
+                    // The second camera always orbits and never follows a trace.
+                    let primary = pipeline.primary_view_focused();
+                    apply_camera_mouse_wheel(
+                        pipeline.focused_camera_mut(),
+                        lock_camera_up || !primary,
+                        scroll_y,
+                        trace_active && primary,
+                    );
+                    let mut ui = self.ui_state.write().unwrap();
+                    ui.tutorial.notify(TutorialAction::Zoomed);
+                }
+            }
+            WindowEvent::KeyboardInput { event, .. } => {
+                if let PhysicalKey::Code(key) = event.physical_key
+                    && event.state == ElementState::Pressed
+                    && !event.repeat
+                    && !gui.keyboard_wants_input()
+                {
+                    match key {
+                        KeyCode::Home => pipeline.center_target_on_origin(),
+                        KeyCode::KeyZ | KeyCode::KeyY
+                            if self.input.held(KeyCode::ControlLeft)
+                                || self.input.held(KeyCode::ControlRight) =>
+                        {
+                            // Ctrl+Shift+Z is accepted as redo alongside Ctrl+Y.
+                            let shift = self.input.held(KeyCode::ShiftLeft)
+                                || self.input.held(KeyCode::ShiftRight);
+                            let direction = if key == KeyCode::KeyY || shift {
+                                UndoDirection::Redo
+                            } else {
+                                UndoDirection::Undo
+                            };
+                            self.ui_state.write().unwrap().pending_undo = Some(direction);
+                        }
+                        KeyCode::End => {
+                            let mut ui = self.ui_state.write().unwrap();
+                            ui.lock_camera_up = !ui.lock_camera_up;
+                        }
+                        KeyCode::KeyO | KeyCode::KeyS
+                            if self.input.held(KeyCode::ControlLeft)
+                                || self.input.held(KeyCode::ControlRight) =>
+                        {
+                            self.ui_state.write().unwrap().pending_snapshot_dialog =
+                                Some(if key == KeyCode::KeyO {
+                                    PendingSnapshotDialog::Load
+                                } else {
+                                    PendingSnapshotDialog::Save
+                                });
+                        }
+                        KeyCode::KeyR
+                            if self.input.held(KeyCode::ControlLeft)
+                                || self.input.held(KeyCode::ControlRight) =>
+                        {
+                            self.ui_state.write().unwrap().request_reset();
+                        }
+                        KeyCode::F1 => self.ui_state.write().unwrap().is_help_window_open = true,
+                        KeyCode::F11 => self.ui_state.write().unwrap().toggle_fullscreen(),
+                        KeyCode::F12 => {
+                            self.ui_state.write().unwrap().still_render_requested = true
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Performs per-frame updates before the event loop waits for new events.
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        if let Some(worker) = self.simulation_worker.as_mut() {
+            worker.restart_on_engine_change();
+        }
+        if let Some(window) = self.window.as_ref() {
+            process_checkpoint(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+            );
+            process_pending_snapshot_dialog(
+                window,
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+                &self.need_redraw,
+            );
+            process_pending_power_spectrum_export(window, &self.ui_state);
+            process_pending_metrics_export(window, &self.ui_state);
+            process_pending_plot_export(window, &self.ui_state);
+            process_pending_font_dialog(window, &self.ui_state);
+            process_pending_display_mode(window, &self.ui_state);
+            process_pending_trajectory_start(window, &self.ui_state);
+            process_pending_batch_export(window, &self.ui_state);
+            process_pending_still_render(
+                window,
+                &self.ui_state,
+                self.vulkan_base.as_ref(),
+                self.render_pipeline.as_mut(),
+            );
+            process_pending_undo(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+                &self.gpu_particle_sync,
+                &self.need_redraw,
+            );
+            process_pending_particle_delete(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+                &self.gpu_particle_sync,
+                &self.need_redraw,
+            );
+            process_pending_region_action(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+                &self.gpu_particle_sync,
+                &self.need_redraw,
+            );
+            process_due_maneuvers(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+                &self.gpu_particle_sync,
+                &self.need_redraw,
+            );
+            process_pending_live_rescale(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+                &self.gpu_particle_sync,
+                &self.need_redraw,
+            );
+            process_pending_engine_switch(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+                &self.gpu_particle_sync,
+                &self.need_redraw,
+            );
+            process_mass_profile_update(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+                &self.gpu_particle_sync,
+            );
+            process_light_cone_update(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+                &self.gpu_particle_sync,
+            );
+            process_orbit_preview_update(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+            );
+            process_grid_alignment(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+                &self.gpu_particle_sync,
+                &self.need_redraw,
+            );
+            process_minimap_update(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+                &self.gpu_particle_sync,
+            );
+            process_phase_space_update(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+                &self.gpu_particle_sync,
+            );
+            process_worldline_recording(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+                &self.gpu_particle_sync,
+            );
+            process_pending_power_spectrum(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+                &self.gpu_particle_sync,
+            );
+            process_pending_group_finder(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+                &self.gpu_particle_sync,
+                &self.need_redraw,
+            );
+            process_pending_group_recolor(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+                &self.gpu_particle_sync,
+                &self.need_redraw,
+            );
+            process_trajectory_recording(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+                &self.gpu_particle_sync,
+            );
+            process_event_triggers(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+                &self.gpu_particle_sync,
+            );
+            process_energy_monitor(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+                &self.gpu_particle_sync,
+            );
+            process_metric_sampling(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+                &self.gpu_particle_sync,
+            );
+            process_run_end_snapshot(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+                &self.gpu_particle_sync,
+            );
+            process_batch_job(&self.ui_state);
+            if let Some(taskbar) = self.taskbar.as_mut() {
+                taskbar.set(self.ui_state.read().unwrap().taskbar_progress());
+            }
+            process_pending_verification(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+                &self.gpu_particle_sync,
+            );
+            process_verification_job(&self.ui_state);
+            process_pending_determinism_audit(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+                &self.gpu_particle_sync,
+            );
+            process_thomas_precession(&self.ui_state);
+            process_memory_budget(&self.ui_state, self.render_pipeline.as_ref());
+            let ui_deadline = self.gui.as_ref().and_then(|gui| gui.repaint_deadline());
+            let schedule = schedule_repaint(
+                self.scene_changing(),
+                ui_deadline,
+                self.last_redraw,
+                Instant::now(),
+            );
+            match schedule {
+                RepaintSchedule::Now => {
+                    event_loop.set_control_flow(ControlFlow::Wait);
+                    window.request_redraw();
+                }
+                RepaintSchedule::At(deadline) => {
+                    event_loop.set_control_flow(ControlFlow::WaitUntil(deadline));
+                }
+            }
+        }
+        self.apply_pending_particle_buffer_reload();
+        process_pending_fit_view(
+            &self.ui_state,
+            &self.simulation_manager,
+            self.render_pipeline.as_mut(),
+            &self.gpu_particle_sync,
+        );
+        let lock_camera_up = self.ui_state.read().unwrap().lock_camera_up;
+        let keyboard_blocked = self
+            .gui
+            .as_ref()
+            .is_some_and(|gui| gui.keyboard_wants_input());
+        if self.last_lock_camera_up != Some(lock_camera_up) {
+            self.last_camera_tick = None;
+            self.last_lock_camera_up = Some(lock_camera_up);
+            if let Some(window) = self.window.as_ref() {
+                Self::clear_spacecraft_steer_anchors(window, &self.ui_state);
+            } else {
+                let mut uis = self.ui_state.write().unwrap();
+                uis.spacecraft_steer_anchor = None;
+                uis.spacecraft_yaw_steer_anchor = None;
+            }
+        }
+        #[cfg(feature = "gamepad")]
+        self.apply_gamepad_input();
+        self.apply_kiosk_tick();
+        let (trace_particle, suppress_space_shift, trace_visual_scale) =
+            resolve_trace_particle_for_camera(
+                &self.ui_state,
+                &self.simulation_manager,
+                self.render_pipeline.as_ref(),
+            );
+        let trace_distance_limits = if suppress_space_shift {
+            let uis = self.ui_state.read().unwrap();
+            Some(compute_trace_follow_distance_limits(
+                trace_visual_scale,
+                uis.link_point_size_to_scale,
+                uis.particle_display_mode,
+            ))
+        } else {
+            None
+        };
+        if let Some(pipeline) = self.render_pipeline.as_mut() {
+            pipeline.set_lock_camera_up(lock_camera_up);
+            if let Some((min, max)) = trace_distance_limits {
+                pipeline
+                    .camera_mut()
+                    .set_trace_follow_distance_limits(min, max);
+                pipeline.camera_mut().reclamp_trace_follow_distance();
+                pipeline.camera_mut().begin_trace_follow();
+            } else {
+                pipeline.camera_mut().end_trace_follow();
+            }
+            if !lock_camera_up {
+                let now = Instant::now();
+                let dt = self
+                    .last_camera_tick
+                    .map(|t| now.duration_since(t).as_secs_f32())
+                    .unwrap_or(0.0);
+                self.last_camera_tick = Some(now);
+                let (yaw_anchor, plus_anchor) = {
+                    let uis = self.ui_state.read().unwrap();
+                    (uis.spacecraft_yaw_steer_anchor, uis.spacecraft_steer_anchor)
+                };
+                tick_spacecraft_steer_and_motion_from_anchors(
+                    pipeline.camera_mut(),
+                    yaw_anchor,
+                    plus_anchor,
+                    self.last_cursor_position,
+                    dt,
+                    &self.input,
+                    keyboard_blocked,
+                    suppress_space_shift,
+                );
+            }
+            tick_orbit_camera(
+                pipeline.camera_mut(),
+                &self.input,
+                lock_camera_up,
+                keyboard_blocked,
+                suppress_space_shift,
+            );
+            pipeline.tick_second_camera();
+            if let Some(particle) = trace_particle {
+                pipeline.trace_selected_particle(
+                    particle.position,
+                    particle.velocity,
+                    trace_visual_scale,
+                );
+            }
+        }
+        if *self.need_redraw.read().unwrap() == false && !self.gpu_particle_sync.has_pending_sync()
+        {
+            return;
+        }
+
+        let uses_gpu = {
+            let uis = self.ui_state.read().unwrap();
+            let uses_gpu = uis.uses_gpu_simulation();
+            if let Some(pipeline) = self.render_pipeline.as_mut() {
+                pipeline.set_use_gpu_sim(uses_gpu);
+            }
+            uses_gpu
+        };
+
+        if self.gpu_particle_sync.take_upload_pending() {
+            if let (Some(pipeline), Ok(manager)) = (
+                self.render_pipeline.as_mut(),
+                self.simulation_manager.try_read(),
+            ) {
+                let simulation_type = {
+                    let uis = self.ui_state.read().unwrap();
+                    uis.active_simulation_type()
+                };
+                pipeline.upload_particles(&manager.particles(), simulation_type);
+            }
+        }
+
+        // GPU-mode "Add": preserve simulated positions of existing particles by
+        // reading back current GPU state before re-uploading the combined buffer.
+        // Cleared only after a successful upload to avoid dropping the request.
+        if self.gpu_particle_sync.append_pending() {
+            let (particles, simulation_type, scale) = {
+                let uis = self.ui_state.read().unwrap();
+                (
+                    self.simulation_manager.read().unwrap().particles(),
+                    uis.active_simulation_type(),
+                    uis.scale,
+                )
+            };
+            if let Some(pipeline) = self.render_pipeline.as_mut() {
+                pipeline.add_particles_preserving_simulated(&particles, simulation_type, scale);
+            }
+            self.gpu_particle_sync.clear_append_pending();
+        }
+
+        if let Some(index) = self.gpu_particle_sync.take_remove_index() {
+            if let Some(pipeline) = self.render_pipeline.as_mut() {
+                pipeline.remove_particle_preserving_simulated(index);
+            }
+        }
+
+        if uses_gpu {
+            return;
+        }
+
+        if *self.need_redraw.read().unwrap() == false {
+            return;
+        }
+        // The worker wrote the frame straight into staging memory; the next redraw copies it.
+        let staged = self
+            .render_pipeline
+            .as_mut()
+            .is_some_and(|pipeline| pipeline.take_staged_frame());
+        if staged {
+            self.need_redraw.write().unwrap().clone_from(&false);
+            return;
+        }
+        if let Some(frame) = self.gpu_particle_sync.take_published_frame() {
+            self.need_redraw.write().unwrap().clone_from(&false);
+            if let Some(pipeline) = self.render_pipeline.as_mut() {
+                let simulation_type = {
+                    let uis = self.ui_state.read().unwrap();
+                    uis.active_simulation_type()
+                };
+                pipeline.upload_particles(&frame, simulation_type);
+            }
+            self.gpu_particle_sync.recycle_frame(frame);
+            return;
+        }
+        if let Ok(manager) = self.simulation_manager.try_read() {
+            self.need_redraw.write().unwrap().clone_from(&false);
+            if let Some(pipeline) = self.render_pipeline.as_mut() {
+                let simulation_type = {
+                    let uis = self.ui_state.read().unwrap();
+                    uis.active_simulation_type()
+                };
+                pipeline.upload_particles(&manager.particles(), simulation_type);
+            }
+        }
+    }
+}
+
+impl App {
+    /// Returns whether the next frame differs from the last without any input: the
+    /// simulation runs, particles wait to be uploaded, or the camera moves on its own or
+    /// from held keys.
+    fn scene_changing(&self) -> bool {
+        let uis = self.ui_state.read().unwrap();
+        uis.is_running
+            || uis.spacecraft_steer_anchor.is_some()
+            || uis.spacecraft_yaw_steer_anchor.is_some()
+            || *self.need_redraw.read().unwrap()
+            || self.gpu_particle_sync.has_pending_sync()
+            || self.input.any_held()
+            || self.gamepad_moving()
+            || uis.kiosk.is_active()
+            || self
+                .render_pipeline
+                .as_ref()
+                .is_some_and(|pipeline| pipeline.is_camera_animating())
+    }
+
+    /// Advances kiosk mode and moves the focused camera along the current entry's script.
+    fn apply_kiosk_tick(&mut self) {
+        let Some(step) = self.ui_state.write().unwrap().tick_kiosk(Instant::now()) else {
+            return;
+        };
+        if let Some(pipeline) = self.render_pipeline.as_mut() {
+            let camera = pipeline.focused_camera_mut();
+            camera.revolve(step.yaw, 0.0);
+            if step.zoom_fraction != 0.0 {
+                camera.zoom(step.zoom_fraction * camera.orbit_distance());
+            }
+        }
+    }
+
+    #[cfg(feature = "gamepad")]
+    fn gamepad_moving(&self) -> bool {
+        self.gamepads.as_ref().is_some_and(Gamepads::is_moving)
+    }
+
+    #[cfg(not(feature = "gamepad"))]
+    fn gamepad_moving(&self) -> bool {
+        false
+    }
+
+    /// Polls the gamepads: sticks and triggers move the focused camera, and buttons run or
+    /// pause, step through the placement presets, or fit the view.
+    #[cfg(feature = "gamepad")]
+    fn apply_gamepad_input(&mut self) {
+        let Some(gamepads) = self.gamepads.as_mut() else {
+            return;
+        };
+        let mut actions = Vec::new();
+        let (axes, dt) = gamepads.poll(&mut actions);
+        if (!axes.is_zero() || !actions.is_empty()) && self.ui_state.write().unwrap().stop_kiosk() {
+            return;
+        }
+        if !axes.is_zero()
+            && let Some(pipeline) = self.render_pipeline.as_mut()
+        {
+            apply_gamepad_camera(pipeline.focused_camera_mut(), axes, dt);
+        }
+        if actions.is_empty() {
+            return;
+        }
+        let mut uis = self.ui_state.write().unwrap();
+        for action in actions {
+            match action {
+                GamepadAction::ToggleRun => uis.is_running ^= true,
+                GamepadAction::NextPreset => uis.cycle_placement_preset(1),
+                GamepadAction::PreviousPreset => uis.cycle_placement_preset(-1),
+                GamepadAction::FitView => uis.fit_view_requested = true,
+            }
+        }
+    }
+
+    fn worker_handles(&self) -> WorkerHandles {
+        WorkerHandles {
+            ui_state: Arc::clone(&self.ui_state),
+            simulation_manager: Arc::clone(&self.simulation_manager),
+            need_redraw: Arc::clone(&self.need_redraw),
+            skip_redraw: Arc::clone(&self.skip_redraw),
+            gpu_particle_sync: self.gpu_particle_sync.clone(),
+        }
+    }
+
+    /// Joins the simulation worker, then finishes the trajectory recording, any batch
+    /// run, and queued exports so nothing is cut off when the window closes. Running
+    /// verifications and determinism audits are aborted.
+    fn shutdown(&mut self) {
+        self.taskbar = None;
+        if let Some(mut worker) = self.simulation_worker.take() {
+            worker.join();
+        }
+        let (batch_job, verification_job, determinism_job) = {
+            let mut uis = self.ui_state.write().unwrap();
+            uis.stop_trajectory_recording();
+            uis.export_writer.finish();
+            (
+                uis.batch_job.take(),
+                uis.verification_job.take(),
+                uis.determinism_job.take(),
+            )
+        };
+        if let Some(job) = batch_job {
+            job.abort();
+            job.join();
+        }
+        if let Some(job) = verification_job {
+            job.abort();
+            let _ = job.join();
+        }
+        if let Some(job) = determinism_job {
+            job.abort();
+            let _ = job.join();
+        }
+    }
+
+    fn sync_spacecraft_yaw_steer_anchor(
+        window: &Window,
+        ui_state: &Arc<RwLock<UiState>>,
+        anchor: Option<(f64, f64)>,
+    ) {
+        ui_state.write().unwrap().spacecraft_yaw_steer_anchor = anchor.map(|(x, y)| [x, y]);
+        window.request_redraw();
+    }
+
+    fn clear_spacecraft_steer_anchors(window: &Window, ui_state: &Arc<RwLock<UiState>>) {
+        {
+            let mut uis = ui_state.write().unwrap();
+            uis.spacecraft_steer_anchor = None;
+            uis.spacecraft_yaw_steer_anchor = None;
+        }
+        window.request_redraw();
+    }
+
+    /// Toggles the spacecraft steer anchor at the given cursor position.
+    fn toggle_spacecraft_steer_anchor(
+        window: &Window,
+        ui_state: &Arc<RwLock<UiState>>,
+        pos: (f64, f64),
+    ) {
+        {
+            let mut uis = ui_state.write().unwrap();
+            toggle_steer_anchor(&mut uis.spacecraft_steer_anchor, pos);
+        }
+        window.request_redraw();
+    }
+
+    /// Applies a snapshot-load request by scheduling a full GPU particle upload.
+    fn apply_pending_particle_buffer_reload(&mut self) {
+        let mut uis = self.ui_state.write().unwrap();
+        let reload_requested = uis.take_particle_buffer_reload_requested();
+        let uses_gpu = uis.uses_gpu_simulation();
+        drop(uis);
+        if reload_requested && uses_gpu {
+            self.gpu_particle_sync.request_full_upload();
+        }
+    }
+
+    /// Clears all internal mouse drag button state flags.
+    fn clear_mouse_drag_flags(&mut self) {
+        self.mouse_left_down = false;
+        self.mouse_right_down = false;
+        self.mouse_middle_down = false;
+    }
+
+    /// Returns `true` when a double-click was recognized (click history is cleared).
+    fn try_consume_double_click(
+        click_pos: (f64, f64),
+        now: Instant,
+        last_time: &mut Option<Instant>,
+        last_pos: &mut Option<(f64, f64)>,
+    ) -> bool {
+        let max_dt = Duration::from_millis(DOUBLE_CLICK_MILLIS);
+        let Some(prev_t) = *last_time else {
+            *last_time = Some(now);
+            *last_pos = Some(click_pos);
+            return false;
+        };
+        let dt = now.duration_since(prev_t);
+        let is_double = if dt <= max_dt {
+            if let Some((px, py)) = *last_pos {
+                let dx = px - click_pos.0;
+                let dy = py - click_pos.1;
+                let dist2 = dx * dx + dy * dy;
+                dist2 <= DOUBLE_CLICK_DIST
+            } else {
+                false
+            }
+        } else {
+            false
+        };
+        if is_double {
+            *last_time = None;
+            *last_pos = None;
+            true
+        } else {
+            *last_time = Some(now);
+            *last_pos = Some(click_pos);
+            false
+        }
+    }
+
+    /// Tracks left-button press/release state for drag gestures.
+    fn left_button(&mut self, state: &ElementState) {
+        self.mouse_left_down = *state == ElementState::Pressed;
+    }
+
+    /// Handles right-button press/release and double-click target-centering behavior.
+    fn right_button(&mut self, state: &ElementState) {
+        let pressed = *state == ElementState::Pressed;
+        self.mouse_right_down = pressed;
+        if pressed {
+            if self.ui_state.read().unwrap().lock_camera_up {
+                let now = Instant::now();
+                let Some(click_pos) = self.last_cursor_position else {
+                    return;
+                };
+                if Self::try_consume_double_click(
+                    click_pos,
+                    now,
+                    &mut self.last_right_click_time,
+                    &mut self.last_right_click_pos,
+                ) && let Some(pipeline) = self.render_pipeline.as_mut()
+                {
+                    pipeline.center_target_on_origin();
+                }
+            }
+        }
+    }
+
+    /// Handles middle-button press/release state tracking for camera roll gestures.
+    fn middle_button(&mut self, state: &ElementState) {
+        let pressed = *state == ElementState::Pressed;
+        self.mouse_middle_down = pressed;
+    }
+
+    /// Picks the particle closest to the last cursor position and stores it in UI state.
+    ///
+    /// Called on a left-button release that did not promote into a drag.
+    /// Reads the most recent particle data from whichever simulation source
+    /// (CPU manager or GPU buffer) the app is currently driving.
+    fn try_pick_particle(&mut self) {
+        let Some(click_pos) = self.last_cursor_position else {
+            return;
+        };
+        let Some(vb) = self.vulkan_base.as_ref() else {
+            return;
+        };
+        let Some(pipeline) = self.render_pipeline.as_ref() else {
+            return;
+        };
+        let extent = vb.swapchain_extent;
+        if extent.width == 0 || extent.height == 0 {
+            return;
+        }
+
+        let (uses_gpu, scale_gauge, simulation_type, scale) = {
+            let uis = self.ui_state.read().unwrap();
+            (
+                uis.uses_gpu_simulation(),
+                uis.scale_gauge,
+                uis.active_simulation_type(),
+                uis.scale,
+            )
+        };
+
+        let particles = if uses_gpu {
+            pipeline.readback_particles(simulation_type, scale)
+        } else {
+            self.simulation_manager.read().unwrap().particles()
+        };
+
+        if particles.is_empty() {
+            return;
+        }
+
+        let click_x = click_pos.0 as f32;
+        let click_y = click_pos.1 as f32;
+        if let Some(idx) =
+            pipeline.pick_nearest_particle(&particles, click_x, click_y, extent, scale_gauge)
+        {
+            let mut uis = self.ui_state.write().unwrap();
+            uis.select_particle(idx);
+            drop(uis);
+            self.need_redraw.write().unwrap().clone_from(&true);
+        }
+    }
+}
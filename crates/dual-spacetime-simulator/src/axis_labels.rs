@@ -0,0 +1,93 @@
+use crate::ui_state::{BaseScaleUnit, DisplaySpace};
+use glam::Vec3;
+
+/// Half-extent in axes space of the XZ grid the renderer draws.
+pub(crate) const AXIS_XZ_GRID_EXTENT: f32 = 2.0;
+pub(crate) const AXIS_XZ_GRID_LINE_COUNT: usize = 9;
+/// Distance in axes space between a grid edge or axis tip and its label.
+const LABEL_OFFSET: f32 = 0.15;
+const TICK_LABEL_COLOR: [f32; 4] = [0.7, 0.7, 0.7, 1.0];
+const X_AXIS_COLOR: [f32; 4] = [1.0, 0.3, 0.3, 1.0];
+const Y_AXIS_COLOR: [f32; 4] = [0.3, 1.0, 0.3, 1.0];
+const Z_AXIS_COLOR: [f32; 4] = [0.4, 0.5, 1.0, 1.0];
+
+/// Text anchored at a point of the grid, in the grid's unrotated axes space.
+#[derive(Clone, PartialEq, Debug)]
+pub struct AxisLabel {
+    pub position: Vec3,
+    pub text: String,
+    pub color: [f32; 4],
+}
+
+/// Labels for the grid: the axis names of `space` at the ends of the center lines and, in
+/// position space, the distance of every grid line along the x and z edges. One axes-space
+/// unit is `meters_per_axes_unit` m. The vertical helper line points along −Y.
+pub fn grid_labels(space: DisplaySpace, meters_per_axes_unit: f64) -> Vec<AxisLabel> {
+    let range = AXIS_XZ_GRID_EXTENT;
+    let step = 2.0 * range / (AXIS_XZ_GRID_LINE_COUNT - 1) as f32;
+    let edge = range + LABEL_OFFSET;
+    let tick_count = if space == DisplaySpace::Position {
+        AXIS_XZ_GRID_LINE_COUNT
+    } else {
+        0
+    };
+    let mut labels = Vec::new();
+    for i in 0..tick_count {
+        let tick = -range + i as f32 * step;
+        let text = format_distance(f64::from(tick) * meters_per_axes_unit);
+        labels.push(AxisLabel {
+            position: Vec3::new(tick, 0.0, edge),
+            text: text.clone(),
+            color: TICK_LABEL_COLOR,
+        });
+        labels.push(AxisLabel {
+            position: Vec3::new(edge, 0.0, tick),
+            text,
+            color: TICK_LABEL_COLOR,
+        });
+    }
+    let [x, y, z] = space.axis_labels();
+    labels.extend([
+        AxisLabel {
+            position: Vec3::new(edge + LABEL_OFFSET, 0.0, 0.0),
+            text: x.to_string(),
+            color: X_AXIS_COLOR,
+        },
+        AxisLabel {
+            position: Vec3::new(0.0, -1.0 - LABEL_OFFSET, 0.0),
+            text: format!("−{y}"),
+            color: Y_AXIS_COLOR,
+        },
+        AxisLabel {
+            position: Vec3::new(0.0, 0.0, edge + LABEL_OFFSET),
+            text: z.to_string(),
+            color: Z_AXIS_COLOR,
+        },
+    ]);
+    labels
+}
+
+/// Formats a distance in m with three significant digits in the largest unit that keeps
+/// the value at least 1, e.g. `1.5 au` or `-750 km`.
+pub fn format_distance(meters: f64) -> String {
+    if meters == 0.0 || !meters.is_finite() {
+        return format!("{meters}");
+    }
+    let unit = BaseScaleUnit::ALL
+        .into_iter()
+        .find(|unit| unit.from_meters(meters.abs()) >= 1.0)
+        .unwrap_or(BaseScaleUnit::Fm);
+    let value = unit.from_meters(meters);
+    let decimals = match value.abs() {
+        v if v >= 100.0 => 0,
+        v if v >= 10.0 => 1,
+        _ => 2,
+    };
+    let text = format!("{value:.decimals$}");
+    let text = if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &text
+    };
+    format!("{text} {unit}")
+}
@@ -0,0 +1,249 @@
+use crate::events::{total_energy, total_momentum};
+use crate::object_input::ObjectInput;
+use crate::sim_clock::estimate_eta;
+use crate::simulation::{EngineConfig, Particle, SimulationManager};
+use crate::ui_state::SimulationType;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+pub const BATCH_FILTER_NAME: &str = "CSV";
+pub const BATCH_FILTER_EXT: &str = "csv";
+pub const BATCH_CSV_HEADER: &str = "simulation_type,dt,frames,simulated_time,initial_energy,\
+final_energy,relative_energy_drift,momentum_drift,live_particles,wall_seconds";
+/// Default comma-separated time steps (seconds per frame) offered by the Batch panel.
+pub const DEFAULT_BATCH_TIME_STEPS: &str = "0.1, 0.05, 0.025";
+/// Default simulated duration of each batch run in seconds.
+pub const DEFAULT_BATCH_DURATION: f64 = 100.0;
+
+/// Parameter matrix for a headless batch: every time step is run with every simulation
+/// type for the same simulated duration, all from one shared set of initial conditions.
+#[derive(Clone, Debug)]
+pub struct BatchConfig {
+    pub object_input: ObjectInput,
+    pub particle_count: u32,
+    pub scale: f64,
+    /// Simulated seconds per run; each run takes `round(duration / dt)` frames.
+    pub duration: f64,
+    pub time_steps: Vec<f64>,
+    pub simulation_types: Vec<SimulationType>,
+    /// Settings every run steps with.
+    pub engine: EngineConfig,
+}
+
+impl BatchConfig {
+    /// Returns the `(simulation type, dt)` combinations in run order.
+    pub fn runs(&self) -> Vec<(SimulationType, f64)> {
+        self.simulation_types
+            .iter()
+            .flat_map(|&ty| self.time_steps.iter().map(move |&dt| (ty, dt)))
+            .collect()
+    }
+}
+
+/// Summary diagnostics of one batch run. Energies and momenta are in simulation units
+/// and use the stored velocity field (rapidity in Lorentz mode), so drifts are only
+/// comparable between runs of the same simulation type.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BatchRunSummary {
+    pub simulation_type: SimulationType,
+    pub time_per_frame: f64,
+    pub frames: u64,
+    pub simulated_time: f64,
+    pub initial_energy: f64,
+    pub final_energy: f64,
+    /// `|E - E₀| / |E₀|`, or NaN when `E₀` is zero.
+    pub relative_energy_drift: f64,
+    /// Magnitude of the change in total momentum `Σ m v`.
+    pub momentum_drift: f64,
+    pub live_particles: usize,
+    pub wall_seconds: f64,
+}
+
+/// Number of frames a run with time step `dt` takes to cover `duration`.
+pub fn frames_for_duration(duration: f64, dt: f64) -> u64 {
+    if dt > 0.0 && duration.is_finite() {
+        (duration / dt).round().max(1.0) as u64
+    } else {
+        0
+    }
+}
+
+/// Parses a comma- or whitespace-separated list of positive, finite numbers.
+pub fn parse_value_list(text: &str) -> Result<Vec<f64>, String> {
+    let values = text
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .map(|s| match s.parse::<f64>() {
+            Ok(v) if v > 0.0 && v.is_finite() => Ok(v),
+            _ => Err(format!("Invalid value: {}", s)),
+        })
+        .collect::<Result<Vec<f64>, String>>()?;
+    if values.is_empty() {
+        return Err("No values given".to_string());
+    }
+    Ok(values)
+}
+
+/// Runs one simulation on the CPU from `initial` and returns its diagnostics.
+pub fn run_single(
+    initial: &[Particle],
+    simulation_type: SimulationType,
+    scale: f64,
+    time_per_frame: f64,
+    frames: u64,
+    config: &EngineConfig,
+    abort: &AtomicBool,
+) -> BatchRunSummary {
+    let started = Instant::now();
+    let manager = SimulationManager::with_config(*config);
+    manager.reset_from_particles(initial.to_vec(), simulation_type, scale);
+    let before = manager.particles();
+    let initial_energy = total_energy(&before);
+    let initial_momentum = total_momentum(&before);
+    let mut completed = 0;
+    while completed < frames && !abort.load(Ordering::Relaxed) {
+        manager.advance(time_per_frame);
+        completed += 1;
+    }
+    let after = manager.particles();
+    let final_energy = total_energy(&after);
+    let relative_energy_drift = if initial_energy != 0.0 {
+        ((final_energy - initial_energy) / initial_energy).abs()
+    } else {
+        f64::NAN
+    };
+    BatchRunSummary {
+        simulation_type,
+        time_per_frame,
+        frames: completed,
+        simulated_time: completed as f64 * time_per_frame,
+        initial_energy,
+        final_energy,
+        relative_energy_drift,
+        momentum_drift: (total_momentum(&after) - initial_momentum).length(),
+        live_particles: after.iter().filter(|p| p.color[3] != 0.0).count(),
+        wall_seconds: started.elapsed().as_secs_f64(),
+    }
+}
+
+/// Runs every combination of `config` in order, bumping `completed` after each run.
+/// Stops early (keeping finished runs) when `abort` is set.
+pub fn run_batch(
+    config: &BatchConfig,
+    completed: &AtomicUsize,
+    abort: &AtomicBool,
+) -> Vec<BatchRunSummary> {
+    let initial = config
+        .object_input
+        .generate_particles(config.particle_count)
+        .particles;
+    let mut summaries = Vec::new();
+    for (simulation_type, dt) in config.runs() {
+        if abort.load(Ordering::Relaxed) {
+            break;
+        }
+        let frames = frames_for_duration(config.duration, dt);
+        summaries.push(run_single(
+            &initial,
+            simulation_type,
+            config.scale,
+            dt,
+            frames,
+            &config.engine,
+            abort,
+        ));
+        completed.fetch_add(1, Ordering::Relaxed);
+    }
+    summaries
+}
+
+/// Formats batch summaries as CSV, one row per run.
+pub fn batch_csv(summaries: &[BatchRunSummary]) -> String {
+    let mut csv = String::from(BATCH_CSV_HEADER);
+    csv.push('\n');
+    for s in summaries {
+        csv.push_str(&format!(
+            "{},{:e},{},{:e},{:e},{:e},{:e},{:e},{},{:.3}\n",
+            s.simulation_type,
+            s.time_per_frame,
+            s.frames,
+            s.simulated_time,
+            s.initial_energy,
+            s.final_energy,
+            s.relative_energy_drift,
+            s.momentum_drift,
+            s.live_particles,
+            s.wall_seconds
+        ));
+    }
+    csv
+}
+
+/// Writes batch summaries to `path`, creating parent directories as needed.
+pub fn save_batch_csv(path: &Path, summaries: &[BatchRunSummary]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut writer = BufWriter::new(File::create(path)?);
+    writer.write_all(batch_csv(summaries).as_bytes())?;
+    writer.flush()
+}
+
+/// Batch running on a background thread so the window stays responsive.
+pub struct BatchJob {
+    completed: Arc<AtomicUsize>,
+    abort: Arc<AtomicBool>,
+    total: usize,
+    started: Instant,
+    handle: JoinHandle<Vec<BatchRunSummary>>,
+}
+
+impl BatchJob {
+    pub fn spawn(config: BatchConfig) -> Self {
+        let completed = Arc::new(AtomicUsize::new(0));
+        let abort = Arc::new(AtomicBool::new(false));
+        let total = config.runs().len();
+        let handle = {
+            let completed = Arc::clone(&completed);
+            let abort = Arc::clone(&abort);
+            std::thread::spawn(move || run_batch(&config, &completed, &abort))
+        };
+        Self {
+            completed,
+            abort,
+            total,
+            started: Instant::now(),
+            handle,
+        }
+    }
+
+    /// Returns `(finished runs, total runs)`.
+    pub fn progress(&self) -> (usize, usize) {
+        (self.completed.load(Ordering::Relaxed), self.total)
+    }
+
+    /// Wall time left at the average time per finished run so far.
+    pub fn eta(&self) -> Option<Duration> {
+        let (done, total) = self.progress();
+        estimate_eta(done as f64, total as f64, self.started.elapsed())
+    }
+
+    /// Asks the worker to stop after the current frame.
+    pub fn abort(&self) {
+        self.abort.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// Waits for the worker and returns the runs it completed.
+    pub fn join(self) -> Vec<BatchRunSummary> {
+        self.handle.join().unwrap_or_default()
+    }
+}
@@ -0,0 +1,131 @@
+use crate::rotating_frame::rotating_frame_velocity_update;
+use crate::simulation::{EPSILON, EngineConfig, G, Particle};
+use dst_math::gravity::newtonian_gravity_pair;
+use dst_math::summation::Vec3Sum;
+use glam::DVec3;
+use rayon::prelude::*;
+
+/// Timestep factor η used until the user picks another: about 125 steps per orbit of a
+/// light body on a circular orbit.
+pub const DEFAULT_BLOCK_STEP_ACCURACY: f64 = 0.05;
+pub const MIN_BLOCK_STEP_ACCURACY: f64 = 0.005;
+pub const MAX_BLOCK_STEP_ACCURACY: f64 = 0.5;
+/// Finest level: the frame is halved at most this many times (1024 substeps).
+pub const MAX_BLOCK_LEVEL: u32 = 10;
+
+/// Level `k` of a particle whose own step should be at most `own_step`, so that its step
+/// `dt / 2^k` is the longest power-of-two fraction of the frame `dt` that fits. Clamped
+/// to `0..=MAX_BLOCK_LEVEL`.
+pub fn block_level(dt: f64, own_step: f64) -> u32 {
+    if own_step >= dt.abs() {
+        return 0;
+    }
+    if own_step <= 0.0 || own_step.is_nan() {
+        return MAX_BLOCK_LEVEL;
+    }
+    ((dt.abs() / own_step).log2().ceil() as u32).min(MAX_BLOCK_LEVEL)
+}
+
+/// Picks every particle's level for a frame of `dt` from its own step `η √(d / |a|)`,
+/// with `a` its Newtonian acceleration and `d` the distance to its nearest neighbor. The
+/// step is the same at any world scale, since simulation lengths and masses scale
+/// together; for a light body on a circular orbit it is `η / ω`.
+///
+/// O(N²), one pass of the direct force sum.
+pub fn block_levels(particles: &[Particle], dt: f64, accuracy: f64) -> Vec<u32> {
+    particles
+        .par_iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let mut acceleration = DVec3::ZERO;
+            let mut nearest_sq = f64::INFINITY;
+            for (j, q) in particles.iter().enumerate() {
+                if j == i {
+                    continue;
+                }
+                acceleration +=
+                    newtonian_gravity_pair(p.position, q.position, q.mass, G, G, EPSILON).1;
+                let distance_sq = (q.position - p.position).length_squared();
+                if distance_sq >= EPSILON {
+                    nearest_sq = nearest_sq.min(distance_sq);
+                }
+            }
+            let magnitude = acceleration.length();
+            let own_step = if magnitude > 0.0 {
+                accuracy * (nearest_sq.sqrt() / magnitude).sqrt()
+            } else {
+                f64::INFINITY
+            };
+            block_level(dt, own_step)
+        })
+        .collect()
+}
+
+/// Kicks the `active` particles by the Newtonian pull of all particles over `steps[i]`,
+/// summed the way the Normal engine sums it, then applies the rotating frame.
+fn kick_active(particles: &mut [Particle], active: &[usize], steps: &[f64], config: &EngineConfig) {
+    let summation = config.summation;
+    let view: &[Particle] = particles;
+    let velocity_deltas: Vec<DVec3> = active
+        .par_iter()
+        .map(|&i| {
+            let pos_i = view[i].position;
+            let time_g = G * steps[i];
+            let mut acceleration = Vec3Sum::new(summation);
+            for (j, q) in view.iter().enumerate() {
+                if j == i {
+                    continue;
+                }
+                acceleration
+                    .add(newtonian_gravity_pair(pos_i, q.position, q.mass, G, time_g, EPSILON).1);
+            }
+            acceleration.value()
+        })
+        .collect();
+    let angular_velocity = config.frame_angular_velocity;
+    for (&i, delta) in active.iter().zip(velocity_deltas) {
+        particles[i].velocity += delta;
+        rotating_frame_velocity_update(
+            std::slice::from_mut(&mut particles[i]),
+            angular_velocity,
+            steps[i],
+        );
+    }
+}
+
+/// Advances the Newtonian engine's particles by one frame of `dt` on hierarchical block
+/// time steps. Each particle gets a level `k` from [`block_levels`] at the start of the
+/// frame; all particles drift on the finest step, and a particle of level `k` is kicked
+/// only at the end of each of its own steps `dt / 2^k`. With every level at 0 this is the
+/// engine's single drift-then-kick step with `config`. Returns the finest level used.
+///
+/// Forces cost O(N) per kicked particle, so a few tight orbits no longer make every
+/// particle pay for their short step.
+pub fn advance_block_steps(
+    particles: &mut [Particle],
+    dt: f64,
+    accuracy: f64,
+    config: &EngineConfig,
+) -> u32 {
+    let levels = block_levels(particles, dt, accuracy);
+    let finest = levels.iter().copied().max().unwrap_or(0);
+    let substeps = 1u32 << finest;
+    let substep = dt / substeps as f64;
+    let steps: Vec<f64> = levels
+        .iter()
+        .map(|&level| dt / (1u32 << level) as f64)
+        .collect();
+    for s in 1..=substeps {
+        particles.par_iter_mut().for_each(|particle| {
+            particle.position += particle.velocity * substep;
+        });
+        let active: Vec<usize> = levels
+            .iter()
+            .enumerate()
+            .filter(|&(_, &level)| s % (substeps >> level) == 0)
+            .map(|(i, _)| i)
+            .collect();
+        kick_active(particles, &active, &steps, config);
+    }
+    finest
+}
@@ -0,0 +1,113 @@
+use crate::simulation::{G, Particle};
+use glam::DVec3;
+use rayon::prelude::*;
+
+/// Steps per encounter time used until the user picks another.
+pub const DEFAULT_STEPS_PER_ENCOUNTER: u32 = 8;
+pub const MIN_STEPS_PER_ENCOUNTER: u32 = 2;
+pub const MAX_STEPS_PER_ENCOUNTER: u32 = 200;
+/// Most substeps one frame is split into, so a near-collision cannot stall the simulation.
+pub const MAX_ENCOUNTER_SUBSTEPS: u32 = 256;
+
+/// The pair that most needs a finer step over the next frame.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Encounter {
+    pub first_id: u64,
+    pub second_id: u64,
+    /// Predicted closest separation during the frame, in simulation units.
+    pub min_separation: f64,
+    /// Substeps the frame is split into to resolve the encounter.
+    pub substeps: u32,
+}
+
+impl Encounter {
+    /// The pair's IDs, lower first.
+    pub fn pair(&self) -> (u64, u64) {
+        (
+            self.first_id.min(self.second_id),
+            self.first_id.max(self.second_id),
+        )
+    }
+}
+
+/// Closest separation of two bodies moving in straight lines over the next `dt`, from
+/// their relative position and velocity.
+pub fn closest_approach(relative_position: DVec3, relative_velocity: DVec3, dt: f64) -> f64 {
+    let speed_sq = relative_velocity.length_squared();
+    let time = if speed_sq > 0.0 {
+        (-relative_position.dot(relative_velocity) / speed_sq).clamp(0.0, dt.abs())
+    } else {
+        0.0
+    };
+    (relative_position + relative_velocity * time).length()
+}
+
+/// Substeps that give an encounter `steps_per_encounter` steps per encounter time, the
+/// shorter of the crossing time `d / v` and the free-fall time `√(d³ / G M)` at the closest
+/// separation `d`. Both times are the same at any world scale, since simulation lengths
+/// and masses scale together. Clamped to `1..=MAX_ENCOUNTER_SUBSTEPS`.
+pub fn encounter_substeps(
+    min_separation: f64,
+    relative_speed: f64,
+    total_mass: f64,
+    dt: f64,
+    steps_per_encounter: u32,
+) -> u32 {
+    let crossing_time = if relative_speed > 0.0 {
+        min_separation / relative_speed
+    } else {
+        f64::INFINITY
+    };
+    let free_fall_time = if total_mass > 0.0 {
+        (min_separation.powi(3) / (G * total_mass)).sqrt()
+    } else {
+        f64::INFINITY
+    };
+    let encounter_time = crossing_time.min(free_fall_time);
+    if encounter_time <= 0.0 {
+        return MAX_ENCOUNTER_SUBSTEPS;
+    }
+    let substeps = (dt.abs() * steps_per_encounter as f64 / encounter_time).ceil();
+    substeps.clamp(1.0, MAX_ENCOUNTER_SUBSTEPS as f64) as u32
+}
+
+/// Finds the pair of live particles whose predicted closest approach during the next
+/// frame of `dt` needs the most substeps. `None` when one step resolves every pair.
+/// `regularized` pairs (IDs, lower first) move along their Kepler orbits and are skipped.
+///
+/// O(N²), like the direct force sum it refines.
+pub fn find_close_encounter(
+    particles: &[Particle],
+    dt: f64,
+    steps_per_encounter: u32,
+    regularized: &[(u64, u64)],
+) -> Option<Encounter> {
+    let live: Vec<&Particle> = particles.iter().filter(|p| p.color[3] != 0.0).collect();
+    live.par_iter()
+        .enumerate()
+        .filter_map(|(i, p)| {
+            live[i + 1..]
+                .iter()
+                .filter(|q| !regularized.contains(&(p.id.min(q.id), p.id.max(q.id))))
+                .map(|q| {
+                    let relative_velocity = q.velocity - p.velocity;
+                    let min_separation =
+                        closest_approach(q.position - p.position, relative_velocity, dt);
+                    Encounter {
+                        first_id: p.id,
+                        second_id: q.id,
+                        min_separation,
+                        substeps: encounter_substeps(
+                            min_separation,
+                            relative_velocity.length(),
+                            p.mass + q.mass,
+                            dt,
+                            steps_per_encounter,
+                        ),
+                    }
+                })
+                .max_by_key(|encounter| encounter.substeps)
+        })
+        .max_by_key(|encounter| encounter.substeps)
+        .filter(|encounter| encounter.substeps > 1)
+}
@@ -0,0 +1,58 @@
+use crate::simulation::Particle;
+use rayon::prelude::*;
+
+pub const DEFAULT_WALL_HALF_SIZE: f64 = 2.0;
+pub const DEFAULT_WALL_RESTITUTION: f64 = 1.0;
+
+/// Reflective cube centered on the origin, in simulation coordinates (base-scale units).
+/// Particles that cross a wall are mirrored back inside with the normal component of their
+/// motion reversed and scaled by `restitution`: 1 bounces elastically, 0 stops them on
+/// the wall.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ContainerWalls {
+    pub half_size: f64,
+    pub restitution: f64,
+}
+
+impl Default for ContainerWalls {
+    fn default() -> Self {
+        Self {
+            half_size: DEFAULT_WALL_HALF_SIZE,
+            restitution: DEFAULT_WALL_RESTITUTION,
+        }
+    }
+}
+
+impl ContainerWalls {
+    /// Bounces `particle` off any wall it has crossed. Both the stored velocity and the
+    /// momentum flip, so the Special engines' momentum and rapidity reflect too.
+    pub fn reflect(&self, particle: &mut Particle) {
+        let half = self.half_size;
+        let e = self.restitution;
+        for axis in 0..3 {
+            let x = particle.position[axis];
+            let outward = if x > half {
+                1.0
+            } else if x < -half {
+                -1.0
+            } else {
+                continue;
+            };
+            let wall = outward * half;
+            particle.position[axis] = (wall - e * (x - wall)).clamp(-half, half);
+            if particle.velocity[axis] * outward > 0.0 {
+                particle.velocity[axis] *= -e;
+            }
+            if particle.momentum[axis] * outward > 0.0 {
+                particle.momentum[axis] *= -e;
+            }
+        }
+    }
+}
+
+/// Bounces every particle that left the container back inside.
+pub fn reflect_off_walls(particles: &mut [Particle], walls: ContainerWalls) {
+    particles
+        .par_iter_mut()
+        .for_each(|particle| walls.reflect(particle));
+}
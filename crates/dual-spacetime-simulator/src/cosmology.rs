@@ -0,0 +1,117 @@
+use crate::simulation::MPC;
+
+/// Hubble constant in km/s/Mpc used until the user picks another.
+pub const DEFAULT_HUBBLE_CONSTANT: f64 = 70.0;
+pub const DEFAULT_MATTER_DENSITY: f64 = 0.3;
+pub const DEFAULT_START_REDSHIFT: f64 = 50.0;
+/// Smallest matter density the ΛCDM history accepts; at zero the universe never decelerates.
+pub const MIN_MATTER_DENSITY: f64 = 1e-3;
+
+/// How the scale factor `a(t)` of the comoving engine grows with cosmic time.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ExpansionHistory {
+    /// Einstein–de Sitter: flat and matter-only, `a ∝ t^(2/3)`.
+    #[default]
+    MatterOnly,
+    /// Flat with matter and a cosmological constant, `Ω_Λ = 1 − Ω_m`.
+    LambdaCdm,
+}
+
+impl ExpansionHistory {
+    pub const ALL: [Self; 2] = [Self::MatterOnly, Self::LambdaCdm];
+}
+
+impl std::fmt::Display for ExpansionHistory {
+    /// Formats expansion history names for selection controls.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            ExpansionHistory::MatterOnly => "Matter Only",
+            ExpansionHistory::LambdaCdm => "ΛCDM",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// Background expansion the comoving engine integrates in. Cosmic time is in seconds since
+/// the big bang and `a = 1` today; the simulation starts at `start_redshift`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Cosmology {
+    pub history: ExpansionHistory,
+    /// H₀ in km/s/Mpc.
+    pub hubble_constant: f64,
+    /// Ω_m today; only the ΛCDM history uses it.
+    pub matter_density: f64,
+    pub start_redshift: f64,
+}
+
+impl Cosmology {
+    pub const DEFAULT: Self = Self {
+        history: ExpansionHistory::MatterOnly,
+        hubble_constant: DEFAULT_HUBBLE_CONSTANT,
+        matter_density: DEFAULT_MATTER_DENSITY,
+        start_redshift: DEFAULT_START_REDSHIFT,
+    };
+
+    /// H₀ in 1/s.
+    pub fn hubble_rate_today(&self) -> f64 {
+        self.hubble_constant * 1_000.0 / MPC
+    }
+
+    /// Scale factor at cosmic time `time`.
+    pub fn scale_factor(&self, time: f64) -> f64 {
+        let h0 = self.hubble_rate_today();
+        match self.history {
+            ExpansionHistory::MatterOnly => (1.5 * h0 * time).powf(2.0 / 3.0),
+            ExpansionHistory::LambdaCdm => {
+                let (matter, lambda) = self.densities();
+                let growth = (1.5 * lambda.sqrt() * h0 * time).sinh();
+                (matter / lambda).cbrt() * growth.powf(2.0 / 3.0)
+            }
+        }
+    }
+
+    /// Hubble rate `ȧ/a` at cosmic time `time`, in 1/s.
+    pub fn hubble_rate(&self, time: f64) -> f64 {
+        let h0 = self.hubble_rate_today();
+        match self.history {
+            ExpansionHistory::MatterOnly => 2.0 / (3.0 * time),
+            ExpansionHistory::LambdaCdm => {
+                let (_, lambda) = self.densities();
+                let x = 1.5 * lambda.sqrt() * h0 * time;
+                h0 * lambda.sqrt() / x.tanh()
+            }
+        }
+    }
+
+    /// Cosmic time at which the scale factor reaches `scale_factor`.
+    pub fn time_at_scale_factor(&self, scale_factor: f64) -> f64 {
+        let h0 = self.hubble_rate_today();
+        match self.history {
+            ExpansionHistory::MatterOnly => scale_factor.powf(1.5) / (1.5 * h0),
+            ExpansionHistory::LambdaCdm => {
+                let (matter, lambda) = self.densities();
+                let growth = (scale_factor.powi(3) * lambda / matter).sqrt();
+                growth.asinh() / (1.5 * lambda.sqrt() * h0)
+            }
+        }
+    }
+
+    /// Cosmic time the simulation starts at.
+    pub fn start_time(&self) -> f64 {
+        self.time_at_scale_factor(1.0 / (1.0 + self.start_redshift))
+    }
+
+    /// Ω_m and Ω_Λ of the flat ΛCDM history.
+    fn densities(&self) -> (f64, f64) {
+        let matter = self
+            .matter_density
+            .clamp(MIN_MATTER_DENSITY, 1.0 - MIN_MATTER_DENSITY);
+        (matter, 1.0 - matter)
+    }
+}
+
+impl Default for Cosmology {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
@@ -0,0 +1,129 @@
+use std::backtrace::Backtrace;
+use std::fs;
+use std::io;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Report written by the panic hook and shown on the next launch.
+pub const CRASH_REPORT_FILE: &str = "crash_report.txt";
+/// Where a report goes once it has been shown, so it is offered only once.
+pub const SEEN_CRASH_REPORT_FILE: &str = "crash_report.last.txt";
+/// Snapshot written periodically while the simulation runs.
+pub const CHECKPOINT_FILE: &str = "checkpoint.zip";
+pub const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(60);
+/// How often the UI thread refreshes the scenario details a report includes.
+pub const CRASH_CONTEXT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Scenario and configuration details copied into a crash report. Kept outside the UI
+/// state so the hook never waits on, or is poisoned by, a lock the panicking thread held.
+static CRASH_CONTEXT: Mutex<String> = Mutex::new(String::new());
+
+pub fn set_crash_context(context: String) {
+    *CRASH_CONTEXT.lock().unwrap_or_else(|e| e.into_inner()) = context;
+}
+
+/// A crash report left by an earlier run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CrashReport {
+    pub path: PathBuf,
+    pub text: String,
+    /// The last checkpoint, if one was written before the crash.
+    pub checkpoint: Option<PathBuf>,
+}
+
+/// Builds the text of a crash report.
+pub fn format_crash_report(
+    thread: &str,
+    message: &str,
+    location: &str,
+    backtrace: &str,
+    context: &str,
+) -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    format!(
+        "{} {} crash report\n\
+         Time: {} s since the Unix epoch\n\
+         Thread: {}\n\
+         Panic: {}\n\
+         Location: {}\n\n\
+         Scenario:\n{}\n\n\
+         Backtrace:\n{}\n",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION"),
+        seconds,
+        thread,
+        message,
+        location,
+        context,
+        backtrace
+    )
+}
+
+/// Returns the panic payload as text when it is a string.
+pub fn panic_message(info: &PanicHookInfo<'_>) -> String {
+    let payload = info.payload();
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "(non-string panic payload)".to_string())
+}
+
+/// Installs a panic hook that writes [`CRASH_REPORT_FILE`] into `dir` with the panicking
+/// thread (simulation, export, or the main render thread), its backtrace, and the latest
+/// crash context, then runs the previous hook.
+pub fn install_panic_hook(dir: PathBuf) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let thread = std::thread::current();
+        let location = info
+            .location()
+            .map(|location| location.to_string())
+            .unwrap_or_default();
+        let context = CRASH_CONTEXT
+            .lock()
+            .map(|context| context.clone())
+            .unwrap_or_default();
+        let report = format_crash_report(
+            thread.name().unwrap_or("unnamed"),
+            &panic_message(info),
+            &location,
+            &Backtrace::force_capture().to_string(),
+            &context,
+        );
+        match write_crash_report(&dir, &report) {
+            Ok(path) => eprintln!("Crash report written to {}", path.display()),
+            Err(e) => eprintln!("Failed to write crash report: {}", e),
+        }
+        previous(info);
+    }));
+}
+
+/// Writes `report` to [`CRASH_REPORT_FILE`] in `dir`, replacing an unseen older one.
+pub fn write_crash_report(dir: &Path, report: &str) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(CRASH_REPORT_FILE);
+    fs::write(&path, report)?;
+    Ok(path)
+}
+
+/// Takes the report an earlier run left in `dir`, moving it to [`SEEN_CRASH_REPORT_FILE`]
+/// so the next launch does not offer it again.
+pub fn take_crash_report(dir: &Path) -> Option<CrashReport> {
+    let text = fs::read_to_string(dir.join(CRASH_REPORT_FILE)).ok()?;
+    let seen = dir.join(SEEN_CRASH_REPORT_FILE);
+    let path = match fs::rename(dir.join(CRASH_REPORT_FILE), &seen) {
+        Ok(()) => seen,
+        Err(_) => dir.join(CRASH_REPORT_FILE),
+    };
+    let checkpoint = Some(dir.join(CHECKPOINT_FILE)).filter(|checkpoint| checkpoint.is_file());
+    Some(CrashReport {
+        path,
+        text,
+        checkpoint,
+    })
+}
@@ -0,0 +1,179 @@
+use serde::{Deserialize, Serialize};
+
+/// How the window covers the monitor in fullscreen.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum FullscreenKind {
+    /// A borderless window at the desktop resolution; switches instantly.
+    #[default]
+    Borderless,
+    /// Takes over the monitor with the chosen video mode.
+    Exclusive,
+}
+
+impl std::fmt::Display for FullscreenKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FullscreenKind::Borderless => write!(f, "Borderless"),
+            FullscreenKind::Exclusive => write!(f, "Exclusive"),
+        }
+    }
+}
+
+impl FullscreenKind {
+    pub const ALL: [Self; 2] = [Self::Borderless, Self::Exclusive];
+}
+
+/// A monitor video mode.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Resolution {
+    pub width: u32,
+    pub height: u32,
+    pub refresh_millihertz: u32,
+}
+
+impl std::fmt::Display for Resolution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} × {} @ {:.2} Hz",
+            self.width,
+            self.height,
+            self.refresh_millihertz as f64 / 1000.0
+        )
+    }
+}
+
+/// Fullscreen state and target, persisted in settings.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, Default)]
+#[serde(default)]
+pub struct DisplayMode {
+    pub fullscreen: bool,
+    pub kind: FullscreenKind,
+    /// Monitor label from [`monitor_label`]; `None` uses the monitor the window is on.
+    pub monitor: Option<String>,
+    /// Video mode for exclusive fullscreen; `None` picks the largest the monitor offers.
+    pub resolution: Option<Resolution>,
+}
+
+/// A connected monitor as listed in the Settings panel.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MonitorInfo {
+    pub label: String,
+    /// Video modes, largest first.
+    pub resolutions: Vec<Resolution>,
+}
+
+/// Names a monitor by the name the platform reports, or by its position when it has none.
+pub fn monitor_label(index: usize, name: Option<String>) -> String {
+    name.unwrap_or_else(|| format!("Monitor {}", index + 1))
+}
+
+/// Returns the index in `modes` of the video mode for exclusive fullscreen: `wanted` when
+/// the monitor offers it, otherwise the fastest mode of the wanted size, otherwise the
+/// largest and fastest mode. `None` when the monitor reports no modes.
+pub fn choose_resolution(modes: &[Resolution], wanted: Option<Resolution>) -> Option<usize> {
+    if let Some(wanted) = wanted
+        && let Some(index) = modes.iter().position(|mode| *mode == wanted)
+    {
+        return Some(index);
+    }
+    let same_size = |mode: &Resolution| {
+        wanted.is_some_and(|w| w.width == mode.width && w.height == mode.height)
+    };
+    modes
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, mode)| {
+            (
+                same_size(mode),
+                mode.width as u64 * mode.height as u64,
+                mode.refresh_millihertz,
+            )
+        })
+        .map(|(index, _)| index)
+}
+
+/// Sorts video modes largest and fastest first and drops duplicates that differ only in
+/// bit depth.
+pub fn sort_resolutions(resolutions: &mut Vec<Resolution>) {
+    resolutions.sort_by_key(|mode| {
+        std::cmp::Reverse((
+            mode.width as u64 * mode.height as u64,
+            mode.width,
+            mode.refresh_millihertz,
+        ))
+    });
+    resolutions.dedup();
+}
+
+#[cfg(feature = "gui")]
+fn resolution_of(mode: &winit::monitor::VideoModeHandle) -> Resolution {
+    let size = mode.size();
+    Resolution {
+        width: size.width,
+        height: size.height,
+        refresh_millihertz: mode.refresh_rate_millihertz(),
+    }
+}
+
+/// Lists the connected monitors and returns the index of the one the window is on.
+#[cfg(feature = "gui")]
+pub fn list_monitors(window: &winit::window::Window) -> (Vec<MonitorInfo>, Option<usize>) {
+    let current = window.current_monitor();
+    let mut current_index = None;
+    let monitors = window
+        .available_monitors()
+        .enumerate()
+        .map(|(index, monitor)| {
+            if current.as_ref() == Some(&monitor) {
+                current_index = Some(index);
+            }
+            let mut resolutions: Vec<Resolution> = monitor
+                .video_modes()
+                .map(|mode| resolution_of(&mode))
+                .collect();
+            sort_resolutions(&mut resolutions);
+            MonitorInfo {
+                label: monitor_label(index, monitor.name()),
+                resolutions,
+            }
+        })
+        .collect();
+    (monitors, current_index)
+}
+
+/// Resolves `mode` against the connected monitors. Exclusive fullscreen falls back to
+/// borderless when the monitor reports no video modes; a missing monitor falls back to the
+/// one the window is on.
+#[cfg(feature = "gui")]
+pub fn fullscreen_for(
+    window: &winit::window::Window,
+    mode: &DisplayMode,
+) -> Option<winit::window::Fullscreen> {
+    use winit::window::Fullscreen;
+
+    if !mode.fullscreen {
+        return None;
+    }
+    let monitor = mode
+        .monitor
+        .as_deref()
+        .and_then(|wanted| {
+            window
+                .available_monitors()
+                .enumerate()
+                .find(|(index, monitor)| monitor_label(*index, monitor.name()) == wanted)
+                .map(|(_, monitor)| monitor)
+        })
+        .or_else(|| window.current_monitor());
+    if mode.kind == FullscreenKind::Exclusive
+        && let Some(monitor) = monitor.as_ref()
+    {
+        let modes: Vec<_> = monitor.video_modes().collect();
+        let resolutions: Vec<Resolution> = modes.iter().map(resolution_of).collect();
+        if let Some(index) = choose_resolution(&resolutions, mode.resolution) {
+            return Some(Fullscreen::Exclusive(modes[index].clone()));
+        }
+    }
+    Some(Fullscreen::Borderless(monitor))
+}
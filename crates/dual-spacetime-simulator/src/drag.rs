@@ -0,0 +1,143 @@
+use crate::simulation::{G, Particle};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Damping rate in 1/s used until the user picks another (an e-folding time of ~3 years).
+pub const DEFAULT_DAMPING_RATE: f64 = 1e-8;
+/// Background density in kg/m³, roughly the stellar density of the solar neighborhood.
+pub const DEFAULT_BACKGROUND_DENSITY: f64 = 5e-21;
+/// Background velocity dispersion in m/s.
+pub const DEFAULT_VELOCITY_DISPERSION: f64 = 2e5;
+pub const DEFAULT_COULOMB_LOGARITHM: f64 = 10.0;
+/// Below this `X = v / (√2 σ)` the Chandrasekhar factor is summed as a series, which avoids
+/// the cancellation between `erf(X)` and its Gaussian correction at small speeds.
+const SERIES_LIMIT: f64 = 2.0;
+
+/// Velocity-dependent drag applied on top of gravity.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum DragModel {
+    #[default]
+    Off,
+    /// `dv/dt = −γ v`.
+    Linear,
+    /// Chandrasekhar dynamical friction against a uniform Maxwellian background.
+    DynamicalFriction,
+}
+
+impl DragModel {
+    pub const ALL: [Self; 3] = [Self::Off, Self::Linear, Self::DynamicalFriction];
+}
+
+impl std::fmt::Display for DragModel {
+    /// Formats drag model names for selection controls.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            DragModel::Off => "Off",
+            DragModel::Linear => "Linear",
+            DragModel::DynamicalFriction => "Dynamical Friction",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// Drag coefficients in SI units; only the fields of the selected model are used.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DragForce {
+    pub model: DragModel,
+    /// Linear: damping rate γ in 1/s.
+    pub damping_rate: f64,
+    /// Dynamical friction: background density ρ in kg/m³.
+    pub background_density: f64,
+    /// Dynamical friction: background velocity dispersion σ in m/s.
+    pub velocity_dispersion: f64,
+    /// Dynamical friction: Coulomb logarithm ln Λ.
+    pub coulomb_logarithm: f64,
+}
+
+impl Default for DragForce {
+    fn default() -> Self {
+        Self {
+            model: DragModel::Off,
+            damping_rate: DEFAULT_DAMPING_RATE,
+            background_density: DEFAULT_BACKGROUND_DENSITY,
+            velocity_dispersion: DEFAULT_VELOCITY_DISPERSION,
+            coulomb_logarithm: DEFAULT_COULOMB_LOGARITHM,
+        }
+    }
+}
+
+impl DragForce {
+    /// Rate in 1/s at which drag shrinks the speed of a particle of `mass` moving at
+    /// `speed`, both in simulation units at world `scale`.
+    pub fn deceleration_rate(&self, speed: f64, mass: f64, scale: f64) -> f64 {
+        match self.model {
+            DragModel::Off => 0.0,
+            DragModel::Linear => self.damping_rate,
+            DragModel::DynamicalFriction => {
+                let sigma = self.velocity_dispersion / scale;
+                if sigma <= 0.0 {
+                    return 0.0;
+                }
+                let x = speed / (std::f64::consts::SQRT_2 * sigma);
+                // The density is scale-invariant: kg/scale³ over (m/scale)³.
+                4.0 * std::f64::consts::PI * G * G * mass * self.dynamical_friction_density()
+                    / (2.0 * std::f64::consts::SQRT_2 * sigma.powi(3))
+                    * chandrasekhar_shape(x)
+            }
+        }
+    }
+
+    /// ρ ln Λ, the background term of the Chandrasekhar formula.
+    pub fn dynamical_friction_density(&self) -> f64 {
+        self.background_density * self.coulomb_logarithm
+    }
+
+    /// Slows `particle` for `delta_seconds`. The rate is applied as an exponential decay,
+    /// so a large step never reverses the velocity.
+    pub fn apply(&self, particle: &mut Particle, scale: f64, delta_seconds: f64) {
+        let speed = particle.velocity.length();
+        let rate = self.deceleration_rate(speed, particle.mass, scale);
+        particle.velocity *= (-rate * delta_seconds).exp();
+    }
+}
+
+/// `[erf(X) − 2X/√π e^(−X²)] / X³`, the Chandrasekhar velocity factor divided by `X³`;
+/// tends to `4 / (3√π)` as `X → 0`.
+pub fn chandrasekhar_shape(x: f64) -> f64 {
+    let two_over_sqrt_pi = std::f64::consts::FRAC_2_SQRT_PI;
+    if x >= SERIES_LIMIT {
+        let bracket = erf(x) - two_over_sqrt_pi * x * (-x * x).exp();
+        return bracket / (x * x * x);
+    }
+    // 2/√π Σₙ₌₁ (−1)ⁿ⁺¹ 2n X²ⁿ⁻² / (n! (2n + 1))
+    let x_sq = x * x;
+    let mut power = 1.0; // (−1)ⁿ⁺¹ X²ⁿ⁻² / n!
+    let mut sum = 0.0;
+    for n in 1..64 {
+        let n = n as f64;
+        let term = power * 2.0 * n / (2.0 * n + 1.0);
+        sum += term;
+        if term.abs() < 1e-17 * sum.abs() {
+            break;
+        }
+        power *= -x_sq / (n + 1.0);
+    }
+    two_over_sqrt_pi * sum
+}
+
+/// Error function (Abramowitz & Stegun 7.1.26, absolute error below 1.5e-7).
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    (1.0 - poly * (-x * x).exp()).copysign(x)
+}
+
+/// Slows every particle by `drag` for `delta_seconds`.
+pub fn apply_drag(particles: &mut [Particle], drag: DragForce, scale: f64, delta_seconds: f64) {
+    particles
+        .par_iter_mut()
+        .for_each(|particle| drag.apply(particle, scale, delta_seconds));
+}
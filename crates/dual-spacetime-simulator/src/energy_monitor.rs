@@ -0,0 +1,108 @@
+/// Simulation frames between energy checks.
+pub const ENERGY_CHECK_INTERVAL: i64 = 30;
+/// Relative drift `|E - E₀| / |E₀|` that raises the warning by default.
+pub const DEFAULT_ENERGY_DRIFT_THRESHOLD: f64 = 1e-2;
+pub const MIN_ENERGY_DRIFT_THRESHOLD: f64 = 1e-8;
+pub const MAX_ENERGY_DRIFT_THRESHOLD: f64 = 1.0;
+/// Largest live particle count checked; the O(N²) energy sum would stall the render loop
+/// beyond it.
+pub const ENERGY_MONITOR_MAX_PARTICLES: usize = 20_000;
+/// Fraction of the step that would just meet the threshold suggested, leaving headroom.
+const SUGGESTION_SAFETY: f64 = 0.5;
+
+/// Tracks the relative total-energy drift of the running simulation against the energy
+/// measured after the last reset.
+#[derive(Clone, Debug)]
+pub struct EnergyMonitor {
+    pub enabled: bool,
+    pub threshold: f64,
+    /// Reference energy, the frame it was measured at, and the live particle count then.
+    reference: Option<(f64, i64, usize)>,
+    drift: Option<f64>,
+    checked_frame: Option<i64>,
+    dismissed: bool,
+}
+
+impl Default for EnergyMonitor {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold: DEFAULT_ENERGY_DRIFT_THRESHOLD,
+            reference: None,
+            drift: None,
+            checked_frame: None,
+            dismissed: false,
+        }
+    }
+}
+
+impl EnergyMonitor {
+    /// Returns whether the energy should be measured at `frame`: every
+    /// [`ENERGY_CHECK_INTERVAL`] frames, and right away after the frame counter went back.
+    pub fn is_due(&self, frame: i64) -> bool {
+        self.enabled
+            && self
+                .checked_frame
+                .is_none_or(|checked| frame < checked || frame - checked >= ENERGY_CHECK_INTERVAL)
+    }
+
+    /// Records the total `energy` of `live_count` particles at `frame`. The first
+    /// measurement, and any after the particle count changed or the frame counter went
+    /// back (adding, deleting, undo, snapshot load), becomes the new reference.
+    pub fn record(&mut self, frame: i64, live_count: usize, energy: f64) {
+        self.checked_frame = Some(frame);
+        match self.reference {
+            Some((reference, reference_frame, count))
+                if count == live_count && frame >= reference_frame =>
+            {
+                self.drift = (reference != 0.0).then(|| ((energy - reference) / reference).abs());
+            }
+            _ => {
+                self.reference = Some((energy, frame, live_count));
+                self.drift = None;
+                self.dismissed = false;
+            }
+        }
+    }
+
+    /// Forgets the reference so the next measurement starts over, as after a reset.
+    pub fn forget(&mut self) {
+        self.reference = None;
+        self.drift = None;
+        self.checked_frame = None;
+        self.dismissed = false;
+    }
+
+    /// Relative drift at the last check, once a reference was measured.
+    pub fn drift(&self) -> Option<f64> {
+        self.drift
+    }
+
+    /// Returns the drift while it exceeds the threshold, or is no longer finite because the
+    /// integration blew up, and the warning is not dismissed.
+    pub fn warning(&self) -> Option<f64> {
+        self.drift.filter(|&drift| {
+            self.enabled && !self.dismissed && (drift.is_nan() || drift > self.threshold)
+        })
+    }
+
+    /// Hides the warning until the next reference is measured.
+    pub fn dismiss(&mut self) {
+        self.dismissed = true;
+    }
+}
+
+/// Suggests a time per frame expected to keep the drift under `threshold`. The integrator
+/// moves positions and then velocities (symplectic Euler), whose energy error grows in
+/// proportion to the step, so the step shrinks by the ratio of threshold to drift, with
+/// headroom; a drift that is no longer finite halves the step. Returns `time_per_frame`
+/// unchanged when the drift is within the threshold.
+pub fn suggested_time_per_frame(time_per_frame: f64, drift: f64, threshold: f64) -> f64 {
+    if !drift.is_finite() {
+        return time_per_frame * SUGGESTION_SAFETY;
+    }
+    if drift <= threshold || threshold <= 0.0 {
+        return time_per_frame;
+    }
+    time_per_frame * SUGGESTION_SAFETY * threshold / drift
+}
@@ -0,0 +1,227 @@
+use crate::simulation::{EPSILON, G, Particle};
+use glam::DVec3;
+use rayon::prelude::*;
+
+/// Default directory (relative to the working directory) for snapshots taken by triggers.
+pub const DEFAULT_EVENT_SNAPSHOT_DIR: &str = "event_snapshots";
+/// Maximum number of lines kept in the event log.
+pub const EVENT_LOG_CAPACITY: usize = 200;
+
+/// Condition kinds offered when adding a trigger.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EventConditionKind {
+    TimeReached,
+    CloseApproach,
+    EnergyDrift,
+}
+
+impl EventConditionKind {
+    pub const ALL: [Self; 3] = [Self::TimeReached, Self::CloseApproach, Self::EnergyDrift];
+
+    /// Returns the condition of this kind with default parameters.
+    pub fn default_condition(self) -> EventCondition {
+        match self {
+            Self::TimeReached => EventCondition::TimeReached { seconds: 0.0 },
+            Self::CloseApproach => EventCondition::CloseApproach {
+                first_id: 1,
+                second_id: 2,
+                distance: 1.0,
+            },
+            Self::EnergyDrift => EventCondition::EnergyDrift { tolerance: 1e-3 },
+        }
+    }
+}
+
+impl std::fmt::Display for EventConditionKind {
+    /// Formats condition kind names for UI selection controls.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            EventConditionKind::TimeReached => "Time Reached",
+            EventConditionKind::CloseApproach => "Close Approach",
+            EventConditionKind::EnergyDrift => "Energy Drift",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// Condition that fires a trigger.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EventCondition {
+    /// Simulation time (seconds) reached or passed.
+    TimeReached { seconds: f64 },
+    /// The particles with these IDs are closer than `distance` (base-scale units).
+    CloseApproach {
+        first_id: u64,
+        second_id: u64,
+        distance: f64,
+    },
+    /// Relative total-energy drift `|E - E₀| / |E₀|` exceeds `tolerance`.
+    EnergyDrift { tolerance: f64 },
+}
+
+impl EventCondition {
+    pub fn kind(&self) -> EventConditionKind {
+        match self {
+            Self::TimeReached { .. } => EventConditionKind::TimeReached,
+            Self::CloseApproach { .. } => EventConditionKind::CloseApproach,
+            Self::EnergyDrift { .. } => EventConditionKind::EnergyDrift,
+        }
+    }
+
+    /// Returns whether evaluating this condition needs the particle list.
+    pub fn needs_particles(&self) -> bool {
+        !matches!(self, Self::TimeReached { .. })
+    }
+
+    /// Evaluates the condition. Energy drift never fires without a reference energy.
+    pub fn is_met(&self, context: &EventContext) -> bool {
+        match *self {
+            Self::TimeReached { seconds } => context.time >= seconds,
+            Self::CloseApproach {
+                first_id,
+                second_id,
+                distance,
+            } => {
+                let find = |id: u64| {
+                    context
+                        .particles
+                        .iter()
+                        .find(|p| p.id == id && p.color[3] != 0.0)
+                };
+                match (find(first_id), find(second_id)) {
+                    (Some(a), Some(b)) if first_id != second_id => {
+                        a.position.distance(b.position) < distance
+                    }
+                    _ => false,
+                }
+            }
+            Self::EnergyDrift { tolerance } => match (context.reference_energy, context.energy) {
+                (Some(reference), Some(energy)) if reference != 0.0 => {
+                    ((energy - reference) / reference).abs() > tolerance
+                }
+                _ => false,
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for EventCondition {
+    /// Formats the condition with its parameters for the event log.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TimeReached { seconds } => write!(f, "t ≥ {:e} s", seconds),
+            Self::CloseApproach {
+                first_id,
+                second_id,
+                distance,
+            } => write!(f, "|r{} - r{}| < {:e}", first_id, second_id, distance),
+            Self::EnergyDrift { tolerance } => write!(f, "|ΔE/E₀| > {:e}", tolerance),
+        }
+    }
+}
+
+/// Action run once when a trigger's condition is met.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EventAction {
+    Pause,
+    Snapshot,
+    Log,
+}
+
+impl EventAction {
+    pub const ALL: [Self; 3] = [Self::Pause, Self::Snapshot, Self::Log];
+}
+
+impl std::fmt::Display for EventAction {
+    /// Formats action names for UI selection controls.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            EventAction::Pause => "Pause",
+            EventAction::Snapshot => "Snapshot",
+            EventAction::Log => "Log",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// One-shot trigger: fires its action the first time the condition holds, then stays
+/// fired until re-armed (on reset or from the Events panel).
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct EventTrigger {
+    pub condition: EventCondition,
+    pub action: EventAction,
+    pub enabled: bool,
+    pub fired: bool,
+}
+
+impl EventTrigger {
+    pub fn new(condition: EventCondition, action: EventAction) -> Self {
+        Self {
+            condition,
+            action,
+            enabled: true,
+            fired: false,
+        }
+    }
+
+    /// Returns whether the trigger can still fire.
+    pub fn is_armed(&self) -> bool {
+        self.enabled && !self.fired
+    }
+}
+
+/// Simulation state a trigger condition is evaluated against.
+pub struct EventContext<'a> {
+    /// Simulation time in seconds.
+    pub time: f64,
+    pub particles: &'a [Particle],
+    /// Total energy when energy triggers were armed.
+    pub reference_energy: Option<f64>,
+    /// Current total energy, computed only when an energy trigger is armed.
+    pub energy: Option<f64>,
+}
+
+/// Marks every armed trigger whose condition is met as fired and returns their indices.
+pub fn fire_triggers(triggers: &mut [EventTrigger], context: &EventContext) -> Vec<usize> {
+    let mut fired = Vec::new();
+    for (index, trigger) in triggers.iter_mut().enumerate() {
+        if trigger.is_armed() && trigger.condition.is_met(context) {
+            trigger.fired = true;
+            fired.push(index);
+        }
+    }
+    fired
+}
+
+/// Newtonian total energy (kinetic plus pairwise potential) of live particles in
+/// simulation units, using the same `ε`-softened potential as the force loop.
+///
+/// O(N²); the Events panel only evaluates it while an energy-drift trigger is armed.
+pub fn total_energy(particles: &[Particle]) -> f64 {
+    let (kinetic, potential) = energy_components(particles);
+    kinetic + potential
+}
+
+/// Kinetic and pairwise potential energy of live particles, the two parts of
+/// [`total_energy`].
+pub fn energy_components(particles: &[Particle]) -> (f64, f64) {
+    let live: Vec<&Particle> = particles.iter().filter(|p| p.color[3] != 0.0).collect();
+    live.par_iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let potential: f64 = live[i + 1..]
+                .iter()
+                .map(|q| -G * p.mass * q.mass / (p.position.distance(q.position) + EPSILON))
+                .sum();
+            (0.5 * p.mass * p.velocity.length_squared(), potential)
+        })
+        .reduce(|| (0.0, 0.0), |a, b| (a.0 + b.0, a.1 + b.1))
+}
+
+/// Total momentum `Σ m v` of live particles in simulation units.
+pub fn total_momentum(particles: &[Particle]) -> DVec3 {
+    particles
+        .iter()
+        .filter(|p| p.color[3] != 0.0)
+        .fold(DVec3::ZERO, |acc, p| acc + p.velocity * p.mass)
+}
@@ -0,0 +1,159 @@
+use crate::toast::{ToastLevel, Toasts};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, SyncSender, TrySendError, sync_channel};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+/// Exports that may wait in the queue; further submissions are refused until it drains.
+pub const EXPORT_QUEUE_CAPACITY: usize = 8;
+
+type WriteFn = Box<dyn FnOnce(&Path) -> io::Result<()> + Send>;
+
+struct ExportJob {
+    path: PathBuf,
+    write: WriteFn,
+}
+
+/// Progress of the export thread as shown in the UI.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExportStatus {
+    /// Jobs submitted but not finished, including the one being written.
+    pub pending: usize,
+    /// File currently being written.
+    pub current: Option<PathBuf>,
+    pub completed: usize,
+    pub failed: usize,
+    /// Outcome of the most recent job: the written path or an error message.
+    pub last_result: Option<Result<PathBuf, String>>,
+}
+
+/// Dedicated I/O thread fed through a bounded channel, so snapshot and CSV writes
+/// (serialization and compression included) never run on the render or simulation
+/// threads. Callers pass cloned state inside the write closure. The thread is started
+/// on the first submission and drained on drop.
+#[derive(Default)]
+pub struct ExportWriter {
+    sender: Option<SyncSender<ExportJob>>,
+    handle: Option<JoinHandle<()>>,
+    status: Arc<Mutex<ExportStatus>>,
+    toasts: Option<Toasts>,
+}
+
+impl ExportWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Like [`Self::new`], but posts a toast when each job finishes.
+    pub fn with_toasts(toasts: Toasts) -> Self {
+        Self {
+            sender: None,
+            handle: None,
+            status: Arc::default(),
+            toasts: Some(toasts),
+        }
+    }
+
+    /// Queues `write(path)` on the export thread. Fails without blocking when
+    /// [`EXPORT_QUEUE_CAPACITY`] jobs are already waiting.
+    pub fn submit(
+        &mut self,
+        path: PathBuf,
+        write: impl FnOnce(&Path) -> io::Result<()> + Send + 'static,
+    ) -> Result<(), String> {
+        if self.sender.is_none() {
+            let (sender, receiver) = sync_channel(EXPORT_QUEUE_CAPACITY);
+            let status = Arc::clone(&self.status);
+            let toasts = self.toasts.clone();
+            self.handle = Some(
+                std::thread::Builder::new()
+                    .name("export-writer".to_string())
+                    .spawn(move || run_export_thread(receiver, status, toasts))
+                    .map_err(|e| format!("Failed to start export thread: {}", e))?,
+            );
+            self.sender = Some(sender);
+        }
+        let Some(sender) = &self.sender else {
+            return Err("Export thread has stopped".to_string());
+        };
+        self.status.lock().unwrap().pending += 1;
+        let job = ExportJob {
+            path,
+            write: Box::new(write),
+        };
+        match sender.try_send(job) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                let message = match e {
+                    TrySendError::Full(_) => {
+                        format!("Export queue is full ({} waiting)", EXPORT_QUEUE_CAPACITY)
+                    }
+                    TrySendError::Disconnected(_) => "Export thread has stopped".to_string(),
+                };
+                let mut status = self.status.lock().unwrap();
+                status.pending -= 1;
+                status.failed += 1;
+                status.last_result = Some(Err(message.clone()));
+                Err(message)
+            }
+        }
+    }
+
+    pub fn status(&self) -> ExportStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.status.lock().unwrap().pending == 0
+    }
+
+    /// Closes the queue and waits until every submitted job has been written.
+    pub fn finish(&mut self) {
+        self.sender = None;
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for ExportWriter {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+fn run_export_thread(
+    receiver: Receiver<ExportJob>,
+    status: Arc<Mutex<ExportStatus>>,
+    toasts: Option<Toasts>,
+) {
+    for job in receiver {
+        status.lock().unwrap().current = Some(job.path.clone());
+        let result = (job.write)(&job.path);
+        let mut status = status.lock().unwrap();
+        status.current = None;
+        status.pending -= 1;
+        match result {
+            Ok(()) => {
+                if let Some(toasts) = &toasts {
+                    toasts.post(
+                        ToastLevel::Success,
+                        format!("Export complete: {}", job.path.display()),
+                    );
+                }
+                status.completed += 1;
+                status.last_result = Some(Ok(job.path));
+            }
+            Err(e) => {
+                eprintln!("Failed to write {}: {}", job.path.display(), e);
+                let message = format!("{}: {}", job.path.display(), e);
+                if let Some(toasts) = &toasts {
+                    toasts.post(ToastLevel::Error, format!("Export failed: {}", message));
+                }
+                status.failed += 1;
+                status.last_result = Some(Err(message));
+            }
+        }
+    }
+}
@@ -0,0 +1,361 @@
+use crate::simulation::{EPSILON, G, Particle};
+use crate::tree_gravity::{NO_CHILD, Octree, rms_relative_error};
+use dst_math::gravity::newtonian_gravity_pair;
+use dst_math::summation::{Summation, Vec3Sum};
+use glam::DVec3;
+use rayon::prelude::*;
+
+/// Expansion order used until the user picks another.
+pub const DEFAULT_EXPANSION_ORDER: u32 = 4;
+pub const MIN_EXPANSION_ORDER: u32 = 1;
+pub const MAX_EXPANSION_ORDER: u32 = 8;
+/// Two cells interact through their expansions when the sum of their radii is below this
+/// fraction of the distance between their expansion centers. The expansion order alone
+/// then sets the accuracy; the error shrinks about as this ratio to the power of the
+/// order.
+const SEPARATION: f64 = 0.4;
+
+/// One Cartesian derivative `∂ⁿ(1/r)` of degree `|n|`, from the recurrence
+/// `|n| r² Tₙ = −(2|n| − 1) Σᵢ nᵢ xᵢ Tₙ₋ₑᵢ − (|n| − 1) Σᵢ nᵢ(nᵢ − 1) Tₙ₋₂ₑᵢ`.
+#[derive(Clone, Debug)]
+struct DerivativeTerms {
+    degree: f64,
+    /// Lower index, axis, and coefficient `−(2|n| − 1) nᵢ`.
+    first: Vec<(usize, usize, f64)>,
+    /// Lower index and coefficient `−(|n| − 1) nᵢ(nᵢ − 1)`.
+    second: Vec<(usize, f64)>,
+}
+
+/// Index tables for Cartesian expansions up to one order. Multi-indices `n = (nx, ny,
+/// nz)` with `|n| ≤ order` are stored by increasing degree.
+#[derive(Clone, Debug)]
+struct Expansion {
+    len: usize,
+    /// For every index but the first: a lower index, the axis it differs on, and
+    /// `1 / nᵢ`, so `vⁿ / n!` follows from the lower one.
+    power_steps: Vec<(usize, usize, f64)>,
+    derivative_terms: Vec<DerivativeTerms>,
+    /// Every `(a, b, a + b)` with `|a + b| ≤ order`.
+    products: Vec<(usize, usize, usize)>,
+    /// Every `(k, axis, k + e_axis)` with `|k| < order`, for the gradient of a local
+    /// expansion.
+    gradient: Vec<(usize, usize, usize)>,
+}
+
+impl Expansion {
+    fn new(order: u32) -> Self {
+        let p = order as usize;
+        let mut indices: Vec<[usize; 3]> = Vec::new();
+        for degree in 0..=p {
+            for x in (0..=degree).rev() {
+                for y in (0..=degree - x).rev() {
+                    indices.push([x, y, degree - x - y]);
+                }
+            }
+        }
+        let side = p + 1;
+        let mut lookup = vec![usize::MAX; side * side * side];
+        for (i, n) in indices.iter().enumerate() {
+            lookup[(n[0] * side + n[1]) * side + n[2]] = i;
+        }
+        let find = |n: [usize; 3]| {
+            if n.iter().sum::<usize>() > p {
+                None
+            } else {
+                Some(lookup[(n[0] * side + n[1]) * side + n[2]])
+            }
+        };
+        let lower = |n: [usize; 3], axis: usize, by: usize| {
+            let mut m = n;
+            m[axis] -= by;
+            find(m).unwrap()
+        };
+        let mut power_steps = Vec::with_capacity(indices.len());
+        let mut derivative_terms = Vec::with_capacity(indices.len());
+        for &n in &indices {
+            let degree = n.iter().sum::<usize>();
+            let axis = (0..3).find(|&axis| n[axis] > 0).unwrap_or(0);
+            power_steps.push(if degree == 0 {
+                (0, 0, 1.0)
+            } else {
+                (lower(n, axis, 1), axis, 1.0 / n[axis] as f64)
+            });
+            let d = degree as f64;
+            derivative_terms.push(DerivativeTerms {
+                degree: d,
+                first: (0..3)
+                    .filter(|&i| n[i] >= 1)
+                    .map(|i| (lower(n, i, 1), i, -(2.0 * d - 1.0) * n[i] as f64))
+                    .collect(),
+                second: (0..3)
+                    .filter(|&i| n[i] >= 2)
+                    .map(|i| {
+                        let c = (n[i] * (n[i] - 1)) as f64;
+                        (lower(n, i, 2), -(d - 1.0) * c)
+                    })
+                    .collect(),
+            });
+        }
+        let mut products = Vec::new();
+        let mut gradient = Vec::new();
+        for (a, na) in indices.iter().enumerate() {
+            for (b, nb) in indices.iter().enumerate() {
+                if let Some(c) = find([na[0] + nb[0], na[1] + nb[1], na[2] + nb[2]]) {
+                    products.push((a, b, c));
+                }
+            }
+            for axis in 0..3 {
+                let mut m = *na;
+                m[axis] += 1;
+                if let Some(c) = find(m) {
+                    gradient.push((a, axis, c));
+                }
+            }
+        }
+        Self {
+            len: indices.len(),
+            power_steps,
+            derivative_terms,
+            products,
+            gradient,
+        }
+    }
+
+    /// Writes `vⁿ / n!` for every index into `out`.
+    fn powers(&self, v: DVec3, out: &mut [f64]) {
+        out[0] = 1.0;
+        for (i, &(lower, axis, inverse)) in self.power_steps.iter().enumerate().skip(1) {
+            out[i] = out[lower] * v[axis] * inverse;
+        }
+    }
+
+    /// Writes `∂ⁿ(1/|r|)` for every index into `out`.
+    fn derivatives(&self, r: DVec3, out: &mut [f64]) {
+        let r_sq = r.length_squared();
+        out[0] = 1.0 / r_sq.sqrt();
+        for (i, terms) in self.derivative_terms.iter().enumerate().skip(1) {
+            let mut sum = 0.0;
+            for &(lower, axis, c) in &terms.first {
+                sum += c * r[axis] * out[lower];
+            }
+            for &(lower, c) in &terms.second {
+                sum += c * out[lower];
+            }
+            out[i] = sum / (terms.degree * r_sq);
+        }
+    }
+}
+
+/// Velocity changes `G dt Σ m r̂ / r²` of every particle over a step with `time_g = G dt`,
+/// by the fast multipole method with Cartesian expansions up to `order`.
+///
+/// Each cell of the octree gets a multipole expansion about its center of mass, built
+/// from its particles or shifted up from its children. A dual walk of the tree pairs
+/// well-separated cells, whose multipoles become local expansions about the target
+/// cell's center; those are shifted down to the leaves and evaluated at each particle.
+/// Neighboring leaves are summed directly with `summation`, like the Normal engine sums
+/// them. Particles without a finite position get no change.
+pub fn fmm_velocity_deltas(
+    positions: &[DVec3],
+    masses: &[f64],
+    order: u32,
+    time_g: f64,
+    summation: Summation,
+) -> Vec<DVec3> {
+    let mut deltas = vec![DVec3::ZERO; positions.len()];
+    let tree = Octree::build(positions, masses);
+    if tree.nodes.is_empty() {
+        return deltas;
+    }
+    let expansion = Expansion::new(order.clamp(MIN_EXPANSION_ORDER, MAX_EXPANSION_ORDER));
+    let len = expansion.len;
+    let nodes = &tree.nodes;
+    let particles_of = |node: usize| nodes[node].particles.as_slice();
+
+    // Radii of every cell about its multipole and local expansion centers: measured for
+    // leaves, bounded through the children's for internal cells.
+    let mut radii: Vec<(f64, f64)> = (0..nodes.len())
+        .into_par_iter()
+        .map(|n| {
+            particles_of(n)
+                .iter()
+                .fold((0.0f64, 0.0f64), |(multipole, local), &i| {
+                    (
+                        multipole.max(positions[i].distance(nodes[n].center_of_mass)),
+                        local.max(positions[i].distance(nodes[n].center)),
+                    )
+                })
+        })
+        .collect();
+    for n in (0..nodes.len()).rev() {
+        for &child in nodes[n].children.iter().filter(|&&c| c != NO_CHILD) {
+            let (child_multipole, child_local) = radii[child as usize];
+            let child = &nodes[child as usize];
+            let multipole =
+                child_multipole + child.center_of_mass.distance(nodes[n].center_of_mass);
+            let local = child_local + child.center.distance(nodes[n].center);
+            radii[n] = (radii[n].0.max(multipole), radii[n].1.max(local));
+        }
+    }
+
+    // Leaf multipoles in parallel, then shifted up; children come after their parents.
+    let mut multipoles = vec![0.0; nodes.len() * len];
+    multipoles
+        .par_chunks_mut(len)
+        .enumerate()
+        .filter(|(n, _)| nodes[*n].is_leaf())
+        .for_each(|(n, multipole)| {
+            let mut powers = vec![0.0; len];
+            for &i in particles_of(n) {
+                expansion.powers(nodes[n].center_of_mass - positions[i], &mut powers);
+                for (m, p) in multipole.iter_mut().zip(&powers) {
+                    *m += masses[i] * p;
+                }
+            }
+        });
+    let mut powers = vec![0.0; len];
+    for n in (0..nodes.len()).rev() {
+        let (head, tail) = multipoles.split_at_mut((n + 1) * len);
+        let parent = &mut head[n * len..];
+        for &child in nodes[n].children.iter().filter(|&&c| c != NO_CHILD) {
+            let child = child as usize;
+            let offset = (child - n - 1) * len;
+            let moments = &tail[offset..offset + len];
+            expansion.powers(
+                nodes[n].center_of_mass - nodes[child].center_of_mass,
+                &mut powers,
+            );
+            for &(a, b, c) in &expansion.products {
+                parent[c] += moments[a] * powers[b];
+            }
+        }
+    }
+
+    // Interaction lists from a dual walk, splitting the larger cell of each pair.
+    let mut far: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+    let mut near: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+    let mut stack = vec![(0usize, 0usize)];
+    while let Some((target, source)) = stack.pop() {
+        let (a, b) = (&nodes[target], &nodes[source]);
+        if b.mass == 0.0 {
+            continue;
+        }
+        let distance = a.center.distance(b.center_of_mass);
+        if radii[target].1 + radii[source].0 < SEPARATION * distance {
+            far[target].push(source);
+        } else if a.is_leaf() && b.is_leaf() {
+            near[target].push(source);
+        } else if b.is_leaf() || (!a.is_leaf() && a.half_size >= b.half_size) {
+            for &child in a.children.iter().filter(|&&c| c != NO_CHILD) {
+                stack.push((child as usize, source));
+            }
+        } else {
+            for &child in b.children.iter().filter(|&&c| c != NO_CHILD) {
+                stack.push((target, child as usize));
+            }
+        }
+    }
+
+    // Multipoles of well-separated cells become local expansions, then shift down.
+    let mut locals = vec![0.0; nodes.len() * len];
+    locals
+        .par_chunks_mut(len)
+        .enumerate()
+        .filter(|(n, _)| !far[*n].is_empty())
+        .for_each(|(n, local)| {
+            let mut derivatives = vec![0.0; len];
+            for &source in &far[n] {
+                expansion.derivatives(
+                    nodes[n].center - nodes[source].center_of_mass,
+                    &mut derivatives,
+                );
+                let moments = &multipoles[source * len..(source + 1) * len];
+                for &(a, b, c) in &expansion.products {
+                    local[a] += moments[b] * derivatives[c];
+                }
+            }
+        });
+    for n in 0..nodes.len() {
+        let (head, tail) = locals.split_at_mut((n + 1) * len);
+        let parent = &head[n * len..];
+        for &child in nodes[n].children.iter().filter(|&&c| c != NO_CHILD) {
+            let child = child as usize;
+            let offset = (child - n - 1) * len;
+            let local = &mut tail[offset..offset + len];
+            expansion.powers(nodes[child].center - nodes[n].center, &mut powers);
+            for &(a, b, c) in &expansion.products {
+                local[a] += parent[c] * powers[b];
+            }
+        }
+    }
+
+    // Local expansions and neighboring leaves at every particle.
+    let leaves: Vec<usize> = (0..nodes.len()).filter(|&n| nodes[n].is_leaf()).collect();
+    let changes: Vec<(usize, DVec3)> = leaves
+        .par_iter()
+        .flat_map_iter(|&n| {
+            let local = &locals[n * len..(n + 1) * len];
+            let mut powers = vec![0.0; len];
+            let expansion = &expansion;
+            let near = &near[n];
+            particles_of(n).iter().map(move |&i| {
+                let point = positions[i];
+                expansion.powers(point - nodes[n].center, &mut powers);
+                let mut gradient = DVec3::ZERO;
+                for &(k, axis, c) in &expansion.gradient {
+                    gradient[axis] += local[c] * powers[k];
+                }
+                let mut acceleration = Vec3Sum::new(summation);
+                acceleration.add(gradient * time_g);
+                for &source in near {
+                    for &j in particles_of(source) {
+                        if j != i {
+                            acceleration.add(
+                                newtonian_gravity_pair(
+                                    point,
+                                    positions[j],
+                                    masses[j],
+                                    G,
+                                    time_g,
+                                    EPSILON,
+                                )
+                                .1,
+                            );
+                        }
+                    }
+                }
+                (i, acceleration.value())
+            })
+        })
+        .collect();
+    for (i, delta) in changes {
+        deltas[i] = delta;
+    }
+    deltas
+}
+
+/// FMM counterpart of the Normal engine's direct velocity update over `delta_seconds`.
+pub fn fmm_velocity_update(
+    particles: &mut [Particle],
+    delta_seconds: f64,
+    order: u32,
+    summation: Summation,
+) {
+    let positions: Vec<DVec3> = particles.iter().map(|p| p.position).collect();
+    let masses: Vec<f64> = particles.iter().map(|p| p.mass).collect();
+    let deltas = fmm_velocity_deltas(&positions, &masses, order, G * delta_seconds, summation);
+    particles
+        .par_iter_mut()
+        .zip(deltas)
+        .for_each(|(particle, delta)| particle.velocity += delta);
+}
+
+/// RMS relative error `|a_fmm − a_direct| / |a_direct|` of the FMM forces at expansion
+/// `order` over the `sample` particles. Costs one FMM pass plus O(N) per sampled
+/// particle; 0 when no sampled particle feels a force.
+pub fn fmm_relative_force_error(particles: &[Particle], order: u32, sample: &[usize]) -> f64 {
+    let positions: Vec<DVec3> = particles.iter().map(|p| p.position).collect();
+    let masses: Vec<f64> = particles.iter().map(|p| p.mass).collect();
+    let deltas = fmm_velocity_deltas(&positions, &masses, order, G, Summation::Naive);
+    rms_relative_error(&positions, &masses, sample, |i| deltas[i])
+}
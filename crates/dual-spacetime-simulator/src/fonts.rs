@@ -0,0 +1,96 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Japanese-capable fonts shipped with Windows, macOS and common Linux distributions, in
+/// the order they are tried when [`crate::settings::AppSettings::system_cjk_font`] is on.
+pub const SYSTEM_CJK_FONT_PATHS: &[&str] = &[
+    "C:\\Windows\\Fonts\\YuGothM.ttc",
+    "C:\\Windows\\Fonts\\meiryo.ttc",
+    "C:\\Windows\\Fonts\\msgothic.ttc",
+    "/System/Library/Fonts/ヒラギノ角ゴシック W3.ttc",
+    "/System/Library/Fonts/Hiragino Sans GB.ttc",
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/fonts-japanese-gothic.ttf",
+];
+
+pub const FONT_FILTER_NAME: &str = "Fonts";
+pub const FONT_FILTER_EXTS: [&str; 4] = ["ttf", "otf", "ttc", "otc"];
+/// Font file signatures: TrueType, OpenType (CFF), old Apple TrueType, and collections.
+const FONT_MAGICS: [&[u8; 4]; 4] = [b"\x00\x01\x00\x00", b"OTTO", b"true", b"ttcf"];
+
+/// Returns the first of [`SYSTEM_CJK_FONT_PATHS`] present on this machine.
+pub fn find_system_cjk_font() -> Option<PathBuf> {
+    SYSTEM_CJK_FONT_PATHS
+        .iter()
+        .map(PathBuf::from)
+        .find(|path| path.is_file())
+}
+
+/// Reads a font file, rejecting files without a TrueType/OpenType signature; egui panics
+/// on font data it cannot parse.
+fn read_font(path: &Path) -> Result<Vec<u8>, String> {
+    let bytes =
+        std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    if !FONT_MAGICS.iter().any(|magic| bytes.starts_with(*magic)) {
+        return Err(format!(
+            "{} is not a TrueType or OpenType font",
+            path.display()
+        ));
+    }
+    Ok(bytes)
+}
+
+/// Builds egui's font set with the user fonts in front of the proportional family, so
+/// they set the look of the UI, and after the default monospace font. The system CJK font
+/// is appended to both as a fallback for characters no earlier font covers. Returns the
+/// definitions and one message per font that could not be loaded.
+pub fn font_definitions(
+    user_fonts: &[PathBuf],
+    system_cjk_font: bool,
+) -> (egui::FontDefinitions, Vec<String>) {
+    let mut definitions = egui::FontDefinitions::default();
+    let mut errors = Vec::new();
+    let mut add = |definitions: &mut egui::FontDefinitions, path: &Path| {
+        let bytes = read_font(path).map_err(|e| errors.push(e)).ok()?;
+        let name = path.display().to_string();
+        definitions
+            .font_data
+            .insert(name.clone(), Arc::new(egui::FontData::from_owned(bytes)));
+        Some(name)
+    };
+    for (position, path) in user_fonts.iter().enumerate() {
+        let Some(name) = add(&mut definitions, path) else {
+            continue;
+        };
+        let proportional = family_mut(&mut definitions, egui::FontFamily::Proportional);
+        proportional.insert(position.min(proportional.len()), name.clone());
+        family_mut(&mut definitions, egui::FontFamily::Monospace).push(name);
+    }
+    if system_cjk_font
+        && let Some(name) = find_system_cjk_font().and_then(|path| add(&mut definitions, &path))
+    {
+        family_mut(&mut definitions, egui::FontFamily::Proportional).push(name.clone());
+        family_mut(&mut definitions, egui::FontFamily::Monospace).push(name);
+    }
+    (definitions, errors)
+}
+
+fn family_mut(
+    definitions: &mut egui::FontDefinitions,
+    family: egui::FontFamily,
+) -> &mut Vec<String> {
+    definitions.families.entry(family).or_default()
+}
+
+/// Replaces the fonts of `ctx` from the next frame on. Returns the load errors.
+pub fn install_fonts(
+    ctx: &egui::Context,
+    user_fonts: &[PathBuf],
+    system_cjk_font: bool,
+) -> Vec<String> {
+    let (definitions, errors) = font_definitions(user_fonts, system_cjk_font);
+    ctx.set_fonts(definitions);
+    errors
+}
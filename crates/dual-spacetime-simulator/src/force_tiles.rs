@@ -0,0 +1,234 @@
+use crate::simulation::{EPSILON, G};
+use dst_math::gravity::newtonian_gravity_pair;
+use dst_math::summation::{Summation, Vec3Sum};
+use glam::{DVec3, Vec3};
+use rayon::prelude::*;
+
+/// Targets per tile: their running sums stay in L1 while a source tile streams past.
+pub const I_TILE: usize = 64;
+/// Sources per tile: 1024 positions and masses (32 KiB in `f64`) fit in L1 or L2, so
+/// each one is fetched from memory once per target tile instead of once per target.
+pub const J_TILE: usize = 1024;
+
+/// Newtonian velocity change of every particle over a step with `time_g = G dt`, one
+/// target at a time against every source. The reference for [`tiled_velocity_deltas`].
+pub fn direct_velocity_deltas(
+    positions: &[DVec3],
+    masses: &[f64],
+    time_g: f64,
+    summation: Summation,
+) -> Vec<DVec3> {
+    positions
+        .par_iter()
+        .enumerate()
+        .map(|(i, &pos_i)| {
+            let mut acceleration = Vec3Sum::new(summation);
+            for (j, &pos_j) in positions.iter().enumerate() {
+                if j == i {
+                    continue;
+                }
+                acceleration
+                    .add(newtonian_gravity_pair(pos_i, pos_j, masses[j], G, time_g, EPSILON).1);
+            }
+            acceleration.value()
+        })
+        .collect()
+}
+
+/// [`direct_velocity_deltas`] in tiles of [`I_TILE`] targets × [`J_TILE`] sources, one
+/// target tile per rayon task. Every target still adds its sources in index order, so the
+/// result is bit-for-bit the same.
+pub fn tiled_velocity_deltas(
+    positions: &[DVec3],
+    masses: &[f64],
+    time_g: f64,
+    summation: Summation,
+) -> Vec<DVec3> {
+    let mut deltas = vec![DVec3::ZERO; positions.len()];
+    deltas
+        .par_chunks_mut(I_TILE)
+        .enumerate()
+        .for_each(|(tile, out)| {
+            let first = tile * I_TILE;
+            let targets = &positions[first..first + out.len()];
+            let mut sums = vec![Vec3Sum::new(summation); out.len()];
+            for (j_tile, (sources, source_masses)) in positions
+                .chunks(J_TILE)
+                .zip(masses.chunks(J_TILE))
+                .enumerate()
+            {
+                let j_first = j_tile * J_TILE;
+                for (k, (&pos_i, sum)) in targets.iter().zip(sums.iter_mut()).enumerate() {
+                    let i = first + k;
+                    for (offset, (&pos_j, &mass_j)) in sources.iter().zip(source_masses).enumerate()
+                    {
+                        if j_first + offset == i {
+                            continue;
+                        }
+                        sum.add(newtonian_gravity_pair(pos_i, pos_j, mass_j, G, time_g, EPSILON).1);
+                    }
+                }
+            }
+            for (delta, sum) in out.iter_mut().zip(&sums) {
+                *delta = sum.value();
+            }
+        });
+    deltas
+}
+
+/// Single-precision [`tiled_velocity_deltas`]: pair forces are computed and summed naively
+/// in `f32`, skipping sources closer than `√EPSILON`.
+pub fn tiled_velocity_deltas_f32(positions: &[Vec3], masses: &[f32], time_g: f32) -> Vec<Vec3> {
+    let epsilon = EPSILON as f32;
+    let mut deltas = vec![Vec3::ZERO; positions.len()];
+    deltas
+        .par_chunks_mut(I_TILE)
+        .enumerate()
+        .for_each(|(tile, out)| {
+            let first = tile * I_TILE;
+            let targets = &positions[first..first + out.len()];
+            for (j_tile, (sources, source_masses)) in positions
+                .chunks(J_TILE)
+                .zip(masses.chunks(J_TILE))
+                .enumerate()
+            {
+                let j_first = j_tile * J_TILE;
+                for (k, (&pos_i, acceleration)) in targets.iter().zip(out.iter_mut()).enumerate() {
+                    let i = first + k;
+                    for (offset, (&pos_j, &mass_j)) in sources.iter().zip(source_masses).enumerate()
+                    {
+                        let diff = pos_j - pos_i;
+                        let distance_sq = diff.length_squared();
+                        if j_first + offset == i || distance_sq < epsilon {
+                            continue;
+                        }
+                        *acceleration +=
+                            diff * (time_g * mass_j / (distance_sq * distance_sq.sqrt()));
+                    }
+                }
+            }
+        });
+    deltas
+}
+
+/// Parses a Linux CPU list such as `0-15,32-47` into CPU numbers. Malformed entries are
+/// skipped.
+pub fn parse_cpu_list(list: &str) -> Vec<usize> {
+    let mut cpus = Vec::new();
+    for entry in list.trim().split(',') {
+        match entry.split_once('-') {
+            Some((first, last)) => {
+                if let (Ok(first), Ok(last)) = (first.parse::<usize>(), last.parse::<usize>()) {
+                    cpus.extend(first..=last);
+                }
+            }
+            None => cpus.extend(entry.parse::<usize>().ok()),
+        }
+    }
+    cpus
+}
+
+/// CPU list of each NUMA node that has CPUs, as the kernel reports them.
+#[cfg(target_os = "linux")]
+fn numa_nodes() -> Vec<Vec<usize>> {
+    (0..)
+        .map(|node| format!("/sys/devices/system/node/node{}/cpulist", node))
+        .map_while(|path| std::fs::read_to_string(path).ok())
+        .map(|list| parse_cpu_list(&list))
+        .filter(|cpus| !cpus.is_empty())
+        .collect()
+}
+
+/// Number of NUMA nodes with CPUs; 1 where the platform does not report them.
+pub fn numa_node_count() -> usize {
+    #[cfg(target_os = "linux")]
+    {
+        numa_nodes().len().max(1)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        1
+    }
+}
+
+/// CPUs this process may run on, grouped by NUMA node: all of node 0's, then node 1's,
+/// and so on, so that consecutive worker threads share a node's memory controller and
+/// last-level cache. Empty where the platform does not report affinity.
+pub fn numa_cpu_order() -> Vec<usize> {
+    #[cfg(target_os = "linux")]
+    {
+        let allowed = affinity::allowed_cpus();
+        let mut order: Vec<usize> = numa_nodes()
+            .into_iter()
+            .flatten()
+            .filter(|cpu| allowed.contains(cpu))
+            .collect();
+        // Kernels without NUMA support have no node directories.
+        for cpu in allowed {
+            if !order.contains(&cpu) {
+                order.push(cpu);
+            }
+        }
+        order
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        Vec::new()
+    }
+}
+
+/// Binds the calling thread to `cpu`. Returns whether it took effect.
+pub fn pin_current_thread(cpu: usize) -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        affinity::pin_current_thread(cpu)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = cpu;
+        false
+    }
+}
+
+/// Rayon pool of `threads` workers for the force loops. With `pin`, worker `k` is bound to
+/// the `k`-th CPU of [`numa_cpu_order`] (wrapping), so threads stop migrating between
+/// sockets and keep both their cached tiles and their node-local memory. Pinning is
+/// skipped where the platform cannot do it.
+pub fn force_thread_pool(threads: usize, pin: bool) -> rayon::ThreadPool {
+    let order = if pin { numa_cpu_order() } else { Vec::new() };
+    let mut builder = rayon::ThreadPoolBuilder::new().num_threads(threads);
+    if !order.is_empty() {
+        builder = builder.start_handler(move |index| {
+            pin_current_thread(order[index % order.len()]);
+        });
+    }
+    builder.build().unwrap()
+}
+
+#[cfg(target_os = "linux")]
+mod affinity {
+    use std::mem::{size_of, zeroed};
+
+    pub(super) fn allowed_cpus() -> Vec<usize> {
+        unsafe {
+            let mut set: libc::cpu_set_t = zeroed();
+            if libc::sched_getaffinity(0, size_of::<libc::cpu_set_t>(), &mut set) != 0 {
+                return Vec::new();
+            }
+            (0..libc::CPU_SETSIZE as usize)
+                .filter(|&cpu| libc::CPU_ISSET(cpu, &set))
+                .collect()
+        }
+    }
+
+    pub(super) fn pin_current_thread(cpu: usize) -> bool {
+        if cpu >= libc::CPU_SETSIZE as usize {
+            return false;
+        }
+        unsafe {
+            let mut set: libc::cpu_set_t = zeroed();
+            libc::CPU_SET(cpu, &mut set);
+            libc::sched_setaffinity(0, size_of::<libc::cpu_set_t>(), &set) == 0
+        }
+    }
+}
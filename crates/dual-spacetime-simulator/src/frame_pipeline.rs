@@ -0,0 +1,279 @@
+use crate::simulation::{Particle, SimulationManager};
+use std::sync::{Arc, Mutex};
+
+/// Double-buffered hand-off of finished CPU frames from the simulation worker to the
+/// render loop. The worker publishes frame k−1 and moves on to integrate frame k while
+/// the render loop uploads the published copy, so neither side waits on the other's
+/// simulation lock. Only the newest unconsumed frame is kept.
+#[derive(Clone, Default)]
+pub struct FrameMailbox {
+    published: Arc<Mutex<Option<Vec<Particle>>>>,
+    spare: Arc<Mutex<Vec<Particle>>>,
+}
+
+impl FrameMailbox {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copies the current state of `manager` into the spare buffer and publishes it,
+    /// replacing any frame the render loop has not taken yet.
+    pub fn publish_from(&self, manager: &SimulationManager) {
+        let mut buffer = std::mem::take(&mut *self.spare.lock().unwrap());
+        manager.copy_particles_into(&mut buffer);
+        let replaced = self.published.lock().unwrap().replace(buffer);
+        if let Some(replaced) = replaced {
+            self.recycle(replaced);
+        }
+    }
+
+    /// Takes the newest published frame, if any.
+    pub fn take(&self) -> Option<Vec<Particle>> {
+        self.published.lock().unwrap().take()
+    }
+
+    /// Returns a consumed frame so its allocation is reused by the next publish.
+    pub fn recycle(&self, buffer: Vec<Particle>) {
+        let mut spare = self.spare.lock().unwrap();
+        if buffer.capacity() > spare.capacity() {
+            *spare = buffer;
+        }
+    }
+
+    /// Drops any published frame, e.g. after a reset made it stale.
+    pub fn clear(&self) {
+        if let Some(stale) = self.take() {
+            self.recycle(stale);
+        }
+    }
+
+    pub fn has_frame(&self) -> bool {
+        self.published.lock().unwrap().is_some()
+    }
+}
+
+/// Staging buffers in a [`StagingRing`]: the worker fills one while the GPU copies from
+/// the other.
+pub const STAGING_SLOTS: usize = 2;
+
+/// Who owns a staging slot's memory.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StagingState {
+    /// Free for the worker to fill or the render thread to resize.
+    Free,
+    /// Being filled by the worker or resized by the render thread; nobody else touches it.
+    Busy,
+    /// Holds the newest finished frame, waiting for the render thread.
+    Written,
+    /// Taken by the render thread; its copy has not been submitted yet.
+    Taken,
+    /// Copied by the command buffer of frame-in-flight `k`; free once that frame's fence
+    /// has signaled.
+    InFlight(usize),
+}
+
+struct StagingSlot<T> {
+    memory: *mut T,
+    capacity: usize,
+    state: StagingState,
+    len: usize,
+    ids: Vec<u64>,
+}
+
+struct StagingSlots<T> {
+    slots: [StagingSlot<T>; STAGING_SLOTS],
+    /// Largest frame the worker could not stage for lack of room.
+    wanted: usize,
+}
+
+// The raw pointers are only dereferenced by whoever holds the slot as `Busy`.
+unsafe impl<T: Send> Send for StagingSlots<T> {}
+
+impl<T> StagingSlots<T> {
+    fn find(&self, matches: impl Fn(&StagingSlot<T>) -> bool) -> Option<usize> {
+        self.slots.iter().position(matches)
+    }
+}
+
+/// Double-buffered hand-off of finished CPU frames through persistently mapped staging
+/// memory. The worker writes a frame straight into a free slot in the GPU layout; the
+/// render thread records a copy from it into the particle buffer and frees the slot when
+/// that command buffer's fence has signaled. Unlike [`FrameMailbox`], no intermediate
+/// particle list is built on either side. Only the newest unconsumed frame is kept.
+///
+/// Slots start without memory. A frame that does not fit records its size, and the
+/// render thread attaches larger buffers for the next one.
+pub struct StagingRing<T> {
+    slots: Arc<Mutex<StagingSlots<T>>>,
+}
+
+impl<T> Clone for StagingRing<T> {
+    fn clone(&self) -> Self {
+        Self {
+            slots: Arc::clone(&self.slots),
+        }
+    }
+}
+
+impl<T> Default for StagingRing<T> {
+    fn default() -> Self {
+        let slot = || StagingSlot {
+            memory: std::ptr::null_mut(),
+            capacity: 0,
+            state: StagingState::Free,
+            len: 0,
+            ids: Vec::new(),
+        };
+        Self {
+            slots: Arc::new(Mutex::new(StagingSlots {
+                slots: [slot(), slot()],
+                wanted: 0,
+            })),
+        }
+    }
+}
+
+impl<T> StagingRing<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fills a slot with a frame of `len` particles: `fill` gets the slot's memory and its
+    /// particle ID list to overwrite. A finished frame the render thread has not taken
+    /// yet is overwritten or dropped. Returns false, without calling `fill`, when no slot
+    /// is free or large enough.
+    pub fn write(&self, len: usize, fill: impl FnOnce(&mut [T], &mut Vec<u64>)) -> bool {
+        if len == 0 {
+            return false;
+        }
+        let (index, memory, mut ids) = {
+            let mut guard = self.slots.lock().unwrap();
+            let fits = |slot: &StagingSlot<T>, state| slot.state == state && slot.capacity >= len;
+            let Some(index) = guard
+                .find(|slot| fits(slot, StagingState::Written))
+                .or_else(|| guard.find(|slot| fits(slot, StagingState::Free)))
+            else {
+                guard.wanted = guard.wanted.max(len);
+                return false;
+            };
+            let slot = &mut guard.slots[index];
+            slot.state = StagingState::Busy;
+            (index, slot.memory, std::mem::take(&mut slot.ids))
+        };
+        // SAFETY: the slot is `Busy`, so this thread alone uses its `capacity >= len`
+        // elements until it is marked written below.
+        let memory = unsafe { std::slice::from_raw_parts_mut(memory, len) };
+        fill(memory, &mut ids);
+        let mut guard = self.slots.lock().unwrap();
+        for slot in &mut guard.slots {
+            if slot.state == StagingState::Written {
+                slot.state = StagingState::Free;
+            }
+        }
+        let slot = &mut guard.slots[index];
+        slot.state = StagingState::Written;
+        slot.len = len;
+        slot.ids = ids;
+        true
+    }
+
+    /// Takes the newest written frame for copying, as its slot and particle count, and
+    /// copies its particle IDs into `ids`.
+    pub fn take(&self, ids: &mut Vec<u64>) -> Option<(usize, usize)> {
+        let mut guard = self.slots.lock().unwrap();
+        let index = guard.find(|slot| slot.state == StagingState::Written)?;
+        let slot = &mut guard.slots[index];
+        slot.state = StagingState::Taken;
+        ids.clone_from(&slot.ids);
+        Some((index, slot.len))
+    }
+
+    /// Marks a taken slot as copied by the command buffer of frame-in-flight `frame`.
+    pub fn submitted(&self, index: usize, frame: usize) {
+        let mut guard = self.slots.lock().unwrap();
+        if guard.slots[index].state == StagingState::Taken {
+            guard.slots[index].state = StagingState::InFlight(frame);
+        }
+    }
+
+    /// Frees the slots copied by frame-in-flight `frame`, once its fence has signaled.
+    pub fn retire(&self, frame: usize) {
+        let mut guard = self.slots.lock().unwrap();
+        for slot in &mut guard.slots {
+            if slot.state == StagingState::InFlight(frame) {
+                slot.state = StagingState::Free;
+            }
+        }
+    }
+
+    /// Frees a taken slot whose copy will not be recorded, e.g. because a newer frame or
+    /// a direct upload replaced it.
+    pub fn release(&self, index: usize) {
+        let mut guard = self.slots.lock().unwrap();
+        if guard.slots[index].state == StagingState::Taken {
+            guard.slots[index].state = StagingState::Free;
+        }
+    }
+
+    /// Drops a written frame, e.g. after a reset made it stale.
+    pub fn clear(&self) {
+        let mut guard = self.slots.lock().unwrap();
+        for slot in &mut guard.slots {
+            if slot.state == StagingState::Written {
+                slot.state = StagingState::Free;
+            }
+        }
+    }
+
+    /// Claims a free slot smaller than the largest frame that did not fit, as its index
+    /// and the capacity it needs. The slot stays busy until [`StagingRing::attach`].
+    pub fn claim_undersized(&self) -> Option<(usize, usize)> {
+        let mut guard = self.slots.lock().unwrap();
+        let wanted = guard.wanted;
+        let index =
+            guard.find(|slot| slot.state == StagingState::Free && slot.capacity < wanted)?;
+        guard.slots[index].state = StagingState::Busy;
+        Some((index, wanted))
+    }
+
+    /// Gives a claimed slot new memory of `capacity` particles and frees it.
+    ///
+    /// # Safety
+    /// `memory` must be valid for reads and writes of `capacity` elements until the next
+    /// `attach` of this slot or [`StagingRing::detach`], and the slot's previous memory
+    /// may be released only after this call.
+    pub unsafe fn attach(&self, index: usize, memory: *mut T, capacity: usize) {
+        let mut guard = self.slots.lock().unwrap();
+        let slot = &mut guard.slots[index];
+        debug_assert_eq!(slot.state, StagingState::Busy);
+        slot.memory = memory;
+        slot.capacity = if memory.is_null() { 0 } else { capacity };
+        slot.state = StagingState::Free;
+    }
+
+    /// Removes every slot's memory, waiting for a worker write in progress to finish, so
+    /// the buffers behind it can be released.
+    pub fn detach(&self) {
+        loop {
+            {
+                let mut guard = self.slots.lock().unwrap();
+                let busy = |slot: &StagingSlot<T>| slot.state == StagingState::Busy;
+                if guard.find(busy).is_none() {
+                    for slot in &mut guard.slots {
+                        slot.memory = std::ptr::null_mut();
+                        slot.capacity = 0;
+                        slot.state = StagingState::Free;
+                    }
+                    guard.wanted = 0;
+                    return;
+                }
+            }
+            std::thread::yield_now();
+        }
+    }
+
+    /// State of slot `index`.
+    pub fn state(&self, index: usize) -> StagingState {
+        self.slots.lock().unwrap().slots[index].state
+    }
+}
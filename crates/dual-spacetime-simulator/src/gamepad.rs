@@ -0,0 +1,146 @@
+use glam::{Vec2, Vec3};
+use vulkanvil::OrbitCamera;
+
+/// Stick deflection ignored around the rest position, so a worn stick does not drift.
+pub const STICK_DEAD_ZONE: f32 = 0.15;
+/// Orbit angle per second at full left-stick deflection (radians).
+pub const GAMEPAD_ORBIT_SPEED: f32 = 1.5;
+/// Pan per second at full right-stick deflection, as a fraction of the orbit distance.
+pub const GAMEPAD_PAN_SPEED: f32 = 0.8;
+/// Zoom per second at a fully pulled trigger, as a fraction of the orbit distance.
+pub const GAMEPAD_ZOOM_SPEED: f32 = 1.0;
+/// Longest step one poll may apply, so a stalled frame does not fling the camera.
+pub const MAX_GAMEPAD_STEP_SECONDS: f32 = 0.1;
+
+/// Stick and trigger positions summed over the connected gamepads, after the dead zone.
+/// Stick `y` is positive when pushed away from the player.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GamepadAxes {
+    pub left: Vec2,
+    pub right: Vec2,
+    /// Right trigger minus left trigger; positive zooms in.
+    pub zoom: f32,
+}
+
+impl GamepadAxes {
+    pub fn is_zero(&self) -> bool {
+        self.left == Vec2::ZERO && self.right == Vec2::ZERO && self.zoom == 0.0
+    }
+}
+
+/// Button presses the simulator reacts to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GamepadAction {
+    /// Start or A: run or pause, like the Pause key.
+    ToggleRun,
+    /// Right bumper: reset into the next placement preset.
+    NextPreset,
+    /// Left bumper: reset into the previous placement preset.
+    PreviousPreset,
+    /// Y: View ▸ Fit.
+    FitView,
+}
+
+/// Zeroes a stick inside [`STICK_DEAD_ZONE`] and rescales the rest so full deflection
+/// still reads one.
+pub fn apply_dead_zone(stick: Vec2) -> Vec2 {
+    let length = stick.length();
+    if length <= STICK_DEAD_ZONE {
+        return Vec2::ZERO;
+    }
+    stick / length * ((length.min(1.0) - STICK_DEAD_ZONE) / (1.0 - STICK_DEAD_ZONE))
+}
+
+/// Moves the camera for `dt` seconds of input: the left stick orbits the target like a
+/// left-button drag, the right stick pans on the XZ plane like WASD, and the triggers zoom
+/// toward the target.
+pub fn apply_gamepad_camera(camera: &mut OrbitCamera, axes: GamepadAxes, dt: f32) {
+    if axes.left != Vec2::ZERO {
+        let angle = GAMEPAD_ORBIT_SPEED * dt;
+        camera.revolve(axes.left.x * angle, -axes.left.y * angle);
+    }
+    let distance = camera.orbit_distance();
+    if axes.right != Vec2::ZERO {
+        let relative = camera.view_relative();
+        let forward_xz = Vec3::new(relative.x, 0.0, relative.z)
+            .try_normalize()
+            .unwrap_or(Vec3::NEG_Z);
+        let right_xz = forward_xz.cross(Vec3::Y);
+        let pan = distance * GAMEPAD_PAN_SPEED * dt;
+        camera.pan_xz((forward_xz * axes.right.y + right_xz * axes.right.x) * pan);
+    }
+    if axes.zoom != 0.0 {
+        camera.zoom(axes.zoom * distance * GAMEPAD_ZOOM_SPEED * dt);
+    }
+}
+
+/// Connected gamepads, read through `gilrs` once per event-loop iteration.
+#[cfg(feature = "gamepad")]
+pub struct Gamepads {
+    gilrs: gilrs::Gilrs,
+    last_poll: Option<std::time::Instant>,
+    axes: GamepadAxes,
+}
+
+#[cfg(feature = "gamepad")]
+impl Gamepads {
+    /// Opens the platform gamepad backend. Fails where none is available, e.g. without
+    /// udev access on Linux.
+    pub fn new() -> Result<Self, String> {
+        let gilrs = gilrs::Gilrs::new().map_err(|e| format!("Gamepads unavailable: {}", e))?;
+        Ok(Self {
+            gilrs,
+            last_poll: None,
+            axes: GamepadAxes::default(),
+        })
+    }
+
+    /// Drains button presses into `actions` and returns the current axes with the seconds
+    /// since the previous poll, capped at [`MAX_GAMEPAD_STEP_SECONDS`].
+    pub fn poll(&mut self, actions: &mut Vec<GamepadAction>) -> (GamepadAxes, f32) {
+        use gilrs::{Axis, Button, EventType};
+
+        while let Some(gilrs::Event { event, .. }) = self.gilrs.next_event() {
+            if let EventType::ButtonPressed(button, _) = event
+                && let Some(action) = action_for_button(button)
+            {
+                actions.push(action);
+            }
+        }
+        let mut axes = GamepadAxes::default();
+        for (_, gamepad) in self.gilrs.gamepads() {
+            let stick = |x, y| apply_dead_zone(Vec2::new(gamepad.value(x), gamepad.value(y)));
+            let trigger = |button| gamepad.button_data(button).map_or(0.0, |data| data.value());
+            axes.left += stick(Axis::LeftStickX, Axis::LeftStickY);
+            axes.right += stick(Axis::RightStickX, Axis::RightStickY);
+            axes.zoom += trigger(Button::RightTrigger2) - trigger(Button::LeftTrigger2);
+        }
+        let now = std::time::Instant::now();
+        let dt = self.last_poll.map_or(0.0, |last| {
+            now.duration_since(last)
+                .as_secs_f32()
+                .min(MAX_GAMEPAD_STEP_SECONDS)
+        });
+        self.last_poll = Some(now);
+        self.axes = axes;
+        (axes, dt)
+    }
+
+    /// Returns whether a stick or trigger was held at the last poll.
+    pub fn is_moving(&self) -> bool {
+        !self.axes.is_zero()
+    }
+}
+
+#[cfg(feature = "gamepad")]
+fn action_for_button(button: gilrs::Button) -> Option<GamepadAction> {
+    use gilrs::Button;
+
+    match button {
+        Button::Start | Button::South => Some(GamepadAction::ToggleRun),
+        Button::RightTrigger => Some(GamepadAction::NextPreset),
+        Button::LeftTrigger => Some(GamepadAction::PreviousPreset),
+        Button::North => Some(GamepadAction::FitView),
+        _ => None,
+    }
+}
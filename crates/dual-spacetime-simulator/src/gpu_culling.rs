@@ -0,0 +1,350 @@
+use ash::vk;
+use gpu_allocator::vulkan::Allocator;
+use std::sync::{Arc, Mutex};
+use vulkanvil::{AllocatedBuffer, create_shader_module};
+
+const WORKGROUP_SIZE: u32 = 64;
+/// `VkDrawIndirectCommand` with a zero vertex count and one instance.
+const EMPTY_DRAW_COMMAND: [u32; 4] = [0, 1, 0, 0];
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct CullPushConstants {
+    view_proj: [[f32; 4]; 4],
+    particle_count: u32,
+    margin_x: f32,
+    margin_y: f32,
+    _pad: u32,
+}
+
+/// GPU-driven frustum culling: a compute pass compacts the indices of on-screen
+/// particles into a storage buffer and writes their count into an indirect draw
+/// command, so the vertex stage only runs for visible particles.
+pub struct GpuParticleCulling {
+    device: ash::Device,
+    allocator: Arc<Mutex<Allocator>>,
+    descriptor_set_layout: vk::DescriptorSetLayout,
+    descriptor_pool: vk::DescriptorPool,
+    descriptor_set: vk::DescriptorSet,
+    cull_pipeline: vk::Pipeline,
+    cull_layout: vk::PipelineLayout,
+    visible_buffer: AllocatedBuffer,
+    visible_capacity: usize,
+    indirect_buffer: AllocatedBuffer,
+    /// Particle SSBO currently written into binding 0; it is recreated when it grows.
+    bound_particle_buffer: vk::Buffer,
+}
+
+impl GpuParticleCulling {
+    /// Creates the cull pipeline, the visible-index buffer, and the indirect draw buffer.
+    pub fn new(device: ash::Device, allocator: Arc<Mutex<Allocator>>) -> Self {
+        let descriptor_set_layout = create_cull_descriptor_set_layout(&device);
+        let cull_layout = create_cull_pipeline_layout(&device, descriptor_set_layout);
+        let cull_pipeline = create_cull_pipeline(&device, cull_layout);
+        let descriptor_pool = create_cull_descriptor_pool(&device);
+        let layouts = [descriptor_set_layout];
+        let alloc_ci = vk::DescriptorSetAllocateInfo::default()
+            .descriptor_pool(descriptor_pool)
+            .set_layouts(&layouts);
+        let descriptor_set = unsafe { device.allocate_descriptor_sets(&alloc_ci).unwrap()[0] };
+        let visible_capacity = 1;
+        let visible_buffer = create_visible_buffer(&device, &allocator, visible_capacity);
+        let indirect_buffer = AllocatedBuffer::new(
+            &device,
+            &allocator,
+            std::mem::size_of_val(&EMPTY_DRAW_COMMAND) as u64,
+            vk::BufferUsageFlags::STORAGE_BUFFER
+                | vk::BufferUsageFlags::INDIRECT_BUFFER
+                | vk::BufferUsageFlags::TRANSFER_DST,
+            gpu_allocator::MemoryLocation::GpuOnly,
+            "gpu_cull_indirect",
+        );
+        let culling = Self {
+            device,
+            allocator,
+            descriptor_set_layout,
+            descriptor_pool,
+            descriptor_set,
+            cull_pipeline,
+            cull_layout,
+            visible_buffer,
+            visible_capacity,
+            indirect_buffer,
+            bound_particle_buffer: vk::Buffer::null(),
+        };
+        culling.write_descriptor(1, culling.visible_buffer.buffer, vk::WHOLE_SIZE);
+        culling.write_descriptor(2, culling.indirect_buffer.buffer, vk::WHOLE_SIZE);
+        culling
+    }
+
+    /// Layout of the culling set; the particle pipelines bind it as set 1 so the
+    /// vertex shader can read the visible indices.
+    pub fn descriptor_set_layout(&self) -> vk::DescriptorSetLayout {
+        self.descriptor_set_layout
+    }
+
+    pub fn descriptor_set(&self) -> vk::DescriptorSet {
+        self.descriptor_set
+    }
+
+    pub fn indirect_buffer(&self) -> vk::Buffer {
+        self.indirect_buffer.buffer
+    }
+
+    /// Records the cull pass for `particle_count` particles of `particle_buffer`.
+    ///
+    /// Must be recorded outside a render pass. `margin` is the half point size in
+    /// clip units (x, y), so sprites overlapping the frustum edge are kept.
+    pub fn record(
+        &mut self,
+        command_buffer: vk::CommandBuffer,
+        particle_buffer: vk::Buffer,
+        particle_count: u32,
+        view_proj: [[f32; 4]; 4],
+        margin: [f32; 2],
+    ) {
+        if particle_buffer != self.bound_particle_buffer {
+            self.write_descriptor(0, particle_buffer, vk::WHOLE_SIZE);
+            self.bound_particle_buffer = particle_buffer;
+        }
+        self.ensure_visible_capacity(particle_count as usize);
+        let push = CullPushConstants {
+            view_proj,
+            particle_count,
+            margin_x: margin[0],
+            margin_y: margin[1],
+            _pad: 0,
+        };
+        let workgroups = particle_count.div_ceil(WORKGROUP_SIZE);
+        unsafe {
+            // Host uploads, the simulation pass, and last frame's draw must all be
+            // done with the buffers before the counter is reset and rewritten.
+            memory_barrier(
+                &self.device,
+                command_buffer,
+                vk::PipelineStageFlags::HOST
+                    | vk::PipelineStageFlags::COMPUTE_SHADER
+                    | vk::PipelineStageFlags::VERTEX_SHADER
+                    | vk::PipelineStageFlags::DRAW_INDIRECT,
+                vk::PipelineStageFlags::TRANSFER | vk::PipelineStageFlags::COMPUTE_SHADER,
+                vk::AccessFlags::HOST_WRITE | vk::AccessFlags::SHADER_WRITE,
+                vk::AccessFlags::TRANSFER_WRITE
+                    | vk::AccessFlags::SHADER_READ
+                    | vk::AccessFlags::SHADER_WRITE,
+            );
+            self.device.cmd_update_buffer(
+                command_buffer,
+                self.indirect_buffer.buffer,
+                0,
+                bytemuck::bytes_of(&EMPTY_DRAW_COMMAND),
+            );
+            memory_barrier(
+                &self.device,
+                command_buffer,
+                vk::PipelineStageFlags::TRANSFER,
+                vk::PipelineStageFlags::COMPUTE_SHADER,
+                vk::AccessFlags::TRANSFER_WRITE,
+                vk::AccessFlags::SHADER_READ | vk::AccessFlags::SHADER_WRITE,
+            );
+            self.device.cmd_bind_pipeline(
+                command_buffer,
+                vk::PipelineBindPoint::COMPUTE,
+                self.cull_pipeline,
+            );
+            self.device.cmd_bind_descriptor_sets(
+                command_buffer,
+                vk::PipelineBindPoint::COMPUTE,
+                self.cull_layout,
+                0,
+                &[self.descriptor_set],
+                &[],
+            );
+            self.device.cmd_push_constants(
+                command_buffer,
+                self.cull_layout,
+                vk::ShaderStageFlags::COMPUTE,
+                0,
+                bytemuck::bytes_of(&push),
+            );
+            self.device.cmd_dispatch(command_buffer, workgroups, 1, 1);
+            memory_barrier(
+                &self.device,
+                command_buffer,
+                vk::PipelineStageFlags::COMPUTE_SHADER,
+                vk::PipelineStageFlags::DRAW_INDIRECT | vk::PipelineStageFlags::VERTEX_SHADER,
+                vk::AccessFlags::SHADER_WRITE,
+                vk::AccessFlags::INDIRECT_COMMAND_READ | vk::AccessFlags::SHADER_READ,
+            );
+        }
+    }
+
+    fn ensure_visible_capacity(&mut self, count: usize) {
+        if count <= self.visible_capacity {
+            return;
+        }
+        let alloc = Arc::clone(&self.allocator);
+        let old = std::mem::replace(
+            &mut self.visible_buffer,
+            create_visible_buffer(&self.device, &alloc, count),
+        );
+        old.destroy(&self.device, &alloc);
+        self.visible_capacity = count;
+        self.write_descriptor(1, self.visible_buffer.buffer, vk::WHOLE_SIZE);
+    }
+
+    fn write_descriptor(&self, binding: u32, buffer: vk::Buffer, range: u64) {
+        let infos = [vk::DescriptorBufferInfo {
+            buffer,
+            offset: 0,
+            range,
+        }];
+        let writes = [vk::WriteDescriptorSet::default()
+            .dst_set(self.descriptor_set)
+            .dst_binding(binding)
+            .descriptor_type(vk::DescriptorType::STORAGE_BUFFER)
+            .buffer_info(&infos)];
+        unsafe {
+            self.device.update_descriptor_sets(&writes, &[]);
+        }
+    }
+}
+
+impl Drop for GpuParticleCulling {
+    fn drop(&mut self) {
+        unsafe {
+            self.device.destroy_pipeline(self.cull_pipeline, None);
+            self.device.destroy_pipeline_layout(self.cull_layout, None);
+            self.device
+                .destroy_descriptor_pool(self.descriptor_pool, None);
+            self.device
+                .destroy_descriptor_set_layout(self.descriptor_set_layout, None);
+        }
+        let alloc = Arc::clone(&self.allocator);
+        for buffer in [&mut self.visible_buffer, &mut self.indirect_buffer] {
+            let taken = std::mem::replace(
+                buffer,
+                AllocatedBuffer {
+                    buffer: vk::Buffer::null(),
+                    allocation: None,
+                },
+            );
+            if taken.buffer != vk::Buffer::null() {
+                taken.destroy(&self.device, &alloc);
+            }
+        }
+    }
+}
+
+/// Clip-space margin (x, y) covering half a point sprite of `size_scale / w` pixels.
+pub fn cull_margin(size_scale: f32, extent: vk::Extent2D) -> [f32; 2] {
+    [
+        size_scale / extent.width.max(1) as f32,
+        size_scale / extent.height.max(1) as f32,
+    ]
+}
+
+fn create_visible_buffer(
+    device: &ash::Device,
+    allocator: &Arc<Mutex<Allocator>>,
+    capacity: usize,
+) -> AllocatedBuffer {
+    AllocatedBuffer::new(
+        device,
+        allocator,
+        (std::mem::size_of::<u32>() * capacity.max(1)) as u64,
+        vk::BufferUsageFlags::STORAGE_BUFFER,
+        gpu_allocator::MemoryLocation::GpuOnly,
+        "gpu_cull_visible_indices",
+    )
+}
+
+pub(crate) unsafe fn memory_barrier(
+    device: &ash::Device,
+    command_buffer: vk::CommandBuffer,
+    src_stage: vk::PipelineStageFlags,
+    dst_stage: vk::PipelineStageFlags,
+    src_access: vk::AccessFlags,
+    dst_access: vk::AccessFlags,
+) {
+    unsafe {
+        let barrier = vk::MemoryBarrier::default()
+            .src_access_mask(src_access)
+            .dst_access_mask(dst_access);
+        device.cmd_pipeline_barrier(
+            command_buffer,
+            src_stage,
+            dst_stage,
+            vk::DependencyFlags::empty(),
+            &[barrier],
+            &[],
+            &[],
+        );
+    }
+}
+
+/// Binding 0: particle SSBO, 1: visible indices (also read by the vertex shader),
+/// 2: indirect draw command.
+fn create_cull_descriptor_set_layout(device: &ash::Device) -> vk::DescriptorSetLayout {
+    let binding = |index: u32, stages: vk::ShaderStageFlags| {
+        vk::DescriptorSetLayoutBinding::default()
+            .binding(index)
+            .descriptor_type(vk::DescriptorType::STORAGE_BUFFER)
+            .descriptor_count(1)
+            .stage_flags(stages)
+    };
+    let bindings = [
+        binding(0, vk::ShaderStageFlags::COMPUTE),
+        binding(
+            1,
+            vk::ShaderStageFlags::COMPUTE | vk::ShaderStageFlags::VERTEX,
+        ),
+        binding(2, vk::ShaderStageFlags::COMPUTE),
+    ];
+    let ci = vk::DescriptorSetLayoutCreateInfo::default().bindings(&bindings);
+    unsafe { device.create_descriptor_set_layout(&ci, None) }.unwrap()
+}
+
+fn create_cull_pipeline_layout(
+    device: &ash::Device,
+    descriptor_set_layout: vk::DescriptorSetLayout,
+) -> vk::PipelineLayout {
+    let ranges = [vk::PushConstantRange {
+        stage_flags: vk::ShaderStageFlags::COMPUTE,
+        offset: 0,
+        size: std::mem::size_of::<CullPushConstants>() as u32,
+    }];
+    let set_layouts = [descriptor_set_layout];
+    let ci = vk::PipelineLayoutCreateInfo::default()
+        .set_layouts(&set_layouts)
+        .push_constant_ranges(&ranges);
+    unsafe { device.create_pipeline_layout(&ci, None) }.unwrap()
+}
+
+fn create_cull_pipeline(device: &ash::Device, layout: vk::PipelineLayout) -> vk::Pipeline {
+    let spv = include_bytes!(concat!(env!("OUT_DIR"), "/shaders/particles_cull.comp.spv"));
+    let module = create_shader_module(device, spv);
+    let stage = vk::PipelineShaderStageCreateInfo::default()
+        .stage(vk::ShaderStageFlags::COMPUTE)
+        .module(module)
+        .name(c"main");
+    let ci = vk::ComputePipelineCreateInfo::default()
+        .stage(stage)
+        .layout(layout);
+    let pipelines =
+        unsafe { device.create_compute_pipelines(vk::PipelineCache::null(), &[ci], None) }.unwrap();
+    unsafe {
+        device.destroy_shader_module(module, None);
+    }
+    pipelines[0]
+}
+
+fn create_cull_descriptor_pool(device: &ash::Device) -> vk::DescriptorPool {
+    let pool_sizes = [vk::DescriptorPoolSize {
+        ty: vk::DescriptorType::STORAGE_BUFFER,
+        descriptor_count: 3,
+    }];
+    let ci = vk::DescriptorPoolCreateInfo::default()
+        .pool_sizes(&pool_sizes)
+        .max_sets(1);
+    unsafe { device.create_descriptor_pool(&ci, None) }.unwrap()
+}
@@ -0,0 +1,460 @@
+use crate::gpu_culling::memory_barrier;
+use ash::vk;
+use gpu_allocator::vulkan::Allocator;
+use std::sync::{Arc, Mutex};
+use vulkanvil::{AllocatedBuffer, create_shader_module};
+
+const WORKGROUP_SIZE: u32 = 256;
+/// Keys ranked by one sort workgroup, four per invocation.
+pub const SORT_BLOCK_SIZE: u32 = WORKGROUP_SIZE * 4;
+/// Digits per pass: 8 bits, so four passes cover a 32-bit key.
+const RADIX: u32 = 256;
+const RADIX_BITS: u32 = 8;
+const PASSES: u32 = u32::BITS / RADIX_BITS;
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct SortPushConstants {
+    view_proj: [[f32; 4]; 4],
+    particle_count: u32,
+    /// Lowest key bit of the current pass's digit.
+    shift: u32,
+    block_count: u32,
+    _pad: u32,
+}
+
+/// GPU radix sort of particle indices by view-space depth, farthest first, so
+/// alpha-blended sprites composite back to front. A key pass writes one depth key and
+/// index per particle; each of the four 8-bit passes then counts digits per block,
+/// scans the counts into output offsets, and scatters the block stably into the other
+/// key/index buffer pair. The sorted indices reach the vertex shader through a set in
+/// the culling layout, in place of the visible list.
+pub struct GpuDepthSort {
+    device: ash::Device,
+    allocator: Arc<Mutex<Allocator>>,
+    descriptor_set_layout: vk::DescriptorSetLayout,
+    descriptor_pool: vk::DescriptorPool,
+    /// Set 0 reads buffer pair 0 and writes pair 1; set 1 the reverse.
+    sort_sets: [vk::DescriptorSet; 2],
+    /// Culling-layout set whose binding 1 holds the sorted indices.
+    draw_set: vk::DescriptorSet,
+    sort_layout: vk::PipelineLayout,
+    keys_pipeline: vk::Pipeline,
+    histogram_pipeline: vk::Pipeline,
+    scan_pipeline: vk::Pipeline,
+    scatter_pipeline: vk::Pipeline,
+    keys: [AllocatedBuffer; 2],
+    values: [AllocatedBuffer; 2],
+    histogram: AllocatedBuffer,
+    capacity: usize,
+    /// Particle SSBO currently written into binding 0; it is recreated when it grows.
+    bound_particle_buffer: vk::Buffer,
+}
+
+impl GpuDepthSort {
+    /// Creates the sort pipelines and buffers. `draw_set_layout` is the culling layout
+    /// the particle pipelines bind as set 1.
+    pub fn new(
+        device: ash::Device,
+        allocator: Arc<Mutex<Allocator>>,
+        draw_set_layout: vk::DescriptorSetLayout,
+    ) -> Self {
+        let descriptor_set_layout = create_sort_descriptor_set_layout(&device);
+        let sort_layout = create_sort_pipeline_layout(&device, descriptor_set_layout);
+        let descriptor_pool = create_sort_descriptor_pool(&device);
+        let layouts = [
+            descriptor_set_layout,
+            descriptor_set_layout,
+            draw_set_layout,
+        ];
+        let alloc_ci = vk::DescriptorSetAllocateInfo::default()
+            .descriptor_pool(descriptor_pool)
+            .set_layouts(&layouts);
+        let sets = unsafe { device.allocate_descriptor_sets(&alloc_ci).unwrap() };
+        let capacity = 1;
+        let sort = Self {
+            keys_pipeline: create_sort_pipeline(
+                &device,
+                sort_layout,
+                include_bytes!(concat!(
+                    env!("OUT_DIR"),
+                    "/shaders/particles_depth_keys.comp.spv"
+                )),
+            ),
+            histogram_pipeline: create_sort_pipeline(
+                &device,
+                sort_layout,
+                include_bytes!(concat!(
+                    env!("OUT_DIR"),
+                    "/shaders/particles_sort_histogram.comp.spv"
+                )),
+            ),
+            scan_pipeline: create_sort_pipeline(
+                &device,
+                sort_layout,
+                include_bytes!(concat!(
+                    env!("OUT_DIR"),
+                    "/shaders/particles_sort_scan.comp.spv"
+                )),
+            ),
+            scatter_pipeline: create_sort_pipeline(
+                &device,
+                sort_layout,
+                include_bytes!(concat!(
+                    env!("OUT_DIR"),
+                    "/shaders/particles_sort_scatter.comp.spv"
+                )),
+            ),
+            keys: [0, 1].map(|_| create_index_buffer(&device, &allocator, capacity, "keys")),
+            values: [0, 1].map(|_| create_index_buffer(&device, &allocator, capacity, "values")),
+            histogram: create_histogram_buffer(&device, &allocator, capacity),
+            device,
+            allocator,
+            descriptor_set_layout,
+            descriptor_pool,
+            sort_sets: [sets[0], sets[1]],
+            draw_set: sets[2],
+            sort_layout,
+            capacity,
+            bound_particle_buffer: vk::Buffer::null(),
+        };
+        sort.write_buffer_descriptors();
+        sort
+    }
+
+    /// Set to bind as set 1 of the particle pipelines when drawing in sorted order.
+    pub fn draw_set(&self) -> vk::DescriptorSet {
+        self.draw_set
+    }
+
+    /// Buffer holding the sorted particle indices after [`Self::record`].
+    pub fn sorted_indices(&self) -> vk::Buffer {
+        self.values[0].buffer
+    }
+
+    /// Records the key pass and the four radix passes over `particle_count` particles of
+    /// `particle_buffer`, ordering them by the depth `view_proj` gives them.
+    ///
+    /// Must be recorded outside a render pass; the indices are ready for the vertex
+    /// stage afterwards.
+    pub fn record(
+        &mut self,
+        command_buffer: vk::CommandBuffer,
+        particle_buffer: vk::Buffer,
+        particle_count: u32,
+        view_proj: [[f32; 4]; 4],
+    ) {
+        if particle_buffer != self.bound_particle_buffer {
+            for set in [self.sort_sets[0], self.sort_sets[1], self.draw_set] {
+                self.write_descriptor(set, 0, particle_buffer);
+            }
+            self.bound_particle_buffer = particle_buffer;
+        }
+        self.ensure_capacity(particle_count as usize);
+        let block_count = sort_block_count(particle_count);
+        let mut push = SortPushConstants {
+            view_proj,
+            particle_count,
+            shift: 0,
+            block_count,
+            _pad: 0,
+        };
+        unsafe {
+            // Host uploads, the simulation pass, and last frame's draw must all be
+            // done with the buffers before the keys are rewritten.
+            memory_barrier(
+                &self.device,
+                command_buffer,
+                vk::PipelineStageFlags::HOST
+                    | vk::PipelineStageFlags::COMPUTE_SHADER
+                    | vk::PipelineStageFlags::VERTEX_SHADER,
+                vk::PipelineStageFlags::COMPUTE_SHADER,
+                vk::AccessFlags::HOST_WRITE | vk::AccessFlags::SHADER_WRITE,
+                vk::AccessFlags::SHADER_READ | vk::AccessFlags::SHADER_WRITE,
+            );
+            // The key pass writes pair 0, the output side of set 1.
+            self.dispatch(
+                command_buffer,
+                self.keys_pipeline,
+                self.sort_sets[1],
+                &push,
+                particle_count.div_ceil(WORKGROUP_SIZE),
+            );
+            for pass in 0..PASSES {
+                push.shift = pass * RADIX_BITS;
+                let set = self.sort_sets[pass as usize % 2];
+                self.dispatch(
+                    command_buffer,
+                    self.histogram_pipeline,
+                    set,
+                    &push,
+                    block_count,
+                );
+                self.dispatch(command_buffer, self.scan_pipeline, set, &push, 1);
+                self.dispatch(
+                    command_buffer,
+                    self.scatter_pipeline,
+                    set,
+                    &push,
+                    block_count,
+                );
+            }
+            memory_barrier(
+                &self.device,
+                command_buffer,
+                vk::PipelineStageFlags::COMPUTE_SHADER,
+                vk::PipelineStageFlags::VERTEX_SHADER,
+                vk::AccessFlags::SHADER_WRITE,
+                vk::AccessFlags::SHADER_READ,
+            );
+        }
+    }
+
+    /// Records one dispatch followed by a barrier for the next pass.
+    unsafe fn dispatch(
+        &self,
+        command_buffer: vk::CommandBuffer,
+        pipeline: vk::Pipeline,
+        set: vk::DescriptorSet,
+        push: &SortPushConstants,
+        workgroups: u32,
+    ) {
+        unsafe {
+            self.device
+                .cmd_bind_pipeline(command_buffer, vk::PipelineBindPoint::COMPUTE, pipeline);
+            self.device.cmd_bind_descriptor_sets(
+                command_buffer,
+                vk::PipelineBindPoint::COMPUTE,
+                self.sort_layout,
+                0,
+                &[set],
+                &[],
+            );
+            self.device.cmd_push_constants(
+                command_buffer,
+                self.sort_layout,
+                vk::ShaderStageFlags::COMPUTE,
+                0,
+                bytemuck::bytes_of(push),
+            );
+            self.device.cmd_dispatch(command_buffer, workgroups, 1, 1);
+            memory_barrier(
+                &self.device,
+                command_buffer,
+                vk::PipelineStageFlags::COMPUTE_SHADER,
+                vk::PipelineStageFlags::COMPUTE_SHADER,
+                vk::AccessFlags::SHADER_WRITE,
+                vk::AccessFlags::SHADER_READ | vk::AccessFlags::SHADER_WRITE,
+            );
+        }
+    }
+
+    fn ensure_capacity(&mut self, count: usize) {
+        if count <= self.capacity {
+            return;
+        }
+        let alloc = Arc::clone(&self.allocator);
+        for pair in 0..2 {
+            let keys = std::mem::replace(
+                &mut self.keys[pair],
+                create_index_buffer(&self.device, &alloc, count, "keys"),
+            );
+            keys.destroy(&self.device, &alloc);
+            let values = std::mem::replace(
+                &mut self.values[pair],
+                create_index_buffer(&self.device, &alloc, count, "values"),
+            );
+            values.destroy(&self.device, &alloc);
+        }
+        let histogram = std::mem::replace(
+            &mut self.histogram,
+            create_histogram_buffer(&self.device, &alloc, count),
+        );
+        histogram.destroy(&self.device, &alloc);
+        self.capacity = count;
+        self.write_buffer_descriptors();
+    }
+
+    /// Points bindings 1–5 of the pass sets at the key, index, and histogram buffers,
+    /// and the draw set at the final indices.
+    fn write_buffer_descriptors(&self) {
+        for (pass, set) in self.sort_sets.into_iter().enumerate() {
+            let (input, output) = (pass, 1 - pass);
+            self.write_descriptor(set, 1, self.keys[input].buffer);
+            self.write_descriptor(set, 2, self.values[input].buffer);
+            self.write_descriptor(set, 3, self.keys[output].buffer);
+            self.write_descriptor(set, 4, self.values[output].buffer);
+            self.write_descriptor(set, 5, self.histogram.buffer);
+        }
+        // Four passes end where they began, in pair 0. Binding 2 of the culling layout
+        // holds the indirect command, which sorted draws do not read.
+        self.write_descriptor(self.draw_set, 1, self.values[0].buffer);
+        self.write_descriptor(self.draw_set, 2, self.histogram.buffer);
+    }
+
+    fn write_descriptor(&self, set: vk::DescriptorSet, binding: u32, buffer: vk::Buffer) {
+        let infos = [vk::DescriptorBufferInfo {
+            buffer,
+            offset: 0,
+            range: vk::WHOLE_SIZE,
+        }];
+        let writes = [vk::WriteDescriptorSet::default()
+            .dst_set(set)
+            .dst_binding(binding)
+            .descriptor_type(vk::DescriptorType::STORAGE_BUFFER)
+            .buffer_info(&infos)];
+        unsafe {
+            self.device.update_descriptor_sets(&writes, &[]);
+        }
+    }
+}
+
+impl Drop for GpuDepthSort {
+    fn drop(&mut self) {
+        unsafe {
+            for pipeline in [
+                self.keys_pipeline,
+                self.histogram_pipeline,
+                self.scan_pipeline,
+                self.scatter_pipeline,
+            ] {
+                self.device.destroy_pipeline(pipeline, None);
+            }
+            self.device.destroy_pipeline_layout(self.sort_layout, None);
+            self.device
+                .destroy_descriptor_pool(self.descriptor_pool, None);
+            self.device
+                .destroy_descriptor_set_layout(self.descriptor_set_layout, None);
+        }
+        let alloc = Arc::clone(&self.allocator);
+        let buffers = self
+            .keys
+            .iter_mut()
+            .chain(self.values.iter_mut())
+            .chain([&mut self.histogram]);
+        for buffer in buffers {
+            let taken = std::mem::replace(
+                buffer,
+                AllocatedBuffer {
+                    buffer: vk::Buffer::null(),
+                    allocation: None,
+                },
+            );
+            if taken.buffer != vk::Buffer::null() {
+                taken.destroy(&self.device, &alloc);
+            }
+        }
+    }
+}
+
+/// Number of [`SORT_BLOCK_SIZE`] blocks, and so of histogram rows, for `count` keys.
+pub fn sort_block_count(count: u32) -> u32 {
+    count.div_ceil(SORT_BLOCK_SIZE).max(1)
+}
+
+/// Sort key the key pass gives a particle at clip-space `w` (its view-space depth):
+/// ascending keys run from the farthest particle to the nearest.
+pub fn depth_sort_key(w: f32) -> u32 {
+    let bits = w.to_bits();
+    let ordered = if bits & 0x8000_0000 != 0 {
+        !bits
+    } else {
+        bits | 0x8000_0000
+    };
+    !ordered
+}
+
+fn create_index_buffer(
+    device: &ash::Device,
+    allocator: &Arc<Mutex<Allocator>>,
+    capacity: usize,
+    name: &str,
+) -> AllocatedBuffer {
+    AllocatedBuffer::new(
+        device,
+        allocator,
+        (std::mem::size_of::<u32>() * capacity.max(1)) as u64,
+        vk::BufferUsageFlags::STORAGE_BUFFER | vk::BufferUsageFlags::TRANSFER_SRC,
+        gpu_allocator::MemoryLocation::GpuOnly,
+        &format!("gpu_depth_sort_{}", name),
+    )
+}
+
+fn create_histogram_buffer(
+    device: &ash::Device,
+    allocator: &Arc<Mutex<Allocator>>,
+    capacity: usize,
+) -> AllocatedBuffer {
+    let rows = sort_block_count(capacity.min(u32::MAX as usize) as u32);
+    AllocatedBuffer::new(
+        device,
+        allocator,
+        std::mem::size_of::<u32>() as u64 * u64::from(RADIX) * u64::from(rows),
+        vk::BufferUsageFlags::STORAGE_BUFFER,
+        gpu_allocator::MemoryLocation::GpuOnly,
+        "gpu_depth_sort_histogram",
+    )
+}
+
+/// Binding 0: particle SSBO, 1–2: keys and indices in, 3–4: keys and indices out,
+/// 5: block digit counts, then offsets.
+fn create_sort_descriptor_set_layout(device: &ash::Device) -> vk::DescriptorSetLayout {
+    let bindings = [0, 1, 2, 3, 4, 5].map(|index| {
+        vk::DescriptorSetLayoutBinding::default()
+            .binding(index)
+            .descriptor_type(vk::DescriptorType::STORAGE_BUFFER)
+            .descriptor_count(1)
+            .stage_flags(vk::ShaderStageFlags::COMPUTE)
+    });
+    let ci = vk::DescriptorSetLayoutCreateInfo::default().bindings(&bindings);
+    unsafe { device.create_descriptor_set_layout(&ci, None) }.unwrap()
+}
+
+fn create_sort_pipeline_layout(
+    device: &ash::Device,
+    descriptor_set_layout: vk::DescriptorSetLayout,
+) -> vk::PipelineLayout {
+    let ranges = [vk::PushConstantRange {
+        stage_flags: vk::ShaderStageFlags::COMPUTE,
+        offset: 0,
+        size: std::mem::size_of::<SortPushConstants>() as u32,
+    }];
+    let set_layouts = [descriptor_set_layout];
+    let ci = vk::PipelineLayoutCreateInfo::default()
+        .set_layouts(&set_layouts)
+        .push_constant_ranges(&ranges);
+    unsafe { device.create_pipeline_layout(&ci, None) }.unwrap()
+}
+
+fn create_sort_pipeline(
+    device: &ash::Device,
+    layout: vk::PipelineLayout,
+    spv: &[u8],
+) -> vk::Pipeline {
+    let module = create_shader_module(device, spv);
+    let stage = vk::PipelineShaderStageCreateInfo::default()
+        .stage(vk::ShaderStageFlags::COMPUTE)
+        .module(module)
+        .name(c"main");
+    let ci = vk::ComputePipelineCreateInfo::default()
+        .stage(stage)
+        .layout(layout);
+    let pipelines =
+        unsafe { device.create_compute_pipelines(vk::PipelineCache::null(), &[ci], None) }.unwrap();
+    unsafe {
+        device.destroy_shader_module(module, None);
+    }
+    pipelines[0]
+}
+
+/// Two pass sets of six bindings and the three-binding draw set.
+fn create_sort_descriptor_pool(device: &ash::Device) -> vk::DescriptorPool {
+    let pool_sizes = [vk::DescriptorPoolSize {
+        ty: vk::DescriptorType::STORAGE_BUFFER,
+        descriptor_count: 15,
+    }];
+    let ci = vk::DescriptorPoolCreateInfo::default()
+        .pool_sizes(&pool_sizes)
+        .max_sets(3);
+    unsafe { device.create_descriptor_pool(&ci, None) }.unwrap()
+}
@@ -1,10 +1,15 @@
-use crate::simulation::{EPSILON, G, LIGHT_SPEED, Particle};
+use crate::container::ContainerWalls;
+use crate::drag::{DragForce, DragModel};
+use crate::frame_pipeline::{STAGING_SLOTS, StagingRing};
+use crate::langevin::LangevinNoise;
+use crate::memory_budget::GPU_PARTICLE_SLOT_BYTES;
+use crate::simulation::{EPSILON, EngineConfig, G, Particle};
 use crate::ui_state::SimulationType;
 use ash::vk;
 use dst_math::s3_galaxy::galaxy_radius_sim;
 use dst_math::spacetime::velocity_from_momentum;
 use glam::{DQuat, DVec3};
-use gpu_allocator::vulkan::Allocator;
+use gpu_allocator::vulkan::{Allocation, Allocator};
 use std::sync::{Arc, Mutex};
 use vulkanvil::{AllocatedBuffer, create_shader_module};
 
@@ -22,7 +27,7 @@ pub struct GpuParticle {
     pub color: [f32; 4],
 }
 
-const _: () = assert!(std::mem::size_of::<GpuParticle>() == 64);
+const _: () = assert!(std::mem::size_of::<GpuParticle>() == GPU_PARTICLE_SLOT_BYTES);
 
 impl GpuParticle {
     pub fn from_cpu(particle: &Particle, simulation_type: SimulationType) -> Self {
@@ -93,7 +98,9 @@ impl GpuParticle {
         }
     }
 
-    pub fn to_cpu(self, simulation_type: SimulationType, scale: f64) -> Particle {
+    /// Converts back to a CPU particle; momentum particles get their velocity back with
+    /// the speed of light `light_speed` in m/s.
+    pub fn to_cpu(self, simulation_type: SimulationType, scale: f64, light_speed: f64) -> Particle {
         if simulation_type == SimulationType::DstGalaxy {
             return Particle {
                 position: DVec3::new(
@@ -117,6 +124,7 @@ impl GpuParticle {
                     self.attrs[3] as f64,
                     self.position[3] as f64,
                 ),
+                id: 0,
             };
         }
         let mass = self.attrs[0] as f64;
@@ -130,7 +138,7 @@ impl GpuParticle {
             DVec3::ZERO
         };
         let velocity = if simulation_type.uses_momentum_particles() {
-            velocity_from_momentum(momentum, mass, LIGHT_SPEED / scale)
+            velocity_from_momentum(momentum, mass, light_speed / scale)
         } else {
             DVec3::new(
                 self.velocity[0] as f64,
@@ -151,6 +159,7 @@ impl GpuParticle {
             proper_time: self.attrs[1] as f64,
             lambda_eff: self.attrs[2] as f64,
             orientation: DQuat::IDENTITY,
+            id: 0,
         }
     }
 }
@@ -168,6 +177,33 @@ struct ComputePushConstants {
     phase: u32,
     galaxy_radius: f32,
     cull_max_angle: f32,
+    frame_angular_velocity: f32,
+    wall_half_size: f32,
+    wall_restitution: f32,
+    drag_model: u32,
+    drag_rate: f32,
+    drag_dispersion: f32,
+    noise_decay: f32,
+    noise_amplitude: f32,
+    noise_seed: u32,
+}
+
+/// Forces layered on top of an engine's gravity for one GPU dispatch.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct ExternalForces {
+    pub walls: Option<ContainerWalls>,
+    pub drag: Option<DragForce>,
+    pub noise: Option<LangevinNoise>,
+}
+
+/// Steps one GPU dispatch advances. The Langevin thermostat keys the noise of each step
+/// on its frame number, counted from `first_frame`.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct GpuAdvance {
+    pub delta_seconds: f64,
+    pub steps: u32,
+    /// Frame number of the first dispatched step.
+    pub first_frame: u64,
 }
 
 pub struct GpuParticleSimulation {
@@ -180,6 +216,17 @@ pub struct GpuParticleSimulation {
     compute_layout: vk::PipelineLayout,
     particle_count: u32,
     buffer_capacity: usize,
+    /// Particle IDs in SSBO slot order; the GPU layout has no room for them, so they are
+    /// kept here in lockstep with uploads, removals, and compaction.
+    particle_ids: Vec<u64>,
+    /// Slots the worker writes CPU frames into, backed by `staging_buffers`.
+    staging: StagingRing<GpuParticle>,
+    /// Persistently mapped staging buffer of each slot of `staging`.
+    staging_buffers: [Option<AllocatedBuffer>; STAGING_SLOTS],
+    /// Taken staging slot and its particle count, waiting to be copied into the SSBO.
+    staged_upload: Option<(usize, usize)>,
+    /// Settings of the running simulation; the GPU steps with its speed of light.
+    config: EngineConfig,
 }
 
 impl GpuParticleSimulation {
@@ -220,6 +267,11 @@ impl GpuParticleSimulation {
             compute_layout,
             particle_count,
             buffer_capacity,
+            particle_ids: particles.iter().map(|p| p.id).collect(),
+            staging: StagingRing::new(),
+            staging_buffers: [None, None],
+            staged_upload: None,
+            config: EngineConfig::default(),
         };
         if !particles.is_empty() {
             sim.write_cpu_particles(particles, SimulationType::Normal);
@@ -231,17 +283,29 @@ impl GpuParticleSimulation {
         self.particle_count
     }
 
+    /// Sets the settings of the running simulation, from the UI state like the CPU
+    /// engines'.
+    pub fn set_config(&mut self, config: EngineConfig) {
+        self.config = config;
+    }
+
     pub fn descriptor_set(&self) -> vk::DescriptorSet {
         self.descriptor_set
     }
 
+    pub fn particle_buffer(&self) -> vk::Buffer {
+        self.particle_buffer.buffer
+    }
+
     /// Uploads CPU simulation particles into the mapped SSBO.
     pub fn upload_from_cpu(
         &mut self,
         particles: &[Particle],
         simulation_type: SimulationType,
     ) {
+        self.release_staged_upload();
         self.particle_count = particles.len() as u32;
+        self.particle_ids = particles.iter().map(|p| p.id).collect();
         if particles.is_empty() {
             return;
         }
@@ -257,6 +321,7 @@ impl GpuParticleSimulation {
         }
         if count == 1 {
             self.particle_count = 0;
+            self.particle_ids.clear();
             return true;
         }
         let Some(slice) = mapped_particle_slice_mut(&self.particle_buffer, count) else {
@@ -264,6 +329,9 @@ impl GpuParticleSimulation {
         };
         slice.copy_within(index + 1..count, index);
         self.particle_count = (count - 1) as u32;
+        if index < self.particle_ids.len() {
+            self.particle_ids.remove(index);
+        }
         true
     }
 
@@ -300,39 +368,51 @@ impl GpuParticleSimulation {
             } else {
                 if write != read {
                     slice[write] = p;
+                    if read < self.particle_ids.len() {
+                        self.particle_ids[write] = self.particle_ids[read];
+                    }
                 }
                 write += 1;
             }
         }
         self.particle_count = write as u32;
+        self.particle_ids.truncate(write);
         removed
     }
 
-    /// Records compute dispatches that advance `steps` simulation steps on the GPU.
+    /// Records compute dispatches that advance `advance.steps` simulation steps on the GPU.
     ///
     /// Each step runs phase 0 (position integration) then phase 1 (velocity update).
     /// DstGravity folds time-delay into phase 1 in a single neighbor pass.
     /// keeping the GPU step count in lockstep with the simulation frame counter.
-    /// `scale` is only consulted for the relativistic simulation types.
+    /// `scale` is only consulted for the relativistic simulation types and dynamical
+    /// friction. The container walls of `forces` bounce particles after each position
+    /// update; drag and the Langevin kicks follow each velocity update.
     pub fn dispatch(
         &self,
         command_buffer: vk::CommandBuffer,
         simulation_type: SimulationType,
-        delta_seconds: f64,
         scale: f64,
-        steps: u32,
         cull_max_angle: f32,
+        advance: GpuAdvance,
+        forces: ExternalForces,
     ) {
+        let GpuAdvance {
+            delta_seconds,
+            steps,
+            first_frame,
+        } = advance;
         if self.particle_count == 0 || steps == 0 {
             return;
         }
 
-        let light_speed_per_scale = (LIGHT_SPEED / scale) as f32;
+        let light_speed_per_scale = (self.config.light_speed() / scale) as f32;
         let galaxy_radius = if simulation_type == SimulationType::DstGalaxy {
             galaxy_radius_sim(scale) as f32
         } else {
             0.0
         };
+        let ExternalForces { walls, drag, noise } = forces;
         let step = ComputePushConstants {
             particle_count: self.particle_count,
             delta_seconds: delta_seconds as f32,
@@ -344,6 +424,27 @@ impl GpuParticleSimulation {
             phase: 0,
             galaxy_radius,
             cull_max_angle,
+            frame_angular_velocity: if simulation_type.supports_rotating_frame() {
+                self.config.frame_angular_velocity as f32
+            } else {
+                0.0
+            },
+            wall_half_size: walls.map_or(0.0, |walls| walls.half_size as f32),
+            wall_restitution: walls.map_or(0.0, |walls| walls.restitution as f32),
+            drag_model: drag.map_or(0, |drag| drag.model as u32),
+            drag_rate: drag.map_or(0.0, |drag| match drag.model {
+                DragModel::Off => 0.0,
+                DragModel::Linear => drag.damping_rate as f32,
+                DragModel::DynamicalFriction => {
+                    (4.0 * std::f64::consts::PI * G * drag.dynamical_friction_density()) as f32
+                }
+            }),
+            drag_dispersion: drag.map_or(0.0, |drag| (drag.velocity_dispersion / scale) as f32),
+            noise_decay: noise.map_or(1.0, |noise| noise.decay(delta_seconds) as f32),
+            noise_amplitude: noise.map_or(0.0, |noise| {
+                noise.kick_amplitude(scale, delta_seconds) as f32
+            }),
+            noise_seed: 0,
         };
         let workgroups = (self.particle_count + WORKGROUP_SIZE - 1) / WORKGROUP_SIZE;
 
@@ -371,10 +472,18 @@ impl GpuParticleSimulation {
                     vk::PipelineStageFlags::COMPUTE_SHADER,
                 );
 
+                // The shader mixes the step's seed with the particle index.
+                let noise_seed = noise.map_or(0, |noise| {
+                    noise.stream_seed(first_frame + u64::from(i), 0) as u32
+                });
                 self.dispatch_phase(
                     command_buffer,
                     workgroups,
-                    ComputePushConstants { phase: 1, ..step },
+                    ComputePushConstants {
+                        phase: 1,
+                        noise_seed,
+                        ..step
+                    },
                 );
 
                 // Between steps the next dispatch reads the buffer in COMPUTE again;
@@ -396,12 +505,17 @@ impl GpuParticleSimulation {
 
     /// Copies GPU particle data back to CPU for snapshot export.
     pub fn readback_to_cpu(&self, simulation_type: SimulationType, scale: f64) -> Vec<Particle> {
-        read_mapped_particles(
+        let mut particles = read_mapped_particles(
             &self.particle_buffer,
             self.particle_count as usize,
             simulation_type,
             scale,
-        )
+            self.config.light_speed(),
+        );
+        for (particle, &id) in particles.iter_mut().zip(&self.particle_ids) {
+            particle.id = id;
+        }
+        particles
     }
 
     /// Reads one particle from the host-mapped SSBO without copying the full buffer.
@@ -414,7 +528,108 @@ impl GpuParticleSimulation {
         if index >= self.particle_count as usize {
             return None;
         }
-        read_mapped_particle_at(&self.particle_buffer, index, simulation_type, scale)
+        let mut particle = read_mapped_particle_at(
+            &self.particle_buffer,
+            index,
+            simulation_type,
+            scale,
+            self.config.light_speed(),
+        )?;
+        particle.id = self.particle_ids.get(index).copied().unwrap_or(0);
+        Some(particle)
+    }
+
+    /// Backs the worker's staging slots with this simulation's buffers, replacing the
+    /// ring they were attached to before.
+    pub fn connect_staging(&mut self, staging: StagingRing<GpuParticle>) {
+        self.release_staged_upload();
+        self.staging.detach();
+        self.staging = staging;
+        self.grow_staging();
+    }
+
+    /// Gives each free staging slot that is too small for the worker's frames a mapped
+    /// buffer large enough. A slot's old buffer is free, so no copy still reads it.
+    pub fn grow_staging(&mut self) {
+        while let Some((index, capacity)) = self.staging.claim_undersized() {
+            let buffer =
+                create_staging_buffer(&self.device, &self.allocator, capacity, "particle_staging");
+            let mapped = buffer.allocation.as_ref().and_then(Allocation::mapped_ptr);
+            let memory = match mapped {
+                Some(mapped) => mapped.as_ptr().cast::<GpuParticle>(),
+                None => std::ptr::null_mut(),
+            };
+            unsafe { self.staging.attach(index, memory, capacity) };
+            if let Some(old) = self.staging_buffers[index].replace(buffer) {
+                old.destroy(&self.device, &self.allocator);
+            }
+            if memory.is_null() {
+                break;
+            }
+        }
+    }
+
+    /// Takes the worker's newest staged frame, to be copied into the SSBO by the next
+    /// [`Self::record_staged_upload`]. Returns whether there was one.
+    pub fn take_staged_frame(&mut self) -> bool {
+        let Some((index, len)) = self.staging.take(&mut self.particle_ids) else {
+            return false;
+        };
+        self.release_staged_upload();
+        self.staged_upload = Some((index, len));
+        self.particle_count = len as u32;
+        self.ensure_buffer_capacity(len);
+        true
+    }
+
+    /// Records the copy of the taken staged frame into the SSBO, then a barrier that makes
+    /// it visible to the shaders reading particles. Returns the staging slot copied, to
+    /// be passed to [`Self::staged_upload_submitted`].
+    pub fn record_staged_upload(&mut self, command_buffer: vk::CommandBuffer) -> Option<usize> {
+        let (index, len) = self.staged_upload.take()?;
+        let Some(staging_buffer) = self.staging_buffers[index].as_ref() else {
+            self.staging.release(index);
+            return None;
+        };
+        let region = vk::BufferCopy::default().size(particle_buffer_size(len));
+        unsafe {
+            self.device.cmd_copy_buffer(
+                command_buffer,
+                staging_buffer.buffer,
+                self.particle_buffer.buffer,
+                &[region],
+            );
+            let barrier = vk::MemoryBarrier::default()
+                .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+                .dst_access_mask(vk::AccessFlags::SHADER_READ | vk::AccessFlags::SHADER_WRITE);
+            self.device.cmd_pipeline_barrier(
+                command_buffer,
+                vk::PipelineStageFlags::TRANSFER,
+                vk::PipelineStageFlags::COMPUTE_SHADER | vk::PipelineStageFlags::VERTEX_SHADER,
+                vk::DependencyFlags::empty(),
+                &[barrier],
+                &[],
+                &[],
+            );
+        }
+        Some(index)
+    }
+
+    /// Marks a staging slot as copied by the command buffer of frame-in-flight `frame`.
+    pub fn staged_upload_submitted(&self, index: usize, frame: usize) {
+        self.staging.submitted(index, frame);
+    }
+
+    /// Frees the staging slots copied by frame-in-flight `frame` after its fence wait.
+    pub fn retire_staging(&self, frame: usize) {
+        self.staging.retire(frame);
+    }
+
+    /// Drops a staged frame whose copy was not recorded yet; a direct upload supersedes it.
+    fn release_staged_upload(&mut self) {
+        if let Some((index, _)) = self.staged_upload.take() {
+            self.staging.release(index);
+        }
     }
 
     fn ensure_buffer_capacity(&mut self, count: usize) {
@@ -483,6 +698,10 @@ impl Drop for GpuParticleSimulation {
         if empty.buffer != vk::Buffer::null() {
             empty.destroy(&self.device, &alloc);
         }
+        self.staging.detach();
+        for buffer in self.staging_buffers.iter_mut().filter_map(Option::take) {
+            buffer.destroy(&self.device, &alloc);
+        }
     }
 }
 
@@ -500,7 +719,25 @@ fn create_particle_storage_buffer(
         device,
         allocator,
         particle_buffer_size(capacity),
-        vk::BufferUsageFlags::STORAGE_BUFFER | vk::BufferUsageFlags::TRANSFER_SRC,
+        vk::BufferUsageFlags::STORAGE_BUFFER
+            | vk::BufferUsageFlags::TRANSFER_SRC
+            | vk::BufferUsageFlags::TRANSFER_DST,
+        gpu_allocator::MemoryLocation::CpuToGpu,
+        name,
+    )
+}
+
+fn create_staging_buffer(
+    device: &ash::Device,
+    allocator: &Arc<Mutex<Allocator>>,
+    capacity: usize,
+    name: &str,
+) -> AllocatedBuffer {
+    AllocatedBuffer::new(
+        device,
+        allocator,
+        particle_buffer_size(capacity),
+        vk::BufferUsageFlags::TRANSFER_SRC,
         gpu_allocator::MemoryLocation::CpuToGpu,
         name,
     )
@@ -520,11 +757,12 @@ fn read_mapped_particle_at(
     index: usize,
     simulation_type: SimulationType,
     scale: f64,
+    light_speed: f64,
 ) -> Option<Particle> {
     let alloc = buffer.allocation.as_ref()?;
     let mapped = alloc.mapped_ptr()?;
     let gpu_particle = unsafe { *(mapped.as_ptr() as *const GpuParticle).add(index) };
-    Some(gpu_particle.to_cpu(simulation_type, scale))
+    Some(gpu_particle.to_cpu(simulation_type, scale, light_speed))
 }
 
 fn read_mapped_particles(
@@ -532,6 +770,7 @@ fn read_mapped_particles(
     count: usize,
     simulation_type: SimulationType,
     scale: f64,
+    light_speed: f64,
 ) -> Vec<Particle> {
     if count == 0 {
         return Vec::new();
@@ -547,7 +786,7 @@ fn read_mapped_particles(
     gpu_particles
         .iter()
         .copied()
-        .map(|gpu| gpu.to_cpu(simulation_type, scale))
+        .map(|gpu| gpu.to_cpu(simulation_type, scale, light_speed))
         .collect()
 }
 
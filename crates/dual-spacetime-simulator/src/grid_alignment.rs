@@ -0,0 +1,137 @@
+use crate::simulation::Particle;
+use glam::{DQuat, DVec3};
+use std::time::Instant;
+
+/// Obliquity of the ecliptic at J2000 in radians: the tilt of the ecliptic from the
+/// ICRF equator, the x–y plane of the Solar System preset.
+pub const OBLIQUITY_J2000: f64 = 0.409_092_804_222_329;
+/// Frames between recomputations of the invariant plane.
+pub const GRID_ALIGNMENT_INTERVAL: i64 = 30;
+/// Duration of the animated turn to a new grid plane.
+const GRID_TURN_SECONDS: f64 = 0.8;
+
+/// Plane the grid is drawn in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum GridAlignment {
+    /// The fixed x–z plane.
+    #[default]
+    Fixed,
+    /// The ecliptic of the Solar System preset's equatorial frame.
+    Ecliptic,
+    /// Perpendicular to the total angular momentum about the center of mass.
+    InvariantPlane,
+}
+
+impl GridAlignment {
+    pub const ALL: [Self; 3] = [Self::Fixed, Self::Ecliptic, Self::InvariantPlane];
+
+    /// Normal of the grid plane; `angular_momentum` is the system's total, used by the
+    /// invariant plane and ignored otherwise. Falls back to +Y for a system without
+    /// angular momentum.
+    pub fn plane_normal(self, angular_momentum: DVec3) -> DVec3 {
+        match self {
+            GridAlignment::Fixed => DVec3::Y,
+            GridAlignment::Ecliptic => {
+                DVec3::new(0.0, -OBLIQUITY_J2000.sin(), OBLIQUITY_J2000.cos())
+            }
+            GridAlignment::InvariantPlane => angular_momentum.try_normalize().unwrap_or(DVec3::Y),
+        }
+    }
+}
+
+impl std::fmt::Display for GridAlignment {
+    /// Formats grid alignment names for selection controls.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            GridAlignment::Fixed => "Fixed (XZ)",
+            GridAlignment::Ecliptic => "Ecliptic",
+            GridAlignment::InvariantPlane => "Invariant Plane",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// Total angular momentum `Σ m (r − R) × (v − V)` about the center of mass, in simulation
+/// units. Massless particles are skipped.
+pub fn total_angular_momentum(particles: &[Particle]) -> DVec3 {
+    let (mass, weighted_position, momentum) = particles
+        .iter()
+        .filter(|particle| particle.mass > 0.0)
+        .fold(
+            (0.0, DVec3::ZERO, DVec3::ZERO),
+            |(mass, position, momentum), particle| {
+                (
+                    mass + particle.mass,
+                    position + particle.position * particle.mass,
+                    momentum + particle.velocity * particle.mass,
+                )
+            },
+        );
+    if mass <= 0.0 {
+        return DVec3::ZERO;
+    }
+    let center = weighted_position / mass;
+    let center_velocity = momentum / mass;
+    particles
+        .iter()
+        .filter(|particle| particle.mass > 0.0)
+        .map(|particle| {
+            (particle.position - center).cross(particle.velocity - center_velocity) * particle.mass
+        })
+        .sum()
+}
+
+/// Rotation turning the grid's +Y normal onto the plane with `normal`, flipping the
+/// normal when that needs the smaller turn since the grid looks the same from both sides.
+pub fn grid_rotation(normal: DVec3) -> DQuat {
+    let normal = normal.try_normalize().unwrap_or(DVec3::Y);
+    let normal = if normal.y < 0.0 { -normal } else { normal };
+    DQuat::from_rotation_arc(DVec3::Y, normal)
+}
+
+/// Grid orientation that eases toward each new target instead of snapping.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GridOrientation {
+    /// Orientation drawn by the last update.
+    shown: DQuat,
+    target: DQuat,
+    /// Orientation the running turn started from, and when.
+    transition: Option<(DQuat, Instant)>,
+}
+
+impl GridOrientation {
+    /// Starts turning from the shown orientation toward `target`; a target matching the
+    /// current one keeps the running turn.
+    pub fn turn_to(&mut self, target: DQuat, now: Instant) {
+        if self.target.dot(target).abs() > 1.0 - 1e-12 {
+            return;
+        }
+        self.target = target;
+        self.transition = Some((self.shown, now));
+    }
+
+    /// Advances to `now` and returns the orientation to draw.
+    pub fn update(&mut self, now: Instant) -> DQuat {
+        if let Some((from, start)) = self.transition {
+            let t = now.duration_since(start).as_secs_f64() / GRID_TURN_SECONDS;
+            if t >= 1.0 {
+                self.transition = None;
+                self.shown = self.target;
+            } else {
+                let eased = t * t * (3.0 - 2.0 * t);
+                self.shown = from.slerp(self.target, eased);
+            }
+        }
+        self.shown
+    }
+
+    /// Orientation drawn by the last update.
+    pub fn shown(&self) -> DQuat {
+        self.shown
+    }
+
+    /// Returns whether a turn is still running, so frames keep being drawn.
+    pub fn is_turning(&self) -> bool {
+        self.transition.is_some()
+    }
+}
@@ -0,0 +1,209 @@
+use crate::palette::group_palette_color;
+use crate::simulation::Particle;
+use ahash::AHashMap;
+use glam::DVec3;
+
+/// Default linking length as a fraction of the mean interparticle separation.
+pub const DEFAULT_LINKING_LENGTH_FACTOR: f64 = 0.2;
+/// Default minimum member count for a friends-of-friends group to be reported.
+pub const DEFAULT_MIN_GROUP_MEMBERS: u32 = 10;
+/// Color given to live particles outside every reported group when coloring by group.
+pub const UNGROUPED_COLOR: [f32; 4] = [0.35, 0.35, 0.35, 1.0];
+
+/// One friends-of-friends group.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParticleGroup {
+    /// Ascending indices of the member particles.
+    pub members: Vec<usize>,
+    /// Sum of member masses in simulation units.
+    pub mass: f64,
+    pub center_of_mass: DVec3,
+    /// Largest member distance from the center of mass (base-scale units).
+    pub radius: f64,
+    /// Member nearest the center of mass, used to select the group from the UI.
+    pub central_member: usize,
+}
+
+/// Column the Groups panel table is sorted by.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum GroupSortKey {
+    #[default]
+    Mass,
+    Radius,
+    Members,
+}
+
+impl GroupSortKey {
+    pub const ALL: [Self; 3] = [Self::Mass, Self::Radius, Self::Members];
+}
+
+impl std::fmt::Display for GroupSortKey {
+    /// Formats group table column names.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            GroupSortKey::Mass => "Mass",
+            GroupSortKey::Radius => "Size",
+            GroupSortKey::Members => "Members",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// Sorts groups by `key`, largest first when `descending`.
+pub fn sort_groups(groups: &mut [ParticleGroup], key: GroupSortKey, descending: bool) {
+    groups.sort_by(|a, b| {
+        let order = match key {
+            GroupSortKey::Mass => a.mass.total_cmp(&b.mass),
+            GroupSortKey::Radius => a.radius.total_cmp(&b.radius),
+            GroupSortKey::Members => a.members.len().cmp(&b.members.len()),
+        };
+        if descending { order.reverse() } else { order }
+    });
+}
+
+/// Returns `(V / N)^(1/3)` for live particles, with `V` the cube of the largest
+/// bounding-box extent so flat systems (disks, planetary orbits) still get a usable scale.
+pub fn mean_interparticle_separation(particles: &[Particle]) -> Option<f64> {
+    let live: Vec<DVec3> = particles
+        .iter()
+        .filter(|p| p.color[3] != 0.0 && p.position.is_finite())
+        .map(|p| p.position)
+        .collect();
+    let first = *live.first()?;
+    let (min, max) = live
+        .iter()
+        .fold((first, first), |(min, max), &p| (min.min(p), max.max(p)));
+    let extent = (max - min).max_element();
+    (extent > 0.0).then(|| extent / (live.len() as f64).cbrt())
+}
+
+fn find_root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// Links live particles closer than `linking_length` into groups and returns those with
+/// at least `min_members` members, heaviest first.
+///
+/// Neighbors are found on a hash grid with cells of one linking length, so each particle
+/// only tests the 27 surrounding cells.
+pub fn friends_of_friends(
+    particles: &[Particle],
+    linking_length: f64,
+    min_members: usize,
+) -> Vec<ParticleGroup> {
+    if !(linking_length > 0.0 && linking_length.is_finite()) {
+        return Vec::new();
+    }
+    let live: Vec<usize> = (0..particles.len())
+        .filter(|&i| particles[i].color[3] != 0.0 && particles[i].position.is_finite())
+        .collect();
+    let cell_of = |i: usize| {
+        let c = (particles[i].position / linking_length).floor();
+        (c.x as i64, c.y as i64, c.z as i64)
+    };
+    let mut cells: AHashMap<(i64, i64, i64), Vec<usize>> = AHashMap::new();
+    for &i in &live {
+        cells.entry(cell_of(i)).or_default().push(i);
+    }
+
+    let mut parent: Vec<usize> = (0..particles.len()).collect();
+    let linking_sq = linking_length * linking_length;
+    for &i in &live {
+        let (cx, cy, cz) = cell_of(i);
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let Some(neighbors) = cells.get(&(cx + dx, cy + dy, cz + dz)) else {
+                        continue;
+                    };
+                    for &j in neighbors {
+                        let separation_sq = particles[i]
+                            .position
+                            .distance_squared(particles[j].position);
+                        if j <= i || separation_sq > linking_sq {
+                            continue;
+                        }
+                        let (a, b) = (find_root(&mut parent, i), find_root(&mut parent, j));
+                        if a != b {
+                            parent[a.max(b)] = a.min(b);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut members_by_root: AHashMap<usize, Vec<usize>> = AHashMap::new();
+    for &i in &live {
+        let root = find_root(&mut parent, i);
+        members_by_root.entry(root).or_default().push(i);
+    }
+    let mut groups: Vec<ParticleGroup> = members_by_root
+        .into_values()
+        .filter(|members| members.len() >= min_members.max(1))
+        .map(|mut members| {
+            members.sort_unstable();
+            let mass: f64 = members.iter().map(|&i| particles[i].mass.max(0.0)).sum();
+            let center_of_mass = if mass > 0.0 {
+                members.iter().fold(DVec3::ZERO, |acc, &i| {
+                    acc + particles[i].position * particles[i].mass.max(0.0)
+                }) / mass
+            } else {
+                members
+                    .iter()
+                    .fold(DVec3::ZERO, |acc, &i| acc + particles[i].position)
+                    / members.len() as f64
+            };
+            let distance = |i: usize| particles[i].position.distance(center_of_mass);
+            let radius = members.iter().map(|&i| distance(i)).fold(0.0, f64::max);
+            let central_member = *members
+                .iter()
+                .min_by(|&&a, &&b| distance(a).total_cmp(&distance(b)))
+                .unwrap();
+            ParticleGroup {
+                members,
+                mass,
+                center_of_mass,
+                radius,
+                central_member,
+            }
+        })
+        .collect();
+    // Hash-map order is arbitrary; fix it first so equal-mass groups sort deterministically.
+    groups.sort_by_key(|group| group.members[0]);
+    sort_groups(&mut groups, GroupSortKey::Mass, true);
+    groups
+}
+
+/// Paints each group's members with a group palette color (in the given order) and every
+/// other live particle with [`UNGROUPED_COLOR`].
+pub fn color_particles_by_group(particles: &mut [Particle], groups: &[ParticleGroup]) {
+    for particle in particles.iter_mut().filter(|p| p.color[3] != 0.0) {
+        particle.color = UNGROUPED_COLOR;
+    }
+    for (rank, group) in groups.iter().enumerate() {
+        let color = group_palette_color(rank);
+        for &i in &group.members {
+            if let Some(particle) = particles.get_mut(i) {
+                particle.color = color;
+            }
+        }
+    }
+}
+
+/// Paints the live members of one group with `color`, leaving every other particle as it
+/// is. Returns whether any particle changed.
+pub fn recolor_group(particles: &mut [Particle], group: &ParticleGroup, color: [f32; 4]) -> bool {
+    let mut changed = false;
+    for &i in &group.members {
+        if let Some(particle) = particles.get_mut(i).filter(|p| p.color[3] != 0.0) {
+            particle.color = color;
+            changed = true;
+        }
+    }
+    changed
+}
@@ -0,0 +1,75 @@
+/// Version shown in the Help window.
+pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// A titled group of (input, action) rows in the Help window.
+pub struct ControlSection {
+    pub title: &'static str,
+    pub controls: &'static [(&'static str, &'static str)],
+}
+
+/// Mouse and keyboard controls, mirroring the event handling in `lib.rs`. Scene gestures
+/// are ignored while the pointer is over a panel, and keys while a text field has focus.
+pub const CONTROL_SECTIONS: [ControlSection; 4] = [
+    ControlSection {
+        title: "Mouse (Lock Camera Up)",
+        controls: &[
+            ("Left drag", "Orbit the camera around the target"),
+            ("Left click", "Select the particle under the cursor"),
+            ("Right drag", "Look around"),
+            ("Right double-click", "Center the target on the origin"),
+            ("Middle drag", "Roll the camera around the view center"),
+            ("Middle click", "Toggle Lock Camera Up"),
+            ("Wheel", "Zoom (follow distance while tracing)"),
+        ],
+    },
+    ControlSection {
+        title: "Mouse (Spacecraft)",
+        controls: &[
+            ("Left click", "Set or clear the ⊕ steer anchor"),
+            ("Right hold", "Yaw toward the ⇔ steer anchor"),
+            ("Middle click", "Toggle Lock Camera Up"),
+            ("Wheel", "Thrust forward or backward"),
+        ],
+    },
+    ControlSection {
+        title: "Keyboard",
+        controls: &[
+            ("W / S", "Move forward / back (spacecraft: pitch)"),
+            ("A / D", "Move left / right (spacecraft: roll)"),
+            ("Q / E", "Yaw"),
+            ("Space / Shift", "Move up / down (spacecraft: thrust)"),
+            ("Arrow keys", "Move the target"),
+            ("Home", "Center the target on the origin"),
+            ("End", "Toggle Lock Camera Up"),
+            ("Ctrl+O", "Open a scenario"),
+            ("Ctrl+S", "Save the scenario"),
+            ("Ctrl+R", "Reset the simulation"),
+            ("Ctrl+Z", "Undo"),
+            ("Ctrl+Y, Ctrl+Shift+Z", "Redo"),
+            ("F1", "Open this window"),
+            ("F11", "Toggle fullscreen"),
+            ("F12", "Save a screenshot at the Render Still size"),
+            ("Pause", "Start or pause the simulation"),
+            ("Escape", "Stop, end tracing, clear the anchor"),
+        ],
+    },
+    ControlSection {
+        title: "Panels",
+        controls: &[
+            ("Slider double-click", "Reset the slider to its default"),
+            ("Help ▸ Tutorial", "Take the guided tour"),
+        ],
+    },
+];
+
+/// (label, URL) pairs shown under the controls.
+pub const PROJECT_LINKS: [(&str, &str); 2] = [
+    (
+        "Source code",
+        "https://github.com/hypernumbernet/dual-spacetime-simulator",
+    ),
+    (
+        "Design overview",
+        "https://github.com/hypernumbernet/dual-spacetime-simulator/blob/main/docs/design_overview.md",
+    ),
+];
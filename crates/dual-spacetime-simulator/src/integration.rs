@@ -1,8 +1,13 @@
 use ash::vk;
 use egui::ClippedPrimitive;
 use egui_winit::winit::event_loop::ActiveEventLoop;
+use std::collections::HashMap;
+use std::time::Instant;
 use winit::window::Window;
 
+/// Offscreen images that can be shown in egui panels at once.
+pub const MAX_USER_IMAGES: u32 = 16;
+
 pub struct Gui {
     pub egui_ctx: egui::Context,
     pub egui_winit: egui_winit::State,
@@ -14,6 +19,14 @@ pub struct Gui {
     pixels_per_point: f32,
     queue: vk::Queue,
     command_pool: vk::CommandPool,
+    /// When egui wants its next pass: now after input it reacted to, later for animations
+    /// and `request_repaint_after`, `None` when it is idle.
+    repaint_deadline: Option<Instant>,
+    device: ash::Device,
+    user_image_sampler: vk::Sampler,
+    user_image_layout: vk::DescriptorSetLayout,
+    user_image_pool: vk::DescriptorPool,
+    user_images: HashMap<egui::TextureId, vk::DescriptorSet>,
 }
 
 impl Gui {
@@ -38,7 +51,7 @@ impl Gui {
         let renderer = egui_ash_renderer::Renderer::with_default_allocator(
             instance,
             physical_device,
-            device,
+            device.clone(),
             render_pass,
             egui_ash_renderer::Options {
                 srgb_framebuffer: is_srgb,
@@ -66,6 +79,9 @@ impl Gui {
         );
 
         let pixels_per_point = window.scale_factor() as f32;
+        let user_image_sampler = create_user_image_sampler(&device);
+        let user_image_layout = create_user_image_descriptor_set_layout(&device);
+        let user_image_pool = create_user_image_descriptor_pool(&device);
 
         Gui {
             egui_ctx,
@@ -78,14 +94,27 @@ impl Gui {
             pixels_per_point,
             queue,
             command_pool,
+            repaint_deadline: Some(Instant::now()),
+            device,
+            user_image_sampler,
+            user_image_layout,
+            user_image_pool,
+            user_images: HashMap::new(),
         }
     }
 
     /// Forwards a window event to egui and reports whether egui consumed it.
     pub fn update(&mut self, window: &Window, winit_event: &winit::event::WindowEvent) -> bool {
-        self.egui_winit
-            .on_window_event(window, winit_event)
-            .consumed
+        let response = self.egui_winit.on_window_event(window, winit_event);
+        if response.repaint {
+            self.repaint_deadline = Some(Instant::now());
+        }
+        response.consumed
+    }
+
+    /// Returns when egui next needs a pass, or `None` while nothing in the UI changes.
+    pub fn repaint_deadline(&self) -> Option<Instant> {
+        self.repaint_deadline
     }
 
     /// Reports whether pointer interactions should be captured by the GUI layer.
@@ -105,6 +134,52 @@ impl Gui {
         layout_function(self);
     }
 
+    /// Makes an offscreen render target drawable with `egui::Image`. The view must stay
+    /// alive, in `SHADER_READ_ONLY_OPTIMAL`, until [`Self::unregister_user_image`] and the
+    /// frames that drew it have finished. Fails once [`MAX_USER_IMAGES`] are registered.
+    pub fn register_user_image(
+        &mut self,
+        image_view: vk::ImageView,
+    ) -> Result<egui::TextureId, String> {
+        let set_layouts = [self.user_image_layout];
+        let alloc_info = vk::DescriptorSetAllocateInfo::default()
+            .descriptor_pool(self.user_image_pool)
+            .set_layouts(&set_layouts);
+        let set = unsafe { self.device.allocate_descriptor_sets(&alloc_info) }
+            .map_err(|e| format!("Failed to allocate a user image descriptor set: {e}"))?[0];
+        let image_info = vk::DescriptorImageInfo::default()
+            .sampler(self.user_image_sampler)
+            .image_view(image_view)
+            .image_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+        let image_infos = [image_info];
+        let write = vk::WriteDescriptorSet::default()
+            .dst_set(set)
+            .dst_binding(0)
+            .descriptor_type(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+            .image_info(&image_infos);
+        unsafe { self.device.update_descriptor_sets(&[write], &[]) };
+        let id = self.renderer.add_user_texture(set);
+        self.user_images.insert(id, set);
+        Ok(id)
+    }
+
+    /// Releases a texture from [`Self::register_user_image`]. Call only once no frame in
+    /// flight draws it.
+    pub fn unregister_user_image(&mut self, id: egui::TextureId) {
+        let Some(set) = self.user_images.remove(&id) else {
+            return;
+        };
+        self.renderer.remove_user_texture(id);
+        unsafe {
+            if let Err(err) = self
+                .device
+                .free_descriptor_sets(self.user_image_pool, &[set])
+            {
+                eprintln!("Gui::unregister_user_image free_descriptor_sets failed: {err:?}");
+            }
+        }
+    }
+
     /// Returns a clone of the egui context for external UI construction.
     pub fn context(&self) -> egui::Context {
         self.egui_ctx.clone()
@@ -154,11 +229,65 @@ impl Gui {
             textures_delta,
             shapes,
             pixels_per_point: _,
-            viewport_output: _,
+            viewport_output,
         } = self.egui_ctx.end_pass();
         self.egui_winit
             .handle_platform_output(window, platform_output);
+        self.repaint_deadline = viewport_output
+            .get(&egui::ViewportId::ROOT)
+            .and_then(|viewport| Instant::now().checked_add(viewport.repaint_delay));
         self.shapes = shapes;
         self.textures_delta = textures_delta;
     }
 }
+
+impl Drop for Gui {
+    /// Destroys the user image sampler, layout, and pool; the pool frees their sets.
+    fn drop(&mut self) {
+        unsafe {
+            self.device
+                .destroy_descriptor_pool(self.user_image_pool, None);
+            self.device
+                .destroy_descriptor_set_layout(self.user_image_layout, None);
+            self.device.destroy_sampler(self.user_image_sampler, None);
+        }
+    }
+}
+
+/// Creates a layout matching the renderer's texture set (a combined image sampler at
+/// binding 0), so registered user images bind like egui's own textures.
+fn create_user_image_descriptor_set_layout(device: &ash::Device) -> vk::DescriptorSetLayout {
+    let binding = vk::DescriptorSetLayoutBinding::default()
+        .binding(0)
+        .descriptor_type(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+        .descriptor_count(1)
+        .stage_flags(vk::ShaderStageFlags::FRAGMENT);
+    let bindings = [binding];
+    let ci = vk::DescriptorSetLayoutCreateInfo::default().bindings(&bindings);
+    unsafe { device.create_descriptor_set_layout(&ci, None) }.unwrap()
+}
+
+fn create_user_image_descriptor_pool(device: &ash::Device) -> vk::DescriptorPool {
+    let pool_size = vk::DescriptorPoolSize {
+        ty: vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
+        descriptor_count: MAX_USER_IMAGES,
+    };
+    let pool_sizes = [pool_size];
+    let ci = vk::DescriptorPoolCreateInfo::default()
+        .flags(vk::DescriptorPoolCreateFlags::FREE_DESCRIPTOR_SET)
+        .max_sets(MAX_USER_IMAGES)
+        .pool_sizes(&pool_sizes);
+    unsafe { device.create_descriptor_pool(&ci, None) }.unwrap()
+}
+
+fn create_user_image_sampler(device: &ash::Device) -> vk::Sampler {
+    let ci = vk::SamplerCreateInfo::default()
+        .mag_filter(vk::Filter::LINEAR)
+        .min_filter(vk::Filter::LINEAR)
+        .mipmap_mode(vk::SamplerMipmapMode::LINEAR)
+        .address_mode_u(vk::SamplerAddressMode::CLAMP_TO_EDGE)
+        .address_mode_v(vk::SamplerAddressMode::CLAMP_TO_EDGE)
+        .address_mode_w(vk::SamplerAddressMode::CLAMP_TO_EDGE)
+        .max_lod(vk::LOD_CLAMP_NONE);
+    unsafe { device.create_sampler(&ci, None) }.unwrap()
+}
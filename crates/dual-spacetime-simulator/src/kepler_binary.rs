@@ -0,0 +1,278 @@
+use crate::simulation::{EPSILON, G, Particle};
+use dst_math::gravity::newtonian_gravity_pair;
+use glam::DVec3;
+use rayon::prelude::*;
+use std::f64::consts::TAU;
+
+/// Steps per orbit below which a tight pair is moved along its Kepler orbit instead.
+pub const DEFAULT_STEPS_PER_ORBIT: u32 = 50;
+pub const MIN_STEPS_PER_ORBIT: u32 = 4;
+pub const MAX_STEPS_PER_ORBIT: u32 = 1000;
+/// Largest tidal perturbation, relative to the pair's own pull at apocenter, a new pair
+/// may have.
+pub const FORM_PERTURBATION: f64 = 1e-3;
+/// Tidal perturbation above which a regularized pair is dissolved. Larger than
+/// [`FORM_PERTURBATION`] so a pair near the limit does not form and dissolve every frame.
+pub const DISSOLVE_PERTURBATION: f64 = 1e-2;
+
+/// Shape of a bound two-body orbit.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Orbit {
+    /// In simulation units.
+    pub semi_major_axis: f64,
+    pub eccentricity: f64,
+    /// In seconds; the same at any world scale.
+    pub period: f64,
+}
+
+impl Orbit {
+    pub fn apocenter(&self) -> f64 {
+        self.semi_major_axis * (1.0 + self.eccentricity)
+    }
+}
+
+/// A tight binary moved along its Kepler orbit over the next frame.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct KeplerPair {
+    /// Indices into the particle list, lower first.
+    pub first: usize,
+    pub second: usize,
+    pub first_id: u64,
+    pub second_id: u64,
+    pub orbit: Orbit,
+    /// Tidal pull of all other particles relative to the pair's own, at apocenter.
+    pub perturbation: f64,
+}
+
+impl KeplerPair {
+    /// The pair's IDs, lower first.
+    pub fn pair(&self) -> (u64, u64) {
+        (
+            self.first_id.min(self.second_id),
+            self.first_id.max(self.second_id),
+        )
+    }
+}
+
+/// Returns the orbit of two bodies with relative position and velocity `relative_*` and
+/// `mu = G (m₁ + m₂)`, or `None` when they are not bound.
+pub fn bound_orbit(relative_position: DVec3, relative_velocity: DVec3, mu: f64) -> Option<Orbit> {
+    let distance = relative_position.length();
+    if !(mu > 0.0 && distance > 0.0) {
+        return None;
+    }
+    let inverse_a = 2.0 / distance - relative_velocity.length_squared() / mu;
+    if !(inverse_a > 0.0 && inverse_a.is_finite()) {
+        return None;
+    }
+    let e_cos = 1.0 - distance * inverse_a;
+    let e_sin = relative_position.dot(relative_velocity) * (inverse_a / mu).sqrt();
+    Some(Orbit {
+        semi_major_axis: 1.0 / inverse_a,
+        eccentricity: e_cos.hypot(e_sin),
+        period: TAU / (mu * inverse_a.powi(3)).sqrt(),
+    })
+}
+
+/// Moves the relative position and velocity of a bound pair along its Kepler orbit by
+/// `dt`, solving Kepler's equation for the change in eccentric anomaly. Whole orbits are
+/// dropped first, so any `dt` costs the same. Unbound pairs move in a straight line.
+pub fn kepler_drift(
+    relative_position: DVec3,
+    relative_velocity: DVec3,
+    mu: f64,
+    dt: f64,
+) -> (DVec3, DVec3) {
+    let r0 = relative_position.length();
+    let inverse_a = 2.0 / r0 - relative_velocity.length_squared() / mu;
+    if !(mu > 0.0 && r0 > 0.0 && inverse_a > 0.0 && inverse_a.is_finite()) {
+        return (
+            relative_position + relative_velocity * dt,
+            relative_velocity,
+        );
+    }
+    let a = 1.0 / inverse_a;
+    let mean_motion = (mu * inverse_a.powi(3)).sqrt();
+    let e_cos = 1.0 - r0 * inverse_a;
+    let e_sin = relative_position.dot(relative_velocity) * (inverse_a / mu).sqrt();
+    let mean_anomaly = (mean_motion * dt).rem_euclid(TAU);
+    // Kepler's equation in the change of eccentric anomaly x. Its derivative r / a is
+    // positive, so Newton's method is kept inside a shrinking bracket on [0, 2π].
+    let kepler = |x: f64| x - e_cos * x.sin() + e_sin * (1.0 - x.cos()) - mean_anomaly;
+    let slope = |x: f64| 1.0 - e_cos * x.cos() + e_sin * x.sin();
+    let (mut low, mut high) = (0.0, TAU);
+    let mut x = mean_anomaly;
+    for _ in 0..64 {
+        let value = kepler(x);
+        if value > 0.0 {
+            high = x;
+        } else {
+            low = x;
+        }
+        let next = x - value / slope(x);
+        let next = if next > low && next < high {
+            next
+        } else {
+            0.5 * (low + high)
+        };
+        let converged = (next - x).abs() <= 1e-15 * TAU;
+        x = next;
+        if converged {
+            break;
+        }
+    }
+    let (sin, cos) = x.sin_cos();
+    let r = a * slope(x);
+    let f = 1.0 - a / r0 * (1.0 - cos);
+    let g = (mean_anomaly - (x - sin)) / mean_motion;
+    let f_dot = -(mu * a).sqrt() * sin / (r * r0);
+    let g_dot = 1.0 - a / r * (1.0 - cos);
+    (
+        relative_position * f + relative_velocity * g,
+        relative_position * f_dot + relative_velocity * g_dot,
+    )
+}
+
+/// Tidal pull of every live particle outside the pair on a separation of `apocenter`,
+/// relative to the pair's own pull: the sum of `2 m_k r³ / (M d_k³)` with `d_k` the
+/// distance from the pair's center of mass.
+pub fn tidal_perturbation(
+    particles: &[Particle],
+    first: usize,
+    second: usize,
+    apocenter: f64,
+) -> f64 {
+    let (p, q) = (&particles[first], &particles[second]);
+    let total_mass = p.mass + q.mass;
+    let center = (p.position * p.mass + q.position * q.mass) / total_mass;
+    let apocenter_cubed = apocenter.powi(3);
+    particles
+        .iter()
+        .enumerate()
+        .filter(|&(k, other)| k != first && k != second && other.color[3] != 0.0)
+        .map(|(_, other)| {
+            let distance = (other.position - center).length();
+            2.0 * other.mass * apocenter_cubed / (total_mass * distance.powi(3))
+        })
+        .sum()
+}
+
+/// Finds the tight binaries to regularize over the next frame of `dt`: live mutual
+/// strongest-pull partners on a bound orbit that `dt` resolves with fewer than
+/// `steps_per_orbit` steps and that the rest of the system barely perturbs. Pairs in
+/// `held` (IDs, lower first) are kept up to twice the period and
+/// [`DISSOLVE_PERTURBATION`].
+///
+/// O(N²), like the direct force sum it replaces for the pairs.
+pub fn find_kepler_pairs(
+    particles: &[Particle],
+    dt: f64,
+    steps_per_orbit: u32,
+    held: &[(u64, u64)],
+) -> Vec<KeplerPair> {
+    let strongest: Vec<Option<usize>> = particles
+        .par_iter()
+        .enumerate()
+        .map(|(i, p)| {
+            if p.color[3] == 0.0 {
+                return None;
+            }
+            particles
+                .iter()
+                .enumerate()
+                .filter(|&(j, q)| j != i && q.color[3] != 0.0)
+                .filter_map(|(j, q)| {
+                    let distance_sq = (q.position - p.position).length_squared();
+                    (distance_sq > 0.0).then_some((j, q.mass / distance_sq))
+                })
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(j, _)| j)
+        })
+        .collect();
+    let max_period = dt.abs() * steps_per_orbit as f64;
+    (0..particles.len())
+        .into_par_iter()
+        .filter_map(|first| {
+            let second = strongest[first]?;
+            if second <= first || strongest[second] != Some(first) {
+                return None;
+            }
+            let (p, q) = (&particles[first], &particles[second]);
+            let mu = G * (p.mass + q.mass);
+            let orbit = bound_orbit(q.position - p.position, q.velocity - p.velocity, mu)?;
+            let ids = (p.id.min(q.id), p.id.max(q.id));
+            let (period_limit, perturbation_limit) = if held.contains(&ids) {
+                (2.0 * max_period, DISSOLVE_PERTURBATION)
+            } else {
+                (max_period, FORM_PERTURBATION)
+            };
+            if orbit.period >= period_limit {
+                return None;
+            }
+            let perturbation = tidal_perturbation(particles, first, second, orbit.apocenter());
+            (perturbation < perturbation_limit).then_some(KeplerPair {
+                first,
+                second,
+                first_id: p.id,
+                second_id: q.id,
+                orbit,
+                perturbation,
+            })
+        })
+        .collect()
+}
+
+/// Returns where each member of `pairs` is after `dt`: the center of mass moves in a
+/// straight line and the members follow their Kepler orbit around it. Each entry is the
+/// particle index, its position, and its velocity.
+pub fn drift_kepler_pairs(
+    particles: &[Particle],
+    pairs: &[KeplerPair],
+    dt: f64,
+) -> Vec<(usize, DVec3, DVec3)> {
+    pairs
+        .iter()
+        .flat_map(|pair| {
+            let (p, q) = (&particles[pair.first], &particles[pair.second]);
+            let total_mass = p.mass + q.mass;
+            let center_velocity = (p.velocity * p.mass + q.velocity * q.mass) / total_mass;
+            let center =
+                (p.position * p.mass + q.position * q.mass) / total_mass + center_velocity * dt;
+            let (relative_position, relative_velocity) = kepler_drift(
+                q.position - p.position,
+                q.velocity - p.velocity,
+                G * total_mass,
+                dt,
+            );
+            let (p_share, q_share) = (q.mass / total_mass, p.mass / total_mass);
+            [
+                (
+                    pair.first,
+                    center - relative_position * p_share,
+                    center_velocity - relative_velocity * p_share,
+                ),
+                (
+                    pair.second,
+                    center + relative_position * q_share,
+                    center_velocity + relative_velocity * q_share,
+                ),
+            ]
+        })
+        .collect()
+}
+
+/// Takes back the members' pull on each other from a velocity update of `dt`, which the
+/// Kepler drift already accounts for. Uses the force sum's own pair term, so only
+/// rounding is left over.
+pub fn remove_mutual_kicks(particles: &mut [Particle], pairs: &[KeplerPair], dt: f64) {
+    let time_g = G * dt;
+    for pair in pairs {
+        let (p, q) = (particles[pair.first], particles[pair.second]);
+        let (_, kick_p) =
+            newtonian_gravity_pair(p.position, q.position, q.mass, G, time_g, EPSILON);
+        let (_, kick_q) =
+            newtonian_gravity_pair(q.position, p.position, p.mass, G, time_g, EPSILON);
+        particles[pair.first].velocity -= kick_p;
+        particles[pair.second].velocity -= kick_q;
+    }
+}
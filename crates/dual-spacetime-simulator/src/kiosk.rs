@@ -0,0 +1,198 @@
+use crate::object_input::ObjectInputType;
+use crate::ui_state::PlacementMode;
+use std::time::{Duration, Instant};
+
+/// Longest camera step one tick may apply, so a stalled frame does not jump the view.
+pub const MAX_KIOSK_CAMERA_STEP_SECONDS: f32 = 0.1;
+
+/// Camera movement scripted for one playlist entry.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct CameraMotion {
+    /// Orbit around the target; positive turns the view to the right (degrees per second).
+    pub yaw_degrees_per_second: f32,
+    /// Zoom toward the target as a fraction of the orbit distance per second; negative
+    /// pulls back.
+    pub zoom_per_second: f32,
+}
+
+/// Camera change for one tick: yaw in radians and zoom as a fraction of the orbit distance.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct CameraStep {
+    pub yaw: f32,
+    pub zoom_fraction: f32,
+}
+
+impl CameraMotion {
+    pub fn step(&self, dt: f32) -> CameraStep {
+        CameraStep {
+            yaw: self.yaw_degrees_per_second.to_radians() * dt,
+            zoom_fraction: self.zoom_per_second * dt,
+        }
+    }
+}
+
+/// One entry of the kiosk playlist: a preset loaded with a hard reset, shown for
+/// `duration` while the camera follows `camera`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct KioskStep {
+    pub placement: PlacementMode,
+    /// Object type generated when `placement` is [`PlacementMode::Manual`].
+    pub object_type: ObjectInputType,
+    pub duration: Duration,
+    pub camera: CameraMotion,
+}
+
+impl KioskStep {
+    /// Name shown in the kiosk caption.
+    pub fn label(&self) -> String {
+        match self.placement {
+            PlacementMode::Manual => self.object_type.to_string(),
+            placement => placement.to_string(),
+        }
+    }
+}
+
+/// The built-in playlist: the placement presets and the more striking generated objects.
+pub fn default_playlist() -> Vec<KioskStep> {
+    let step =
+        |placement, object_type, seconds, yaw_degrees_per_second, zoom_per_second| KioskStep {
+            placement,
+            object_type,
+            duration: Duration::from_secs(seconds),
+            camera: CameraMotion {
+                yaw_degrees_per_second,
+                zoom_per_second,
+            },
+        };
+    vec![
+        step(
+            PlacementMode::Manual,
+            ObjectInputType::SpiralDisk,
+            60,
+            6.0,
+            0.0,
+        ),
+        step(
+            PlacementMode::SolarSystem,
+            ObjectInputType::RandomSphere,
+            45,
+            4.0,
+            0.01,
+        ),
+        step(
+            PlacementMode::Manual,
+            ObjectInputType::RandomSphere,
+            40,
+            -5.0,
+            0.0,
+        ),
+        step(
+            PlacementMode::SatelliteOrbit,
+            ObjectInputType::RandomSphere,
+            40,
+            8.0,
+            0.0,
+        ),
+        step(
+            PlacementMode::Manual,
+            ObjectInputType::KeplerianSystem,
+            40,
+            5.0,
+            -0.005,
+        ),
+    ]
+}
+
+/// What the event loop does on a kiosk tick.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct KioskTick {
+    /// Entry to reset into, when one starts this tick.
+    pub load: Option<KioskStep>,
+    pub camera: CameraStep,
+}
+
+/// Attract mode for exhibitions: cycles through a playlist of presets until the visitor
+/// touches a control. Inactive until started.
+#[derive(Clone, Debug)]
+pub struct Kiosk {
+    playlist: Vec<KioskStep>,
+    /// Current entry and when it was loaded; `None` until the first tick loads it.
+    position: Option<(usize, Option<Instant>)>,
+    last_tick: Option<Instant>,
+}
+
+impl Default for Kiosk {
+    fn default() -> Self {
+        Self::new(default_playlist())
+    }
+}
+
+impl Kiosk {
+    pub fn new(playlist: Vec<KioskStep>) -> Self {
+        Self {
+            playlist,
+            position: None,
+            last_tick: None,
+        }
+    }
+
+    pub fn playlist(&self) -> &[KioskStep] {
+        &self.playlist
+    }
+
+    /// Starts from the first entry on the next tick. Does nothing with an empty playlist.
+    pub fn start(&mut self) {
+        if !self.playlist.is_empty() {
+            self.position = Some((0, None));
+            self.last_tick = None;
+        }
+    }
+
+    /// Returns whether the kiosk was running.
+    pub fn stop(&mut self) -> bool {
+        self.last_tick = None;
+        self.position.take().is_some()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.position.is_some()
+    }
+
+    /// Returns the current entry's index and the entry.
+    pub fn current(&self) -> Option<(usize, KioskStep)> {
+        let (index, _) = self.position?;
+        Some((index, self.playlist[index]))
+    }
+
+    /// Returns how long the current entry still runs.
+    pub fn remaining(&self, now: Instant) -> Option<Duration> {
+        let (index, started) = self.position?;
+        let elapsed = started.map_or(Duration::ZERO, |started| now.duration_since(started));
+        Some(self.playlist[index].duration.saturating_sub(elapsed))
+    }
+
+    /// Loads the first entry after [`Self::start`] and moves to the next one (wrapping) once
+    /// the current entry has run its duration. Returns `None` while inactive.
+    pub fn tick(&mut self, now: Instant) -> Option<KioskTick> {
+        let (index, started) = self.position?;
+        let load = match started {
+            Some(started) if now.duration_since(started) < self.playlist[index].duration => None,
+            Some(_) => Some((index + 1) % self.playlist.len()),
+            None => Some(index),
+        };
+        if let Some(next) = load {
+            self.position = Some((next, Some(now)));
+        }
+        let dt = self.last_tick.map_or(0.0, |last| {
+            now.duration_since(last)
+                .as_secs_f32()
+                .min(MAX_KIOSK_CAMERA_STEP_SECONDS)
+        });
+        self.last_tick = Some(now);
+        let (current, _) = self.position?;
+        Some(KioskTick {
+            load: load.map(|next| self.playlist[next]),
+            camera: self.playlist[current].camera.step(dt),
+        })
+    }
+}
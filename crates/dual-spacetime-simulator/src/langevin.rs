@@ -0,0 +1,96 @@
+use crate::simulation::Particle;
+use glam::DVec3;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand_distr::{Distribution, StandardNormal};
+use rayon::prelude::*;
+
+/// Boltzmann constant in J/K.
+pub const BOLTZMANN: f64 = 1.380649e-23;
+/// Temperature in K used until the user picks another; a solar-mass particle then
+/// jitters at about 1 km/s.
+pub const DEFAULT_NOISE_TEMPERATURE: f64 = 1.5e59;
+/// Damping rate in 1/s used until the user picks another.
+pub const DEFAULT_NOISE_DAMPING_RATE: f64 = 1e-8;
+pub const DEFAULT_NOISE_SEED: u64 = 1;
+
+/// Langevin thermostat: friction `−γ v` balanced by random kicks, so every particle
+/// relaxes toward a Maxwellian with `⟨v²⟩ = 3 k T / m`. The kicks are drawn from a
+/// stream keyed on the seed, the step number, and the particle id, so a run repeats
+/// exactly for the same seed regardless of thread scheduling.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LangevinNoise {
+    /// Bath temperature in K.
+    pub temperature: f64,
+    /// Damping rate γ in 1/s.
+    pub damping_rate: f64,
+    pub seed: u64,
+}
+
+impl Default for LangevinNoise {
+    fn default() -> Self {
+        Self {
+            temperature: DEFAULT_NOISE_TEMPERATURE,
+            damping_rate: DEFAULT_NOISE_DAMPING_RATE,
+            seed: DEFAULT_NOISE_SEED,
+        }
+    }
+}
+
+impl LangevinNoise {
+    /// One-axis thermal speed `√(kT/m)` in m/s of a particle of `mass_kg`.
+    pub fn thermal_speed(&self, mass_kg: f64) -> f64 {
+        (BOLTZMANN * self.temperature / mass_kg).sqrt()
+    }
+
+    /// Velocity retained over one step of `delta_seconds`: `e^(−γ dt)`.
+    pub fn decay(&self, delta_seconds: f64) -> f64 {
+        (-self.damping_rate * delta_seconds).exp()
+    }
+
+    /// Standard deviation of one step's kick for a particle of unit simulation mass at
+    /// world `scale`, in simulation velocity units; divide by `√m` for other masses.
+    /// The exact Ornstein–Uhlenbeck variance `kT/m (1 − e^(−2γ dt))`, with the SI mass
+    /// `m · scale³` and SI speeds divided by `scale`.
+    pub fn kick_amplitude(&self, scale: f64, delta_seconds: f64) -> f64 {
+        let decay = self.decay(delta_seconds);
+        let variance = BOLTZMANN * self.temperature.max(0.0) * (1.0 - decay * decay);
+        (variance / scale.powi(5)).sqrt()
+    }
+
+    /// Seed of the random stream for `particle_id` at `step`.
+    pub fn stream_seed(&self, step: u64, particle_id: u64) -> u64 {
+        let mixed = split_mix(self.seed ^ split_mix(step));
+        split_mix(mixed ^ particle_id)
+    }
+}
+
+/// SplitMix64 finalizer; spreads nearby inputs over unrelated seeds.
+fn split_mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Applies one Langevin step of `delta_seconds` to every particle at world `scale`.
+/// `step` selects the random numbers, so callers pass the frame being advanced.
+pub fn apply_langevin_noise(
+    particles: &mut [Particle],
+    noise: LangevinNoise,
+    scale: f64,
+    delta_seconds: f64,
+    step: u64,
+) {
+    let decay = noise.decay(delta_seconds);
+    let amplitude = noise.kick_amplitude(scale, delta_seconds);
+    particles.par_iter_mut().for_each(|particle| {
+        if particle.mass <= 0.0 {
+            return;
+        }
+        let mut rng = StdRng::seed_from_u64(noise.stream_seed(step, particle.id));
+        let mut gaussian = || -> f64 { StandardNormal.sample(&mut rng) };
+        let kick = DVec3::new(gaussian(), gaussian(), gaussian());
+        particle.velocity = particle.velocity * decay + kick * (amplitude / particle.mass.sqrt());
+    });
+}
@@ -1,75 +1,144 @@
 //! Library crate for `dual-spacetime-simulator` (binary entry in `main.rs`).
 //! Exposes modules for integration tests under `tests/`.
-
+//!
+//! The window, Vulkan renderer and egui panels are behind the default `gui` feature;
+//! without it the crate builds only the simulation and its command-line front end.
+
+pub mod annotations;
+#[cfg(feature = "gui")]
+mod app;
+pub mod axis_labels;
+pub mod batch_runner;
+pub mod block_steps;
+pub mod close_encounter;
+pub mod container;
+pub mod cosmology;
+pub mod crash_report;
+pub mod display_mode;
+pub mod drag;
+pub mod energy_monitor;
+pub mod events;
+pub mod export_writer;
+pub mod fast_multipole;
+#[cfg(feature = "gui")]
+pub mod fonts;
+pub mod force_tiles;
+pub mod frame_pipeline;
+#[cfg(feature = "gui")]
+pub mod gamepad;
+#[cfg(feature = "gui")]
+pub mod gpu_culling;
+#[cfg(feature = "gui")]
+pub mod gpu_depth_sort;
+#[cfg(feature = "gui")]
 pub mod gpu_simulation;
+pub mod grid_alignment;
+pub mod group_finder;
+pub mod help;
+#[cfg(feature = "gui")]
 pub mod integration;
+pub mod kepler_binary;
+pub mod kiosk;
+pub mod langevin;
+pub mod light_cone;
+pub mod live_scaling;
+pub mod maneuver;
+pub mod mass_evolution;
+pub mod mass_profile;
+pub mod memory_budget;
+pub mod metric_expr;
+pub mod metrics;
+pub mod minimap;
+#[cfg(feature = "gui")]
+pub mod motion_blur;
 pub mod object_input;
+pub mod orbit_preview;
+pub mod orbital_elements;
+pub mod palette;
 pub mod particle_snapshot;
 pub mod particle_selection_marker;
+pub mod phase_space;
+#[cfg(feature = "gui")]
 pub mod pipeline;
+pub mod plots;
+pub mod point_budget;
+pub mod power_spectrum;
+pub mod region_selection;
+pub mod relativistic_view;
+#[cfg(feature = "gui")]
+pub mod render_frames;
+pub mod repaint;
+pub mod rest_frame;
+pub mod rotating_frame;
+pub mod run_target;
 pub mod settings;
+pub mod sim_clock;
+pub mod simulate;
 pub mod simulation;
+#[cfg(feature = "gui")]
+pub mod simulation_worker;
 pub mod solar_system_data;
+pub mod speed_tuning;
+pub mod split_view;
+pub mod status_bar;
+pub mod still_image;
+pub mod taskbar;
+pub mod thomas_precession;
+pub mod time_format;
+pub mod toast;
 pub mod trace_follow;
+pub mod trajectory_export;
+pub mod tree_gravity;
+pub mod tutorial;
+#[cfg(feature = "gui")]
 pub mod ui;
+pub mod ui_profile;
 pub mod ui_state;
+#[cfg(feature = "gui")]
 pub mod ui_styles;
-
-use crate::integration::Gui;
-use crate::object_input::{ObjectInput, SolarSystemBuildError, build_solar_system_particles};
-use crate::pipeline::ParticleRenderPipeline;
-use crate::settings::AppSettings;
-use crate::simulation::SimulationManager;
-use crate::ui::{draw_ui, process_pending_particle_delete, process_pending_snapshot_dialog, resolve_trace_particle_for_camera};
-use crate::trace_follow::compute_trace_follow_distance_limits;
-use crate::ui_state::{DragOwner, PlacementMode, SimulationType, UiState};
-use ash::vk;
+pub mod undo_history;
+pub mod verification;
+pub mod view_fit;
+pub mod window_icon;
+pub mod worldline;
+
+use crate::frame_pipeline::FrameMailbox;
+#[cfg(feature = "gui")]
+use crate::frame_pipeline::StagingRing;
+#[cfg(feature = "gui")]
+use crate::gpu_simulation::GpuParticle;
+use crate::simulation::{Particle, SimulationManager};
+use crate::ui_state::SimulationType;
+use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
-use std::sync::{Arc, RwLock};
-use std::time::{Duration, Instant};
-use vulkanvil::{
-    InputState, VulkanBase, apply_camera_mouse_wheel, spacecraft_scene_wheel_allowed,
-    tick_orbit_camera, tick_spacecraft_steer_and_motion_from_anchors,
-    toggle_spacecraft_steer_anchor as toggle_steer_anchor,
-};
-use winit::{
-    application::ApplicationHandler,
-    dpi::PhysicalPosition,
-    error::EventLoopError,
-    event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent},
-    event_loop::{ActiveEventLoop, EventLoop},
-    keyboard::{KeyCode, PhysicalKey},
-    window::Window,
-};
 
-const DOUBLE_CLICK_MILLIS: u64 = 400;
-const DOUBLE_CLICK_DIST: f64 = 25.0;
-const DEFAULT_WINDOW_WIDTH: f32 = 1280.0;
-const DEFAULT_WINDOW_HEIGHT: f32 = 800.0;
+#[cfg(feature = "gui")]
+pub use crate::app::{App, run};
 
 /// Coordinates CPU→GPU particle buffer synchronization between the UI, worker, and render loop.
 const GPU_REMOVE_NONE: usize = usize::MAX;
 /// DST Galaxy: advancing frames between S³-angle culling passes. On the CPU path
 /// this is the retain interval; on the GPU path it is the interval of the stall-free
 /// dead-slot scan (the shader itself marks particles dead every step).
+#[cfg(feature = "gui")]
 const GALAXY_CULL_INTERVAL: u32 = 60;
-/// DST Galaxy, GPU path: minimum dead slots before threshold-gated compaction.
-/// Compacting stalls the GPU pipeline (device-idle wait), so it only pays off once
-/// enough slots can be reclaimed; below this, dead particles stay parked in their
-/// slots (massless and invisible) until the forced interval fires.
-const GALAXY_COMPACT_MIN_DEAD: usize = 64;
-/// DST Galaxy, GPU path: advancing frames between unconditional dead-slot
-/// compactions, so stragglers are reclaimed even when the threshold is never met.
-const GALAXY_COMPACT_INTERVAL: u32 = 10_000;
 
 #[derive(Clone)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
 pub(crate) struct GpuParticleSync {
     upload_pending: Arc<AtomicBool>,
     append_pending: Arc<AtomicBool>,
     remove_index: Arc<AtomicUsize>,
     advance_steps: Arc<AtomicU32>,
+    /// CPU pipelined stepping: finished frames waiting for upload.
+    frames: FrameMailbox,
+    /// CPU pipelined stepping: finished frames already in the GPU layout, in mapped
+    /// staging memory. `frames` takes a frame only when no staging slot can.
+    #[cfg(feature = "gui")]
+    staging: StagingRing<GpuParticle>,
 }
 
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
 impl GpuParticleSync {
     fn new(initial_upload: bool) -> Self {
         Self {
@@ -77,6 +146,9 @@ impl GpuParticleSync {
             append_pending: Arc::new(AtomicBool::new(false)),
             remove_index: Arc::new(AtomicUsize::new(GPU_REMOVE_NONE)),
             advance_steps: Arc::new(AtomicU32::new(0)),
+            frames: FrameMailbox::new(),
+            #[cfg(feature = "gui")]
+            staging: StagingRing::new(),
         }
     }
 
@@ -90,6 +162,7 @@ impl GpuParticleSync {
 
     fn request_full_upload(&self) {
         self.reset_advance_steps();
+        self.clear_frames();
         self.upload_pending.store(true, Ordering::Release);
         self.append_pending.store(false, Ordering::Release);
         self.clear_pending_remove();
@@ -110,6 +183,7 @@ impl GpuParticleSync {
 
     fn request_cpu_mode_upload(&self) {
         self.reset_advance_steps();
+        self.clear_frames();
         self.upload_pending.store(true, Ordering::Release);
     }
 
@@ -148,1139 +222,42 @@ impl GpuParticleSync {
         self.advance_steps.fetch_add(1, Ordering::Release);
     }
 
-    fn clear_advance_steps(&self) {
-        self.advance_steps.store(0, Ordering::Release);
-    }
-}
-
-/// Run the desktop application (window + Vulkan + UI loop).
-pub fn run() -> Result<(), EventLoopError> {
-    let event_loop = EventLoop::new()?;
-    let mut app = App::default();
-    spawn_simulation_worker(
-        Arc::clone(&app.ui_state),
-        Arc::clone(&app.simulation_manager),
-        Arc::clone(&app.need_redraw),
-        Arc::clone(&app.skip_redraw),
-        app.gpu_particle_sync.clone(),
-    );
-    event_loop.run_app(&mut app)
-}
-
-/// Spawns a background thread that advances simulation state and schedules redraws.
-pub(crate) fn spawn_simulation_worker(
-    ui_state_clone: Arc<RwLock<UiState>>,
-    simulation_manager: Arc<RwLock<SimulationManager>>,
-    need_redraw: Arc<RwLock<bool>>,
-    skip_redraw: Arc<RwLock<u32>>,
-    gpu_particle_sync: GpuParticleSync,
-) {
-    let thread_pool = rayon::ThreadPoolBuilder::new()
-        .num_threads(num_cpus::get())
-        .build()
-        .unwrap();
-    std::thread::spawn(move || {
-        let mut last_advance = Instant::now();
-        let mut last_fps = Instant::now();
-        let mut prev_frame: i64 = 1;
-        let mut cpu_cull_counter: u32 = 0;
-        loop {
-            {
-                let ui_state = ui_state_clone.read().unwrap();
-                let is_reset_requested = ui_state.is_reset_requested;
-                let is_add_particles_requested = ui_state.is_add_particles_requested;
-                if is_reset_requested || is_add_particles_requested {
-                    let selected_object_input = ui_state.object_input.clone();
-                    let simulation_type = ui_state.active_simulation_type();
-                    let skip = ui_state.skip;
-                    let add_particle_count = ui_state.add_particle_count;
-                    let scale = ui_state.scale;
-                    let base_scale = ui_state.base_scale;
-                    let add_center = ui_state.add_center;
-                    let max_particle_count = ui_state.max_particle_count;
-                    let uses_gpu = ui_state.uses_gpu_simulation();
-                    let reset_repopulates = ui_state.reset_repopulates_particles();
-                    let reset_object_input = ui_state.build_reset_object_input();
-                    let placement_mode = ui_state.placement_mode;
-                    let reset_log_abort = Arc::clone(&ui_state.reset_log.abort_requested);
-                    drop(ui_state);
-                    if is_reset_requested {
-                        let mut reset_applied = false;
-                        if reset_repopulates && placement_mode == PlacementMode::SolarSystem {
-                            if let ObjectInput::SolarSystem {
-                                scale,
-                                start_year,
-                                start_month,
-                                start_day,
-                                start_hour,
-                            } = reset_object_input
-                            {
-                                let ui_state_for_log = Arc::clone(&ui_state_clone);
-                                let need_redraw_for_log = Arc::clone(&need_redraw);
-                                let log = move |line: &str| {
-                                    {
-                                        let mut ui_state = ui_state_for_log.write().unwrap();
-                                        ui_state.append_reset_log(line);
-                                    }
-                                    need_redraw_for_log.write().unwrap().clone_from(&true);
-                                };
-                                match build_solar_system_particles(
-                                    scale,
-                                    start_year,
-                                    start_month,
-                                    start_day,
-                                    start_hour,
-                                    &log,
-                                    reset_log_abort.as_ref(),
-                                ) {
-                                    Ok(particles) => {
-                                        simulation_manager.read().unwrap().reset_from_particles(
-                                            particles,
-                                            simulation_type,
-                                            base_scale,
-                                        );
-                                        reset_applied = true;
-                                    }
-                                    Err(SolarSystemBuildError::Aborted) => {
-                                        let mut ui_state = ui_state_clone.write().unwrap();
-                                        ui_state.append_reset_log("Aborted.");
-                                        ui_state.finish_reset_log();
-                                        ui_state.is_reset_requested = false;
-                                        drop(ui_state);
-                                        need_redraw.write().unwrap().clone_from(&true);
-                                        continue;
-                                    }
-                                }
-                            }
-                        } else if reset_repopulates {
-                            simulation_manager.read().unwrap().reset(
-                                reset_object_input,
-                                simulation_type,
-                                add_particle_count,
-                                base_scale,
-                            );
-                            reset_applied = true;
-                        } else {
-                            simulation_manager
-                                .read()
-                                .unwrap()
-                                .clear(simulation_type, scale);
-                            reset_applied = true;
-                        }
-                        let mut ui_state = ui_state_clone.write().unwrap();
-                        if reset_applied {
-                            ui_state.frame = 1;
-                            ui_state.simulation_time = 0.0;
-                            ui_state.clear_selected_particle();
-                        }
-                        ui_state.is_reset_requested = false;
-                        if placement_mode == PlacementMode::SolarSystem {
-                            ui_state.finish_reset_log();
-                        }
-                        drop(ui_state);
-                        if reset_applied {
-                            gpu_particle_sync.request_full_upload();
-                        }
-                        need_redraw.write().unwrap().clone_from(&true);
-                        skip_redraw.write().unwrap().clone_from(&skip);
-                        continue;
-                    }
-                    simulation_manager.write().unwrap().append_particles(
-                        selected_object_input,
-                        simulation_type,
-                        add_particle_count,
-                        scale,
-                        add_center,
-                        base_scale,
-                        max_particle_count,
-                    );
-                    let mut ui_state = ui_state_clone.write().unwrap();
-                    ui_state.is_add_particles_requested = false;
-                    drop(ui_state);
-                    if uses_gpu {
-                        gpu_particle_sync.request_append_preserving();
-                    } else {
-                        gpu_particle_sync.request_cpu_mode_upload();
-                    }
-                    need_redraw.write().unwrap().clone_from(&true);
-                    skip_redraw.write().unwrap().clone_from(&skip);
-                    continue;
-                }
-            }
-            if *need_redraw.read().unwrap() {
-                std::thread::sleep(Duration::from_millis(16));
-                continue;
-            }
-            let ui_state = ui_state_clone.read().unwrap();
-            let is_running = ui_state.is_running;
-            let max_fps = ui_state.max_fps;
-            let max_fps_unlimited = ui_state.max_fps_unlimited;
-            let time_per_frame = ui_state.time_per_frame;
-            let skip = ui_state.skip;
-            let uses_gpu = ui_state.uses_gpu_simulation();
-            let simulation_type = ui_state.active_simulation_type();
-            let galaxy_cull_enabled = ui_state.galaxy_cull_enabled;
-            let galaxy_cull_max_angle = ui_state.galaxy_cull_max_angle;
-            drop(ui_state);
-            let now = Instant::now();
-            let dt = now.duration_since(last_fps).as_secs_f64();
-            if dt >= 1.0 {
-                let mut ui_state = ui_state_clone.write().unwrap();
-                ui_state.fps = if ui_state.frame - prev_frame > 0 {
-                    ui_state.frame - prev_frame
-                } else {
-                    0
-                };
-                prev_frame = ui_state.frame;
-                drop(ui_state);
-                last_fps = now;
-            }
-            if !is_running {
-                std::thread::sleep(Duration::from_millis(16));
-                continue;
-            }
-            let dt = now.duration_since(last_advance).as_secs_f64();
-            if !max_fps_unlimited {
-                let target_fps = max_fps as f64;
-                if dt < 1.0 / target_fps {
-                    continue;
-                }
-            }
-            if uses_gpu {
-                gpu_particle_sync.fetch_add_advance_step();
-            } else {
-                thread_pool.install(|| {
-                    simulation_manager.read().unwrap().advance(time_per_frame);
-                });
-                if galaxy_cull_enabled && simulation_type == SimulationType::DstGalaxy {
-                    cpu_cull_counter += 1;
-                    if cpu_cull_counter >= GALAXY_CULL_INTERVAL {
-                        cpu_cull_counter = 0;
-                        let removed = simulation_manager
-                            .read()
-                            .unwrap()
-                            .cull_galaxy_by_angle(galaxy_cull_max_angle);
-                        if !removed.is_empty() {
-                            ui_state_clone
-                                .write()
-                                .unwrap()
-                                .adjust_selection_after_removal(&removed);
-                        }
-                    }
-                }
-            }
-            if *skip_redraw.read().unwrap() < 1 {
-                let mut sr = skip_redraw.write().unwrap();
-                *sr = skip;
-                need_redraw.write().unwrap().clone_from(&true);
-            } else {
-                let mut sr = skip_redraw.write().unwrap();
-                *sr -= 1;
-            }
-            last_advance = now;
-            let mut ui_state = ui_state_clone.write().unwrap();
-            ui_state.frame += 1;
-            ui_state.simulation_time += time_per_frame;
-        }
-    });
-}
-
-/// Builds the window title from crate name and version metadata.
-fn generate_window_title() -> String {
-    let package_name = env!("CARGO_PKG_NAME");
-    let package_version = env!("CARGO_PKG_VERSION");
-    format!("{} v{}", package_name, package_version)
-}
-
-pub struct App {
-    // Drop order matters: gui and pipeline must be dropped before vulkan_base
-    gui: Option<Gui>,
-    render_pipeline: Option<ParticleRenderPipeline>,
-    vulkan_base: Option<VulkanBase>,
-    window: Option<Arc<Window>>,
-    ui_state: Arc<RwLock<UiState>>,
-    simulation_manager: Arc<RwLock<SimulationManager>>,
-    need_redraw: Arc<RwLock<bool>>,
-    skip_redraw: Arc<RwLock<u32>>,
-    gpu_particle_sync: GpuParticleSync,
-    mouse_left_down: bool,
-    mouse_right_down: bool,
-    mouse_middle_down: bool,
-    last_cursor_position: Option<(f64, f64)>,
-    last_right_click_time: Option<Instant>,
-    last_right_click_pos: Option<(f64, f64)>,
-    settings: AppSettings,
-    drag_owner: DragOwner,
-    input: InputState,
-    last_camera_tick: Option<Instant>,
-    last_lock_camera_up: Option<bool>,
-    /// Accumulated GPU advance steps since the last DST Galaxy dead-slot scan.
-    gpu_cull_accumulated_steps: u32,
-    /// Accumulated GPU advance steps since the last DST Galaxy compaction; drives
-    /// the unconditional garbage-collect interval.
-    gpu_forced_compact_steps: u32,
-}
-
-impl Drop for App {
-    /// Waits for all pending GPU work to finish before dropping GUI and pipeline resources.
-    ///
-    /// The last frame's command buffer may still be executing on the GPU when the event loop
-    /// exits (e.g. via CloseRequested or request_exit). The gui (egui renderer) and
-    /// render_pipeline own Vulkan resources (vertex buffers, textures, descriptor sets,
-    /// pipelines, etc.) that are referenced by in-flight command buffers.
-    ///
-    /// Destroying them before the work completes violates Vulkan's rules and commonly
-    /// results in ERROR_DEVICE_LOST (visible in the Drop impls of ParticleRenderPipeline
-    /// and VulkanBase).
-    ///
-    /// We perform the wait here, while all resources are still alive. The waits inside
-    /// the individual drops will then be fast and succeed.
-    fn drop(&mut self) {
-        if let Some(vb) = &self.vulkan_base {
-            // Ignore error: on shutdown we just want to be as clean as possible.
-            // Real device loss from a bad submit would have been observable earlier too.
-            let _ = unsafe { vb.device.device_wait_idle() };
-        }
-    }
-}
-
-impl Default for App {
-    /// Creates application state with loaded settings and default runtime resources.
-    fn default() -> Self {
-        let settings = AppSettings::load();
-        let mut ui_state = UiState::default();
-        ui_state.apply_settings(&settings);
-        Self {
-            window: None,
-            vulkan_base: None,
-            render_pipeline: None,
-            gui: None,
-            ui_state: Arc::new(RwLock::new(ui_state)),
-            simulation_manager: Arc::new(RwLock::new(SimulationManager::default())),
-            need_redraw: Arc::new(RwLock::new(true)),
-            skip_redraw: Arc::new(RwLock::new(0)),
-            gpu_particle_sync: GpuParticleSync::new(true),
-            mouse_left_down: false,
-            mouse_right_down: false,
-            mouse_middle_down: false,
-            last_cursor_position: None,
-            last_right_click_time: None,
-            last_right_click_pos: None,
-            settings,
-            drag_owner: DragOwner::None,
-            input: InputState::default(),
-            last_camera_tick: None,
-            last_lock_camera_up: None,
-            gpu_cull_accumulated_steps: 0,
-            gpu_forced_compact_steps: 0,
-        }
-    }
-}
-
-impl ApplicationHandler for App {
-    /// Creates window and graphics resources when the app is resumed by the event loop.
-    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
-        let ui_state = self.ui_state.write().unwrap();
-
-        let window_attrs = Window::default_attributes()
-            .with_title(generate_window_title())
-            .with_inner_size(winit::dpi::LogicalSize::new(
-                DEFAULT_WINDOW_WIDTH,
-                DEFAULT_WINDOW_HEIGHT,
-            ))
-            .with_min_inner_size(winit::dpi::LogicalSize::new(
-                ui_state.min_window_width,
-                ui_state.min_window_height,
-            ));
-        let window = Arc::new(event_loop.create_window(window_attrs).unwrap());
-
-        if self.settings.start_maximized {
-            window.set_maximized(true);
-        }
-
-        let vulkan_base = VulkanBase::new(
-            &window,
-            self.settings.mailbox_present_mode,
-            c"DualSpacetimeSimulator",
-            vk::make_api_version(0, 0, 2, 0),
-        );
-        let render_pipeline = ParticleRenderPipeline::new(&vulkan_base);
-
-        let gui = Gui::new(
-            event_loop,
-            &window,
-            &vulkan_base.instance,
-            vulkan_base.physical_device,
-            vulkan_base.device.clone(),
-            vulkan_base.graphics_queue,
-            vulkan_base.command_pool,
-            render_pipeline.render_pass(),
-            vulkan_base.swapchain_format,
-        );
-
-        self.window = Some(window);
-        self.render_pipeline = Some(render_pipeline);
-        self.vulkan_base = Some(vulkan_base);
-        self.gui = Some(gui);
-
-        let object_input = ObjectInput::default();
-        let add_particle_count = ui_state.add_particle_count;
-        let scale = ui_state.scale;
-        let sim_type = ui_state.active_simulation_type();
-        self.simulation_manager.write().unwrap().reset(
-            object_input,
-            sim_type,
-            add_particle_count,
-            scale,
-        );
-        self.skip_redraw.write().unwrap().clone_from(&ui_state.skip);
-        self.gpu_particle_sync.clear_advance_steps();
+    fn has_pending_advance_steps(&self) -> bool {
+        self.advance_steps.load(Ordering::Acquire) > 0
     }
 
-    /// Handles window, input, rendering, and camera events for each platform event.
-    fn window_event(
-        &mut self,
-        event_loop: &ActiveEventLoop,
-        _window_id: winit::window::WindowId,
-        event: WindowEvent,
-    ) {
-        let Some(window) = self.window.as_ref() else {
-            return;
-        };
-        let Some(vb) = self.vulkan_base.as_mut() else {
-            return;
-        };
-        let gui = self.gui.as_mut().unwrap();
-        let Some(pipeline) = self.render_pipeline.as_mut() else {
-            return;
-        };
-
-        {
-            let mut ui_state = self.ui_state.write().unwrap();
-            if ui_state.request_exit {
-                ui_state.request_exit = false;
-                event_loop.exit();
-                return;
-            }
-        }
-
-        let lock_camera_up = {
-            let ui_state = self.ui_state.read().unwrap();
-            ui_state.lock_camera_up
-        };
-        pipeline.set_lock_camera_up(lock_camera_up);
-
-        match &event {
-            WindowEvent::KeyboardInput { event, .. } => {
-                if let PhysicalKey::Code(code) = event.physical_key {
-                    self.input.key_event(code, event.state);
-                    // Pause/Escape shortcuts stay reachable even when heavy draw-skipping
-                    // makes the egui controls hard to click.
-                    if code == KeyCode::Escape && event.state == ElementState::Pressed {
-                        let cleared_anchor = self.ui_state.write().unwrap().apply_escape_shortcut();
-                        if cleared_anchor {
-                            window.request_redraw();
-                        }
-                    } else if code == KeyCode::Pause
-                        && event.state == ElementState::Pressed
-                        && !event.repeat
-                    {
-                        self.ui_state.write().unwrap().is_running ^= true;
-                    }
-                }
-            }
-            WindowEvent::Resized(size) => {
-                if size.width > 0 && size.height > 0 {
-                    vb.recreate_swapchain(window);
-                    pipeline.recreate_framebuffers(vb);
-                }
-            }
-            WindowEvent::ScaleFactorChanged { .. } => {
-                vb.recreate_swapchain(window);
-                pipeline.recreate_framebuffers(vb);
-            }
-            WindowEvent::CloseRequested => {
-                event_loop.exit();
-            }
-            WindowEvent::RedrawRequested => {
-                gui.immediate_ui(window, |gui| {
-                    let ctx = gui.context();
-                    draw_ui(
-                        &self.ui_state,
-                        &self.simulation_manager,
-                        Some(pipeline),
-                        &mut self.settings,
-                        &ctx,
-                    );
-                });
-                let desired_mailbox_present_mode = {
-                    let ui_state = self.ui_state.read().unwrap();
-                    pipeline.sync_add_center_marker(&ui_state);
-                    pipeline.sync_selection_marker(&ui_state);
-                    ui_state.mailbox_present_mode
-                };
-                if vb.mailbox_present_mode != desired_mailbox_present_mode {
-                    vb.mailbox_present_mode = desired_mailbox_present_mode;
-                    vb.recreate_swapchain(window);
-                    pipeline.recreate_framebuffers(vb);
-                }
-                gui.prepare_frame(window);
-
-                vb.wait_for_fence();
-
-                let image_index = match vb.acquire_next_image() {
-                    Ok((idx, _)) => idx,
-                    Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => {
-                        vb.recreate_swapchain(window);
-                        pipeline.recreate_framebuffers(vb);
-                        return;
-                    }
-                    Err(e) => panic!("Failed to acquire swapchain image: {:?}", e),
-                };
-
-                vb.reset_fence();
-
-                let cb = vb.current_command_buffer();
-                let begin_ci = vk::CommandBufferBeginInfo::default()
-                    .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT);
-                unsafe {
-                    vb.device
-                        .reset_command_buffer(cb, vk::CommandBufferResetFlags::empty())
-                        .unwrap();
-                    vb.device.begin_command_buffer(cb, &begin_ci).unwrap();
-                }
-
-                let ui_state = self.ui_state.read().unwrap();
-                let scale = ui_state.scale_gauge;
-                let link_point_size_to_scale = ui_state.link_point_size_to_scale;
-                let show_grid = ui_state.show_grid;
-                let particle_display_mode = ui_state.particle_display_mode;
-                let uses_gpu = ui_state.uses_gpu_simulation();
-                let time_per_frame = ui_state.time_per_frame;
-                let simulation_type = ui_state.active_simulation_type();
-                let sim_scale = ui_state.scale;
-                let galaxy_cull_enabled = ui_state.galaxy_cull_enabled;
-                let galaxy_cull_max_angle = ui_state.galaxy_cull_max_angle;
-                drop(ui_state);
-
-                let pending_steps = if uses_gpu {
-                    self.gpu_particle_sync.take_advance_steps()
-                } else {
-                    0
-                };
-                // The compute shader marks particles beyond the S³ cull angle dead
-                // in-place (mass 0, invisible) with no CPU-GPU sync. Slot reclamation
-                // below is the only stalling step, so it runs rarely: a periodic
-                // stall-free scan of the mapped SSBO triggers compaction once enough
-                // dead slots accumulated, and a long unconditional interval sweeps up
-                // stragglers that never reach the threshold.
-                if uses_gpu && simulation_type == SimulationType::DstGalaxy && pending_steps > 0
-                {
-                    self.gpu_cull_accumulated_steps += pending_steps;
-                    self.gpu_forced_compact_steps += pending_steps;
-                    if self.gpu_cull_accumulated_steps >= GALAXY_CULL_INTERVAL {
-                        self.gpu_cull_accumulated_steps = 0;
-                        let forced = self.gpu_forced_compact_steps >= GALAXY_COMPACT_INTERVAL;
-                        let dead = pipeline.count_dead_galaxy_particles();
-                        let total = pipeline.gpu_particle_count() as usize;
-                        let threshold_hit =
-                            dead >= GALAXY_COMPACT_MIN_DEAD && dead * 8 >= total;
-                        if threshold_hit || (forced && dead > 0) {
-                            let removed = pipeline.compact_dead_galaxy_particles();
-                            if !removed.is_empty() {
-                                // Mirror onto the CPU list so index correspondence holds
-                                // and culled particles are not resurrected on the next add.
-                                self.simulation_manager
-                                    .write()
-                                    .unwrap()
-                                    .remove_particles_at_sorted(&removed);
-                                self.ui_state
-                                    .write()
-                                    .unwrap()
-                                    .adjust_selection_after_removal(&removed);
-                            }
-                        }
-                        // Any compaction (or a forced pass finding nothing) restarts
-                        // the forced-GC clock.
-                        if threshold_hit || forced {
-                            self.gpu_forced_compact_steps = 0;
-                        }
-                    }
-                }
-                if pending_steps > 0 {
-                    let cull_max_angle = if galaxy_cull_enabled
-                        && simulation_type == SimulationType::DstGalaxy
-                    {
-                        galaxy_cull_max_angle as f32
-                    } else {
-                        0.0
-                    };
-                    pipeline.record_gpu_advance(
-                        cb,
-                        simulation_type,
-                        time_per_frame,
-                        sim_scale,
-                        pending_steps,
-                        cull_max_angle,
-                    );
-                }
-
-                pipeline.render(
-                    cb,
-                    image_index as usize,
-                    vb.swapchain_extent,
-                    gui,
-                    scale,
-                    link_point_size_to_scale,
-                    show_grid,
-                    particle_display_mode,
-                );
-
-                unsafe {
-                    vb.device.end_command_buffer(cb).unwrap();
-                }
-
-                match vb.submit_and_present(image_index) {
-                    Ok(true) | Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => {
-                        vb.recreate_swapchain(window);
-                        pipeline.recreate_framebuffers(vb);
-                    }
-                    Ok(false) => {}
-                    Err(e) => panic!("Failed to present: {:?}", e),
-                }
-
-                gui.finish_frame();
-                vb.advance_frame();
-                if uses_gpu {
-                    self.need_redraw.write().unwrap().clone_from(&false);
-                }
-            }
-            _ => (),
-        }
-
-        let window_clone = window.clone();
-        let ui_consumed = gui.update(&window_clone, &event);
-        let ui_wants_pointer = gui.pointer_wants_input();
-
-        match &event {
-            WindowEvent::MouseInput { state, button, .. } => {
-                let pressed = *state == ElementState::Pressed;
-                if pressed {
-                    if ui_wants_pointer || ui_consumed {
-                        self.drag_owner = DragOwner::Ui;
-                        self.clear_mouse_drag_flags();
-                    } else {
-                        match button {
-                            MouseButton::Left => {
-                                self.drag_owner = DragOwner::PendingSceneLeft;
-                                if !self.ui_state.read().unwrap().lock_camera_up {
-                                    if let Some(pos) = self.last_cursor_position {
-                                        Self::toggle_spacecraft_steer_anchor(
-                                            window,
-                                            &self.ui_state,
-                                            pos,
-                                        );
-                                    }
-                                }
-                                self.left_button(state);
-                            }
-                            MouseButton::Right => {
-                                self.drag_owner = DragOwner::PendingSceneRight;
-                                if !self.ui_state.read().unwrap().lock_camera_up {
-                                    if let Some(pos) = self.last_cursor_position {
-                                        Self::sync_spacecraft_yaw_steer_anchor(
-                                            window,
-                                            &self.ui_state,
-                                            Some(pos),
-                                        );
-                                    }
-                                }
-                                self.right_button(state);
-                            }
-                            MouseButton::Middle => {
-                                self.drag_owner = DragOwner::PendingSceneMiddle;
-                                self.middle_button(state);
-                            }
-                            _ => {}
-                        }
-                    }
-                } else {
-                    match button {
-                        MouseButton::Left => {
-                            let lock_camera_up = self.ui_state.read().unwrap().lock_camera_up;
-                            let is_scene_click =
-                                matches!(self.drag_owner, DragOwner::PendingSceneLeft);
-                            self.left_button(state);
-                            if is_scene_click && lock_camera_up {
-                                self.try_pick_particle();
-                            }
-                        }
-                        MouseButton::Right => {
-                            let lock_camera_up = self.ui_state.read().unwrap().lock_camera_up;
-                            if !lock_camera_up {
-                                Self::sync_spacecraft_yaw_steer_anchor(
-                                    window,
-                                    &self.ui_state,
-                                    None,
-                                );
-                            }
-                            self.right_button(state);
-                        }
-                        MouseButton::Middle => {
-                            let is_scene_click =
-                                matches!(self.drag_owner, DragOwner::PendingSceneMiddle);
-                            self.middle_button(state);
-                            if is_scene_click {
-                                let mut ui = self.ui_state.write().unwrap();
-                                ui.lock_camera_up = !ui.lock_camera_up;
-                            }
-                        }
-                        _ => {}
-                    }
-                    self.drag_owner = DragOwner::None;
-                }
-            }
-            WindowEvent::CursorMoved { position, .. } => {
-                let (x, y) = (position.x, position.y);
-                let ui_blocks = ui_wants_pointer || ui_consumed;
-                if let Some(new_owner) = self.drag_owner.promote_from_pending(ui_blocks) {
-                    if new_owner == DragOwner::Ui {
-                        self.mouse_left_down = false;
-                        self.mouse_right_down = false;
-                        self.mouse_middle_down = false;
-                    }
-                    self.drag_owner = new_owner;
-                }
-                let lock_camera_up = self.ui_state.read().unwrap().lock_camera_up;
-                if let Some((lx, ly)) = self.last_cursor_position {
-                    match self.drag_owner {
-                        DragOwner::SceneLeft if lock_camera_up => {
-                            pipeline.revolve_camera(x - lx, y - ly);
-                        }
-                        DragOwner::SceneLeft => {}
-                        DragOwner::SceneRight if lock_camera_up => {
-                            pipeline.look_around(x - lx, y - ly);
-                        }
-                        DragOwner::SceneMiddle if lock_camera_up => {
-                            let window_size = window.inner_size();
-                            let center_x = window_size.width as f64 / 2.0;
-                            let center_y = window_size.height as f64 / 2.0;
-                            pipeline.rotate_camera(x, lx, y, ly, center_x, center_y);
-                        }
-                        DragOwner::None
-                        | DragOwner::Ui
-                        | DragOwner::SceneMiddle
-                        | DragOwner::SceneRight
-                        | DragOwner::PendingSceneLeft
-                        | DragOwner::PendingSceneRight
-                        | DragOwner::PendingSceneMiddle => {}
-                    }
-                }
-                self.last_cursor_position = Some((x, y));
-            }
-            WindowEvent::MouseWheel { delta, .. } => {
-                let (lock_camera_up, steer_anchor_active, trace_may_be_active) = {
-                    let ui = self.ui_state.read().unwrap();
-                    (
-                        ui.lock_camera_up,
-                        ui.spacecraft_steer_anchor.is_some(),
-                        ui.is_trace_enabled
-                            && ui.is_particle_info_panel_open
-                            && ui.selected_particle.is_some(),
-                    )
-                };
-                let trace_active = trace_may_be_active
-                    && resolve_trace_particle_for_camera(
-                        &self.ui_state,
-                        &self.simulation_manager,
-                        Some(&*pipeline),
-                    )
-                    .1;
-                if spacecraft_scene_wheel_allowed(
-                    lock_camera_up,
-                    steer_anchor_active,
-                    ui_wants_pointer || ui_consumed,
-                ) {
-                    match delta {
-                        MouseScrollDelta::LineDelta(_, y) => {
-                            apply_camera_mouse_wheel(
-                                pipeline.camera_mut(),
-                                lock_camera_up,
-                                *y,
-                                trace_active,
-                            );
-                        }
-                        MouseScrollDelta::PixelDelta(PhysicalPosition { y, .. }) => {
-                            apply_camera_mouse_wheel(
-                                pipeline.camera_mut(),
-                                lock_camera_up,
-                                *y as f32,
-                                trace_active,
-                            );
-                        }
-                    }
-                }
-            }
-            WindowEvent::KeyboardInput { event, .. } => {
-                if let PhysicalKey::Code(key) = event.physical_key
-                    && event.state == ElementState::Pressed
-                    && !event.repeat
-                    && !gui.keyboard_wants_input()
-                {
-                    match key {
-                        KeyCode::Home => pipeline.center_target_on_origin(),
-                        KeyCode::End => {
-                            let mut ui = self.ui_state.write().unwrap();
-                            ui.lock_camera_up = !ui.lock_camera_up;
-                        }
-                        _ => {}
-                    }
-                }
-            }
-            _ => {}
-        }
+    fn clear_advance_steps(&self) {
+        self.advance_steps.store(0, Ordering::Release);
     }
 
-    /// Performs per-frame updates before the event loop waits for new events.
-    fn about_to_wait(&mut self, _event_loop: &ActiveEventLoop) {
-        if let Some(window) = self.window.as_ref() {
-            process_pending_snapshot_dialog(
-                window,
-                &self.ui_state,
-                &self.simulation_manager,
-                self.render_pipeline.as_ref(),
-                &self.need_redraw,
-            );
-            process_pending_particle_delete(
-                &self.ui_state,
-                &self.simulation_manager,
-                &self.gpu_particle_sync,
-                &self.need_redraw,
-            );
-            window.request_redraw();
-        }
-        self.apply_pending_particle_buffer_reload();
-        let lock_camera_up = self.ui_state.read().unwrap().lock_camera_up;
-        let keyboard_blocked = self
-            .gui
-            .as_ref()
-            .is_some_and(|gui| gui.keyboard_wants_input());
-        if self.last_lock_camera_up != Some(lock_camera_up) {
-            self.last_camera_tick = None;
-            self.last_lock_camera_up = Some(lock_camera_up);
-            if let Some(window) = self.window.as_ref() {
-                Self::clear_spacecraft_steer_anchors(window, &self.ui_state);
-            } else {
-                let mut uis = self.ui_state.write().unwrap();
-                uis.spacecraft_steer_anchor = None;
-                uis.spacecraft_yaw_steer_anchor = None;
-            }
-        }
-        let (trace_particle, suppress_space_shift, trace_visual_scale) =
-            resolve_trace_particle_for_camera(
-                &self.ui_state,
-                &self.simulation_manager,
-                self.render_pipeline.as_ref(),
-            );
-        let trace_distance_limits = if suppress_space_shift {
-            let uis = self.ui_state.read().unwrap();
-            Some(compute_trace_follow_distance_limits(
-                trace_visual_scale,
-                uis.link_point_size_to_scale,
-                uis.particle_display_mode,
-            ))
-        } else {
-            None
-        };
-        if let Some(pipeline) = self.render_pipeline.as_mut() {
-            pipeline.set_lock_camera_up(lock_camera_up);
-            if let Some((min, max)) = trace_distance_limits {
-                pipeline
-                    .camera_mut()
-                    .set_trace_follow_distance_limits(min, max);
-                pipeline.camera_mut().reclamp_trace_follow_distance();
-                pipeline.camera_mut().begin_trace_follow();
-            } else {
-                pipeline.camera_mut().end_trace_follow();
-            }
-            if !lock_camera_up {
-                let now = Instant::now();
-                let dt = self
-                    .last_camera_tick
-                    .map(|t| now.duration_since(t).as_secs_f32())
-                    .unwrap_or(0.0);
-                self.last_camera_tick = Some(now);
-                let (yaw_anchor, plus_anchor) = {
-                    let uis = self.ui_state.read().unwrap();
-                    (uis.spacecraft_yaw_steer_anchor, uis.spacecraft_steer_anchor)
-                };
-                tick_spacecraft_steer_and_motion_from_anchors(
-                    pipeline.camera_mut(),
-                    yaw_anchor,
-                    plus_anchor,
-                    self.last_cursor_position,
-                    dt,
-                    &self.input,
-                    keyboard_blocked,
-                    suppress_space_shift,
-                );
-            }
-            tick_orbit_camera(
-                pipeline.camera_mut(),
-                &self.input,
-                lock_camera_up,
-                keyboard_blocked,
-                suppress_space_shift,
-            );
-            if let Some(particle) = trace_particle {
-                pipeline.trace_selected_particle(
-                    particle.position,
-                    particle.velocity,
-                    trace_visual_scale,
-                );
-            }
-        }
-        if *self.need_redraw.read().unwrap() == false && !self.gpu_particle_sync.has_pending_sync()
-        {
+    /// Hands the current state of `manager` to the render loop, written straight into a
+    /// staging slot when one is free and large enough.
+    fn publish_frame(&self, manager: &SimulationManager, simulation_type: SimulationType) {
+        #[cfg(feature = "gui")]
+        if manager.write_gpu_frame(&self.staging, simulation_type) {
             return;
         }
-
-        let uses_gpu = {
-            let uis = self.ui_state.read().unwrap();
-            let uses_gpu = uis.uses_gpu_simulation();
-            if let Some(pipeline) = self.render_pipeline.as_mut() {
-                pipeline.set_use_gpu_sim(uses_gpu);
-            }
-            uses_gpu
-        };
-
-        if self.gpu_particle_sync.take_upload_pending() {
-            if let (Some(pipeline), Ok(manager)) = (
-                self.render_pipeline.as_mut(),
-                self.simulation_manager.try_read(),
-            ) {
-                let simulation_type = {
-                    let uis = self.ui_state.read().unwrap();
-                    uis.active_simulation_type()
-                };
-                pipeline.upload_particles(&manager.particles(), simulation_type);
-            }
-        }
-
-        // GPU-mode "Add": preserve simulated positions of existing particles by
-        // reading back current GPU state before re-uploading the combined buffer.
-        // Cleared only after a successful upload to avoid dropping the request.
-        if self.gpu_particle_sync.append_pending() {
-            let (particles, simulation_type, scale) = {
-                let uis = self.ui_state.read().unwrap();
-                (
-                    self.simulation_manager.read().unwrap().particles(),
-                    uis.active_simulation_type(),
-                    uis.scale,
-                )
-            };
-            if let Some(pipeline) = self.render_pipeline.as_mut() {
-                pipeline.add_particles_preserving_simulated(&particles, simulation_type, scale);
-            }
-            self.gpu_particle_sync.clear_append_pending();
-        }
-
-        if let Some(index) = self.gpu_particle_sync.take_remove_index() {
-            if let Some(pipeline) = self.render_pipeline.as_mut() {
-                pipeline.remove_particle_preserving_simulated(index);
-            }
-        }
-
-        if uses_gpu {
-            return;
-        }
-
-        if *self.need_redraw.read().unwrap() == false {
-            return;
-        }
-        if let Ok(manager) = self.simulation_manager.try_read() {
-            self.need_redraw.write().unwrap().clone_from(&false);
-            if let Some(pipeline) = self.render_pipeline.as_mut() {
-                let simulation_type = {
-                    let uis = self.ui_state.read().unwrap();
-                    uis.active_simulation_type()
-                };
-                pipeline.upload_particles(&manager.particles(), simulation_type);
-            }
-        }
+        #[cfg(not(feature = "gui"))]
+        let _ = simulation_type;
+        self.frames.publish_from(manager);
     }
-}
 
-impl App {
-    fn sync_spacecraft_yaw_steer_anchor(
-        window: &Window,
-        ui_state: &Arc<RwLock<UiState>>,
-        anchor: Option<(f64, f64)>,
-    ) {
-        ui_state.write().unwrap().spacecraft_yaw_steer_anchor = anchor.map(|(x, y)| [x, y]);
-        window.request_redraw();
+    fn clear_frames(&self) {
+        self.frames.clear();
+        #[cfg(feature = "gui")]
+        self.staging.clear();
     }
 
-    fn clear_spacecraft_steer_anchors(window: &Window, ui_state: &Arc<RwLock<UiState>>) {
-        {
-            let mut uis = ui_state.write().unwrap();
-            uis.spacecraft_steer_anchor = None;
-            uis.spacecraft_yaw_steer_anchor = None;
-        }
-        window.request_redraw();
-    }
-
-    /// Toggles the spacecraft steer anchor at the given cursor position.
-    fn toggle_spacecraft_steer_anchor(
-        window: &Window,
-        ui_state: &Arc<RwLock<UiState>>,
-        pos: (f64, f64),
-    ) {
-        {
-            let mut uis = ui_state.write().unwrap();
-            toggle_steer_anchor(&mut uis.spacecraft_steer_anchor, pos);
-        }
-        window.request_redraw();
+    #[cfg(feature = "gui")]
+    fn staging(&self) -> &StagingRing<GpuParticle> {
+        &self.staging
     }
 
-    /// Applies a snapshot-load request by scheduling a full GPU particle upload.
-    fn apply_pending_particle_buffer_reload(&mut self) {
-        let mut uis = self.ui_state.write().unwrap();
-        let reload_requested = uis.take_particle_buffer_reload_requested();
-        let uses_gpu = uis.uses_gpu_simulation();
-        drop(uis);
-        if reload_requested && uses_gpu {
-            self.gpu_particle_sync.request_full_upload();
-        }
+    fn take_published_frame(&self) -> Option<Vec<Particle>> {
+        self.frames.take()
     }
 
-    /// Clears all internal mouse drag button state flags.
-    fn clear_mouse_drag_flags(&mut self) {
-        self.mouse_left_down = false;
-        self.mouse_right_down = false;
-        self.mouse_middle_down = false;
-    }
-
-    /// Returns `true` when a double-click was recognized (click history is cleared).
-    fn try_consume_double_click(
-        click_pos: (f64, f64),
-        now: Instant,
-        last_time: &mut Option<Instant>,
-        last_pos: &mut Option<(f64, f64)>,
-    ) -> bool {
-        let max_dt = Duration::from_millis(DOUBLE_CLICK_MILLIS);
-        let Some(prev_t) = *last_time else {
-            *last_time = Some(now);
-            *last_pos = Some(click_pos);
-            return false;
-        };
-        let dt = now.duration_since(prev_t);
-        let is_double = if dt <= max_dt {
-            if let Some((px, py)) = *last_pos {
-                let dx = px - click_pos.0;
-                let dy = py - click_pos.1;
-                let dist2 = dx * dx + dy * dy;
-                dist2 <= DOUBLE_CLICK_DIST
-            } else {
-                false
-            }
-        } else {
-            false
-        };
-        if is_double {
-            *last_time = None;
-            *last_pos = None;
-            true
-        } else {
-            *last_time = Some(now);
-            *last_pos = Some(click_pos);
-            false
-        }
-    }
-
-    /// Tracks left-button press/release state for drag gestures.
-    fn left_button(&mut self, state: &ElementState) {
-        self.mouse_left_down = *state == ElementState::Pressed;
-    }
-
-    /// Handles right-button press/release and double-click target-centering behavior.
-    fn right_button(&mut self, state: &ElementState) {
-        let pressed = *state == ElementState::Pressed;
-        self.mouse_right_down = pressed;
-        if pressed {
-            if self.ui_state.read().unwrap().lock_camera_up {
-                let now = Instant::now();
-                let Some(click_pos) = self.last_cursor_position else {
-                    return;
-                };
-                if Self::try_consume_double_click(
-                    click_pos,
-                    now,
-                    &mut self.last_right_click_time,
-                    &mut self.last_right_click_pos,
-                ) && let Some(pipeline) = self.render_pipeline.as_mut()
-                {
-                    pipeline.center_target_on_origin();
-                }
-            }
-        }
-    }
-
-    /// Handles middle-button press/release state tracking for camera roll gestures.
-    fn middle_button(&mut self, state: &ElementState) {
-        let pressed = *state == ElementState::Pressed;
-        self.mouse_middle_down = pressed;
-    }
-
-    /// Picks the particle closest to the last cursor position and stores it in UI state.
-    ///
-    /// Called on a left-button release that did not promote into a drag.
-    /// Reads the most recent particle data from whichever simulation source
-    /// (CPU manager or GPU buffer) the app is currently driving.
-    fn try_pick_particle(&mut self) {
-        let Some(click_pos) = self.last_cursor_position else {
-            return;
-        };
-        let Some(vb) = self.vulkan_base.as_ref() else {
-            return;
-        };
-        let Some(pipeline) = self.render_pipeline.as_ref() else {
-            return;
-        };
-        let extent = vb.swapchain_extent;
-        if extent.width == 0 || extent.height == 0 {
-            return;
-        }
-
-        let (uses_gpu, scale_gauge, simulation_type, scale) = {
-            let uis = self.ui_state.read().unwrap();
-            (
-                uis.uses_gpu_simulation(),
-                uis.scale_gauge,
-                uis.active_simulation_type(),
-                uis.scale,
-            )
-        };
-
-        let particles = if uses_gpu {
-            pipeline.readback_particles(simulation_type, scale)
-        } else {
-            self.simulation_manager.read().unwrap().particles()
-        };
-
-        if particles.is_empty() {
-            return;
-        }
-
-        let click_x = click_pos.0 as f32;
-        let click_y = click_pos.1 as f32;
-        if let Some(idx) =
-            pipeline.pick_nearest_particle(&particles, click_x, click_y, extent, scale_gauge)
-        {
-            let mut uis = self.ui_state.write().unwrap();
-            uis.select_particle(idx);
-            drop(uis);
-            self.need_redraw.write().unwrap().clone_from(&true);
-        }
+    fn recycle_frame(&self, frame: Vec<Particle>) {
+        self.frames.recycle(frame);
     }
 }
@@ -0,0 +1,75 @@
+use crate::relativistic_view::{ViewKinematics, coordinate_velocity, retarded_position};
+use crate::simulation::Particle;
+use glam::DVec3;
+
+/// Frames between light-cone recomputations while the camera is still.
+pub const LIGHT_CONE_INTERVAL: i64 = 10;
+/// Worldline crossings drawn at most; larger systems are sampled with a fixed stride.
+pub const LIGHT_CONE_MAX_MARKERS: usize = 2000;
+/// Horizontal rings drawn on the cone surface.
+pub const LIGHT_CONE_RINGS: usize = 6;
+
+/// Where one particle's worldline crosses the observer's past light cone.
+///
+/// The cone is drawn as a (2+1)-dimensional spacetime diagram anchored at the camera: the
+/// grid plane (x, z) is space and the vertical axis is `c·t`, with the past below the
+/// camera. Particle motion out of the grid plane is left out of the diagram.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LightConeCrossing {
+    /// The particle now, on the observer's present plane.
+    pub now: DVec3,
+    /// The crossing event: retarded position in the plane, `c·τ` below the observer.
+    pub event: DVec3,
+    /// Look-back time τ in seconds.
+    pub look_back: f64,
+}
+
+/// Crossing of a particle moving uniformly at `velocity` with the past light cone of an
+/// observer at `observer`, or `None` when the particle moves at or above light speed in
+/// the grid plane.
+pub fn light_cone_crossing(
+    position: DVec3,
+    velocity: DVec3,
+    observer: DVec3,
+    light_speed: f64,
+) -> Option<LightConeCrossing> {
+    let planar = |v: DVec3| DVec3::new(v.x, 0.0, v.z);
+    let velocity = planar(velocity);
+    if velocity.length_squared() >= light_speed * light_speed {
+        return None;
+    }
+    let (retarded, look_back) =
+        retarded_position(planar(position), velocity, planar(observer), light_speed);
+    Some(LightConeCrossing {
+        now: DVec3::new(position.x, observer.y, position.z),
+        event: DVec3::new(retarded.x, observer.y - light_speed * look_back, retarded.z),
+        look_back,
+    })
+}
+
+/// Crossings for up to [`LIGHT_CONE_MAX_MARKERS`] particles, sampled evenly by index.
+pub fn light_cone_crossings(
+    particles: &[Particle],
+    kinematics: ViewKinematics,
+    observer: DVec3,
+    light_speed: f64,
+) -> Vec<LightConeCrossing> {
+    let stride = particles.len().div_ceil(LIGHT_CONE_MAX_MARKERS).max(1);
+    particles
+        .iter()
+        .step_by(stride)
+        .filter(|p| p.color[3] != 0.0)
+        .filter_map(|p| {
+            let velocity = coordinate_velocity(p, kinematics, light_speed);
+            light_cone_crossing(p.position, velocity, observer, light_speed)
+        })
+        .collect()
+}
+
+/// Height of the cone to draw: the deepest crossing below the observer.
+pub fn light_cone_depth(crossings: &[LightConeCrossing], light_speed: f64) -> f64 {
+    crossings
+        .iter()
+        .map(|crossing| crossing.look_back * light_speed)
+        .fold(0.0, f64::max)
+}
@@ -0,0 +1,121 @@
+use crate::simulation::{EPSILON, G, Particle, assign_particle_ids};
+use crate::ui_state::SimulationType;
+use dst_math::s3_galaxy::{
+    galaxy_radius_sim, orientation_from_disk_position, orientation_to_display_position,
+};
+use glam::DVec3;
+use rand::Rng;
+use rayon::prelude::*;
+
+/// Sampled particles are displaced from their source by up to this fraction of the
+/// source's distance from the centre of mass, so clones do not sit on top of each other.
+pub const LIVE_SAMPLE_JITTER: f64 = 0.05;
+
+/// Particles added and removed by one live rescale.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct LiveRescale {
+    pub added: usize,
+    pub removed: usize,
+}
+
+/// Orbital energy of every particle, `½mv² − Σ G m m_j / (r + ε)`, with the same
+/// softening as the force loop. Larger values are less bound. O(N²).
+pub fn binding_energies(particles: &[Particle]) -> Vec<f64> {
+    particles
+        .par_iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let potential: f64 = particles
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .map(|(_, q)| -G * p.mass * q.mass / (p.position.distance(q.position) + EPSILON))
+                .sum();
+            0.5 * p.mass * p.velocity.length_squared() + potential
+        })
+        .collect()
+}
+
+/// Indices of the `count` least-bound particles, in ascending order.
+pub fn least_bound_indices(particles: &[Particle], count: usize) -> Vec<usize> {
+    let energies = binding_energies(particles);
+    let mut order: Vec<usize> = (0..particles.len()).collect();
+    order.sort_by(|&a, &b| energies[b].total_cmp(&energies[a]));
+    order.truncate(count);
+    order.sort_unstable();
+    order
+}
+
+/// Draws `count` new particles by cloning randomly chosen live particles and jittering
+/// their positions. Velocity, mass, and color are copied, so the new particles follow
+/// the current distribution in whatever representation the engine stores. Ids are
+/// cleared so the caller can assign fresh ones.
+pub fn sample_particles(particles: &[Particle], count: usize, rng: &mut impl Rng) -> Vec<Particle> {
+    if particles.is_empty() {
+        return Vec::new();
+    }
+    let center = mass_center(particles);
+    (0..count)
+        .map(|_| {
+            let mut particle = particles[rng.random_range(0..particles.len())];
+            let radius = LIVE_SAMPLE_JITTER * particle.position.distance(center);
+            particle.position += random_unit_vector(rng) * radius * rng.random::<f64>();
+            particle.proper_time = 0.0;
+            particle.id = 0;
+            particle
+        })
+        .collect()
+}
+
+/// Grows or shrinks `particles` to `target` without resetting the simulation: new
+/// particles are sampled from the current ones and removals take the least-bound
+/// particles first. DstGalaxy samples have their S³ orientation re-derived from the
+/// jittered position.
+pub fn rescale_particle_count(
+    particles: &mut Vec<Particle>,
+    target: usize,
+    simulation_type: SimulationType,
+    scale: f64,
+) -> LiveRescale {
+    let current = particles.len();
+    if target < current {
+        let removed = least_bound_indices(particles, current - target);
+        for &index in removed.iter().rev() {
+            particles.remove(index);
+        }
+        return LiveRescale {
+            added: 0,
+            removed: removed.len(),
+        };
+    }
+    let mut added = sample_particles(particles, target - current, &mut rand::rng());
+    if simulation_type == SimulationType::DstGalaxy {
+        let r_galaxy = galaxy_radius_sim(scale);
+        for particle in added.iter_mut() {
+            particle.orientation = orientation_from_disk_position(particle.position, r_galaxy);
+            particle.position = orientation_to_display_position(particle.orientation, r_galaxy);
+        }
+    }
+    let count = added.len();
+    particles.extend(added);
+    assign_particle_ids(particles);
+    LiveRescale {
+        added: count,
+        removed: 0,
+    }
+}
+
+fn mass_center(particles: &[Particle]) -> DVec3 {
+    let mass: f64 = particles.iter().map(|p| p.mass).sum();
+    if mass <= 0.0 {
+        return DVec3::ZERO;
+    }
+    particles.iter().map(|p| p.position * p.mass).sum::<DVec3>() / mass
+}
+
+fn random_unit_vector(rng: &mut impl Rng) -> DVec3 {
+    let cos_theta = rng.random::<f64>() * 2.0 - 1.0;
+    let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+    let phi = rng.random_range(0.0..std::f64::consts::TAU);
+    DVec3::new(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta)
+}
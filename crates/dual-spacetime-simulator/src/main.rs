@@ -1,6 +1,40 @@
-#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+#![cfg_attr(
+    all(feature = "gui", not(debug_assertions)),
+    windows_subsystem = "windows"
+)]
 
-/// Starts the simulator application event loop.
-fn main() -> Result<(), winit::error::EventLoopError> {
-    dual_spacetime_simulator::run()
+use dual_spacetime_simulator::simulate::{SIMULATE_USAGE, parse_simulate_args, simulate};
+
+/// Runs a scenario headless with `--simulate`. Otherwise starts the simulator application
+/// event loop, or renders frames of a scenario without a window with `--render-frames`.
+#[cfg(feature = "gui")]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use dual_spacetime_simulator::render_frames::{
+        RENDER_FRAMES_USAGE, parse_render_frames_args, render_frames,
+    };
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match (parse_simulate_args(&args), parse_render_frames_args(&args)) {
+        (Ok(Some(options)), _) => Ok(simulate(&options)?),
+        (Ok(None), Ok(Some(options))) => Ok(render_frames(&options)?),
+        (Ok(None), Ok(None)) => Ok(dual_spacetime_simulator::run()?),
+        (Err(e), _) => exit_with_usage(&e, SIMULATE_USAGE),
+        (Ok(None), Err(e)) => exit_with_usage(&e, RENDER_FRAMES_USAGE),
+    }
+}
+
+/// Simulation-only build: `--simulate` is the only mode.
+#[cfg(not(feature = "gui"))]
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match parse_simulate_args(&args) {
+        Ok(Some(options)) => Ok(simulate(&options)?),
+        Ok(None) => exit_with_usage("This build has no window; use --simulate", SIMULATE_USAGE),
+        Err(e) => exit_with_usage(&e, SIMULATE_USAGE),
+    }
+}
+
+fn exit_with_usage(error: &str, usage: &str) -> ! {
+    eprintln!("{}\n\n{}", error, usage);
+    std::process::exit(2);
 }
@@ -0,0 +1,83 @@
+use crate::simulation::Particle;
+use glam::DVec3;
+
+/// Impulsive burn scheduled for the particle with `particle_id`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Maneuver {
+    pub particle_id: u64,
+    /// Velocity change in m/s along the prograde (`x`), radial-out (`y`), and orbit
+    /// normal (`z`) directions at the moment of the burn.
+    pub delta_v: DVec3,
+    /// Simulation time in s at which the burn fires.
+    pub burn_time: f64,
+}
+
+/// Orbit-relative directions of a particle around its primary.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct OrbitalFrame {
+    /// Along the velocity relative to the primary.
+    pub prograde: DVec3,
+    /// In the orbit plane, perpendicular to prograde and pointing away from the primary.
+    pub radial: DVec3,
+    /// Along the orbital angular momentum.
+    pub normal: DVec3,
+}
+
+impl OrbitalFrame {
+    /// Builds the frame from the position and velocity relative to the primary. Returns
+    /// `None` when the particle is at rest relative to it or moving straight along the
+    /// line between them, since the orbit plane is then undefined.
+    pub fn from_relative_state(position: DVec3, velocity: DVec3) -> Option<Self> {
+        let prograde = velocity.try_normalize()?;
+        let normal = position.cross(velocity).try_normalize()?;
+        Some(Self {
+            prograde,
+            radial: prograde.cross(normal),
+            normal,
+        })
+    }
+
+    /// Converts prograde/radial/normal components into a world-space vector.
+    pub fn to_world(&self, components: DVec3) -> DVec3 {
+        self.prograde * components.x + self.radial * components.y + self.normal * components.z
+    }
+}
+
+/// Index of the particle pulling hardest on `particles[index]` (largest `m / r²`), the
+/// natural primary for orbit-relative readouts.
+pub fn dominant_body(particles: &[Particle], index: usize) -> Option<usize> {
+    let position = particles.get(index)?.position;
+    particles
+        .iter()
+        .enumerate()
+        .filter(|&(other, particle)| other != index && particle.mass > 0.0)
+        .filter_map(|(other, particle)| {
+            let distance_sq = (particle.position - position).length_squared();
+            (distance_sq > 0.0).then(|| (other, particle.mass / distance_sq))
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(other, _)| other)
+}
+
+/// Applies `maneuver` to its particle at world `scale`, orienting the burn against the
+/// particle's dominant body. Returns whether the burn was applied; it is skipped when the
+/// particle is gone or its orbit frame is undefined.
+pub fn apply_maneuver(particles: &mut [Particle], maneuver: &Maneuver, scale: f64) -> bool {
+    let Some(index) = particles
+        .iter()
+        .position(|particle| particle.id == maneuver.particle_id)
+    else {
+        return false;
+    };
+    let Some(primary) = dominant_body(particles, index) else {
+        return false;
+    };
+    let relative_position = particles[index].position - particles[primary].position;
+    let relative_velocity = particles[index].velocity - particles[primary].velocity;
+    let Some(frame) = OrbitalFrame::from_relative_state(relative_position, relative_velocity)
+    else {
+        return false;
+    };
+    particles[index].velocity += frame.to_world(maneuver.delta_v) / scale;
+    true
+}
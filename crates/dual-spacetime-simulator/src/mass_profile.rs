@@ -0,0 +1,111 @@
+use crate::simulation::{G, Particle};
+use glam::DVec3;
+
+/// Enclosed-mass fractions whose radii are reported and drawn as Lagrangian spheres.
+pub const LAGRANGIAN_MASS_FRACTIONS: [f64; 3] = [0.1, 0.5, 0.9];
+/// Default number of frames between mass-profile updates.
+pub const DEFAULT_MASS_PROFILE_INTERVAL: u32 = 10;
+
+/// Global center of mass and Lagrangian radii of the live particles.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MassProfile {
+    pub center_of_mass: DVec3,
+    /// Radii about the center of mass enclosing [`LAGRANGIAN_MASS_FRACTIONS`] of the mass.
+    pub lagrangian_radii: [f64; LAGRANGIAN_MASS_FRACTIONS.len()],
+}
+
+/// Computes the center of mass and Lagrangian radii of live (non-zero alpha) particles.
+///
+/// Returns `None` when no live particle has positive mass.
+pub fn mass_profile(particles: &[Particle]) -> Option<MassProfile> {
+    let live: Vec<&Particle> = particles
+        .iter()
+        .filter(|p| p.color[3] != 0.0 && p.mass > 0.0 && p.position.is_finite())
+        .collect();
+    let total_mass: f64 = live.iter().map(|p| p.mass).sum();
+    if total_mass <= 0.0 {
+        return None;
+    }
+    let center_of_mass = live
+        .iter()
+        .fold(DVec3::ZERO, |acc, p| acc + p.position * p.mass)
+        / total_mass;
+    let mut shells: Vec<(f64, f64)> = live
+        .iter()
+        .map(|p| (p.position.distance(center_of_mass), p.mass))
+        .collect();
+    shells.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut lagrangian_radii = [0.0; LAGRANGIAN_MASS_FRACTIONS.len()];
+    let mut enclosed = 0.0;
+    let mut next = 0;
+    for (radius, mass) in shells {
+        enclosed += mass;
+        while next < lagrangian_radii.len()
+            && enclosed >= LAGRANGIAN_MASS_FRACTIONS[next] * total_mass
+        {
+            lagrangian_radii[next] = radius;
+            next += 1;
+        }
+    }
+    Some(MassProfile {
+        center_of_mass,
+        lagrangian_radii,
+    })
+}
+
+/// Returns indices (ascending) of live particles moving faster than the local escape speed.
+///
+/// With no tree or mesh potential available, the potential is the spherical estimate about
+/// the center of mass, `Φ(r_i) = -G (M(<r_i) / r_i + Σ_{r_j > r_i} m_j / r_j)`, excluding
+/// the particle itself. Speeds are taken relative to the mass-weighted mean velocity, so a
+/// drifting cluster does not count as escaping.
+pub fn escaping_particle_indices(particles: &[Particle]) -> Vec<usize> {
+    let live: Vec<usize> = (0..particles.len())
+        .filter(|&i| particles[i].color[3] != 0.0 && particles[i].position.is_finite())
+        .collect();
+    let total_mass: f64 = live.iter().map(|&i| particles[i].mass.max(0.0)).sum();
+    if total_mass <= 0.0 {
+        return Vec::new();
+    }
+    let (mass_position, mass_velocity) =
+        live.iter()
+            .fold((DVec3::ZERO, DVec3::ZERO), |(position, velocity), &i| {
+                let p = &particles[i];
+                let mass = p.mass.max(0.0);
+                (position + p.position * mass, velocity + p.velocity * mass)
+            });
+    let center_of_mass = mass_position / total_mass;
+    let mean_velocity = mass_velocity / total_mass;
+
+    let mut shells: Vec<(usize, f64)> = live
+        .into_iter()
+        .map(|i| (i, particles[i].position.distance(center_of_mass)))
+        .collect();
+    shells.sort_by(|a, b| a.1.total_cmp(&b.1));
+    let shell_term = |i: usize, radius: f64| {
+        if radius > 0.0 {
+            particles[i].mass.max(0.0) / radius
+        } else {
+            0.0
+        }
+    };
+    let mut outer: f64 = shells
+        .iter()
+        .map(|&(i, radius)| shell_term(i, radius))
+        .sum();
+    let mut inner_mass = 0.0;
+    let mut escaping = Vec::new();
+    for (i, radius) in shells {
+        outer -= shell_term(i, radius);
+        if radius > 0.0 {
+            let potential = -G * (inner_mass / radius + outer.max(0.0));
+            let relative_speed_sq = (particles[i].velocity - mean_velocity).length_squared();
+            if relative_speed_sq > -2.0 * potential {
+                escaping.push(i);
+            }
+        }
+        inner_mass += particles[i].mass.max(0.0);
+    }
+    escaping.sort_unstable();
+    escaping
+}
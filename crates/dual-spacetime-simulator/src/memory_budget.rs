@@ -0,0 +1,125 @@
+use crate::simulation::Particle;
+
+/// Default memory budget in MiB.
+pub const DEFAULT_MEMORY_BUDGET_MB: u32 = 4096;
+/// Phase-space sampling is never reduced below this many points.
+pub const MIN_PHASE_SPACE_POINTS: u32 = 500;
+
+const MIB: u64 = 1024 * 1024;
+/// CPU bytes per particle in the simulation state.
+pub const CPU_PARTICLE_BYTES: u64 = std::mem::size_of::<Particle>() as u64;
+/// Size of one `GpuParticle` slot in the particle SSBO; `gpu_simulation` asserts it.
+pub const GPU_PARTICLE_SLOT_BYTES: usize = 64;
+/// GPU bytes per particle: the particle SSBO slot plus its visible-index entry.
+pub const GPU_PARTICLE_BYTES: u64 = (GPU_PARTICLE_SLOT_BYTES + std::mem::size_of::<u32>()) as u64;
+/// Bytes per sampled phase-space point (coordinates plus particle index).
+pub const PHASE_SPACE_POINT_BYTES: u64 =
+    (std::mem::size_of::<[f64; 2]>() + std::mem::size_of::<usize>()) as u64;
+
+/// Estimated memory held by the simulation, in bytes.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct MemoryUsage {
+    /// CPU particle state plus pipelined frame copies.
+    pub particle_state: u64,
+    /// GPU particle and culling buffers.
+    pub gpu_buffers: u64,
+    /// Diagnostic sample buffers (phase space).
+    pub diagnostics: u64,
+}
+
+impl MemoryUsage {
+    pub fn total(&self) -> u64 {
+        self.particle_state + self.gpu_buffers + self.diagnostics
+    }
+}
+
+/// Settings the budget may lower, and the inputs they are estimated from.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MemoryDemand {
+    pub particle_count: u32,
+    pub phase_space_max_points: u32,
+    pub pipelined_stepping: bool,
+}
+
+impl MemoryDemand {
+    /// Pipelined stepping keeps up to two extra copies of the particle state.
+    fn frame_copies(&self) -> u64 {
+        if self.pipelined_stepping { 2 } else { 0 }
+    }
+
+    fn bytes_per_particle(&self) -> u64 {
+        CPU_PARTICLE_BYTES * (1 + self.frame_copies()) + GPU_PARTICLE_BYTES
+    }
+
+    pub fn usage(&self) -> MemoryUsage {
+        let n = self.particle_count as u64;
+        MemoryUsage {
+            particle_state: n * CPU_PARTICLE_BYTES * (1 + self.frame_copies()),
+            gpu_buffers: n * GPU_PARTICLE_BYTES,
+            diagnostics: self.phase_space_max_points as u64 * PHASE_SPACE_POINT_BYTES,
+        }
+    }
+}
+
+/// Outcome of fitting a [`MemoryDemand`] into a budget.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BudgetPlan {
+    /// The demand with any reductions applied.
+    pub demand: MemoryDemand,
+    pub usage: MemoryUsage,
+    /// Largest particle count that fits alongside the (reduced) diagnostics.
+    pub particle_limit: u32,
+    /// Set when something was reduced or the budget still cannot be met.
+    pub warning: Option<String>,
+}
+
+pub fn budget_bytes(budget_mb: u32) -> u64 {
+    budget_mb as u64 * MIB
+}
+
+fn megabytes(bytes: u64) -> f64 {
+    bytes as f64 / MIB as f64
+}
+
+/// Fits `demand` into `budget` bytes, first lowering the phase-space sample count, then
+/// turning off pipelined stepping. Particles are never removed; when they alone exceed
+/// the budget the plan only warns and caps further growth.
+pub fn plan_within_budget(budget: u64, demand: MemoryDemand) -> BudgetPlan {
+    let mut planned = demand;
+    let mut reductions = Vec::new();
+    if planned.usage().total() > budget && planned.phase_space_max_points > MIN_PHASE_SPACE_POINTS {
+        let usage = planned.usage();
+        let room = budget.saturating_sub(usage.particle_state + usage.gpu_buffers);
+        let fitting = (room / PHASE_SPACE_POINT_BYTES).min(u32::MAX as u64) as u32;
+        planned.phase_space_max_points =
+            fitting.clamp(MIN_PHASE_SPACE_POINTS, planned.phase_space_max_points);
+        if planned.phase_space_max_points < demand.phase_space_max_points {
+            reductions.push(format!(
+                "phase-space points {} → {}",
+                demand.phase_space_max_points, planned.phase_space_max_points
+            ));
+        }
+    }
+    if planned.usage().total() > budget && planned.pipelined_stepping {
+        planned.pipelined_stepping = false;
+        reductions.push("pipelined stepping off".to_string());
+    }
+    let usage = planned.usage();
+    let particle_limit = (budget.saturating_sub(usage.diagnostics) / planned.bytes_per_particle())
+        .min(u32::MAX as u64) as u32;
+    let mut warning = (!reductions.is_empty())
+        .then(|| format!("Memory budget: reduced {}", reductions.join(", ")));
+    if usage.total() > budget {
+        warning = Some(format!(
+            "Particles need {:.0} MiB, over the {:.0} MiB budget; adding is disabled",
+            megabytes(usage.total()),
+            megabytes(budget)
+        ));
+    }
+    BudgetPlan {
+        demand: planned,
+        usage,
+        particle_limit,
+        warning,
+    }
+}
@@ -0,0 +1,324 @@
+use serde::{Deserialize, Serialize};
+
+/// Function a metric expression can call by name.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MetricFunction {
+    Abs,
+    Sqrt,
+    Ln,
+    Log10,
+    Exp,
+}
+
+impl MetricFunction {
+    pub const ALL: [Self; 5] = [Self::Abs, Self::Sqrt, Self::Ln, Self::Log10, Self::Exp];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            MetricFunction::Abs => "abs",
+            MetricFunction::Sqrt => "sqrt",
+            MetricFunction::Ln => "ln",
+            MetricFunction::Log10 => "log10",
+            MetricFunction::Exp => "exp",
+        }
+    }
+
+    fn apply(self, x: f64) -> f64 {
+        match self {
+            MetricFunction::Abs => x.abs(),
+            MetricFunction::Sqrt => x.sqrt(),
+            MetricFunction::Ln => x.ln(),
+            MetricFunction::Log10 => x.log10(),
+            MetricFunction::Exp => x.exp(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+/// Parsed arithmetic over metric values: numbers, metric names, `+ - * / ^`, unary
+/// minus, parentheses, `|x|` for the absolute value, and the [`MetricFunction`]s.
+/// `^` binds tighter than unary minus and groups to the right, so `-a^2` is `-(a^2)`.
+#[derive(Clone, PartialEq, Debug)]
+pub enum MetricExpr {
+    Number(f64),
+    Metric(String),
+    Neg(Box<MetricExpr>),
+    Call(MetricFunction, Box<MetricExpr>),
+    Binary(BinaryOp, Box<MetricExpr>, Box<MetricExpr>),
+}
+
+impl MetricExpr {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let tokens = tokenize(text)?;
+        if tokens.is_empty() {
+            return Err("Empty expression".to_string());
+        }
+        let mut parser = Parser { tokens, next: 0 };
+        let expr = parser.sum()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(token) => Err(format!("Unexpected {}", token)),
+        }
+    }
+
+    /// Evaluates the expression with `value` giving each metric's value; `None` when a
+    /// metric has none. Division by zero and the like give infinities or NaN, as in `f64`.
+    pub fn evaluate(&self, value: &impl Fn(&str) -> Option<f64>) -> Option<f64> {
+        Some(match self {
+            MetricExpr::Number(x) => *x,
+            MetricExpr::Metric(name) => value(name)?,
+            MetricExpr::Neg(x) => -x.evaluate(value)?,
+            MetricExpr::Call(function, x) => function.apply(x.evaluate(value)?),
+            MetricExpr::Binary(op, a, b) => {
+                let (a, b) = (a.evaluate(value)?, b.evaluate(value)?);
+                match op {
+                    BinaryOp::Add => a + b,
+                    BinaryOp::Sub => a - b,
+                    BinaryOp::Mul => a * b,
+                    BinaryOp::Div => a / b,
+                    BinaryOp::Pow => a.powf(b),
+                }
+            }
+        })
+    }
+
+    /// Returns whether the expression reads metric `name`.
+    pub fn uses(&self, name: &str) -> bool {
+        match self {
+            MetricExpr::Number(_) => false,
+            MetricExpr::Metric(metric) => metric == name,
+            MetricExpr::Neg(x) | MetricExpr::Call(_, x) => x.uses(name),
+            MetricExpr::Binary(_, a, b) => a.uses(name) || b.uses(name),
+        }
+    }
+}
+
+/// Returns whether `name` can name a metric: ASCII letters, digits, and underscores, not
+/// starting with a digit, and not a function name.
+pub fn is_metric_name(name: &str) -> bool {
+    name.chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && MetricFunction::ALL.iter().all(|f| f.name() != name)
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Symbol(char),
+}
+
+impl std::fmt::Display for Token {
+    /// Formats a token for parse error messages.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Number(x) => write!(f, "number {}", x),
+            Token::Name(name) => write!(f, "'{}'", name),
+            Token::Symbol(c) => write!(f, "'{}'", c),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            // Exponent, only when digits follow so `2e` stays an error rather than `2 * e`.
+            if i < chars.len() && matches!(chars[i], 'e' | 'E') {
+                let sign = usize::from(chars.get(i + 1).is_some_and(|&s| s == '+' || s == '-'));
+                if chars.get(i + 1 + sign).is_some_and(char::is_ascii_digit) {
+                    i += 1 + sign;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let literal: String = chars[start..i].iter().collect();
+            let value = literal
+                .parse()
+                .map_err(|_| format!("Invalid number '{}'", literal))?;
+            tokens.push(Token::Number(value));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Name(chars[start..i].iter().collect()));
+        } else if "+-*/^()|".contains(c) {
+            tokens.push(Token::Symbol(c));
+            i += 1;
+        } else {
+            return Err(format!("Unexpected '{}'", c));
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive-descent parser over the tokens, one method per precedence level.
+struct Parser {
+    tokens: Vec<Token>,
+    next: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next)
+    }
+
+    fn take_symbol(&mut self, symbol: char) -> bool {
+        let found = self.peek() == Some(&Token::Symbol(symbol));
+        if found {
+            self.next += 1;
+        }
+        found
+    }
+
+    fn expect_symbol(&mut self, symbol: char) -> Result<(), String> {
+        if self.take_symbol(symbol) {
+            return Ok(());
+        }
+        match self.peek() {
+            Some(token) => Err(format!("Expected '{}' before {}", symbol, token)),
+            None => Err(format!("Expected '{}' at the end", symbol)),
+        }
+    }
+
+    fn sum(&mut self) -> Result<MetricExpr, String> {
+        let mut expr = self.product()?;
+        loop {
+            let op = if self.take_symbol('+') {
+                BinaryOp::Add
+            } else if self.take_symbol('-') {
+                BinaryOp::Sub
+            } else {
+                return Ok(expr);
+            };
+            expr = MetricExpr::Binary(op, Box::new(expr), Box::new(self.product()?));
+        }
+    }
+
+    fn product(&mut self) -> Result<MetricExpr, String> {
+        let mut expr = self.unary()?;
+        loop {
+            let op = if self.take_symbol('*') {
+                BinaryOp::Mul
+            } else if self.take_symbol('/') {
+                BinaryOp::Div
+            } else {
+                return Ok(expr);
+            };
+            expr = MetricExpr::Binary(op, Box::new(expr), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<MetricExpr, String> {
+        if self.take_symbol('-') {
+            return Ok(MetricExpr::Neg(Box::new(self.unary()?)));
+        }
+        let base = self.atom()?;
+        if self.take_symbol('^') {
+            let exponent = self.unary()?;
+            return Ok(MetricExpr::Binary(
+                BinaryOp::Pow,
+                Box::new(base),
+                Box::new(exponent),
+            ));
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<MetricExpr, String> {
+        let Some(token) = self.tokens.get(self.next).cloned() else {
+            return Err("Unexpected end of expression".to_string());
+        };
+        self.next += 1;
+        match token {
+            Token::Number(x) => Ok(MetricExpr::Number(x)),
+            Token::Name(name) => {
+                if !self.take_symbol('(') {
+                    return Ok(MetricExpr::Metric(name));
+                }
+                let function = MetricFunction::ALL
+                    .into_iter()
+                    .find(|f| f.name() == name)
+                    .ok_or_else(|| format!("Unknown function '{}'", name))?;
+                let argument = self.sum()?;
+                self.expect_symbol(')')?;
+                Ok(MetricExpr::Call(function, Box::new(argument)))
+            }
+            Token::Symbol('(') => {
+                let expr = self.sum()?;
+                self.expect_symbol(')')?;
+                Ok(expr)
+            }
+            Token::Symbol('|') => {
+                let expr = self.sum()?;
+                self.expect_symbol('|')?;
+                Ok(MetricExpr::Call(MetricFunction::Abs, Box::new(expr)))
+            }
+            token => Err(format!("Unexpected {}", token)),
+        }
+    }
+}
+
+/// Name and expression of a derived metric, as saved in the settings.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct DerivedMetricDefinition {
+    pub name: String,
+    pub expression: String,
+}
+
+/// Metric computed from others by an expression each time one of them is sampled, and
+/// kept in the metrics store like the measured ones.
+#[derive(Clone, PartialEq, Debug)]
+pub struct DerivedMetric {
+    definition: DerivedMetricDefinition,
+    expr: MetricExpr,
+}
+
+impl DerivedMetric {
+    /// Parses the definition's expression; the name must pass [`is_metric_name`] and the
+    /// expression may not read the metric itself.
+    pub fn new(definition: DerivedMetricDefinition) -> Result<Self, String> {
+        if !is_metric_name(&definition.name) {
+            return Err(format!("Invalid metric name '{}'", definition.name));
+        }
+        let expr = MetricExpr::parse(&definition.expression)?;
+        if expr.uses(&definition.name) {
+            return Err(format!("'{}' cannot use itself", definition.name));
+        }
+        Ok(Self { definition, expr })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.definition.name
+    }
+
+    pub fn definition(&self) -> &DerivedMetricDefinition {
+        &self.definition
+    }
+
+    pub fn expr(&self) -> &MetricExpr {
+        &self.expr
+    }
+}
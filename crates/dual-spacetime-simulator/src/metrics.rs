@@ -0,0 +1,314 @@
+use crate::metric_expr::{DerivedMetric, DerivedMetricDefinition};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+pub const METRICS_FILTER_NAME: &str = "CSV";
+pub const METRICS_FILTER_EXT: &str = "csv";
+pub const DEFAULT_METRIC_CAPACITY: usize = 4096;
+pub const MIN_METRIC_CAPACITY: usize = 16;
+pub const MAX_METRIC_CAPACITY: usize = 1_000_000;
+/// Frames between samples of the metrics measured for the Plots window.
+pub const METRIC_SAMPLE_INTERVAL: i64 = 10;
+/// Largest particle count whose total momentum is sampled; beyond it, copying the
+/// particles every interval would stall the render loop.
+pub const MOMENTUM_SAMPLE_MAX_PARTICLES: usize = 100_000;
+
+/// Total energy measured by the energy monitor.
+pub const TOTAL_ENERGY: &str = "total_energy";
+/// Kinetic part of the total energy.
+pub const KINETIC_ENERGY: &str = "kinetic_energy";
+/// Pairwise potential part of the total energy.
+pub const POTENTIAL_ENERGY: &str = "potential_energy";
+/// Relative energy drift `|E - E₀| / |E₀|` against the monitor's reference.
+pub const ENERGY_DRIFT: &str = "energy_drift";
+/// Magnitude of the total momentum `|Σ m v|` of the live particles.
+pub const TOTAL_MOMENTUM: &str = "total_momentum";
+/// Simulation frames per second.
+pub const FPS: &str = "fps";
+/// Mean wall time per simulation step since the previous sample, in milliseconds.
+pub const STEP_TIME: &str = "step_time";
+/// Distance of the selected particle from the origin.
+pub const SELECTED_RADIUS: &str = "selected_radius";
+/// Metrics the application measures, which derived metrics cannot be named after.
+pub const BUILT_IN_METRICS: [&str; 8] = [
+    TOTAL_ENERGY,
+    KINETIC_ENERGY,
+    POTENTIAL_ENERGY,
+    ENERGY_DRIFT,
+    TOTAL_MOMENTUM,
+    FPS,
+    STEP_TIME,
+    SELECTED_RADIUS,
+];
+
+/// How much history every metric keeps.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    /// Samples kept per metric; the oldest are dropped first.
+    pub capacity: usize,
+    /// Simulation time kept behind a metric's newest sample; 0 keeps all that fit.
+    pub max_age: f64,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_METRIC_CAPACITY,
+            max_age: 0.0,
+        }
+    }
+}
+
+/// Ring buffer of one metric's `(t, v)` samples, in ascending simulation time.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MetricSeries {
+    samples: VecDeque<(f64, f64)>,
+}
+
+impl MetricSeries {
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Newest sample.
+    pub fn latest(&self) -> Option<(f64, f64)> {
+        self.samples.back().copied()
+    }
+
+    /// Samples from oldest to newest.
+    pub fn iter(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.samples.iter().copied()
+    }
+
+    /// Samples as `[t, v]` points for plotting, skipping values that are not finite.
+    pub fn points(&self) -> Vec<[f64; 2]> {
+        self.iter()
+            .filter(|(_, v)| v.is_finite())
+            .map(|(t, v)| [t, v])
+            .collect()
+    }
+
+    /// Appends a sample at `t`. Samples at or after `t` are dropped first, so a run that
+    /// went back in time (undo, snapshot load) overwrites the history it left.
+    fn push(&mut self, t: f64, v: f64, retention: RetentionPolicy) {
+        while self.samples.back().is_some_and(|&(last, _)| last >= t) {
+            self.samples.pop_back();
+        }
+        self.samples.push_back((t, v));
+        self.trim(retention);
+    }
+
+    /// Drops the oldest samples beyond the capacity or the age limit.
+    fn trim(&mut self, retention: RetentionPolicy) {
+        let excess = self.samples.len().saturating_sub(retention.capacity.max(1));
+        self.samples.drain(..excess);
+        if retention.max_age > 0.0
+            && let Some((newest, _)) = self.latest()
+        {
+            let oldest = newest - retention.max_age;
+            while self.samples.front().is_some_and(|&(t, _)| t < oldest) {
+                self.samples.pop_front();
+            }
+        }
+    }
+}
+
+/// In-memory time series of every diagnostic, keyed by metric name, which plots and
+/// exports read instead of keeping buffers of their own. All metrics share one
+/// [`RetentionPolicy`].
+#[derive(Clone, Debug, Default)]
+pub struct MetricsStore {
+    series: BTreeMap<String, MetricSeries>,
+    retention: RetentionPolicy,
+    /// Evaluated in order after each recorded sample, so one may use those before it.
+    derived: Vec<DerivedMetric>,
+}
+
+impl MetricsStore {
+    pub fn new(retention: RetentionPolicy) -> Self {
+        Self {
+            series: BTreeMap::new(),
+            retention,
+            derived: Vec::new(),
+        }
+    }
+
+    /// Records value `v` of metric `name` at simulation time `t`, creating the metric on
+    /// its first sample, then updates the derived metrics that use it.
+    pub fn record(&mut self, name: &str, t: f64, v: f64) {
+        push_sample(&mut self.series, name, t, v, self.retention);
+        let mut updated = vec![name.to_string()];
+        for derived in &self.derived {
+            if !updated.iter().any(|metric| derived.expr().uses(metric)) {
+                continue;
+            }
+            let value = derived
+                .expr()
+                .evaluate(&|metric: &str| Some(self.series.get(metric)?.latest()?.1));
+            if let Some(value) = value {
+                push_sample(&mut self.series, derived.name(), t, value, self.retention);
+                updated.push(derived.name().to_string());
+            }
+        }
+    }
+
+    /// Adds a derived metric after the others; it is first evaluated when one of the
+    /// metrics it uses is next recorded. Fails on a name a metric already has.
+    pub fn define(&mut self, definition: DerivedMetricDefinition) -> Result<(), String> {
+        let derived = DerivedMetric::new(definition)?;
+        let name = derived.name();
+        if BUILT_IN_METRICS.contains(&name)
+            || self.series.contains_key(name)
+            || self.derived.iter().any(|d| d.name() == name)
+        {
+            return Err(format!("Metric '{}' already exists", name));
+        }
+        self.derived.push(derived);
+        Ok(())
+    }
+
+    /// Removes a derived metric and its history.
+    pub fn undefine(&mut self, name: &str) {
+        self.derived.retain(|d| d.name() != name);
+        self.series.remove(name);
+    }
+
+    pub fn derived(&self) -> &[DerivedMetric] {
+        &self.derived
+    }
+
+    /// Definitions of the derived metrics, in evaluation order, for saving.
+    pub fn derived_definitions(&self) -> Vec<DerivedMetricDefinition> {
+        self.derived
+            .iter()
+            .map(|d| d.definition().clone())
+            .collect()
+    }
+
+    /// Replaces the derived metrics with `definitions`, skipping those that fail to
+    /// define; returns their errors.
+    pub fn set_derived(&mut self, definitions: &[DerivedMetricDefinition]) -> Vec<String> {
+        for derived in std::mem::take(&mut self.derived) {
+            self.series.remove(derived.name());
+        }
+        definitions
+            .iter()
+            .filter_map(|definition| {
+                self.define(definition.clone())
+                    .err()
+                    .map(|e| format!("{}: {}", definition.name, e))
+            })
+            .collect()
+    }
+
+    pub fn series(&self, name: &str) -> Option<&MetricSeries> {
+        self.series.get(name)
+    }
+
+    /// Names of the recorded metrics, sorted.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.series.keys().map(String::as_str)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.series.is_empty()
+    }
+
+    pub fn retention(&self) -> RetentionPolicy {
+        self.retention
+    }
+
+    /// Replaces the retention policy and trims the history already kept to it.
+    pub fn set_retention(&mut self, retention: RetentionPolicy) {
+        self.retention = retention;
+        for series in self.series.values_mut() {
+            series.trim(retention);
+        }
+    }
+
+    /// Forgets every sample, as after a reset; derived metrics stay defined.
+    pub fn clear(&mut self) {
+        self.series.clear();
+    }
+
+    /// Formats every sample as CSV rows `metric,t,value`, metrics in name order.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("metric,t,value\n");
+        for (name, series) in &self.series {
+            for (t, v) in series.iter() {
+                let _ = writeln!(csv, "{},{:e},{:e}", name, t, v);
+            }
+        }
+        csv
+    }
+
+    pub fn save_csv(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_csv())
+    }
+}
+
+/// Appends a sample to metric `name`, creating its series on the first one.
+fn push_sample(
+    series: &mut BTreeMap<String, MetricSeries>,
+    name: &str,
+    t: f64,
+    v: f64,
+    retention: RetentionPolicy,
+) {
+    match series.get_mut(name) {
+        Some(metric) => metric.push(t, v, retention),
+        None => {
+            let mut metric = MetricSeries::default();
+            metric.push(t, v, retention);
+            series.insert(name.to_string(), metric);
+        }
+    }
+}
+
+/// Paces the sampled metrics and measures the step time between samples from the
+/// simulation's wall-clock runtime.
+#[derive(Clone, Debug, Default)]
+pub struct MetricSampler {
+    /// Frame and wall runtime at the last sample.
+    last: Option<(i64, Duration)>,
+}
+
+impl MetricSampler {
+    /// Returns whether metrics should be sampled at `frame`: every
+    /// [`METRIC_SAMPLE_INTERVAL`] frames, and right away after the frame counter went back.
+    pub fn is_due(&self, frame: i64) -> bool {
+        self.last
+            .is_none_or(|(sampled, _)| frame < sampled || frame - sampled >= METRIC_SAMPLE_INTERVAL)
+    }
+
+    /// Marks a sample at `frame` after `wall_runtime` of running, and returns the mean
+    /// wall time per step since the previous sample in milliseconds. `None` for the first
+    /// sample and after the frame counter or the runtime went back.
+    pub fn sample(&mut self, frame: i64, wall_runtime: Duration) -> Option<f64> {
+        let previous = self.last.replace((frame, wall_runtime));
+        let (sampled, runtime) = previous?;
+        let steps = frame - sampled;
+        if steps <= 0 || wall_runtime < runtime {
+            return None;
+        }
+        Some((wall_runtime - runtime).as_secs_f64() * 1e3 / steps as f64)
+    }
+
+    /// Forgets the last sample, as after a reset.
+    pub fn forget(&mut self) {
+        self.last = None;
+    }
+}
@@ -0,0 +1,71 @@
+use glam::{Mat4, Vec3};
+
+/// Frames between refreshes of the system bounds the minimap frames.
+pub const MINIMAP_INTERVAL: i64 = 30;
+/// Side of the square inset as a fraction of the shorter window side.
+const MINIMAP_SIZE_FRACTION: f32 = 0.25;
+/// Smallest inset side in pixels; smaller windows get no minimap.
+const MINIMAP_MIN_SIZE: u32 = 64;
+/// Gap in pixels between the inset and the window's bottom-right corner.
+const MINIMAP_MARGIN: u32 = 16;
+/// Extra room around the system so the outermost particles stay off the inset edge.
+const MINIMAP_PADDING: f32 = 1.1;
+
+/// Pixel rectangle of the minimap inset: top-left corner and side length.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MinimapRect {
+    pub x: u32,
+    pub y: u32,
+    pub size: u32,
+}
+
+/// Places the square inset in the bottom-right corner of a `width` × `height` window, or
+/// returns `None` when the window is too small for it.
+pub fn minimap_rect(width: u32, height: u32) -> Option<MinimapRect> {
+    let size = (width.min(height) as f32 * MINIMAP_SIZE_FRACTION) as u32;
+    if size < MINIMAP_MIN_SIZE {
+        return None;
+    }
+    Some(MinimapRect {
+        x: width.checked_sub(size + MINIMAP_MARGIN)?,
+        y: height.checked_sub(size + MINIMAP_MARGIN)?,
+        size,
+    })
+}
+
+/// Orthographic view straight down the grid normal onto a sphere of `radius` at `center`,
+/// both in axes space. Looks along +Y, since the main camera starts on the −Y side.
+pub fn minimap_view_proj(center: Vec3, radius: f32) -> Mat4 {
+    let radius = radius.max(f32::MIN_POSITIVE) * MINIMAP_PADDING;
+    let eye = center - Vec3::Y * (2.0 * radius);
+    let view = Mat4::look_at_rh(eye, center, Vec3::Z);
+    let proj = Mat4::orthographic_rh(-radius, radius, -radius, radius, 0.0, 4.0 * radius);
+    proj * view
+}
+
+/// Line segments outlining what a camera at `position` looking at `target` sees: the
+/// edges from the camera to the corners of the view rectangle at the target's distance,
+/// and that rectangle. Empty when the camera sits on its target or looks along `up`.
+pub fn camera_frustum_lines(
+    position: Vec3,
+    target: Vec3,
+    up: Vec3,
+    aspect_ratio: f32,
+    fov_y: f32,
+) -> Vec<[Vec3; 2]> {
+    let offset = target - position;
+    let distance = offset.length();
+    let forward = offset.normalize_or_zero();
+    let right = forward.cross(up).normalize_or_zero();
+    if distance == 0.0 || right == Vec3::ZERO {
+        return Vec::new();
+    }
+    let true_up = right.cross(forward);
+    let half_height = distance * (0.5 * fov_y).tan();
+    let half_width = half_height * aspect_ratio;
+    let corners = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+        .map(|(x, y)| target + right * (x * half_width) + true_up * (y * half_height));
+    let mut lines: Vec<[Vec3; 2]> = corners.iter().map(|&corner| [position, corner]).collect();
+    lines.extend((0..4).map(|i| [corners[i], corners[(i + 1) % 4]]));
+    lines
+}
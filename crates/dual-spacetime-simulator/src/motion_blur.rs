@@ -0,0 +1,459 @@
+use crate::pipeline::{additive_blend, create_graphics_pipeline, create_pipeline_layout};
+use ash::vk;
+use gpu_allocator::vulkan::Allocator;
+use std::sync::{Arc, Mutex};
+use vulkanvil::AllocatedImage;
+
+/// Share of a frame's light left in the accumulation after the configured number of
+/// frames: below one step of an 8-bit swapchain.
+pub const TRAIL_FLOOR: f32 = 1.0 / 256.0;
+/// Half floats, so faint trail tails keep fading smoothly instead of banding.
+const ACCUMULATION_FORMAT: vk::Format = vk::Format::R16G16B16A16_SFLOAT;
+
+/// Share of the accumulated light each frame keeps when trails last `frames` frames:
+/// after `frames` fades it is down to [`TRAIL_FLOOR`]. 0 frames turns motion blur off.
+pub fn blur_retention(frames: u32) -> f32 {
+    if frames == 0 {
+        0.0
+    } else {
+        TRAIL_FLOOR.powf((frames as f32).recip())
+    }
+}
+
+/// Scale of the composited accumulation, `1 − retention`. The frame weights then sum to
+/// 1, so a particle at rest keeps its unblurred brightness while a moving one spreads it
+/// along its streak, as in a long exposure.
+pub fn composite_gain(retention: f32) -> f32 {
+    1.0 - retention
+}
+
+/// Image and framebuffer of the accumulation at one extent.
+struct AccumulationTarget {
+    image: AllocatedImage,
+    framebuffer: vk::Framebuffer,
+    extent: vk::Extent2D,
+}
+
+/// Temporal accumulation behind motion blur. Before the scene pass, a pass over a
+/// half-float image scales what it holds by the retention and adds this frame's particle
+/// draw; the main view then composites it in place of its particles. A particle leaves
+/// a streak over the distance it covered in the last frames, so fast and slow ones tell
+/// apart in recordings. Every display mode adds its light here, like Glow.
+pub struct MotionBlur {
+    device: ash::Device,
+    allocator: Arc<Mutex<Allocator>>,
+    render_pass: vk::RenderPass,
+    sampler: vk::Sampler,
+    descriptor_set_layout: vk::DescriptorSetLayout,
+    descriptor_pool: vk::DescriptorPool,
+    descriptor_set: vk::DescriptorSet,
+    fade_layout: vk::PipelineLayout,
+    fade_pipeline: vk::Pipeline,
+    composite_layout: vk::PipelineLayout,
+    composite_pipeline: vk::Pipeline,
+    /// Created at the frame's extent by the first pass that needs it.
+    target: Option<AccumulationTarget>,
+    /// The next pass starts from black: after the image is created or blur turns on.
+    needs_clear: bool,
+}
+
+impl MotionBlur {
+    /// Creates the accumulation pass with its fade pipeline, and the composite pipeline
+    /// in `scene_render_pass`.
+    pub fn new(
+        device: ash::Device,
+        allocator: Arc<Mutex<Allocator>>,
+        scene_render_pass: vk::RenderPass,
+    ) -> Self {
+        let render_pass = create_accumulation_render_pass(&device);
+        let sampler_ci = vk::SamplerCreateInfo::default()
+            .mag_filter(vk::Filter::NEAREST)
+            .min_filter(vk::Filter::NEAREST)
+            .address_mode_u(vk::SamplerAddressMode::CLAMP_TO_EDGE)
+            .address_mode_v(vk::SamplerAddressMode::CLAMP_TO_EDGE)
+            .address_mode_w(vk::SamplerAddressMode::CLAMP_TO_EDGE);
+        let sampler = unsafe { device.create_sampler(&sampler_ci, None) }.unwrap();
+        let binding = vk::DescriptorSetLayoutBinding::default()
+            .binding(0)
+            .descriptor_type(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+            .descriptor_count(1)
+            .stage_flags(vk::ShaderStageFlags::FRAGMENT);
+        let bindings = [binding];
+        let layout_ci = vk::DescriptorSetLayoutCreateInfo::default().bindings(&bindings);
+        let descriptor_set_layout =
+            unsafe { device.create_descriptor_set_layout(&layout_ci, None) }.unwrap();
+        let pool_sizes = [vk::DescriptorPoolSize {
+            ty: vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
+            descriptor_count: 1,
+        }];
+        let pool_ci = vk::DescriptorPoolCreateInfo::default()
+            .pool_sizes(&pool_sizes)
+            .max_sets(1);
+        let descriptor_pool = unsafe { device.create_descriptor_pool(&pool_ci, None) }.unwrap();
+        let set_layouts = [descriptor_set_layout];
+        let alloc_ci = vk::DescriptorSetAllocateInfo::default()
+            .descriptor_pool(descriptor_pool)
+            .set_layouts(&set_layouts);
+        let descriptor_set = unsafe { device.allocate_descriptor_sets(&alloc_ci) }.unwrap()[0];
+
+        let vs_spv = include_bytes!(concat!(
+            env!("OUT_DIR"),
+            "/shaders/fullscreen_triangle.vert.spv"
+        ));
+        let push_size = std::mem::size_of::<f32>() as u32;
+        let fade_layout =
+            create_pipeline_layout(&device, push_size, vk::ShaderStageFlags::FRAGMENT, &[]);
+        let fade_pipeline = create_graphics_pipeline(
+            &device,
+            render_pass,
+            fade_layout,
+            vs_spv,
+            include_bytes!(concat!(
+                env!("OUT_DIR"),
+                "/shaders/accumulation_fade.frag.spv"
+            )),
+            &[],
+            &[],
+            vk::PrimitiveTopology::TRIANGLE_LIST,
+            fade_blend(),
+            vk::CullModeFlags::NONE,
+            false,
+        );
+        let composite_layout = create_pipeline_layout(
+            &device,
+            push_size,
+            vk::ShaderStageFlags::FRAGMENT,
+            &set_layouts,
+        );
+        let composite_pipeline = create_graphics_pipeline(
+            &device,
+            scene_render_pass,
+            composite_layout,
+            vs_spv,
+            include_bytes!(concat!(
+                env!("OUT_DIR"),
+                "/shaders/accumulation_composite.frag.spv"
+            )),
+            &[],
+            &[],
+            vk::PrimitiveTopology::TRIANGLE_LIST,
+            additive_blend(),
+            vk::CullModeFlags::NONE,
+            false,
+        );
+
+        Self {
+            device,
+            allocator,
+            render_pass,
+            sampler,
+            descriptor_set_layout,
+            descriptor_pool,
+            descriptor_set,
+            fade_layout,
+            fade_pipeline,
+            composite_layout,
+            composite_pipeline,
+            target: None,
+            needs_clear: true,
+        }
+    }
+
+    /// Render pass of the accumulation, which the particle pipelines drawing into it
+    /// are made for. It has no depth attachment.
+    pub fn render_pass(&self) -> vk::RenderPass {
+        self.render_pass
+    }
+
+    /// Starts the next accumulation from black, dropping the trails drawn so far.
+    pub fn clear(&mut self) {
+        self.needs_clear = true;
+    }
+
+    /// Releases the accumulation image; the next pass recreates it at its extent. The
+    /// GPU must be done with it, as on a swapchain resize.
+    pub fn release_target(&mut self) {
+        if let Some(mut target) = self.target.take() {
+            unsafe { self.device.destroy_framebuffer(target.framebuffer, None) };
+            target.image.destroy(&self.device, &self.allocator);
+        }
+    }
+
+    /// Begins the accumulation pass over `extent` and fades what it holds to
+    /// `retention`. The caller then sets its viewport, draws the particles with the
+    /// accumulation pipelines, and calls [`Self::end`].
+    pub fn begin(&mut self, cb: vk::CommandBuffer, extent: vk::Extent2D, retention: f32) {
+        if self.target.as_ref().is_some_and(|t| t.extent != extent) {
+            unsafe {
+                if let Err(err) = self.device.device_wait_idle() {
+                    eprintln!("MotionBlur::begin device_wait_idle failed: {err:?}");
+                }
+            }
+            self.release_target();
+        }
+        let framebuffer = match &self.target {
+            Some(target) => target.framebuffer,
+            None => self.create_target(extent),
+        };
+        if self.needs_clear {
+            self.record_clear(cb);
+            self.needs_clear = false;
+        }
+        let area = vk::Rect2D {
+            offset: vk::Offset2D::default(),
+            extent,
+        };
+        let begin_info = vk::RenderPassBeginInfo::default()
+            .render_pass(self.render_pass)
+            .framebuffer(framebuffer)
+            .render_area(area);
+        let viewport = vk::Viewport {
+            x: 0.0,
+            y: 0.0,
+            width: extent.width as f32,
+            height: extent.height as f32,
+            min_depth: 0.0,
+            max_depth: 1.0,
+        };
+        unsafe {
+            self.device
+                .cmd_begin_render_pass(cb, &begin_info, vk::SubpassContents::INLINE);
+            self.device.cmd_set_viewport(cb, 0, &[viewport]);
+            self.device.cmd_set_scissor(cb, 0, &[area]);
+            self.device
+                .cmd_bind_pipeline(cb, vk::PipelineBindPoint::GRAPHICS, self.fade_pipeline);
+            self.device.cmd_push_constants(
+                cb,
+                self.fade_layout,
+                vk::ShaderStageFlags::FRAGMENT,
+                0,
+                bytemuck::bytes_of(&retention),
+            );
+            self.device.cmd_draw(cb, 3, 1, 0, 0);
+        }
+    }
+
+    /// Ends the accumulation pass, leaving the image for [`Self::composite`] to read.
+    pub fn end(&self, cb: vk::CommandBuffer) {
+        unsafe { self.device.cmd_end_render_pass(cb) };
+    }
+
+    /// Adds the accumulation scaled by `gain` over the current viewport of the scene
+    /// pass, texel for texel.
+    pub fn composite(&self, cb: vk::CommandBuffer, gain: f32) {
+        if self.target.is_none() {
+            return;
+        }
+        unsafe {
+            self.device.cmd_bind_pipeline(
+                cb,
+                vk::PipelineBindPoint::GRAPHICS,
+                self.composite_pipeline,
+            );
+            self.device.cmd_bind_descriptor_sets(
+                cb,
+                vk::PipelineBindPoint::GRAPHICS,
+                self.composite_layout,
+                0,
+                &[self.descriptor_set],
+                &[],
+            );
+            self.device.cmd_push_constants(
+                cb,
+                self.composite_layout,
+                vk::ShaderStageFlags::FRAGMENT,
+                0,
+                bytemuck::bytes_of(&gain),
+            );
+            self.device.cmd_draw(cb, 3, 1, 0, 0);
+        }
+    }
+
+    /// Creates the accumulation image and framebuffer at `extent`, points the composite
+    /// set at the image, and returns the framebuffer.
+    fn create_target(&mut self, extent: vk::Extent2D) -> vk::Framebuffer {
+        let image = AllocatedImage::new(
+            &self.device,
+            &self.allocator,
+            extent.width,
+            extent.height,
+            ACCUMULATION_FORMAT,
+            vk::ImageUsageFlags::COLOR_ATTACHMENT
+                | vk::ImageUsageFlags::SAMPLED
+                | vk::ImageUsageFlags::TRANSFER_DST,
+            vk::ImageAspectFlags::COLOR,
+            "motion-blur-accumulation",
+        );
+        let attachments = [image.view];
+        let framebuffer_ci = vk::FramebufferCreateInfo::default()
+            .render_pass(self.render_pass)
+            .attachments(&attachments)
+            .width(extent.width)
+            .height(extent.height)
+            .layers(1);
+        let framebuffer = unsafe { self.device.create_framebuffer(&framebuffer_ci, None) }.unwrap();
+        let image_info = [vk::DescriptorImageInfo::default()
+            .sampler(self.sampler)
+            .image_view(image.view)
+            .image_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)];
+        let write = vk::WriteDescriptorSet::default()
+            .dst_set(self.descriptor_set)
+            .dst_binding(0)
+            .descriptor_type(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+            .image_info(&image_info);
+        unsafe { self.device.update_descriptor_sets(&[write], &[]) };
+        self.target = Some(AccumulationTarget {
+            image,
+            framebuffer,
+            extent,
+        });
+        self.needs_clear = true;
+        framebuffer
+    }
+
+    /// Clears the accumulation image to black and leaves it in the layout the pass
+    /// starts from. Its earlier contents, if any, are discarded.
+    fn record_clear(&self, cb: vk::CommandBuffer) {
+        let Some(target) = &self.target else {
+            return;
+        };
+        let range = vk::ImageSubresourceRange {
+            aspect_mask: vk::ImageAspectFlags::COLOR,
+            base_mip_level: 0,
+            level_count: 1,
+            base_array_layer: 0,
+            layer_count: 1,
+        };
+        let to_transfer = vk::ImageMemoryBarrier::default()
+            .src_access_mask(vk::AccessFlags::empty())
+            .dst_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+            .old_layout(vk::ImageLayout::UNDEFINED)
+            .new_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .image(target.image.image)
+            .subresource_range(range);
+        let to_pass = vk::ImageMemoryBarrier::default()
+            .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+            .dst_access_mask(
+                vk::AccessFlags::COLOR_ATTACHMENT_READ | vk::AccessFlags::COLOR_ATTACHMENT_WRITE,
+            )
+            .old_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+            .new_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .image(target.image.image)
+            .subresource_range(range);
+        let black = vk::ClearColorValue { float32: [0.0; 4] };
+        unsafe {
+            // The previous frame's composite may still be reading the image.
+            self.device.cmd_pipeline_barrier(
+                cb,
+                vk::PipelineStageFlags::FRAGMENT_SHADER,
+                vk::PipelineStageFlags::TRANSFER,
+                vk::DependencyFlags::empty(),
+                &[],
+                &[],
+                &[to_transfer],
+            );
+            self.device.cmd_clear_color_image(
+                cb,
+                target.image.image,
+                vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                &black,
+                &[range],
+            );
+            self.device.cmd_pipeline_barrier(
+                cb,
+                vk::PipelineStageFlags::TRANSFER,
+                vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+                vk::DependencyFlags::empty(),
+                &[],
+                &[],
+                &[to_pass],
+            );
+        }
+    }
+}
+
+impl Drop for MotionBlur {
+    fn drop(&mut self) {
+        self.release_target();
+        unsafe {
+            self.device.destroy_pipeline(self.fade_pipeline, None);
+            self.device.destroy_pipeline(self.composite_pipeline, None);
+            self.device.destroy_pipeline_layout(self.fade_layout, None);
+            self.device
+                .destroy_pipeline_layout(self.composite_layout, None);
+            self.device
+                .destroy_descriptor_pool(self.descriptor_pool, None);
+            self.device
+                .destroy_descriptor_set_layout(self.descriptor_set_layout, None);
+            self.device.destroy_sampler(self.sampler, None);
+            self.device.destroy_render_pass(self.render_pass, None);
+        }
+    }
+}
+
+/// Blend state of the fade: the destination is multiplied by the source alpha.
+fn fade_blend() -> vk::PipelineColorBlendAttachmentState {
+    vk::PipelineColorBlendAttachmentState::default()
+        .color_write_mask(vk::ColorComponentFlags::RGBA)
+        .blend_enable(true)
+        .src_color_blend_factor(vk::BlendFactor::ZERO)
+        .dst_color_blend_factor(vk::BlendFactor::SRC_ALPHA)
+        .color_blend_op(vk::BlendOp::ADD)
+        .src_alpha_blend_factor(vk::BlendFactor::ZERO)
+        .dst_alpha_blend_factor(vk::BlendFactor::SRC_ALPHA)
+        .alpha_blend_op(vk::BlendOp::ADD)
+}
+
+/// Creates the accumulation pass: one color attachment that keeps its contents and is
+/// sampled between passes.
+fn create_accumulation_render_pass(device: &ash::Device) -> vk::RenderPass {
+    let color = vk::AttachmentDescription::default()
+        .format(ACCUMULATION_FORMAT)
+        .samples(vk::SampleCountFlags::TYPE_1)
+        .load_op(vk::AttachmentLoadOp::LOAD)
+        .store_op(vk::AttachmentStoreOp::STORE)
+        .stencil_load_op(vk::AttachmentLoadOp::DONT_CARE)
+        .stencil_store_op(vk::AttachmentStoreOp::DONT_CARE)
+        .initial_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+        .final_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+    let color_refs = [vk::AttachmentReference {
+        attachment: 0,
+        layout: vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+    }];
+    let subpass = vk::SubpassDescription::default()
+        .pipeline_bind_point(vk::PipelineBindPoint::GRAPHICS)
+        .color_attachments(&color_refs);
+
+    // The previous frame's composite reads the image before this pass writes it, and
+    // this frame's composite reads it after.
+    let dependencies = [
+        vk::SubpassDependency::default()
+            .src_subpass(vk::SUBPASS_EXTERNAL)
+            .dst_subpass(0)
+            .src_stage_mask(vk::PipelineStageFlags::FRAGMENT_SHADER)
+            .src_access_mask(vk::AccessFlags::empty())
+            .dst_stage_mask(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
+            .dst_access_mask(
+                vk::AccessFlags::COLOR_ATTACHMENT_READ | vk::AccessFlags::COLOR_ATTACHMENT_WRITE,
+            ),
+        vk::SubpassDependency::default()
+            .src_subpass(0)
+            .dst_subpass(vk::SUBPASS_EXTERNAL)
+            .src_stage_mask(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
+            .src_access_mask(vk::AccessFlags::COLOR_ATTACHMENT_WRITE)
+            .dst_stage_mask(vk::PipelineStageFlags::FRAGMENT_SHADER)
+            .dst_access_mask(vk::AccessFlags::SHADER_READ),
+    ];
+
+    let attachments = [color];
+    let subpasses = [subpass];
+    let ci = vk::RenderPassCreateInfo::default()
+        .attachments(&attachments)
+        .subpasses(&subpasses)
+        .dependencies(&dependencies);
+    unsafe { device.create_render_pass(&ci, None) }.unwrap()
+}
@@ -1,3 +1,5 @@
+use crate::orbital_elements::{OrbitalElements, OrbitingBody};
+use crate::palette::particle_palette_color;
 use crate::simulation::{Particle, SimulationNormal};
 use crate::solar_system_data::{UpdateDataError, update_datafiles_with_log};
 use glam::DVec3;
@@ -24,7 +26,8 @@ pub const EARTH_RADIUS: f64 = 6.371e6;
 /// Minimum allowed world scale in meters (0.01 fm; values at or below this are clamped).
 pub const MIN_WORLD_SCALE: f64 = 1e-17;
 
-/// Default particle colors used by random batch generators (Red, Blue, Yellow, Purple, Cyan).
+/// Basic colors offered for single particles and orbiting bodies (Red, Blue, Yellow, Purple,
+/// Cyan).
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
 pub enum ParticleBasicColor {
     #[default]
@@ -79,6 +82,186 @@ pub fn clamp_world_scale(scale: f64) -> f64 {
     }
 }
 
+/// Salpeter (1955) IMF slope `α` in `dN/dm ∝ m^-α`.
+const SALPETER_SLOPE: f64 = 2.35;
+/// Kroupa (2001) IMF slopes below and above the break mass.
+const KROUPA_SLOPES: (f64, f64) = (1.3, 2.3);
+/// Kroupa IMF break mass in solar masses.
+const KROUPA_BREAK_SOLAR_MASSES: f64 = 0.5;
+
+/// Distribution used to draw particle masses within a cluster's mass range.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum MassFunction {
+    #[default]
+    Uniform,
+    LogUniform,
+    Salpeter,
+    Kroupa,
+}
+
+impl MassFunction {
+    /// All mass functions in UI display order.
+    pub const ALL: [Self; 4] = [
+        Self::Uniform,
+        Self::LogUniform,
+        Self::Salpeter,
+        Self::Kroupa,
+    ];
+
+    /// Draws one mass in `[lower, upper)`; non-uniform shapes require `lower > 0`.
+    /// Uniform draws consume the generator exactly as the flat mass draw always has, so a
+    /// seeded generator still gives the same scene.
+    ///
+    /// `solar_mass` is one solar mass in the same units, used to place the Kroupa break.
+    pub fn sample(self, lower: f64, upper: f64, solar_mass: f64, rng: &mut impl Rng) -> f64 {
+        match self {
+            Self::Uniform => rng.random_range(lower..upper),
+            // Power laws are undefined at zero mass, so fall back to a flat draw.
+            _ if lower <= 0.0 => rng.random_range(lower..upper),
+            Self::LogUniform => sample_power_law(1.0, lower, upper, rng.random()),
+            Self::Salpeter => sample_power_law(SALPETER_SLOPE, lower, upper, rng.random()),
+            Self::Kroupa => {
+                let u = rng.random::<f64>();
+                let (low_slope, high_slope) = KROUPA_SLOPES;
+                let knee = KROUPA_BREAK_SOLAR_MASSES * solar_mass;
+                if upper <= knee {
+                    return sample_power_law(low_slope, lower, upper, u);
+                }
+                if lower >= knee {
+                    return sample_power_law(high_slope, lower, upper, u);
+                }
+                // Continuous at the knee: m^-α₁ below, knee^(α₂-α₁)·m^-α₂ above.
+                let low_weight = power_law_integral(low_slope, lower, knee);
+                let high_weight =
+                    knee.powf(high_slope - low_slope) * power_law_integral(high_slope, knee, upper);
+                let split = low_weight / (low_weight + high_weight);
+                if u < split {
+                    sample_power_law(low_slope, lower, knee, u / split)
+                } else {
+                    sample_power_law(high_slope, knee, upper, (u - split) / (1.0 - split))
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for MassFunction {
+    /// Formats mass-function names for UI selection controls.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Uniform => write!(f, "Uniform"),
+            Self::LogUniform => write!(f, "Log-Uniform"),
+            Self::Salpeter => write!(f, "Salpeter"),
+            Self::Kroupa => write!(f, "Kroupa"),
+        }
+    }
+}
+
+/// Returns `∫ m^-α dm` over `[lower, upper]`.
+fn power_law_integral(alpha: f64, lower: f64, upper: f64) -> f64 {
+    if (alpha - 1.0).abs() < 1e-12 {
+        (upper / lower).ln()
+    } else {
+        let k = 1.0 - alpha;
+        (upper.powf(k) - lower.powf(k)) / k
+    }
+}
+
+/// Inverts the CDF of `dN/dm ∝ m^-α` on `[lower, upper]` at quantile `u`.
+fn sample_power_law(alpha: f64, lower: f64, upper: f64, u: f64) -> f64 {
+    if (alpha - 1.0).abs() < 1e-12 {
+        lower * (upper / lower).powf(u)
+    } else {
+        let k = 1.0 - alpha;
+        let (lo, hi) = (lower.powf(k), upper.powf(k));
+        (lo + (hi - lo) * u).powf(1.0 / k).clamp(lower, upper)
+    }
+}
+
+/// Self-gravitational energy coefficient of a uniform cube: `W = -k·GM²/L`.
+const UNIFORM_CUBE_POTENTIAL_COEFFICIENT: f64 = 0.941_156;
+
+/// Optional kinematic adjustments applied on top of random cluster sampling.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RandomClusterOptions {
+    /// Replaces the uniform velocity draw with an isotropic Gaussian whose dispersion
+    /// satisfies the virial theorem (2K + W = 0) for the uniform-density shape.
+    pub virial_equilibrium: bool,
+    /// Bulk velocity in m/s added to every particle after sampling (e.g. merger approach).
+    pub bulk_velocity: DVec3,
+    /// Peebles spin parameter `λ = J|E|^½ / (G M^{5/2})` injected as solid-body rotation.
+    pub spin: f64,
+    /// Rotation axis for `spin` (normalized on use; zero disables rotation).
+    pub spin_axis: DVec3,
+    /// Distribution used to draw masses within the cluster's mass range.
+    pub mass_function: MassFunction,
+}
+
+impl Default for RandomClusterOptions {
+    /// Uniform masses with no virial draw, bulk motion, or spin; rotation axis defaults to +Y.
+    fn default() -> Self {
+        Self {
+            virial_equilibrium: false,
+            bulk_velocity: DVec3::ZERO,
+            spin: 0.0,
+            spin_axis: DVec3::Y,
+            mass_function: MassFunction::Uniform,
+        }
+    }
+}
+
+/// Returns the self-gravitational potential energy of a uniform sphere, `-3GM²/(5R)`.
+pub fn uniform_sphere_potential_energy(total_mass: f64, radius: f64) -> f64 {
+    if radius <= 0.0 {
+        return 0.0;
+    }
+    -0.6 * crate::simulation::G * total_mass * total_mass / radius
+}
+
+/// Returns the self-gravitational potential energy of a uniform cube of side `side`.
+pub fn uniform_cube_potential_energy(total_mass: f64, side: f64) -> f64 {
+    if side <= 0.0 {
+        return 0.0;
+    }
+    -UNIFORM_CUBE_POTENTIAL_COEFFICIENT * crate::simulation::G * total_mass * total_mass / side
+}
+
+/// Returns the 1D velocity dispersion in virial equilibrium for a uniform sphere.
+///
+/// With `W = -3GM²/(5R)` and `2K + W = 0`, each Cartesian component has `σ² = GM/(5R)`.
+pub fn virial_velocity_dispersion(total_mass: f64, radius: f64) -> f64 {
+    virial_dispersion_from_potential(
+        total_mass,
+        uniform_sphere_potential_energy(total_mass, radius),
+    )
+}
+
+/// Returns the 1D dispersion `σ² = |W|/(3M)` that puts a cluster in virial equilibrium.
+fn virial_dispersion_from_potential(total_mass: f64, potential_energy: f64) -> f64 {
+    if total_mass <= 0.0 {
+        return 0.0;
+    }
+    (potential_energy.abs() / (3.0 * total_mass)).sqrt()
+}
+
+/// Returns the solid-body angular speed that gives a cluster the Peebles spin `lambda`.
+///
+/// `moment_of_inertia` is about the spin axis and `energy` is the total energy of the
+/// non-rotating cluster; the rotational kinetic energy added by the spin is neglected.
+pub fn angular_speed_for_spin(
+    lambda: f64,
+    total_mass: f64,
+    moment_of_inertia: f64,
+    energy: f64,
+) -> f64 {
+    if lambda == 0.0 || moment_of_inertia <= 0.0 || energy == 0.0 {
+        return 0.0;
+    }
+    let angular_momentum =
+        lambda * crate::simulation::G * total_mass.powf(2.5) / energy.abs().sqrt();
+    angular_momentum / moment_of_inertia
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub enum ObjectInput {
     RandomSphere {
@@ -86,12 +269,14 @@ pub enum ObjectInput {
         radius: f64,
         mass_range: (f64, f64),
         velocity_std: f64,
+        options: RandomClusterOptions,
     },
     RandomCube {
         scale: f64,
         cube_size: f64,
         mass_range: (f64, f64),
         velocity_std: f64,
+        options: RandomClusterOptions,
     },
     SpiralDisk {
         scale: f64,
@@ -125,6 +310,11 @@ pub enum ObjectInput {
         velocity: DVec3,
         color: ParticleBasicColor,
     },
+    KeplerianSystem {
+        scale: f64,
+        primary_mass: f64,
+        bodies: Vec<OrbitingBody>,
+    },
 }
 
 impl std::fmt::Display for ObjectInput {
@@ -138,6 +328,7 @@ impl std::fmt::Display for ObjectInput {
             ObjectInput::SatelliteOrbit { .. } => write!(f, "Satellite Orbit"),
             ObjectInput::EllipticalOrbit { .. } => write!(f, "Elliptical Orbit"),
             ObjectInput::SingleParticle { .. } => write!(f, "Single Particle"),
+            ObjectInput::KeplerianSystem { .. } => write!(f, "Keplerian System"),
         }
     }
 }
@@ -149,6 +340,7 @@ pub enum ObjectInputType {
     SpiralDisk,
     EllipticalOrbit,
     SingleParticle,
+    KeplerianSystem,
 }
 
 impl Default for ObjectInputType {
@@ -167,18 +359,20 @@ impl std::fmt::Display for ObjectInputType {
             ObjectInputType::SpiralDisk => write!(f, "Spiral Disk"),
             ObjectInputType::EllipticalOrbit => write!(f, "Elliptical Orbit"),
             ObjectInputType::SingleParticle => write!(f, "Single Particle"),
+            ObjectInputType::KeplerianSystem => write!(f, "Keplerian System"),
         }
     }
 }
 
 impl ObjectInputType {
     /// All add-type variants in UI display order.
-    pub const ALL: [Self; 5] = [
+    pub const ALL: [Self; 6] = [
         Self::RandomSphere,
         Self::RandomCube,
         Self::SpiralDisk,
         Self::EllipticalOrbit,
         Self::SingleParticle,
+        Self::KeplerianSystem,
     ];
 
     /// Returns whether the add-particle-count slider applies to this type.
@@ -197,6 +391,7 @@ impl ObjectInputType {
             ObjectInputType::SpiralDisk => 1e7,
             ObjectInputType::EllipticalOrbit => 1.5e11,
             ObjectInputType::SingleParticle => 1e10,
+            ObjectInputType::KeplerianSystem => 1.5e11,
         }
     }
 
@@ -212,12 +407,14 @@ impl ObjectInputType {
                 radius: 1e10 * factor,
                 mass_range: (1e29 * factor_cubed, 1e31 * factor_cubed),
                 velocity_std: 1e6 * factor,
+                options: RandomClusterOptions::default(),
             },
             ObjectInputType::RandomCube => ObjectInput::RandomCube {
                 scale,
                 cube_size: 2e10 * factor,
                 mass_range: (1e29 * factor_cubed, 1e31 * factor_cubed),
                 velocity_std: 1e6 * factor,
+                options: RandomClusterOptions::default(),
             },
             ObjectInputType::SpiralDisk => ObjectInput::SpiralDisk {
                 scale,
@@ -238,6 +435,51 @@ impl ObjectInputType {
                 velocity: DVec3::new(0.0, 0.0, 1e6 * factor),
                 color: ParticleBasicColor::default(),
             },
+            ObjectInputType::KeplerianSystem => ObjectInput::KeplerianSystem {
+                scale,
+                primary_mass: MASS_SUN * factor_cubed,
+                bodies: default_keplerian_bodies()
+                    .into_iter()
+                    .map(|body| body.scaled(factor))
+                    .collect(),
+            },
+        }
+    }
+}
+
+/// Default Keplerian bodies: an Earth-like orbit and an inclined eccentric companion.
+fn default_keplerian_bodies() -> Vec<OrbitingBody> {
+    vec![
+        OrbitingBody {
+            mass: MASS_EARTH,
+            elements: OrbitalElements::default(),
+            color: ParticleBasicColor::Blue,
+        },
+        OrbitingBody {
+            mass: MASS_MARS,
+            elements: OrbitalElements {
+                semi_major_axis: 2.5e11,
+                eccentricity: 0.6,
+                inclination: 20f64.to_radians(),
+                ascending_node: 45f64.to_radians(),
+                argument_of_periapsis: 90f64.to_radians(),
+                true_anomaly: 0.0,
+            },
+            color: ParticleBasicColor::Red,
+        },
+    ]
+}
+
+impl OrbitingBody {
+    /// Rescales length and mass so the body keeps its shape under a base-scale change.
+    fn scaled(self, factor: f64) -> Self {
+        Self {
+            mass: self.mass * factor * factor * factor,
+            elements: OrbitalElements {
+                semi_major_axis: self.elements.semi_major_axis * factor,
+                ..self.elements
+            },
+            color: self.color,
         }
     }
 }
@@ -248,7 +490,33 @@ pub enum SolarSystemBuildError {
     Aborted,
 }
 
-/// Builds Solar System particles with progress logging and cooperative abort.
+/// Solar System particles and the name of each body, in the same order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolarSystemBodies {
+    pub particles: Vec<Particle>,
+    pub names: Vec<&'static str>,
+}
+
+/// Returns the label shown for a Solar System body; the Earth–Moon barycenter stands in
+/// for Earth.
+fn solar_system_body_name(body: SolarSystem) -> &'static str {
+    match body {
+        SolarSystem::Mercury => "Mercury",
+        SolarSystem::Venus => "Venus",
+        SolarSystem::EMB => "Earth",
+        SolarSystem::Mars => "Mars",
+        SolarSystem::Jupiter => "Jupiter",
+        SolarSystem::Saturn => "Saturn",
+        SolarSystem::Uranus => "Uranus",
+        SolarSystem::Neptune => "Neptune",
+        SolarSystem::Pluto => "Pluto",
+        SolarSystem::Sun => "Sun",
+        _ => "",
+    }
+}
+
+/// Builds Solar System particles and body names with progress logging and cooperative
+/// abort.
 pub fn build_solar_system_particles(
     scale: f64,
     start_year: i32,
@@ -257,7 +525,7 @@ pub fn build_solar_system_particles(
     start_hour: i32,
     log: &impl Fn(&str),
     abort: &AtomicBool,
-) -> Result<Vec<Particle>, SolarSystemBuildError> {
+) -> Result<SolarSystemBodies, SolarSystemBuildError> {
     let correct = Correct::new(scale);
     match update_datafiles_with_log(log, abort) {
         Ok(()) => {}
@@ -272,6 +540,7 @@ pub fn build_solar_system_particles(
     let time = Instant::from_datetime(start_year, start_month, start_day, start_hour, 0, 0.0)
         .unwrap_or_else(|_| Instant::from_datetime(2000, 1, 1, 12, 0, 0.0).unwrap());
     let mut particles: Vec<Particle> = vec![];
+    let mut names = vec![];
     let bodies = vec![
         SolarSystem::Mercury,
         SolarSystem::Venus,
@@ -330,18 +599,19 @@ pub fn build_solar_system_particles(
                         _ => [1.0, 1.0, 1.0, 1.0],
                     },
                 ));
+                names.push(solar_system_body_name(body));
             }
             Err(e) => {
                 log(&format!("Error for {:?}: {}", body, e));
             }
         }
     }
-    Ok(particles)
+    Ok(SolarSystemBodies { particles, names })
 }
 
 /// Provides a small deterministic solar-system particle set when ephemeris data is unavailable.
-fn get_solar_system_fallback_particles(correct: &Correct) -> Vec<Particle> {
-    vec![
+fn get_solar_system_fallback_particles(correct: &Correct) -> SolarSystemBodies {
+    let particles = vec![
         Particle::from_kinematics(
             DVec3::ZERO,
             DVec3::ZERO,
@@ -376,7 +646,11 @@ fn get_solar_system_fallback_particles(correct: &Correct) -> Vec<Particle> {
             MASS_MERCURY * correct.kg,
             [0.5, 0.5, 0.5, 1.0], // Grayish color
         ),
-    ]
+    ];
+    SolarSystemBodies {
+        particles,
+        names: vec!["Sun", "Earth", "Mars", "Venus", "Mercury"],
+    }
 }
 
 impl ObjectInput {
@@ -390,6 +664,7 @@ impl ObjectInput {
             ObjectInput::SatelliteOrbit { scale, .. } => *scale,
             ObjectInput::EllipticalOrbit { scale, .. } => *scale,
             ObjectInput::SingleParticle { scale, .. } => *scale,
+            ObjectInput::KeplerianSystem { scale, .. } => *scale,
         })
     }
 
@@ -409,6 +684,21 @@ impl ObjectInput {
                 planetary_distance, ..
             } => planetary_distance * correct.m,
             ObjectInput::SingleParticle { position, .. } => position.length() * correct.m,
+            ObjectInput::KeplerianSystem { bodies, .. } => {
+                bodies
+                    .iter()
+                    .map(|body| {
+                        let e = body.elements.eccentricity;
+                        let a = body.elements.semi_major_axis;
+                        if e < 1.0 {
+                            a * (1.0 + e)
+                        } else {
+                            body.elements.periapsis_distance()
+                        }
+                    })
+                    .fold(0.0, f64::max)
+                    * correct.m
+            }
         }
     }
 
@@ -483,6 +773,7 @@ impl ObjectInput {
                 radius,
                 mass_range,
                 velocity_std,
+                options,
             } => {
                 let correct = Correct::new(*scale);
                 let pos_max = radius * correct.m;
@@ -493,7 +784,7 @@ impl ObjectInput {
                 } else {
                     mass_range.1 * correct.kg
                 };
-                let particles = (0..particle_count)
+                let mut particles: Vec<Particle> = (0..particle_count)
                     .map(|i| {
                         let pos = Self::position_in_sphere(DVec3::ZERO, pos_max, &mut rng);
                         let vel = DVec3 {
@@ -501,18 +792,31 @@ impl ObjectInput {
                             y: rng.random_range(-speed_max..speed_max),
                             z: rng.random_range(-speed_max..speed_max),
                         };
-                        let mass = rng.random_range(mass_lower..mass_upper);
+                        let mass = options.mass_function.sample(
+                            mass_lower,
+                            mass_upper,
+                            MASS_SUN * correct.kg,
+                            &mut rng,
+                        );
                         let color = Self::basic_particle_color(i);
                         Particle::from_kinematics(pos, vel, mass, color)
                     })
                     .collect();
-                SimulationNormal { particles }
+                Self::apply_cluster_options(
+                    &mut particles,
+                    options,
+                    |mass| uniform_sphere_potential_energy(mass, pos_max),
+                    &correct,
+                    &mut rng,
+                );
+                SimulationNormal::new(particles)
             }
             ObjectInput::RandomCube {
                 scale,
                 cube_size,
                 mass_range,
                 velocity_std,
+                options,
             } => {
                 let correct = Correct::new(*scale);
                 let pos_max = cube_size * 0.5 * correct.m;
@@ -523,7 +827,7 @@ impl ObjectInput {
                 } else {
                     mass_range.1 * correct.kg
                 };
-                let particles = (0..particle_count)
+                let mut particles: Vec<Particle> = (0..particle_count)
                     .map(|i| {
                         let pos = DVec3 {
                             x: rng.random_range(-pos_max..pos_max),
@@ -535,12 +839,24 @@ impl ObjectInput {
                             y: rng.random_range(-speed_max..speed_max),
                             z: rng.random_range(-speed_max..speed_max),
                         };
-                        let mass = rng.random_range(mass_lower..mass_upper);
+                        let mass = options.mass_function.sample(
+                            mass_lower,
+                            mass_upper,
+                            MASS_SUN * correct.kg,
+                            &mut rng,
+                        );
                         let color = Self::basic_particle_color(i);
                         Particle::from_kinematics(pos, vel, mass, color)
                     })
                     .collect();
-                SimulationNormal { particles }
+                Self::apply_cluster_options(
+                    &mut particles,
+                    options,
+                    |mass| uniform_cube_potential_energy(mass, 2.0 * pos_max),
+                    &correct,
+                    &mut rng,
+                );
+                SimulationNormal::new(particles)
             }
             ObjectInput::SpiralDisk {
                 scale,
@@ -576,7 +892,7 @@ impl ObjectInput {
                         Particle::from_kinematics(pos, vel, mass, color)
                     })
                     .collect();
-                SimulationNormal { particles }
+                SimulationNormal::new(particles)
             }
             ObjectInput::SolarSystem {
                 scale,
@@ -586,7 +902,7 @@ impl ObjectInput {
                 start_hour,
             } => {
                 static NO_ABORT: AtomicBool = AtomicBool::new(false);
-                let particles = build_solar_system_particles(
+                let bodies = build_solar_system_particles(
                     *scale,
                     *start_year,
                     *start_month,
@@ -596,7 +912,7 @@ impl ObjectInput {
                     &NO_ABORT,
                 )
                 .unwrap_or_else(|_| get_solar_system_fallback_particles(&Correct::new(*scale)));
-                SimulationNormal { particles }
+                SimulationNormal::new(bodies.particles)
             }
             ObjectInput::SatelliteOrbit {
                 scale,
@@ -639,7 +955,7 @@ impl ObjectInput {
                         [1.0, 1.0, 1.0, 1.0],
                     ));
                 }
-                SimulationNormal { particles }
+                SimulationNormal::new(particles)
             }
             ObjectInput::EllipticalOrbit {
                 scale,
@@ -667,7 +983,7 @@ impl ObjectInput {
                         [0.2, 0.5, 1.0, 1.0], // Blue
                     ),
                 ];
-                SimulationNormal { particles }
+                SimulationNormal::new(particles)
             }
             ObjectInput::SingleParticle {
                 scale,
@@ -683,15 +999,119 @@ impl ObjectInput {
                     *mass * correct.kg,
                     color.rgba(),
                 )];
-                SimulationNormal { particles }
+                SimulationNormal::new(particles)
+            }
+            ObjectInput::KeplerianSystem {
+                scale,
+                primary_mass,
+                bodies,
+            } => {
+                let correct = Correct::new(*scale);
+                let mut particles = Vec::with_capacity(1 + bodies.len());
+                particles.push(Particle::from_kinematics(
+                    DVec3::ZERO,
+                    DVec3::ZERO,
+                    *primary_mass * correct.kg,
+                    [1.0, 1.0, 0.0, 1.0], // Yellow
+                ));
+                for body in bodies {
+                    let mu = crate::simulation::G * (*primary_mass + body.mass);
+                    let Some((position, velocity)) = body.elements.to_state_vectors(mu) else {
+                        continue;
+                    };
+                    particles.push(Particle::from_kinematics(
+                        position * correct.m,
+                        velocity * correct.m,
+                        body.mass * correct.kg,
+                        body.color.rgba(),
+                    ));
+                }
+                // Give the primary its reflex motion so the centre of mass starts at rest
+                // instead of drifting with the bodies' momentum.
+                let total_mass: f64 = particles.iter().map(|p| p.mass).sum();
+                if total_mass > 0.0 {
+                    let momentum: DVec3 = particles.iter().map(|p| p.velocity * p.mass).sum();
+                    let drift = momentum / total_mass;
+                    for particle in particles.iter_mut() {
+                        particle.velocity -= drift;
+                    }
+                }
+                SimulationNormal::new(particles)
             }
         };
         sim
     }
 
-    /// Returns one of the basic particle colors by index.
+    /// Applies virial velocities, spin, and bulk motion to a freshly sampled random cluster.
+    ///
+    /// `potential_energy` maps the sampled total mass to the cluster's self-gravitational
+    /// energy in simulation units.
+    fn apply_cluster_options(
+        particles: &mut [Particle],
+        options: &RandomClusterOptions,
+        potential_energy: impl Fn(f64) -> f64,
+        correct: &Correct,
+        rng: &mut impl Rng,
+    ) {
+        if particles.is_empty() {
+            return;
+        }
+        let total_mass: f64 = particles.iter().map(|p| p.mass).sum();
+        let potential = potential_energy(total_mass);
+        if options.virial_equilibrium {
+            let sigma = virial_dispersion_from_potential(total_mass, potential);
+            if sigma > 0.0 {
+                let normal = rand_distr::Normal::new(0.0, sigma).unwrap();
+                for particle in particles.iter_mut() {
+                    particle.velocity =
+                        DVec3::new(normal.sample(rng), normal.sample(rng), normal.sample(rng));
+                }
+                // Remove sampling drift so the cluster's centre of mass starts at rest.
+                let momentum: DVec3 = particles.iter().map(|p| p.velocity * p.mass).sum();
+                let drift = momentum / total_mass;
+                for particle in particles.iter_mut() {
+                    particle.velocity -= drift;
+                }
+            }
+        }
+        let axis = options.spin_axis.normalize_or_zero();
+        if options.spin != 0.0 && axis != DVec3::ZERO {
+            let center: DVec3 =
+                particles.iter().map(|p| p.position * p.mass).sum::<DVec3>() / total_mass;
+            let moment_of_inertia: f64 = particles
+                .iter()
+                .map(|p| {
+                    p.mass
+                        * (p.position - center)
+                            .reject_from_normalized(axis)
+                            .length_squared()
+                })
+                .sum();
+            let kinetic: f64 = particles
+                .iter()
+                .map(|p| 0.5 * p.mass * p.velocity.length_squared())
+                .sum();
+            let omega = angular_speed_for_spin(
+                options.spin,
+                total_mass,
+                moment_of_inertia,
+                kinetic + potential,
+            ) * axis;
+            for particle in particles.iter_mut() {
+                particle.velocity += omega.cross(particle.position - center);
+            }
+        }
+        let bulk = options.bulk_velocity * correct.m;
+        if bulk != DVec3::ZERO {
+            for particle in particles.iter_mut() {
+                particle.velocity += bulk;
+            }
+        }
+    }
+
+    /// Returns the `index`-th color of the active particle palette.
     fn basic_particle_color(index: u32) -> [f32; 4] {
-        ParticleBasicColor::ALL[(index as usize) % ParticleBasicColor::ALL.len()].rgba()
+        particle_palette_color(index as usize)
     }
 
     /// Samples a uniformly distributed position inside a sphere around the given center.
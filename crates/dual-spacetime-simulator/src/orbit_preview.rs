@@ -0,0 +1,87 @@
+use crate::maneuver::OrbitalFrame;
+use glam::DVec3;
+
+/// Frames between refreshes of the predicted orbit.
+pub const ORBIT_PREVIEW_INTERVAL: i64 = 10;
+/// Cap on integration steps per prediction, so long burn delays stay cheap.
+pub const ORBIT_PREVIEW_MAX_STEPS: usize = 2000;
+/// Step length as a fraction of the local dynamical time `√(r³/μ)`; about 300 steps per
+/// circular orbit, shorter near periapsis.
+const STEP_FRACTION: f64 = 0.02;
+/// Unbound paths are drawn until they are this many times farther out than at the start.
+const UNBOUND_EXTENT: f64 = 10.0;
+
+/// Burn folded into a prediction: `delta_v` in m/s along prograde, radial-out, and
+/// normal at `delay` seconds from now, like [`crate::maneuver::Maneuver`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PreviewBurn {
+    pub delay: f64,
+    pub delta_v: DVec3,
+}
+
+/// Predicts the two-body path of a body at `position`/`velocity` relative to its primary
+/// (m, m/s, `mu` in m³/s²), applying `burn` on the way. The path runs up to the burn
+/// and then over one full orbit, or outward until the body is well away for unbound
+/// orbits. Returns positions relative to the primary in m, starting at `position`.
+pub fn predict_orbit(
+    position: DVec3,
+    velocity: DVec3,
+    mu: f64,
+    burn: Option<PreviewBurn>,
+) -> Vec<DVec3> {
+    if !(mu.is_finite() && mu > 0.0) || position.length_squared() == 0.0 {
+        return Vec::new();
+    }
+    let mut state = (position, velocity);
+    let mut points = vec![position];
+    if let Some(burn) = burn {
+        integrate(&mut state, mu, &mut points, |elapsed, _| {
+            (elapsed < burn.delay).then_some(burn.delay - elapsed)
+        });
+        if let Some(frame) = OrbitalFrame::from_relative_state(state.0, state.1) {
+            state.1 += frame.to_world(burn.delta_v);
+        }
+    }
+    let start_radius = state.0.length();
+    let energy = 0.5 * state.1.length_squared() - mu / start_radius;
+    if energy < 0.0 {
+        let semi_major_axis = -mu / (2.0 * energy);
+        let period = std::f64::consts::TAU * (semi_major_axis.powi(3) / mu).sqrt();
+        integrate(&mut state, mu, &mut points, |elapsed, _| {
+            (elapsed < period).then_some(period - elapsed)
+        });
+    } else {
+        integrate(&mut state, mu, &mut points, |_, position| {
+            (position.length() < UNBOUND_EXTENT * start_radius).then_some(f64::INFINITY)
+        });
+    }
+    points
+}
+
+/// Leapfrog-integrates `state` while `remaining(elapsed, position)` returns the time left,
+/// pushing each new position onto `points` until they hold [`ORBIT_PREVIEW_MAX_STEPS`].
+fn integrate(
+    state: &mut (DVec3, DVec3),
+    mu: f64,
+    points: &mut Vec<DVec3>,
+    remaining: impl Fn(f64, DVec3) -> Option<f64>,
+) {
+    let acceleration = |position: DVec3| -position * (mu / position.length().powi(3));
+    let mut elapsed = 0.0;
+    while points.len() < ORBIT_PREVIEW_MAX_STEPS {
+        let Some(left) = remaining(elapsed, state.0) else {
+            break;
+        };
+        let radius = state.0.length();
+        if radius == 0.0 {
+            break;
+        }
+        let dt = (STEP_FRACTION * (radius.powi(3) / mu).sqrt()).min(left);
+        let (position, velocity) = state;
+        *velocity += acceleration(*position) * (0.5 * dt);
+        *position += *velocity * dt;
+        *velocity += acceleration(*position) * (0.5 * dt);
+        elapsed += dt;
+        points.push(*position);
+    }
+}
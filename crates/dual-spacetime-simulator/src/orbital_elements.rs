@@ -0,0 +1,185 @@
+use crate::object_input::ParticleBasicColor;
+use crate::simulation::{G, Particle};
+use glam::DVec3;
+use std::f64::consts::TAU;
+
+/// Classical Keplerian orbital elements of a body relative to its primary.
+///
+/// Angles are in radians and referenced to the x–y plane with the ascending node
+/// measured from +X, matching the ephemeris frame used by the Solar System preset.
+/// Elliptic orbits use `e < 1` with `a > 0`; hyperbolic orbits use `e > 1` with `a < 0`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct OrbitalElements {
+    /// Semi-major axis `a` in meters.
+    pub semi_major_axis: f64,
+    /// Eccentricity `e`.
+    pub eccentricity: f64,
+    /// Inclination `i` from the reference plane.
+    pub inclination: f64,
+    /// Longitude of the ascending node `Ω`.
+    pub ascending_node: f64,
+    /// Argument of periapsis `ω`.
+    pub argument_of_periapsis: f64,
+    /// True anomaly `ν` at epoch.
+    pub true_anomaly: f64,
+}
+
+impl OrbitalElements {
+    /// Returns a circular, equatorial orbit of radius `radius` at the given true anomaly.
+    pub fn circular(radius: f64, true_anomaly: f64) -> Self {
+        Self {
+            semi_major_axis: radius,
+            eccentricity: 0.0,
+            inclination: 0.0,
+            ascending_node: 0.0,
+            argument_of_periapsis: 0.0,
+            true_anomaly,
+        }
+    }
+
+    /// Returns the semi-latus rectum `p = a(1 − e²)`; non-positive for invalid element sets.
+    pub fn semi_latus_rectum(&self) -> f64 {
+        self.semi_major_axis * (1.0 - self.eccentricity * self.eccentricity)
+    }
+
+    /// Returns the periapsis distance `a(1 − e)`.
+    pub fn periapsis_distance(&self) -> f64 {
+        self.semi_latus_rectum() / (1.0 + self.eccentricity)
+    }
+
+    /// Returns the apoapsis distance `a(1 + e)`, or `None` for unbound orbits.
+    pub fn apoapsis_distance(&self) -> Option<f64> {
+        (self.eccentricity < 1.0).then_some(self.semi_major_axis * (1.0 + self.eccentricity))
+    }
+
+    /// Returns the orbital period `2π √(a³/μ)`, or `None` for unbound orbits.
+    pub fn period(&self, mu: f64) -> Option<f64> {
+        (self.eccentricity < 1.0 && self.semi_major_axis > 0.0 && mu > 0.0)
+            .then(|| TAU * (self.semi_major_axis.powi(3) / mu).sqrt())
+    }
+
+    /// Returns whether the element set describes a bound or unbound conic that can be converted.
+    pub fn is_valid(&self) -> bool {
+        let e = self.eccentricity;
+        let p = self.semi_latus_rectum();
+        if !(e.is_finite() && e >= 0.0 && p.is_finite() && p > 0.0) || (e - 1.0).abs() < 1e-12 {
+            return false;
+        }
+        // Hyperbolic trajectories only exist for |ν| below the asymptote angle.
+        1.0 + e * self.true_anomaly.cos() > 0.0
+    }
+
+    /// Converts the elements into a Cartesian position/velocity pair relative to the primary.
+    ///
+    /// `mu` is the gravitational parameter `G(M + m)` in the same units as the elements.
+    /// Returns `None` when the element set is not a convertible conic.
+    pub fn to_state_vectors(&self, mu: f64) -> Option<(DVec3, DVec3)> {
+        if !(self.is_valid() && mu.is_finite() && mu > 0.0) {
+            return None;
+        }
+        let e = self.eccentricity;
+        let p = self.semi_latus_rectum();
+        let (sin_nu, cos_nu) = self.true_anomaly.sin_cos();
+        let r = p / (1.0 + e * cos_nu);
+        let speed_factor = (mu / p).sqrt();
+
+        // Perifocal frame: P toward periapsis, Q 90° ahead in the orbital plane.
+        let position_pf = DVec3::new(r * cos_nu, r * sin_nu, 0.0);
+        let velocity_pf = DVec3::new(-speed_factor * sin_nu, speed_factor * (e + cos_nu), 0.0);
+
+        let rotation = self.perifocal_to_reference();
+        Some((rotation * position_pf, rotation * velocity_pf))
+    }
+
+    /// Converts a Cartesian position/velocity pair relative to the primary into the
+    /// osculating elements; the inverse of [`Self::to_state_vectors`].
+    ///
+    /// Circular orbits report `ω = 0` with `ν` measured from the ascending node, and
+    /// equatorial orbits report `Ω = 0` with angles measured from +X. Returns `None` when
+    /// the body sits on the primary or moves straight toward or away from it.
+    pub fn from_state_vectors(position: DVec3, velocity: DVec3, mu: f64) -> Option<Self> {
+        let r = position.length();
+        if !(mu.is_finite() && mu > 0.0 && r > 0.0) {
+            return None;
+        }
+        let angular_momentum = position.cross(velocity);
+        let normal = angular_momentum.try_normalize()?;
+        let eccentricity_vector = velocity.cross(angular_momentum) / mu - position / r;
+        let eccentricity = eccentricity_vector.length();
+        let energy = 0.5 * velocity.length_squared() - mu / r;
+        let node = DVec3::Z.cross(normal);
+        let equatorial = node.length() < 1e-12;
+        // In-plane axes: toward the ascending node (or +X) and 90° ahead of it.
+        let node_axis = if equatorial {
+            DVec3::X
+        } else {
+            node.normalize()
+        };
+        let ahead_axis = normal.cross(node_axis);
+        let angle_in_plane = |v: DVec3| v.dot(ahead_axis).atan2(v.dot(node_axis));
+        let ascending_node = if equatorial {
+            0.0
+        } else {
+            node.y.atan2(node.x)
+        };
+        let argument_of_periapsis = if eccentricity < 1e-12 {
+            0.0
+        } else {
+            angle_in_plane(eccentricity_vector)
+        };
+        Some(Self {
+            semi_major_axis: -mu / (2.0 * energy),
+            eccentricity,
+            inclination: normal.z.clamp(-1.0, 1.0).acos(),
+            ascending_node: ascending_node.rem_euclid(TAU),
+            argument_of_periapsis: argument_of_periapsis.rem_euclid(TAU),
+            true_anomaly: (angle_in_plane(position) - argument_of_periapsis).rem_euclid(TAU),
+        })
+    }
+
+    /// Rotation `R_z(Ω) · R_x(i) · R_z(ω)` from the perifocal frame to the reference frame.
+    fn perifocal_to_reference(&self) -> glam::DMat3 {
+        glam::DMat3::from_rotation_z(self.ascending_node)
+            * glam::DMat3::from_rotation_x(self.inclination)
+            * glam::DMat3::from_rotation_z(self.argument_of_periapsis)
+    }
+}
+
+impl Default for OrbitalElements {
+    /// Returns an Earth-like orbit (1 au, e = 0.0167) in the reference plane.
+    fn default() -> Self {
+        Self {
+            semi_major_axis: crate::simulation::AU,
+            eccentricity: 0.0167,
+            ..Self::circular(crate::simulation::AU, 0.0)
+        }
+    }
+}
+
+/// Osculating elements in SI units of `body` around `primary`, both in simulation units
+/// at world `scale`, with `μ = G(M + m)`.
+pub fn osculating_elements(
+    body: &Particle,
+    primary: &Particle,
+    scale: f64,
+) -> Option<OrbitalElements> {
+    OrbitalElements::from_state_vectors(
+        (body.position - primary.position) * scale,
+        (body.velocity - primary.velocity) * scale,
+        gravitational_parameter(body, primary, scale),
+    )
+}
+
+/// `G(M + m)` in m³/s² of two particles in simulation units at world `scale`.
+pub fn gravitational_parameter(body: &Particle, primary: &Particle, scale: f64) -> f64 {
+    G * (body.mass + primary.mass) * scale.powi(3)
+}
+
+/// A secondary body described by its mass and orbital elements around the primary.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct OrbitingBody {
+    /// Body mass in kilograms.
+    pub mass: f64,
+    pub elements: OrbitalElements,
+    pub color: ParticleBasicColor,
+}
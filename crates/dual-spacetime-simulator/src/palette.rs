@@ -0,0 +1,200 @@
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+/// Largest number of colors in a custom palette.
+pub const MAX_PALETTE_COLORS: usize = 16;
+pub const DEFAULT_PARTICLE_PALETTE: &str = "Classic";
+pub const DEFAULT_GROUP_PALETTE: &str = "Okabe-Ito";
+
+/// Built-in palettes: the original five-color cycle, then the color-blind safe sets of
+/// Okabe & Ito and Paul Tol without their black, which would vanish against the background.
+pub const BUILTIN_PALETTES: [(&str, &[[f32; 3]]); 4] = [
+    (
+        "Classic",
+        &[
+            [1.0, 0.3, 0.2],
+            [0.2, 0.5, 1.0],
+            [1.0, 0.8, 0.2],
+            [0.9, 0.4, 1.0],
+            [0.6, 1.0, 0.8],
+        ],
+    ),
+    (
+        "Okabe-Ito",
+        &[
+            [0.902, 0.624, 0.0],
+            [0.337, 0.706, 0.914],
+            [0.0, 0.620, 0.451],
+            [0.941, 0.894, 0.259],
+            [0.0, 0.447, 0.698],
+            [0.835, 0.369, 0.0],
+            [0.8, 0.475, 0.655],
+        ],
+    ),
+    (
+        "Tol Bright",
+        &[
+            [0.267, 0.467, 0.667],
+            [0.933, 0.4, 0.467],
+            [0.133, 0.533, 0.2],
+            [0.8, 0.733, 0.267],
+            [0.4, 0.8, 0.933],
+            [0.667, 0.2, 0.467],
+            [0.733, 0.733, 0.733],
+        ],
+    ),
+    (
+        "Tol Vibrant",
+        &[
+            [0.933, 0.467, 0.2],
+            [0.0, 0.467, 0.733],
+            [0.2, 0.733, 0.933],
+            [0.933, 0.2, 0.467],
+            [0.8, 0.2, 0.067],
+            [0.0, 0.6, 0.533],
+            [0.733, 0.733, 0.733],
+        ],
+    ),
+];
+
+static PARTICLE_PALETTE: RwLock<Vec<[f32; 3]>> = RwLock::new(Vec::new());
+static GROUP_PALETTE: RwLock<Vec<[f32; 3]>> = RwLock::new(Vec::new());
+
+/// Returns the colors of a built-in palette.
+pub fn builtin_palette(name: &str) -> Option<&'static [[f32; 3]]> {
+    BUILTIN_PALETTES
+        .iter()
+        .find(|(builtin, _)| *builtin == name)
+        .map(|(_, colors)| *colors)
+}
+
+/// Returns the `index`-th color of `colors` as opaque RGBA, wrapping around the palette;
+/// white when the palette is empty.
+pub fn palette_color(colors: &[[f32; 3]], index: usize) -> [f32; 4] {
+    match colors.len() {
+        0 => [1.0; 4],
+        len => {
+            let [r, g, b] = colors[index % len];
+            [r, g, b, 1.0]
+        }
+    }
+}
+
+/// Returns the `index`-th color of the palette the particle generators cycle through.
+pub fn particle_palette_color(index: usize) -> [f32; 4] {
+    let colors = PARTICLE_PALETTE.read().unwrap();
+    if colors.is_empty() {
+        return palette_color(
+            builtin_palette(DEFAULT_PARTICLE_PALETTE).unwrap_or_default(),
+            index,
+        );
+    }
+    palette_color(&colors, index)
+}
+
+/// Returns the color of the friends-of-friends group ranked `rank`.
+pub fn group_palette_color(rank: usize) -> [f32; 4] {
+    let colors = GROUP_PALETTE.read().unwrap();
+    if colors.is_empty() {
+        return palette_color(
+            builtin_palette(DEFAULT_GROUP_PALETTE).unwrap_or_default(),
+            rank,
+        );
+    }
+    palette_color(&colors, rank)
+}
+
+/// A user-defined palette saved in the config.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Palette {
+    pub name: String,
+    pub colors: Vec<[f32; 3]>,
+}
+
+/// Custom palettes and which palette each use is assigned.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct PaletteSettings {
+    /// Palette the particle generators cycle through.
+    pub particle_palette: String,
+    /// Palette that colors friends-of-friends groups by rank.
+    pub group_palette: String,
+    pub custom: Vec<Palette>,
+}
+
+impl Default for PaletteSettings {
+    fn default() -> Self {
+        Self {
+            particle_palette: DEFAULT_PARTICLE_PALETTE.to_string(),
+            group_palette: DEFAULT_GROUP_PALETTE.to_string(),
+            custom: Vec::new(),
+        }
+    }
+}
+
+impl PaletteSettings {
+    /// Names of every palette: built-ins first, then custom ones in creation order.
+    pub fn names(&self) -> Vec<String> {
+        BUILTIN_PALETTES
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .chain(self.custom.iter().map(|palette| palette.name.clone()))
+            .collect()
+    }
+
+    pub fn is_custom(&self, name: &str) -> bool {
+        self.custom.iter().any(|palette| palette.name == name)
+    }
+
+    /// Returns the colors of the palette named `name`, or of the default particle palette
+    /// when there is none.
+    pub fn colors(&self, name: &str) -> &[[f32; 3]] {
+        self.custom
+            .iter()
+            .find(|palette| palette.name == name)
+            .map(|palette| palette.colors.as_slice())
+            .or_else(|| builtin_palette(name))
+            .or_else(|| builtin_palette(DEFAULT_PARTICLE_PALETTE))
+            .unwrap_or_default()
+    }
+
+    pub fn custom_mut(&mut self, name: &str) -> Option<&mut Palette> {
+        self.custom.iter_mut().find(|palette| palette.name == name)
+    }
+
+    /// Adds a custom copy of the palette named `name` and returns the copy's name.
+    pub fn duplicate(&mut self, name: &str) -> String {
+        let names = self.names();
+        let copy = (1..)
+            .map(|n| format!("Custom {}", n))
+            .find(|candidate| !names.contains(candidate))
+            .unwrap();
+        self.custom.push(Palette {
+            name: copy.clone(),
+            colors: self.colors(name).to_vec(),
+        });
+        copy
+    }
+
+    /// Removes a custom palette; uses assigned to it go back to their defaults. Built-in
+    /// palettes cannot be removed.
+    pub fn remove(&mut self, name: &str) {
+        if !self.is_custom(name) {
+            return;
+        }
+        self.custom.retain(|palette| palette.name != name);
+        if self.particle_palette == name {
+            self.particle_palette = DEFAULT_PARTICLE_PALETTE.to_string();
+        }
+        if self.group_palette == name {
+            self.group_palette = DEFAULT_GROUP_PALETTE.to_string();
+        }
+    }
+
+    /// Makes the assigned palettes the ones the particle generators and the group finder
+    /// use from now on; particles already colored keep their colors.
+    pub fn apply(&self) {
+        *PARTICLE_PALETTE.write().unwrap() = self.colors(&self.particle_palette).to_vec();
+        *GROUP_PALETTE.write().unwrap() = self.colors(&self.group_palette).to_vec();
+    }
+}
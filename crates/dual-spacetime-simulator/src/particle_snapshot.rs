@@ -5,6 +5,9 @@ use std::path::Path;
 use zip::write::SimpleFileOptions;
 use zip::{ZipArchive, ZipWriter};
 
+use crate::annotations::Annotation;
+use crate::drag::DragForce;
+use crate::mass_evolution::ParticleMassRule;
 use crate::simulation::Particle;
 use crate::ui_state::SimulationType;
 
@@ -21,6 +24,15 @@ pub struct ParticleSnapshot {
     pub simulation_type: SimulationType,
     pub scale: f64,
     pub particles: Vec<Particle>,
+    /// Drag coefficients of the scenario; snapshots without them load with drag off.
+    #[serde(default)]
+    pub drag: DragForce,
+    /// Per-particle mass rules, keyed by particle ID.
+    #[serde(default)]
+    pub mass_rules: Vec<ParticleMassRule>,
+    /// Text labels on particles and points.
+    #[serde(default)]
+    pub annotations: Vec<Annotation>,
 }
 
 impl ParticleSnapshot {
@@ -31,6 +43,9 @@ impl ParticleSnapshot {
             simulation_type,
             scale,
             particles,
+            drag: DragForce::default(),
+            mass_rules: Vec::new(),
+            annotations: Vec::new(),
         }
     }
 
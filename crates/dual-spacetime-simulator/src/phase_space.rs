@@ -0,0 +1,155 @@
+use crate::simulation::Particle;
+use glam::DVec3;
+
+/// Default cap on plotted points; larger populations are sampled with a fixed stride.
+pub const DEFAULT_PHASE_SPACE_MAX_POINTS: u32 = 2000;
+/// Frames between phase-space refreshes while the panel is open.
+pub const PHASE_SPACE_REFRESH_FRAMES: i64 = 10;
+
+/// Per-particle quantity selectable for a phase-space plot axis.
+///
+/// Positions and velocities are measured relative to the mass-weighted center of mass
+/// and mean velocity, so the plot follows a drifting system.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PhaseSpaceQuantity {
+    Radius,
+    RadialVelocity,
+    TangentialVelocity,
+    Speed,
+    X,
+    Y,
+    Z,
+    Vx,
+    Vy,
+    Vz,
+}
+
+impl PhaseSpaceQuantity {
+    pub const ALL: [Self; 10] = [
+        Self::Radius,
+        Self::RadialVelocity,
+        Self::TangentialVelocity,
+        Self::Speed,
+        Self::X,
+        Self::Y,
+        Self::Z,
+        Self::Vx,
+        Self::Vy,
+        Self::Vz,
+    ];
+
+    /// Evaluates the quantity for a relative position and velocity.
+    pub fn evaluate(self, position: DVec3, velocity: DVec3) -> f64 {
+        let radius = position.length();
+        let radial = if radius > 0.0 {
+            velocity.dot(position) / radius
+        } else {
+            0.0
+        };
+        match self {
+            Self::Radius => radius,
+            Self::RadialVelocity => radial,
+            Self::TangentialVelocity => (velocity.length_squared() - radial * radial)
+                .max(0.0)
+                .sqrt(),
+            Self::Speed => velocity.length(),
+            Self::X => position.x,
+            Self::Y => position.y,
+            Self::Z => position.z,
+            Self::Vx => velocity.x,
+            Self::Vy => velocity.y,
+            Self::Vz => velocity.z,
+        }
+    }
+}
+
+impl std::fmt::Display for PhaseSpaceQuantity {
+    /// Formats phase-space quantity names for UI selection controls.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            PhaseSpaceQuantity::Radius => "r",
+            PhaseSpaceQuantity::RadialVelocity => "v_r",
+            PhaseSpaceQuantity::TangentialVelocity => "v_t",
+            PhaseSpaceQuantity::Speed => "|v|",
+            PhaseSpaceQuantity::X => "x",
+            PhaseSpaceQuantity::Y => "y",
+            PhaseSpaceQuantity::Z => "z",
+            PhaseSpaceQuantity::Vx => "v_x",
+            PhaseSpaceQuantity::Vy => "v_y",
+            PhaseSpaceQuantity::Vz => "v_z",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// Downsampled scatter data with the particle index behind each point.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct PhaseSpacePoints {
+    pub points: Vec<[f64; 2]>,
+    pub indices: Vec<usize>,
+}
+
+impl PhaseSpacePoints {
+    /// Returns `[min, max]` of each axis, or `None` when there are no points.
+    pub fn bounds(&self) -> Option<[[f64; 2]; 2]> {
+        let first = *self.points.first()?;
+        Some(
+            self.points
+                .iter()
+                .fold([[first[0], first[0]], [first[1], first[1]]], |[x, y], p| {
+                    [
+                        [x[0].min(p[0]), x[1].max(p[0])],
+                        [y[0].min(p[1]), y[1].max(p[1])],
+                    ]
+                }),
+        )
+    }
+}
+
+/// Samples live particles into `(x, y)` pairs, keeping at most `max_points` with a fixed
+/// stride; `always_include` (e.g. the selected particle) is kept even when skipped.
+pub fn sample_phase_space(
+    particles: &[Particle],
+    x: PhaseSpaceQuantity,
+    y: PhaseSpaceQuantity,
+    max_points: usize,
+    always_include: Option<usize>,
+) -> PhaseSpacePoints {
+    let live: Vec<usize> = (0..particles.len())
+        .filter(|&i| {
+            particles[i].color[3] != 0.0
+                && particles[i].position.is_finite()
+                && particles[i].velocity.is_finite()
+        })
+        .collect();
+    let total_mass: f64 = live.iter().map(|&i| particles[i].mass.max(0.0)).sum();
+    let weight = |i: usize| {
+        if total_mass > 0.0 {
+            particles[i].mass.max(0.0) / total_mass
+        } else {
+            1.0 / live.len() as f64
+        }
+    };
+    let center = live.iter().fold(DVec3::ZERO, |acc, &i| {
+        acc + particles[i].position * weight(i)
+    });
+    let mean_velocity = live.iter().fold(DVec3::ZERO, |acc, &i| {
+        acc + particles[i].velocity * weight(i)
+    });
+
+    let stride = live.len().div_ceil(max_points.max(1)).max(1);
+    let mut sample = PhaseSpacePoints::default();
+    for (n, &i) in live.iter().enumerate() {
+        if n % stride != 0 && always_include != Some(i) {
+            continue;
+        }
+        let position = particles[i].position - center;
+        let velocity = particles[i].velocity - mean_velocity;
+        sample.points.push([
+            x.evaluate(position, velocity),
+            y.evaluate(position, velocity),
+        ]);
+        sample.indices.push(i);
+    }
+    sample
+}
@@ -1,14 +1,36 @@
-use crate::gpu_simulation::{GpuParticleSimulation, create_particle_descriptor_set_layout};
+use crate::axis_labels::{AXIS_XZ_GRID_EXTENT, AXIS_XZ_GRID_LINE_COUNT};
+use crate::frame_pipeline::StagingRing;
+use crate::gpu_culling::{GpuParticleCulling, cull_margin};
+use crate::gpu_depth_sort::GpuDepthSort;
+use crate::gpu_simulation::{
+    ExternalForces, GpuAdvance, GpuParticle, GpuParticleSimulation,
+    create_particle_descriptor_set_layout,
+};
 use crate::integration::Gui;
+use crate::light_cone::{LIGHT_CONE_RINGS, LightConeCrossing};
+use crate::mass_profile::{LAGRANGIAN_MASS_FRACTIONS, MassProfile};
+use crate::minimap::{camera_frustum_lines, minimap_rect, minimap_view_proj};
+use crate::motion_blur::{MotionBlur, blur_retention, composite_gain};
 use crate::particle_selection_marker::{
     BRACKET_RADIUS_RATIO, MIN_HALF_SIZE_PX, SELECTION_MARKER_VERTEX_COUNT,
     selection_index_bits,
 };
-use crate::simulation::Particle;
+use crate::point_budget::{area_compensation, sampled_fraction, sampling_phase};
+use crate::region_selection::{RegionShape, SelectionRegion};
+use crate::relativistic_view::{ObserverView, VELOCITY_SPACE_RADIUS};
+use crate::simulation::{EngineConfig, Particle};
+use crate::split_view::{ViewRect, ViewSide, view_rect, view_side_at};
+use crate::status_bar::intersect_grid_plane;
+use crate::still_image::{
+    MAX_STILL_SIZE, STILL_SUPERSAMPLING, STILL_TILE_SIZE, StillSettings, resolve_tile, still_tiles,
+    tile_clip_transform,
+};
+use crate::thomas_precession::{THOMAS_ORBIT_RADIUS, ThomasPrecession};
 use crate::trace_follow::PARTICLE_SIZE_RATIO;
 use crate::ui_state::*;
+use crate::view_fit::{BoundingSphere, fit_camera_distance};
 use ash::vk;
-use glam::{Mat4, Vec3, Vec4};
+use glam::{Mat4, Quat, Vec3};
 use gpu_allocator::vulkan::Allocator;
 use std::sync::{Arc, Mutex};
 use vulkanvil::{
@@ -21,8 +43,8 @@ const MOUSE_LEFT_DRAG_SENS: f32 = 0.003f32;
 const MOUSE_RIGHT_DRAG_SENS: f32 = 0.001f32;
 const INITIAL_POSITION: Vec3 = Vec3::new(1.6, -1.6, 3.0);
 const INITIAL_TARGET: Vec3 = Vec3::new(0.0, 0.0, 0.0);
-const AXIS_XZ_GRID_EXTENT: f32 = 2.0;
-const AXIS_XZ_GRID_LINE_COUNT: usize = 9;
+/// Vertical field of view shared by the axes and particle projections.
+const CAMERA_FOV_Y: f32 = std::f32::consts::FRAC_PI_4;
 const ADD_CENTER_MARKER_EDGE_COUNT: usize = 12;
 const ADD_CENTER_MARKER_VERTICES: usize = ADD_CENTER_MARKER_EDGE_COUNT * 2;
 const ADD_CENTER_WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
@@ -41,6 +63,54 @@ const ADD_CENTER_MARKER_EDGES: [([i8; 3], [i8; 3], [f32; 4]); ADD_CENTER_MARKER_
     ([0, -1, 0], [0, 0, -1], ADD_CENTER_WHITE),
 ];
 
+const REGION_MARKER_COLOR: [f32; 4] = [1.0, 0.8, 0.2, 1.0];
+/// Segments per great circle in sphere outlines (region and Lagrangian radii).
+const OUTLINE_SPHERE_SEGMENTS: usize = 48;
+const CENTER_OF_MASS_MARKER_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+/// Center-of-mass cross arm length in axes space (independent of the scale gauge).
+const CENTER_OF_MASS_MARKER_HALF_EXTENT: f32 = 0.08;
+const ESCAPER_MARKER_COLOR: [f32; 4] = [1.0, 0.2, 0.2, 1.0];
+/// Escaping-particle cross arm length in axes space.
+const ESCAPER_MARKER_HALF_EXTENT: f32 = 0.02;
+/// Light-cone surface lines; dim so the cone reads as translucent over the particles
+/// (the line pipeline does not blend).
+const LIGHT_CONE_COLOR: [f32; 4] = [0.45, 0.38, 0.12, 1.0];
+/// Lines from the apex to the rim of the light cone.
+const LIGHT_CONE_GENERATORS: usize = 8;
+const LIGHT_CONE_CROSSING_COLOR: [f32; 4] = [0.4, 1.0, 1.0, 1.0];
+const LIGHT_CONE_WORLDLINE_COLOR: [f32; 4] = [0.15, 0.4, 0.4, 1.0];
+/// Worldline-crossing cross arm length in axes space.
+const LIGHT_CONE_CROSSING_HALF_EXTENT: f32 = 0.015;
+/// Light-speed boundary sphere of the velocity display.
+const LIGHT_SPEED_SPHERE_COLOR: [f32; 4] = [0.45, 0.38, 0.12, 1.0];
+const CONTAINER_WALLS_COLOR: [f32; 4] = [0.5, 0.55, 0.65, 1.0];
+/// Dashed predicted orbit of the selected particle.
+const ORBIT_PREVIEW_COLOR: [f32; 4] = [0.4, 0.85, 0.55, 1.0];
+/// Main camera frustum drawn in the minimap.
+const MINIMAP_FRUSTUM_COLOR: [f32; 4] = [1.0, 0.85, 0.3, 1.0];
+const MINIMAP_BACKGROUND_COLOR: [f32; 4] = [0.04, 0.04, 0.07, 1.0];
+const MINIMAP_BORDER_COLOR: [f32; 4] = [0.5, 0.5, 0.55, 1.0];
+/// Point size in pixels of particles in the minimap, whose orthographic view has no
+/// perspective scaling.
+const MINIMAP_POINT_SIZE: f32 = 2.0;
+const SPLIT_VIEW_DIVIDER_COLOR: [f32; 4] = [0.5, 0.5, 0.55, 1.0];
+const THOMAS_ORBIT_COLOR: [f32; 4] = [0.35, 0.35, 0.45, 1.0];
+const THOMAS_VELOCITY_COLOR: [f32; 4] = [1.0, 1.0, 0.4, 1.0];
+/// Particle x, y, z axes of the Thomas precession triad.
+const THOMAS_TRIAD_COLORS: [[f32; 4]; 3] = [
+    [1.0, 0.35, 0.35, 1.0],
+    [0.4, 1.0, 0.4, 1.0],
+    [0.45, 0.6, 1.0, 1.0],
+];
+/// Triad arm length as a fraction of the orbit radius.
+const THOMAS_TRIAD_SCALE: f32 = 0.3;
+/// Outline colors for the 10%, 50%, and 90% Lagrangian radii.
+const LAGRANGIAN_RADIUS_COLORS: [[f32; 4]; LAGRANGIAN_MASS_FRACTIONS.len()] = [
+    [0.4, 0.9, 1.0, 1.0],
+    [0.5, 1.0, 0.5, 1.0],
+    [1.0, 0.5, 0.4, 1.0],
+];
+
 #[repr(C)]
 #[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
 struct AxesVertex {
@@ -59,6 +129,22 @@ struct AxesPushConstants {
 struct PushConstants {
     view_proj: [[f32; 4]; 4],
     size_scale: f32,
+    /// Nonzero: `gl_VertexIndex` indexes the visible list written by the cull pass, or
+    /// the back-to-front list written by the depth sort.
+    culled: u32,
+    /// [`ViewKinematics`](crate::relativistic_view::ViewKinematics) code of the velocity
+    /// buffer contents.
+    kinematics: u32,
+    /// `VIEW_FLAG_*` bits; nonzero moves particles off their simulation-frame positions.
+    view_flags: u32,
+    /// `xyz`: camera position in particle space, `w`: light speed in sim units.
+    observer: [f32; 4],
+    /// `xyz`: reference particle position for the rest-frame view, `w`: fraction of the
+    /// particles drawn under the point budget.
+    frame_origin: [f32; 4],
+    /// `xyz`: reference particle velocity for the rest-frame view, `w`: the frame's
+    /// 24-bit sampling phase.
+    frame_velocity: [f32; 4],
 }
 
 #[repr(C)]
@@ -71,6 +157,19 @@ struct SelectionMarkerPushConstants {
     viewport: [f32; 4],
 }
 
+/// One camera's view of the scene for [`ParticleRenderPipeline::draw_view`].
+#[derive(Clone, Copy)]
+struct SceneView {
+    /// Part of the render target the view fills.
+    area: ViewRect,
+    /// Axes-space transform of the grid and line overlays.
+    view_proj: Mat4,
+    /// Push constants of the particle pass, with its own transform.
+    pc: PushConstants,
+    /// With a gain, the motion-blur accumulation scaled by it stands in for the particles.
+    accumulation_gain: Option<f32>,
+}
+
 pub struct ParticleRenderPipeline {
     device: ash::Device,
     allocator: Arc<Mutex<Allocator>>,
@@ -81,6 +180,8 @@ pub struct ParticleRenderPipeline {
     pipeline_axes: vk::Pipeline,
     pipeline_selection: vk::Pipeline,
     particle_pipelines: [vk::Pipeline; ParticleDisplayMode::ALL.len()],
+    /// Particle pipelines of the motion blur's accumulation pass.
+    accumulation_pipelines: [vk::Pipeline; ParticleDisplayMode::ALL.len()],
     layout_axes: vk::PipelineLayout,
     layout_selection: vk::PipelineLayout,
     layout_particles: vk::PipelineLayout,
@@ -92,45 +193,176 @@ pub struct ParticleRenderPipeline {
     add_center_marker_buffer: Option<AllocatedBuffer>,
     add_center_marker_vertex_count: u32,
     last_add_center_marker_key: Option<(glam::DVec3, u64, u64)>,
+    region_marker_buffer: Option<AllocatedBuffer>,
+    region_marker_vertex_count: u32,
+    last_region_marker_key: Option<(SelectionRegion, u64)>,
+    mass_profile_marker_buffer: Option<AllocatedBuffer>,
+    mass_profile_marker_vertex_count: u32,
+    last_mass_profile_marker_key: Option<(MassProfile, u64)>,
+    escaper_marker_buffer: Option<AllocatedBuffer>,
+    escaper_marker_vertex_count: u32,
+    last_escaper_marker_key: Option<(u64, u64)>,
+    light_cone_buffer: Option<AllocatedBuffer>,
+    light_cone_vertex_count: u32,
+    last_light_cone_key: Option<(u64, u64)>,
+    light_speed_sphere_buffer: Option<AllocatedBuffer>,
+    light_speed_sphere_vertex_count: u32,
+    last_light_speed_sphere_key: Option<u64>,
+    thomas_buffer: Option<AllocatedBuffer>,
+    thomas_vertex_count: u32,
+    last_thomas_key: Option<(u64, u64)>,
+    walls_buffer: Option<AllocatedBuffer>,
+    walls_vertex_count: u32,
+    last_walls_key: Option<(u64, u64)>,
+    orbit_preview_buffer: Option<AllocatedBuffer>,
+    orbit_preview_vertex_count: u32,
+    last_orbit_preview_key: Option<(u64, u64)>,
+    /// System bounds in particle space the minimap frames; `None` hides the minimap.
+    minimap_bounds: Option<BoundingSphere>,
+    minimap_frustum_buffer: Option<AllocatedBuffer>,
+    minimap_frustum_vertex_count: u32,
+    last_minimap_frustum_key: Option<(Vec3, Vec3, Vec3, f32)>,
     selection_marker_index: i32,
     particle_descriptor_set_layout: vk::DescriptorSetLayout,
     gpu_sim: GpuParticleSimulation,
     use_gpu_sim: bool,
+    culling: GpuParticleCulling,
+    gpu_culling: bool,
+    depth_sort: GpuDepthSort,
+    /// Points per frame the particle draw is subsampled to; 0 draws every particle.
+    point_budget: u32,
+    /// Frames drawn so far, which seed the sampling phase.
+    sample_frame: u32,
+    motion_blur: MotionBlur,
+    /// Frames a motion-blur trail lasts; 0 turns motion blur off.
+    motion_blur_frames: u32,
+    observer_view: ObserverView,
+    /// Rotation of the grid from the x–z plane.
+    grid_rotation: Quat,
     retired_buffers: Vec<AllocatedBuffer>,
 
     applied_lock_camera_up: Option<bool>,
     camera: OrbitCamera,
+    /// Shows the second camera's view beside the main one.
+    split_view: bool,
+    /// Orbit camera of the right half in split view.
+    second_camera: OrbitCamera,
+    /// View whose camera mouse drags and the wheel move, picked where the cursor was.
+    focused_view: ViewSide,
+}
+
+/// Queue and color format an off-screen render uses: the window's, or those of a device
+/// without a surface in the render tests.
+#[derive(Clone, Copy, Debug)]
+pub struct OffscreenTarget {
+    pub color_format: vk::Format,
+    pub queue: vk::Queue,
+    pub queue_family: u32,
+}
+
+impl OffscreenTarget {
+    pub fn from_base(base: &VulkanBase) -> Self {
+        Self {
+            color_format: base.swapchain_format,
+            queue: base.graphics_queue,
+            queue_family: base.graphics_queue_family,
+        }
+    }
+}
+
+/// Where and how [`ParticleRenderPipeline::render_tiles`] draws a still: the target, the
+/// output size and supersampling its tiles cover, and whether a single-tile still adds
+/// to and draws the motion-blur trails.
+#[derive(Clone, Copy, Debug)]
+struct TilePass {
+    target: OffscreenTarget,
+    settings: StillSettings,
+    trails: bool,
 }
 
 impl ParticleRenderPipeline {
     /// Creates graphics and compute pipelines with all persistent rendering resources.
     pub fn new(base: &VulkanBase) -> Self {
-        let device = base.device.clone();
-        let allocator = Arc::clone(base.allocator.as_ref().unwrap());
+        Self::create(
+            base.device.clone(),
+            Arc::clone(base.allocator.as_ref().unwrap()),
+            select_depth_format(&base.instance, base.physical_device),
+            base.swapchain_format,
+            vk::ImageLayout::PRESENT_SRC_KHR,
+            base.swapchain_extent,
+            &base.swapchain_image_views,
+        )
+    }
 
-        let depth_format = select_depth_format(&base.instance, base.physical_device);
-        let render_pass = create_render_pass(&device, base.swapchain_format, depth_format);
+    /// Creates the pipelines on a device without a surface, for drawing only through
+    /// [`Self::render_offscreen`] into targets of `color_format`. Used by the render tests.
+    pub fn new_headless(
+        instance: &ash::Instance,
+        physical_device: vk::PhysicalDevice,
+        device: ash::Device,
+        allocator: Arc<Mutex<Allocator>>,
+        color_format: vk::Format,
+    ) -> Self {
+        Self::create(
+            device,
+            allocator,
+            select_depth_format(instance, physical_device),
+            color_format,
+            vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            vk::Extent2D {
+                width: 1,
+                height: 1,
+            },
+            &[],
+        )
+    }
+
+    fn create(
+        device: ash::Device,
+        allocator: Arc<Mutex<Allocator>>,
+        depth_format: vk::Format,
+        color_format: vk::Format,
+        color_final_layout: vk::ImageLayout,
+        extent: vk::Extent2D,
+        image_views: &[vk::ImageView],
+    ) -> Self {
+        let render_pass =
+            create_render_pass(&device, color_format, depth_format, color_final_layout);
         let depth_image = create_depth_image(
             &device,
             &allocator,
             depth_format,
-            base.swapchain_extent,
+            extent,
             "particle-depth-buffer",
         );
-        let framebuffers = create_framebuffers(
-            &device,
-            render_pass,
-            &base.swapchain_image_views,
-            depth_image.view,
-            base.swapchain_extent,
-        );
+        let framebuffers =
+            create_framebuffers(&device, render_pass, image_views, depth_image.view, extent);
 
         let (layout_axes, pipeline_axes) = create_axes_pipeline(&device, render_pass);
         let particle_descriptor_set_layout = create_particle_descriptor_set_layout(&device);
         let (layout_selection, pipeline_selection) =
             create_selection_marker_pipeline(&device, render_pass, particle_descriptor_set_layout);
-        let (layout_particles, particle_pipelines) =
-            create_particles_pipelines(&device, render_pass, particle_descriptor_set_layout);
+        let culling = GpuParticleCulling::new(device.clone(), Arc::clone(&allocator));
+        let depth_sort = GpuDepthSort::new(
+            device.clone(),
+            Arc::clone(&allocator),
+            culling.descriptor_set_layout(),
+        );
+        let (layout_particles, particle_pipelines) = create_particles_pipelines(
+            &device,
+            render_pass,
+            &[
+                particle_descriptor_set_layout,
+                culling.descriptor_set_layout(),
+            ],
+        );
+        let motion_blur = MotionBlur::new(device.clone(), Arc::clone(&allocator), render_pass);
+        let accumulation_pipelines = create_particle_mode_pipelines(
+            &device,
+            motion_blur.render_pass(),
+            layout_particles,
+            true,
+        );
 
         let (axes_buffer, axes_vertex_count) = create_axes_vertices(&device, &allocator);
         let gpu_sim = GpuParticleSimulation::new(
@@ -141,6 +373,8 @@ impl ParticleRenderPipeline {
         );
 
         let camera = OrbitCamera::new(INITIAL_POSITION, INITIAL_TARGET);
+        let mut second_camera = OrbitCamera::new(INITIAL_POSITION, INITIAL_TARGET);
+        second_camera.set_lock_up(true);
 
         Self {
             device,
@@ -150,6 +384,7 @@ impl ParticleRenderPipeline {
             pipeline_axes,
             pipeline_selection,
             particle_pipelines,
+            accumulation_pipelines,
             layout_axes,
             layout_selection,
             layout_particles,
@@ -160,13 +395,53 @@ impl ParticleRenderPipeline {
             add_center_marker_buffer: None,
             add_center_marker_vertex_count: 0,
             last_add_center_marker_key: None,
+            region_marker_buffer: None,
+            region_marker_vertex_count: 0,
+            last_region_marker_key: None,
+            mass_profile_marker_buffer: None,
+            mass_profile_marker_vertex_count: 0,
+            last_mass_profile_marker_key: None,
+            escaper_marker_buffer: None,
+            escaper_marker_vertex_count: 0,
+            last_escaper_marker_key: None,
+            light_cone_buffer: None,
+            light_cone_vertex_count: 0,
+            last_light_cone_key: None,
+            light_speed_sphere_buffer: None,
+            light_speed_sphere_vertex_count: 0,
+            last_light_speed_sphere_key: None,
+            thomas_buffer: None,
+            thomas_vertex_count: 0,
+            last_thomas_key: None,
+            walls_buffer: None,
+            walls_vertex_count: 0,
+            last_walls_key: None,
+            orbit_preview_buffer: None,
+            orbit_preview_vertex_count: 0,
+            last_orbit_preview_key: None,
+            minimap_bounds: None,
+            minimap_frustum_buffer: None,
+            minimap_frustum_vertex_count: 0,
+            last_minimap_frustum_key: None,
             selection_marker_index: -1,
             particle_descriptor_set_layout,
             gpu_sim,
             use_gpu_sim: false,
+            culling,
+            gpu_culling: true,
+            depth_sort,
+            point_budget: 0,
+            sample_frame: 0,
+            motion_blur,
+            motion_blur_frames: 0,
+            observer_view: ObserverView::default(),
+            grid_rotation: Quat::IDENTITY,
             retired_buffers: Vec::new(),
             applied_lock_camera_up: None,
             camera,
+            split_view: false,
+            second_camera,
+            focused_view: ViewSide::Primary,
         }
     }
 
@@ -180,28 +455,90 @@ impl ParticleRenderPipeline {
         self.use_gpu_sim
     }
 
-    /// Records `steps` GPU simulation steps before rendering when GPU mode is active.
+    /// Enables or disables GPU frustum culling with an indirect particle draw.
+    pub fn set_gpu_culling(&mut self, gpu_culling: bool) {
+        self.gpu_culling = gpu_culling;
+    }
+
+    /// Sets the settings of the running simulation, which GPU stepping and readback use.
+    pub fn set_engine_config(&mut self, config: EngineConfig) {
+        self.gpu_sim.set_config(config);
+    }
+
+    /// Sets how many points a frame may draw; beyond it, each frame draws a random subset
+    /// with compensated brightness. 0 draws every particle.
+    pub fn set_point_budget(&mut self, point_budget: u32) {
+        self.point_budget = point_budget;
+    }
+
+    /// Sets how many frames a motion-blur trail lasts in the main view; 0 turns motion
+    /// blur off. Turning it on starts from a clean image.
+    pub fn set_motion_blur_frames(&mut self, frames: u32) {
+        if frames > 0 && self.motion_blur_frames == 0 {
+            self.motion_blur.clear();
+        }
+        self.motion_blur_frames = frames;
+    }
+
+    /// Sets how particles are drawn relative to their simulation-frame positions: as seen
+    /// from the camera with Doppler beaming, and/or in a reference particle's rest frame.
+    /// `view.light_speed` is in simulation units.
+    pub fn set_observer_view(&mut self, view: ObserverView) {
+        self.observer_view = view;
+    }
+
+    /// Sets the rotation of the grid from the x–z plane.
+    pub fn set_grid_rotation(&mut self, rotation: Quat) {
+        self.grid_rotation = rotation;
+    }
+
+    /// Sets the system bounds the minimap frames, or hides it with `None`.
+    pub fn set_minimap_bounds(&mut self, bounds: Option<BoundingSphere>) {
+        self.minimap_bounds = bounds;
+    }
+
+    /// Shows or hides the second view. Turning it on starts the second camera at the main
+    /// camera's pose.
+    pub fn set_split_view(&mut self, split_view: bool) {
+        if split_view == self.split_view {
+            return;
+        }
+        self.split_view = split_view;
+        if split_view {
+            self.second_camera
+                .reset_pose(self.camera.position, self.camera.target);
+        } else {
+            self.focused_view = ViewSide::Primary;
+        }
+    }
+
+    /// Camera position in the particle buffer's coordinates.
+    fn observer_position(&self, scale_factor: f32) -> Vec3 {
+        self.camera.position / scale_factor
+    }
+
+    /// Records `advance.steps` GPU simulation steps before rendering when GPU mode is active.
     ///
     /// `cull_max_angle` is the DstGalaxy S³ cull threshold in radians (0 disables);
     /// the compute shader marks particles beyond it dead in-place, so no CPU-GPU
-    /// synchronization is needed on the hot path.
+    /// synchronization is needed on the hot path. `forces` apply in the same pass.
     pub fn record_gpu_advance(
         &self,
         command_buffer: vk::CommandBuffer,
         simulation_type: SimulationType,
-        delta_seconds: f64,
         scale: f64,
-        steps: u32,
         cull_max_angle: f32,
+        advance: GpuAdvance,
+        forces: ExternalForces,
     ) {
         if self.use_gpu_sim {
             self.gpu_sim.dispatch(
                 command_buffer,
                 simulation_type,
-                delta_seconds,
                 scale,
-                steps,
                 cull_max_angle,
+                advance,
+                forces,
             );
         }
     }
@@ -211,6 +548,36 @@ impl ParticleRenderPipeline {
         self.gpu_sim.upload_from_cpu(particles, simulation_type);
     }
 
+    /// Backs the worker's staging slots with mapped buffers of this pipeline.
+    pub fn connect_staging(&mut self, staging: StagingRing<GpuParticle>) {
+        self.gpu_sim.connect_staging(staging);
+    }
+
+    /// Takes the worker's newest frame from staging memory, to be copied into the particle
+    /// buffer at the start of the next [`Self::record_staged_upload`]. Grows undersized
+    /// staging slots first, so that frames which did not fit are staged from now on.
+    pub fn take_staged_frame(&mut self) -> bool {
+        self.gpu_sim.grow_staging();
+        self.gpu_sim.take_staged_frame()
+    }
+
+    /// Records the copy of a taken staged frame, before anything reads particles. Returns
+    /// the staging slot to report with [`Self::staged_upload_submitted`].
+    pub fn record_staged_upload(&mut self, command_buffer: vk::CommandBuffer) -> Option<usize> {
+        self.gpu_sim.record_staged_upload(command_buffer)
+    }
+
+    /// Marks staging slot `index` as copied by the command buffer of frame-in-flight
+    /// `frame`.
+    pub fn staged_upload_submitted(&self, index: usize, frame: usize) {
+        self.gpu_sim.staged_upload_submitted(index, frame);
+    }
+
+    /// Frees the staging slots copied by frame-in-flight `frame`; call after its fence.
+    pub fn retire_staging(&self, frame: usize) {
+        self.gpu_sim.retire_staging(frame);
+    }
+
     /// Reads back GPU particle state for snapshot export.
     pub fn readback_particles(
         &self,
@@ -310,6 +677,7 @@ impl ParticleRenderPipeline {
             base.swapchain_extent,
             "particle-depth-buffer",
         );
+        self.motion_blur.release_target();
         self.framebuffers = create_framebuffers(
             &self.device,
             self.render_pass,
@@ -332,19 +700,7 @@ impl ParticleRenderPipeline {
         particle_display_mode: ParticleDisplayMode,
     ) {
         self.flush_retired_buffers();
-        let clear_values = [
-            vk::ClearValue {
-                color: vk::ClearColorValue {
-                    float32: [0.0, 0.0, 0.0, 1.0],
-                },
-            },
-            vk::ClearValue {
-                depth_stencil: vk::ClearDepthStencilValue {
-                    depth: 1.0,
-                    stencil: 0,
-                },
-            },
-        ];
+        let clear_values = scene_clear_values();
         let render_pass_info = vk::RenderPassBeginInfo::default()
             .render_pass(self.render_pass)
             .framebuffer(self.framebuffers[framebuffer_index])
@@ -354,6 +710,78 @@ impl ParticleRenderPipeline {
             })
             .clear_values(&clear_values);
 
+        let split = self.split_view && extent.width >= 2;
+        let primary = view_rect(ViewSide::Primary, extent.width, extent.height, split);
+        let aspect_ratio = primary.aspect_ratio();
+        let scale_factor = particle_visual_scale_factor(scale);
+        let view_proj = self.compute_mvp_particle(aspect_ratio, scale_factor);
+        let point_scale_factor = if link_point_size_to_scale {
+            scale_factor
+        } else {
+            1.0
+        };
+        let view_proj_cols = view_proj.to_cols_array_2d();
+        let particle_count = self.gpu_sim.particle_count();
+        let fraction = sampled_fraction(particle_count, self.point_budget);
+        let phase = sampling_phase(self.sample_frame);
+        self.sample_frame = self.sample_frame.wrapping_add(1);
+        // Glow adds up, so its fragment shader compensates brightness instead.
+        let size_scale = compute_particle_size_scale(
+            extent.height as f32,
+            point_scale_factor,
+            particle_display_mode,
+        ) * if particle_display_mode.is_additive() {
+            1.0
+        } else {
+            area_compensation(fraction)
+        };
+        let sorted = particle_display_mode.is_depth_sorted() && particle_count > 0;
+        // The cull pass tests simulation-frame positions, which the observer view moves.
+        // Sorted modes draw every particle from the sorted list instead.
+        let culled =
+            self.gpu_culling && !sorted && particle_count > 0 && !self.observer_view.is_active();
+        // Compute work cannot be recorded inside the render pass, so the cull pass
+        // runs first and the particle draw later reads its indirect command.
+        if sorted {
+            self.depth_sort.record(
+                command_buffer,
+                self.gpu_sim.particle_buffer(),
+                particle_count,
+                view_proj_cols,
+            );
+        } else if culled {
+            self.culling.record(
+                command_buffer,
+                self.gpu_sim.particle_buffer(),
+                particle_count,
+                view_proj_cols,
+                cull_margin(size_scale, extent),
+            );
+        }
+        let observer = self.observer_position(scale_factor);
+        let pc = with_sampling(
+            PushConstants {
+                culled: (culled || sorted) as u32,
+                ..self.particle_push_constants(view_proj, size_scale, observer)
+            },
+            fraction,
+            phase,
+        );
+        // The accumulation pass is a render pass of its own, so it also goes first.
+        let retention = blur_retention(self.motion_blur_frames);
+        let blurred = retention > 0.0;
+        if blurred {
+            self.motion_blur.begin(command_buffer, extent, retention);
+            self.set_viewport(command_buffer, view_area(primary));
+            self.draw_particles_with(
+                command_buffer,
+                &pc,
+                particle_display_mode,
+                self.accumulation_pipelines[particle_display_mode.pipeline_index()],
+            );
+            self.motion_blur.end(command_buffer);
+        }
+
         unsafe {
             self.device.cmd_begin_render_pass(
                 command_buffer,
@@ -362,92 +790,413 @@ impl ParticleRenderPipeline {
             );
         }
 
-        let viewport = vk::Viewport {
-            x: 0.0,
-            y: 0.0,
-            width: extent.width as f32,
-            height: extent.height as f32,
-            min_depth: 0.0,
-            max_depth: 1.0,
+        let primary_view = SceneView {
+            area: primary,
+            view_proj: self.compute_mvp_axes(aspect_ratio),
+            pc,
+            accumulation_gain: blurred.then(|| composite_gain(retention)),
         };
-        unsafe {
-            self.device.cmd_set_viewport(command_buffer, 0, &[viewport]);
-            self.device.cmd_set_scissor(
+        self.draw_view(
+            command_buffer,
+            &primary_view,
+            show_grid,
+            particle_display_mode,
+        );
+
+        if split {
+            let secondary = view_rect(ViewSide::Secondary, extent.width, extent.height, true);
+            let view_proj = camera_view_proj(&self.second_camera, secondary.aspect_ratio());
+            // The cull pass kept only what the main camera sees, and the depth sort
+            // ordered for it, so draw everything in buffer order.
+            let second_pc = with_sampling(
+                self.particle_push_constants(
+                    view_proj * Mat4::from_scale(Vec3::splat(scale_factor)),
+                    size_scale,
+                    self.second_camera.position / scale_factor,
+                ),
+                fraction,
+                phase,
+            );
+            let second_view = SceneView {
+                area: secondary,
+                view_proj,
+                pc: second_pc,
+                accumulation_gain: None,
+            };
+            self.draw_view(
                 command_buffer,
-                0,
-                &[vk::Rect2D {
-                    offset: vk::Offset2D::default(),
-                    extent,
-                }],
+                &second_view,
+                show_grid,
+                particle_display_mode,
+            );
+            let divider = vk::Rect2D {
+                offset: vk::Offset2D {
+                    x: secondary.x as i32 - 1,
+                    y: 0,
+                },
+                extent: vk::Extent2D {
+                    width: 2,
+                    height: extent.height,
+                },
+            };
+            self.clear_area(command_buffer, divider, SPLIT_VIEW_DIVIDER_COLOR);
+            self.set_viewport(command_buffer, full_area(extent));
+        }
+
+        if let Some(bounds) = self.minimap_bounds {
+            self.sync_minimap_frustum(aspect_ratio);
+            self.draw_minimap(
+                command_buffer,
+                extent,
+                &pc,
+                bounds,
+                scale_factor,
+                particle_display_mode,
             );
         }
 
-        let aspect_ratio = extent.width as f32 / extent.height as f32;
+        gui.draw(command_buffer, extent);
 
-        if show_grid {
-            let view_proj = self.compute_mvp_axes(aspect_ratio);
-            let pc = AxesPushConstants {
-                view_proj: view_proj.to_cols_array_2d(),
-            };
-            self.draw_axes(command_buffer, &pc);
+        unsafe {
+            self.device.cmd_end_render_pass(command_buffer);
         }
+    }
+
+    /// Renders the main camera's view off screen at `settings.width` × `settings.height`,
+    /// independent of the window size, and returns it as tightly packed 8-bit RGB.
+    ///
+    /// Each tile from [`still_tiles`] is drawn `settings.supersampling` times larger and
+    /// box-filtered down, with point sizes following the supersampled height as they follow
+    /// the window's. The minimap, the UI, and motion-blur trails are left out. Waits for the
+    /// GPU to go idle.
+    pub fn render_still(
+        &mut self,
+        base: &VulkanBase,
+        settings: StillSettings,
+        scale: f64,
+        link_point_size_to_scale: bool,
+        show_grid: bool,
+        particle_display_mode: ParticleDisplayMode,
+    ) -> Result<Vec<u8>, String> {
+        let pass = TilePass {
+            target: OffscreenTarget::from_base(base),
+            settings,
+            trails: false,
+        };
+        self.render_tiles(
+            pass,
+            scale,
+            link_point_size_to_scale,
+            show_grid,
+            particle_display_mode,
+        )
+    }
+
+    /// [`Self::render_still`] on an explicit queue and color format, so the scene can be
+    /// drawn into an image without a swapchain.
+    ///
+    /// With motion blur on and a still of a single tile, each call also adds a frame to
+    /// the trails and draws them, so a sequence of offscreen frames shows trails as the
+    /// window does. Larger stills are drawn without trails.
+    pub fn render_offscreen(
+        &mut self,
+        target: OffscreenTarget,
+        settings: StillSettings,
+        scale: f64,
+        link_point_size_to_scale: bool,
+        show_grid: bool,
+        particle_display_mode: ParticleDisplayMode,
+    ) -> Result<Vec<u8>, String> {
+        let pass = TilePass {
+            target,
+            settings,
+            trails: true,
+        };
+        self.render_tiles(
+            pass,
+            scale,
+            link_point_size_to_scale,
+            show_grid,
+            particle_display_mode,
+        )
+    }
+
+    /// Shared body of [`Self::render_still`] and [`Self::render_offscreen`].
+    fn render_tiles(
+        &mut self,
+        pass: TilePass,
+        scale: f64,
+        link_point_size_to_scale: bool,
+        show_grid: bool,
+        particle_display_mode: ParticleDisplayMode,
+    ) -> Result<Vec<u8>, String> {
+        let TilePass {
+            target,
+            settings,
+            trails,
+        } = pass;
+        let factor = settings.supersampling;
+        if !(1..=MAX_STILL_SIZE).contains(&settings.width)
+            || !(1..=MAX_STILL_SIZE).contains(&settings.height)
+            || !STILL_SUPERSAMPLING.contains(&factor)
+        {
+            return Err(format!(
+                "Unsupported still size {} × {} at {}× supersampling",
+                settings.width, settings.height, factor
+            ));
+        }
+        self.wait_device_idle("render_still");
+        self.flush_retired_buffers();
+
+        let tile_extent = vk::Extent2D {
+            width: STILL_TILE_SIZE,
+            height: STILL_TILE_SIZE,
+        };
+        let render_pass = create_render_pass(
+            &self.device,
+            target.color_format,
+            self.depth_format,
+            vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+        );
+        let mut color_image = AllocatedImage::new(
+            &self.device,
+            &self.allocator,
+            STILL_TILE_SIZE,
+            STILL_TILE_SIZE,
+            target.color_format,
+            vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::TRANSFER_SRC,
+            vk::ImageAspectFlags::COLOR,
+            "still-color",
+        );
+        let mut depth_image = create_depth_image(
+            &self.device,
+            &self.allocator,
+            self.depth_format,
+            tile_extent,
+            "still-depth",
+        );
+        let framebuffer = create_framebuffers(
+            &self.device,
+            render_pass,
+            &[color_image.view],
+            depth_image.view,
+            tile_extent,
+        )[0];
+        let readback = AllocatedBuffer::new(
+            &self.device,
+            &self.allocator,
+            u64::from(STILL_TILE_SIZE) * u64::from(STILL_TILE_SIZE) * 4,
+            vk::BufferUsageFlags::TRANSFER_DST,
+            gpu_allocator::MemoryLocation::GpuToCpu,
+            "still-readback",
+        );
+        let pool_ci = vk::CommandPoolCreateInfo::default()
+            .flags(vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER)
+            .queue_family_index(target.queue_family);
+        let command_pool = unsafe { self.device.create_command_pool(&pool_ci, None) }.unwrap();
+        let cb_ci = vk::CommandBufferAllocateInfo::default()
+            .command_pool(command_pool)
+            .level(vk::CommandBufferLevel::PRIMARY)
+            .command_buffer_count(1);
+        let cb = unsafe { self.device.allocate_command_buffers(&cb_ci) }.unwrap()[0];
+        let fence = unsafe {
+            self.device
+                .create_fence(&vk::FenceCreateInfo::default(), None)
+        }
+        .unwrap();
 
         let scale_factor = particle_visual_scale_factor(scale);
-        let view_proj = self.compute_mvp_particle(aspect_ratio, scale_factor);
         let point_scale_factor = if link_point_size_to_scale {
             scale_factor
         } else {
             1.0
         };
-        let view_proj_cols = view_proj.to_cols_array_2d();
         let size_scale = compute_particle_size_scale(
-            extent.height as f32,
+            (settings.height * factor) as f32,
             point_scale_factor,
             particle_display_mode,
         );
-        let pc = PushConstants {
-            view_proj: view_proj_cols,
-            size_scale,
+        let view_proj = self.compute_mvp_axes(settings.width as f32 / settings.height as f32);
+        let model = Mat4::from_scale(Vec3::splat(scale_factor));
+        let observer = self.observer_position(scale_factor);
+        let particle_count = self.gpu_sim.particle_count();
+        let sorted = particle_display_mode.is_depth_sorted() && particle_count > 0;
+        let bgra = matches!(
+            target.color_format,
+            vk::Format::B8G8R8A8_UNORM | vk::Format::B8G8R8A8_SRGB
+        );
+        let clear_values = scene_clear_values();
+        let tiles = still_tiles(settings);
+        // The accumulation holds one image, so only a single-tile still can keep trails.
+        let retention = if trails && tiles.len() == 1 {
+            blur_retention(self.motion_blur_frames)
+        } else {
+            0.0
         };
-
-        self.draw_particles(command_buffer, &pc, particle_display_mode);
-
-        if self.selection_marker_index >= 0 {
-            let width = extent.width.max(1) as f32;
-            let height = extent.height.max(1) as f32;
-            let selection_pc = SelectionMarkerPushConstants {
-                view_proj: view_proj_cols,
-                sizing: [
-                    size_scale,
-                    MIN_HALF_SIZE_PX,
-                    BRACKET_RADIUS_RATIO,
-                    selection_index_bits(self.selection_marker_index),
-                ],
-                viewport: [2.0 / width, 2.0 / height, 0.0, 0.0],
+        let blurred = retention > 0.0;
+        let mut rgb = vec![0; settings.width as usize * settings.height as usize * 3];
+        let mut result = Ok(());
+        for tile in tiles {
+            let area = ViewRect {
+                x: 0,
+                y: 0,
+                width: tile.width * factor,
+                height: tile.height * factor,
             };
-            self.draw_selection_marker(command_buffer, &selection_pc);
-        }
-
-        if self.add_center_marker_vertex_count > 0 {
-            if let Some(ref buf) = self.add_center_marker_buffer {
-                let add_center_pc = AxesPushConstants {
-                    view_proj: self
-                        .compute_mvp_axes(aspect_ratio)
-                        .to_cols_array_2d(),
-                };
-                self.draw_axes_lines(
-                    command_buffer,
-                    &add_center_pc,
-                    buf.buffer,
-                    self.add_center_marker_vertex_count,
+            let view_proj = tile_clip_transform(tile, settings.width, settings.height) * view_proj;
+            let pc = PushConstants {
+                culled: sorted as u32,
+                ..self.particle_push_constants(view_proj * model, size_scale, observer)
+            };
+            let render_pass_info = vk::RenderPassBeginInfo::default()
+                .render_pass(render_pass)
+                .framebuffer(framebuffer)
+                .render_area(view_area(area))
+                .clear_values(&clear_values);
+            let begin_ci = vk::CommandBufferBeginInfo::default()
+                .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT);
+            unsafe {
+                self.device
+                    .reset_command_buffer(cb, vk::CommandBufferResetFlags::empty())
+                    .unwrap();
+                self.device.begin_command_buffer(cb, &begin_ci).unwrap();
+            }
+            if sorted {
+                self.depth_sort.record(
+                    cb,
+                    self.gpu_sim.particle_buffer(),
+                    particle_count,
+                    pc.view_proj,
+                );
+            }
+            if blurred {
+                self.motion_blur.begin(cb, tile_extent, retention);
+                self.set_viewport(cb, view_area(area));
+                self.draw_particles_with(
+                    cb,
+                    &pc,
+                    particle_display_mode,
+                    self.accumulation_pipelines[particle_display_mode.pipeline_index()],
+                );
+                self.motion_blur.end(cb);
+            }
+            unsafe {
+                self.device.cmd_begin_render_pass(
+                    cb,
+                    &render_pass_info,
+                    vk::SubpassContents::INLINE,
                 );
             }
+            let view = SceneView {
+                area,
+                view_proj,
+                pc,
+                accumulation_gain: blurred.then(|| composite_gain(retention)),
+            };
+            self.draw_view(cb, &view, show_grid, particle_display_mode);
+            let region = vk::BufferImageCopy::default()
+                .image_subresource(vk::ImageSubresourceLayers {
+                    aspect_mask: vk::ImageAspectFlags::COLOR,
+                    mip_level: 0,
+                    base_array_layer: 0,
+                    layer_count: 1,
+                })
+                .image_extent(vk::Extent3D {
+                    width: area.width,
+                    height: area.height,
+                    depth: 1,
+                });
+            let to_transfer = vk::MemoryBarrier::default()
+                .src_access_mask(vk::AccessFlags::COLOR_ATTACHMENT_WRITE)
+                .dst_access_mask(vk::AccessFlags::TRANSFER_READ);
+            let to_host = vk::MemoryBarrier::default()
+                .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+                .dst_access_mask(vk::AccessFlags::HOST_READ);
+            unsafe {
+                self.device.cmd_end_render_pass(cb);
+                self.device.cmd_pipeline_barrier(
+                    cb,
+                    vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT,
+                    vk::PipelineStageFlags::TRANSFER,
+                    vk::DependencyFlags::empty(),
+                    &[to_transfer],
+                    &[],
+                    &[],
+                );
+                self.device.cmd_copy_image_to_buffer(
+                    cb,
+                    color_image.image,
+                    vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+                    readback.buffer,
+                    &[region],
+                );
+                self.device.cmd_pipeline_barrier(
+                    cb,
+                    vk::PipelineStageFlags::TRANSFER,
+                    vk::PipelineStageFlags::HOST,
+                    vk::DependencyFlags::empty(),
+                    &[to_host],
+                    &[],
+                    &[],
+                );
+                self.device.end_command_buffer(cb).unwrap();
+            }
+            let command_buffers = [cb];
+            let submit_info = vk::SubmitInfo::default().command_buffers(&command_buffers);
+            let finished = unsafe {
+                self.device
+                    .queue_submit(target.queue, &[submit_info], fence)
+                    .and_then(|()| self.device.wait_for_fences(&[fence], true, u64::MAX))
+                    .and_then(|()| self.device.reset_fences(&[fence]))
+            };
+            if let Err(err) = finished {
+                result = Err(format!("Still rendering failed: {err:?}"));
+                break;
+            }
+            let Some(pixels) = readback
+                .allocation
+                .as_ref()
+                .and_then(|alloc| alloc.mapped_slice())
+            else {
+                result = Err("Still readback buffer is not mapped".to_string());
+                break;
+            };
+            resolve_tile(&mut rgb, settings.width, tile, pixels, factor, bgra);
         }
 
-        gui.draw(command_buffer, extent);
-
+        self.wait_device_idle("render_still");
         unsafe {
-            self.device.cmd_end_render_pass(command_buffer);
+            self.device.destroy_fence(fence, None);
+            self.device.destroy_command_pool(command_pool, None);
+            self.device.destroy_framebuffer(framebuffer, None);
+            self.device.destroy_render_pass(render_pass, None);
+        }
+        readback.destroy(&self.device, &self.allocator);
+        depth_image.destroy(&self.device, &self.allocator);
+        color_image.destroy(&self.device, &self.allocator);
+        result.map(|()| rgb)
+    }
+
+    /// Push constants of an unculled, unsampled particle pass through `view_proj`, in
+    /// particle space, with the observer view seen from `observer`.
+    fn particle_push_constants(
+        &self,
+        view_proj: Mat4,
+        size_scale: f32,
+        observer: Vec3,
+    ) -> PushConstants {
+        let frame = self.observer_view.reference_frame.unwrap_or_default();
+        PushConstants {
+            view_proj: view_proj.to_cols_array_2d(),
+            size_scale,
+            culled: 0,
+            kinematics: self.observer_view.kinematics.shader_code(),
+            view_flags: self.observer_view.shader_flags(),
+            observer: observer
+                .extend(self.observer_view.light_speed as f32)
+                .to_array(),
+            frame_origin: frame.origin.as_vec3().extend(1.0).to_array(),
+            frame_velocity: frame.velocity.as_vec3().extend(0.0).to_array(),
         }
     }
 
@@ -521,16 +1270,315 @@ impl ParticleRenderPipeline {
         );
     }
 
-    // --- Camera methods ---
+    /// Rebuilds the region-of-interest outline while the Region panel shows it.
+    pub fn sync_region_marker(&mut self, ui_state: &crate::ui_state::UiState) {
+        let show_marker = ui_state.is_region_panel_open && ui_state.show_region_outline;
+        let marker_key = (ui_state.region, ui_state.scale_gauge.to_bits());
+        if show_marker && self.last_region_marker_key == Some(marker_key) {
+            return;
+        }
+        let verts = if show_marker {
+            self.last_region_marker_key = Some(marker_key);
+            build_region_marker_vertices(
+                &ui_state.region,
+                particle_visual_scale_factor(ui_state.scale_gauge),
+            )
+        } else if self.region_marker_buffer.is_some() {
+            self.last_region_marker_key = None;
+            Vec::new()
+        } else {
+            return;
+        };
+        let allocator = Arc::clone(&self.allocator);
+        upload_axes_line_buffer(
+            &self.device,
+            &allocator,
+            &mut self.retired_buffers,
+            &mut self.region_marker_buffer,
+            &mut self.region_marker_vertex_count,
+            &verts,
+            "region_marker",
+        );
+    }
+
+    /// Rebuilds the center-of-mass and Lagrangian-radius overlay from the latest profile.
+    pub fn sync_mass_profile_marker(&mut self, ui_state: &crate::ui_state::UiState) {
+        let profile = ui_state
+            .mass_profile
+            .filter(|_| ui_state.show_mass_profile_overlay);
+        let marker_key = profile.map(|profile| (profile, ui_state.scale_gauge.to_bits()));
+        if self.last_mass_profile_marker_key == marker_key {
+            return;
+        }
+        self.last_mass_profile_marker_key = marker_key;
+        let verts = profile
+            .map(|profile| {
+                build_mass_profile_vertices(
+                    &profile,
+                    particle_visual_scale_factor(ui_state.scale_gauge),
+                )
+            })
+            .unwrap_or_default();
+        let allocator = Arc::clone(&self.allocator);
+        upload_axes_line_buffer(
+            &self.device,
+            &allocator,
+            &mut self.retired_buffers,
+            &mut self.mass_profile_marker_buffer,
+            &mut self.mass_profile_marker_vertex_count,
+            &verts,
+            "mass_profile_marker",
+        );
+    }
+
+    /// Rebuilds the escaping-particle crosses after each mass-profile update.
+    pub fn sync_escaper_marker(&mut self, ui_state: &crate::ui_state::UiState) {
+        let marker_key = ui_state.show_escaper_highlight.then_some((
+            ui_state.mass_profile_revision,
+            ui_state.scale_gauge.to_bits(),
+        ));
+        if self.last_escaper_marker_key == marker_key {
+            return;
+        }
+        self.last_escaper_marker_key = marker_key;
+        let verts = if marker_key.is_some() {
+            let visual_scale = particle_visual_scale_factor(ui_state.scale_gauge);
+            ui_state
+                .escaper_positions
+                .iter()
+                .flat_map(|position| {
+                    cross_marker_vertices(
+                        position.as_vec3() * visual_scale,
+                        ESCAPER_MARKER_HALF_EXTENT,
+                        ESCAPER_MARKER_COLOR,
+                    )
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let allocator = Arc::clone(&self.allocator);
+        upload_axes_line_buffer(
+            &self.device,
+            &allocator,
+            &mut self.retired_buffers,
+            &mut self.escaper_marker_buffer,
+            &mut self.escaper_marker_vertex_count,
+            &verts,
+            "escaper_marker",
+        );
+    }
+
+    /// Rebuilds the past light cone and worldline crossings after each light-cone update.
+    pub fn sync_light_cone(&mut self, ui_state: &crate::ui_state::UiState) {
+        let key = ui_state
+            .show_light_cone
+            .then_some((ui_state.light_cone_revision, ui_state.scale_gauge.to_bits()));
+        if self.last_light_cone_key == key {
+            return;
+        }
+        self.last_light_cone_key = key;
+        let verts = match (key, ui_state.light_cone_observer) {
+            (Some(_), Some(observer)) => build_light_cone_vertices(
+                &ui_state.light_cone,
+                observer.as_vec3(),
+                ui_state.light_cone_depth as f32,
+                particle_visual_scale_factor(ui_state.scale_gauge),
+            ),
+            _ => Vec::new(),
+        };
+        let allocator = Arc::clone(&self.allocator);
+        upload_axes_line_buffer(
+            &self.device,
+            &allocator,
+            &mut self.retired_buffers,
+            &mut self.light_cone_buffer,
+            &mut self.light_cone_vertex_count,
+            &verts,
+            "light_cone",
+        );
+    }
+
+    /// Rebuilds the light-speed boundary sphere of the velocity display when the display
+    /// space or the scale gauge changes.
+    pub fn sync_light_speed_sphere(&mut self, ui_state: &crate::ui_state::UiState) {
+        let key = (ui_state.display_space == DisplaySpace::Velocity)
+            .then_some(ui_state.scale_gauge.to_bits());
+        if self.last_light_speed_sphere_key == key {
+            return;
+        }
+        self.last_light_speed_sphere_key = key;
+        let verts = match key {
+            Some(_) => sphere_outline_vertices(
+                Vec3::ZERO,
+                VELOCITY_SPACE_RADIUS as f32 * particle_visual_scale_factor(ui_state.scale_gauge),
+                LIGHT_SPEED_SPHERE_COLOR,
+            ),
+            None => Vec::new(),
+        };
+        let allocator = Arc::clone(&self.allocator);
+        upload_axes_line_buffer(
+            &self.device,
+            &allocator,
+            &mut self.retired_buffers,
+            &mut self.light_speed_sphere_buffer,
+            &mut self.light_speed_sphere_vertex_count,
+            &verts,
+            "light_speed_sphere",
+        );
+    }
+
+    /// Rebuilds the Thomas precession orbit and particle triad while its panel is open.
+    pub fn sync_thomas_precession(&mut self, ui_state: &crate::ui_state::UiState) {
+        let key = ui_state
+            .is_thomas_panel_open
+            .then_some((ui_state.thomas_revision, ui_state.scale_gauge.to_bits()));
+        if self.last_thomas_key == key {
+            return;
+        }
+        self.last_thomas_key = key;
+        let verts = match key {
+            Some(_) => build_thomas_precession_vertices(
+                &ui_state.thomas_precession,
+                particle_visual_scale_factor(ui_state.scale_gauge),
+            ),
+            None => Vec::new(),
+        };
+        let allocator = Arc::clone(&self.allocator);
+        upload_axes_line_buffer(
+            &self.device,
+            &allocator,
+            &mut self.retired_buffers,
+            &mut self.thomas_buffer,
+            &mut self.thomas_vertex_count,
+            &verts,
+            "thomas_precession",
+        );
+    }
+
+    /// Rebuilds the container outline while reflective walls are active.
+    pub fn sync_container_walls(&mut self, ui_state: &crate::ui_state::UiState) {
+        let walls = ui_state.container_walls();
+        let key = walls.map(|walls| (walls.half_size.to_bits(), ui_state.scale_gauge.to_bits()));
+        if self.last_walls_key == key {
+            return;
+        }
+        self.last_walls_key = key;
+        let verts = match walls {
+            Some(walls) => {
+                let half =
+                    walls.half_size as f32 * particle_visual_scale_factor(ui_state.scale_gauge);
+                box_outline_vertices(Vec3::ZERO, Vec3::splat(half), CONTAINER_WALLS_COLOR)
+            }
+            None => Vec::new(),
+        };
+        let allocator = Arc::clone(&self.allocator);
+        upload_axes_line_buffer(
+            &self.device,
+            &allocator,
+            &mut self.retired_buffers,
+            &mut self.walls_buffer,
+            &mut self.walls_vertex_count,
+            &verts,
+            "container_walls",
+        );
+    }
+
+    /// Rebuilds the dashed orbit preview after each preview update.
+    pub fn sync_orbit_preview(&mut self, ui_state: &crate::ui_state::UiState) {
+        let key = (!ui_state.orbit_preview.is_empty()).then_some((
+            ui_state.orbit_preview_revision,
+            ui_state.scale_gauge.to_bits(),
+        ));
+        if self.last_orbit_preview_key == key {
+            return;
+        }
+        self.last_orbit_preview_key = key;
+        let visual_scale = particle_visual_scale_factor(ui_state.scale_gauge);
+        // Every other segment, for a dashed line.
+        let verts: Vec<AxesVertex> = ui_state
+            .orbit_preview
+            .windows(2)
+            .step_by(2)
+            .flat_map(|segment| {
+                segment.iter().map(|position| AxesVertex {
+                    position: (position.as_vec3() * visual_scale).to_array(),
+                    color: ORBIT_PREVIEW_COLOR,
+                })
+            })
+            .collect();
+        let allocator = Arc::clone(&self.allocator);
+        upload_axes_line_buffer(
+            &self.device,
+            &allocator,
+            &mut self.retired_buffers,
+            &mut self.orbit_preview_buffer,
+            &mut self.orbit_preview_vertex_count,
+            &verts,
+            "orbit_preview",
+        );
+    }
+
+    /// Camera position in particle space, the observer for the light cone and the
+    /// relativistic view.
+    pub fn camera_observer(&self, scale_gauge: f64) -> glam::DVec3 {
+        self.observer_position(particle_visual_scale_factor(scale_gauge))
+            .as_dvec3()
+    }
+
+    // --- Camera methods ---
+
+    /// Returns mutable access to the orbit camera.
+    pub fn camera_mut(&mut self) -> &mut OrbitCamera {
+        &mut self.camera
+    }
+
+    /// Focuses the view under the window column `x` for the following mouse input.
+    pub fn focus_view_at(&mut self, x: f64, window_width: u32) {
+        self.focused_view = view_side_at(x, window_width, self.split_view);
+    }
+
+    /// Returns whether mouse input goes to the main camera.
+    pub fn primary_view_focused(&self) -> bool {
+        self.focused_view == ViewSide::Primary
+    }
 
-    /// Returns mutable access to the orbit camera.
-    pub fn camera_mut(&mut self) -> &mut OrbitCamera {
-        &mut self.camera
+    fn focused_camera(&self) -> &OrbitCamera {
+        match self.focused_view {
+            ViewSide::Primary => &self.camera,
+            ViewSide::Secondary => &self.second_camera,
+        }
+    }
+
+    /// Returns the camera of the focused view.
+    pub fn focused_camera_mut(&mut self) -> &mut OrbitCamera {
+        match self.focused_view {
+            ViewSide::Primary => &mut self.camera,
+            ViewSide::Secondary => &mut self.second_camera,
+        }
+    }
+
+    /// Pixel rectangle of the focused view in a `width` × `height` window.
+    pub fn focused_view_rect(&self, width: u32, height: u32) -> ViewRect {
+        view_rect(self.focused_view, width, height, self.split_view)
+    }
+
+    /// Advances the second camera's recentering animation; the main camera's advances in
+    /// `tick_orbit_camera`.
+    pub fn tick_second_camera(&mut self) {
+        if self.split_view {
+            self.second_camera.update_animation();
+        }
+    }
+
+    /// Returns whether a camera recentering animation is running in either view.
+    pub fn is_camera_animating(&self) -> bool {
+        self.camera.is_animating() || (self.split_view && self.second_camera.is_animating())
     }
 
     /// Rotates camera around target using viewport-relative yaw and pitch deltas.
     pub fn revolve_camera(&mut self, delta_yaw: f64, delta_pitch: f64) {
-        self.camera.revolve(
+        self.focused_camera_mut().revolve(
             delta_yaw as f32 * MOUSE_LEFT_DRAG_SENS,
             delta_pitch as f32 * MOUSE_LEFT_DRAG_SENS,
         );
@@ -538,7 +1586,7 @@ impl ParticleRenderPipeline {
 
     /// Rotates camera view direction in place from cursor deltas.
     pub fn look_around(&mut self, dx: f64, dy: f64) {
-        self.camera.look_around(
+        self.focused_camera_mut().look_around(
             dx as f32 * MOUSE_RIGHT_DRAG_SENS,
             dy as f32 * MOUSE_RIGHT_DRAG_SENS,
         );
@@ -557,13 +1605,14 @@ impl ParticleRenderPipeline {
         let prev_angle = (ly - center_y).atan2(lx - center_x);
         let current_angle = (y - center_y).atan2(x - center_x);
         let delta_roll = current_angle - prev_angle;
-        self.camera.rotate(delta_roll as f32);
+        self.focused_camera_mut().rotate(delta_roll as f32);
     }
 
     /// Triggers camera target-centering animation toward world origin.
     pub fn center_target_on_origin(&mut self) {
-        reset_spacecraft_motion(&mut self.camera);
-        self.camera.center_target_on_origin();
+        let camera = self.focused_camera_mut();
+        reset_spacecraft_motion(camera);
+        camera.center_target_on_origin();
     }
 
     /// Restores the camera to its initial position and rotation center.
@@ -573,6 +1622,18 @@ impl ParticleRenderPipeline {
         self.applied_lock_camera_up = None;
     }
 
+    /// Re-targets the camera on a sphere in axes space, keeping the current view direction
+    /// and backing off until the whole sphere fits the vertical field of view.
+    pub fn fit_camera_to_sphere(&mut self, center: Vec3, radius: f32) {
+        let direction = (self.camera.target - self.camera.position)
+            .try_normalize()
+            .unwrap_or_else(|| (INITIAL_TARGET - INITIAL_POSITION).normalize());
+        let distance = fit_camera_distance(radius, CAMERA_FOV_Y);
+        reset_spacecraft_motion(&mut self.camera);
+        self.camera.reset_pose(center - direction * distance, center);
+        self.applied_lock_camera_up = None;
+    }
+
     /// Follows a particle from behind, preserving the current orbit distance.
     pub fn trace_selected_particle(
         &mut self,
@@ -603,6 +1664,7 @@ impl ParticleRenderPipeline {
     /// Reuses the exact MVP transform used by the particle pass so the picked
     /// particle matches what the user actually sees. Particles whose clip-space
     /// position is behind the camera or outside the view frustum are skipped.
+    /// In split view, picks through the camera of the focused view.
     ///
     /// Returns the index into `particles` of the nearest visible particle, or
     /// `None` if no particle is currently visible.
@@ -617,11 +1679,15 @@ impl ParticleRenderPipeline {
         if particles.is_empty() || extent.width == 0 || extent.height == 0 {
             return None;
         }
-        let aspect_ratio = extent.width as f32 / extent.height as f32;
+        let view = self.focused_view_rect(extent.width, extent.height);
+        let camera = self.focused_camera();
         let scale_factor = particle_visual_scale_factor(scale_gauge);
-        let mvp = self.compute_mvp_particle(aspect_ratio, scale_factor);
-        let width = extent.width as f32;
-        let height = extent.height as f32;
+        let mvp = camera_view_proj(camera, view.aspect_ratio())
+            * Mat4::from_scale(Vec3::splat(scale_factor));
+        let width = view.width as f32;
+        let height = view.height as f32;
+        let (click_x, click_y) = (click_x - view.x as f32, click_y - view.y as f32);
+        let observer = (camera.position / scale_factor).as_dvec3();
 
         let mut best: Option<(usize, f32)> = None;
         for (i, p) in particles.iter().enumerate() {
@@ -629,7 +1695,9 @@ impl ParticleRenderPipeline {
             if p.color[3] == 0.0 {
                 continue;
             }
-            let Some(screen_px) = project_particle_screen_px(p, mvp, width, height) else {
+            let mut seen = *p;
+            seen.position = self.observer_view.observe(p, observer).position;
+            let Some(screen_px) = project_particle_screen_px(&seen, mvp, width, height) else {
                 continue;
             };
             let dx = screen_px[0] - click_x;
@@ -642,6 +1710,52 @@ impl ParticleRenderPipeline {
         best.map(|(i, _)| i)
     }
 
+    /// Projects a point of the grid's unrotated axes space to window coordinates of a
+    /// `width` × `height` viewport, following the grid's current rotation.
+    pub fn project_grid_point(&self, point: Vec3, width: f32, height: f32) -> Option<[f32; 2]> {
+        if width <= 0.0 || height <= 0.0 {
+            return None;
+        }
+        let mvp = self.compute_mvp_axes(width / height) * Mat4::from_quat(self.grid_rotation);
+        project_screen_px(point, mvp, width, height)
+    }
+
+    /// Finds the point of the grid plane under window coordinates `x`, `y` of a `width` ×
+    /// `height` viewport, in the grid's unrotated axes space; the inverse of
+    /// [`Self::project_grid_point`] for points on the plane.
+    pub fn unproject_grid_point(&self, x: f32, y: f32, width: f32, height: f32) -> Option<Vec3> {
+        if width <= 0.0 || height <= 0.0 {
+            return None;
+        }
+        let mvp = self.compute_mvp_axes(width / height) * Mat4::from_quat(self.grid_rotation);
+        let inverse = mvp.inverse();
+        let ndc_x = x / width * 2.0 - 1.0;
+        let ndc_y = y / height * 2.0 - 1.0;
+        let near = inverse.project_point3(Vec3::new(ndc_x, ndc_y, 0.0));
+        let far = inverse.project_point3(Vec3::new(ndc_x, ndc_y, 1.0));
+        intersect_grid_plane(near, far)
+    }
+
+    /// Projects where `particle` is seen, after the observer view, to window coordinates
+    /// of a `width` × `height` viewport, like picking does.
+    pub fn project_seen_particle(
+        &self,
+        particle: &Particle,
+        width: f32,
+        height: f32,
+        scale_gauge: f64,
+    ) -> Option<[f32; 2]> {
+        if width <= 0.0 || height <= 0.0 {
+            return None;
+        }
+        let scale_factor = particle_visual_scale_factor(scale_gauge);
+        let mvp = self.compute_mvp_particle(width / height, scale_factor);
+        let observer = self.observer_position(scale_factor).as_dvec3();
+        let mut seen = *particle;
+        seen.position = self.observer_view.observe(particle, observer).position;
+        project_particle_screen_px(&seen, mvp, width, height)
+    }
+
     // --- Draw helpers ---
 
     /// Records draw commands for axis and grid line geometry.
@@ -674,6 +1788,231 @@ impl ParticleRenderPipeline {
         }
     }
 
+    /// Draws the grid, particles, selection marker, and line overlays of `view`.
+    fn draw_view(
+        &self,
+        cb: vk::CommandBuffer,
+        view: &SceneView,
+        show_grid: bool,
+        particle_display_mode: ParticleDisplayMode,
+    ) {
+        let SceneView {
+            area,
+            view_proj,
+            ref pc,
+            accumulation_gain,
+        } = *view;
+        self.set_viewport(cb, view_area(area));
+
+        if show_grid {
+            let grid_pc = AxesPushConstants {
+                view_proj: (view_proj * Mat4::from_quat(self.grid_rotation)).to_cols_array_2d(),
+            };
+            self.draw_axes(cb, &grid_pc);
+        }
+
+        match accumulation_gain {
+            Some(gain) => self.motion_blur.composite(cb, gain),
+            None => self.draw_particles(cb, pc, particle_display_mode),
+        }
+
+        if self.selection_marker_index >= 0 {
+            let width = area.width.max(1) as f32;
+            let height = area.height.max(1) as f32;
+            let selection_pc = SelectionMarkerPushConstants {
+                view_proj: pc.view_proj,
+                sizing: [
+                    pc.size_scale,
+                    MIN_HALF_SIZE_PX,
+                    BRACKET_RADIUS_RATIO,
+                    selection_index_bits(self.selection_marker_index),
+                ],
+                viewport: [2.0 / width, 2.0 / height, 0.0, 0.0],
+            };
+            self.draw_selection_marker(cb, &selection_pc);
+        }
+
+        let overlay_pc = AxesPushConstants {
+            view_proj: view_proj.to_cols_array_2d(),
+        };
+        let overlays = [
+            (
+                &self.add_center_marker_buffer,
+                self.add_center_marker_vertex_count,
+            ),
+            (&self.region_marker_buffer, self.region_marker_vertex_count),
+            (
+                &self.mass_profile_marker_buffer,
+                self.mass_profile_marker_vertex_count,
+            ),
+            (
+                &self.escaper_marker_buffer,
+                self.escaper_marker_vertex_count,
+            ),
+            (&self.light_cone_buffer, self.light_cone_vertex_count),
+            (
+                &self.light_speed_sphere_buffer,
+                self.light_speed_sphere_vertex_count,
+            ),
+            (&self.thomas_buffer, self.thomas_vertex_count),
+            (&self.walls_buffer, self.walls_vertex_count),
+            (&self.orbit_preview_buffer, self.orbit_preview_vertex_count),
+        ];
+        for (buffer, vertex_count) in overlays {
+            if let Some(buf) = buffer {
+                self.draw_axes_lines(cb, &overlay_pc, buf.buffer, vertex_count);
+            }
+        }
+    }
+
+    /// Points the viewport and the scissor at `area`.
+    fn set_viewport(&self, cb: vk::CommandBuffer, area: vk::Rect2D) {
+        let viewport = vk::Viewport {
+            x: area.offset.x as f32,
+            y: area.offset.y as f32,
+            width: area.extent.width as f32,
+            height: area.extent.height as f32,
+            min_depth: 0.0,
+            max_depth: 1.0,
+        };
+        unsafe {
+            self.device.cmd_set_viewport(cb, 0, &[viewport]);
+            self.device.cmd_set_scissor(cb, 0, &[area]);
+        }
+    }
+
+    /// Fills `area` with `color` and clears its depth.
+    fn clear_area(&self, cb: vk::CommandBuffer, area: vk::Rect2D, color: [f32; 4]) {
+        let attachments = [
+            vk::ClearAttachment {
+                aspect_mask: vk::ImageAspectFlags::COLOR,
+                color_attachment: 0,
+                clear_value: vk::ClearValue {
+                    color: vk::ClearColorValue { float32: color },
+                },
+            },
+            vk::ClearAttachment {
+                aspect_mask: vk::ImageAspectFlags::DEPTH,
+                color_attachment: 0,
+                clear_value: vk::ClearValue {
+                    depth_stencil: vk::ClearDepthStencilValue {
+                        depth: 1.0,
+                        stencil: 0,
+                    },
+                },
+            },
+        ];
+        let clear_rect = vk::ClearRect {
+            rect: area,
+            base_array_layer: 0,
+            layer_count: 1,
+        };
+        unsafe {
+            self.device
+                .cmd_clear_attachments(cb, &attachments, &[clear_rect]);
+        }
+    }
+
+    /// Draws the top-down minimap over the bottom-right corner: every particle and the
+    /// main camera's frustum inside a framed inset. Restores the full viewport afterwards.
+    fn draw_minimap(
+        &self,
+        cb: vk::CommandBuffer,
+        extent: vk::Extent2D,
+        pc: &PushConstants,
+        bounds: BoundingSphere,
+        scale_factor: f32,
+        particle_display_mode: ParticleDisplayMode,
+    ) {
+        let Some(rect) = minimap_rect(extent.width, extent.height) else {
+            return;
+        };
+        let view_proj = minimap_view_proj(
+            bounds.center.as_vec3() * scale_factor,
+            bounds.radius as f32 * scale_factor,
+        );
+        let inset = vk::Rect2D {
+            offset: vk::Offset2D {
+                x: rect.x as i32,
+                y: rect.y as i32,
+            },
+            extent: vk::Extent2D {
+                width: rect.size,
+                height: rect.size,
+            },
+        };
+        let border = vk::Rect2D {
+            offset: vk::Offset2D {
+                x: rect.x as i32 - 1,
+                y: rect.y as i32 - 1,
+            },
+            extent: vk::Extent2D {
+                width: rect.size + 2,
+                height: rect.size + 2,
+            },
+        };
+        // Clearing the border and then the inset inside it leaves a 1 px frame.
+        self.clear_area(cb, border, MINIMAP_BORDER_COLOR);
+        self.clear_area(cb, inset, MINIMAP_BACKGROUND_COLOR);
+        self.set_viewport(cb, inset);
+
+        // The cull pass kept only what the main camera sees, so draw everything.
+        let particle_pc = PushConstants {
+            view_proj: (view_proj * Mat4::from_scale(Vec3::splat(scale_factor))).to_cols_array_2d(),
+            size_scale: MINIMAP_POINT_SIZE,
+            culled: 0,
+            ..*pc
+        };
+        self.draw_particles(cb, &particle_pc, particle_display_mode);
+        if let Some(ref buf) = self.minimap_frustum_buffer {
+            let frustum_pc = AxesPushConstants {
+                view_proj: view_proj.to_cols_array_2d(),
+            };
+            self.draw_axes_lines(
+                cb,
+                &frustum_pc,
+                buf.buffer,
+                self.minimap_frustum_vertex_count,
+            );
+        }
+
+        self.set_viewport(cb, full_area(extent));
+    }
+
+    /// Rebuilds the main camera's frustum lines for the minimap after the camera moved.
+    fn sync_minimap_frustum(&mut self, aspect_ratio: f32) {
+        let camera = &self.camera;
+        let key = Some((camera.position, camera.target, camera.up, aspect_ratio));
+        if self.last_minimap_frustum_key == key {
+            return;
+        }
+        self.last_minimap_frustum_key = key;
+        let verts: Vec<AxesVertex> = camera_frustum_lines(
+            camera.position,
+            camera.target,
+            camera.up,
+            aspect_ratio,
+            CAMERA_FOV_Y,
+        )
+        .into_iter()
+        .flatten()
+        .map(|position| AxesVertex {
+            position: position.to_array(),
+            color: MINIMAP_FRUSTUM_COLOR,
+        })
+        .collect();
+        let allocator = Arc::clone(&self.allocator);
+        upload_axes_line_buffer(
+            &self.device,
+            &allocator,
+            &mut self.retired_buffers,
+            &mut self.minimap_frustum_buffer,
+            &mut self.minimap_frustum_vertex_count,
+            &verts,
+            "minimap_frustum",
+        );
+    }
+
     fn draw_selection_marker(&self, cb: vk::CommandBuffer, pc: &SelectionMarkerPushConstants) {
         unsafe {
             self.device.cmd_bind_pipeline(
@@ -706,14 +2045,37 @@ impl ParticleRenderPipeline {
         cb: vk::CommandBuffer,
         pc: &PushConstants,
         particle_display_mode: ParticleDisplayMode,
+    ) {
+        self.draw_particles_with(
+            cb,
+            pc,
+            particle_display_mode,
+            self.particle_pipelines[particle_display_mode.pipeline_index()],
+        );
+    }
+
+    /// Records the particle draw of `particle_display_mode` through `pipeline`, one of
+    /// the scene or the accumulation pipelines.
+    fn draw_particles_with(
+        &self,
+        cb: vk::CommandBuffer,
+        pc: &PushConstants,
+        particle_display_mode: ParticleDisplayMode,
+        pipeline: vk::Pipeline,
     ) {
         let draw_count = self.gpu_sim.particle_count();
         if draw_count == 0 {
             return;
         }
-        let pipeline = self.particle_pipelines[particle_display_mode.pipeline_index()];
+        let sorted = particle_display_mode.is_depth_sorted();
+        let index_set = if sorted {
+            self.depth_sort.draw_set()
+        } else {
+            self.culling.descriptor_set()
+        };
         unsafe {
-            if !self.use_gpu_sim {
+            // With culling or sorting on, that pass already waited for host writes.
+            if !self.use_gpu_sim && pc.culled == 0 {
                 let barrier = vk::MemoryBarrier::default()
                     .src_access_mask(vk::AccessFlags::HOST_WRITE)
                     .dst_access_mask(vk::AccessFlags::SHADER_READ);
@@ -734,7 +2096,7 @@ impl ParticleRenderPipeline {
                 vk::PipelineBindPoint::GRAPHICS,
                 self.layout_particles,
                 0,
-                &[self.gpu_sim.descriptor_set()],
+                &[self.gpu_sim.descriptor_set(), index_set],
                 &[],
             );
             self.device.cmd_push_constants(
@@ -744,23 +2106,24 @@ impl ParticleRenderPipeline {
                 0,
                 bytemuck::bytes_of(pc),
             );
-            self.device.cmd_draw(cb, draw_count, 1, 0, 0);
+            if pc.culled != 0 && !sorted {
+                self.device
+                    .cmd_draw_indirect(cb, self.culling.indirect_buffer(), 0, 1, 0);
+            } else {
+                self.device.cmd_draw(cb, draw_count, 1, 0, 0);
+            }
         }
     }
 
     /// Computes model-view-projection transform for axes and helper geometry.
     fn compute_mvp_axes(&self, aspect_ratio: f32) -> Mat4 {
-        let view = Mat4::look_at_rh(self.camera.position, self.camera.target, self.camera.up);
-        let proj = Mat4::perspective_rh(std::f32::consts::FRAC_PI_4, aspect_ratio, 0.1, 100.0);
-        proj * view
+        camera_view_proj(&self.camera, aspect_ratio)
     }
 
     /// Computes model-view-projection transform for particle-space rendering.
     fn compute_mvp_particle(&self, aspect_ratio: f32, scale_factor: f32) -> Mat4 {
-        let view = Mat4::look_at_rh(self.camera.position, self.camera.target, self.camera.up);
-        let proj = Mat4::perspective_rh(std::f32::consts::FRAC_PI_4, aspect_ratio, 0.1, 100.0);
         let model = Mat4::from_scale(Vec3::splat(scale_factor));
-        proj * view * model
+        self.compute_mvp_axes(aspect_ratio) * model
     }
 
     /// Releases deferred buffers after `wait_for_fence` has completed for the frame.
@@ -786,6 +2149,33 @@ impl Drop for ParticleRenderPipeline {
             if let Some(buf) = self.add_center_marker_buffer.take() {
                 buf.destroy(&self.device, self.allocator());
             }
+            if let Some(buf) = self.region_marker_buffer.take() {
+                buf.destroy(&self.device, self.allocator());
+            }
+            if let Some(buf) = self.mass_profile_marker_buffer.take() {
+                buf.destroy(&self.device, self.allocator());
+            }
+            if let Some(buf) = self.escaper_marker_buffer.take() {
+                buf.destroy(&self.device, self.allocator());
+            }
+            if let Some(buf) = self.light_cone_buffer.take() {
+                buf.destroy(&self.device, self.allocator());
+            }
+            if let Some(buf) = self.light_speed_sphere_buffer.take() {
+                buf.destroy(&self.device, self.allocator());
+            }
+            if let Some(buf) = self.thomas_buffer.take() {
+                buf.destroy(&self.device, self.allocator());
+            }
+            if let Some(buf) = self.walls_buffer.take() {
+                buf.destroy(&self.device, self.allocator());
+            }
+            if let Some(buf) = self.orbit_preview_buffer.take() {
+                buf.destroy(&self.device, self.allocator());
+            }
+            if let Some(buf) = self.minimap_frustum_buffer.take() {
+                buf.destroy(&self.device, self.allocator());
+            }
             self.flush_retired_buffers();
 
             if let Some(alloc) = self.axes_buffer.allocation.take() {
@@ -799,7 +2189,11 @@ impl Drop for ParticleRenderPipeline {
             self.depth_image.destroy(&self.device, &self.allocator);
             self.device.destroy_pipeline(self.pipeline_axes, None);
             self.device.destroy_pipeline(self.pipeline_selection, None);
-            for pipeline in &self.particle_pipelines {
+            for pipeline in self
+                .particle_pipelines
+                .iter()
+                .chain(&self.accumulation_pipelines)
+            {
                 self.device.destroy_pipeline(*pipeline, None);
             }
             self.device.destroy_pipeline_layout(self.layout_axes, None);
@@ -844,6 +2238,200 @@ fn build_add_center_marker_vertices(
     })
 }
 
+/// Builds line-list vertices outlining a selection region in axes space: three great
+/// circles for a sphere, twelve edges for a box.
+fn build_region_marker_vertices(region: &SelectionRegion, visual_scale: f32) -> Vec<AxesVertex> {
+    let center = region.center.as_vec3() * visual_scale;
+    match region.shape {
+        RegionShape::Sphere => sphere_outline_vertices(
+            center,
+            region.radius as f32 * visual_scale,
+            REGION_MARKER_COLOR,
+        ),
+        RegionShape::Box => box_outline_vertices(
+            center,
+            region.half_size.as_vec3() * visual_scale,
+            REGION_MARKER_COLOR,
+        ),
+    }
+}
+
+/// Line-list vertices for the twelve edges of an axis-aligned box in axes space.
+fn box_outline_vertices(center: Vec3, half: Vec3, color: [f32; 4]) -> Vec<AxesVertex> {
+    let corner = |bits: usize| {
+        center
+            + Vec3::new(
+                if bits & 1 == 0 { -half.x } else { half.x },
+                if bits & 2 == 0 { -half.y } else { half.y },
+                if bits & 4 == 0 { -half.z } else { half.z },
+            )
+    };
+    (0..8usize)
+        .flat_map(|a| [1usize, 2, 4].into_iter().map(move |bit| (a, a | bit)))
+        .filter(|(a, b)| a != b)
+        .flat_map(|(a, b)| [corner(a), corner(b)])
+        .map(|position| AxesVertex {
+            position: position.to_array(),
+            color,
+        })
+        .collect()
+}
+
+/// Builds the center-of-mass cross and one sphere outline per Lagrangian radius.
+fn build_mass_profile_vertices(profile: &MassProfile, visual_scale: f32) -> Vec<AxesVertex> {
+    let center = profile.center_of_mass.as_vec3() * visual_scale;
+    let mut verts = cross_marker_vertices(
+        center,
+        CENTER_OF_MASS_MARKER_HALF_EXTENT,
+        CENTER_OF_MASS_MARKER_COLOR,
+    )
+    .to_vec();
+    let radii = profile
+        .lagrangian_radii
+        .map(|radius| radius as f32 * visual_scale);
+    for (radius, color) in radii.into_iter().zip(LAGRANGIAN_RADIUS_COLORS) {
+        verts.extend(sphere_outline_vertices(center, radius, color));
+    }
+    verts
+}
+
+/// Builds the past light cone in axes space: rings and generators from the apex at the
+/// observer down to `depth`, plus a cross at each worldline crossing joined to the
+/// particle's present position.
+fn build_light_cone_vertices(
+    crossings: &[LightConeCrossing],
+    observer: Vec3,
+    depth: f32,
+    visual_scale: f32,
+) -> Vec<AxesVertex> {
+    let apex = observer * visual_scale;
+    let depth = depth * visual_scale;
+    let vertex = |position: Vec3, color: [f32; 4]| AxesVertex {
+        position: position.to_array(),
+        color,
+    };
+    let mut verts = Vec::new();
+    if depth > 0.0 {
+        for ring in 1..=LIGHT_CONE_RINGS {
+            let height = depth * ring as f32 / LIGHT_CONE_RINGS as f32;
+            verts.extend(horizontal_circle_vertices(
+                apex - Vec3::Y * height,
+                height,
+                LIGHT_CONE_COLOR,
+            ));
+        }
+        for i in 0..LIGHT_CONE_GENERATORS {
+            let angle = i as f32 / LIGHT_CONE_GENERATORS as f32 * std::f32::consts::TAU;
+            let (sin, cos) = angle.sin_cos();
+            let rim = apex + Vec3::new(cos * depth, -depth, sin * depth);
+            verts.extend([
+                vertex(apex, LIGHT_CONE_COLOR),
+                vertex(rim, LIGHT_CONE_COLOR),
+            ]);
+        }
+    }
+    for crossing in crossings {
+        let event = crossing.event.as_vec3() * visual_scale;
+        let now = crossing.now.as_vec3() * visual_scale;
+        verts.extend([
+            vertex(event, LIGHT_CONE_WORLDLINE_COLOR),
+            vertex(now, LIGHT_CONE_WORLDLINE_COLOR),
+        ]);
+        verts.extend(cross_marker_vertices(
+            event,
+            LIGHT_CONE_CROSSING_HALF_EXTENT,
+            LIGHT_CONE_CROSSING_COLOR,
+        ));
+    }
+    verts
+}
+
+/// Builds the Thomas precession orbit, the particle's velocity, and its axes triad.
+fn build_thomas_precession_vertices(
+    precession: &ThomasPrecession,
+    visual_scale: f32,
+) -> Vec<AxesVertex> {
+    let radius = THOMAS_ORBIT_RADIUS as f32 * visual_scale;
+    let center = precession.position().as_vec3() * visual_scale;
+    let vertex = |position: Vec3, color: [f32; 4]| AxesVertex {
+        position: position.to_array(),
+        color,
+    };
+    let mut verts = horizontal_circle_vertices(Vec3::ZERO, radius, THOMAS_ORBIT_COLOR);
+    let arm = radius * THOMAS_TRIAD_SCALE;
+    verts.extend([
+        vertex(center, THOMAS_VELOCITY_COLOR),
+        vertex(
+            center + precession.velocity().as_vec3() * arm,
+            THOMAS_VELOCITY_COLOR,
+        ),
+    ]);
+    let orientation = precession.orientation().as_quat();
+    let axes = [Vec3::X, Vec3::Y, Vec3::Z];
+    for (axis, color) in axes.into_iter().zip(THOMAS_TRIAD_COLORS) {
+        verts.extend([
+            vertex(center, color),
+            vertex(center + orientation * axis * arm, color),
+        ]);
+    }
+    verts
+}
+
+/// Line-list vertices for a circle parallel to the grid plane.
+fn horizontal_circle_vertices(center: Vec3, radius: f32, color: [f32; 4]) -> Vec<AxesVertex> {
+    let point = |i: usize| {
+        let angle = i as f32 / OUTLINE_SPHERE_SEGMENTS as f32 * std::f32::consts::TAU;
+        let (sin, cos) = angle.sin_cos();
+        center + Vec3::new(cos, 0.0, sin) * radius
+    };
+    (0..OUTLINE_SPHERE_SEGMENTS)
+        .flat_map(|i| [point(i), point(i + 1)])
+        .map(|position| AxesVertex {
+            position: position.to_array(),
+            color,
+        })
+        .collect()
+}
+
+/// Line-list vertices for an axis-aligned three-line cross centered at `center`.
+fn cross_marker_vertices(center: Vec3, half_extent: f32, color: [f32; 4]) -> [AxesVertex; 6] {
+    let tip = |axis: Vec3| AxesVertex {
+        position: (center + axis * half_extent).to_array(),
+        color,
+    };
+    [
+        tip(-Vec3::X),
+        tip(Vec3::X),
+        tip(-Vec3::Y),
+        tip(Vec3::Y),
+        tip(-Vec3::Z),
+        tip(Vec3::Z),
+    ]
+}
+
+/// Line-list vertices for three orthogonal great circles of a sphere in axes space.
+fn sphere_outline_vertices(center: Vec3, radius: f32, color: [f32; 4]) -> Vec<AxesVertex> {
+    let point = |axis: usize, i: usize| {
+        let angle = i as f32 / OUTLINE_SPHERE_SEGMENTS as f32 * std::f32::consts::TAU;
+        let (sin, cos) = angle.sin_cos();
+        let circle = [
+            Vec3::new(cos, sin, 0.0),
+            Vec3::new(0.0, cos, sin),
+            Vec3::new(cos, 0.0, sin),
+        ];
+        center + circle[axis] * radius
+    };
+    (0..3)
+        .flat_map(|axis| {
+            (0..OUTLINE_SPHERE_SEGMENTS).flat_map(move |i| [point(axis, i), point(axis, i + 1)])
+        })
+        .map(|position| AxesVertex {
+            position: position.to_array(),
+            color,
+        })
+        .collect()
+}
+
 fn upload_axes_line_buffer(
     device: &ash::Device,
     allocator: &Mutex<Allocator>,
@@ -909,13 +2497,12 @@ pub fn project_particle_screen_px(
     width: f32,
     height: f32,
 ) -> Option<[f32; 2]> {
-    let pos = Vec4::new(
-        particle.position.x as f32,
-        particle.position.y as f32,
-        particle.position.z as f32,
-        1.0,
-    );
-    let clip = mvp * pos;
+    project_screen_px(particle.position.as_vec3(), mvp, width, height)
+}
+
+/// Projects a point through MVP to window coordinates when it is inside the view volume.
+fn project_screen_px(position: Vec3, mvp: Mat4, width: f32, height: f32) -> Option<[f32; 2]> {
+    let clip = mvp * position.extend(1.0);
     if !clip.x.is_finite() || !clip.y.is_finite() || !clip.w.is_finite() {
         return None;
     }
@@ -936,6 +2523,59 @@ pub fn project_particle_screen_px(
     Some([screen_x, screen_y])
 }
 
+/// Clear values of the scene's color and depth attachments.
+fn scene_clear_values() -> [vk::ClearValue; 2] {
+    [
+        vk::ClearValue {
+            color: vk::ClearColorValue {
+                float32: [0.0, 0.0, 0.0, 1.0],
+            },
+        },
+        vk::ClearValue {
+            depth_stencil: vk::ClearDepthStencilValue {
+                depth: 1.0,
+                stencil: 0,
+            },
+        },
+    ]
+}
+
+/// View-projection transform of `camera` in axes space.
+fn camera_view_proj(camera: &OrbitCamera, aspect_ratio: f32) -> Mat4 {
+    let view = Mat4::look_at_rh(camera.position, camera.target, camera.up);
+    let proj = Mat4::perspective_rh(CAMERA_FOV_Y, aspect_ratio, 0.1, 100.0);
+    proj * view
+}
+
+/// Render area of a view rectangle.
+fn view_area(rect: ViewRect) -> vk::Rect2D {
+    vk::Rect2D {
+        offset: vk::Offset2D {
+            x: rect.x as i32,
+            y: rect.y as i32,
+        },
+        extent: vk::Extent2D {
+            width: rect.width,
+            height: rect.height,
+        },
+    }
+}
+
+/// Render area covering the whole framebuffer.
+fn full_area(extent: vk::Extent2D) -> vk::Rect2D {
+    vk::Rect2D {
+        offset: vk::Offset2D::default(),
+        extent,
+    }
+}
+
+/// `pc` drawing `fraction` of the particles, picked by the 24-bit `phase`.
+fn with_sampling(mut pc: PushConstants, fraction: f32, phase: u32) -> PushConstants {
+    pc.frame_origin[3] = fraction;
+    pc.frame_velocity[3] = phase as f32;
+    pc
+}
+
 /// Computes perspective-correct point sprite size for the active display mode.
 fn compute_particle_size_scale(
     framebuffer_height: f32,
@@ -945,11 +2585,13 @@ fn compute_particle_size_scale(
     framebuffer_height * PARTICLE_SIZE_RATIO * point_scale_factor * mode.size_scale_factor()
 }
 
-/// Creates a render pass compatible with swapchain color and depth attachments.
+/// Creates a render pass compatible with swapchain color and depth attachments, leaving
+/// the color attachment in `color_final_layout`.
 fn create_render_pass(
     device: &ash::Device,
     color_format: vk::Format,
     depth_format: vk::Format,
+    color_final_layout: vk::ImageLayout,
 ) -> vk::RenderPass {
     let color = vk::AttachmentDescription::default()
         .format(color_format)
@@ -959,7 +2601,7 @@ fn create_render_pass(
         .stencil_load_op(vk::AttachmentLoadOp::DONT_CARE)
         .stencil_store_op(vk::AttachmentStoreOp::DONT_CARE)
         .initial_layout(vk::ImageLayout::UNDEFINED)
-        .final_layout(vk::ImageLayout::PRESENT_SRC_KHR);
+        .final_layout(color_final_layout);
 
     let depth = vk::AttachmentDescription::default()
         .format(depth_format)
@@ -1039,11 +2681,11 @@ fn create_framebuffers(
 }
 
 /// Creates graphics pipeline layout with push constants and descriptor sets.
-fn create_pipeline_layout(
+pub(crate) fn create_pipeline_layout(
     device: &ash::Device,
     push_constant_size: u32,
     push_stages: vk::ShaderStageFlags,
-    descriptor_set_layout: Option<vk::DescriptorSetLayout>,
+    set_layouts: &[vk::DescriptorSetLayout],
 ) -> vk::PipelineLayout {
     let push_range = vk::PushConstantRange {
         stage_flags: push_stages,
@@ -1051,21 +2693,14 @@ fn create_pipeline_layout(
         size: push_constant_size,
     };
     let ranges = [push_range];
-    let layout = if let Some(descriptor_set_layout) = descriptor_set_layout {
-        let set_layouts = [descriptor_set_layout];
-        let ci = vk::PipelineLayoutCreateInfo::default()
-            .set_layouts(&set_layouts)
-            .push_constant_ranges(&ranges);
-        unsafe { device.create_pipeline_layout(&ci, None) }.unwrap()
-    } else {
-        let ci = vk::PipelineLayoutCreateInfo::default().push_constant_ranges(&ranges);
-        unsafe { device.create_pipeline_layout(&ci, None) }.unwrap()
-    };
-    layout
+    let ci = vk::PipelineLayoutCreateInfo::default()
+        .set_layouts(set_layouts)
+        .push_constant_ranges(&ranges);
+    unsafe { device.create_pipeline_layout(&ci, None) }.unwrap()
 }
 
 /// Builds a graphics pipeline from shaders and fixed-function states.
-fn create_graphics_pipeline(
+pub(crate) fn create_graphics_pipeline(
     device: &ash::Device,
     render_pass: vk::RenderPass,
     layout: vk::PipelineLayout,
@@ -1164,8 +2799,31 @@ fn default_blend() -> vk::PipelineColorBlendAttachmentState {
         .blend_enable(false)
 }
 
+/// Returns source-over alpha blend state for sprites drawn back to front.
+fn alpha_blend() -> vk::PipelineColorBlendAttachmentState {
+    vk::PipelineColorBlendAttachmentState::default()
+        .color_write_mask(vk::ColorComponentFlags::RGBA)
+        .blend_enable(true)
+        .src_color_blend_factor(vk::BlendFactor::SRC_ALPHA)
+        .dst_color_blend_factor(vk::BlendFactor::ONE_MINUS_SRC_ALPHA)
+        .color_blend_op(vk::BlendOp::ADD)
+        .src_alpha_blend_factor(vk::BlendFactor::ONE)
+        .dst_alpha_blend_factor(vk::BlendFactor::ONE_MINUS_SRC_ALPHA)
+        .alpha_blend_op(vk::BlendOp::ADD)
+}
+
+/// Returns the blend state a particle mode accumulates motion blur with: additive, with
+/// the occluding modes weighted by their coverage alpha.
+fn accumulation_blend(mode: ParticleDisplayMode) -> vk::PipelineColorBlendAttachmentState {
+    if mode.is_additive() {
+        additive_blend()
+    } else {
+        additive_blend().src_color_blend_factor(vk::BlendFactor::SRC_ALPHA)
+    }
+}
+
 /// Returns additive blend state for luminous point rendering.
-fn additive_blend() -> vk::PipelineColorBlendAttachmentState {
+pub(crate) fn additive_blend() -> vk::PipelineColorBlendAttachmentState {
     vk::PipelineColorBlendAttachmentState::default()
         .color_write_mask(vk::ColorComponentFlags::RGBA)
         .blend_enable(true)
@@ -1204,14 +2862,6 @@ fn axes_vertex_desc() -> (
     (binding, attrs)
 }
 
-/// Defines vertex input bindings and attributes for particle vertices.
-fn particle_vertex_desc() -> (
-    Vec<vk::VertexInputBindingDescription>,
-    Vec<vk::VertexInputAttributeDescription>,
-) {
-    (Vec::new(), Vec::new())
-}
-
 /// Creates graphics pipeline specialized for axis rendering.
 fn create_axes_pipeline(
     device: &ash::Device,
@@ -1221,7 +2871,7 @@ fn create_axes_pipeline(
         device,
         std::mem::size_of::<AxesPushConstants>() as u32,
         vk::ShaderStageFlags::VERTEX,
-        None,
+        &[],
     );
     let (binding, attrs) = axes_vertex_desc();
     let pipeline = create_graphics_pipeline(
@@ -1250,9 +2900,9 @@ fn create_selection_marker_pipeline(
         device,
         std::mem::size_of::<SelectionMarkerPushConstants>() as u32,
         vk::ShaderStageFlags::VERTEX,
-        Some(descriptor_set_layout),
+        &[descriptor_set_layout],
     );
-    let (binding, attrs) = particle_vertex_desc();
+    // No vertex input: the marker is procedural around a particle read from the SSBO.
     let pipeline = create_graphics_pipeline(
         device,
         render_pass,
@@ -1262,8 +2912,8 @@ fn create_selection_marker_pipeline(
             "/shaders/selection_marker_vertex.vert.spv"
         )),
         include_bytes!(concat!(env!("OUT_DIR"), "/shaders/axes_fragment.frag.spv")),
-        &binding,
-        &attrs,
+        &[],
+        &[],
         vk::PrimitiveTopology::LINE_LIST,
         default_blend(),
         vk::CullModeFlags::NONE,
@@ -1276,7 +2926,7 @@ fn create_selection_marker_pipeline(
 fn create_particles_pipelines(
     device: &ash::Device,
     render_pass: vk::RenderPass,
-    descriptor_set_layout: vk::DescriptorSetLayout,
+    set_layouts: &[vk::DescriptorSetLayout],
 ) -> (
     vk::PipelineLayout,
     [vk::Pipeline; ParticleDisplayMode::ALL.len()],
@@ -1285,9 +2935,22 @@ fn create_particles_pipelines(
         device,
         std::mem::size_of::<PushConstants>() as u32,
         vk::ShaderStageFlags::VERTEX,
-        Some(descriptor_set_layout),
+        set_layouts,
     );
-    let (binding, attrs) = particle_vertex_desc();
+    let pipelines = create_particle_mode_pipelines(device, render_pass, layout, false);
+    (layout, pipelines)
+}
+
+/// Creates one particle pipeline per display mode in `render_pass` with `layout`. With
+/// `accumulate`, for the motion-blur pass, every mode adds its light without depth.
+fn create_particle_mode_pipelines(
+    device: &ash::Device,
+    render_pass: vk::RenderPass,
+    layout: vk::PipelineLayout,
+    accumulate: bool,
+) -> [vk::Pipeline; ParticleDisplayMode::ALL.len()] {
+    // No vertex input: the vertex shader reads the compute storage buffer by
+    // `gl_VertexIndex`, so the GPU simulation renders from its own buffer.
     let vs_spv = include_bytes!(concat!(
         env!("OUT_DIR"),
         "/shaders/particles_vertex_ssbo.vert.spv"
@@ -1295,21 +2958,26 @@ fn create_particles_pipelines(
     let mut pipelines = [vk::Pipeline::null(); ParticleDisplayMode::ALL.len()];
     for mode in ParticleDisplayMode::ALL {
         let (fs_spv, blend, depth_enabled) = particle_pipeline_spec(mode);
+        let (blend, depth_enabled) = if accumulate {
+            (accumulation_blend(mode), false)
+        } else {
+            (blend, depth_enabled)
+        };
         pipelines[mode.pipeline_index()] = create_graphics_pipeline(
             device,
             render_pass,
             layout,
             vs_spv,
             fs_spv,
-            &binding,
-            &attrs,
+            &[],
+            &[],
             vk::PrimitiveTopology::POINT_LIST,
             blend,
             vk::CullModeFlags::NONE,
             depth_enabled,
         );
     }
-    (layout, pipelines)
+    pipelines
 }
 
 /// Returns fragment shader bytes, blend state, and depth usage for a particle mode.
@@ -1333,6 +3001,15 @@ fn particle_pipeline_spec(
             default_blend(),
             true,
         ),
+        // Drawn in the depth sort's back-to-front order, so depth testing is not needed.
+        ParticleDisplayMode::Sprite => (
+            include_bytes!(concat!(
+                env!("OUT_DIR"),
+                "/shaders/particles_sprite_fragment.frag.spv"
+            )),
+            alpha_blend(),
+            false,
+        ),
     }
 }
 
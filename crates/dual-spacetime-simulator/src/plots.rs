@@ -0,0 +1,201 @@
+use crate::metrics::{
+    ENERGY_DRIFT, FPS, KINETIC_ENERGY, MetricSeries, POTENTIAL_ENERGY, SELECTED_RADIUS, STEP_TIME,
+    TOTAL_ENERGY, TOTAL_MOMENTUM,
+};
+use std::fmt::Write as _;
+
+/// Size in pixels of a chart exported as PNG.
+pub const PLOT_IMAGE_WIDTH: u32 = 800;
+pub const PLOT_IMAGE_HEIGHT: u32 = 400;
+pub const PLOT_PNG_FILTER_NAME: &str = "PNG Image";
+pub const PLOT_PNG_FILTER_EXT: &str = "png";
+pub const PLOT_CSV_FILTER_NAME: &str = "CSV";
+pub const PLOT_CSV_FILTER_EXT: &str = "csv";
+
+/// Pixels between the edge of an exported chart and its plot area.
+const PLOT_MARGIN: u32 = 16;
+const PLOT_BACKGROUND: [u8; 3] = [16, 18, 24];
+const PLOT_FRAME: [u8; 3] = [90, 96, 110];
+const PLOT_LINE: [u8; 3] = [230, 232, 240];
+
+/// Name of a metric for UI labels; metrics without one show their store name.
+pub fn metric_label(name: &str) -> &str {
+    match name {
+        TOTAL_ENERGY => "Total Energy",
+        KINETIC_ENERGY => "Kinetic Energy",
+        POTENTIAL_ENERGY => "Potential Energy",
+        ENERGY_DRIFT => "Energy Drift",
+        TOTAL_MOMENTUM => "Total Momentum",
+        FPS => "FPS",
+        STEP_TIME => "Step Time (ms)",
+        SELECTED_RADIUS => "Selected Radius",
+        other => other,
+    }
+}
+
+/// Scale of one chart axis.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum AxisScale {
+    #[default]
+    Linear,
+    /// Base-10 logarithm; values that are not positive are left out.
+    Log,
+}
+
+impl AxisScale {
+    pub const ALL: [Self; 2] = [Self::Linear, Self::Log];
+
+    /// Position of `value` along the axis, or `None` when the axis cannot show it.
+    pub fn to_axis(self, value: f64) -> Option<f64> {
+        match self {
+            Self::Linear => Some(value),
+            Self::Log => (value > 0.0).then(|| value.log10()),
+        }
+    }
+
+    /// Value at axis position `position`; the inverse of [`Self::to_axis`].
+    pub fn from_axis(self, position: f64) -> f64 {
+        match self {
+            Self::Linear => position,
+            Self::Log => 10f64.powf(position),
+        }
+    }
+}
+
+impl std::fmt::Display for AxisScale {
+    /// Formats axis scale names for UI selection controls.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            AxisScale::Linear => "Linear",
+            AxisScale::Log => "Log",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// File a chart is exported to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PlotExportFormat {
+    /// The chart as drawn, [`PLOT_IMAGE_WIDTH`]×[`PLOT_IMAGE_HEIGHT`] pixels.
+    Png,
+    /// The metric's samples.
+    Csv,
+}
+
+/// One chart of the Plots window: a metric from the store against simulation time.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PlotChart {
+    pub metric: String,
+    pub time_scale: AxisScale,
+    pub value_scale: AxisScale,
+}
+
+impl PlotChart {
+    pub fn new(metric: &str) -> Self {
+        Self {
+            metric: metric.to_string(),
+            time_scale: AxisScale::Linear,
+            value_scale: AxisScale::Linear,
+        }
+    }
+
+    /// Samples of `series` as `[t, v]` positions on the chart's axes, skipping those an
+    /// axis cannot show.
+    pub fn axis_points(&self, series: &MetricSeries) -> Vec<[f64; 2]> {
+        series
+            .points()
+            .into_iter()
+            .filter_map(|[t, v]| Some([self.time_scale.to_axis(t)?, self.value_scale.to_axis(v)?]))
+            .collect()
+    }
+
+    /// Formats every sample of `series` as CSV with a `t,<metric>` header; the values
+    /// are unscaled whatever the axes.
+    pub fn to_csv(&self, series: &MetricSeries) -> String {
+        let mut csv = format!("t,{}\n", self.metric);
+        for (t, v) in series.iter() {
+            let _ = writeln!(csv, "{:e},{:e}", t, v);
+        }
+        csv
+    }
+}
+
+/// Axis ranges `[[x_min, x_max], [y_min, y_max]]` of `points`, widened around a single
+/// value so every range has a length.
+pub fn plot_bounds(points: &[[f64; 2]]) -> Option<[[f64; 2]; 2]> {
+    let first = *points.first()?;
+    let bounds = points
+        .iter()
+        .fold([[first[0], first[0]], [first[1], first[1]]], |[x, y], p| {
+            [
+                [x[0].min(p[0]), x[1].max(p[0])],
+                [y[0].min(p[1]), y[1].max(p[1])],
+            ]
+        });
+    Some(bounds.map(|[min, max]| {
+        if max > min {
+            [min, max]
+        } else {
+            [min - 0.5, max + 0.5]
+        }
+    }))
+}
+
+/// Draws `points` (axis positions) as a polyline over their [`plot_bounds`] into
+/// `width`×`height` RGB8 pixels, for a chart's PNG export.
+pub fn render_plot_rgb(points: &[[f64; 2]], width: u32, height: u32) -> Vec<u8> {
+    let (w, h) = (width as usize, height as usize);
+    let mut rgb = PLOT_BACKGROUND.repeat(w * h);
+    let mut set = |x: usize, y: usize, color: [u8; 3]| {
+        if x < w && y < h {
+            let i = (y * w + x) * 3;
+            rgb[i..i + 3].copy_from_slice(&color);
+        }
+    };
+    for x in 0..w {
+        set(x, 0, PLOT_FRAME);
+        set(x, h.saturating_sub(1), PLOT_FRAME);
+    }
+    for y in 0..h {
+        set(0, y, PLOT_FRAME);
+        set(w.saturating_sub(1), y, PLOT_FRAME);
+    }
+    let Some([[x_min, x_max], [y_min, y_max]]) = plot_bounds(points) else {
+        return rgb;
+    };
+    let margin = PLOT_MARGIN as f64;
+    let plot_width = (width as f64 - 2.0 * margin - 1.0).max(0.0);
+    let plot_height = (height as f64 - 2.0 * margin - 1.0).max(0.0);
+    let pixels: Vec<(f64, f64)> = points
+        .iter()
+        .map(|p| {
+            (
+                margin + (p[0] - x_min) / (x_max - x_min) * plot_width,
+                margin + (y_max - p[1]) / (y_max - y_min) * plot_height,
+            )
+        })
+        .collect();
+    let mut previous = pixels[0];
+    set(
+        previous.0.round() as usize,
+        previous.1.round() as usize,
+        PLOT_LINE,
+    );
+    for &(x, y) in &pixels[1..] {
+        let steps = (x - previous.0)
+            .abs()
+            .max((y - previous.1).abs())
+            .ceil()
+            .max(1.0);
+        for step in 1..=steps as usize {
+            let s = step as f64 / steps;
+            set(
+                (previous.0 + (x - previous.0) * s).round() as usize,
+                (previous.1 + (y - previous.1) * s).round() as usize,
+                PLOT_LINE,
+            );
+        }
+        previous = (x, y);
+    }
+    rgb
+}
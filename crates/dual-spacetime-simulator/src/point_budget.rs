@@ -0,0 +1,42 @@
+/// Resolution of the sampling hash and phase: 24 bits, exact in `f32`.
+const HASH_RANGE: f32 = 16_777_216.0;
+const HASH_MASK: u32 = 0x00FF_FFFF;
+
+/// Fraction of `count` particles to draw under a budget of `budget` points per frame;
+/// 1 when the budget is off (0) or the particles fit in it. Additive glow multiplies the
+/// kept particles' brightness by its inverse, so the expected image equals the full draw.
+pub fn sampled_fraction(count: u32, budget: u32) -> f32 {
+    if budget == 0 || count <= budget {
+        1.0
+    } else {
+        budget as f32 / count as f32
+    }
+}
+
+/// 24-bit sampling phase of `frame`. Successive frames step by the golden ratio of the
+/// hash range, so the kept sets of nearby frames overlap as little as possible.
+pub fn sampling_phase(frame: u32) -> u32 {
+    frame.wrapping_mul(0x9E37_79B9) >> 8
+}
+
+/// 24-bit hash of a particle index (PCG output permutation).
+pub fn sample_hash(index: u32) -> u32 {
+    let state = index.wrapping_mul(747_796_405).wrapping_add(2_891_336_453);
+    let word = ((state >> ((state >> 28) + 4)) ^ state).wrapping_mul(277_803_737);
+    ((word >> 22) ^ word) >> 8
+}
+
+/// Whether particle `index` is drawn in the frame with `phase` at `fraction`: its hash,
+/// offset by the phase, falls below `fraction` of the hash range. Every particle is kept
+/// with that probability, and the kept set changes with the phase. Mirrors
+/// `particles_vertex_ssbo.vert` bit for bit.
+pub fn is_sampled(index: u32, fraction: f32, phase: u32) -> bool {
+    let slot = (sample_hash(index).wrapping_add(phase) & HASH_MASK) as f32;
+    slot < fraction * HASH_RANGE
+}
+
+/// Point size multiplier that keeps the expected covered area of an occluding display
+/// mode when only `fraction` of the particles are drawn.
+pub fn area_compensation(fraction: f32) -> f32 {
+    fraction.recip().sqrt()
+}
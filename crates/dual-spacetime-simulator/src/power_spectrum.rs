@@ -0,0 +1,215 @@
+use crate::simulation::Particle;
+use std::f64::consts::TAU;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Grid resolutions (cells per side) offered for density deposition; all are powers of two.
+pub const POWER_SPECTRUM_GRID_SIZES: [usize; 3] = [16, 32, 64];
+pub const DEFAULT_POWER_SPECTRUM_GRID: usize = 32;
+pub const POWER_SPECTRUM_FILTER_NAME: &str = "CSV";
+pub const POWER_SPECTRUM_FILTER_EXT: &str = "csv";
+
+/// One spherical shell in k-space.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PowerSpectrumBin {
+    /// Mean wavenumber of the modes in the shell (radians per base-scale unit).
+    pub k: f64,
+    /// Shell-averaged power `V |δ_k|² / N_cells²` (base-scale units cubed).
+    pub power: f64,
+    pub modes: usize,
+}
+
+/// Shell-averaged density power spectrum of the particle distribution.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PowerSpectrum {
+    /// Side of the cubic deposition box (base-scale units).
+    pub box_size: f64,
+    /// Cells per side of the deposition grid.
+    pub grid: usize,
+    /// Shells from the fundamental mode up to the Nyquist wavenumber, ascending in k.
+    pub bins: Vec<PowerSpectrumBin>,
+}
+
+impl PowerSpectrum {
+    /// Formats the bins as CSV with a `k,power,modes` header.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("k,power,modes\n");
+        for bin in &self.bins {
+            let _ = writeln!(csv, "{:e},{:e},{}", bin.k, bin.power, bin.modes);
+        }
+        csv
+    }
+
+    /// Writes [`Self::to_csv`] to `path`, creating parent directories as needed.
+    pub fn save_csv(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_csv())
+    }
+}
+
+/// In-place radix-2 forward DFT, `X_k = Σ x_n e^{-2πi kn/N}`, on `[re, im]` pairs.
+///
+/// `data.len()` must be a power of two.
+pub fn fft_in_place(data: &mut [[f64; 2]]) {
+    let n = data.len();
+    assert!(n.is_power_of_two(), "FFT length must be a power of two");
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let angle = -TAU / len as f64;
+        for start in (0..n).step_by(len) {
+            for m in 0..len / 2 {
+                let (sin, cos) = (angle * m as f64).sin_cos();
+                let [ar, ai] = data[start + m];
+                let [br, bi] = data[start + m + len / 2];
+                let (tr, ti) = (br * cos - bi * sin, br * sin + bi * cos);
+                data[start + m] = [ar + tr, ai + ti];
+                data[start + m + len / 2] = [ar - tr, ai - ti];
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// Transforms an `n³` grid (index `(x * n + y) * n + z`) along all three axes.
+fn fft_3d(grid: &mut [[f64; 2]], n: usize) {
+    let mut line = vec![[0.0; 2]; n];
+    for stride in [1, n, n * n] {
+        for base in 0..n * n {
+            // Enumerate the n² lines running along the axis with this stride.
+            let start = (base / stride) * stride * n + base % stride;
+            for (i, value) in line.iter_mut().enumerate() {
+                *value = grid[start + i * stride];
+            }
+            fft_in_place(&mut line);
+            for (i, value) in line.iter().enumerate() {
+                grid[start + i * stride] = *value;
+            }
+        }
+    }
+}
+
+/// Deposits live particle mass on an `n³` cloud-in-cell grid and returns the density
+/// contrast `δ = ρ / ρ̄ - 1` with the box side.
+///
+/// The cubic box encloses the particles' bounding box with one empty cell of margin per
+/// side; the grid is treated as periodic, so the spectrum of an isolated system also picks
+/// up its overall shape on the largest scales.
+fn deposit_density_contrast(particles: &[Particle], n: usize) -> Option<(Vec<f64>, f64)> {
+    let live: Vec<&Particle> = particles
+        .iter()
+        .filter(|p| p.color[3] != 0.0 && p.position.is_finite())
+        .collect();
+    let first = live.first()?.position;
+    let (min, max) = live.iter().fold((first, first), |(min, max), p| {
+        (min.min(p.position), max.max(p.position))
+    });
+    let extent = (max - min).max_element();
+    if extent <= 0.0 || !extent.is_finite() {
+        return None;
+    }
+    let box_size = extent * n as f64 / (n - 2) as f64;
+    let origin = min - glam::DVec3::splat(box_size / n as f64);
+    let total_mass: f64 = live.iter().map(|p| p.mass.max(0.0)).sum();
+    let weight = |p: &Particle| {
+        if total_mass > 0.0 {
+            p.mass.max(0.0)
+        } else {
+            1.0
+        }
+    };
+
+    let mut density = vec![0.0; n * n * n];
+    for p in &live {
+        let u = (p.position - origin) / box_size * n as f64 - 0.5;
+        let cell = u.floor();
+        let frac = u - cell;
+        for corner in 0..8 {
+            let offset = [corner & 1, (corner >> 1) & 1, (corner >> 2) & 1];
+            let mut w = weight(p);
+            let mut index = 0;
+            for axis in 0..3 {
+                let f = frac[axis];
+                w *= if offset[axis] == 1 { f } else { 1.0 - f };
+                let i = (cell[axis] as i64 + offset[axis] as i64).rem_euclid(n as i64);
+                index = index * n + i as usize;
+            }
+            density[index] += w;
+        }
+    }
+    let mean = density.iter().sum::<f64>() / density.len() as f64;
+    if mean <= 0.0 {
+        return None;
+    }
+    for value in &mut density {
+        *value = *value / mean - 1.0;
+    }
+    Some((density, box_size))
+}
+
+/// Computes the shell-averaged density power spectrum of live particles on an `n³` grid.
+///
+/// Returns `None` for a grid that is not a power of two of at least 4, or when the live
+/// particles do not span a finite, non-zero extent.
+pub fn power_spectrum(particles: &[Particle], n: usize) -> Option<PowerSpectrum> {
+    if n < 4 || !n.is_power_of_two() {
+        return None;
+    }
+    let (contrast, box_size) = deposit_density_contrast(particles, n)?;
+    let mut grid: Vec<[f64; 2]> = contrast.into_iter().map(|d| [d, 0.0]).collect();
+    fft_3d(&mut grid, n);
+
+    let nyquist = n / 2;
+    let cells = (n * n * n) as f64;
+    let volume = box_size.powi(3);
+    let mut sums = vec![(0.0, 0.0, 0usize); nyquist + 1];
+    let signed = |i: usize| {
+        if i < nyquist {
+            i as f64
+        } else {
+            i as f64 - n as f64
+        }
+    };
+    for (index, [re, im]) in grid.iter().enumerate() {
+        let (x, y, z) = (index / (n * n), (index / n) % n, index % n);
+        let radius = (signed(x).powi(2) + signed(y).powi(2) + signed(z).powi(2)).sqrt();
+        let shell = radius.round() as usize;
+        if shell == 0 || shell > nyquist {
+            continue;
+        }
+        let bin = &mut sums[shell];
+        bin.0 += radius;
+        bin.1 += (re * re + im * im) * volume / (cells * cells);
+        bin.2 += 1;
+    }
+    let fundamental = TAU / box_size;
+    let bins = sums
+        .into_iter()
+        .filter(|&(_, _, modes)| modes > 0)
+        .map(|(radius, power, modes)| PowerSpectrumBin {
+            k: fundamental * radius / modes as f64,
+            power: power / modes as f64,
+            modes,
+        })
+        .collect();
+    Some(PowerSpectrum {
+        box_size,
+        grid: n,
+        bins,
+    })
+}
@@ -0,0 +1,235 @@
+use crate::rest_frame::ReferenceFrame;
+use crate::simulation::{Particle, velocity_from_rapidity};
+use crate::ui_state::{DisplaySpace, SimulationType};
+use dst_math::spacetime::velocity_from_momentum;
+use glam::DVec3;
+
+/// Beamed brightness scales as `D^BEAMING_EXPONENT`, the bolometric law for a moving
+/// point source.
+pub const BEAMING_EXPONENT: i32 = 4;
+/// Cap on the beaming brightness factor so approaching particles do not wash out the frame.
+pub const MAX_BEAMING_INTENSITY: f64 = 16.0;
+
+/// Bit in [`ObserverView::shader_flags`]: draw retarded positions with Doppler beaming.
+pub const VIEW_FLAG_ABERRATION: u32 = 1;
+/// Bit in [`ObserverView::shader_flags`]: draw particles in a reference particle's rest frame.
+pub const VIEW_FLAG_REST_FRAME: u32 = 2;
+/// Bit in [`ObserverView::shader_flags`]: plot particles at their velocity.
+pub const VIEW_FLAG_VELOCITY_SPACE: u32 = 4;
+/// Bit in [`ObserverView::shader_flags`]: plot particles at their rapidity.
+pub const VIEW_FLAG_RAPIDITY_SPACE: u32 = 8;
+
+/// Particle-space distance at which the velocity display draws light speed; unit rapidity
+/// lies at the same distance in the rapidity display.
+pub const VELOCITY_SPACE_RADIUS: f64 = 1.0;
+
+/// What the particle buffer stores in `velocity`, so the observer view can recover the
+/// coordinate velocity. Passed to the particle vertex shader as a push constant.
+#[repr(u32)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ViewKinematics {
+    /// Coordinate velocity, with no light-speed limit; the observer view is unavailable.
+    #[default]
+    Off = 0,
+    /// Relativistic momentum (SpeedOfLightLimit).
+    Momentum = 1,
+    /// Rapidity (LorentzTransformation).
+    Rapidity = 2,
+}
+
+impl ViewKinematics {
+    /// Kinematics of the Special engines; `Off` for engines without a light-speed limit
+    /// on their stored velocities.
+    pub fn for_simulation(simulation_type: SimulationType) -> Self {
+        if simulation_type.uses_momentum_particles() {
+            Self::Momentum
+        } else if simulation_type.uses_rapidity_particles() {
+            Self::Rapidity
+        } else {
+            Self::Off
+        }
+    }
+
+    /// Returns the discriminant passed to the particle vertex shader.
+    pub fn shader_code(self) -> u32 {
+        self as u32
+    }
+}
+
+/// Apparent position and brightness of a particle as seen by the camera.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ObservedParticle {
+    pub position: DVec3,
+    /// Multiplier applied to the particle color.
+    pub intensity: f64,
+}
+
+/// Coordinate velocity of a particle stored with `kinematics`.
+pub fn coordinate_velocity(
+    particle: &Particle,
+    kinematics: ViewKinematics,
+    light_speed: f64,
+) -> DVec3 {
+    match kinematics {
+        ViewKinematics::Off => particle.velocity,
+        ViewKinematics::Momentum => {
+            velocity_from_momentum(particle.momentum, particle.mass, light_speed)
+        }
+        ViewKinematics::Rapidity => velocity_from_rapidity(particle.velocity, light_speed),
+    }
+}
+
+/// Where an observer at `observer` sees the particle now: the point it left the light that
+/// arrives now, assuming uniform motion meanwhile. Solves `|x − vτ − o| = cτ` for the
+/// look-back time τ and returns the retarded position with τ. For an observer at rest in
+/// the simulation frame this is the whole aberration of the source.
+pub fn retarded_position(
+    position: DVec3,
+    velocity: DVec3,
+    observer: DVec3,
+    light_speed: f64,
+) -> (DVec3, f64) {
+    let k = light_speed * light_speed - velocity.length_squared();
+    if k <= 0.0 {
+        return (position, 0.0);
+    }
+    let d = position - observer;
+    let dv = d.dot(velocity);
+    let tau = (-dv + (dv * dv + k * d.length_squared()).sqrt()) / k;
+    (position - velocity * tau, tau)
+}
+
+/// Relativistic Doppler factor `D = 1 / (γ (1 − β·n))` for light travelling from a source
+/// at `apparent` to `observer`. Above 1 for approaching sources.
+pub fn doppler_factor(velocity: DVec3, apparent: DVec3, observer: DVec3, light_speed: f64) -> f64 {
+    let beta = velocity / light_speed;
+    let beta_squared = beta.length_squared();
+    if beta_squared >= 1.0 {
+        return 1.0;
+    }
+    let n = (observer - apparent).normalize_or_zero();
+    let gamma = (1.0 - beta_squared).sqrt().recip();
+    1.0 / (gamma * (1.0 - beta.dot(n)))
+}
+
+/// Brightness multiplier for Doppler factor `doppler`.
+pub fn beaming_intensity(doppler: f64) -> f64 {
+    doppler.powi(BEAMING_EXPONENT).min(MAX_BEAMING_INTENSITY)
+}
+
+/// Rapidity vector `atanh(|v|/c) v̂` of a particle moving at `velocity`, clamped just
+/// below light speed.
+pub fn rapidity(velocity: DVec3, light_speed: f64) -> DVec3 {
+    let speed = velocity.length();
+    if speed == 0.0 {
+        return DVec3::ZERO;
+    }
+    velocity * ((speed / light_speed).min(1.0 - f64::EPSILON).atanh() / speed)
+}
+
+/// Where the velocity and rapidity displays plot a particle moving at `velocity`, in
+/// particle space: light speed, and unit rapidity, at [`VELOCITY_SPACE_RADIUS`]. `None`
+/// for [`DisplaySpace::Position`].
+pub fn display_space_position(
+    space: DisplaySpace,
+    velocity: DVec3,
+    light_speed: f64,
+) -> Option<DVec3> {
+    match space {
+        DisplaySpace::Position => None,
+        DisplaySpace::Velocity => Some(velocity * (VELOCITY_SPACE_RADIUS / light_speed)),
+        DisplaySpace::Rapidity => Some(rapidity(velocity, light_speed) * VELOCITY_SPACE_RADIUS),
+    }
+}
+
+/// How the particle vertex shader moves particles away from their simulation-frame
+/// positions. The observer effects need a light-speed limit, so they apply only when
+/// `kinematics` is not [`ViewKinematics::Off`]; the velocity and rapidity displays work
+/// for every engine.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct ObserverView {
+    pub kinematics: ViewKinematics,
+    /// Draw particles where the camera sees them, with Doppler beaming.
+    pub aberration: bool,
+    /// Display frame; `None` draws the simulation frame.
+    pub reference_frame: Option<ReferenceFrame>,
+    pub display_space: DisplaySpace,
+    pub light_speed: f64,
+}
+
+impl ObserverView {
+    /// Returns the `VIEW_FLAG_*` bits passed to the particle vertex shader.
+    pub fn shader_flags(&self) -> u32 {
+        let mut flags = match self.display_space {
+            DisplaySpace::Position => 0,
+            DisplaySpace::Velocity => VIEW_FLAG_VELOCITY_SPACE,
+            DisplaySpace::Rapidity => VIEW_FLAG_RAPIDITY_SPACE,
+        };
+        if self.kinematics == ViewKinematics::Off {
+            return flags;
+        }
+        // Light-travel delay is meaningless once particles are plotted in velocity space.
+        if self.aberration && self.display_space == DisplaySpace::Position {
+            flags |= VIEW_FLAG_ABERRATION;
+        }
+        if self.reference_frame.is_some() {
+            flags |= VIEW_FLAG_REST_FRAME;
+        }
+        flags
+    }
+
+    /// Whether drawn positions differ from simulation-frame positions.
+    pub fn is_active(&self) -> bool {
+        self.shader_flags() != 0
+    }
+
+    /// Apparent position and brightness of `particle` for a camera at `observer`; mirrors
+    /// the particle vertex shader so picking matches what is drawn.
+    pub fn observe(&self, particle: &Particle, observer: DVec3) -> ObservedParticle {
+        let flags = self.shader_flags();
+        let mut seen = ObservedParticle {
+            position: particle.position,
+            intensity: 1.0,
+        };
+        if flags == 0 {
+            return seen;
+        }
+        let mut velocity = coordinate_velocity(particle, self.kinematics, self.light_speed);
+        if let Some(frame) = self
+            .reference_frame
+            .filter(|_| flags & VIEW_FLAG_REST_FRAME != 0)
+        {
+            (seen.position, velocity) = frame.transform(seen.position, velocity, self.light_speed);
+        }
+        if let Some(position) =
+            display_space_position(self.display_space, velocity, self.light_speed)
+        {
+            seen.position = position;
+        } else if flags & VIEW_FLAG_ABERRATION != 0 {
+            let (position, _) =
+                retarded_position(seen.position, velocity, observer, self.light_speed);
+            let doppler = doppler_factor(velocity, position, observer, self.light_speed);
+            seen.position = position;
+            seen.intensity = beaming_intensity(doppler);
+        }
+        seen
+    }
+}
+
+/// Apparent position and brightness of `particle` for a camera at rest in the simulation
+/// frame at `observer`.
+pub fn observe(
+    particle: &Particle,
+    kinematics: ViewKinematics,
+    observer: DVec3,
+    light_speed: f64,
+) -> ObservedParticle {
+    ObserverView {
+        kinematics,
+        aberration: true,
+        reference_frame: None,
+        display_space: DisplaySpace::Position,
+        light_speed,
+    }
+    .observe(particle, observer)
+}
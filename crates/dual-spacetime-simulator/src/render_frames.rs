@@ -0,0 +1,173 @@
+use crate::particle_snapshot::ParticleSnapshot;
+use crate::pipeline::{OffscreenTarget, ParticleRenderPipeline};
+use crate::simulate::{DEFAULT_FRAME_TIME, parse_number, split_mode_args};
+use crate::simulation::SimulationManager;
+use crate::still_image::{STILL_SUPERSAMPLING, StillSettings, save_png};
+use crate::ui_state::{ParticleDisplayMode, particle_visual_scale_factor};
+use crate::view_fit::{fit_scale_gauge, particle_bounding_sphere};
+use ash::vk;
+use std::path::{Path, PathBuf};
+use vulkanvil::HeadlessBase;
+
+/// Flag that switches the binary from the window to [`render_frames`].
+pub const RENDER_FRAMES_FLAG: &str = "--render-frames";
+const OFFSCREEN_FORMAT: vk::Format = vk::Format::R8G8B8A8_UNORM;
+
+pub const RENDER_FRAMES_USAGE: &str = "\
+Usage: dual-spacetime-simulator --render-frames <scenario.zip> [options]
+
+Renders frames of a saved scenario to PNG files without opening a window.
+
+Options:
+  --out <dir>                Output directory (default: frames)
+  --frames <n>               Frames to write (default: 100)
+  --steps-per-frame <n>      Simulation steps between frames (default: 1)
+  --time-per-frame <s>       Simulated seconds per step (default: 10)
+  --width <px>               Image width (default: 1920)
+  --height <px>              Image height (default: 1080)
+  --supersampling <1|2|4>    Rendered pixels per output pixel (default: 1)
+  --display <mode>           Particle display: glow, sphere, or sprite (default: glow)
+  --grid                     Draw the axes grid";
+
+/// What a `--render-frames` run renders and where it writes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderFramesOptions {
+    pub scenario: PathBuf,
+    pub output_dir: PathBuf,
+    pub frames: u32,
+    pub steps_per_frame: u32,
+    pub time_per_frame: f64,
+    pub still: StillSettings,
+    pub particle_display_mode: ParticleDisplayMode,
+    pub show_grid: bool,
+}
+
+impl RenderFramesOptions {
+    pub fn new(scenario: PathBuf) -> Self {
+        Self {
+            scenario,
+            output_dir: PathBuf::from("frames"),
+            frames: 100,
+            steps_per_frame: 1,
+            time_per_frame: DEFAULT_FRAME_TIME,
+            still: StillSettings {
+                width: 1920,
+                height: 1080,
+                supersampling: 1,
+            },
+            particle_display_mode: ParticleDisplayMode::Glow,
+            show_grid: false,
+        }
+    }
+}
+
+/// Parses the command line (without the program name). Returns `None` when
+/// [`RENDER_FRAMES_FLAG`] is absent, so the window starts as usual.
+pub fn parse_render_frames_args(args: &[String]) -> Result<Option<RenderFramesOptions>, String> {
+    let Some(split) = split_mode_args(args, RENDER_FRAMES_FLAG) else {
+        return Ok(None);
+    };
+    let (scenario, rest) = split?;
+    let mut options = RenderFramesOptions::new(scenario);
+    let mut rest = rest.into_iter();
+    while let Some(arg) = rest.next() {
+        let mut value = || rest.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--out" => options.output_dir = PathBuf::from(value()?),
+            "--frames" => options.frames = parse_number(arg, value()?)?,
+            "--steps-per-frame" => options.steps_per_frame = parse_number(arg, value()?)?,
+            "--time-per-frame" => options.time_per_frame = parse_number(arg, value()?)?,
+            "--width" => options.still.width = parse_number(arg, value()?)?,
+            "--height" => options.still.height = parse_number(arg, value()?)?,
+            "--supersampling" => options.still.supersampling = parse_number(arg, value()?)?,
+            "--display" => {
+                options.particle_display_mode = match value()?.as_str() {
+                    "glow" => ParticleDisplayMode::Glow,
+                    "sphere" => ParticleDisplayMode::Sphere,
+                    "sprite" => ParticleDisplayMode::Sprite,
+                    other => return Err(format!("Unknown display mode '{}'", other)),
+                }
+            }
+            "--grid" => options.show_grid = true,
+            other => return Err(format!("Unknown option '{}'", other)),
+        }
+    }
+    if options.frames == 0 {
+        return Err("--frames must be at least 1".to_string());
+    }
+    if !STILL_SUPERSAMPLING.contains(&options.still.supersampling) {
+        return Err(format!(
+            "--supersampling must be one of {:?}",
+            STILL_SUPERSAMPLING
+        ));
+    }
+    if !options.time_per_frame.is_finite() {
+        return Err("--time-per-frame must be finite".to_string());
+    }
+    Ok(Some(options))
+}
+
+/// File the frame at `index` is written to.
+pub fn frame_path(output_dir: &Path, index: u32) -> PathBuf {
+    output_dir.join(format!("frame_{:05}.png", index))
+}
+
+/// Loads the scenario, then renders `options.frames` frames on a device without a window,
+/// advancing the CPU simulation `steps_per_frame` steps between them. The first frame
+/// shows the loaded state. The camera is fitted to the particles once, like View ▸ Fit.
+pub fn render_frames(options: &RenderFramesOptions) -> Result<(), String> {
+    let snapshot = ParticleSnapshot::load(&options.scenario)
+        .map_err(|e| format!("Failed to load {}: {}", options.scenario.display(), e))?;
+    let simulation_type = snapshot.simulation_type;
+    let simulation_manager = SimulationManager::new();
+    simulation_manager.load_from_snapshot(snapshot);
+    std::fs::create_dir_all(&options.output_dir)
+        .map_err(|e| format!("Failed to create {}: {}", options.output_dir.display(), e))?;
+
+    let base = HeadlessBase::new(c"DualSpacetimeSimulator", vk::make_api_version(0, 0, 2, 0))?;
+    let mut pipeline = ParticleRenderPipeline::new_headless(
+        &base.instance,
+        base.physical_device,
+        base.device.clone(),
+        base.allocator.clone().unwrap(),
+        OFFSCREEN_FORMAT,
+    );
+    let target = OffscreenTarget {
+        color_format: OFFSCREEN_FORMAT,
+        queue: base.graphics_queue,
+        queue_family: base.graphics_queue_family,
+    };
+
+    let mut particles = simulation_manager.particles();
+    let sphere = particle_bounding_sphere(&particles);
+    let scale_gauge = fit_scale_gauge(sphere.map_or(0.0, |sphere| sphere.radius));
+    if let Some(sphere) = sphere {
+        let visual_scale = particle_visual_scale_factor(scale_gauge);
+        pipeline.fit_camera_to_sphere(
+            sphere.center.as_vec3() * visual_scale,
+            sphere.radius as f32 * visual_scale,
+        );
+    }
+    for index in 0..options.frames {
+        if index > 0 {
+            for _ in 0..options.steps_per_frame {
+                simulation_manager.advance(options.time_per_frame);
+            }
+            simulation_manager.copy_particles_into(&mut particles);
+        }
+        pipeline.upload_particles(&particles, simulation_type);
+        let rgb = pipeline.render_offscreen(
+            target,
+            options.still,
+            scale_gauge,
+            false,
+            options.show_grid,
+            options.particle_display_mode,
+        )?;
+        let path = frame_path(&options.output_dir, index);
+        save_png(&path, options.still.width, options.still.height, &rgb)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        println!("Wrote {}", path.display());
+    }
+    Ok(())
+}
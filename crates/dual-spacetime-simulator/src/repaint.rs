@@ -0,0 +1,72 @@
+use std::time::{Duration, Instant};
+
+/// Longest an idle window waits between redraws, so readouts that change without an input
+/// event (export progress, worker status) still refresh while the simulation is paused.
+pub const IDLE_REPAINT_INTERVAL: Duration = Duration::from_millis(100);
+/// How often the frame, time and FPS readouts pick up new values (10 Hz).
+pub const INDICATOR_REFRESH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// When the event loop draws its next frame.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RepaintSchedule {
+    Now,
+    At(Instant),
+}
+
+/// Decides the next redraw. A changing scene (running simulation, pending upload, moving
+/// camera) redraws at once; otherwise the window waits for the UI's own repaint deadline,
+/// but never longer than [`IDLE_REPAINT_INTERVAL`] after `last_redraw`.
+pub fn schedule_repaint(
+    scene_changing: bool,
+    ui_deadline: Option<Instant>,
+    last_redraw: Option<Instant>,
+    now: Instant,
+) -> RepaintSchedule {
+    let Some(last_redraw) = last_redraw.filter(|_| !scene_changing) else {
+        return RepaintSchedule::Now;
+    };
+    let idle_deadline = last_redraw + IDLE_REPAINT_INTERVAL;
+    let deadline = ui_deadline.map_or(idle_deadline, |ui| ui.min(idle_deadline));
+    if deadline <= now {
+        RepaintSchedule::Now
+    } else {
+        RepaintSchedule::At(deadline)
+    }
+}
+
+/// A readout value refreshed from its source at most once per interval, so labels that
+/// change every simulation step stay legible and do not force a repaint of their own.
+#[derive(Clone, Copy, Debug)]
+pub struct Throttled<T> {
+    interval: Duration,
+    shown: Option<(T, Instant)>,
+}
+
+impl<T: Copy> Throttled<T> {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            shown: None,
+        }
+    }
+
+    /// Returns the shown value, taking `latest` instead once `interval` has passed since
+    /// the last refresh.
+    pub fn get(&mut self, now: Instant, latest: impl FnOnce() -> T) -> T {
+        match self.shown {
+            Some((value, refreshed_at)) if now.duration_since(refreshed_at) < self.interval => {
+                value
+            }
+            _ => {
+                let value = latest();
+                self.shown = Some((value, now));
+                value
+            }
+        }
+    }
+
+    /// Makes the next [`Self::get`] take the latest value, e.g. after a reset.
+    pub fn invalidate(&mut self) {
+        self.shown = None;
+    }
+}
@@ -0,0 +1,116 @@
+use crate::relativistic_view::rapidity;
+use dst_math::spacetime::Spacetime;
+use glam::DVec3;
+use std::time::Instant;
+
+/// Seconds a frame switch takes to blend from the old display frame to the new one.
+pub const FRAME_TRANSITION_SECONDS: f64 = 0.75;
+
+/// Inertial frame particles are displayed in: the instantaneous rest frame of a reference
+/// particle at `origin` moving at `velocity` through the simulation frame.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct ReferenceFrame {
+    pub origin: DVec3,
+    pub velocity: DVec3,
+}
+
+impl ReferenceFrame {
+    /// Displayed position and velocity of a particle at `position` moving at `velocity`,
+    /// both in the simulation frame at the current simulation time.
+    ///
+    /// The event is boosted into this frame with
+    /// [`Spacetime::apply_lorentz_transform_by_velocity`] and then moved along the boosted
+    /// velocity to the frame's own present, so the result is a snapshot simultaneous for
+    /// the reference particle. The origin stays where it is drawn in the simulation frame.
+    pub fn transform(self, position: DVec3, velocity: DVec3, light_speed: f64) -> (DVec3, DVec3) {
+        let inverse_light_speed = light_speed.recip();
+        let d = position - self.origin;
+        let mut event = Spacetime::new(0.0, d.x, d.y, d.z);
+        event.apply_lorentz_transform_by_velocity(-self.velocity, inverse_light_speed);
+        let gamma = (1.0 - velocity.length_squared() * inverse_light_speed * inverse_light_speed)
+            .max(f64::MIN_POSITIVE)
+            .sqrt()
+            .recip();
+        let mut four_velocity = Spacetime::new(
+            gamma * light_speed,
+            gamma * velocity.x,
+            gamma * velocity.y,
+            gamma * velocity.z,
+        );
+        four_velocity.apply_lorentz_transform_by_velocity(-self.velocity, inverse_light_speed);
+        let boosted = DVec3::new(four_velocity.x, four_velocity.y, four_velocity.z)
+            * (light_speed / four_velocity.t);
+        let displaced = DVec3::new(event.x, event.y, event.z) - boosted * (event.t / light_speed);
+        (self.origin + displaced, boosted)
+    }
+
+    /// Blend `t` (0..=1) of the way to `to`. The origin moves linearly and the velocity
+    /// through rapidity, so intermediate frames never reach light speed.
+    pub fn lerp(self, to: Self, t: f64, light_speed: f64) -> Self {
+        let rapidity =
+            rapidity(self.velocity, light_speed).lerp(rapidity(to.velocity, light_speed), t);
+        let eta = rapidity.length();
+        let velocity = if eta == 0.0 {
+            DVec3::ZERO
+        } else {
+            rapidity * (light_speed * eta.tanh() / eta)
+        };
+        Self {
+            origin: self.origin.lerp(to.origin, t),
+            velocity,
+        }
+    }
+}
+
+/// The display frame over time: follows the reference particle's frame and blends over
+/// [`FRAME_TRANSITION_SECONDS`] whenever the reference changes.
+#[derive(Clone, Debug, Default)]
+pub struct FrameSwitcher {
+    /// Frame displayed by the last update; `None` is the simulation frame.
+    shown: Option<ReferenceFrame>,
+    /// Frame the running blend started from, and when.
+    transition: Option<(Option<ReferenceFrame>, Instant)>,
+}
+
+impl FrameSwitcher {
+    /// Starts blending from the currently shown frame; call when the reference changes.
+    pub fn begin(&mut self, now: Instant) {
+        self.transition = Some((self.shown, now));
+    }
+
+    /// Advances to `now` towards `target` (`None` for the simulation frame) and returns
+    /// the frame to display, `None` once back in the simulation frame.
+    pub fn update(
+        &mut self,
+        target: Option<ReferenceFrame>,
+        now: Instant,
+        light_speed: f64,
+    ) -> Option<ReferenceFrame> {
+        // Returning to the simulation frame keeps the last origin so only the boost fades.
+        let goal = target.unwrap_or(ReferenceFrame {
+            origin: self.shown.map_or(DVec3::ZERO, |frame| frame.origin),
+            velocity: DVec3::ZERO,
+        });
+        let frame = match self.transition {
+            Some((from, start)) => {
+                let t = now.duration_since(start).as_secs_f64() / FRAME_TRANSITION_SECONDS;
+                if t >= 1.0 {
+                    self.transition = None;
+                    target
+                } else {
+                    // Leaving the simulation frame starts at the goal's origin so only the
+                    // boost grows.
+                    let from = from.unwrap_or(ReferenceFrame {
+                        origin: goal.origin,
+                        velocity: DVec3::ZERO,
+                    });
+                    let eased = t * t * (3.0 - 2.0 * t);
+                    Some(from.lerp(goal, eased, light_speed))
+                }
+            }
+            None => target,
+        };
+        self.shown = frame;
+        frame
+    }
+}
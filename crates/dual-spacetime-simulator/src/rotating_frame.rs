@@ -0,0 +1,54 @@
+use crate::simulation::Particle;
+use glam::{DQuat, DVec3};
+use rayon::prelude::*;
+
+/// Velocity a particle moving at `velocity` through the inertial frame has in a frame
+/// rotating at `angular_velocity` (rad/s about +y): `v − Ω × r`.
+pub fn velocity_in_rotating_frame(
+    position: DVec3,
+    velocity: DVec3,
+    angular_velocity: f64,
+) -> DVec3 {
+    velocity - DVec3::Y.cross(position) * angular_velocity
+}
+
+/// Re-expresses inertial-frame velocities in the rotating frame, so generated initial
+/// conditions mean the same motion once the fictitious forces apply.
+pub fn enter_rotating_frame(particles: &mut [Particle], angular_velocity: f64) {
+    if angular_velocity == 0.0 {
+        return;
+    }
+    for particle in particles.iter_mut() {
+        particle.velocity =
+            velocity_in_rotating_frame(particle.position, particle.velocity, angular_velocity);
+    }
+}
+
+/// Applies one step of the centrifugal and Coriolis accelerations of a frame rotating
+/// at `angular_velocity` about +y. The Coriolis term is applied as an exact rotation of
+/// the velocity, so it turns particles without doing work on them.
+pub fn rotating_frame_velocity_update(
+    particles: &mut [Particle],
+    angular_velocity: f64,
+    delta_seconds: f64,
+) {
+    if angular_velocity == 0.0 {
+        return;
+    }
+    let centrifugal = angular_velocity * angular_velocity * delta_seconds;
+    let coriolis = DQuat::from_rotation_y(-2.0 * angular_velocity * delta_seconds);
+    particles.par_iter_mut().for_each(|particle| {
+        let radial = DVec3::new(particle.position.x, 0.0, particle.position.z);
+        particle.velocity = coriolis * (particle.velocity + radial * centrifugal);
+    });
+}
+
+/// Angular velocity about +y of a particle at `position` moving at `velocity` around the
+/// origin, in rad/s; negative is clockwise seen from +y. Zero on the axis.
+pub fn orbital_angular_velocity(position: DVec3, velocity: DVec3) -> f64 {
+    let radius_squared = position.x * position.x + position.z * position.z;
+    if radius_squared == 0.0 {
+        return 0.0;
+    }
+    position.cross(velocity).y / radius_squared
+}
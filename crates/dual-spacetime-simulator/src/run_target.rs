@@ -0,0 +1,92 @@
+use crate::time_format::{SECONDS_PER_DAY, SECONDS_PER_MYR, SECONDS_PER_YEAR};
+
+/// Unit a finite run's length is entered in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum RunLengthUnit {
+    Seconds,
+    Days,
+    #[default]
+    Years,
+    Myr,
+}
+
+impl RunLengthUnit {
+    pub const ALL: [Self; 4] = [Self::Seconds, Self::Days, Self::Years, Self::Myr];
+
+    /// Simulation seconds in one of this unit.
+    pub fn seconds(self) -> f64 {
+        match self {
+            Self::Seconds => 1.0,
+            Self::Days => SECONDS_PER_DAY,
+            Self::Years => SECONDS_PER_YEAR,
+            Self::Myr => SECONDS_PER_MYR,
+        }
+    }
+}
+
+impl std::fmt::Display for RunLengthUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            RunLengthUnit::Seconds => "s",
+            RunLengthUnit::Days => "d",
+            RunLengthUnit::Years => "yr",
+            RunLengthUnit::Myr => "Myr",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// Simulation time a run stops at, measured from the last reset.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RunTarget {
+    pub enabled: bool,
+    pub length: f64,
+    pub unit: RunLengthUnit,
+    /// Saves a snapshot to the event snapshot directory when the run ends.
+    pub snapshot_at_end: bool,
+}
+
+impl Default for RunTarget {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            length: 10.0,
+            unit: RunLengthUnit::default(),
+            snapshot_at_end: false,
+        }
+    }
+}
+
+impl RunTarget {
+    /// End time in simulation seconds; `None` while disabled or without a positive length.
+    pub fn end_time(&self) -> Option<f64> {
+        (self.enabled && self.length > 0.0).then(|| self.length * self.unit.seconds())
+    }
+}
+
+/// Fraction of the run to `end_time` done at `simulation_time`, in `[0, 1]`.
+pub fn run_progress(simulation_time: f64, end_time: f64) -> f32 {
+    if end_time <= 0.0 {
+        return 1.0;
+    }
+    (simulation_time / end_time).clamp(0.0, 1.0) as f32
+}
+
+/// Returns whether the step from `previous_time` to `simulation_time` reaches `end_time`.
+/// Only the step that crosses the end counts, so a run restarted past its end continues.
+/// Summing `time_per_frame` step by step can fall just short of a whole multiple, so the
+/// end counts as reached within a millionth of a step.
+pub fn reaches_run_end(
+    previous_time: f64,
+    simulation_time: f64,
+    end_time: f64,
+    time_per_frame: f64,
+) -> bool {
+    let end = end_time - time_per_frame.abs() * 1e-6;
+    previous_time < end && simulation_time >= end
+}
+
+/// File name of the snapshot saved when a run ends at `frame`.
+pub fn run_end_snapshot_file_name(frame: i64) -> String {
+    format!("run_end_frame_{}.zip", frame)
+}
@@ -1,4 +1,12 @@
-use crate::ui_state::ParticleDisplayMode;
+use crate::display_mode::DisplayMode;
+use crate::energy_monitor::DEFAULT_ENERGY_DRIFT_THRESHOLD;
+use crate::memory_budget::DEFAULT_MEMORY_BUDGET_MB;
+use crate::metric_expr::DerivedMetricDefinition;
+use crate::metrics::RetentionPolicy;
+use crate::palette::PaletteSettings;
+use crate::time_format::TimeDisplayUnit;
+use crate::ui_profile::{DEFAULT_UI_PROFILE, UiProfile};
+use crate::ui_state::{ParticleDisplayMode, ScaleGaugeMode};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io;
@@ -15,6 +23,37 @@ pub struct AppSettings {
     pub mailbox_present_mode: bool,
     #[serde(default)]
     pub particle_display_mode: ParticleDisplayMode,
+    #[serde(default)]
+    pub time_display_unit: TimeDisplayUnit,
+    #[serde(default)]
+    pub auto_fit_on_reset: bool,
+    #[serde(default)]
+    pub scale_gauge_mode: ScaleGaugeMode,
+    pub gpu_frustum_culling: bool,
+    /// Points drawn per frame before the particles are subsampled; 0 draws them all.
+    pub point_budget: u32,
+    /// Frames a motion-blur trail lasts; 0 turns motion blur off.
+    pub motion_blur_frames: u32,
+    pub memory_budget_mb: u32,
+    pub palettes: PaletteSettings,
+    /// Name of the active UI profile.
+    pub ui_profile: String,
+    pub ui_profiles: Vec<UiProfile>,
+    /// Font files loaded into the UI at startup, in front of the default font.
+    pub ui_fonts: Vec<PathBuf>,
+    /// Fall back to the system's Japanese font for characters the UI fonts lack.
+    pub system_cjk_font: bool,
+    /// Open in kiosk mode, cycling through the preset playlist until someone touches a control.
+    pub start_in_kiosk_mode: bool,
+    /// Fullscreen state, monitor and video mode, restored at launch.
+    pub display_mode: DisplayMode,
+    /// Warn when the relative energy drift exceeds `energy_drift_threshold`.
+    pub energy_warning_enabled: bool,
+    pub energy_drift_threshold: f64,
+    /// History every recorded diagnostic keeps.
+    pub metrics_retention: RetentionPolicy,
+    /// Metrics computed from others, in evaluation order.
+    pub derived_metrics: Vec<DerivedMetricDefinition>,
 }
 
 impl Default for AppSettings {
@@ -28,16 +67,40 @@ impl Default for AppSettings {
             link_point_size_to_scale: true,
             mailbox_present_mode: false,
             particle_display_mode: ParticleDisplayMode::default(),
+            time_display_unit: TimeDisplayUnit::default(),
+            auto_fit_on_reset: false,
+            scale_gauge_mode: ScaleGaugeMode::default(),
+            gpu_frustum_culling: true,
+            point_budget: 0,
+            motion_blur_frames: 0,
+            memory_budget_mb: DEFAULT_MEMORY_BUDGET_MB,
+            palettes: PaletteSettings::default(),
+            ui_profile: DEFAULT_UI_PROFILE.to_string(),
+            ui_profiles: Vec::new(),
+            ui_fonts: Vec::new(),
+            system_cjk_font: true,
+            start_in_kiosk_mode: false,
+            display_mode: DisplayMode::default(),
+            energy_warning_enabled: true,
+            energy_drift_threshold: DEFAULT_ENERGY_DRIFT_THRESHOLD,
+            metrics_retention: RetentionPolicy::default(),
+            derived_metrics: Vec::new(),
         }
     }
 }
 
 impl AppSettings {
-    /// Resolves the filesystem path used to load and save persisted settings.
-    fn config_path() -> io::Result<PathBuf> {
+    /// Resolves the directory holding the settings, crash reports, and checkpoints: the
+    /// executable's own.
+    pub fn app_dir() -> io::Result<PathBuf> {
         let exe_path = std::env::current_exe()?;
         let dir = exe_path.parent().unwrap_or_else(|| Path::new("."));
-        Ok(dir.join("setting.config"))
+        Ok(dir.to_path_buf())
+    }
+
+    /// Resolves the filesystem path used to load and save persisted settings.
+    fn config_path() -> io::Result<PathBuf> {
+        Ok(Self::app_dir()?.join("setting.config"))
     }
 
     /// Loads settings from disk and falls back to defaults on any read or parse failure.
@@ -0,0 +1,13 @@
+#version 450
+layout(set = 0, binding = 0) uniform sampler2D accumulation;
+layout(push_constant) uniform CompositePushConstants {
+    float gain;
+} pc;
+
+layout(location = 0) out vec4 f_color;
+
+// The accumulation covers the whole framebuffer, so it is read texel for texel.
+void main() {
+    vec4 sum = texelFetch(accumulation, ivec2(gl_FragCoord.xy), 0);
+    f_color = vec4(sum.rgb * pc.gain, 0.0);
+}
@@ -0,0 +1,11 @@
+#version 450
+layout(push_constant) uniform FadePushConstants {
+    float retention;
+} pc;
+
+layout(location = 0) out vec4 f_color;
+
+// Blended as dst * src.a, so every texel of the accumulation keeps `retention` of itself.
+void main() {
+    f_color = vec4(0.0, 0.0, 0.0, pc.retention);
+}
@@ -1,5 +1,6 @@
 #version 450
 layout(location = 0) in vec4 v_color;
+layout(location = 1) in float v_sample_gain;
 
 layout(location = 0) out vec4 f_color;
 
@@ -11,7 +12,8 @@ void main() {
     float core = exp(-dist * 8.0);
     intensity = intensity + core * 0.5;
     float energyFalloff = exp(-dist * 4.0);
-    vec3 color = v_color.rgb * intensity * energyFalloff;
+    // Additive, so scaling by the inverse sampled fraction keeps the expected image.
+    vec3 color = v_color.rgb * intensity * energyFalloff * v_sample_gain;
     float alpha = energyFalloff * v_color.a;
     f_color = vec4(color, alpha);
 }
\ No newline at end of file
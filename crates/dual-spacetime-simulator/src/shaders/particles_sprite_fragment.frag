@@ -0,0 +1,13 @@
+#version 450
+layout(location = 0) in vec4 v_color;
+
+layout(location = 0) out vec4 f_color;
+
+// Soft-edged disc for alpha blending; drawn back to front after the depth sort.
+void main() {
+    vec2 coord = gl_PointCoord - vec2(0.5);
+    float dist = length(coord);
+    if (dist > 0.5) discard;
+    float coverage = 1.0 - smoothstep(0.3, 0.5, dist);
+    f_color = vec4(v_color.rgb, v_color.a * coverage);
+}
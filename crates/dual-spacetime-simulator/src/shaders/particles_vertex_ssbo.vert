@@ -7,28 +7,145 @@ struct Particle {
     vec4 color;
 };
 
-layout(std430, binding = 0) readonly buffer Particles {
+layout(std430, set = 0, binding = 0) readonly buffer Particles {
     Particle particles[];
 };
 
+// Indices that survived GPU frustum culling, consumed by an indirect draw, or every
+// index in back-to-front order from the depth sort.
+layout(std430, set = 1, binding = 1) readonly buffer Visible {
+    uint visible_indices[];
+};
+
 layout(location = 0) out vec4 v_color;
+// Inverse of the sampled fraction; the glow fragment shader scales brightness by it.
+layout(location = 1) out float v_sample_gain;
 
 layout(push_constant) uniform PushConstants {
     mat4 view_proj;
     float size_scale;
+    uint culled;
+    // 0 velocity holds coordinate velocity, 1 momentum, 2 rapidity.
+    uint kinematics;
+    // Bit 0: retarded positions and Doppler beaming, bit 1: reference rest frame,
+    // bit 2: plot at velocity, bit 3: plot at rapidity.
+    uint view_flags;
+    // xyz: camera position in particle space, w: light speed in sim units.
+    vec4 observer;
+    // xyz: reference particle position, w: fraction of particles drawn (point budget).
+    vec4 frame_origin;
+    // xyz: reference particle velocity, w: this frame's 24-bit sampling phase.
+    vec4 frame_velocity;
 } push;
 
+const uint VIEW_FLAG_ABERRATION = 1u;
+const uint VIEW_FLAG_REST_FRAME = 2u;
+const uint VIEW_FLAG_VELOCITY_SPACE = 4u;
+const uint VIEW_FLAG_RAPIDITY_SPACE = 8u;
+// Particle-space distance of light speed (velocity) or unit rapidity; see relativistic_view.rs.
+const float VELOCITY_SPACE_RADIUS = 1.0;
+
+const float BEAMING_EXPONENT = 4.0;
+const float MAX_BEAMING_INTENSITY = 16.0;
+
+// 24-bit hash of a particle index; mirrored by point_budget::sample_hash.
+uint sample_hash(uint index) {
+    uint state = index * 747796405u + 2891336453u;
+    uint word = ((state >> ((state >> 28u) + 4u)) ^ state) * 277803737u;
+    return ((word >> 22u) ^ word) >> 8u;
+}
+
+// Coordinate velocity from the stored kinematics; see relativistic_view.rs.
+vec3 coordinate_velocity(Particle p, float c) {
+    vec3 k = p.velocity.xyz;
+    if (push.kinematics == 0u) {
+        return k;
+    }
+    if (push.kinematics == 1u) {
+        vec3 u = k / p.attrs.x;
+        return u / sqrt(1.0 + dot(u, u) / (c * c));
+    }
+    // Same boost the Lorentz engine applies to its rapidity each step.
+    float a = dot(k, k);
+    if (a == 0.0) {
+        return vec3(0.0);
+    }
+    float ch = cosh(0.5 * a);
+    vec3 q = k / a * sinh(0.5 * a);
+    return c * 2.0 * ch * q / (ch * ch + dot(q, q));
+}
+
+// Passive boost of the event (ct, x) into the frame moving at v.
+vec4 boost(vec4 event, vec3 v, float c) {
+    float vv = dot(v, v);
+    if (vv == 0.0) {
+        return event;
+    }
+    vec3 beta = v / c;
+    float gamma = 1.0 / sqrt(1.0 - dot(beta, beta));
+    float bx = dot(beta, event.yzw);
+    vec3 x = event.yzw + ((gamma - 1.0) * dot(v, event.yzw) / vv - gamma * event.x / c) * v;
+    return vec4(gamma * (event.x - bx), x);
+}
+
+// Position and velocity in the reference particle's rest frame, at that frame's present;
+// see rest_frame.rs.
+void to_rest_frame(inout vec3 position, inout vec3 v, float c) {
+    vec4 event = boost(vec4(0.0, position - push.frame_origin.xyz), push.frame_velocity.xyz, c);
+    float gamma = 1.0 / sqrt(max(1.0 - dot(v, v) / (c * c), 1e-30));
+    vec4 u = boost(gamma * vec4(c, v), push.frame_velocity.xyz, c);
+    v = u.yzw * (c / u.x);
+    position = push.frame_origin.xyz + event.yzw - v * (event.x / c);
+}
+
 void main() {
-    Particle p = particles[gl_VertexIndex];
+    uint index = push.culled != 0u ? visible_indices[gl_VertexIndex] : gl_VertexIndex;
+    float fraction = push.frame_origin.w;
+    v_sample_gain = 1.0 / fraction;
+    // Particles left out of this frame's sample are parked before their data is read.
     // Alpha 0 marks a dead (culled) particle still occupying its buffer slot;
     // park it outside the clip volume so it never rasterizes.
-    if (p.color.a == 0.0) {
+    bool sampled = fraction >= 1.0
+        || float((sample_hash(index) + uint(push.frame_velocity.w)) & 0xFFFFFFu)
+            < fraction * 16777216.0;
+    if (!sampled || particles[index].color.a == 0.0) {
         gl_Position = vec4(0.0, 0.0, 2.0, 1.0);
         gl_PointSize = 0.0;
         v_color = vec4(0.0);
         return;
     }
-    gl_Position = push.view_proj * vec4(p.position.xyz, 1.0);
+    Particle p = particles[index];
+    vec3 position = p.position.xyz;
+    vec4 color = p.color;
+    if (push.view_flags != 0u) {
+        float c = push.observer.w;
+        vec3 v = coordinate_velocity(p, c);
+        if ((push.view_flags & VIEW_FLAG_REST_FRAME) != 0u) {
+            to_rest_frame(position, v, c);
+        }
+        if ((push.view_flags & VIEW_FLAG_VELOCITY_SPACE) != 0u) {
+            position = v / c * VELOCITY_SPACE_RADIUS;
+        } else if ((push.view_flags & VIEW_FLAG_RAPIDITY_SPACE) != 0u) {
+            float speed = length(v);
+            float beta = min(speed / c, 1.0 - 1e-7);
+            position = speed > 0.0 ? v / speed * atanh(beta) * VELOCITY_SPACE_RADIUS : vec3(0.0);
+        }
+        // Retarded position (light-travel delay and aberration) and Doppler beaming.
+        float k = (push.view_flags & VIEW_FLAG_ABERRATION) != 0u ? c * c - dot(v, v) : 0.0;
+        if (k > 0.0) {
+            vec3 d = position - push.observer.xyz;
+            float dv = dot(d, v);
+            float tau = (-dv + sqrt(dv * dv + k * dot(d, d))) / k;
+            position -= v * tau;
+            vec3 beta = v / c;
+            vec3 n = push.observer.xyz - position;
+            n = dot(n, n) > 0.0 ? normalize(n) : vec3(0.0);
+            float doppler = sqrt(1.0 - dot(beta, beta)) / (1.0 - dot(beta, n));
+            float intensity = min(pow(doppler, BEAMING_EXPONENT), MAX_BEAMING_INTENSITY);
+            color.rgb *= intensity;
+        }
+    }
+    gl_Position = push.view_proj * vec4(position, 1.0);
     gl_PointSize = push.size_scale / gl_Position.w;
-    v_color = p.color;
+    v_color = color;
 }
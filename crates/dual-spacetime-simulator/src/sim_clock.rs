@@ -0,0 +1,104 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Frame counters published together by the simulation thread, so readouts never mix
+/// the frame of one step with the simulation time of another.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ClockSnapshot {
+    pub frame: i64,
+    pub simulation_time: f64,
+    pub fps: i64,
+    /// Wall-clock time spent running since the last reset; paused time is excluded.
+    pub wall_runtime: Duration,
+    /// Estimated wall time left until the target simulation time, when one is set.
+    pub eta: Option<Duration>,
+    /// Simulation seconds advanced per wall-clock second over the last measurement.
+    pub time_rate: Option<f64>,
+}
+
+#[derive(Default)]
+struct ClockState {
+    snapshot: ClockSnapshot,
+    target_time: Option<f64>,
+    time_rate: Option<f64>,
+}
+
+/// Shared handle to the latest [`ClockSnapshot`]; cloning shares the same clock.
+#[derive(Clone, Default)]
+pub struct SimulationClock {
+    state: Arc<Mutex<ClockState>>,
+}
+
+impl SimulationClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn snapshot(&self) -> ClockSnapshot {
+        self.state.lock().unwrap().snapshot
+    }
+
+    /// Replaces the snapshot in one step; the ETA is derived from the recent rate set by
+    /// [`Self::set_time_rate`], or from the average rate of simulation time per wall second
+    /// since the last reset until one is measured.
+    pub fn publish(&self, frame: i64, simulation_time: f64, fps: i64, wall_runtime: Duration) {
+        let mut state = self.state.lock().unwrap();
+        let eta = state.target_time.and_then(|target| match state.time_rate {
+            Some(rate) if rate > 0.0 => estimate_eta_at_rate(simulation_time, target, rate),
+            _ => estimate_eta(simulation_time, target, wall_runtime),
+        });
+        state.snapshot = ClockSnapshot {
+            frame,
+            simulation_time,
+            fps,
+            wall_runtime,
+            eta,
+            time_rate: state.time_rate,
+        };
+    }
+
+    /// Sets the simulation time an ETA is estimated against; `None` disables the ETA.
+    pub fn set_target_time(&self, target_time: Option<f64>) {
+        let mut state = self.state.lock().unwrap();
+        state.target_time = target_time;
+        if target_time.is_none() {
+            state.snapshot.eta = None;
+        }
+    }
+
+    pub fn target_time(&self) -> Option<f64> {
+        self.state.lock().unwrap().target_time
+    }
+
+    /// Sets the simulation time advanced per wall second over the last measurement, shown
+    /// from the next publish; a rate of `None` or zero estimates the ETA from the average
+    /// since the last reset.
+    pub fn set_time_rate(&self, time_rate: Option<f64>) {
+        self.state.lock().unwrap().time_rate = time_rate;
+    }
+}
+
+/// Wall time left to go from `done` to `total` at the average rate so far. `None` until
+/// there is progress to extrapolate from; zero once `total` is reached.
+pub fn estimate_eta(done: f64, total: f64, elapsed: Duration) -> Option<Duration> {
+    if done >= total {
+        return Some(Duration::ZERO);
+    }
+    if done <= 0.0 || elapsed.is_zero() {
+        return None;
+    }
+    let seconds = elapsed.as_secs_f64() * (total - done) / done;
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+/// Wall time left to go from `done` to `total` at `rate` per wall second. `None` without a
+/// positive rate; zero once `total` is reached.
+pub fn estimate_eta_at_rate(done: f64, total: f64, rate: f64) -> Option<Duration> {
+    if done >= total {
+        return Some(Duration::ZERO);
+    }
+    if rate <= 0.0 {
+        return None;
+    }
+    Duration::try_from_secs_f64((total - done) / rate).ok()
+}
@@ -0,0 +1,127 @@
+use crate::events::total_energy;
+use crate::particle_snapshot::ParticleSnapshot;
+use crate::simulation::SimulationManager;
+use std::path::PathBuf;
+use std::time::Instant;
+
+/// Flag that runs [`simulate`] instead of opening the window. It is the only mode of a
+/// build without the `gui` feature.
+pub const SIMULATE_FLAG: &str = "--simulate";
+/// Simulated seconds per step when `--time-per-frame` is not given; the Simulation
+/// panel's default.
+pub const DEFAULT_FRAME_TIME: f64 = 10.0;
+
+pub const SIMULATE_USAGE: &str = "\
+Usage: dual-spacetime-simulator --simulate <scenario.zip> [options]
+
+Advances a saved scenario on the CPU and reports its energy drift.
+
+Options:
+  --frames <n>               Steps to run (default: 1000)
+  --time-per-frame <s>       Simulated seconds per step (default: 10)
+  --out <file.zip>           Save the final state as a scenario";
+
+/// What a `--simulate` run advances and where it saves the result.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimulateOptions {
+    pub scenario: PathBuf,
+    pub frames: u64,
+    pub time_per_frame: f64,
+    pub output: Option<PathBuf>,
+}
+
+impl SimulateOptions {
+    pub fn new(scenario: PathBuf) -> Self {
+        Self {
+            scenario,
+            frames: 1000,
+            time_per_frame: DEFAULT_FRAME_TIME,
+            output: None,
+        }
+    }
+}
+
+/// Returns the argument after `flag` in `args`, the scenario file of a CLI mode, and the
+/// remaining arguments. `None` when `flag` is absent.
+pub(crate) fn split_mode_args<'a>(
+    args: &'a [String],
+    flag: &str,
+) -> Option<Result<(PathBuf, Vec<&'a String>), String>> {
+    let index = args.iter().position(|arg| arg == flag)?;
+    let Some(scenario) = args.get(index + 1).filter(|arg| !arg.starts_with("--")) else {
+        return Some(Err(format!("{} needs a scenario file", flag)));
+    };
+    let rest = args[..index].iter().chain(&args[index + 2..]).collect();
+    Some(Ok((PathBuf::from(scenario), rest)))
+}
+
+pub(crate) fn parse_number<T: std::str::FromStr>(flag: &str, text: &str) -> Result<T, String> {
+    text.parse()
+        .map_err(|_| format!("Invalid value '{}' for {}", text, flag))
+}
+
+/// Parses the command line (without the program name). Returns `None` when
+/// [`SIMULATE_FLAG`] is absent.
+pub fn parse_simulate_args(args: &[String]) -> Result<Option<SimulateOptions>, String> {
+    let Some(split) = split_mode_args(args, SIMULATE_FLAG) else {
+        return Ok(None);
+    };
+    let (scenario, rest) = split?;
+    let mut options = SimulateOptions::new(scenario);
+    let mut rest = rest.into_iter();
+    while let Some(arg) = rest.next() {
+        let mut value = || rest.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--frames" => options.frames = parse_number(arg, value()?)?,
+            "--time-per-frame" => options.time_per_frame = parse_number(arg, value()?)?,
+            "--out" => options.output = Some(PathBuf::from(value()?)),
+            other => return Err(format!("Unknown option '{}'", other)),
+        }
+    }
+    if !options.time_per_frame.is_finite() {
+        return Err("--time-per-frame must be finite".to_string());
+    }
+    Ok(Some(options))
+}
+
+/// Loads the scenario and advances it `options.frames` steps with the CPU engine, then
+/// prints the energy drift and saves the final state when `options.output` is set. Only
+/// the engine's forces act; drag, mass rules and the other per-frame effects the window
+/// applies are not run.
+pub fn simulate(options: &SimulateOptions) -> Result<(), String> {
+    let snapshot = ParticleSnapshot::load(&options.scenario)
+        .map_err(|e| format!("Failed to load {}: {}", options.scenario.display(), e))?;
+    let simulation_manager = SimulationManager::new();
+    simulation_manager.load_from_snapshot(snapshot.clone());
+    let initial_energy = total_energy(&simulation_manager.particles());
+    let started = Instant::now();
+    for _ in 0..options.frames {
+        simulation_manager.advance(options.time_per_frame);
+    }
+    let particles = simulation_manager.particles();
+    let final_energy = total_energy(&particles);
+    let relative_energy_drift = if initial_energy != 0.0 {
+        ((final_energy - initial_energy) / initial_energy).abs()
+    } else {
+        f64::NAN
+    };
+    println!(
+        "Simulated {} frames ({} s) of {} particles in {:.2} s; relative energy drift {:.3e}",
+        options.frames,
+        options.frames as f64 * options.time_per_frame,
+        particles.len(),
+        started.elapsed().as_secs_f64(),
+        relative_energy_drift
+    );
+    if let Some(path) = &options.output {
+        let result = ParticleSnapshot {
+            particles,
+            ..snapshot
+        };
+        result
+            .save(path)
+            .map_err(|e| format!("Failed to save {}: {}", path.display(), e))?;
+        println!("Saved {}", path.display());
+    }
+    Ok(())
+}
@@ -1,22 +1,47 @@
-use glam::{DQuat, DVec3};
+use glam::{DQuat, DVec3, Vec3};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, RwLock};
 
+use crate::block_steps::advance_block_steps;
+use crate::close_encounter::{Encounter, find_close_encounter};
+use crate::container::{ContainerWalls, reflect_off_walls};
+use crate::cosmology::Cosmology;
+use crate::drag::{DragForce, apply_drag};
+use crate::fast_multipole::{
+    DEFAULT_EXPANSION_ORDER, MAX_EXPANSION_ORDER, MIN_EXPANSION_ORDER, fmm_relative_force_error,
+    fmm_velocity_update,
+};
+use crate::force_tiles::{tiled_velocity_deltas, tiled_velocity_deltas_f32};
+#[cfg(feature = "gui")]
+use crate::frame_pipeline::StagingRing;
+#[cfg(feature = "gui")]
+use crate::gpu_simulation::GpuParticle;
+use crate::kepler_binary::{
+    KeplerPair, drift_kepler_pairs, find_kepler_pairs, remove_mutual_kicks,
+};
+use crate::langevin::{LangevinNoise, apply_langevin_noise};
+use crate::mass_evolution::{ParticleMassRule, evolve_masses};
 use crate::object_input::ObjectInput;
 use crate::particle_snapshot::ParticleSnapshot;
-use crate::ui_state::SimulationType;
-use dst_math::gravity::{
-    dst_gravity_step_at, k_scale_from_light_speed, newtonian_gravity_pair,
+use crate::rotating_frame::{enter_rotating_frame, rotating_frame_velocity_update};
+use crate::tree_gravity::{
+    DEFAULT_OPENING_ANGLE, DEFAULT_TREE_REBUILD_INTERVAL, ForceSolver, MAX_OPENING_ANGLE,
+    MAX_TREE_REBUILD_INTERVAL, MIN_OPENING_ANGLE, MIN_TREE_REBUILD_INTERVAL, TreeCache, TreeTiming,
+    relative_force_error, sample_indices,
 };
+use crate::ui_state::{Precision, SimulationType};
+use dst_math::gravity::{dst_gravity_step_at_with, gravitomagnetic_pair, k_scale_from_light_speed};
 use dst_math::s3_galaxy::{
-    galaxy_gravity_step_at_orientations, galaxy_radius_sim, integrate_orientation,
+    galaxy_gravity_step_at_orientations_with, galaxy_radius_sim, integrate_orientation,
     orientation_from_disk_position, orientation_to_display_position, s3_angle_from_origin,
 };
 use dst_math::spacetime::{
     Spacetime, momentum_from_velocity, position_delta_from_momentum, rapidity_from_momentum,
     velocity_from_momentum,
 };
+pub use dst_math::summation::Summation;
+use dst_math::summation::Vec3Sum;
 
 // Speed of light and Julian light year: single source of truth in dst-math,
 // shared with the S³ galaxy radius so both sides can never drift apart.
@@ -32,6 +57,82 @@ pub const SUBLUMINAL_SPEED_FRACTION: f64 = 0.9999999;
 pub const G: f64 = 6.6743e-11; // Gravitational constant in m^3 kg^-1 s^-2
 pub const EPSILON: f64 = 1e-10;
 pub const DEFAULT_WORLD_SCALE: f64 = 1e10;
+/// Smallest light-speed factor offered for the pedagogical slider (c ≈ 3 m/s).
+pub const MIN_LIGHT_SPEED_FACTOR: f64 = 1e-8;
+
+/// Settings the CPU engines step with. The window's simulation reads the force settings
+/// from the UI state every frame, but picks up the precision, speed of light, cosmology,
+/// and frame rotation only when it resets. Batch and verification runs pass their own.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct EngineConfig {
+    /// How every engine accumulates per-particle pairwise forces. Each particle's sum
+    /// always runs over neighbors in index order on one thread, so results do not depend
+    /// on the rayon thread count in either mode.
+    pub summation: Summation,
+    /// Precision of the Newtonian engine's force loop.
+    pub precision: Precision,
+    /// How the Newtonian engine in double precision finds forces.
+    pub force_solver: ForceSolver,
+    /// The tree solver's opening angle θ.
+    pub opening_angle: f64,
+    /// Steps the tree solver reuses its tree before rebuilding it; 1 rebuilds it every
+    /// step.
+    pub tree_rebuild_interval: u32,
+    /// The FMM solver's expansion order.
+    pub expansion_order: u32,
+    /// Factor the speed of light of the Special and DST Gravity engines is scaled by.
+    /// Values below 1 make relativistic effects visible at everyday speeds.
+    pub light_speed_factor: f64,
+    /// Expansion history new comoving simulations start from.
+    pub cosmology: Cosmology,
+    /// Angular velocity (rad/s about +y) of the frame the classical engines integrate in;
+    /// zero is the inertial frame. Positive is counterclockwise seen from +y.
+    pub frame_angular_velocity: f64,
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        Self {
+            summation: Summation::Naive,
+            precision: Precision::Double,
+            force_solver: ForceSolver::Direct,
+            opening_angle: DEFAULT_OPENING_ANGLE,
+            tree_rebuild_interval: DEFAULT_TREE_REBUILD_INTERVAL,
+            expansion_order: DEFAULT_EXPANSION_ORDER,
+            light_speed_factor: 1.0,
+            cosmology: Cosmology::DEFAULT,
+            frame_angular_velocity: 0.0,
+        }
+    }
+}
+
+impl EngineConfig {
+    /// Sets the opening angle θ, clamped to the supported range.
+    pub fn set_opening_angle(&mut self, theta: f64) {
+        self.opening_angle = theta.clamp(MIN_OPENING_ANGLE, MAX_OPENING_ANGLE);
+    }
+
+    /// Sets the tree rebuild interval, clamped to the supported range.
+    pub fn set_tree_rebuild_interval(&mut self, steps: u32) {
+        self.tree_rebuild_interval =
+            steps.clamp(MIN_TREE_REBUILD_INTERVAL, MAX_TREE_REBUILD_INTERVAL);
+    }
+
+    /// Sets the expansion order, clamped to the supported range.
+    pub fn set_expansion_order(&mut self, order: u32) {
+        self.expansion_order = order.clamp(MIN_EXPANSION_ORDER, MAX_EXPANSION_ORDER);
+    }
+
+    /// Sets the light-speed factor, clamped to the supported range.
+    pub fn set_light_speed_factor(&mut self, factor: f64) {
+        self.light_speed_factor = factor.clamp(MIN_LIGHT_SPEED_FACTOR, 1.0);
+    }
+
+    /// Returns the speed of light the engines integrate with, in meters per second.
+    pub fn light_speed(&self) -> f64 {
+        LIGHT_SPEED * self.light_speed_factor
+    }
+}
 
 /// Returns the maximum subluminal speed in meters per second.
 pub fn max_subluminal_speed_m_s() -> f64 {
@@ -62,11 +163,12 @@ pub fn clamp_velocity_m_s(velocity: DVec3) -> DVec3 {
     }
 }
 
-/// Clamps particle velocities in simulation units to subluminal when at or above c.
-pub fn clamp_particle_velocities_sim(particles: &mut [Particle], scale: f64) {
-    let light_speed_sim = LIGHT_SPEED / scale;
+/// Clamps particle velocities in simulation units to subluminal when at or above
+/// `light_speed` (m/s).
+pub fn clamp_particle_velocities_sim(particles: &mut [Particle], scale: f64, light_speed: f64) {
+    let light_speed_sim = light_speed / scale;
     let light_speed_sim_squared = light_speed_sim * light_speed_sim;
-    let max_speed = max_subluminal_speed_sim(scale);
+    let max_speed = light_speed_sim * SUBLUMINAL_SPEED_FRACTION;
     for particle in particles.iter_mut() {
         let speed_squared = particle.velocity.length_squared();
         if speed_squared == 0.0 || speed_squared < light_speed_sim_squared {
@@ -78,13 +180,15 @@ pub fn clamp_particle_velocities_sim(particles: &mut [Particle], scale: f64) {
 
 pub trait SimulationEngine {
     /// Updates particle velocities for one simulation step duration.
-    fn update_velocities(&mut self, delta_seconds: f64);
+    fn update_velocities(&mut self, delta_seconds: f64, config: &EngineConfig);
     /// Advances particle positions or states for one simulation step duration.
-    fn advance_time(&mut self, delta_seconds: f64);
+    fn advance_time(&mut self, delta_seconds: f64, config: &EngineConfig);
 }
 
 pub struct SimulationNormal {
     pub particles: Vec<Particle>,
+    /// Barnes–Hut tree reused across steps by the tree solver.
+    pub tree: TreeCache,
 }
 
 pub struct SimulationSpeedOfLightLimit {
@@ -102,6 +206,18 @@ pub struct SimulationDstGravity {
     pub scale: f64,
 }
 
+pub struct SimulationGravitomagnetic {
+    pub particles: Vec<Particle>,
+    pub scale: f64,
+}
+
+pub struct SimulationComoving {
+    pub particles: Vec<Particle>,
+    pub cosmology: Cosmology,
+    /// Cosmic time in seconds.
+    pub time: f64,
+}
+
 pub struct SimulationDstGalaxy {
     pub particles: Vec<Particle>,
     pub scale: f64,
@@ -114,6 +230,8 @@ pub enum SimulationState {
     LorentzTransformation(SimulationLorentzTransformation),
     DstGravity(SimulationDstGravity),
     DstGalaxy(SimulationDstGalaxy),
+    Gravitomagnetic(SimulationGravitomagnetic),
+    Comoving(SimulationComoving),
 }
 
 fn default_orientation() -> DQuat {
@@ -134,6 +252,9 @@ pub struct Particle {
     pub lambda_eff: f64,
     #[serde(default = "default_orientation")]
     pub orientation: DQuat,
+    /// Stable identifier assigned when the particle enters a simulation; 0 means unassigned.
+    #[serde(default)]
+    pub id: u64,
 }
 
 impl Particle {
@@ -153,32 +274,49 @@ impl Particle {
             proper_time: 0.0,
             lambda_eff: 0.0,
             orientation: DQuat::IDENTITY,
+            id: 0,
         }
     }
 }
 
-fn newtonian_velocity_update(particles: &mut [Particle], delta_seconds: f64) {
+/// Gives every particle with an unassigned (0) ID a fresh one above the current maximum,
+/// so IDs stay unique and existing ones (e.g. from a snapshot) are kept.
+pub fn assign_particle_ids(particles: &mut [Particle]) {
+    let first_id = particles.iter().map(|p| p.id).max().unwrap_or(0) + 1;
+    let unassigned = particles.iter_mut().filter(|p| p.id == 0);
+    for (particle, id) in unassigned.zip(first_id..) {
+        particle.id = id;
+    }
+}
+
+/// Single-precision variant of [`newtonian_velocity_update`]: pair forces are computed and
+/// summed in `f32` (always naively), then added to the `f64` velocities.
+fn newtonian_velocity_update_f32(particles: &mut [Particle], delta_seconds: f64) {
+    let positions: Vec<Vec3> = particles.iter().map(|p| p.position.as_vec3()).collect();
+    let masses: Vec<f32> = particles.iter().map(|p| p.mass as f32).collect();
+    let deltas = tiled_velocity_deltas_f32(&positions, &masses, (G * delta_seconds) as f32);
+    particles
+        .par_iter_mut()
+        .zip(deltas)
+        .for_each(|(particle, delta)| particle.velocity += delta.as_dvec3());
+}
+
+fn newtonian_velocity_update(particles: &mut [Particle], delta_seconds: f64, summation: Summation) {
     let positions: Vec<DVec3> = particles.iter().map(|p| p.position).collect();
     let masses: Vec<f64> = particles.iter().map(|p| p.mass).collect();
-    let time_g = G * delta_seconds;
+    let deltas = tiled_velocity_deltas(&positions, &masses, G * delta_seconds, summation);
     particles
         .par_iter_mut()
-        .enumerate()
-        .for_each(|(i, particle)| {
-            let pos_i = particle.position;
-            let mut acceleration = DVec3::ZERO;
-            for (j, &pos_j) in positions.iter().enumerate() {
-                if j == i {
-                    continue;
-                }
-                acceleration +=
-                    newtonian_gravity_pair(pos_i, pos_j, masses[j], G, time_g, EPSILON).1;
-            }
-            particle.velocity += acceleration;
-        });
+        .zip(deltas)
+        .for_each(|(particle, delta)| particle.velocity += delta);
 }
 
-fn dst_gravity_velocity_update(particles: &mut [Particle], delta_seconds: f64, k_scale: f64) {
+fn dst_gravity_velocity_update(
+    particles: &mut [Particle],
+    delta_seconds: f64,
+    k_scale: f64,
+    summation: Summation,
+) {
     let positions: Vec<DVec3> = particles.iter().map(|p| p.position).collect();
     let masses: Vec<f64> = particles.iter().map(|p| p.mass).collect();
     let time_g = G * delta_seconds;
@@ -187,7 +325,7 @@ fn dst_gravity_velocity_update(particles: &mut [Particle], delta_seconds: f64, k
         .par_iter_mut()
         .enumerate()
         .for_each(|(i, particle)| {
-            let (velocity_delta, lambda_eff, proper_time_delta) = dst_gravity_step_at(
+            let (velocity_delta, lambda_eff, proper_time_delta) = dst_gravity_step_at_with(
                 i,
                 &positions,
                 &masses,
@@ -196,6 +334,7 @@ fn dst_gravity_velocity_update(particles: &mut [Particle], delta_seconds: f64, k
                 k_scale,
                 EPSILON,
                 delta_seconds,
+                summation,
             );
             particle.velocity += velocity_delta;
             particle.lambda_eff = lambda_eff;
@@ -205,12 +344,34 @@ fn dst_gravity_velocity_update(particles: &mut [Particle], delta_seconds: f64, k
 
 impl SimulationEngine for SimulationNormal {
     /// Applies Newtonian gravity to update velocities for all particles.
-    fn update_velocities(&mut self, delta_seconds: f64) {
-        newtonian_velocity_update(&mut self.particles, delta_seconds);
+    fn update_velocities(&mut self, delta_seconds: f64, config: &EngineConfig) {
+        match (config.precision, config.force_solver) {
+            (Precision::Double, ForceSolver::Direct) => {
+                newtonian_velocity_update(&mut self.particles, delta_seconds, config.summation)
+            }
+            (Precision::Double, ForceSolver::Tree) => self.tree.velocity_update(
+                &mut self.particles,
+                delta_seconds,
+                config.opening_angle,
+                config.tree_rebuild_interval,
+                config.summation,
+            ),
+            (Precision::Double, ForceSolver::Fmm) => fmm_velocity_update(
+                &mut self.particles,
+                delta_seconds,
+                config.expansion_order,
+                config.summation,
+            ),
+            (Precision::Single, _) => {
+                newtonian_velocity_update_f32(&mut self.particles, delta_seconds)
+            }
+        }
+        let angular_velocity = config.frame_angular_velocity;
+        rotating_frame_velocity_update(&mut self.particles, angular_velocity, delta_seconds);
     }
 
     /// Advances positions using current velocities under classical kinematics.
-    fn advance_time(&mut self, delta_seconds: f64) {
+    fn advance_time(&mut self, delta_seconds: f64, _config: &EngineConfig) {
         self.particles.par_iter_mut().for_each(|particle| {
             particle.position += particle.velocity * delta_seconds;
         });
@@ -219,16 +380,18 @@ impl SimulationEngine for SimulationNormal {
 
 impl SimulationEngine for SimulationSpeedOfLightLimit {
     /// Applies Lorentz-type gravity to update momentum before position integration.
-    fn update_velocities(&mut self, delta_seconds: f64) {
+    fn update_velocities(&mut self, delta_seconds: f64, config: &EngineConfig) {
         let positions: Vec<DVec3> = self.particles.iter().map(|p| p.position).collect();
         let masses: Vec<f64> = self.particles.iter().map(|p| p.mass).collect();
         let time_g = G * delta_seconds;
+        let ls = config.light_speed() / self.scale;
+        let summation = config.summation;
         self.particles
             .par_iter_mut()
             .enumerate()
             .for_each(|(i, particle)| {
                 let mass_i = particle.mass;
-                let mut impulse = DVec3::ZERO;
+                let mut impulse = Vec3Sum::new(summation);
                 for (j, (&pos_j, &mass_j)) in positions.iter().zip(masses.iter()).enumerate() {
                     if i == j {
                         continue;
@@ -239,18 +402,17 @@ impl SimulationEngine for SimulationSpeedOfLightLimit {
                         continue;
                     }
                     let force = time_g * mass_i * mass_j / r_squared;
-                    impulse += force * diff.normalize();
+                    impulse.add(force * diff.normalize());
                 }
-                particle.momentum += impulse;
-                let ls = LIGHT_SPEED / self.scale;
+                particle.momentum += impulse.value();
                 particle.velocity =
                     velocity_from_momentum(particle.momentum, particle.mass, ls);
             });
     }
 
     /// Advances positions using momentum-based relativistic kinematics.
-    fn advance_time(&mut self, delta_seconds: f64) {
-        let ls = LIGHT_SPEED / self.scale;
+    fn advance_time(&mut self, delta_seconds: f64, config: &EngineConfig) {
+        let ls = config.light_speed() / self.scale;
         self.particles.par_iter_mut().for_each(|particle| {
             particle.position += position_delta_from_momentum(
                 particle.momentum,
@@ -264,19 +426,28 @@ impl SimulationEngine for SimulationSpeedOfLightLimit {
     }
 }
 
+/// Coordinate velocity of a Lorentz-engine particle, from the same boost `advance_time`
+/// applies to its rapidity.
+pub fn velocity_from_rapidity(rapidity: DVec3, light_speed: f64) -> DVec3 {
+    let mut st = Spacetime::from_t(1.0);
+    st.apply_lorentz_transform_by_rapidity(rapidity);
+    DVec3::new(st.x, st.y, st.z) * (light_speed / st.t)
+}
+
 impl SimulationEngine for SimulationLorentzTransformation {
     /// Updates rapidity-like velocities from momentum-based relativistic interactions.
-    fn update_velocities(&mut self, delta_seconds: f64) {
+    fn update_velocities(&mut self, delta_seconds: f64, config: &EngineConfig) {
         let positions: Vec<DVec3> = self.particles.iter().map(|p| p.position).collect();
         let masses: Vec<f64> = self.particles.iter().map(|p| p.mass).collect();
         let time_g = G * delta_seconds;
-        let ls = LIGHT_SPEED / self.scale;
+        let ls = config.light_speed() / self.scale;
+        let summation = config.summation;
         self.particles
             .par_iter_mut()
             .enumerate()
             .for_each(|(i, particle)| {
                 let mass_i = particle.mass;
-                let mut acceleration = DVec3::ZERO;
+                let mut acceleration = Vec3Sum::new(summation);
                 for (j, (&pos_j, &mass_j)) in positions.iter().zip(masses.iter()).enumerate() {
                     if i == j {
                         continue;
@@ -288,15 +459,15 @@ impl SimulationEngine for SimulationLorentzTransformation {
                     }
                     let force = time_g * mass_i * mass_j / r_squared;
                     let rapidity = rapidity_from_momentum(force * diff.normalize(), mass_i, ls);
-                    acceleration += rapidity;
+                    acceleration.add(rapidity);
                 }
-                particle.velocity += acceleration;
+                particle.velocity += acceleration.value();
             });
     }
 
     /// Advances positions by applying Lorentz transformation to proper-time increments.
-    fn advance_time(&mut self, delta_seconds: f64) {
-        let ct = delta_seconds * LIGHT_SPEED / self.scale;
+    fn advance_time(&mut self, delta_seconds: f64, config: &EngineConfig) {
+        let ct = delta_seconds * config.light_speed() / self.scale;
         self.particles.par_iter_mut().for_each(|particle| {
             let mut st = Spacetime::from_t(ct);
             st.apply_lorentz_transform_by_rapidity(particle.velocity);
@@ -308,16 +479,103 @@ impl SimulationEngine for SimulationLorentzTransformation {
 
 impl SimulationEngine for SimulationDstGravity {
     /// Advances positions using current velocities.
-    fn advance_time(&mut self, delta_seconds: f64) {
+    fn advance_time(&mut self, delta_seconds: f64, _config: &EngineConfig) {
         self.particles.par_iter_mut().for_each(|particle| {
             particle.position += particle.velocity * delta_seconds;
         });
     }
 
     /// Applies sign-flipped Newtonian gravity when dτ/dt < 0, then updates time delay state.
-    fn update_velocities(&mut self, delta_seconds: f64) {
-        let k_scale = k_scale_from_light_speed(LIGHT_SPEED / self.scale);
-        dst_gravity_velocity_update(&mut self.particles, delta_seconds, k_scale);
+    fn update_velocities(&mut self, delta_seconds: f64, config: &EngineConfig) {
+        let k_scale = k_scale_from_light_speed(config.light_speed() / self.scale);
+        dst_gravity_velocity_update(&mut self.particles, delta_seconds, k_scale, config.summation);
+        let angular_velocity = config.frame_angular_velocity;
+        rotating_frame_velocity_update(&mut self.particles, angular_velocity, delta_seconds);
+    }
+}
+
+/// Newtonian gravity plus the gravitomagnetic pull between moving masses, from velocities
+/// snapshotted before the step so every particle sees the same neighbor state.
+fn gravitomagnetic_velocity_update(
+    particles: &mut [Particle],
+    delta_seconds: f64,
+    light_speed: f64,
+    summation: Summation,
+) {
+    let positions: Vec<DVec3> = particles.iter().map(|p| p.position).collect();
+    let velocities: Vec<DVec3> = particles.iter().map(|p| p.velocity).collect();
+    let masses: Vec<f64> = particles.iter().map(|p| p.mass).collect();
+    let time_g = G * delta_seconds;
+    let inverse_light_speed_sq = (light_speed * light_speed).recip();
+    particles
+        .par_iter_mut()
+        .enumerate()
+        .for_each(|(i, particle)| {
+            let (pos_i, vel_i) = (positions[i], velocities[i]);
+            let mut acceleration = Vec3Sum::new(summation);
+            for (j, (&pos_j, &vel_j)) in positions.iter().zip(velocities.iter()).enumerate() {
+                if j == i {
+                    continue;
+                }
+                acceleration.add(gravitomagnetic_pair(
+                    pos_i,
+                    vel_i,
+                    pos_j,
+                    vel_j,
+                    masses[j],
+                    time_g,
+                    inverse_light_speed_sq,
+                    EPSILON,
+                ));
+            }
+            particle.velocity += acceleration.value();
+        });
+}
+
+impl SimulationEngine for SimulationGravitomagnetic {
+    /// Advances positions using current velocities.
+    fn advance_time(&mut self, delta_seconds: f64, _config: &EngineConfig) {
+        self.particles.par_iter_mut().for_each(|particle| {
+            particle.position += particle.velocity * delta_seconds;
+        });
+    }
+
+    /// Applies weak-field gravitoelectromagnetic gravity to velocities.
+    fn update_velocities(&mut self, delta_seconds: f64, config: &EngineConfig) {
+        let light_speed = config.light_speed() / self.scale;
+        gravitomagnetic_velocity_update(
+            &mut self.particles,
+            delta_seconds,
+            light_speed,
+            config.summation,
+        );
+        let angular_velocity = config.frame_angular_velocity;
+        rotating_frame_velocity_update(&mut self.particles, angular_velocity, delta_seconds);
+    }
+}
+
+impl SimulationEngine for SimulationComoving {
+    /// Advances comoving positions using peculiar velocities.
+    fn advance_time(&mut self, delta_seconds: f64, _config: &EngineConfig) {
+        self.particles.par_iter_mut().for_each(|particle| {
+            particle.position += particle.velocity * delta_seconds;
+        });
+    }
+
+    /// Applies Hubble drag `−2Hu` exactly over the step, then the comoving Newtonian pull,
+    /// weakened by `1/a³` as the particles drift apart with the expansion.
+    fn update_velocities(&mut self, delta_seconds: f64, config: &EngineConfig) {
+        let scale_factor = self.cosmology.scale_factor(self.time);
+        let drag = (-2.0 * self.cosmology.hubble_rate(self.time) * delta_seconds).exp();
+        self.particles.par_iter_mut().for_each(|particle| {
+            particle.velocity *= drag;
+        });
+        newtonian_velocity_update(
+            &mut self.particles,
+            delta_seconds / scale_factor.powi(3),
+            config.summation,
+        );
+        self.time += delta_seconds;
     }
 }
 
@@ -325,6 +583,7 @@ fn dst_galaxy_velocity_update(
     particles: &mut [Particle],
     delta_seconds: f64,
     galaxy_radius: f64,
+    summation: Summation,
 ) {
     let orientations: Vec<DQuat> = particles.iter().map(|p| p.orientation).collect();
     let masses: Vec<f64> = particles.iter().map(|p| p.mass).collect();
@@ -334,13 +593,14 @@ fn dst_galaxy_velocity_update(
         .par_iter_mut()
         .enumerate()
         .for_each(|(i, particle)| {
-            let delta_v = galaxy_gravity_step_at_orientations(
+            let delta_v = galaxy_gravity_step_at_orientations_with(
                 i,
                 &orientations,
                 &masses,
                 galaxy_radius,
                 time_g,
                 EPSILON,
+                summation,
             );
             particle.velocity += delta_v;
             particle.orientation = integrate_orientation(
@@ -356,7 +616,7 @@ fn dst_galaxy_velocity_update(
 
 impl SimulationEngine for SimulationDstGalaxy {
     /// Integrates S³ orientations and updates display positions.
-    fn advance_time(&mut self, delta_seconds: f64) {
+    fn advance_time(&mut self, delta_seconds: f64, _config: &EngineConfig) {
         let galaxy_radius = self.galaxy_radius;
         self.particles.par_iter_mut().for_each(|particle| {
             particle.orientation = integrate_orientation(
@@ -371,31 +631,50 @@ impl SimulationEngine for SimulationDstGalaxy {
     }
 
     /// Applies Ln-space galaxy gravity to angular velocities.
-    fn update_velocities(&mut self, delta_seconds: f64) {
-        dst_galaxy_velocity_update(&mut self.particles, delta_seconds, self.galaxy_radius);
+    fn update_velocities(&mut self, delta_seconds: f64, config: &EngineConfig) {
+        dst_galaxy_velocity_update(
+            &mut self.particles,
+            delta_seconds,
+            self.galaxy_radius,
+            config.summation,
+        );
     }
 }
 
 impl SimulationEngine for SimulationState {
     /// Delegates velocity updates to the active simulation variant.
-    fn update_velocities(&mut self, delta_seconds: f64) {
+    fn update_velocities(&mut self, delta_seconds: f64, config: &EngineConfig) {
         match self {
-            SimulationState::Normal(s) => s.update_velocities(delta_seconds),
-            SimulationState::SpeedOfLightLimit(s) => s.update_velocities(delta_seconds),
-            SimulationState::LorentzTransformation(s) => s.update_velocities(delta_seconds),
-            SimulationState::DstGravity(s) => s.update_velocities(delta_seconds),
-            SimulationState::DstGalaxy(s) => s.update_velocities(delta_seconds),
+            SimulationState::Normal(s) => s.update_velocities(delta_seconds, config),
+            SimulationState::SpeedOfLightLimit(s) => s.update_velocities(delta_seconds, config),
+            SimulationState::LorentzTransformation(s) => s.update_velocities(delta_seconds, config),
+            SimulationState::DstGravity(s) => s.update_velocities(delta_seconds, config),
+            SimulationState::DstGalaxy(s) => s.update_velocities(delta_seconds, config),
+            SimulationState::Gravitomagnetic(s) => s.update_velocities(delta_seconds, config),
+            SimulationState::Comoving(s) => s.update_velocities(delta_seconds, config),
         }
     }
 
     /// Delegates time advancement to the active simulation variant.
-    fn advance_time(&mut self, delta_seconds: f64) {
+    fn advance_time(&mut self, delta_seconds: f64, config: &EngineConfig) {
         match self {
-            SimulationState::Normal(s) => s.advance_time(delta_seconds),
-            SimulationState::SpeedOfLightLimit(s) => s.advance_time(delta_seconds),
-            SimulationState::LorentzTransformation(s) => s.advance_time(delta_seconds),
-            SimulationState::DstGravity(s) => s.advance_time(delta_seconds),
-            SimulationState::DstGalaxy(s) => s.advance_time(delta_seconds),
+            SimulationState::Normal(s) => s.advance_time(delta_seconds, config),
+            SimulationState::SpeedOfLightLimit(s) => s.advance_time(delta_seconds, config),
+            SimulationState::LorentzTransformation(s) => s.advance_time(delta_seconds, config),
+            SimulationState::DstGravity(s) => s.advance_time(delta_seconds, config),
+            SimulationState::DstGalaxy(s) => s.advance_time(delta_seconds, config),
+            SimulationState::Gravitomagnetic(s) => s.advance_time(delta_seconds, config),
+            SimulationState::Comoving(s) => s.advance_time(delta_seconds, config),
+        }
+    }
+}
+
+impl SimulationNormal {
+    /// Creates a Newtonian simulation state over `particles`.
+    pub fn new(particles: Vec<Particle>) -> Self {
+        Self {
+            particles,
+            tree: TreeCache::default(),
         }
     }
 }
@@ -403,7 +682,7 @@ impl SimulationEngine for SimulationState {
 impl Default for SimulationNormal {
     /// Creates an empty Newtonian simulation state.
     fn default() -> Self {
-        Self { particles: vec![] }
+        Self::new(vec![])
     }
 }
 
@@ -436,6 +715,26 @@ impl Default for SimulationDstGravity {
     }
 }
 
+impl Default for SimulationGravitomagnetic {
+    fn default() -> Self {
+        Self {
+            particles: vec![],
+            scale: DEFAULT_WORLD_SCALE,
+        }
+    }
+}
+
+impl Default for SimulationComoving {
+    fn default() -> Self {
+        let cosmology = Cosmology::DEFAULT;
+        Self {
+            particles: vec![],
+            cosmology,
+            time: cosmology.start_time(),
+        }
+    }
+}
+
 impl Default for SimulationDstGalaxy {
     fn default() -> Self {
         let scale = DEFAULT_WORLD_SCALE;
@@ -456,6 +755,21 @@ impl SimulationState {
             SimulationState::LorentzTransformation(s) => &s.particles,
             SimulationState::DstGravity(s) => &s.particles,
             SimulationState::DstGalaxy(s) => &s.particles,
+            SimulationState::Gravitomagnetic(s) => &s.particles,
+            SimulationState::Comoving(s) => &s.particles,
+        }
+    }
+
+    /// Returns the engine this state integrates with.
+    pub fn simulation_type(&self) -> SimulationType {
+        match self {
+            SimulationState::Normal(_) => SimulationType::Normal,
+            SimulationState::SpeedOfLightLimit(_) => SimulationType::SpeedOfLightLimit,
+            SimulationState::LorentzTransformation(_) => SimulationType::LorentzTransformation,
+            SimulationState::DstGravity(_) => SimulationType::DstGravity,
+            SimulationState::DstGalaxy(_) => SimulationType::DstGalaxy,
+            SimulationState::Gravitomagnetic(_) => SimulationType::Gravitomagnetic,
+            SimulationState::Comoving(_) => SimulationType::Comoving,
         }
     }
 
@@ -466,6 +780,8 @@ impl SimulationState {
             SimulationState::LorentzTransformation(s) => &mut s.particles,
             SimulationState::DstGravity(s) => &mut s.particles,
             SimulationState::DstGalaxy(s) => &mut s.particles,
+            SimulationState::Gravitomagnetic(s) => &mut s.particles,
+            SimulationState::Comoving(s) => &mut s.particles,
         }
     }
 }
@@ -479,35 +795,74 @@ impl Default for SimulationState {
 
 pub struct SimulationManager {
     pub state: Arc<RwLock<SimulationState>>,
+    /// Settings the engines step with.
+    pub config: RwLock<EngineConfig>,
 }
 
 impl SimulationManager {
     /// Creates a simulation manager with an initially empty default state.
     pub fn new() -> Self {
+        Self::with_config(EngineConfig::default())
+    }
+
+    /// Creates a simulation manager with an empty state whose engines step with `config`.
+    pub fn with_config(config: EngineConfig) -> Self {
         Self {
             state: Arc::new(RwLock::new(SimulationState::default())),
+            config: RwLock::new(config),
         }
     }
 
+    /// Returns the settings the engines step with.
+    pub fn config(&self) -> EngineConfig {
+        *self.config.read().unwrap()
+    }
+
+    /// Replaces the settings the engines step with. The speed of light and the cosmology
+    /// should only change along with a reset, which rebuilds the particles for them.
+    pub fn set_config(&self, config: EngineConfig) {
+        *self.config.write().unwrap() = config;
+    }
+
     /// Builds a simulation state from object inputs and selected simulation model.
     pub fn create_simulation(
         object_input: ObjectInput,
         simulation_type: SimulationType,
         particle_count: u32,
         scale: f64,
+        config: &EngineConfig,
     ) -> SimulationState {
         let normal = object_input.generate_particles(particle_count);
-        let particles = Self::prepare_particles(normal.particles, simulation_type, scale);
-        Self::state_from_particles(simulation_type, particles, scale)
+        let mut particles = Self::prepare_particles(
+            normal.particles,
+            simulation_type,
+            scale,
+            config.light_speed(),
+        );
+        Self::enter_frame(&mut particles, simulation_type, config);
+        Self::state_from_particles(simulation_type, particles, scale, config.cosmology)
+    }
+
+    /// Moves newly generated particles, given in the inertial frame, into the rotating
+    /// frame of `config` that the classical engines integrate in.
+    fn enter_frame(
+        particles: &mut [Particle],
+        simulation_type: SimulationType,
+        config: &EngineConfig,
+    ) {
+        if simulation_type.supports_rotating_frame() {
+            enter_rotating_frame(particles, config.frame_angular_velocity);
+        }
     }
 
     fn prepare_particles(
         mut particles: Vec<Particle>,
         simulation_type: SimulationType,
         scale: f64,
+        light_speed: f64,
     ) -> Vec<Particle> {
         if simulation_type.requires_subluminal_velocity() {
-            clamp_particle_velocities_sim(&mut particles, scale);
+            clamp_particle_velocities_sim(&mut particles, scale, light_speed);
         }
         if simulation_type == SimulationType::DstGalaxy {
             // Initial conditions that carry only 3D positions (identity orientation)
@@ -523,9 +878,9 @@ impl SimulationManager {
             }
         }
         if simulation_type.uses_rapidity_particles() {
-            Self::convert_to_lorentz(particles, scale)
+            Self::convert_to_lorentz(particles, scale, light_speed)
         } else if simulation_type.uses_momentum_particles() {
-            Self::convert_to_momentum(particles, scale)
+            Self::convert_to_momentum(particles, scale, light_speed)
         } else {
             particles
         }
@@ -533,11 +888,13 @@ impl SimulationManager {
 
     fn state_from_particles(
         simulation_type: SimulationType,
-        particles: Vec<Particle>,
+        mut particles: Vec<Particle>,
         scale: f64,
+        cosmology: Cosmology,
     ) -> SimulationState {
+        assign_particle_ids(&mut particles);
         match simulation_type {
-            SimulationType::Normal => SimulationState::Normal(SimulationNormal { particles }),
+            SimulationType::Normal => SimulationState::Normal(SimulationNormal::new(particles)),
             SimulationType::SpeedOfLightLimit => {
                 SimulationState::SpeedOfLightLimit(SimulationSpeedOfLightLimit { particles, scale })
             }
@@ -557,12 +914,27 @@ impl SimulationManager {
                     galaxy_radius: galaxy_radius_sim(scale),
                 })
             }
+            SimulationType::Gravitomagnetic => {
+                SimulationState::Gravitomagnetic(SimulationGravitomagnetic { particles, scale })
+            }
+            SimulationType::Comoving => {
+                SimulationState::Comoving(SimulationComoving {
+                    particles,
+                    cosmology,
+                    time: cosmology.start_time(),
+                })
+            }
         }
     }
 
-    /// Converts particle velocities into rapidity representation for Lorentz mode.
-    pub fn convert_to_lorentz(particles: Vec<Particle>, scale: f64) -> Vec<Particle> {
-        let ls = scale / LIGHT_SPEED;
+    /// Converts particle velocities into rapidity representation for Lorentz mode, with
+    /// the speed of light `light_speed` in m/s.
+    pub fn convert_to_lorentz(
+        particles: Vec<Particle>,
+        scale: f64,
+        light_speed: f64,
+    ) -> Vec<Particle> {
+        let ls = scale / light_speed;
         particles
             .into_iter()
             .map(|p| Particle {
@@ -574,13 +946,19 @@ impl SimulationManager {
                 proper_time: p.proper_time,
                 lambda_eff: p.lambda_eff,
                 orientation: p.orientation,
+                id: p.id,
             })
             .collect()
     }
 
-    /// Converts particle velocities into momentum representation for SpeedOfLightLimit mode.
-    pub fn convert_to_momentum(particles: Vec<Particle>, scale: f64) -> Vec<Particle> {
-        let ls = LIGHT_SPEED / scale;
+    /// Converts particle velocities into momentum representation for SpeedOfLightLimit mode,
+    /// with the speed of light `light_speed` in m/s.
+    pub fn convert_to_momentum(
+        particles: Vec<Particle>,
+        scale: f64,
+        light_speed: f64,
+    ) -> Vec<Particle> {
+        let ls = light_speed / scale;
         particles
             .into_iter()
             .map(|p| {
@@ -598,11 +976,59 @@ impl SimulationManager {
                     proper_time: p.proper_time,
                     lambda_eff: p.lambda_eff,
                     orientation: p.orientation,
+                    id: p.id,
                 }
             })
             .collect()
     }
 
+    /// Converts particles stored for engine `from` into the representation `to` integrates.
+    /// Rapidities become the coordinate velocity the Lorentz engine moves them at, momenta
+    /// are rebuilt from velocity, and S³ orientations are re-derived from display positions;
+    /// relativistic targets clamp velocities below light speed.
+    pub fn convert_particles(
+        particles: Vec<Particle>,
+        from: SimulationType,
+        to: SimulationType,
+        scale: f64,
+        light_speed: f64,
+    ) -> Vec<Particle> {
+        if from == to {
+            return particles;
+        }
+        let ls = light_speed / scale;
+        let particles = particles
+            .into_iter()
+            .map(|mut p| {
+                if from.uses_rapidity_particles() {
+                    p.velocity = velocity_from_rapidity(p.velocity, ls);
+                }
+                p.momentum = DVec3::ZERO;
+                p.orientation = DQuat::IDENTITY;
+                p
+            })
+            .collect();
+        Self::prepare_particles(particles, to, scale, light_speed)
+    }
+
+    /// Switches the running simulation to another engine without a reset, converting its
+    /// particles in place. Positions, ids, and proper times carry over.
+    pub fn switch_simulation_type(&self, simulation_type: SimulationType, scale: f64) {
+        let config = self.config();
+        let mut state_guard = self.state.write().unwrap();
+        let from = state_guard.simulation_type();
+        let particles = std::mem::take(state_guard.particles_mut());
+        let particles = Self::convert_particles(
+            particles,
+            from,
+            simulation_type,
+            scale,
+            config.light_speed(),
+        );
+        *state_guard =
+            Self::state_from_particles(simulation_type, particles, scale, config.cosmology);
+    }
+
     /// Replaces current simulation state with a freshly generated one.
     pub fn reset(
         &self,
@@ -611,8 +1037,13 @@ impl SimulationManager {
         particle_count: u32,
         scale: f64,
     ) {
-        let new_state =
-            Self::create_simulation(object_input, simulation_type, particle_count, scale);
+        let new_state = Self::create_simulation(
+            object_input,
+            simulation_type,
+            particle_count,
+            scale,
+            &self.config(),
+        );
         let mut state_guard = self.state.write().unwrap();
         *state_guard = new_state;
     }
@@ -624,23 +1055,180 @@ impl SimulationManager {
         simulation_type: SimulationType,
         scale: f64,
     ) {
-        let particles = Self::prepare_particles(particles, simulation_type, scale);
+        let config = self.config();
+        let mut particles =
+            Self::prepare_particles(particles, simulation_type, scale, config.light_speed());
+        Self::enter_frame(&mut particles, simulation_type, &config);
         let mut state_guard = self.state.write().unwrap();
-        *state_guard = Self::state_from_particles(simulation_type, particles, scale);
+        *state_guard =
+            Self::state_from_particles(simulation_type, particles, scale, config.cosmology);
+    }
+
+    /// Replaces current simulation state with particles already in the engine's
+    /// representation (rapidity, momentum, S³ orientation), such as an undo checkpoint.
+    /// A comoving simulation resumes at `cosmic_time` when given, so the scale factor is
+    /// the checkpoint's rather than the start of expansion.
+    pub fn restore_particles(
+        &self,
+        particles: Vec<Particle>,
+        simulation_type: SimulationType,
+        scale: f64,
+        cosmic_time: Option<(Cosmology, f64)>,
+    ) {
+        let cosmology = cosmic_time.map_or(self.config().cosmology, |(cosmology, _)| cosmology);
+        let mut state = Self::state_from_particles(simulation_type, particles, scale, cosmology);
+        if let (SimulationState::Comoving(comoving), Some((_, time))) = (&mut state, cosmic_time) {
+            comoving.time = time;
+        }
+        *self.state.write().unwrap() = state;
     }
 
     /// Clears all particles while preserving simulation type and scale settings.
     pub fn clear(&self, simulation_type: SimulationType, scale: f64) {
-        let new_state = Self::state_from_particles(simulation_type, vec![], scale);
+        let new_state =
+            Self::state_from_particles(simulation_type, vec![], scale, self.config().cosmology);
         let mut state_guard = self.state.write().unwrap();
         *state_guard = new_state;
     }
 
     /// Advances the active simulation by one frame and updates velocities.
     pub fn advance(&self, time_per_frame: f64) {
+        let config = self.config();
         let mut sim = self.state.write().unwrap();
-        sim.advance_time(time_per_frame);
-        sim.update_velocities(time_per_frame);
+        sim.advance_time(time_per_frame, &config);
+        sim.update_velocities(time_per_frame, &config);
+    }
+
+    /// Advances one frame like [`Self::advance`], but moves each of `pairs` along its Kepler
+    /// orbit instead of in straight lines and leaves the members' pull on each other out
+    /// of the velocity update.
+    pub fn advance_with_kepler_pairs(&self, time_per_frame: f64, pairs: &[KeplerPair]) {
+        let config = self.config();
+        let mut sim = self.state.write().unwrap();
+        let drifted = drift_kepler_pairs(sim.particles(), pairs, time_per_frame);
+        sim.advance_time(time_per_frame, &config);
+        let particles = sim.particles_mut();
+        for (index, position, velocity) in drifted {
+            particles[index].position = position;
+            particles[index].velocity = velocity;
+        }
+        sim.update_velocities(time_per_frame, &config);
+        remove_mutual_kicks(sim.particles_mut(), pairs, time_per_frame);
+    }
+
+    /// Advances one frame on per-particle block time steps with timestep factor
+    /// `accuracy` and returns the finest level used (see [`advance_block_steps`]). Engines
+    /// other than the Newtonian one take a single step.
+    pub fn advance_block_steps(&self, time_per_frame: f64, accuracy: f64) -> u32 {
+        let config = self.config();
+        let mut sim = self.state.write().unwrap();
+        if let SimulationState::Normal(normal) = &mut *sim {
+            return advance_block_steps(&mut normal.particles, time_per_frame, accuracy, &config);
+        }
+        sim.advance_time(time_per_frame, &config);
+        sim.update_velocities(time_per_frame, &config);
+        0
+    }
+
+    /// Returns the RMS relative error of the tree forces at opening angle `theta` against
+    /// direct summation, over `sample_size` particles picked by `seed`.
+    pub fn sample_force_error(&self, theta: f64, sample_size: usize, seed: u64) -> f64 {
+        let state = self.state.read().unwrap();
+        let particles = state.particles();
+        let sample = sample_indices(particles.len(), sample_size, seed);
+        relative_force_error(particles, theta, &sample)
+    }
+
+    /// Returns where the time of the tree solver's last velocity update went, when the
+    /// Newtonian engine has run one.
+    pub fn tree_timing(&self) -> Option<TreeTiming> {
+        match &*self.state.read().unwrap() {
+            SimulationState::Normal(normal) => normal.tree.timing(),
+            _ => None,
+        }
+    }
+
+    /// Returns the RMS relative error of the FMM forces at expansion `order` against
+    /// direct summation, over `sample_size` particles picked by `seed`.
+    pub fn sample_fmm_force_error(&self, order: u32, sample_size: usize, seed: u64) -> f64 {
+        let state = self.state.read().unwrap();
+        let particles = state.particles();
+        let sample = sample_indices(particles.len(), sample_size, seed);
+        fmm_relative_force_error(particles, order, &sample)
+    }
+
+    /// Finds the tight binaries a frame of `time_per_frame` would resolve with fewer than
+    /// `steps_per_orbit` steps; `held` are the pairs regularized last frame.
+    pub fn find_kepler_pairs(
+        &self,
+        time_per_frame: f64,
+        steps_per_orbit: u32,
+        held: &[(u64, u64)],
+    ) -> Vec<KeplerPair> {
+        let state = self.state.read().unwrap();
+        find_kepler_pairs(state.particles(), time_per_frame, steps_per_orbit, held)
+    }
+
+    /// Finds the close encounter a frame of `time_per_frame` would resolve with fewer than
+    /// `steps_per_encounter` steps, if any, leaving out the `regularized` pairs.
+    pub fn find_close_encounter(
+        &self,
+        time_per_frame: f64,
+        steps_per_encounter: u32,
+        regularized: &[(u64, u64)],
+    ) -> Option<Encounter> {
+        let state = self.state.read().unwrap();
+        find_close_encounter(
+            state.particles(),
+            time_per_frame,
+            steps_per_encounter,
+            regularized,
+        )
+    }
+
+    /// Bounces particles that left the container back inside.
+    pub fn reflect_off_walls(&self, walls: ContainerWalls) {
+        let mut state_guard = self.state.write().unwrap();
+        reflect_off_walls(state_guard.particles_mut(), walls);
+    }
+
+    /// Slows particles by `drag` for one step of `delta_seconds` at world `scale`.
+    pub fn apply_drag(&self, drag: DragForce, scale: f64, delta_seconds: f64) {
+        let mut state_guard = self.state.write().unwrap();
+        apply_drag(state_guard.particles_mut(), drag, scale, delta_seconds);
+    }
+
+    /// Applies one Langevin thermostat step; `step` selects the random kicks.
+    pub fn apply_langevin_noise(
+        &self,
+        noise: LangevinNoise,
+        scale: f64,
+        delta_seconds: f64,
+        step: u64,
+    ) {
+        let mut state_guard = self.state.write().unwrap();
+        let particles = state_guard.particles_mut();
+        apply_langevin_noise(particles, noise, scale, delta_seconds, step);
+    }
+
+    /// Applies one step of per-particle mass rules. Returns the indices of accreted
+    /// particles, which are removed, in ascending order.
+    pub fn evolve_masses(
+        &self,
+        rules: &[ParticleMassRule],
+        scale: f64,
+        delta_seconds: f64,
+    ) -> Vec<usize> {
+        let mut state_guard = self.state.write().unwrap();
+        evolve_masses(state_guard.particles_mut(), rules, scale, delta_seconds)
+    }
+
+    /// Returns the expansion history and cosmic time of a comoving simulation.
+    pub fn cosmic_time(&self) -> Option<(Cosmology, f64)> {
+        match &*self.state.read().unwrap() {
+            SimulationState::Comoving(s) => Some((s.cosmology, s.time)),
+            _ => None,
+        }
     }
 
     /// Returns the number of particles in the current simulation state.
@@ -654,17 +1242,60 @@ impl SimulationManager {
         state.particles().clone()
     }
 
+    /// Copies the current particles into `out`, reusing its allocation.
+    pub fn copy_particles_into(&self, out: &mut Vec<Particle>) {
+        let state = self.state.read().unwrap();
+        out.clone_from(state.particles());
+    }
+
+    /// Writes the current particles into a slot of `staging` in the GPU layout, under one
+    /// read lock. Returns false when no slot was free or large enough.
+    #[cfg(feature = "gui")]
+    pub(crate) fn write_gpu_frame(
+        &self,
+        staging: &StagingRing<GpuParticle>,
+        simulation_type: SimulationType,
+    ) -> bool {
+        let state = self.state.read().unwrap();
+        let particles = state.particles();
+        staging.write(particles.len(), |slots, ids| {
+            for (slot, particle) in slots.iter_mut().zip(particles) {
+                *slot = GpuParticle::from_cpu(particle, simulation_type);
+            }
+            ids.clear();
+            ids.extend(particles.iter().map(|p| p.id));
+        })
+    }
+
+    /// Returns a copy of the particle at `index`, if any.
+    pub fn particle_at(&self, index: usize) -> Option<Particle> {
+        self.state.read().unwrap().particles().get(index).copied()
+    }
+
+    /// Returns the index of the particle with `id`, checking `hint` before scanning.
+    pub fn find_particle_index(&self, id: u64, hint: usize) -> Option<usize> {
+        let state = self.state.read().unwrap();
+        let particles = state.particles();
+        if particles.get(hint).is_some_and(|p| p.id == id) {
+            return Some(hint);
+        }
+        particles.iter().position(|p| p.id == id)
+    }
+
     /// Replaces current simulation state with particles from a saved snapshot.
     pub fn load_from_snapshot(&self, snapshot: ParticleSnapshot) {
+        let config = self.config();
         let particles = Self::prepare_particles(
             snapshot.particles,
             snapshot.simulation_type,
             snapshot.scale,
+            config.light_speed(),
         );
         *self.state.write().unwrap() = Self::state_from_particles(
             snapshot.simulation_type,
             particles,
             snapshot.scale,
+            config.cosmology,
         );
     }
 
@@ -682,7 +1313,10 @@ impl SimulationManager {
         let mut new_particles = object_input
             .generate_particles_at_center(batch_count, center, base_scale)
             .particles;
-        new_particles = Self::prepare_particles(new_particles, simulation_type, scale);
+        let config = self.config();
+        new_particles =
+            Self::prepare_particles(new_particles, simulation_type, scale, config.light_speed());
+        Self::enter_frame(&mut new_particles, simulation_type, &config);
 
         let mut state_guard = self.state.write().unwrap();
         let particles = state_guard.particles_mut();
@@ -690,6 +1324,7 @@ impl SimulationManager {
         let remaining = max_particle_count.saturating_sub(particles.len() as u32) as usize;
         let to_add = new_particles.len().min(remaining);
         particles.extend(new_particles.into_iter().take(to_add));
+        assign_particle_ids(particles);
         to_add as u32
     }
 
@@ -725,6 +1360,13 @@ impl SimulationManager {
         removed
     }
 
+    /// Runs `f` on the particle list under a single write lock, so edits cannot
+    /// interleave with a worker step.
+    pub fn with_particles_mut<R>(&self, f: impl FnOnce(&mut Vec<Particle>) -> R) -> R {
+        let mut state_guard = self.state.write().unwrap();
+        f(state_guard.particles_mut())
+    }
+
     /// Removes particles at the given ascending indices. Out-of-range indices are
     /// skipped. Used to mirror a GPU-side cull onto the CPU particle list.
     pub fn remove_particles_at_sorted(&self, sorted_asc: &[usize]) {
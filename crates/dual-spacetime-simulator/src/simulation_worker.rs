@@ -0,0 +1,503 @@
+use crate::force_tiles::{force_thread_pool, numa_node_count};
+use crate::kepler_binary::KeplerPair;
+use crate::object_input::{ObjectInput, SolarSystemBuildError, build_solar_system_particles};
+use crate::run_target::reaches_run_end;
+use crate::sim_clock::SimulationClock;
+use crate::simulation::SimulationManager;
+use crate::speed_tuning::tune_skip;
+use crate::toast::ToastLevel;
+use crate::tree_gravity::{
+    FORCE_ERROR_CHECK_INTERVAL, FORCE_ERROR_SAMPLE_SIZE, tune_opening_angle,
+};
+use crate::ui_state::{PlacementMode, SimulationType, UiState};
+use crate::{GALAXY_CULL_INTERVAL, GpuParticleSync};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// State the simulation thread shares with the render loop.
+#[derive(Clone)]
+pub(crate) struct WorkerHandles {
+    pub ui_state: Arc<RwLock<UiState>>,
+    pub simulation_manager: Arc<RwLock<SimulationManager>>,
+    pub need_redraw: Arc<RwLock<bool>>,
+    pub skip_redraw: Arc<RwLock<u32>>,
+    pub gpu_particle_sync: GpuParticleSync,
+}
+
+/// Background thread that advances the simulation and schedules redraws.
+///
+/// Stopping is cooperative: the loop checks the flag at the top of every iteration, so
+/// `join` returns after the current step. Dropping the worker joins it.
+pub(crate) struct SimulationWorker {
+    handles: WorkerHandles,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+    /// Engine the running thread was started for.
+    simulation_type: SimulationType,
+}
+
+impl SimulationWorker {
+    pub(crate) fn start(handles: WorkerHandles) -> Self {
+        let mut worker = Self {
+            handles,
+            stop: Arc::new(AtomicBool::new(false)),
+            thread: None,
+            simulation_type: SimulationType::Normal,
+        };
+        worker.spawn();
+        worker
+    }
+
+    /// Asks the thread to exit after its current iteration, aborting an in-progress
+    /// Solar System download so it does not hold the thread.
+    pub(crate) fn stop(&self) {
+        self.stop.store(true, Ordering::Release);
+        let ui_state = self.handles.ui_state.read().unwrap();
+        if ui_state.reset_log.in_progress {
+            ui_state.request_reset_abort();
+        }
+    }
+
+    /// Stops the thread and waits for it to exit.
+    pub(crate) fn join(&mut self) {
+        self.stop();
+        if let Some(thread) = self.thread.take()
+            && thread.join().is_err()
+        {
+            eprintln!("Simulation thread panicked");
+        }
+    }
+
+    /// Joins the current thread and starts a fresh one on the same shared state.
+    pub(crate) fn restart(&mut self) {
+        self.join();
+        self.stop = Arc::new(AtomicBool::new(false));
+        self.spawn();
+    }
+
+    /// Restarts the thread once a completed reset has switched the active engine, so
+    /// per-engine loop state (cull counters, FPS baseline) starts clean. Returns whether
+    /// it restarted.
+    pub(crate) fn restart_on_engine_change(&mut self) -> bool {
+        let changed = {
+            let ui_state = self.handles.ui_state.read().unwrap();
+            !ui_state.is_reset_requested
+                && ui_state.active_simulation_type() != self.simulation_type
+        };
+        if changed {
+            self.restart();
+        }
+        changed
+    }
+
+    fn spawn(&mut self) {
+        let handles = self.handles.clone();
+        let (clock, simulation_type) = {
+            let ui_state = handles.ui_state.read().unwrap();
+            (ui_state.clock.clone(), ui_state.active_simulation_type())
+        };
+        self.simulation_type = simulation_type;
+        let stop = Arc::clone(&self.stop);
+        let spawned = std::thread::Builder::new()
+            .name("simulation".to_string())
+            .spawn(move || run_simulation_loop(handles, clock, stop));
+        match spawned {
+            Ok(thread) => self.thread = Some(thread),
+            Err(e) => eprintln!("Failed to start simulation thread: {}", e),
+        }
+    }
+}
+
+impl Drop for SimulationWorker {
+    fn drop(&mut self) {
+        self.join();
+    }
+}
+
+/// Advances the simulation and schedules redraws until `stop` is set.
+fn run_simulation_loop(handles: WorkerHandles, clock: SimulationClock, stop: Arc<AtomicBool>) {
+    let WorkerHandles {
+        ui_state: ui_state_clone,
+        simulation_manager,
+        need_redraw,
+        skip_redraw,
+        gpu_particle_sync,
+    } = handles;
+    // Pinning only pays off across NUMA nodes; on one node it just fights the scheduler.
+    let thread_pool = force_thread_pool(num_cpus::get(), numa_node_count() > 1);
+    let mut last_advance = Instant::now();
+    let mut last_fps = Instant::now();
+    let mut last_tick = Instant::now();
+    let mut wall_runtime = Duration::ZERO;
+    let mut prev_frame: i64 = 1;
+    // Frames handed to the render loop since the last FPS update.
+    let mut draws: u32 = 0;
+    let mut cpu_cull_counter: u32 = 0;
+    loop {
+        if stop.load(Ordering::Acquire) {
+            break;
+        }
+        {
+            let ui_state = ui_state_clone.read().unwrap();
+            let is_reset_requested = ui_state.is_reset_requested;
+            let is_add_particles_requested = ui_state.is_add_particles_requested;
+            if is_reset_requested || is_add_particles_requested {
+                let selected_object_input = ui_state.object_input.clone();
+                let simulation_type = ui_state.active_simulation_type();
+                let engine_config = ui_state.engine_config();
+                let skip = ui_state.skip;
+                let add_particle_count = ui_state.add_particle_count;
+                let scale = ui_state.scale;
+                let base_scale = ui_state.base_scale;
+                let add_center = ui_state.add_center;
+                let max_particle_count = ui_state.max_particle_count;
+                let uses_gpu = ui_state.uses_gpu_simulation();
+                let reset_repopulates = ui_state.reset_repopulates_particles();
+                let reset_object_input = ui_state.build_reset_object_input();
+                let placement_mode = ui_state.placement_mode;
+                let reset_epoch = ui_state.reset_simulation_epoch();
+                let reset_log_abort = Arc::clone(&ui_state.reset_log.abort_requested);
+                let toasts = ui_state.toasts.clone();
+                drop(ui_state);
+                if is_reset_requested {
+                    simulation_manager.read().unwrap().set_config(engine_config);
+                    let mut reset_applied = false;
+                    let mut body_names = None;
+                    if reset_repopulates && placement_mode == PlacementMode::SolarSystem {
+                        if let ObjectInput::SolarSystem {
+                            scale,
+                            start_year,
+                            start_month,
+                            start_day,
+                            start_hour,
+                        } = reset_object_input
+                        {
+                            let ui_state_for_log = Arc::clone(&ui_state_clone);
+                            let need_redraw_for_log = Arc::clone(&need_redraw);
+                            let log = move |line: &str| {
+                                {
+                                    let mut ui_state = ui_state_for_log.write().unwrap();
+                                    ui_state.append_reset_log(line);
+                                }
+                                need_redraw_for_log.write().unwrap().clone_from(&true);
+                            };
+                            match build_solar_system_particles(
+                                scale,
+                                start_year,
+                                start_month,
+                                start_day,
+                                start_hour,
+                                &log,
+                                reset_log_abort.as_ref(),
+                            ) {
+                                Ok(bodies) => {
+                                    toasts.post(
+                                        ToastLevel::Success,
+                                        format!(
+                                            "Solar system loaded: {} bodies",
+                                            bodies.names.len()
+                                        ),
+                                    );
+                                    simulation_manager.read().unwrap().reset_from_particles(
+                                        bodies.particles,
+                                        simulation_type,
+                                        base_scale,
+                                    );
+                                    body_names = Some(bodies.names);
+                                    reset_applied = true;
+                                }
+                                Err(SolarSystemBuildError::Aborted) => {
+                                    let mut ui_state = ui_state_clone.write().unwrap();
+                                    ui_state.append_reset_log("Aborted.");
+                                    ui_state.finish_reset_log();
+                                    ui_state.is_reset_requested = false;
+                                    drop(ui_state);
+                                    need_redraw.write().unwrap().clone_from(&true);
+                                    continue;
+                                }
+                            }
+                        }
+                    } else if reset_repopulates {
+                        simulation_manager.read().unwrap().reset(
+                            reset_object_input,
+                            simulation_type,
+                            add_particle_count,
+                            base_scale,
+                        );
+                        reset_applied = true;
+                    } else {
+                        simulation_manager
+                            .read()
+                            .unwrap()
+                            .clear(simulation_type, scale);
+                        reset_applied = true;
+                    }
+                    let mut ui_state = ui_state_clone.write().unwrap();
+                    if reset_applied {
+                        ui_state.finish_applied_reset(reset_epoch);
+                    }
+                    if let Some(names) = body_names {
+                        // Reset assigns IDs in order, so the names line up with them.
+                        let particles = simulation_manager.read().unwrap().particles();
+                        ui_state.set_body_labels(particles.iter().map(|p| p.id).zip(names));
+                    }
+                    ui_state.is_reset_requested = false;
+                    if placement_mode == PlacementMode::SolarSystem {
+                        ui_state.finish_reset_log();
+                    }
+                    drop(ui_state);
+                    if reset_applied {
+                        gpu_particle_sync.request_full_upload();
+                    }
+                    need_redraw.write().unwrap().clone_from(&true);
+                    skip_redraw.write().unwrap().clone_from(&skip);
+                    continue;
+                }
+                simulation_manager.write().unwrap().append_particles(
+                    selected_object_input,
+                    simulation_type,
+                    add_particle_count,
+                    scale,
+                    add_center,
+                    base_scale,
+                    max_particle_count,
+                );
+                let mut ui_state = ui_state_clone.write().unwrap();
+                ui_state.is_add_particles_requested = false;
+                drop(ui_state);
+                if uses_gpu {
+                    gpu_particle_sync.request_append_preserving();
+                } else {
+                    gpu_particle_sync.request_cpu_mode_upload();
+                }
+                need_redraw.write().unwrap().clone_from(&true);
+                skip_redraw.write().unwrap().clone_from(&skip);
+                continue;
+            }
+        }
+        // Pipelined stepping does not wait for the render loop to consume a frame:
+        // it integrates the next one while the last published copy is uploaded.
+        let pipelined = ui_state_clone.read().unwrap().uses_pipelined_stepping();
+        if *need_redraw.read().unwrap() && !pipelined {
+            std::thread::sleep(Duration::from_millis(16));
+            continue;
+        }
+        let ui_state = ui_state_clone.read().unwrap();
+        let is_running = ui_state.is_running;
+        let max_fps = ui_state.max_fps;
+        // Auto Speed steps as fast as it can and paces drawing through the skip instead.
+        let max_fps_unlimited = ui_state.max_fps_unlimited || ui_state.auto_speed;
+        let time_per_frame = ui_state.time_per_frame;
+        let encounter_refinement = ui_state.encounter_refinement();
+        let binary_regularization = ui_state.binary_regularization();
+        let held_pairs = ui_state.kepler_pairs.clone();
+        let block_stepping = ui_state.block_stepping();
+        let uses_tree_forces = ui_state.uses_tree_forces();
+        let uses_fast_multipoles = ui_state.uses_fast_multipoles();
+        let skip = ui_state.skip;
+        let uses_gpu = ui_state.uses_gpu_simulation();
+        let simulation_type = ui_state.active_simulation_type();
+        let galaxy_cull_enabled = ui_state.galaxy_cull_enabled;
+        let galaxy_cull_max_angle = ui_state.galaxy_cull_max_angle;
+        let engine_config = ui_state.engine_config();
+        let container_walls = ui_state.container_walls();
+        let drag = ui_state.drag_force();
+        let noise = ui_state.langevin_noise();
+        let mass_rules = ui_state.mass_rules_in_effect().to_vec();
+        let frame = ui_state.frame as u64;
+        let scale = ui_state.scale;
+        drop(ui_state);
+        simulation_manager.read().unwrap().set_config(engine_config);
+        let now = Instant::now();
+        if is_running {
+            wall_runtime += now.duration_since(last_tick);
+        }
+        last_tick = now;
+        let dt = now.duration_since(last_fps).as_secs_f64();
+        if dt >= 1.0 {
+            let mut ui_state = ui_state_clone.write().unwrap();
+            ui_state.fps = if ui_state.frame - prev_frame > 0 {
+                ui_state.frame - prev_frame
+            } else {
+                0
+            };
+            prev_frame = ui_state.frame;
+            clock.set_time_rate(Some(ui_state.fps as f64 * time_per_frame / dt));
+            ui_state.draw_fps = draws as i64;
+            if ui_state.auto_speed && is_running {
+                let target = ui_state.target_render_fps as f64;
+                ui_state.skip = tune_skip(ui_state.skip, draws as f64 / dt, target);
+            }
+            draws = 0;
+            drop(ui_state);
+            last_fps = now;
+        }
+        if !is_running {
+            publish_clock(&clock, &ui_state_clone.read().unwrap(), &mut wall_runtime);
+            std::thread::sleep(Duration::from_millis(16));
+            continue;
+        }
+        let dt = now.duration_since(last_advance).as_secs_f64();
+        if !max_fps_unlimited {
+            let target_fps = max_fps as f64;
+            if dt < 1.0 / target_fps {
+                continue;
+            }
+        }
+        if uses_gpu {
+            gpu_particle_sync.fetch_add_advance_step();
+        } else {
+            let (accreted, encounter, kepler_pairs, block_level) = thread_pool.install(|| {
+                let manager = simulation_manager.read().unwrap();
+                let kepler_pairs = binary_regularization.map_or_else(Vec::new, |steps| {
+                    manager.find_kepler_pairs(time_per_frame, steps, &held_pairs)
+                });
+                let regularized: Vec<(u64, u64)> =
+                    kepler_pairs.iter().map(KeplerPair::pair).collect();
+                let encounter = encounter_refinement.and_then(|steps| {
+                    manager.find_close_encounter(time_per_frame, steps, &regularized)
+                });
+                let block_level = block_stepping
+                    .map(|accuracy| manager.advance_block_steps(time_per_frame, accuracy));
+                if block_level.is_none() {
+                    let substeps = encounter.map_or(1, |encounter| encounter.substeps);
+                    let substep = time_per_frame / substeps as f64;
+                    for _ in 0..substeps {
+                        manager.advance_with_kepler_pairs(substep, &kepler_pairs);
+                    }
+                }
+                if let Some(drag) = drag {
+                    manager.apply_drag(drag, scale, time_per_frame);
+                }
+                if let Some(noise) = noise {
+                    manager.apply_langevin_noise(noise, scale, time_per_frame, frame);
+                }
+                if let Some(walls) = container_walls {
+                    manager.reflect_off_walls(walls);
+                }
+                let accreted = manager.evolve_masses(&mass_rules, scale, time_per_frame);
+                (accreted, encounter, kepler_pairs, block_level)
+            });
+            if block_stepping.is_some() {
+                ui_state_clone.write().unwrap().block_level = block_level;
+            }
+            if uses_tree_forces {
+                let timing = simulation_manager.read().unwrap().tree_timing();
+                ui_state_clone.write().unwrap().tree_timing = timing;
+            }
+            if uses_tree_forces && frame.is_multiple_of(FORCE_ERROR_CHECK_INTERVAL as u64) {
+                let theta = engine_config.opening_angle;
+                let error = thread_pool.install(|| {
+                    simulation_manager.read().unwrap().sample_force_error(
+                        theta,
+                        FORCE_ERROR_SAMPLE_SIZE,
+                        frame,
+                    )
+                });
+                let mut ui_state = ui_state_clone.write().unwrap();
+                ui_state.force_error = Some(error);
+                if ui_state.auto_tune_opening_angle {
+                    ui_state.opening_angle =
+                        tune_opening_angle(theta, error, ui_state.target_force_error);
+                }
+            }
+            if uses_fast_multipoles && frame.is_multiple_of(FORCE_ERROR_CHECK_INTERVAL as u64) {
+                let order = engine_config.expansion_order;
+                let error = thread_pool.install(|| {
+                    simulation_manager.read().unwrap().sample_fmm_force_error(
+                        order,
+                        FORCE_ERROR_SAMPLE_SIZE,
+                        frame,
+                    )
+                });
+                ui_state_clone.write().unwrap().force_error = Some(error);
+            }
+            if encounter_refinement.is_some() {
+                ui_state_clone.write().unwrap().note_encounter(encounter);
+            }
+            // Still noted once turned off, so the held pairs are logged as dissolved.
+            if binary_regularization.is_some() || !held_pairs.is_empty() {
+                ui_state_clone
+                    .write()
+                    .unwrap()
+                    .note_kepler_pairs(&kepler_pairs);
+            }
+            if !accreted.is_empty() {
+                ui_state_clone
+                    .write()
+                    .unwrap()
+                    .adjust_selection_after_removal(&accreted);
+            }
+            if galaxy_cull_enabled && simulation_type == SimulationType::DstGalaxy {
+                cpu_cull_counter += 1;
+                if cpu_cull_counter >= GALAXY_CULL_INTERVAL {
+                    cpu_cull_counter = 0;
+                    let removed = simulation_manager
+                        .read()
+                        .unwrap()
+                        .cull_galaxy_by_angle(galaxy_cull_max_angle);
+                    if !removed.is_empty() {
+                        ui_state_clone
+                            .write()
+                            .unwrap()
+                            .adjust_selection_after_removal(&removed);
+                    }
+                }
+            }
+        }
+        let drawn = *skip_redraw.read().unwrap() < 1;
+        if drawn {
+            if pipelined && !uses_gpu {
+                gpu_particle_sync
+                    .publish_frame(&simulation_manager.read().unwrap(), simulation_type);
+            }
+            let mut sr = skip_redraw.write().unwrap();
+            *sr = skip;
+            need_redraw.write().unwrap().clone_from(&true);
+            draws += 1;
+        } else {
+            let mut sr = skip_redraw.write().unwrap();
+            *sr -= 1;
+        }
+        last_advance = now;
+        let mut ui_state = ui_state_clone.write().unwrap();
+        let previous_time = ui_state.simulation_time;
+        ui_state.frame += 1;
+        ui_state.simulation_time += time_per_frame;
+        let run_complete = ui_state.run_target.end_time().is_some_and(|end| {
+            reaches_run_end(previous_time, ui_state.simulation_time, end, time_per_frame)
+        });
+        if run_complete {
+            ui_state.finish_run_target();
+        }
+        publish_clock(&clock, &ui_state, &mut wall_runtime);
+        drop(ui_state);
+        if run_complete && !drawn {
+            // Draw the final state even when it falls on a skipped frame.
+            if pipelined && !uses_gpu {
+                gpu_particle_sync
+                    .publish_frame(&simulation_manager.read().unwrap(), simulation_type);
+            }
+            skip_redraw.write().unwrap().clone_from(&skip);
+            need_redraw.write().unwrap().clone_from(&true);
+        }
+    }
+}
+
+/// Publishes frame, time, and FPS as one snapshot, with the ETA toward the run end time.
+/// A frame counter that went backwards (reset, undo, snapshot load) restarts the
+/// wall-clock runtime.
+fn publish_clock(clock: &SimulationClock, ui_state: &UiState, wall_runtime: &mut Duration) {
+    clock.set_target_time(ui_state.run_target.end_time());
+    if ui_state.frame < clock.snapshot().frame {
+        *wall_runtime = Duration::ZERO;
+    }
+    clock.publish(
+        ui_state.frame,
+        ui_state.simulation_time,
+        ui_state.fps,
+        *wall_runtime,
+    );
+}
@@ -0,0 +1,29 @@
+/// Render rate Auto Speed aims for by default (frames drawn per second).
+pub const DEFAULT_TARGET_RENDER_FPS: u32 = 30;
+pub const MIN_TARGET_RENDER_FPS: u32 = 5;
+pub const MAX_TARGET_RENDER_FPS: u32 = 240;
+/// Largest "Skip drawing frames" value Auto Speed sets, matching the slider.
+pub const MAX_AUTO_SKIP: u32 = 1000;
+/// Render rates within this fraction of the target leave the skip unchanged, so the value
+/// does not oscillate around the target.
+const DEAD_BAND: f64 = 0.1;
+/// Largest factor one adjustment may change the steps per drawn frame by.
+const MAX_STEP_RATIO: f64 = 2.0;
+
+/// Returns the next "Skip drawing frames" value for Auto Speed. Each drawn frame costs
+/// render time the simulation cannot spend stepping, so the largest skip that still draws
+/// `target_fps` frames per second gives the most steps per second. Steps per drawn frame
+/// (`skip + 1`) scale with the measured over target render rate, at most doubling or
+/// halving per adjustment. Returns `skip` unchanged while nothing is drawn.
+pub fn tune_skip(skip: u32, render_fps: f64, target_fps: f64) -> u32 {
+    if render_fps <= 0.0 || target_fps <= 0.0 {
+        return skip;
+    }
+    let ratio = render_fps / target_fps;
+    if (ratio - 1.0).abs() <= DEAD_BAND {
+        return skip;
+    }
+    let ratio = ratio.clamp(1.0 / MAX_STEP_RATIO, MAX_STEP_RATIO);
+    let steps_per_draw = ((skip as f64 + 1.0) * ratio).round().max(1.0);
+    (steps_per_draw as u32 - 1).min(MAX_AUTO_SKIP)
+}
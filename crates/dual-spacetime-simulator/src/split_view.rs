@@ -0,0 +1,62 @@
+/// One of the two views shown side by side in split view.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ViewSide {
+    /// Left half, or the whole window without split view; seen by the main camera.
+    #[default]
+    Primary,
+    /// Right half, seen by the second camera.
+    Secondary,
+}
+
+/// Pixel rectangle of a view: top-left corner and size.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ViewRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ViewRect {
+    pub fn aspect_ratio(self) -> f32 {
+        self.width as f32 / self.height.max(1) as f32
+    }
+
+    /// Center of the rectangle in window pixels.
+    pub fn center(self) -> (f64, f64) {
+        (
+            self.x as f64 + self.width as f64 / 2.0,
+            self.y as f64 + self.height as f64 / 2.0,
+        )
+    }
+}
+
+/// Rectangle of the `side` view in a `width` × `height` window. With `split`, the window
+/// is divided vertically and the left half takes the odd column; without it, both sides
+/// span the whole window.
+pub fn view_rect(side: ViewSide, width: u32, height: u32, split: bool) -> ViewRect {
+    let left_width = if split { width.div_ceil(2) } else { width };
+    match side {
+        ViewSide::Secondary if split => ViewRect {
+            x: left_width,
+            y: 0,
+            width: width - left_width,
+            height,
+        },
+        _ => ViewRect {
+            x: 0,
+            y: 0,
+            width: left_width,
+            height,
+        },
+    }
+}
+
+/// Returns the view under the window column `x`.
+pub fn view_side_at(x: f64, width: u32, split: bool) -> ViewSide {
+    if split && x >= width.div_ceil(2) as f64 {
+        ViewSide::Secondary
+    } else {
+        ViewSide::Primary
+    }
+}
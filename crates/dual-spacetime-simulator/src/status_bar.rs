@@ -0,0 +1,100 @@
+use glam::Vec3;
+use std::time::{Duration, Instant};
+
+/// How long a notification stays in the status bar.
+pub const STATUS_MESSAGE_DURATION: Duration = Duration::from_secs(6);
+
+/// What scene drags and keys currently do, as reported in the status bar.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ToolMode {
+    Orbit,
+    Spacecraft,
+    /// The camera follows the selected particle.
+    Trace,
+}
+
+impl ToolMode {
+    pub fn current(lock_camera_up: bool, trace_active: bool) -> Self {
+        if trace_active {
+            Self::Trace
+        } else if lock_camera_up {
+            Self::Orbit
+        } else {
+            Self::Spacecraft
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Orbit => "Orbit",
+            Self::Spacecraft => "Spacecraft",
+            Self::Trace => "Trace",
+        }
+    }
+
+    /// One-line reminder of the controls that matter in this mode.
+    pub fn hint(self) -> &'static str {
+        match self {
+            Self::Orbit => "Left drag orbit · Right drag look · Wheel zoom · Click select",
+            Self::Spacecraft => "W/S pitch · A/D roll · Space/Shift thrust · Click steer anchor",
+            Self::Trace => "Wheel follow distance · Escape end tracing",
+        }
+    }
+}
+
+/// A notification shown in the status bar until it expires or is replaced.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatusMessage {
+    pub text: String,
+    pub is_error: bool,
+    pub posted_at: Instant,
+}
+
+/// Latest notification for the status bar. Failures that used to go only to stderr are
+/// posted here as errors.
+#[derive(Clone, Debug, Default)]
+pub struct StatusBar {
+    message: Option<StatusMessage>,
+}
+
+impl StatusBar {
+    pub fn info(&mut self, text: impl Into<String>) {
+        self.post(text.into(), false);
+    }
+
+    /// Posts an error and also writes it to stderr.
+    pub fn error(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        eprintln!("{}", text);
+        self.post(text, true);
+    }
+
+    fn post(&mut self, text: String, is_error: bool) {
+        self.message = Some(StatusMessage {
+            text,
+            is_error,
+            posted_at: Instant::now(),
+        });
+    }
+
+    /// Returns the notification to show at `now`, if it has not expired.
+    pub fn message(&self, now: Instant) -> Option<&StatusMessage> {
+        self.message
+            .as_ref()
+            .filter(|message| now.duration_since(message.posted_at) < STATUS_MESSAGE_DURATION)
+    }
+}
+
+/// Intersects the line through `near` and `far` with the y = 0 plane. Returns `None`
+/// when the line is parallel to the plane or meets it behind `near`.
+pub fn intersect_grid_plane(near: Vec3, far: Vec3) -> Option<Vec3> {
+    let direction = far - near;
+    if direction.y.abs() <= f32::EPSILON {
+        return None;
+    }
+    let t = -near.y / direction.y;
+    if !t.is_finite() || t < 0.0 {
+        return None;
+    }
+    Some(near + direction * t)
+}
@@ -0,0 +1,190 @@
+use glam::{Mat4, Vec3};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::path::Path;
+
+/// Largest side in pixels of an offscreen tile, supersampling included; bigger stills are
+/// rendered tile by tile so they need not fit one GPU image.
+pub const STILL_TILE_SIZE: u32 = 2048;
+/// Largest side in pixels of a still.
+pub const MAX_STILL_SIZE: u32 = 16384;
+/// Resolutions offered in the Render Still panel.
+pub const STILL_RESOLUTION_PRESETS: [(&str, u32, u32); 4] = [
+    ("Full HD", 1920, 1080),
+    ("4K UHD", 3840, 2160),
+    ("8K UHD", 7680, 4320),
+    ("Square 4K", 4096, 4096),
+];
+/// Supersampling factors offered; each divides [`STILL_TILE_SIZE`].
+pub const STILL_SUPERSAMPLING: [u32; 3] = [1, 2, 4];
+pub const STILL_FILTER_NAME: &str = "PNG Image";
+/// Per-pixel difference [`compare_images`] treats as visible, in 8-bit luma steps.
+pub const VISIBLE_PIXEL_DIFFERENCE: f32 = 8.0;
+pub const STILL_FILTER_EXT: &str = "png";
+
+/// Output size and supersampling of a still.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct StillSettings {
+    pub width: u32,
+    pub height: u32,
+    /// Rendered pixels per output pixel along each axis.
+    pub supersampling: u32,
+}
+
+impl Default for StillSettings {
+    fn default() -> Self {
+        Self {
+            width: 3840,
+            height: 2160,
+            supersampling: 2,
+        }
+    }
+}
+
+/// Rectangle of a still in output pixels: top-left corner and size.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct StillTile {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Splits a still into row-major tiles that fit [`STILL_TILE_SIZE`] once supersampled.
+pub fn still_tiles(settings: StillSettings) -> Vec<StillTile> {
+    let side = STILL_TILE_SIZE / settings.supersampling.max(1);
+    let mut tiles = Vec::new();
+    for y in (0..settings.height).step_by(side as usize) {
+        for x in (0..settings.width).step_by(side as usize) {
+            tiles.push(StillTile {
+                x,
+                y,
+                width: side.min(settings.width - x),
+                height: side.min(settings.height - y),
+            });
+        }
+    }
+    tiles
+}
+
+/// Clip-space transform that makes `tile` of a `width` × `height` frame fill the viewport:
+/// applied after the frame's projection, it scales and shifts x and y only, so depth and
+/// pixel-sized point sprites are unchanged.
+pub fn tile_clip_transform(tile: StillTile, width: u32, height: u32) -> Mat4 {
+    let scale_x = width as f32 / tile.width as f32;
+    let scale_y = height as f32 / tile.height as f32;
+    let offset_x = (width as f32 - 2.0 * tile.x as f32 - tile.width as f32) / tile.width as f32;
+    let offset_y = (height as f32 - 2.0 * tile.y as f32 - tile.height as f32) / tile.height as f32;
+    Mat4::from_translation(Vec3::new(offset_x, offset_y, 0.0))
+        * Mat4::from_scale(Vec3::new(scale_x, scale_y, 1.0))
+}
+
+/// Averages each `factor` × `factor` block of a rendered tile's 4-byte pixels into the RGB
+/// pixel at its place in `output`, an image `width` pixels wide. `bgra` marks blue-first
+/// pixels. Alpha is dropped.
+pub fn resolve_tile(
+    output: &mut [u8],
+    width: u32,
+    tile: StillTile,
+    pixels: &[u8],
+    factor: u32,
+    bgra: bool,
+) {
+    let factor = factor.max(1) as usize;
+    let samples = (factor * factor) as u32;
+    let row_bytes = tile.width as usize * factor * 4;
+    let (red, blue) = if bgra { (2, 0) } else { (0, 2) };
+    for y in 0..tile.height as usize {
+        for x in 0..tile.width as usize {
+            let mut sum = [0u32; 3];
+            for sy in 0..factor {
+                let row = (y * factor + sy) * row_bytes;
+                for sx in 0..factor {
+                    let pixel = &pixels[row + (x * factor + sx) * 4..][..4];
+                    sum[0] += u32::from(pixel[red]);
+                    sum[1] += u32::from(pixel[1]);
+                    sum[2] += u32::from(pixel[blue]);
+                }
+            }
+            let out = ((tile.y as usize + y) * width as usize + tile.x as usize + x) * 3;
+            for (channel, total) in sum.into_iter().enumerate() {
+                output[out + channel] = ((total + samples / 2) / samples) as u8;
+            }
+        }
+    }
+}
+
+/// Writes an 8-bit RGB image to `path` as PNG.
+pub fn save_png(path: &Path, width: u32, height: u32, rgb: &[u8]) -> io::Result<()> {
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = png::Encoder::new(file, width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(io::Error::other)?;
+    writer.write_image_data(rgb).map_err(io::Error::other)?;
+    writer.finish().map_err(io::Error::other)
+}
+
+/// Reads an 8-bit RGB PNG as written by [`save_png`]; returns its width, height and pixels.
+pub fn load_png(path: &Path) -> io::Result<(u32, u32, Vec<u8>)> {
+    let decoder = png::Decoder::new(BufReader::new(File::open(path)?));
+    let mut reader = decoder.read_info().map_err(io::Error::other)?;
+    if reader.output_color_type() != (png::ColorType::Rgb, png::BitDepth::Eight) {
+        return Err(io::Error::other("Not an 8-bit RGB PNG"));
+    }
+    let size = reader
+        .output_buffer_size()
+        .ok_or_else(|| io::Error::other("PNG too large"))?;
+    let mut rgb = vec![0; size];
+    let frame = reader.next_frame(&mut rgb).map_err(io::Error::other)?;
+    rgb.truncate(frame.buffer_size());
+    Ok((frame.width, frame.height, rgb))
+}
+
+/// How far an image is from the one it should match.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ImageDifference {
+    /// Largest per-pixel difference, in 8-bit luma steps.
+    pub max_difference: f32,
+    /// Pixels that differ by more than the threshold given to [`compare_images`].
+    pub differing_pixels: usize,
+    pub pixel_count: usize,
+}
+
+impl ImageDifference {
+    pub fn differing_fraction(&self) -> f64 {
+        self.differing_pixels as f64 / self.pixel_count.max(1) as f64
+    }
+}
+
+/// Compares two 8-bit RGB images of the same size pixel by pixel. Channel differences are
+/// weighted by their share of luma (Rec. 601), so a change the eye barely sees, such as in
+/// blue, counts for less than the same change in green.
+pub fn compare_images(
+    expected: &[u8],
+    actual: &[u8],
+    threshold: f32,
+) -> Result<ImageDifference, String> {
+    if expected.len() != actual.len() || !expected.len().is_multiple_of(3) {
+        return Err(format!(
+            "Image sizes differ ({} and {} bytes)",
+            expected.len(),
+            actual.len()
+        ));
+    }
+    let mut difference = ImageDifference {
+        max_difference: 0.0,
+        differing_pixels: 0,
+        pixel_count: expected.len() / 3,
+    };
+    for (a, b) in expected.chunks_exact(3).zip(actual.chunks_exact(3)) {
+        let delta = |channel: usize| f32::from(a[channel]) - f32::from(b[channel]);
+        let pixel =
+            (0.299 * delta(0).powi(2) + 0.587 * delta(1).powi(2) + 0.114 * delta(2).powi(2)).sqrt();
+        difference.max_difference = difference.max_difference.max(pixel);
+        if pixel > threshold {
+            difference.differing_pixels += 1;
+        }
+    }
+    Ok(difference)
+}
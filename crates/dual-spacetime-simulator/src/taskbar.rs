@@ -0,0 +1,172 @@
+/// Progress shown on the application's taskbar button.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum TaskbarProgress {
+    #[default]
+    None,
+    /// Work of unknown length, such as files waiting on the export thread.
+    Indeterminate,
+    /// Fraction complete in `[0, 1]`.
+    Normal(f32),
+}
+
+/// Picks what the taskbar shows: a running batch sweep by its finished runs, otherwise
+/// pending exports as indeterminate work.
+pub fn taskbar_progress(batch: Option<(usize, usize)>, pending_exports: usize) -> TaskbarProgress {
+    match batch {
+        Some((done, total)) if total > 0 => {
+            TaskbarProgress::Normal((done as f32 / total as f32).clamp(0.0, 1.0))
+        }
+        _ if pending_exports > 0 => TaskbarProgress::Indeterminate,
+        _ => TaskbarProgress::None,
+    }
+}
+
+/// The window's taskbar button. Shows progress through `ITaskbarList3` on Windows and does
+/// nothing elsewhere.
+#[cfg(feature = "gui")]
+pub struct Taskbar {
+    shown: TaskbarProgress,
+    #[cfg(windows)]
+    list: Option<windows::TaskbarList>,
+}
+
+#[cfg(feature = "gui")]
+impl Taskbar {
+    pub fn new(window: &winit::window::Window) -> Self {
+        #[cfg(not(windows))]
+        let _ = window;
+        Self {
+            shown: TaskbarProgress::None,
+            #[cfg(windows)]
+            list: windows::TaskbarList::new(window)
+                .map_err(|e| eprintln!("Taskbar progress unavailable: {}", e))
+                .ok(),
+        }
+    }
+
+    /// Updates the taskbar button when `progress` differs from what it shows, in steps of
+    /// 0.1 %.
+    pub fn set(&mut self, progress: TaskbarProgress) {
+        let progress = match progress {
+            TaskbarProgress::Normal(fraction) => {
+                TaskbarProgress::Normal((fraction * 1000.0).round() / 1000.0)
+            }
+            other => other,
+        };
+        if progress == self.shown {
+            return;
+        }
+        self.shown = progress;
+        #[cfg(windows)]
+        if let Some(list) = self.list.as_ref() {
+            list.set(progress);
+        }
+    }
+}
+
+#[cfg(all(feature = "gui", windows))]
+mod windows {
+    use super::TaskbarProgress;
+    use raw_window_handle::{HasWindowHandle, RawWindowHandle};
+    use std::ffi::c_void;
+    use windows_sys::Win32::System::Com::{
+        CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED, CoCreateInstance, CoInitializeEx,
+    };
+    use windows_sys::core::{GUID, HRESULT};
+
+    const CLSID_TASKBAR_LIST: GUID = GUID::from_u128(0x56fdf344_fd6d_11d0_958a_006097c9a090);
+    const IID_ITASKBAR_LIST3: GUID = GUID::from_u128(0xea1afb91_9e28_4b86_90e9_9e9f8a5eefaf);
+    const TBPF_NOPROGRESS: i32 = 0;
+    const TBPF_INDETERMINATE: i32 = 1;
+    const TBPF_NORMAL: i32 = 2;
+    /// `SetProgressValue` total; fractions are sent as parts per this.
+    const PROGRESS_TOTAL: u64 = 1000;
+
+    /// Leading entries of the `ITaskbarList3` vtable, up to `SetProgressState`.
+    #[repr(C)]
+    struct TaskbarList3Vtbl {
+        query_interface: usize,
+        add_ref: usize,
+        release: unsafe extern "system" fn(*mut TaskbarList3) -> u32,
+        hr_init: unsafe extern "system" fn(*mut TaskbarList3) -> HRESULT,
+        add_tab: usize,
+        delete_tab: usize,
+        activate_tab: usize,
+        set_active_alt: usize,
+        mark_fullscreen_window: usize,
+        set_progress_value:
+            unsafe extern "system" fn(*mut TaskbarList3, *mut c_void, u64, u64) -> HRESULT,
+        set_progress_state:
+            unsafe extern "system" fn(*mut TaskbarList3, *mut c_void, i32) -> HRESULT,
+    }
+
+    #[repr(C)]
+    struct TaskbarList3 {
+        vtbl: *const TaskbarList3Vtbl,
+    }
+
+    /// `ITaskbarList3` bound to one window. Lives on the event-loop thread, which COM
+    /// initializes as a single-threaded apartment.
+    pub(super) struct TaskbarList {
+        list: *mut TaskbarList3,
+        hwnd: *mut c_void,
+    }
+
+    impl TaskbarList {
+        pub(super) fn new(window: &winit::window::Window) -> Result<Self, String> {
+            let hwnd = match window.window_handle().map(|handle| handle.as_raw()) {
+                Ok(RawWindowHandle::Win32(handle)) => handle.hwnd.get() as *mut c_void,
+                _ => return Err("no Win32 window handle".to_string()),
+            };
+            let mut list: *mut TaskbarList3 = std::ptr::null_mut();
+            unsafe {
+                // Fails harmlessly when winit already initialized COM on this thread.
+                CoInitializeEx(std::ptr::null(), COINIT_APARTMENTTHREADED as _);
+                let hr = CoCreateInstance(
+                    &CLSID_TASKBAR_LIST,
+                    std::ptr::null_mut(),
+                    CLSCTX_INPROC_SERVER,
+                    &IID_ITASKBAR_LIST3,
+                    (&mut list as *mut *mut TaskbarList3).cast(),
+                );
+                if hr < 0 || list.is_null() {
+                    return Err(format!("CoCreateInstance failed: {:#010x}", hr));
+                }
+                let hr = ((*(*list).vtbl).hr_init)(list);
+                if hr < 0 {
+                    ((*(*list).vtbl).release)(list);
+                    return Err(format!("HrInit failed: {:#010x}", hr));
+                }
+            }
+            Ok(Self { list, hwnd })
+        }
+
+        pub(super) fn set(&self, progress: TaskbarProgress) {
+            let vtbl = unsafe { &*(*self.list).vtbl };
+            unsafe {
+                match progress {
+                    TaskbarProgress::None => {
+                        (vtbl.set_progress_state)(self.list, self.hwnd, TBPF_NOPROGRESS);
+                    }
+                    TaskbarProgress::Indeterminate => {
+                        (vtbl.set_progress_state)(self.list, self.hwnd, TBPF_INDETERMINATE);
+                    }
+                    TaskbarProgress::Normal(fraction) => {
+                        (vtbl.set_progress_state)(self.list, self.hwnd, TBPF_NORMAL);
+                        let completed = (fraction as f64 * PROGRESS_TOTAL as f64) as u64;
+                        (vtbl.set_progress_value)(self.list, self.hwnd, completed, PROGRESS_TOTAL);
+                    }
+                }
+            }
+        }
+    }
+
+    impl Drop for TaskbarList {
+        fn drop(&mut self) {
+            unsafe {
+                ((*(*self.list).vtbl).set_progress_state)(self.list, self.hwnd, TBPF_NOPROGRESS);
+                ((*(*self.list).vtbl).release)(self.list);
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use dst_math::bivector::{BivectorBoost, LorentzRotor};
+use glam::{DQuat, DVec3};
+use std::f64::consts::TAU;
+
+/// Particle-space radius of the demonstration orbit.
+pub const THOMAS_ORBIT_RADIUS: f64 = 1.0;
+pub const DEFAULT_THOMAS_BETA: f64 = 0.8;
+pub const DEFAULT_THOMAS_STEPS_PER_REVOLUTION: u32 = 720;
+
+/// A gyroscope-carrying particle kept on a circular orbit in the grid plane by a boost in
+/// its own rest frame at every step. The boosts do not commute, so the particle's axes
+/// come back rotated after each revolution: Thomas precession, the accumulated Wigner
+/// rotation.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ThomasPrecession {
+    /// Transformation from the particle's rest frame to the lab frame.
+    rotor: LorentzRotor,
+    beta: f64,
+    steps_per_revolution: u32,
+    steps: u64,
+    /// Wigner rotation accumulated about the orbit axis (+y) in radians; negative is
+    /// retrograde.
+    pub wigner_angle: f64,
+}
+
+impl ThomasPrecession {
+    /// Starts at `(0, 0, R)` moving along +x at `beta`·c, orbiting counterclockwise about
+    /// +y in `steps_per_revolution` rest-frame boosts.
+    pub fn new(beta: f64, steps_per_revolution: u32) -> Self {
+        let mut precession = Self {
+            rotor: LorentzRotor::IDENTITY,
+            beta,
+            steps_per_revolution: steps_per_revolution.max(3),
+            steps: 0,
+            wigner_angle: 0.0,
+        };
+        precession.rotor = LorentzRotor::from_boost(precession.lab_boost(0));
+        precession
+    }
+
+    /// Applies the next rest-frame boost: the pure boost that turns the lab velocity by
+    /// one step at constant speed. Whatever rotation that leaves over is this step's
+    /// Wigner rotation.
+    pub fn step(&mut self) {
+        let (boost, rotation) = self.rotor.decompose();
+        let boost = LorentzRotor::from_boost(boost);
+        let orientation = LorentzRotor::from_rotation(rotation);
+        self.steps += 1;
+        let target = LorentzRotor::from_boost(self.lab_boost(self.steps));
+        let (thrust, wigner) = (boost.inverse() * target).decompose();
+        // The thrust is along lab-aligned axes; express it in the particle's own axes.
+        let rest_frame_boost =
+            orientation.inverse() * LorentzRotor::from_boost(thrust) * orientation;
+        self.rotor = boost * orientation * rest_frame_boost;
+        self.wigner_angle -= wigner.j;
+    }
+
+    pub fn beta(&self) -> f64 {
+        self.beta
+    }
+
+    pub fn gamma(&self) -> f64 {
+        (1.0 - self.beta * self.beta).sqrt().recip()
+    }
+
+    pub fn steps_per_revolution(&self) -> u32 {
+        self.steps_per_revolution
+    }
+
+    /// Orbit angle swept so far, in radians.
+    pub fn orbit_angle(&self) -> f64 {
+        self.steps as f64 * TAU / self.steps_per_revolution as f64
+    }
+
+    pub fn revolutions(&self) -> f64 {
+        self.steps as f64 / self.steps_per_revolution as f64
+    }
+
+    /// Particle-space position on the orbit.
+    pub fn position(&self) -> DVec3 {
+        let (sin, cos) = self.orbit_angle().sin_cos();
+        DVec3::new(sin, 0.0, cos) * THOMAS_ORBIT_RADIUS
+    }
+
+    /// Lab velocity in units of c, recovered from the rotor.
+    pub fn velocity(&self) -> DVec3 {
+        let (boost, _) = self.rotor.decompose();
+        let rapidity = DVec3::new(boost.i, boost.j, boost.k);
+        let eta = rapidity.length();
+        if eta == 0.0 {
+            DVec3::ZERO
+        } else {
+            rapidity * (eta.tanh() / eta)
+        }
+    }
+
+    /// Rotation of the particle's axes relative to the lab axes.
+    pub fn orientation(&self) -> DQuat {
+        let (_, rotation) = self.rotor.decompose();
+        DQuat::from_scaled_axis(DVec3::new(rotation.i, rotation.j, rotation.k))
+    }
+
+    /// Lab boost of the particle after `steps` steps.
+    fn lab_boost(&self, steps: u64) -> BivectorBoost {
+        let angle = steps as f64 * TAU / self.steps_per_revolution as f64;
+        let (sin, cos) = angle.sin_cos();
+        let eta = self.beta.atanh();
+        BivectorBoost::new(eta * cos, 0.0, -eta * sin)
+    }
+}
+
+/// Thomas precession of a circular orbit at `beta`·c per revolution, in radians:
+/// `−2π(γ − 1)`, retrograde.
+pub fn thomas_precession_per_revolution(beta: f64) -> f64 {
+    -TAU * ((1.0 - beta * beta).sqrt().recip() - 1.0)
+}
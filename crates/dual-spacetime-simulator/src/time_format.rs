@@ -0,0 +1,218 @@
+use serde::{Deserialize, Serialize};
+
+pub const SECONDS_PER_DAY: f64 = 86_400.0;
+/// Julian year in seconds (365.25 days).
+pub const SECONDS_PER_YEAR: f64 = 365.25 * SECONDS_PER_DAY;
+pub const SECONDS_PER_MYR: f64 = 1e6 * SECONDS_PER_YEAR;
+
+/// Unit used to display elapsed simulation time in the Simulation panel.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum TimeDisplayUnit {
+    /// `days hh:mm:ss`, the original compact format.
+    #[default]
+    DayClock,
+    Seconds,
+    Days,
+    Years,
+    Myr,
+    /// Absolute UTC date from the reset epoch; falls back to `DayClock` without one.
+    Calendar,
+}
+
+impl TimeDisplayUnit {
+    /// All units in UI display order.
+    pub const ALL: [Self; 6] = [
+        Self::DayClock,
+        Self::Seconds,
+        Self::Days,
+        Self::Years,
+        Self::Myr,
+        Self::Calendar,
+    ];
+}
+
+impl std::fmt::Display for TimeDisplayUnit {
+    /// Formats time display units for UI selection controls.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            TimeDisplayUnit::DayClock => "d hh:mm:ss",
+            TimeDisplayUnit::Seconds => "Seconds",
+            TimeDisplayUnit::Days => "Days",
+            TimeDisplayUnit::Years => "Years",
+            TimeDisplayUnit::Myr => "Myr",
+            TimeDisplayUnit::Calendar => "Calendar",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// UTC start date of a simulation whose initial conditions come from a dated ephemeris.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CalendarEpoch {
+    pub year: i32,
+    pub month: i32,
+    pub day: i32,
+    pub hour: i32,
+}
+
+impl CalendarEpoch {
+    /// J2000.0 (2000-01-01 12:00 UTC), also the fallback for invalid dates.
+    pub const J2000: Self = Self {
+        year: 2000,
+        month: 1,
+        day: 1,
+        hour: 12,
+    };
+
+    /// Builds an epoch, substituting [`Self::J2000`] for out-of-range fields as the
+    /// Solar System preset does when the ephemeris rejects a date.
+    pub fn new(year: i32, month: i32, day: i32, hour: i32) -> Self {
+        let valid = (1..=12).contains(&month)
+            && (1..=days_in_month(year, month)).contains(&day)
+            && (0..24).contains(&hour);
+        if valid {
+            Self {
+                year,
+                month,
+                day,
+                hour,
+            }
+        } else {
+            Self::J2000
+        }
+    }
+
+    /// Returns the epoch as seconds since 1970-01-01 00:00 UTC.
+    fn unix_seconds(self) -> f64 {
+        days_from_civil(self.year, self.month, self.day) as f64 * SECONDS_PER_DAY
+            + self.hour as f64 * 3600.0
+    }
+}
+
+/// Formats elapsed simulation time in the requested unit.
+///
+/// `epoch` is only consulted for [`TimeDisplayUnit::Calendar`].
+pub fn format_simulation_time(
+    simulation_time: f64,
+    unit: TimeDisplayUnit,
+    epoch: Option<CalendarEpoch>,
+) -> String {
+    match unit {
+        TimeDisplayUnit::DayClock => format_day_clock(simulation_time),
+        TimeDisplayUnit::Seconds => format!("{} s", format_scaled(simulation_time)),
+        TimeDisplayUnit::Days => {
+            format!("{} d", format_scaled(simulation_time / SECONDS_PER_DAY))
+        }
+        TimeDisplayUnit::Years => {
+            format!("{} yr", format_scaled(simulation_time / SECONDS_PER_YEAR))
+        }
+        TimeDisplayUnit::Myr => {
+            format!("{} Myr", format_scaled(simulation_time / SECONDS_PER_MYR))
+        }
+        TimeDisplayUnit::Calendar => match epoch {
+            Some(epoch) => format_calendar(epoch, simulation_time),
+            None => format_day_clock(simulation_time),
+        },
+    }
+}
+
+/// Formats a wall-clock duration as `h:mm:ss`.
+pub fn format_wall_duration(duration: std::time::Duration) -> String {
+    let total = duration.as_secs();
+    let (hours, minutes, seconds) = (total / 3600, total % 3600 / 60, total % 60);
+    format!("{}:{:02}:{:02}", hours, minutes, seconds)
+}
+
+/// Formats simulation seconds per wall-clock second in the largest of s, d, yr, and Myr
+/// that keeps the value at least one, e.g. `3.500 d/s`.
+pub fn format_time_rate(rate: f64) -> String {
+    let (unit, seconds) = [
+        ("Myr", SECONDS_PER_MYR),
+        ("yr", SECONDS_PER_YEAR),
+        ("d", SECONDS_PER_DAY),
+    ]
+    .into_iter()
+    .find(|&(_, seconds)| rate.abs() >= seconds)
+    .unwrap_or(("s", 1.0));
+    format!("{} {}/s", format_scaled(rate / seconds), unit)
+}
+
+/// Formats simulation time into a compact signed `days hh:mm:ss` string.
+fn format_day_clock(simulation_time: f64) -> String {
+    let sign = if simulation_time < 0.0 { "-" } else { "" };
+    let total_seconds = simulation_time.abs();
+    let days = (total_seconds / SECONDS_PER_DAY).floor() as i64;
+    let remaining_seconds = total_seconds % SECONDS_PER_DAY;
+    let hours = (remaining_seconds / 3600.0).floor() as i64;
+    let minutes = ((remaining_seconds % 3600.0) / 60.0).floor() as i64;
+    let seconds = (remaining_seconds % 60.0).floor() as i64;
+    format!(
+        "{}{} {:02}:{:02}:{:02}",
+        sign, days, hours, minutes, seconds
+    )
+}
+
+/// Fixed three decimals in the readable range, scientific notation outside it.
+fn format_scaled(value: f64) -> String {
+    if value != 0.0 && (value.abs() >= 1e9 || value.abs() < 1e-3) {
+        format!("{:.3e}", value)
+    } else {
+        format!("{:.3}", value)
+    }
+}
+
+/// Formats `epoch + simulation_time` as `YYYY-MM-DD hh:mm:ss` (UTC, proleptic Gregorian).
+fn format_calendar(epoch: CalendarEpoch, simulation_time: f64) -> String {
+    let total = (epoch.unix_seconds() + simulation_time).floor();
+    let days = (total / SECONDS_PER_DAY).floor();
+    let seconds_of_day = (total - days * SECONDS_PER_DAY) as i64;
+    let (year, month, day) = civil_from_days(days as i64);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60,
+        seconds_of_day % 60
+    )
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: i32) -> i32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date (Howard Hinnant's algorithm).
+fn days_from_civil(year: i32, month: i32, day: i32) -> i64 {
+    let year = year as i64 - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Inverse of [`days_from_civil`].
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,76 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long a toast stays on screen unless dismissed.
+pub const TOAST_DURATION: Duration = Duration::from_secs(5);
+/// Toasts shown at once; posting more drops the oldest.
+pub const MAX_TOASTS: usize = 4;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ToastLevel {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Toast {
+    /// Identifies the toast for [`Toasts::dismiss`]; unique within its queue.
+    pub id: u64,
+    pub level: ToastLevel,
+    pub text: String,
+    pub posted_at: Instant,
+}
+
+/// Queue of non-blocking notifications drawn in a corner of the window. Clones share the
+/// queue, so background threads (the export writer, the simulation worker) hold a clone
+/// and post without touching the UI state lock.
+#[derive(Clone, Debug, Default)]
+pub struct Toasts {
+    queue: Arc<Mutex<ToastQueue>>,
+}
+
+#[derive(Debug, Default)]
+struct ToastQueue {
+    toasts: VecDeque<Toast>,
+    next_id: u64,
+}
+
+impl Toasts {
+    pub fn post(&self, level: ToastLevel, text: impl Into<String>) {
+        let mut queue = self.queue.lock().unwrap();
+        let id = queue.next_id;
+        queue.next_id += 1;
+        queue.toasts.push_back(Toast {
+            id,
+            level,
+            text: text.into(),
+            posted_at: Instant::now(),
+        });
+        while queue.toasts.len() > MAX_TOASTS {
+            queue.toasts.pop_front();
+        }
+    }
+
+    /// Drops toasts older than [`TOAST_DURATION`] at `now` and returns the rest, oldest
+    /// first.
+    pub fn visible(&self, now: Instant) -> Vec<Toast> {
+        let mut queue = self.queue.lock().unwrap();
+        queue
+            .toasts
+            .retain(|toast| now.duration_since(toast.posted_at) < TOAST_DURATION);
+        queue.toasts.iter().cloned().collect()
+    }
+
+    /// Removes the toast with the given id, if it is still queued. Toasts posted or
+    /// expired since [`Self::visible`] returned do not shift which one goes.
+    pub fn dismiss(&self, id: u64) {
+        self.queue
+            .lock()
+            .unwrap()
+            .toasts
+            .retain(|toast| toast.id != id);
+    }
+}
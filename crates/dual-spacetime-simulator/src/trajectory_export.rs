@@ -0,0 +1,74 @@
+use crate::simulation::Particle;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+pub const TRAJECTORY_FILTER_NAME: &str = "CSV";
+pub const TRAJECTORY_FILTER_EXT: &str = "csv";
+pub const TRAJECTORY_CSV_HEADER: &str = "t,id,x,y,z,vx,vy,vz";
+/// Default number of frames between recorded trajectory samples.
+pub const DEFAULT_TRAJECTORY_INTERVAL: u32 = 1;
+
+/// Streams trajectories of a fixed set of particle IDs to a CSV file.
+///
+/// Each sample writes one row per tracked particle still present: simulation time in
+/// seconds, the particle ID, then position and velocity in simulation units (base-scale
+/// units and base-scale units per second; rapidity in Lorentz mode).
+pub struct TrajectoryRecorder {
+    writer: BufWriter<File>,
+    /// Tracked IDs, sorted for binary search.
+    ids: Vec<u64>,
+    rows: usize,
+}
+
+impl TrajectoryRecorder {
+    /// Creates `path` (and parent directories) and writes the CSV header.
+    pub fn create(path: &Path, ids: &[u64]) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "{}", TRAJECTORY_CSV_HEADER)?;
+        let mut ids = ids.to_vec();
+        ids.sort_unstable();
+        ids.dedup();
+        Ok(Self {
+            writer,
+            ids,
+            rows: 0,
+        })
+    }
+
+    /// Appends one row per tracked particle found in `particles`.
+    pub fn record(&mut self, time: f64, particles: &[Particle]) -> io::Result<()> {
+        for p in particles
+            .iter()
+            .filter(|p| self.ids.binary_search(&p.id).is_ok())
+        {
+            writeln!(
+                self.writer,
+                "{:e},{},{:e},{:e},{:e},{:e},{:e},{:e}",
+                time,
+                p.id,
+                p.position.x,
+                p.position.y,
+                p.position.z,
+                p.velocity.x,
+                p.velocity.y,
+                p.velocity.z
+            )?;
+            self.rows += 1;
+        }
+        Ok(())
+    }
+
+    /// Number of data rows written so far.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Flushes buffered rows to disk.
+    pub fn finish(mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
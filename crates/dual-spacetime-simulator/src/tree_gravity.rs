@@ -0,0 +1,501 @@
+use crate::simulation::{EPSILON, G, Particle};
+use dst_math::gravity::newtonian_gravity_pair;
+use dst_math::summation::{Summation, Vec3Sum};
+use glam::DVec3;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rayon::prelude::*;
+use std::time::Instant;
+
+/// Opening angle θ used until the user or the auto-tuning picks another.
+pub const DEFAULT_OPENING_ANGLE: f64 = 0.5;
+pub const MIN_OPENING_ANGLE: f64 = 0.1;
+pub const MAX_OPENING_ANGLE: f64 = 1.2;
+/// Relative force error the auto-tuning aims for by default.
+pub const DEFAULT_TARGET_FORCE_ERROR: f64 = 1e-3;
+pub const MIN_TARGET_FORCE_ERROR: f64 = 1e-6;
+pub const MAX_TARGET_FORCE_ERROR: f64 = 1e-1;
+/// Simulation frames between force error checks.
+pub const FORCE_ERROR_CHECK_INTERVAL: i64 = 60;
+/// Particles whose tree force is compared with direct summation at each check.
+pub const FORCE_ERROR_SAMPLE_SIZE: usize = 64;
+/// Steps between full tree rebuilds until the user picks another; in between, the tree
+/// is updated in place.
+pub const DEFAULT_TREE_REBUILD_INTERVAL: u32 = 16;
+pub const MIN_TREE_REBUILD_INTERVAL: u32 = 1;
+pub const MAX_TREE_REBUILD_INTERVAL: u32 = 256;
+/// Particles a cell holds before it is split.
+const LEAF_CAPACITY: usize = 8;
+/// Deepest split, so coincident particles cannot recurse forever.
+const MAX_DEPTH: u32 = 48;
+/// Errors within this fraction of the target leave θ unchanged.
+const DEAD_BAND: f64 = 0.1;
+/// Largest factor one adjustment may change θ by.
+const MAX_ANGLE_RATIO: f64 = 1.5;
+pub(crate) const NO_CHILD: u32 = u32::MAX;
+
+/// How the CPU Newtonian engine finds forces: over every pair, through an octree, or
+/// through the fast multipole method.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ForceSolver {
+    #[default]
+    Direct,
+    /// Barnes–Hut octree: distant cells act through their total mass at their center of
+    /// mass, O(N log N) per step.
+    Tree,
+    /// Fast multipole method on the same octree: cells interact through Cartesian
+    /// expansions of a chosen order, O(N) per step.
+    Fmm,
+}
+
+impl ForceSolver {
+    pub const ALL: [Self; 3] = [Self::Direct, Self::Tree, Self::Fmm];
+}
+
+impl std::fmt::Display for ForceSolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ForceSolver::Direct => write!(f, "Direct"),
+            ForceSolver::Tree => write!(f, "Tree"),
+            ForceSolver::Fmm => write!(f, "FMM"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Node {
+    pub(crate) center: DVec3,
+    pub(crate) half_size: f64,
+    pub(crate) mass: f64,
+    pub(crate) center_of_mass: DVec3,
+    pub(crate) children: [u32; 8],
+    /// Particles of a leaf; empty for internal nodes.
+    pub(crate) particles: Vec<usize>,
+    depth: u32,
+}
+
+impl Node {
+    fn new(center: DVec3, half_size: f64, depth: u32) -> Self {
+        Self {
+            center,
+            half_size,
+            mass: 0.0,
+            center_of_mass: center,
+            children: [NO_CHILD; 8],
+            particles: Vec::new(),
+            depth,
+        }
+    }
+
+    pub(crate) fn is_leaf(&self) -> bool {
+        self.children.iter().all(|&child| child == NO_CHILD)
+    }
+
+    fn contains(&self, point: DVec3) -> bool {
+        (point - self.center).abs().max_element() <= self.half_size
+    }
+
+    fn octant(&self, point: DVec3) -> usize {
+        (point.x >= self.center.x) as usize
+            | (((point.y >= self.center.y) as usize) << 1)
+            | (((point.z >= self.center.z) as usize) << 2)
+    }
+}
+
+/// Barnes–Hut octree over particle positions and masses. Cells keep their geometry
+/// between [`Self::update`] calls; only leaves are split, so a cell stays a leaf or an
+/// internal node for the tree's lifetime.
+#[derive(Clone, Debug, Default)]
+pub struct Octree {
+    /// Parents come before their children.
+    pub(crate) nodes: Vec<Node>,
+    /// Leaf holding each particle; [`NO_CHILD`] for particles without a finite position.
+    leaf_of: Vec<u32>,
+}
+
+impl Octree {
+    /// Builds the tree over the particles with finite positions.
+    pub fn build(positions: &[DVec3], masses: &[f64]) -> Self {
+        let mut tree = Self {
+            nodes: Vec::new(),
+            leaf_of: vec![NO_CHILD; positions.len()],
+        };
+        let finite: Vec<usize> = (0..positions.len())
+            .filter(|&i| positions[i].is_finite())
+            .collect();
+        if finite.is_empty() {
+            return tree;
+        }
+        let (min, max) = finite.iter().fold(
+            (DVec3::splat(f64::INFINITY), DVec3::splat(f64::NEG_INFINITY)),
+            |(min, max), &i| (min.min(positions[i]), max.max(positions[i])),
+        );
+        let half_size = ((max - min).max_element() * 0.5).max(f64::MIN_POSITIVE);
+        tree.nodes.push(Node::new((min + max) * 0.5, half_size, 0));
+        for i in finite {
+            tree.insert(positions, i);
+        }
+        tree.refresh(positions, masses);
+        tree
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Moves every particle that left its leaf into the leaf now holding it, splitting
+    /// leaves that overflow, then refreshes every cell's mass and center of mass. Returns
+    /// how many particles moved, or `None` when the tree has to be rebuilt instead: the
+    /// particle count changed, or a particle left the root cell.
+    pub fn update(&mut self, positions: &[DVec3], masses: &[f64]) -> Option<usize> {
+        if self.nodes.is_empty() || positions.len() != self.leaf_of.len() {
+            return None;
+        }
+        let moved: Vec<usize> = (0..positions.len())
+            .into_par_iter()
+            .filter(|&i| match self.leaf_of[i] {
+                NO_CHILD => positions[i].is_finite(),
+                leaf => !self.nodes[leaf as usize].contains(positions[i]),
+            })
+            .collect();
+        let escaped = moved
+            .iter()
+            .any(|&i| positions[i].is_finite() && !self.nodes[0].contains(positions[i]));
+        if escaped {
+            return None;
+        }
+        for &i in &moved {
+            self.remove(i);
+            if positions[i].is_finite() {
+                self.insert(positions, i);
+            }
+        }
+        self.refresh(positions, masses);
+        Some(moved.len())
+    }
+
+    /// Puts particle `i` into the leaf containing it, creating the leaf if needed.
+    fn insert(&mut self, positions: &[DVec3], i: usize) {
+        let point = positions[i];
+        let mut node = 0;
+        while !self.nodes[node].is_leaf() {
+            node = self.child_toward(node, point);
+        }
+        self.nodes[node].particles.push(i);
+        self.leaf_of[i] = node as u32;
+        self.split_if_full(positions, node);
+    }
+
+    fn remove(&mut self, i: usize) {
+        let leaf = std::mem::replace(&mut self.leaf_of[i], NO_CHILD);
+        if leaf != NO_CHILD {
+            let particles = &mut self.nodes[leaf as usize].particles;
+            if let Some(k) = particles.iter().position(|&j| j == i) {
+                particles.swap_remove(k);
+            }
+        }
+    }
+
+    /// Child of `node` in the octant of `point`, created empty when missing.
+    fn child_toward(&mut self, node: usize, point: DVec3) -> usize {
+        let parent = &self.nodes[node];
+        let octant = parent.octant(point);
+        if parent.children[octant] != NO_CHILD {
+            return parent.children[octant] as usize;
+        }
+        let offset = DVec3::new(
+            if octant & 1 != 0 { 1.0 } else { -1.0 },
+            if octant & 2 != 0 { 1.0 } else { -1.0 },
+            if octant & 4 != 0 { 1.0 } else { -1.0 },
+        );
+        let quarter = parent.half_size * 0.5;
+        let child = Node::new(parent.center + offset * quarter, quarter, parent.depth + 1);
+        let index = self.nodes.len();
+        self.nodes.push(child);
+        self.nodes[node].children[octant] = index as u32;
+        index
+    }
+
+    fn split_if_full(&mut self, positions: &[DVec3], node: usize) {
+        if self.nodes[node].particles.len() <= LEAF_CAPACITY || self.nodes[node].depth >= MAX_DEPTH
+        {
+            return;
+        }
+        let particles = std::mem::take(&mut self.nodes[node].particles);
+        for &i in &particles {
+            let child = self.child_toward(node, positions[i]);
+            self.nodes[child].particles.push(i);
+            self.leaf_of[i] = child as u32;
+        }
+        for child in self.nodes[node].children {
+            if child != NO_CHILD {
+                self.split_if_full(positions, child as usize);
+            }
+        }
+    }
+
+    /// Recomputes every cell's mass and center of mass, leaves first.
+    fn refresh(&mut self, positions: &[DVec3], masses: &[f64]) {
+        self.nodes
+            .par_iter_mut()
+            .filter(|node| node.is_leaf())
+            .for_each(|node| {
+                let (mass, weighted) = node
+                    .particles
+                    .iter()
+                    .fold((0.0, DVec3::ZERO), |(mass, weighted), &i| {
+                        (mass + masses[i], weighted + positions[i] * masses[i])
+                    });
+                node.mass = mass;
+                node.center_of_mass = if mass != 0.0 {
+                    weighted / mass
+                } else {
+                    node.center
+                };
+            });
+        for n in (0..self.nodes.len()).rev() {
+            if self.nodes[n].is_leaf() {
+                continue;
+            }
+            let (mass, weighted) = self.nodes[n]
+                .children
+                .iter()
+                .filter(|&&child| child != NO_CHILD)
+                .map(|&child| &self.nodes[child as usize])
+                .fold((0.0, DVec3::ZERO), |(mass, weighted), child| {
+                    (
+                        mass + child.mass,
+                        weighted + child.center_of_mass * child.mass,
+                    )
+                });
+            let node = &mut self.nodes[n];
+            node.mass = mass;
+            node.center_of_mass = if mass != 0.0 {
+                weighted / mass
+            } else {
+                node.center
+            };
+        }
+    }
+
+    /// Velocity change `G dt Σ m r̂ / r²` of particle `index` over a step with
+    /// `time_g = G dt`. Cells smaller than `theta` times their distance, and not holding
+    /// the particle, act through their center of mass; the rest are opened down to their
+    /// particles, which are summed directly like the Normal engine sums them.
+    pub fn velocity_delta(
+        &self,
+        positions: &[DVec3],
+        masses: &[f64],
+        index: usize,
+        theta: f64,
+        time_g: f64,
+        summation: Summation,
+    ) -> DVec3 {
+        let point = positions[index];
+        let mut acceleration = Vec3Sum::new(summation);
+        if self.nodes.is_empty() {
+            return acceleration.value();
+        }
+        let mut stack = vec![0u32];
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node as usize];
+            if node.mass == 0.0 {
+                continue;
+            }
+            let distance = (node.center_of_mass - point).length();
+            if 2.0 * node.half_size < theta * distance && !node.contains(point) {
+                acceleration.add(
+                    newtonian_gravity_pair(
+                        point,
+                        node.center_of_mass,
+                        node.mass,
+                        G,
+                        time_g,
+                        EPSILON,
+                    )
+                    .1,
+                );
+            } else if node.is_leaf() {
+                for &j in &node.particles {
+                    if j != index {
+                        acceleration.add(
+                            newtonian_gravity_pair(
+                                point,
+                                positions[j],
+                                masses[j],
+                                G,
+                                time_g,
+                                EPSILON,
+                            )
+                            .1,
+                        );
+                    }
+                }
+            } else {
+                stack.extend(node.children.iter().filter(|&&child| child != NO_CHILD));
+            }
+        }
+        acceleration.value()
+    }
+}
+
+/// Where the time of one tree velocity update went.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TreeTiming {
+    /// Building or updating the tree, in seconds.
+    pub tree_seconds: f64,
+    /// Walking the tree for every particle's force, in seconds.
+    pub walk_seconds: f64,
+    /// Whether the tree was built from scratch.
+    pub rebuilt: bool,
+    /// Particles the in-place update moved to another leaf.
+    pub moved: usize,
+    pub node_count: usize,
+}
+
+/// Barnes–Hut tree kept across the steps of one engine, rebuilt from scratch every
+/// `rebuild_interval` steps and updated in place in between.
+#[derive(Clone, Debug, Default)]
+pub struct TreeCache {
+    tree: Octree,
+    steps_since_rebuild: u32,
+    timing: Option<TreeTiming>,
+}
+
+impl TreeCache {
+    /// Tree velocity update over `delta_seconds` like [`tree_velocity_update`], reusing
+    /// the tree of the previous step when it is younger than `rebuild_interval` steps
+    /// and every particle stayed inside the root cell.
+    pub fn velocity_update(
+        &mut self,
+        particles: &mut [Particle],
+        delta_seconds: f64,
+        theta: f64,
+        rebuild_interval: u32,
+        summation: Summation,
+    ) {
+        let started = Instant::now();
+        let positions: Vec<DVec3> = particles.iter().map(|p| p.position).collect();
+        let masses: Vec<f64> = particles.iter().map(|p| p.mass).collect();
+        let moved = if self.steps_since_rebuild < rebuild_interval {
+            self.tree.update(&positions, &masses)
+        } else {
+            None
+        };
+        if moved.is_none() {
+            self.tree = Octree::build(&positions, &masses);
+            self.steps_since_rebuild = 0;
+        }
+        self.steps_since_rebuild += 1;
+        let tree_seconds = started.elapsed().as_secs_f64();
+        let started = Instant::now();
+        let tree = &self.tree;
+        let time_g = G * delta_seconds;
+        particles
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(i, particle)| {
+                particle.velocity +=
+                    tree.velocity_delta(&positions, &masses, i, theta, time_g, summation);
+            });
+        self.timing = Some(TreeTiming {
+            tree_seconds,
+            walk_seconds: started.elapsed().as_secs_f64(),
+            rebuilt: moved.is_none(),
+            moved: moved.unwrap_or(0),
+            node_count: tree.node_count(),
+        });
+    }
+
+    /// Timing of the last velocity update, if any.
+    pub fn timing(&self) -> Option<TreeTiming> {
+        self.timing
+    }
+}
+
+/// Tree counterpart of the Normal engine's direct velocity update over `delta_seconds`.
+pub fn tree_velocity_update(
+    particles: &mut [Particle],
+    delta_seconds: f64,
+    theta: f64,
+    summation: Summation,
+) {
+    let positions: Vec<DVec3> = particles.iter().map(|p| p.position).collect();
+    let masses: Vec<f64> = particles.iter().map(|p| p.mass).collect();
+    let tree = Octree::build(&positions, &masses);
+    let time_g = G * delta_seconds;
+    particles
+        .par_iter_mut()
+        .enumerate()
+        .for_each(|(i, particle)| {
+            particle.velocity +=
+                tree.velocity_delta(&positions, &masses, i, theta, time_g, summation);
+        });
+}
+
+/// Picks up to `count` distinct particle indices out of `len`, differently for each
+/// `seed`.
+pub fn sample_indices(len: usize, count: usize, seed: u64) -> Vec<usize> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    rand::seq::index::sample(&mut rng, len, count.min(len)).into_vec()
+}
+
+/// RMS relative error `|a_tree − a_direct| / |a_direct|` of the tree forces at opening
+/// angle `theta` over the `sample` particles. Costs one tree build plus O(N) per sampled
+/// particle; 0 when no sampled particle feels a force.
+pub fn relative_force_error(particles: &[Particle], theta: f64, sample: &[usize]) -> f64 {
+    let positions: Vec<DVec3> = particles.iter().map(|p| p.position).collect();
+    let masses: Vec<f64> = particles.iter().map(|p| p.mass).collect();
+    let tree = Octree::build(&positions, &masses);
+    rms_relative_error(&positions, &masses, sample, |i| {
+        tree.velocity_delta(&positions, &masses, i, theta, G, Summation::Naive)
+    })
+}
+
+/// RMS relative error of `approximate(i)`, a velocity change with `time_g = G`, against
+/// direct summation over the `sample` particles.
+pub(crate) fn rms_relative_error(
+    positions: &[DVec3],
+    masses: &[f64],
+    sample: &[usize],
+    approximate: impl Fn(usize) -> DVec3 + Sync,
+) -> f64 {
+    let errors: Vec<f64> = sample
+        .par_iter()
+        .filter_map(|&i| {
+            let direct: DVec3 = (0..positions.len())
+                .filter(|&j| j != i)
+                .map(|j| {
+                    newtonian_gravity_pair(positions[i], positions[j], masses[j], G, G, EPSILON).1
+                })
+                .sum();
+            let magnitude = direct.length();
+            (magnitude > 0.0).then(|| (approximate(i) - direct).length() / magnitude)
+        })
+        .collect();
+    if errors.is_empty() {
+        return 0.0;
+    }
+    (errors.iter().map(|e| e * e).sum::<f64>() / errors.len() as f64).sqrt()
+}
+
+/// Returns the opening angle that should bring the measured relative force error to
+/// `target`. The monopole error grows about as θ², so θ scales with the square root of
+/// the target over measured error, by at most [`MAX_ANGLE_RATIO`] per adjustment and
+/// clamped to `MIN_OPENING_ANGLE..=MAX_OPENING_ANGLE`. Errors within the dead band, or
+/// no measurement, leave θ unchanged.
+pub fn tune_opening_angle(theta: f64, measured: f64, target: f64) -> f64 {
+    if target.is_nan() || target <= 0.0 || measured.is_nan() {
+        return theta;
+    }
+    let ratio = if measured > 0.0 {
+        if (measured / target - 1.0).abs() <= DEAD_BAND {
+            return theta;
+        }
+        (target / measured).sqrt()
+    } else {
+        MAX_ANGLE_RATIO
+    };
+    let ratio = ratio.clamp(1.0 / MAX_ANGLE_RATIO, MAX_ANGLE_RATIO);
+    (theta * ratio).clamp(MIN_OPENING_ANGLE, MAX_OPENING_ANGLE)
+}
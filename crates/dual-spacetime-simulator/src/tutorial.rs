@@ -0,0 +1,157 @@
+/// Steps of the guided tour, in order.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TutorialStep {
+    Welcome,
+    OpenObjectInput,
+    LoadPreset,
+    Start,
+    MoveCamera,
+    Zoom,
+    Diagnostics,
+}
+
+/// User actions the tour waits for.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TutorialAction {
+    /// The Next button on the tutorial card.
+    Next,
+    OpenedObjectInput,
+    Reset,
+    Started,
+    /// A scene drag revolved, panned, or rotated the camera.
+    CameraMoved,
+    /// The mouse wheel or the Scale slider changed the zoom.
+    Zoomed,
+}
+
+/// UI elements a step points at.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TutorialTarget {
+    ObjectInputButton,
+    ResetButton,
+    StartButton,
+    ScaleSlider,
+    Diagnostics,
+}
+
+impl TutorialStep {
+    pub const ALL: [Self; 7] = [
+        Self::Welcome,
+        Self::OpenObjectInput,
+        Self::LoadPreset,
+        Self::Start,
+        Self::MoveCamera,
+        Self::Zoom,
+        Self::Diagnostics,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Welcome => "Welcome",
+            Self::OpenObjectInput => "Object Input",
+            Self::LoadPreset => "Load a Preset",
+            Self::Start => "Run the Simulation",
+            Self::MoveCamera => "Move the Camera",
+            Self::Zoom => "Zoom",
+            Self::Diagnostics => "Diagnostics",
+        }
+    }
+
+    pub fn instructions(self) -> &'static str {
+        match self {
+            Self::Welcome => {
+                "This tour shows how to load a scenario, look around, and read what the \
+                 simulation reports. Press Next to begin."
+            }
+            Self::OpenObjectInput => {
+                "Press Object Input in the Simulation panel to open the scenario settings."
+            }
+            Self::LoadPreset => {
+                "Pick a Placement such as Solar System, or an object type with its \
+                 parameters, then press Reset to load it."
+            }
+            Self::Start => "Press Start to run the simulation. Press it again to pause.",
+            Self::MoveCamera => {
+                "Drag in the scene with the left mouse button to orbit the camera, with the \
+                 right button to look around, or with the middle button to roll."
+            }
+            Self::Zoom => "Turn the mouse wheel over the scene, or drag the Scale slider.",
+            Self::Diagnostics => {
+                "The Simulation panel shows frames per second, the frame count, simulated \
+                 time, wall-clock runtime, and the particle count. Press Next to finish."
+            }
+        }
+    }
+
+    /// Returns the UI element the step highlights, if it has one.
+    pub fn target(self) -> Option<TutorialTarget> {
+        match self {
+            Self::Welcome | Self::MoveCamera => None,
+            Self::OpenObjectInput => Some(TutorialTarget::ObjectInputButton),
+            Self::LoadPreset => Some(TutorialTarget::ResetButton),
+            Self::Start => Some(TutorialTarget::StartButton),
+            Self::Zoom => Some(TutorialTarget::ScaleSlider),
+            Self::Diagnostics => Some(TutorialTarget::Diagnostics),
+        }
+    }
+
+    /// Returns the action that completes the step.
+    pub fn completed_by(self) -> TutorialAction {
+        match self {
+            Self::Welcome | Self::Diagnostics => TutorialAction::Next,
+            Self::OpenObjectInput => TutorialAction::OpenedObjectInput,
+            Self::LoadPreset => TutorialAction::Reset,
+            Self::Start => TutorialAction::Started,
+            Self::MoveCamera => TutorialAction::CameraMoved,
+            Self::Zoom => TutorialAction::Zoomed,
+        }
+    }
+}
+
+/// Progress through the guided tour; inactive until started.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Tutorial {
+    step: Option<TutorialStep>,
+}
+
+impl Tutorial {
+    pub fn start(&mut self) {
+        self.step = Some(TutorialStep::Welcome);
+    }
+
+    pub fn stop(&mut self) {
+        self.step = None;
+    }
+
+    pub fn step(&self) -> Option<TutorialStep> {
+        self.step
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.step.is_some()
+    }
+
+    /// Advances when `action` completes the current step; completing the last step ends
+    /// the tour. Returns whether the tour advanced.
+    pub fn notify(&mut self, action: TutorialAction) -> bool {
+        let Some(step) = self.step else {
+            return false;
+        };
+        if step.completed_by() != action {
+            return false;
+        }
+        let next = TutorialStep::ALL
+            .iter()
+            .position(|candidate| *candidate == step)
+            .and_then(|index| TutorialStep::ALL.get(index + 1));
+        self.step = next.copied();
+        true
+    }
+
+    /// Returns the current step's 1-based number and the step count.
+    pub fn progress(&self) -> Option<(usize, usize)> {
+        let step = self.step?;
+        let index = TutorialStep::ALL.iter().position(|s| *s == step)?;
+        Some((index + 1, TutorialStep::ALL.len()))
+    }
+}
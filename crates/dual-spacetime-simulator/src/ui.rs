@@ -1,40 +1,199 @@
-use crate::object_input::{ObjectInputType, ParticleBasicColor, clamp_world_scale};
+use crate::annotations::{AnnotationTarget, place_labels};
+use crate::axis_labels::{format_distance, grid_labels};
+use crate::batch_runner::{BATCH_FILTER_EXT, BATCH_FILTER_NAME, BatchJob, save_batch_csv};
+use crate::block_steps::{MAX_BLOCK_STEP_ACCURACY, MIN_BLOCK_STEP_ACCURACY};
+use crate::close_encounter::{MAX_STEPS_PER_ENCOUNTER, MIN_STEPS_PER_ENCOUNTER};
+use crate::cosmology::{Cosmology, ExpansionHistory, MIN_MATTER_DENSITY};
+use crate::crash_report::{
+    CHECKPOINT_FILE, CHECKPOINT_INTERVAL, CRASH_CONTEXT_INTERVAL, set_crash_context,
+};
+use crate::display_mode::{FullscreenKind, fullscreen_for, list_monitors};
+use crate::drag::DragModel;
+use crate::energy_monitor::{
+    ENERGY_MONITOR_MAX_PARTICLES, MAX_ENERGY_DRIFT_THRESHOLD, MIN_ENERGY_DRIFT_THRESHOLD,
+    suggested_time_per_frame,
+};
+use crate::events::{
+    EventAction, EventCondition, EventConditionKind, EventContext, EventTrigger, energy_components,
+    fire_triggers, total_energy, total_momentum,
+};
+use crate::export_writer::ExportStatus;
+use crate::fast_multipole::{DEFAULT_EXPANSION_ORDER, MAX_EXPANSION_ORDER, MIN_EXPANSION_ORDER};
+use crate::fonts::{FONT_FILTER_EXTS, FONT_FILTER_NAME, install_fonts};
+use crate::grid_alignment::{GridAlignment, grid_rotation, total_angular_momentum};
+use crate::group_finder::{
+    GroupSortKey, color_particles_by_group, friends_of_friends, mean_interparticle_separation,
+    recolor_group, sort_groups,
+};
+use crate::help::{APP_VERSION, CONTROL_SECTIONS, PROJECT_LINKS};
+use crate::kepler_binary::{MAX_STEPS_PER_ORBIT, MIN_STEPS_PER_ORBIT};
+use crate::light_cone::{light_cone_crossings, light_cone_depth};
+use crate::live_scaling::rescale_particle_count;
+use crate::maneuver::{apply_maneuver, dominant_body};
+use crate::mass_evolution::{MassRule, MassRuleKind};
+use crate::mass_profile::{LAGRANGIAN_MASS_FRACTIONS, escaping_particle_indices, mass_profile};
+use crate::metric_expr::DerivedMetricDefinition;
+use crate::metrics::{
+    BUILT_IN_METRICS, ENERGY_DRIFT, FPS, KINETIC_ENERGY, MAX_METRIC_CAPACITY, METRICS_FILTER_EXT,
+    METRICS_FILTER_NAME, MIN_METRIC_CAPACITY, MOMENTUM_SAMPLE_MAX_PARTICLES, MetricsStore,
+    POTENTIAL_ENERGY, SELECTED_RADIUS, STEP_TIME, TOTAL_ENERGY, TOTAL_MOMENTUM,
+};
+use crate::object_input::{
+    MASS_SUN, MassFunction, ObjectInputType, ParticleBasicColor, RandomClusterOptions,
+    clamp_world_scale,
+};
+use crate::orbit_preview::predict_orbit;
+use crate::orbital_elements::{gravitational_parameter, osculating_elements};
+use crate::palette::{DEFAULT_PARTICLE_PALETTE, MAX_PALETTE_COLORS};
 use crate::particle_snapshot::{ParticleSnapshot, SNAPSHOT_FILTER_EXT, SNAPSHOT_FILTER_NAME};
+use crate::phase_space::{PhaseSpacePoints, PhaseSpaceQuantity, sample_phase_space};
 use crate::pipeline::ParticleRenderPipeline;
+use crate::plots::{
+    AxisScale, PLOT_CSV_FILTER_EXT, PLOT_CSV_FILTER_NAME, PLOT_IMAGE_HEIGHT, PLOT_IMAGE_WIDTH,
+    PLOT_PNG_FILTER_EXT, PLOT_PNG_FILTER_NAME, PlotChart, PlotExportFormat, metric_label,
+    plot_bounds, render_plot_rgb,
+};
+use crate::power_spectrum::{
+    POWER_SPECTRUM_FILTER_EXT, POWER_SPECTRUM_FILTER_NAME, POWER_SPECTRUM_GRID_SIZES,
+    PowerSpectrum, power_spectrum,
+};
+use crate::region_selection::{
+    DyeInjection, RegionAction, RegionShape, apply_region_action, dye_color, region_statistics,
+};
+use crate::relativistic_view::{
+    ObserverView, ViewKinematics, coordinate_velocity, display_space_position,
+};
+use crate::rest_frame::ReferenceFrame;
+use crate::run_target::{RunLengthUnit, run_end_snapshot_file_name};
 use crate::settings::AppSettings;
-use crate::simulation::{AU, KPC, LY, MPC, PC, Particle, SimulationManager};
+use crate::simulation::{
+    AU, KPC, LIGHT_SPEED, LY, MIN_LIGHT_SPEED_FACTOR, MPC, PC, Particle, SimulationManager,
+    Summation,
+};
+use crate::speed_tuning::{MAX_TARGET_RENDER_FPS, MIN_TARGET_RENDER_FPS};
+use crate::status_bar::{STATUS_MESSAGE_DURATION, ToolMode};
+use crate::still_image::{
+    MAX_STILL_SIZE, STILL_FILTER_EXT, STILL_FILTER_NAME, STILL_RESOLUTION_PRESETS,
+    STILL_SUPERSAMPLING, save_png,
+};
+use crate::thomas_precession::thomas_precession_per_revolution;
+use crate::time_format::{
+    TimeDisplayUnit, format_simulation_time, format_time_rate, format_wall_duration,
+};
+use crate::toast::{TOAST_DURATION, ToastLevel, Toasts};
+use crate::trajectory_export::{TRAJECTORY_FILTER_EXT, TRAJECTORY_FILTER_NAME, TrajectoryRecorder};
+use crate::tree_gravity::{
+    DEFAULT_OPENING_ANGLE, DEFAULT_TREE_REBUILD_INTERVAL, ForceSolver, MAX_OPENING_ANGLE,
+    MAX_TARGET_FORCE_ERROR, MAX_TREE_REBUILD_INTERVAL, MIN_OPENING_ANGLE, MIN_TARGET_FORCE_ERROR,
+    MIN_TREE_REBUILD_INTERVAL,
+};
+use crate::tutorial::{TutorialAction, TutorialTarget};
+use crate::ui_profile::{find_ui_profile, is_builtin_ui_profile, ui_profile_names};
 use crate::ui_state::*;
 use crate::ui_styles::*;
+use crate::undo_history::{ParticleCheckpoint, UndoDirection, UndoEntry};
+use crate::verification::{
+    DeterminismAudit, MAX_DETERMINISM_PARTICLES, MAX_VERIFICATION_PARTICLES,
+    MAX_VERIFICATION_STEPS, VerificationJob,
+};
+use crate::view_fit::{fit_scale_gauge, particle_bounding_sphere};
+use crate::worldline::{MAX_MINKOWSKI_BETA, Worldline, WorldlineEvent, record_worldlines};
 use egui::{Checkbox, ComboBox, Slider};
+use std::path::Path;
 use std::sync::{Arc, RwLock};
+use std::time::Instant;
+use vulkanvil::VulkanBase;
 use winit::window::Window;
 
 const MENU_POPUP_WIDTH: f32 = 180.0;
 const PANEL_DEFAULT_X: f32 = 16.0;
 const PANEL_MENU_OFFSET_Y: f32 = 16.0;
 /// Draws the full control UI and applies user edits to shared UI/application state.
-pub fn draw_ui(
+pub(crate) fn draw_ui(
     ui_state: &Arc<RwLock<UiState>>,
     simulation_manager: &Arc<RwLock<SimulationManager>>,
     mut render_pipeline: Option<&mut ParticleRenderPipeline>,
+    gpu_particle_sync: &crate::GpuParticleSync,
     settings: &mut AppSettings,
     ctx: &egui::Context,
 ) {
     let mut uis = ui_state.write().unwrap();
+    if std::mem::take(&mut uis.fonts_changed) {
+        for error in install_fonts(ctx, &uis.ui_fonts, uis.system_cjk_font) {
+            uis.status.error(error);
+        }
+    }
+    let parameters_before = uis.parameters();
+    let was_reset_requested = uis.is_reset_requested;
     let menu_bar_height = egui::TopBottomPanel::top("menu_bar")
         .show(ctx, |ui| {
             egui::MenuBar::new().ui(ui, |ui| {
                 ui.menu_button("File", |ui| {
                     ui.set_min_width(MENU_POPUP_WIDTH);
+                    if ui
+                        .add(egui::Button::new("Open Scenario…").shortcut_text("Ctrl+O"))
+                        .clicked()
+                    {
+                        uis.pending_snapshot_dialog = Some(PendingSnapshotDialog::Load);
+                        ui.close_kind(egui::UiKind::Menu);
+                    }
+                    if ui
+                        .add(egui::Button::new("Save Scenario…").shortcut_text("Ctrl+S"))
+                        .clicked()
+                    {
+                        uis.pending_snapshot_dialog = Some(PendingSnapshotDialog::Save);
+                        ui.close_kind(egui::UiKind::Menu);
+                    }
+                    ui.separator();
+                    if ui.button("Export Trajectories…").clicked() {
+                        uis.is_trajectory_panel_open = true;
+                        ui.close_kind(egui::UiKind::Menu);
+                    }
+                    if ui
+                        .add(egui::Button::new("Screenshot…").shortcut_text("F12"))
+                        .clicked()
+                    {
+                        uis.still_render_requested = true;
+                        ui.close_kind(egui::UiKind::Menu);
+                    }
+                    if ui.button("Render Still…").clicked() {
+                        uis.is_render_still_panel_open = true;
+                        ui.close_kind(egui::UiKind::Menu);
+                    }
+                    ui.separator();
                     if ui.button("Exit").clicked() {
                         uis.request_exit = true;
                         ui.close_kind(egui::UiKind::Menu);
                     }
                 });
 
+                ui.menu_button("Edit", |ui| {
+                    ui.set_min_width(MENU_POPUP_WIDTH);
+                    let can_undo = uis.undo_history.can_undo();
+                    if ui
+                        .add_enabled(can_undo, egui::Button::new("Undo").shortcut_text("Ctrl+Z"))
+                        .clicked()
+                    {
+                        uis.pending_undo = Some(UndoDirection::Undo);
+                        ui.close_kind(egui::UiKind::Menu);
+                    }
+                    let can_redo = uis.undo_history.can_redo();
+                    if ui
+                        .add_enabled(can_redo, egui::Button::new("Redo").shortcut_text("Ctrl+Y"))
+                        .clicked()
+                    {
+                        uis.pending_undo = Some(UndoDirection::Redo);
+                        ui.close_kind(egui::UiKind::Menu);
+                    }
+                });
+
                 ui.menu_button("Panel", |ui| {
                     ui.set_min_width(MENU_POPUP_WIDTH);
-                    for panel in PANELS {
+                    let show_advanced = uis.show_advanced_controls;
+                    for panel in PANELS
+                        .iter()
+                        .filter(|panel| show_advanced || !panel.is_advanced())
+                    {
                         if ui
                             .checkbox(uis.panel_open_mut(*panel), panel.label())
                             .clicked()
@@ -49,27 +208,211 @@ pub fn draw_ui(
                     if ui.checkbox(&mut uis.show_grid, "Show Grid").clicked() {
                         ui.close_kind(egui::UiKind::Menu);
                     }
+                    if ui.button("Fit View").clicked() {
+                        uis.fit_view_requested = true;
+                        ui.close_kind(egui::UiKind::Menu);
+                    }
+                    if ui
+                        .checkbox(&mut uis.show_mass_profile_overlay, "Lagrangian Radii")
+                        .clicked()
+                    {
+                        uis.invalidate_mass_profile();
+                        ui.close_kind(egui::UiKind::Menu);
+                    }
+                    if ui
+                        .checkbox(&mut uis.show_escaper_highlight, "Highlight Escapers")
+                        .clicked()
+                    {
+                        uis.invalidate_mass_profile();
+                        ui.close_kind(egui::UiKind::Menu);
+                    }
+                    if ui.checkbox(&mut uis.show_minimap, "Minimap").clicked() {
+                        ui.close_kind(egui::UiKind::Menu);
+                    }
+                    if ui.checkbox(&mut uis.split_view, "Split View").clicked() {
+                        ui.close_kind(egui::UiKind::Menu);
+                    }
+                    if ui
+                        .checkbox(&mut uis.show_body_labels, "Body Labels")
+                        .clicked()
+                    {
+                        ui.close_kind(egui::UiKind::Menu);
+                    }
+                    if ui
+                        .checkbox(&mut uis.show_light_cone, "Past Light Cone")
+                        .clicked()
+                    {
+                        uis.light_cone_frame = None;
+                        ui.close_kind(egui::UiKind::Menu);
+                    }
+                    if ui
+                        .add_enabled(
+                            uis.rest_frame_particle_id.is_some(),
+                            egui::Button::new("Simulation Frame"),
+                        )
+                        .clicked()
+                    {
+                        uis.set_rest_frame_particle(None);
+                        ui.close_kind(egui::UiKind::Menu);
+                    }
+                    ui.separator();
+                    let mut fullscreen = uis.display_mode.fullscreen;
+                    if ui.checkbox(&mut fullscreen, "Fullscreen").clicked() {
+                        uis.toggle_fullscreen();
+                        ui.close_kind(egui::UiKind::Menu);
+                    }
+                    if ui.button("Kiosk Mode").clicked() {
+                        uis.start_kiosk();
+                        ui.close_kind(egui::UiKind::Menu);
+                    }
                 });
 
                 ui.menu_button("Simulation", |ui| {
                     ui.set_min_width(MENU_POPUP_WIDTH);
+                    let run_label = if uis.is_running { "Pause" } else { "Start" };
                     if ui
-                        .button(if uis.is_running { "Pause" } else { "Start" })
+                        .add(egui::Button::new(run_label).shortcut_text("Pause"))
                         .clicked()
                     {
                         uis.is_running = !uis.is_running;
                         ui.close_kind(egui::UiKind::Menu);
                     }
-                    if ui.button("Reset").clicked() {
+                    if ui
+                        .add(egui::Button::new("Reset").shortcut_text("Ctrl+R"))
+                        .clicked()
+                    {
                         uis.request_reset();
                         ui.close_kind(egui::UiKind::Menu);
                     }
+                    if ui.button("Soft Reset").clicked() {
+                        uis.request_soft_reset();
+                        ui.close_kind(egui::UiKind::Menu);
+                    }
+                    if ui
+                        .add_enabled(
+                            uis.can_switch_engine(),
+                            egui::Button::new("Switch Engine, Keep Particles"),
+                        )
+                        .clicked()
+                    {
+                        uis.request_engine_switch();
+                        ui.close_kind(egui::UiKind::Menu);
+                    }
+                    ui.separator();
+                    ui.menu_button("Engine", |ui| {
+                        let previous_type = uis.simulation_type;
+                        for ty in SimulationType::ALL {
+                            if ui
+                                .radio(uis.simulation_type == ty, format!("{}", ty))
+                                .clicked()
+                            {
+                                uis.simulation_type = ty;
+                                ui.close_kind(egui::UiKind::Menu);
+                            }
+                        }
+                        uis.apply_simulation_type_change(previous_type);
+                    });
+                    ui.menu_button("Computing Unit", |ui| {
+                        let previous_unit = uis.computing_unit;
+                        if ui
+                            .radio(uis.computing_unit == ComputingUnit::Cpu, "CPU")
+                            .clicked()
+                        {
+                            uis.computing_unit = ComputingUnit::Cpu;
+                            ui.close_kind(egui::UiKind::Menu);
+                        }
+                        let available = uis.gpu_computing_available();
+                        if ui
+                            .add_enabled(
+                                available,
+                                egui::RadioButton::new(
+                                    uis.computing_unit == ComputingUnit::Gpu,
+                                    "GPU",
+                                ),
+                            )
+                            .clicked()
+                        {
+                            uis.computing_unit = ComputingUnit::Gpu;
+                            ui.close_kind(egui::UiKind::Menu);
+                        }
+                        uis.apply_computing_unit_change(previous_unit);
+                    });
+                });
+
+                ui.menu_button("Profile", |ui| {
+                    ui.set_min_width(MENU_POPUP_WIDTH);
+                    let mut profiles_changed = false;
+                    for name in ui_profile_names(&uis.ui_profiles) {
+                        if ui.radio(uis.ui_profile == name, name.as_str()).clicked() {
+                            if let Some(profile) = find_ui_profile(&uis.ui_profiles, &name) {
+                                uis.apply_ui_profile(&profile);
+                            }
+                            profiles_changed = true;
+                            ui.close_kind(egui::UiKind::Menu);
+                        }
+                    }
+                    ui.separator();
+                    ui.checkbox(&mut uis.show_advanced_controls, "Advanced Controls");
+                    ui.separator();
+                    ui.text_edit_singleline(&mut uis.ui_profile_name);
+                    let name = uis.ui_profile_name.trim().to_string();
+                    if ui
+                        .add_enabled(
+                            !name.is_empty() && !is_builtin_ui_profile(&name),
+                            egui::Button::new("Save Current as Profile"),
+                        )
+                        .clicked()
+                    {
+                        let profile = uis.capture_ui_profile(&name);
+                        uis.save_ui_profile(profile);
+                        profiles_changed = true;
+                        ui.close_kind(egui::UiKind::Menu);
+                    }
+                    if ui
+                        .add_enabled(
+                            !is_builtin_ui_profile(&uis.ui_profile),
+                            egui::Button::new("Delete Profile"),
+                        )
+                        .clicked()
+                    {
+                        uis.delete_ui_profile();
+                        profiles_changed = true;
+                        ui.close_kind(egui::UiKind::Menu);
+                    }
+                    if profiles_changed {
+                        settings.ui_profile = uis.ui_profile.clone();
+                        settings.ui_profiles = uis.ui_profiles.clone();
+                        if let Err(e) = settings.save() {
+                            uis.status.error(format!("Failed to save settings: {}", e));
+                        }
+                    }
+                });
+
+                ui.menu_button("Help", |ui| {
+                    ui.set_min_width(MENU_POPUP_WIDTH);
+                    if ui.button("Tutorial").clicked() {
+                        uis.tutorial.start();
+                        ui.close_kind(egui::UiKind::Menu);
+                    }
+                    if ui
+                        .add(egui::Button::new("Controls and About").shortcut_text("F1"))
+                        .clicked()
+                    {
+                        uis.is_help_window_open = true;
+                        ui.close_kind(egui::UiKind::Menu);
+                    }
                 });
 
+                let clock = uis.readout_clock();
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    ui.label(format!("Frame {}", uis.frame));
+                    ui.label(format!("Frame {}", clock.frame));
                     ui.separator();
-                    ui.label(format!("FPS {}", uis.fps));
+                    ui.label(format!("FPS {}", clock.fps));
+                    if uis.display_space != DisplaySpace::Position {
+                        let [x, y, z] = uis.display_space.axis_labels();
+                        ui.separator();
+                        ui.label(format!("Axes {} {} {}", x, y, z));
+                    }
                 });
             });
         })
@@ -90,60 +433,133 @@ pub fn draw_ui(
         },
         |ui| {
             let particle_count = simulation_manager.read().unwrap().particle_count();
-            ui.horizontal(|ui| {
+            let clock = uis.readout_clock();
+            let fps_row = ui.horizontal(|ui| {
                 label_normal(ui, "FPS");
-                label_indicator(ui, &uis.fps.to_string());
+                label_indicator(ui, &clock.fps.to_string());
             });
             ui.horizontal(|ui| {
                 label_normal(ui, "Frame");
-                label_indicator(ui, &uis.frame.to_string());
+                label_indicator(ui, &clock.frame.to_string());
             });
             ui.horizontal(|ui| {
                 label_normal(ui, "Time");
-                label_indicator(ui, &format_simulation_time(uis.simulation_time));
+                label_indicator(
+                    ui,
+                    &format_simulation_time(
+                        clock.simulation_time,
+                        uis.time_display_unit,
+                        uis.simulation_epoch,
+                    ),
+                );
             });
             ui.horizontal(|ui| {
+                label_normal(ui, "Runtime");
+                label_indicator(ui, &format_wall_duration(clock.wall_runtime));
+            });
+            if let Some(rate) = clock.time_rate {
+                ui.horizontal(|ui| {
+                    label_normal(ui, "Sim/Wall");
+                    label_indicator(ui, &format_time_rate(rate));
+                });
+            }
+            if let Some(eta) = clock.eta {
+                ui.horizontal(|ui| {
+                    label_normal(ui, "ETA");
+                    label_indicator(ui, &format_wall_duration(eta));
+                });
+            }
+            let count_row = ui.horizontal(|ui| {
                 label_normal(ui, "Particle Count");
                 label_indicator(ui, &particle_count.to_string());
             });
+            mark_tutorial_target(
+                ui.ctx(),
+                &uis,
+                TutorialTarget::Diagnostics,
+                fps_row.response.rect.union(count_row.response.rect),
+            );
+            slider_live_particle_count(ui, &mut uis, particle_count);
+            ui.horizontal(|ui| {
+                label_normal(ui, "Precision");
+                label_indicator(ui, &uis.effective_precision().to_string());
+            });
+            if uis.show_mass_profile_overlay || uis.show_escaper_highlight {
+                mass_profile_readout(ui, &mut uis);
+            }
             ui.separator();
-            if button_normal(
+            let start = button_normal(
                 ui,
                 if uis.is_running { "Pause" } else { "Start" },
                 uis.is_running,
-            )
-            .clicked()
-            {
+            );
+            mark_tutorial_target(ui.ctx(), &uis, TutorialTarget::StartButton, start.rect);
+            if start.clicked() {
                 uis.is_running = !uis.is_running;
             }
+            run_target_controls(ui, &mut uis);
             ui.separator();
-            if button_normal(ui, "Object Input", false).clicked() {
+            let object_input = button_normal(ui, "Object Input", false);
+            mark_tutorial_target(
+                ui.ctx(),
+                &uis,
+                TutorialTarget::ObjectInputButton,
+                object_input.rect,
+            );
+            if object_input.clicked() {
                 uis.is_object_input_panel_open = !uis.is_object_input_panel_open;
             }
             ui.separator();
             dragvalue_normal(ui, &mut uis.time_per_frame, 1.0, "Time(sec)/Frame");
-            ui.separator();
             let dbl_click = primary_double_click_pos(ui);
+            force_solver_controls(ui, &mut uis, dbl_click);
+            block_step_controls(ui, &mut uis, dbl_click);
+            encounter_refinement_controls(ui, &mut uis, dbl_click);
+            binary_regularization_controls(ui, &mut uis, dbl_click);
+            ui.separator();
             ui.horizontal(|ui| {
                 label_normal(ui, "Scale");
                 label_indicator(ui, format_scale(uis.scale_gauge, uis.scale).as_str());
             });
-            let scale_slider = slider_pure(
-                ui,
-                &mut uis.scale_gauge,
-                DEFAULT_SCALE_UI * 0.2..=DEFAULT_SCALE_UI * 3.0,
+            let scale_gauge_mode = uis.scale_gauge_mode;
+            let mut scale_slider_value = scale_gauge_mode.gauge_to_slider(uis.scale_gauge);
+            let scale_slider =
+                slider_pure(ui, &mut scale_slider_value, scale_gauge_mode.slider_range());
+            mark_tutorial_target(
+                ui.ctx(),
+                &uis,
+                TutorialTarget::ScaleSlider,
+                scale_slider.rect,
             );
+            if scale_slider.changed() {
+                uis.scale_gauge = scale_gauge_mode
+                    .slider_to_gauge(scale_slider_value)
+                    .clamp(SCALE_GAUGE_MIN, SCALE_GAUGE_MAX);
+                uis.tutorial.notify(TutorialAction::Zoomed);
+            }
+            if scale_gauge_mode != ScaleGaugeMode::Legacy {
+                scale_slider_ticks(ui, &scale_slider, scale_gauge_mode, uis.scale);
+            }
             apply_slider_double_click_reset_with_pos(&scale_slider, dbl_click, || {
                 uis.reset_scale_to_base();
             });
+            if button_normal(ui, "Fit View", false).clicked() {
+                uis.fit_view_requested = true;
+            }
             ui.separator();
             ui.style_mut().spacing.slider_width = 160.0;
+            auto_speed_controls(ui, &mut uis, dbl_click);
+            ui.separator();
+            let auto_speed = uis.auto_speed;
             ui.horizontal(|ui| {
                 label_normal(ui, "Max FPS");
-                ui.checkbox(&mut uis.max_fps_unlimited, "Unlimited");
+                ui.add_enabled(
+                    !auto_speed,
+                    Checkbox::new(&mut uis.max_fps_unlimited, "Unlimited"),
+                );
             });
             let max_fps_slider = ui.add_enabled(
-                !uis.max_fps_unlimited,
+                !uis.max_fps_unlimited && !auto_speed,
                 Slider::new(&mut uis.max_fps, 1..=1000),
             );
             apply_slider_double_click_reset_with_pos(&max_fps_slider, dbl_click, || {
@@ -151,7 +567,7 @@ pub fn draw_ui(
             });
             ui.separator();
             label_normal(ui, "Skip drawing frames");
-            let skip_slider = ui.add(Slider::new(&mut uis.skip, 0..=1000));
+            let skip_slider = ui.add_enabled(!auto_speed, Slider::new(&mut uis.skip, 0..=1000));
             apply_slider_double_click_reset_with_pos(&skip_slider, dbl_click, || {
                 uis.reset_skip_to_default();
             });
@@ -171,6 +587,25 @@ pub fn draw_ui(
                     uis.show_grid = v;
                 }
             });
+            if uis.show_grid {
+                ui.horizontal(|ui| {
+                    label_normal(ui, "Grid Plane");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        combobox_compact(
+                            ui,
+                            "grid_alignment_combobox",
+                            &mut uis.grid_alignment,
+                            &GridAlignment::ALL,
+                        );
+                    });
+                });
+                ui.horizontal(|ui| {
+                    let mut v = uis.show_axis_labels;
+                    if ui.add(Checkbox::new(&mut v, "Axis Labels")).changed() {
+                        uis.show_axis_labels = v;
+                    }
+                });
+            }
             ui.separator();
             let (save, load) = button_row_pair(ui, "Save", "Load");
             if save.clicked() {
@@ -179,6 +614,8 @@ pub fn draw_ui(
             if load.clicked() {
                 uis.pending_snapshot_dialog = Some(PendingSnapshotDialog::Load);
             }
+            export_status(ui, &uis.export_writer.status());
+            memory_budget_warning(ui, &mut uis);
         },
     );
 
@@ -192,11 +629,45 @@ pub fn draw_ui(
             dragvalue_normal(ui, &mut uis.min_window_width, 1.0, "Min Window Width");
             dragvalue_normal(ui, &mut uis.min_window_height, 1.0, "Min Window Height");
             dragvalue_normal(ui, &mut uis.max_particle_count, 10.0, "Max Particle Count");
+            dragvalue_normal(ui, &mut uis.memory_budget_mb, 16.0, "Memory Budget (MiB)");
+            label_normal(
+                ui,
+                &format!(
+                    "Estimated use: {:.1} MiB",
+                    uis.memory_usage.total() as f64 / (1024.0 * 1024.0)
+                ),
+            );
             combobox_particle_display_mode(ui, &mut uis);
-            if uis.active_simulation_type() == SimulationType::DstGalaxy {
+            dragvalue_normal(ui, &mut uis.point_budget, 1000.0, "Point Budget (0 = all)");
+            dragvalue_normal(
+                ui,
+                &mut uis.motion_blur_frames,
+                1.0,
+                "Motion Blur Frames (0 = off)",
+            );
+            combobox_display_space(ui, &mut uis);
+            combobox_time_display_unit(ui, &mut uis);
+            combobox_scale_gauge_mode(ui, &mut uis);
+            if uis.show_advanced_controls
+                && uis.active_simulation_type() == SimulationType::DstGalaxy
+            {
                 ui.separator();
                 galaxy_cull_controls(ui, &mut uis);
             }
+            if uis.active_simulation_type().supports_container_walls() {
+                ui.separator();
+                container_walls_controls(ui, &mut uis);
+            }
+            if uis.active_simulation_type().supports_drag() {
+                ui.separator();
+                drag_controls(ui, &mut uis);
+                ui.separator();
+                langevin_noise_controls(ui, &mut uis);
+            }
+            if ViewKinematics::for_simulation(uis.active_simulation_type()) != ViewKinematics::Off {
+                ui.separator();
+                relativistic_view_checkbox(ui, &mut uis);
+            }
             ui.separator();
             ui.horizontal(|ui| {
                 let mut v = uis.start_maximized;
@@ -222,17 +693,66 @@ pub fn draw_ui(
                     uis.mailbox_present_mode = v;
                 }
             });
+            ui.horizontal(|ui| {
+                let mut v = uis.auto_fit_on_reset;
+                if ui.add(Checkbox::new(&mut v, "Auto Fit on Reset")).changed() {
+                    uis.auto_fit_on_reset = v;
+                }
+            });
+            ui.horizontal(|ui| {
+                let mut v = uis.start_in_kiosk_mode;
+                if ui
+                    .add(Checkbox::new(&mut v, "Start in Kiosk Mode"))
+                    .changed()
+                {
+                    uis.start_in_kiosk_mode = v;
+                }
+            });
+            ui.horizontal(|ui| {
+                let mut v = uis.gpu_frustum_culling;
+                if ui
+                    .add(Checkbox::new(&mut v, "GPU Frustum Culling"))
+                    .changed()
+                {
+                    uis.gpu_frustum_culling = v;
+                }
+            });
+            energy_warning_controls(ui, &mut uis);
+            metrics_controls(ui, &mut uis);
+            ui.separator();
+            display_mode_controls(ui, &mut uis);
+            ui.separator();
+            fonts_controls(ui, &mut uis);
             ui.separator();
             if button_normal(ui, "Save Settings", false).clicked() {
                 settings.window_min_width = uis.min_window_width;
                 settings.window_min_height = uis.min_window_height;
                 settings.max_particle_count = uis.max_particle_count;
+                settings.memory_budget_mb = uis.memory_budget_mb;
                 settings.start_maximized = uis.start_maximized;
                 settings.link_point_size_to_scale = uis.link_point_size_to_scale;
                 settings.mailbox_present_mode = uis.mailbox_present_mode;
                 settings.particle_display_mode = uis.particle_display_mode;
-                if let Err(e) = settings.save() {
-                    eprintln!("Failed to save settings: {}", e);
+                settings.time_display_unit = uis.time_display_unit;
+                settings.auto_fit_on_reset = uis.auto_fit_on_reset;
+                settings.scale_gauge_mode = uis.scale_gauge_mode;
+                settings.gpu_frustum_culling = uis.gpu_frustum_culling;
+                settings.point_budget = uis.point_budget;
+                settings.motion_blur_frames = uis.motion_blur_frames;
+                settings.ui_fonts = uis.ui_fonts.clone();
+                settings.system_cjk_font = uis.system_cjk_font;
+                settings.start_in_kiosk_mode = uis.start_in_kiosk_mode;
+                settings.display_mode = uis.display_mode.clone();
+                settings.energy_warning_enabled = uis.energy_monitor.enabled;
+                settings.energy_drift_threshold = uis.energy_monitor.threshold;
+                settings.metrics_retention = uis.metrics.retention();
+                settings.derived_metrics = uis.metrics.derived_definitions();
+                settings.palettes = uis.palettes.clone();
+                settings.ui_profile = uis.ui_profile.clone();
+                settings.ui_profiles = uis.ui_profiles.clone();
+                match settings.save() {
+                    Ok(()) => uis.status.info("Settings saved"),
+                    Err(e) => uis.status.error(format!("Failed to save settings: {}", e)),
                 }
             }
         },
@@ -246,10 +766,27 @@ pub fn draw_ui(
         |window| window,
         |ui| {
             combobox_simulation_type(ui, &mut uis);
+            if uis.can_switch_engine()
+                && button_normal(ui, "Switch Engine, Keep Particles", false).clicked()
+            {
+                uis.request_engine_switch();
+            }
             ui.separator();
             computing_unit_gpu_checkbox(ui, &mut uis);
+            if uis.show_advanced_controls {
+                combobox_force_summation(ui, &mut uis);
+                combobox_precision(ui, &mut uis);
+                combobox_force_solver(ui, &mut uis);
+                pipelined_stepping_checkbox(ui, &mut uis);
+            }
             ui.separator();
             base_scale_input(ui, &mut uis);
+            light_speed_slider(ui, &mut uis);
+            rotating_frame_input(ui, &mut uis);
+            if uis.simulation_type == SimulationType::Comoving {
+                let cosmic_time = simulation_manager.read().unwrap().cosmic_time();
+                cosmology_input(ui, &mut uis, cosmic_time);
+            }
             ui.separator();
             combobox_placement_mode(ui, &mut uis);
             placement_mode_conditions(ui, &mut uis);
@@ -286,21 +823,76 @@ pub fn draw_ui(
         uis.object_input = uis.build_reset_object_input();
         uis.reset_scale_to_base();
         uis.is_trace_enabled = false;
-        if let Some(pipeline) = render_pipeline.as_mut() {
-            pipeline.reset_camera_to_initial();
+        if uis.reset_kind == ResetKind::Hard {
+            if let Some(pipeline) = render_pipeline.as_mut() {
+                pipeline.reset_camera_to_initial();
+            }
+            uis.apply_reset_timing_defaults();
+            uis.restore_display_defaults();
         }
-        uis.apply_reset_timing_defaults();
     }
 
     if uis.reset_log.is_open {
         solar_system_reset_log_window(ctx, &mut uis);
     }
 
-    let selection = {
+    let (selection, orbit_primary) = {
         let manager = simulation_manager.read().unwrap();
-        resolve_selected_particle_live(&mut uis, &manager, render_pipeline.as_deref())
+        let selection =
+            resolve_selected_particle_live(&mut uis, &manager, render_pipeline.as_deref());
+        let orbit_primary = selection
+            .filter(|_| uis.active_simulation_type().supports_drag())
+            .and_then(|(index, _)| {
+                resolve_orbit_primary_live(&mut uis, &manager, render_pipeline.as_deref(), index)
+            });
+        (selection, orbit_primary)
     };
-    particle_info_window(ctx, &mut uis, selection);
+    region_window(
+        ctx,
+        &mut uis,
+        selection.map(|(_, particle)| particle.position),
+    );
+    phase_space_window(ctx, &mut uis);
+    minkowski_window(ctx, &mut uis, selection.map(|(_, particle)| particle.id));
+    power_spectrum_window(ctx, &mut uis);
+    plots_window(ctx, &mut uis);
+    groups_window(ctx, &mut uis);
+    trajectory_window(ctx, &mut uis);
+    events_window(ctx, &mut uis);
+    batch_window(ctx, &mut uis);
+    verification_window(ctx, &mut uis);
+    thomas_precession_window(ctx, &mut uis);
+    annotations_window(ctx, &mut uis, selection.map(|(_, particle)| particle));
+    render_still_window(ctx, &mut uis);
+    palettes_window(ctx, &mut uis);
+    help_window(ctx, &mut uis);
+    if uis.crash_report.is_some() {
+        crash_report_window(ctx, &mut uis);
+    }
+    status_bar(
+        ctx,
+        &uis,
+        render_pipeline.as_deref(),
+        selection.map(|(_, particle)| particle.id),
+    );
+    particle_info_window(ctx, &mut uis, selection, orbit_primary);
+    if (uis.show_annotations && !uis.annotations.is_empty())
+        || (uis.show_body_labels && !uis.body_labels.is_empty())
+    {
+        let manager = simulation_manager.read().unwrap();
+        annotation_overlay(ctx, &mut uis, &manager, render_pipeline.as_deref());
+    }
+    if uis.show_grid && uis.show_axis_labels {
+        axis_label_overlay(ctx, &uis, render_pipeline.as_deref());
+    }
+    if uis.tutorial.is_active() {
+        tutorial_overlay(ctx, &mut uis);
+    }
+    if uis.kiosk.is_active() {
+        kiosk_caption(ctx, &uis);
+    }
+    energy_drift_banner(ctx, &mut uis, menu_bar_height);
+    toast_overlay(ctx, &uis.toasts, menu_bar_height);
 
     if !uis.lock_camera_up {
         match uis.spacecraft_yaw_steer_anchor {
@@ -312,6 +904,23 @@ pub fn draw_ui(
             }
         }
     }
+
+    // The worker cannot start the reset while this guard is held, so the checkpoint
+    // still sees the particles it is about to replace.
+    let checkpoint = match render_pipeline.as_deref() {
+        Some(pipeline) if uis.is_reset_requested && !was_reset_requested => Some(
+            particle_checkpoint(&uis, simulation_manager, pipeline, gpu_particle_sync),
+        ),
+        _ => None,
+    };
+    let parameters_after = uis.parameters();
+    let gesture_active = ctx.input(|i| i.pointer.any_down());
+    uis.undo_history.record_frame(
+        parameters_before,
+        &parameters_after,
+        gesture_active,
+        checkpoint,
+    );
 }
 
 const RESET_LOG_MONO_SIZE: f32 = 12.0;
@@ -399,6 +1008,88 @@ pub(crate) fn resolve_selected_particle_live(
     Some((index, particle))
 }
 
+/// Resolves the live particle orbit elements of the selected particle at
+/// `selected_index` are measured around: the chosen primary, or else the dominant body.
+/// In GPU mode the dominant body is picked from the CPU copy and then read live.
+fn resolve_orbit_primary_live(
+    uis: &mut UiState,
+    simulation_manager: &SimulationManager,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+    selected_index: usize,
+) -> Option<(usize, Particle)> {
+    let chosen = uis.orbit_primary_id.and_then(|id| {
+        let index = simulation_manager.find_particle_index(id, uis.orbit_primary_index);
+        if index.is_none() {
+            uis.orbit_primary_id = None;
+        }
+        index
+    });
+    let index = match chosen {
+        Some(index) => {
+            uis.orbit_primary_index = index;
+            index
+        }
+        None => dominant_body(&simulation_manager.particles(), selected_index)?,
+    };
+    if index == selected_index {
+        return None;
+    }
+    let particle = if uis.uses_gpu_simulation() {
+        render_pipeline?.read_particle_at(index, uis.active_simulation_type(), uis.scale)?
+    } else {
+        simulation_manager.particle_at(index)?
+    };
+    Some((index, particle))
+}
+
+/// Builds this frame's observer view: aberration from the Settings toggle and the rest
+/// frame of the reference particle, blended while switching frames. Drops the reference
+/// particle once it no longer exists.
+pub(crate) fn resolve_observer_view(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+) -> ObserverView {
+    let mut uis = ui_state.write().unwrap();
+    let simulation_type = uis.active_simulation_type();
+    let kinematics = ViewKinematics::for_simulation(simulation_type);
+    let light_speed = uis.light_speed() / uis.scale;
+    let reference = match uis.rest_frame_particle_id {
+        Some(id) if kinematics != ViewKinematics::Off => {
+            let manager = simulation_manager.read().unwrap();
+            let particle = manager
+                .find_particle_index(id, uis.rest_frame_index)
+                .and_then(|index| {
+                    uis.rest_frame_index = index;
+                    if uis.uses_gpu_simulation() {
+                        render_pipeline?.read_particle_at(index, simulation_type, uis.scale)
+                    } else {
+                        manager.particle_at(index)
+                    }
+                });
+            if particle.is_none() {
+                uis.set_rest_frame_particle(None);
+            }
+            particle
+        }
+        _ => None,
+    };
+    let target = reference.map(|particle| ReferenceFrame {
+        origin: particle.position,
+        velocity: coordinate_velocity(&particle, kinematics, light_speed),
+    });
+    let reference_frame = uis
+        .frame_switcher
+        .update(target, Instant::now(), light_speed);
+    ObserverView {
+        kinematics,
+        aberration: uis.relativistic_view,
+        reference_frame,
+        display_space: uis.display_space,
+        light_speed,
+    }
+}
+
 /// Resolves the selected particle for camera trace follow.
 ///
 /// Returns the live particle, whether trace mode remains active, and the visual scale factor.
@@ -423,7 +1114,12 @@ pub(crate) fn resolve_trace_particle_for_camera(
 ///
 /// Displays live position and velocity resolved each frame from simulation state.
 /// Closing the window clears the selection so later picks always start from a clean state.
-fn particle_info_window(ctx: &egui::Context, uis: &mut UiState, selection: Option<(usize, Particle)>) {
+fn particle_info_window(
+    ctx: &egui::Context,
+    uis: &mut UiState,
+    selection: Option<(usize, Particle)>,
+    orbit_primary: Option<(usize, Particle)>,
+) {
     let Some((index, particle)) = selection else {
         return;
     };
@@ -460,6 +1156,10 @@ fn particle_info_window(ctx: &egui::Context, uis: &mut UiState, selection: Optio
                 label_normal(ui, "Index");
                 label_indicator(ui, &index.to_string());
             });
+            ui.horizontal(|ui| {
+                label_normal(ui, "ID");
+                label_indicator(ui, &particle.id.to_string());
+            });
             ui.horizontal(|ui| {
                 label_normal(ui, "Mass (kg)");
                 label_indicator(ui, &format_drag_value(mass_kg));
@@ -556,6 +1256,41 @@ fn particle_info_window(ctx: &egui::Context, uis: &mut UiState, selection: Optio
             {
                 uis.is_trace_enabled = !uis.is_trace_enabled;
             }
+            let tracked = uis.trajectory_ids.contains(&particle.id);
+            if button_normal(ui, "Track Trajectory", tracked).clicked() && !tracked {
+                uis.track_trajectory_id(particle.id);
+            }
+            let in_rest_frame = uis.rest_frame_particle_id == Some(particle.id);
+            ui.add_enabled_ui(simulation_type.is_special_relativistic(), |ui| {
+                if button_normal(
+                    ui,
+                    if in_rest_frame {
+                        "Rest Frame On"
+                    } else {
+                        "Rest Frame"
+                    },
+                    in_rest_frame,
+                )
+                .clicked()
+                {
+                    uis.set_rest_frame_particle((!in_rest_frame).then_some(particle.id));
+                }
+            });
+            ui.add_enabled_ui(simulation_type.supports_rotating_frame(), |ui| {
+                if button_normal(ui, "Co-rotate With Orbit", false).clicked() {
+                    uis.co_rotate_with_orbit(position, velocity);
+                }
+            });
+            if simulation_type.supports_drag() {
+                ui.separator();
+                orbit_element_readout(ui, uis, &particle, orbit_primary);
+                ui.separator();
+                mass_rule_input(ui, uis, particle.id);
+                if uis.placement_mode == PlacementMode::SatelliteOrbit {
+                    ui.separator();
+                    maneuver_planner(ui, uis, particle.id);
+                }
+            }
         },
     );
 
@@ -564,443 +1299,4746 @@ fn particle_info_window(ctx: &egui::Context, uis: &mut UiState, selection: Optio
     }
 }
 
-/// Draws a labeled color swatch matching the `label_indicator` row layout.
-fn draw_particle_color_swatch(ui: &mut egui::Ui, color: [f32; 4]) {
-    let color32 = egui::Color32::from_rgba_unmultiplied(
-        (color[0].clamp(0.0, 1.0) * 255.0) as u8,
-        (color[1].clamp(0.0, 1.0) * 255.0) as u8,
-        (color[2].clamp(0.0, 1.0) * 255.0) as u8,
-        (color[3].clamp(0.0, 1.0) * 255.0) as u8,
-    );
+/// Shows the osculating orbit of `particle` around `primary` and the button that makes
+/// `particle` the primary for later selections.
+fn orbit_element_readout(
+    ui: &mut egui::Ui,
+    uis: &mut UiState,
+    particle: &Particle,
+    primary: Option<(usize, Particle)>,
+) {
+    let is_primary = uis.orbit_primary_id == Some(particle.id);
+    if button_normal(
+        ui,
+        if is_primary {
+            "Orbit Primary On"
+        } else {
+            "Orbit Primary"
+        },
+        is_primary,
+    )
+    .clicked()
+    {
+        uis.orbit_primary_id = (!is_primary).then_some(particle.id);
+    }
+    let mut show_preview = uis.show_orbit_preview;
+    if ui
+        .add(Checkbox::new(&mut show_preview, "Preview Orbit"))
+        .changed()
+    {
+        uis.show_orbit_preview = show_preview;
+    }
+    let Some((primary_index, primary)) = primary else {
+        return;
+    };
+    let Some(elements) = osculating_elements(particle, &primary, uis.scale) else {
+        return;
+    };
+    let mu = gravitational_parameter(particle, &primary, uis.scale);
+    label_normal(ui, &format!("Orbit Around #{primary_index}"));
+    let rows = [
+        ("Semi-major Axis a (m)", Some(elements.semi_major_axis)),
+        ("Eccentricity e", Some(elements.eccentricity)),
+        ("Inclination i (°)", Some(elements.inclination.to_degrees())),
+        ("Period (s)", elements.period(mu)),
+        ("Periapsis (m)", Some(elements.periapsis_distance())),
+        ("Apoapsis (m)", elements.apoapsis_distance()),
+    ];
+    for (label, value) in rows {
+        ui.horizontal(|ui| {
+            label_normal(ui, label);
+            label_indicator(
+                ui,
+                &value.map_or_else(|| "Unbound".to_string(), format_particle_info_value),
+            );
+        });
+    }
+}
+
+/// Renders the mass rule of the particle with `id`: the rule kind and its parameter.
+fn mass_rule_input(ui: &mut egui::Ui, uis: &mut UiState, id: u64) {
+    let current = uis.mass_rule(id);
+    let mut rule = current;
+    let mut kind = rule.map_or(MassRuleKind::None, MassRule::kind);
     ui.horizontal(|ui| {
-        label_normal(ui, "Color");
+        label_normal(ui, "Mass Rule");
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-            let (rect, _) = ui.allocate_exact_size(egui::vec2(120.0, 16.0), egui::Sense::hover());
-            ui.painter().rect_filled(rect, 2.0, color32);
+            combobox_compact(ui, "mass_rule_combobox", &mut kind, &MassRuleKind::ALL);
         });
     });
+    if kind != rule.map_or(MassRuleKind::None, MassRule::kind) {
+        rule = kind.default_rule();
+    }
+    match &mut rule {
+        Some(MassRule::MassLoss { rate }) => {
+            dragvalue_normal(ui, rate, 1e13, "Loss Rate (kg/s)");
+            *rate = rate.max(0.0);
+        }
+        Some(MassRule::Accretion { radius }) => {
+            dragvalue_normal(ui, radius, 1e6, "Capture Radius (m)");
+            *radius = radius.max(0.0);
+        }
+        None => {}
+    }
+    if rule != current {
+        uis.set_mass_rule(id, rule);
+    }
+    if rule.is_some() && uis.uses_gpu_simulation() {
+        label_normal(ui, "Applied by the CPU engine only");
+    }
 }
 
-/// Formats simulation time into a compact signed human-readable duration string.
-fn format_simulation_time(simulation_time: f64) -> String {
-    let sign = if simulation_time < 0.0 { "-" } else { "" };
-    let total_seconds = simulation_time.abs();
-    let days = (total_seconds / 86400.0).floor() as i64;
-    let remaining_seconds = total_seconds % 86400.0;
-    let hours = (remaining_seconds / 3600.0).floor() as i64;
-    let minutes = ((remaining_seconds % 3600.0) / 60.0).floor() as i64;
-    let seconds = (remaining_seconds % 60.0).floor() as i64;
-    format!(
-        "{}{} {:02}:{:02}:{:02}",
-        sign, days, hours, minutes, seconds
-    )
+/// Renders the burn planner for the particle with `id`: the Δv components relative to its
+/// orbit around the dominant body, the delay, and the burns still waiting.
+fn maneuver_planner(ui: &mut egui::Ui, uis: &mut UiState, id: u64) {
+    label_normal(ui, "Maneuver Δv (m/s)");
+    dragvalue_normal(ui, &mut uis.maneuver_delta_v.x, 1.0, "Prograde");
+    dragvalue_normal(ui, &mut uis.maneuver_delta_v.y, 1.0, "Radial");
+    dragvalue_normal(ui, &mut uis.maneuver_delta_v.z, 1.0, "Normal");
+    dragvalue_normal(ui, &mut uis.maneuver_delay, 10.0, "Burn In (s)");
+    uis.maneuver_delay = uis.maneuver_delay.max(0.0);
+    if button_normal(ui, "Schedule Burn", false).clicked() {
+        uis.schedule_maneuver(id);
+    }
+    let now = uis.simulation_time;
+    let pending: Vec<f64> = uis
+        .maneuvers
+        .iter()
+        .filter(|maneuver| maneuver.particle_id == id)
+        .map(|maneuver| maneuver.burn_time - now)
+        .collect();
+    if pending.is_empty() {
+        return;
+    }
+    for remaining in pending {
+        ui.horizontal(|ui| {
+            label_normal(ui, "Burn In (s)");
+            label_indicator(ui, &format_particle_info_value(remaining));
+        });
+    }
+    if button_normal(ui, "Cancel Burns", false).clicked() {
+        uis.maneuvers.retain(|maneuver| maneuver.particle_id != id);
+    }
 }
 
-/// Formats current scale and gauge ratio for display in the simulation panel.
-fn format_scale(scale_guage: f64, scale: f64) -> String {
-    let scale_inv = DEFAULT_SCALE_UI / scale_guage;
-    let pow10 = scale_inv.powi(4) * scale;
-    if pow10 >= MPC {
-        format!("{:.3e} Mpc", pow10 / MPC)
-    } else if pow10 >= KPC {
-        format!("{:.3e} kpc", pow10 / KPC)
-    } else if pow10 >= PC {
-        format!("{:.3e} pc", pow10 / PC)
-    } else if pow10 >= LY {
-        format!("{:.3e} ly", pow10 / LY)
-    } else if pow10 >= AU {
-        format!("{:.3} au", pow10 / AU)
-    } else if pow10 >= 1e9 {
-        format!("{:.3e} km", pow10 / 1e3)
-    } else if pow10 >= 1e3 {
-        format!("{:.3} km", pow10 / 1e3)
-    } else if pow10 < 1e-15 {
-        format!("{:.6} fm", pow10 * 1e15)
-    } else if pow10 < 1e-9 {
-        format!("{:.6} nm", pow10 * 1e9)
-    } else if pow10 < 1e-3 {
-        format!("{:.6} mm", pow10 * 1e3)
-    } else {
-        format!("{:.3} m", pow10)
+/// Shows the center of mass, Lagrangian radii, and escaper count with the update interval.
+fn mass_profile_readout(ui: &mut egui::Ui, uis: &mut UiState) {
+    ui.separator();
+    dragvalue_normal(
+        ui,
+        &mut uis.mass_profile_interval,
+        1.0,
+        "Update Every (frames)",
+    );
+    uis.mass_profile_interval = uis.mass_profile_interval.max(1);
+    if uis.show_escaper_highlight {
+        ui.horizontal(|ui| {
+            label_normal(ui, "Escapers");
+            label_indicator(ui, &uis.escaper_positions.len().to_string());
+        });
+    }
+    let Some(profile) = uis.mass_profile.filter(|_| uis.show_mass_profile_overlay) else {
+        return;
+    };
+    label_normal(ui, "Center of Mass (Base Scale Units)");
+    for (axis, value) in [
+        ("X", profile.center_of_mass.x),
+        ("Y", profile.center_of_mass.y),
+        ("Z", profile.center_of_mass.z),
+    ] {
+        ui.horizontal(|ui| {
+            label_normal(ui, axis);
+            label_indicator(ui, &format_particle_info_value(value));
+        });
+    }
+    for (fraction, radius) in LAGRANGIAN_MASS_FRACTIONS
+        .iter()
+        .zip(profile.lagrangian_radii)
+    {
+        ui.horizontal(|ui| {
+            label_normal(ui, &format!("r{:.0}%", fraction * 100.0));
+            label_indicator(ui, &format_particle_info_value(radius));
+        });
     }
 }
 
-/// Renders base-scale value input with selectable length units.
-fn base_scale_input(ui: &mut egui::Ui, uis: &mut UiState) {
-    label_normal(ui, "Base Scale");
-    let previous_unit = uis.base_scale_unit;
-    let unit = uis.base_scale_unit;
-    let mut display = uis.base_scale_display_value();
-    let format_display = |value: f64| unit.format_display(value);
-    ui.horizontal(|ui| {
-        dragvalue_positive_f64(
-            ui,
-            &mut display,
-            BASE_SCALE_DRAG_SPEED,
-            unit.min_display_value(),
-            110.0,
-            None,
-            Some(&format_display),
-        );
-        display = unit.sanitize_display(display);
-        let id = ui.make_persistent_id("base_scale_unit_combobox");
-        ComboBox::from_id_salt(id)
-            .selected_text(format!("{}", uis.base_scale_unit))
-            .width(60.0)
-            .show_ui(ui, |ui| {
-                for unit in BaseScaleUnit::ALL {
-                    selectable_value(ui, &mut uis.base_scale_unit, unit);
+/// Renders the region-of-interest panel: numeric region input, statistics, and actions.
+fn region_window(ctx: &egui::Context, uis: &mut UiState, selected_position: Option<glam::DVec3>) {
+    let velocity_label = match uis.active_simulation_type() {
+        SimulationType::LorentzTransformation => "Rapidity",
+        _ => "Velocity (Base Scale Units/s)",
+    };
+    let mass_unit = uis.base_scale.powi(3);
+    uis.is_region_panel_open = show_fixed_width_closable_window(
+        ctx,
+        "Region",
+        uis.is_region_panel_open,
+        INPUT_PANEL_WIDTH,
+        |window| window,
+        |ui| {
+            combobox_region_shape(ui, uis);
+            label_normal(ui, "Center (Base Scale Units)");
+            dragvalue_normal(ui, &mut uis.region.center.x, 0.01, "X");
+            dragvalue_normal(ui, &mut uis.region.center.y, 0.01, "Y");
+            dragvalue_normal(ui, &mut uis.region.center.z, 0.01, "Z");
+            match uis.region.shape {
+                RegionShape::Sphere => {
+                    dragvalue_normal(ui, &mut uis.region.radius, 0.01, "Radius");
+                    uis.region.radius = uis.region.radius.max(0.0);
+                }
+                RegionShape::Box => {
+                    label_normal(ui, "Half Size");
+                    dragvalue_normal(ui, &mut uis.region.half_size.x, 0.01, "X");
+                    dragvalue_normal(ui, &mut uis.region.half_size.y, 0.01, "Y");
+                    dragvalue_normal(ui, &mut uis.region.half_size.z, 0.01, "Z");
+                    uis.region.half_size = uis.region.half_size.max(glam::DVec3::ZERO);
+                }
+            }
+            ui.add_enabled_ui(selected_position.is_some(), |ui| {
+                if button_normal(ui, "Center on Selected", false).clicked()
+                    && let Some(position) = selected_position
+                {
+                    uis.region.center = position;
                 }
             });
-    });
-    uis.apply_base_scale_edit(display, uis.base_scale_unit != previous_unit);
-}
-
-/// Renders the DST Galaxy S³-angle culling controls (enable toggle + angle threshold).
-fn galaxy_cull_controls(ui: &mut egui::Ui, uis: &mut UiState) {
-    ui.horizontal(|ui| {
-        let mut v = uis.galaxy_cull_enabled;
-        if ui
-            .add(Checkbox::new(&mut v, "Cull Beyond S³ Angle"))
-            .changed()
-        {
-            uis.galaxy_cull_enabled = v;
-        }
-    });
-    // Radial ratio |p|/R = 2α/π gives an intuitive sense of the cutoff distance.
-    let format_angle =
-        |a: f64| format!("{:.3} rad (|p|/R={:.2})", a, 2.0 * a / std::f64::consts::PI);
-    let mut angle = uis.galaxy_cull_max_angle;
-    dragvalue_positive_f64(
-        ui,
-        &mut angle,
-        0.01,
-        0.01,
-        110.0,
-        Some("Cull Angle"),
-        Some(&format_angle),
+            ui.horizontal(|ui| {
+                let mut v = uis.show_region_outline;
+                if ui.add(Checkbox::new(&mut v, "Show Outline")).changed() {
+                    uis.show_region_outline = v;
+                }
+            });
+            ui.separator();
+            if button_normal(ui, "Measure", false).clicked() {
+                uis.pending_region_action = Some(RegionAction::Measure);
+            }
+            if let Some(stats) = uis.region_statistics {
+                ui.horizontal(|ui| {
+                    label_normal(ui, "Count");
+                    label_indicator(ui, &stats.count.to_string());
+                });
+                ui.horizontal(|ui| {
+                    label_normal(ui, "Total Mass (kg)");
+                    label_indicator(ui, &format_drag_value(stats.total_mass * mass_unit));
+                });
+                label_normal(ui, velocity_label);
+                for (axis, value) in [
+                    ("Mean X", stats.mean_velocity.x),
+                    ("Mean Y", stats.mean_velocity.y),
+                    ("Mean Z", stats.mean_velocity.z),
+                    ("Dispersion σ", stats.velocity_dispersion),
+                ] {
+                    ui.horizontal(|ui| {
+                        label_normal(ui, axis);
+                        label_indicator(ui, &format_particle_info_value(value));
+                    });
+                }
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                label_normal(ui, "Recolor");
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.color_edit_button_rgb(&mut uis.region_color);
+                });
+            });
+            let (recolor, isolate) = button_row_pair(ui, "Recolor", "Isolate");
+            if recolor.clicked() {
+                let [r, g, b] = uis.region_color;
+                uis.pending_region_action = Some(RegionAction::Recolor([r, g, b, 1.0]));
+            }
+            if isolate.clicked() {
+                uis.pending_region_action = Some(RegionAction::Isolate);
+            }
+            ui.separator();
+            if button_normal(ui, "Inject Dye", false).clicked() {
+                let color = dye_color(uis.dye_injections.len());
+                uis.pending_region_action = Some(RegionAction::Dye(color));
+            }
+            for (number, dye) in uis.dye_injections.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    let (rect, _) =
+                        ui.allocate_exact_size(egui::vec2(12.0, 12.0), egui::Sense::hover());
+                    ui.painter()
+                        .rect_filled(rect, 2.0, color32_from_rgba(dye.color));
+                    label_normal(ui, &format!("Dye {} @ frame {}", number + 1, dye.frame));
+                    label_indicator(ui, &dye.count.to_string());
+                });
+            }
+        },
     );
-    uis.galaxy_cull_max_angle = angle.min(std::f64::consts::PI);
 }
 
-/// Renders the simulation-type combo box and updates dependent UI state.
-fn combobox_simulation_type(ui: &mut egui::Ui, uis: &mut UiState) {
-    label_normal(ui, "Simulation Type");
-    let previous_type = uis.simulation_type;
-    let id = ui.make_persistent_id("simulation_type_combobox");
-    ComboBox::from_id_salt(id)
-        .selected_text(format!("{}", uis.simulation_type))
-        .width(ui.available_width())
-        .show_ui(ui, |ui| {
-            for ty in SimulationType::ALL {
-                selectable_value(ui, &mut uis.simulation_type, ty);
+const PHASE_SPACE_PANEL_WIDTH: f32 = 320.0;
+const PHASE_SPACE_PLOT_HEIGHT: f32 = 240.0;
+const PHASE_SPACE_POINT_RADIUS: f32 = 1.5;
+const PHASE_SPACE_SELECTED_RADIUS: f32 = 4.0;
+/// Maximum screen distance (px) from a point for a click to select its particle.
+const PHASE_SPACE_PICK_RADIUS: f32 = 6.0;
+
+/// Renders the phase-space scatter panel; clicking a point selects its particle.
+fn phase_space_window(ctx: &egui::Context, uis: &mut UiState) {
+    uis.is_phase_space_panel_open = show_fixed_width_closable_window(
+        ctx,
+        "Phase Space",
+        uis.is_phase_space_panel_open,
+        PHASE_SPACE_PANEL_WIDTH,
+        |window| window,
+        |ui| {
+            let (x, y) = (uis.phase_space_x, uis.phase_space_y);
+            combobox_phase_space_quantity(ui, "X Axis", &mut uis.phase_space_x);
+            combobox_phase_space_quantity(ui, "Y Axis", &mut uis.phase_space_y);
+            let mut max_points = uis.phase_space_max_points;
+            ui.horizontal(|ui| {
+                label_normal(ui, "Max Points");
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.add(egui::DragValue::new(&mut max_points).range(1..=100_000));
+                });
+            });
+            if (x, y) != (uis.phase_space_x, uis.phase_space_y)
+                || max_points != uis.phase_space_max_points
+            {
+                uis.phase_space_max_points = max_points;
+                uis.phase_space_frame = None;
             }
-        });
-    uis.apply_simulation_type_change(previous_type);
+            let selected = uis.selected_particle.map(|s| s.index);
+            if let Some(index) = draw_phase_space_plot(ui, &uis.phase_space, selected) {
+                uis.select_particle(index);
+            }
+            ui.horizontal(|ui| {
+                label_normal(ui, "Points");
+                label_indicator(ui, &uis.phase_space.points.len().to_string());
+            });
+        },
+    );
 }
 
-/// Renders the computing-unit GPU checkbox and updates dependent UI state.
-fn computing_unit_gpu_checkbox(ui: &mut egui::Ui, uis: &mut UiState) {
-    let previous_unit = uis.computing_unit;
+/// Renders a phase-space quantity combo box with a leading label.
+fn combobox_phase_space_quantity(
+    ui: &mut egui::Ui,
+    label: &str,
+    quantity: &mut PhaseSpaceQuantity,
+) {
     ui.horizontal(|ui| {
-        label_normal(ui, "Computing Unit");
-        let mut gpu_enabled = uis.computing_unit == ComputingUnit::Gpu;
-        if ui.checkbox(&mut gpu_enabled, "GPU").changed() {
-            uis.computing_unit = if gpu_enabled {
-                ComputingUnit::Gpu
-            } else {
-                ComputingUnit::Cpu
-            };
-        }
+        label_normal(ui, label);
+        let id = ui.make_persistent_id(("phase_space_quantity_combobox", label));
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ComboBox::from_id_salt(id)
+                .selected_text(format!("{}", quantity))
+                .width(90.0)
+                .show_ui(ui, |ui| {
+                    for option in PhaseSpaceQuantity::ALL {
+                        selectable_value(ui, quantity, option);
+                    }
+                });
+        });
     });
-    uis.apply_computing_unit_change(previous_unit);
 }
 
-/// Renders the placement-mode combo box and updates dependent UI state.
-fn combobox_placement_mode(ui: &mut egui::Ui, uis: &mut UiState) {
-    label_normal(ui, "Placement Mode");
-    let previous_mode = uis.placement_mode;
-    let id = ui.make_persistent_id("placement_mode_combobox");
-    ComboBox::from_id_salt(id)
-        .selected_text(format!("{}", uis.placement_mode))
-        .width(ui.available_width())
-        .show_ui(ui, |ui| {
-            for mode in PlacementMode::ALL {
-                selectable_value(ui, &mut uis.placement_mode, mode);
-            }
-        });
-    uis.apply_placement_mode_change(previous_mode);
+/// Paints the scatter plot with min/max axis labels. Returns the particle index of the
+/// point nearest a click, if any lies within [`PHASE_SPACE_PICK_RADIUS`].
+fn draw_phase_space_plot(
+    ui: &mut egui::Ui,
+    sample: &PhaseSpacePoints,
+    selected: Option<usize>,
+) -> Option<usize> {
+    let size = egui::vec2(ui.available_width(), PHASE_SPACE_PLOT_HEIGHT);
+    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click());
+    let painter = ui.painter_at(rect);
+    let visuals = ui.visuals();
+    painter.rect_filled(rect, 2.0, visuals.extreme_bg_color);
+    painter.rect_stroke(
+        rect,
+        2.0,
+        visuals.widgets.noninteractive.bg_stroke,
+        egui::StrokeKind::Inside,
+    );
+    let [[x_min, x_max], [y_min, y_max]] = sample.bounds()?;
+    let plot = rect.shrink(8.0);
+    let span = |min: f64, max: f64| if max > min { max - min } else { 1.0 };
+    let to_screen = |p: [f64; 2]| {
+        egui::pos2(
+            plot.left() + ((p[0] - x_min) / span(x_min, x_max)) as f32 * plot.width(),
+            plot.bottom() - ((p[1] - y_min) / span(y_min, y_max)) as f32 * plot.height(),
+        )
+    };
+    let point_color = visuals.text_color();
+    let mut selected_point = None;
+    for (&point, &index) in sample.points.iter().zip(&sample.indices) {
+        let position = to_screen(point);
+        if Some(index) == selected {
+            selected_point = Some(position);
+        }
+        painter.circle_filled(position, PHASE_SPACE_POINT_RADIUS, point_color);
+    }
+    if let Some(position) = selected_point {
+        painter.circle_filled(position, PHASE_SPACE_SELECTED_RADIUS, egui::Color32::YELLOW);
+    }
+    let font = egui::FontId::monospace(10.0);
+    let label_color = visuals.weak_text_color();
+    for (anchor, align, value) in [
+        (rect.left_bottom(), egui::Align2::LEFT_BOTTOM, x_min),
+        (rect.right_bottom(), egui::Align2::RIGHT_BOTTOM, x_max),
+        (rect.left_top(), egui::Align2::LEFT_TOP, y_max),
+        (
+            rect.left_bottom() - egui::vec2(0.0, 12.0),
+            egui::Align2::LEFT_BOTTOM,
+            y_min,
+        ),
+    ] {
+        painter.text(
+            anchor,
+            align,
+            format_particle_info_value(value),
+            font.clone(),
+            label_color,
+        );
+    }
+    let click = response
+        .interact_pointer_pos()
+        .filter(|_| response.clicked())?;
+    sample
+        .points
+        .iter()
+        .zip(&sample.indices)
+        .map(|(&point, &index)| (to_screen(point).distance(click), index))
+        .filter(|&(distance, _)| distance <= PHASE_SPACE_PICK_RADIUS)
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, index)| index)
 }
 
-/// Renders the object-input type combo box and syncs scaled parameters on change.
-fn combobox_object_input_type(ui: &mut egui::Ui, uis: &mut UiState) {
-    label_normal(ui, "Object Input Type");
-    let previous_type = uis.object_input_type;
-    let id = ui.make_persistent_id("object_input_type_combobox");
-    ComboBox::from_id_salt(id)
-        .selected_text(format!("{}", uis.object_input_type))
-        .width(ui.available_width())
-        .show_ui(ui, |ui| {
-            for ty in ObjectInputType::ALL {
-                selectable_value(ui, &mut uis.object_input_type, ty);
-            }
-        });
-    uis.apply_object_input_type_change(previous_type);
-}
+const MINKOWSKI_PLOT_HEIGHT: f32 = 260.0;
+const MINKOWSKI_EVENT_RADIUS: f32 = 2.5;
 
-/// Renders parameter controls for the active placement mode.
-fn placement_mode_conditions(ui: &mut egui::Ui, uis: &mut UiState) {
-    match uis.placement_mode {
-        PlacementMode::SolarSystem => condition_solar_system(ui, uis),
-        PlacementMode::SatelliteOrbit => condition_satellite_orbit(ui, uis),
-        PlacementMode::Manual => {}
-    }
+/// Renders the Minkowski-diagram panel: worldline recording controls, the (x, ct) diagram,
+/// and elapsed coordinate and proper time per tracked particle.
+fn minkowski_window(ctx: &egui::Context, uis: &mut UiState, selected_id: Option<u64>) {
+    uis.is_minkowski_panel_open = show_fixed_width_closable_window(
+        ctx,
+        "Minkowski",
+        uis.is_minkowski_panel_open,
+        PHASE_SPACE_PANEL_WIDTH,
+        |window| window,
+        |ui| {
+            if !uis.active_simulation_type().is_special_relativistic() {
+                label_normal(ui, "Worldlines are recorded in the Special engines only");
+            }
+            ui.horizontal(|ui| {
+                label_normal(ui, "Tracked IDs");
+                label_indicator(ui, &uis.trajectory_ids.len().to_string());
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    let selected_id = selected_id.filter(|&id| id != 0);
+                    if ui
+                        .add_enabled(selected_id.is_some(), egui::Button::new("Track Selected"))
+                        .clicked()
+                        && let Some(id) = selected_id
+                    {
+                        uis.track_trajectory_id(id);
+                    }
+                });
+            });
+            let record_label = if uis.worldline_recording {
+                "Stop Recording"
+            } else {
+                "Record"
+            };
+            let (record, clear) = button_row_pair(ui, record_label, "Clear");
+            if record.clicked() {
+                uis.worldline_recording = !uis.worldline_recording;
+            }
+            if clear.clicked() {
+                uis.clear_worldlines();
+            }
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut uis.show_minkowski_boosted_axes, "Boosted Axes β");
+                ui.add_enabled(
+                    uis.show_minkowski_boosted_axes,
+                    Slider::new(
+                        &mut uis.minkowski_beta,
+                        -MAX_MINKOWSKI_BETA..=MAX_MINKOWSKI_BETA,
+                    ),
+                );
+            });
+            let beta = uis
+                .show_minkowski_boosted_axes
+                .then_some(uis.minkowski_beta);
+            draw_minkowski_diagram(ui, &uis.worldlines, uis.light_speed() / uis.scale, beta);
+            for worldline in &uis.worldlines {
+                ui.horizontal(|ui| {
+                    label_normal(ui, &format!("ID {}", worldline.id));
+                    label_indicator(
+                        ui,
+                        &format!(
+                            "Δt {}  Δτ {}",
+                            format_particle_info_value(worldline.elapsed_time()),
+                            format_particle_info_value(worldline.elapsed_proper_time())
+                        ),
+                    );
+                });
+            }
+        },
+    );
 }
 
-/// Renders parameter controls for the active object-input type.
-fn object_input_type_conditions(ui: &mut egui::Ui, uis: &mut UiState) {
-    match uis.object_input_type {
-        ObjectInputType::RandomSphere => condition_random_sphere(ui, uis),
-        ObjectInputType::RandomCube => condition_random_cube(ui, uis),
-        ObjectInputType::SpiralDisk => condition_spiral_disk(ui, uis),
-        ObjectInputType::EllipticalOrbit => condition_elliptical_orbit(ui, uis),
-        ObjectInputType::SingleParticle => condition_single_particle(ui, uis),
+/// Paints worldlines in the (x, ct) plane at equal scale on both axes, so light travels
+/// at 45°. Light-cone guides and, with `beta`, the boosted frame's x' and ct' axes are
+/// drawn through the first recorded event.
+fn draw_minkowski_diagram(
+    ui: &mut egui::Ui,
+    worldlines: &[Worldline],
+    light_speed: f64,
+    beta: Option<f64>,
+) {
+    let size = egui::vec2(ui.available_width(), MINKOWSKI_PLOT_HEIGHT);
+    let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let visuals = ui.visuals();
+    painter.rect_filled(rect, 2.0, visuals.extreme_bg_color);
+    painter.rect_stroke(
+        rect,
+        2.0,
+        visuals.widgets.noninteractive.bg_stroke,
+        egui::StrokeKind::Inside,
+    );
+    let Some(origin) = worldlines.iter().find_map(|w| w.events.first()).copied() else {
+        return;
+    };
+    let diagram = |event: &WorldlineEvent| [event.x - origin.x, (event.t - origin.t) * light_speed];
+    let (mut min, mut max) = ([0.0f64; 2], [0.0f64; 2]);
+    for point in worldlines.iter().flat_map(|w| &w.events).map(diagram) {
+        for axis in 0..2 {
+            min[axis] = min[axis].min(point[axis]);
+            max[axis] = max[axis].max(point[axis]);
+        }
     }
-}
-
-/// Renders parameter controls for the random-sphere object input.
-fn condition_random_sphere(ui: &mut egui::Ui, uis: &mut UiState) {
-    dragvalue_normal(ui, &mut uis.random_sphere.radius, 1e9, "Sphere Radius (m)");
-    dragvalue_normal(
-        ui,
-        &mut uis.random_sphere.mass_range.0,
-        1e20,
-        "Mass Min (kg)",
+    let plot = rect.shrink(8.0);
+    let span = |axis: usize| (max[axis] - min[axis]).max(f64::MIN_POSITIVE);
+    let scale = (plot.width() as f64 / span(0)).min(plot.height() as f64 / span(1));
+    if !scale.is_finite() {
+        return;
+    }
+    let center = [0.5 * (min[0] + max[0]), 0.5 * (min[1] + max[1])];
+    let to_screen = |p: [f64; 2]| {
+        plot.center()
+            + egui::vec2(
+                ((p[0] - center[0]) * scale) as f32,
+                (-(p[1] - center[1]) * scale) as f32,
+            )
+    };
+    let origin_screen = to_screen([0.0, 0.0]);
+    let reach = rect.size().length();
+    let guide = |direction: egui::Vec2, stroke: egui::Stroke| {
+        let direction = direction.normalized() * reach;
+        painter.line_segment(
+            [origin_screen - direction, origin_screen + direction],
+            stroke,
+        );
+    };
+    let cone_stroke = egui::Stroke::new(1.0, visuals.weak_text_color());
+    guide(egui::vec2(1.0, -1.0), cone_stroke);
+    guide(egui::vec2(1.0, 1.0), cone_stroke);
+    if let Some(beta) = beta {
+        let axis_stroke = egui::Stroke::new(1.0, egui::Color32::LIGHT_BLUE);
+        guide(egui::vec2(beta as f32, -1.0), axis_stroke);
+        guide(egui::vec2(1.0, -(beta as f32)), axis_stroke);
+    }
+    for worldline in worldlines {
+        let [r, g, b, _] = worldline.color.map(|c| (c.clamp(0.0, 1.0) * 255.0) as u8);
+        let color = egui::Color32::from_rgb(r, g, b);
+        let points: Vec<egui::Pos2> = worldline
+            .events
+            .iter()
+            .map(|event| to_screen(diagram(event)))
+            .collect();
+        if let Some(&last) = points.last() {
+            painter.circle_filled(last, MINKOWSKI_EVENT_RADIUS, color);
+        }
+        painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, color)));
+    }
+    let font = egui::FontId::monospace(10.0);
+    let label_color = visuals.weak_text_color();
+    painter.text(
+        rect.right_bottom(),
+        egui::Align2::RIGHT_BOTTOM,
+        format!("x {}", format_particle_info_value(max[0] - min[0])),
+        font.clone(),
+        label_color,
     );
-    dragvalue_normal(
-        ui,
-        &mut uis.random_sphere.mass_range.1,
-        1e20,
-        "Mass Max (kg)",
+    painter.text(
+        rect.left_top(),
+        egui::Align2::LEFT_TOP,
+        format!("ct {}", format_particle_info_value(max[1] - min[1])),
+        font,
+        label_color,
     );
-    dragvalue_normal(
-        ui,
-        &mut uis.random_sphere.velocity_std,
-        1e3,
-        "Velocity Std (m/s)",
+}
+
+/// Renders the power-spectrum panel: grid choice, on-demand computation, plot, and export.
+fn power_spectrum_window(ctx: &egui::Context, uis: &mut UiState) {
+    uis.is_power_spectrum_panel_open = show_fixed_width_closable_window(
+        ctx,
+        "Power Spectrum",
+        uis.is_power_spectrum_panel_open,
+        PHASE_SPACE_PANEL_WIDTH,
+        |window| window,
+        |ui| {
+            ui.horizontal(|ui| {
+                label_normal(ui, "Grid");
+                let id = ui.make_persistent_id("power_spectrum_grid_combobox");
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ComboBox::from_id_salt(id)
+                        .selected_text(format!("{}³", uis.power_spectrum_grid))
+                        .width(90.0)
+                        .show_ui(ui, |ui| {
+                            for grid in POWER_SPECTRUM_GRID_SIZES {
+                                ui.selectable_value(
+                                    &mut uis.power_spectrum_grid,
+                                    grid,
+                                    format!("{}³", grid),
+                                );
+                            }
+                        });
+                });
+            });
+            let (compute, export) = button_row_pair(ui, "Compute", "Export CSV");
+            if compute.clicked() {
+                uis.power_spectrum_requested = true;
+            }
+            if export.clicked() && uis.power_spectrum.is_some() {
+                uis.power_spectrum_export_requested = true;
+            }
+            if let Some(spectrum) = &uis.power_spectrum {
+                draw_power_spectrum_plot(ui, spectrum);
+                ui.horizontal(|ui| {
+                    label_normal(ui, "Frame");
+                    label_indicator(ui, &uis.power_spectrum_frame.to_string());
+                });
+                ui.horizontal(|ui| {
+                    label_normal(ui, "Box Size");
+                    label_indicator(ui, &format_particle_info_value(spectrum.box_size));
+                });
+            }
+        },
     );
-    uis.clamp_velocity_inputs();
 }
 
-/// Renders parameter controls for the random-cube object input.
-fn condition_random_cube(ui: &mut egui::Ui, uis: &mut UiState) {
-    dragvalue_normal(ui, &mut uis.random_cube.cube_size, 1e3, "Cube Size (m)");
-    dragvalue_normal(ui, &mut uis.random_cube.mass_range.0, 1e20, "Mass Min (kg)");
-    dragvalue_normal(ui, &mut uis.random_cube.mass_range.1, 1e20, "Mass Max (kg)");
-    dragvalue_normal(
-        ui,
-        &mut uis.random_cube.velocity_std,
-        1e3,
-        "Velocity Std (m/s)",
+/// Paints P(k) on log-log axes with the k and P ranges as corner labels.
+fn draw_power_spectrum_plot(ui: &mut egui::Ui, spectrum: &PowerSpectrum) {
+    let size = egui::vec2(ui.available_width(), PHASE_SPACE_PLOT_HEIGHT);
+    let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let visuals = ui.visuals();
+    painter.rect_filled(rect, 2.0, visuals.extreme_bg_color);
+    painter.rect_stroke(
+        rect,
+        2.0,
+        visuals.widgets.noninteractive.bg_stroke,
+        egui::StrokeKind::Inside,
     );
-    uis.clamp_velocity_inputs();
+    let points: Vec<[f64; 2]> = spectrum
+        .bins
+        .iter()
+        .filter(|bin| bin.k > 0.0 && bin.power > 0.0)
+        .map(|bin| [bin.k.log10(), bin.power.log10()])
+        .collect();
+    let sample = PhaseSpacePoints {
+        indices: (0..points.len()).collect(),
+        points,
+    };
+    let Some([[x_min, x_max], [y_min, y_max]]) = sample.bounds() else {
+        return;
+    };
+    let plot = rect.shrink(8.0);
+    let span = |min: f64, max: f64| if max > min { max - min } else { 1.0 };
+    let line: Vec<egui::Pos2> = sample
+        .points
+        .iter()
+        .map(|p| {
+            egui::pos2(
+                plot.left() + ((p[0] - x_min) / span(x_min, x_max)) as f32 * plot.width(),
+                plot.bottom() - ((p[1] - y_min) / span(y_min, y_max)) as f32 * plot.height(),
+            )
+        })
+        .collect();
+    let stroke = egui::Stroke::new(1.5, visuals.text_color());
+    for &position in &line {
+        painter.circle_filled(position, PHASE_SPACE_POINT_RADIUS, stroke.color);
+    }
+    painter.line(line, stroke);
+    let font = egui::FontId::monospace(10.0);
+    let label_color = visuals.weak_text_color();
+    for (anchor, align, text) in [
+        (
+            rect.left_bottom(),
+            egui::Align2::LEFT_BOTTOM,
+            format!("k {}", format_particle_info_value(10f64.powf(x_min))),
+        ),
+        (
+            rect.right_bottom(),
+            egui::Align2::RIGHT_BOTTOM,
+            format_particle_info_value(10f64.powf(x_max)),
+        ),
+        (
+            rect.left_top(),
+            egui::Align2::LEFT_TOP,
+            format!("P {}", format_particle_info_value(10f64.powf(y_max))),
+        ),
+        (
+            rect.left_bottom() - egui::vec2(0.0, 12.0),
+            egui::Align2::LEFT_BOTTOM,
+            format!("P {}", format_particle_info_value(10f64.powf(y_min))),
+        ),
+    ] {
+        painter.text(anchor, align, text, font.clone(), label_color);
+    }
 }
 
-/// Renders parameter controls for the spiral-disk object input.
-fn condition_spiral_disk(ui: &mut egui::Ui, uis: &mut UiState) {
-    dragvalue_normal(ui, &mut uis.spiral_disk.disk_radius, 1e7, "Disk Radius (m)");
-    dragvalue_normal(ui, &mut uis.spiral_disk.mass_fixed, 1e20, "Mass Fixed (kg)");
+const PLOT_CHART_HEIGHT: f32 = 160.0;
+const PLOT_CHARTS_MAX_HEIGHT: f32 = 640.0;
+
+/// Renders the Plots window: charts of metrics from the time-series store, each with its
+/// metric, axis scales, and PNG/CSV export.
+fn plots_window(ctx: &egui::Context, uis: &mut UiState) {
+    uis.is_plots_panel_open = show_fixed_width_closable_window(
+        ctx,
+        "Plots",
+        uis.is_plots_panel_open,
+        PHASE_SPACE_PANEL_WIDTH,
+        |window| window,
+        |ui| {
+            derived_metrics_controls(ui, uis);
+            ui.separator();
+            if button_normal(ui, "Add Chart", false).clicked() {
+                uis.plot_charts.push(PlotChart::new(TOTAL_ENERGY));
+            }
+            let mut removed = None;
+            egui::ScrollArea::vertical()
+                .max_height(PLOT_CHARTS_MAX_HEIGHT)
+                .show(ui, |ui| {
+                    for (slot, chart) in uis.plot_charts.iter_mut().enumerate() {
+                        ui.separator();
+                        ui.push_id(slot, |ui| {
+                            let (delete, export) = plot_chart_editor(ui, chart, &uis.metrics);
+                            if delete {
+                                removed = Some(slot);
+                            }
+                            if let Some(format) = export {
+                                uis.plot_export_request = Some((slot, format));
+                            }
+                        });
+                    }
+                });
+            if let Some(slot) = removed {
+                uis.plot_charts.remove(slot);
+            }
+        },
+    );
 }
 
-/// Renders start-date controls for the solar-system object input.
-fn condition_solar_system(ui: &mut egui::Ui, uis: &mut UiState) {
-    dragvalue_normal(ui, &mut uis.solar_system.start_year, 1, "Year");
-    dragvalue_normal(ui, &mut uis.solar_system.start_month, 1, "Month");
-    dragvalue_normal(ui, &mut uis.solar_system.start_day, 1, "Day");
-    dragvalue_normal(ui, &mut uis.solar_system.start_hour, 1, "Hour");
+/// Renders the derived metrics as `name = expression` rows and the row defining a new
+/// one; a definition that fails to parse is reported in the status bar.
+fn derived_metrics_controls(ui: &mut egui::Ui, uis: &mut UiState) {
+    label_normal(ui, "Derived Metrics");
+    let mut removed = None;
+    for derived in uis.metrics.derived() {
+        ui.horizontal(|ui| {
+            let definition = derived.definition();
+            ui.monospace(format!("{} = {}", definition.name, definition.expression));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.small_button("×").clicked() {
+                    removed = Some(definition.name.clone());
+                }
+            });
+        });
+    }
+    if let Some(name) = removed {
+        uis.metrics.undefine(&name);
+    }
+    ui.horizontal(|ui| {
+        label_normal(ui, "Name");
+        ui.text_edit_singleline(&mut uis.derived_metric_name);
+    });
+    ui.horizontal(|ui| {
+        label_normal(ui, "Expression");
+        ui.text_edit_singleline(&mut uis.derived_metric_expression);
+    });
+    if button_normal(ui, "Add Metric", false).clicked() {
+        let definition = DerivedMetricDefinition {
+            name: uis.derived_metric_name.trim().to_string(),
+            expression: uis.derived_metric_expression.trim().to_string(),
+        };
+        match uis.metrics.define(definition) {
+            Ok(()) => {
+                uis.derived_metric_name.clear();
+                uis.derived_metric_expression.clear();
+            }
+            Err(e) => uis
+                .status
+                .error(format!("Failed to add derived metric: {}", e)),
+        }
+    }
 }
 
-/// Renders parameter controls for the satellite-orbit object input.
-fn condition_satellite_orbit(ui: &mut egui::Ui, uis: &mut UiState) {
-    dragvalue_normal(
-        ui,
-        &mut uis.satellite_orbit.orbit_altitude_min,
-        1e3,
-        "Orbit Min (m)",
-    );
-    dragvalue_normal(
-        ui,
-        &mut uis.satellite_orbit.orbit_altitude_max,
-        1e3,
-        "Orbit Max (m)",
-    );
-    if let Some(range) = uis.satellite_count_slider() {
-        let response = slider_labeled_u32(
+/// Renders one chart with its metric and scale choices. Returns whether it was deleted
+/// and the export format clicked, if any.
+fn plot_chart_editor(
+    ui: &mut egui::Ui,
+    chart: &mut PlotChart,
+    metrics: &MetricsStore,
+) -> (bool, Option<PlotExportFormat>) {
+    let mut delete = false;
+    ui.horizontal(|ui| {
+        let id = ui.make_persistent_id("plot_metric_combobox");
+        ComboBox::from_id_salt(id)
+            .selected_text(metric_label(&chart.metric))
+            .width(200.0)
+            .show_ui(ui, |ui| {
+                let derived = metrics.derived().iter().map(|derived| derived.name());
+                for name in BUILT_IN_METRICS.into_iter().chain(derived) {
+                    ui.selectable_value(&mut chart.metric, name.to_string(), metric_label(name));
+                }
+            });
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            delete = ui.small_button("×").clicked();
+        });
+    });
+    ui.horizontal(|ui| {
+        label_normal(ui, "Time");
+        combobox_compact(
             ui,
-            "Satellite Count",
-            &mut uis.satellite_orbit.satellite_count,
-            range,
+            "plot_time_scale",
+            &mut chart.time_scale,
+            &AxisScale::ALL,
         );
-        apply_slider_double_click_reset(ui, &response, || {
-            uis.reset_satellite_count_to_default();
+        label_normal(ui, "Value");
+        combobox_compact(
+            ui,
+            "plot_value_scale",
+            &mut chart.value_scale,
+            &AxisScale::ALL,
+        );
+    });
+    let series = metrics.series(&chart.metric);
+    let points = series
+        .map(|series| chart.axis_points(series))
+        .unwrap_or_default();
+    draw_metric_plot(ui, chart, &points);
+    if let Some((t, v)) = series.and_then(|series| series.latest()) {
+        ui.horizontal(|ui| {
+            label_normal(ui, &format!("t {}", format_particle_info_value(t)));
+            label_indicator(ui, &format_particle_info_value(v));
         });
     }
+    let (png, csv) = button_row_pair(ui, "Export PNG", "Export CSV");
+    let export = if series.is_none() {
+        None
+    } else if png.clicked() {
+        Some(PlotExportFormat::Png)
+    } else if csv.clicked() {
+        Some(PlotExportFormat::Csv)
+    } else {
+        None
+    };
+    (delete, export)
 }
 
-/// Renders parameter controls for the elliptical-orbit object input.
-fn condition_elliptical_orbit(ui: &mut egui::Ui, uis: &mut UiState) {
-    label_normal(ui, "Central Body");
-    dragvalue_normal(
-        ui,
-        &mut uis.elliptical_orbit.central_mass,
-        1e20,
-        "Mass (kg)",
-    );
-    label_normal(ui, "Planetary Body");
-    dragvalue_normal(
-        ui,
-        &mut uis.elliptical_orbit.planetary_mass,
-        1e20,
-        "Mass (kg)",
-    );
-    dragvalue_normal(
-        ui,
-        &mut uis.elliptical_orbit.planetary_speed,
-        1e3,
-        "Initial Speed (m/s)",
+/// Paints a chart's axis `points` as a line with the time and value ranges as corner
+/// labels.
+fn draw_metric_plot(ui: &mut egui::Ui, chart: &PlotChart, points: &[[f64; 2]]) {
+    let size = egui::vec2(ui.available_width(), PLOT_CHART_HEIGHT);
+    let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let visuals = ui.visuals();
+    painter.rect_filled(rect, 2.0, visuals.extreme_bg_color);
+    painter.rect_stroke(
+        rect,
+        2.0,
+        visuals.widgets.noninteractive.bg_stroke,
+        egui::StrokeKind::Inside,
     );
-    uis.clamp_velocity_inputs();
-    dragvalue_normal(
-        ui,
-        &mut uis.elliptical_orbit.planetary_distance,
-        1e7,
-        "Initial Distance (m)",
+    let Some([[x_min, x_max], [y_min, y_max]]) = plot_bounds(points) else {
+        return;
+    };
+    let plot = rect.shrink(8.0);
+    let line: Vec<egui::Pos2> = points
+        .iter()
+        .map(|p| {
+            egui::pos2(
+                plot.left() + ((p[0] - x_min) / (x_max - x_min)) as f32 * plot.width(),
+                plot.bottom() - ((p[1] - y_min) / (y_max - y_min)) as f32 * plot.height(),
+            )
+        })
+        .collect();
+    painter.line(line, egui::Stroke::new(1.5, visuals.text_color()));
+    let font = egui::FontId::monospace(10.0);
+    let label_color = visuals.weak_text_color();
+    let time = |position| format_particle_info_value(chart.time_scale.from_axis(position));
+    let value = |position| format_particle_info_value(chart.value_scale.from_axis(position));
+    for (anchor, align, text) in [
+        (
+            rect.left_bottom(),
+            egui::Align2::LEFT_BOTTOM,
+            format!("t {}", time(x_min)),
+        ),
+        (rect.right_bottom(), egui::Align2::RIGHT_BOTTOM, time(x_max)),
+        (rect.left_top(), egui::Align2::LEFT_TOP, value(y_max)),
+        (
+            rect.left_bottom() - egui::vec2(0.0, 12.0),
+            egui::Align2::LEFT_BOTTOM,
+            value(y_min),
+        ),
+    ] {
+        painter.text(anchor, align, text, font.clone(), label_color);
+    }
+}
+
+const GROUP_TABLE_MAX_HEIGHT: f32 = 240.0;
+
+/// Renders the friends-of-friends panel: parameters, on-demand run, and a sortable group
+/// table. Clicking a group row selects its member nearest the group's center of mass.
+fn groups_window(ctx: &egui::Context, uis: &mut UiState) {
+    let mass_unit = uis.base_scale.powi(3);
+    uis.is_groups_panel_open = show_fixed_width_closable_window(
+        ctx,
+        "Groups",
+        uis.is_groups_panel_open,
+        PHASE_SPACE_PANEL_WIDTH,
+        |window| window,
+        |ui| {
+            dragvalue_normal(ui, &mut uis.fof_linking_factor, 0.01, "Linking Length (b)");
+            uis.fof_linking_factor = uis.fof_linking_factor.max(1e-3);
+            ui.horizontal(|ui| {
+                label_normal(ui, "Min Members");
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.add(egui::DragValue::new(&mut uis.fof_min_members).range(1..=100_000));
+                });
+            });
+            ui.horizontal(|ui| {
+                let mut v = uis.fof_color_by_group;
+                if ui.add(Checkbox::new(&mut v, "Color by Group")).changed() {
+                    uis.fof_color_by_group = v;
+                }
+            });
+            ui.horizontal(|ui| {
+                label_normal(ui, "Paint Color");
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.color_edit_button_rgb(&mut uis.group_color);
+                });
+            });
+            if button_normal(ui, "Find Groups", false).clicked() {
+                uis.group_finder_requested = true;
+            }
+            ui.horizontal(|ui| {
+                label_normal(ui, "Groups");
+                label_indicator(ui, &uis.particle_groups.len().to_string());
+                label_normal(ui, "Frame");
+                label_indicator(ui, &uis.particle_groups_frame.to_string());
+            });
+            if uis.particle_groups.is_empty() {
+                return;
+            }
+            ui.separator();
+            let clicked_group = egui::ScrollArea::vertical()
+                .max_height(GROUP_TABLE_MAX_HEIGHT)
+                .show(ui, |ui| group_table(ui, uis, mass_unit))
+                .inner;
+            if let Some(number) = clicked_group {
+                let index = uis.particle_groups[number].central_member;
+                uis.select_particle(index);
+            }
+        },
     );
 }
 
-/// Renders parameter controls for the single-particle object input.
-fn condition_single_particle(ui: &mut egui::Ui, uis: &mut UiState) {
-    dragvalue_normal(ui, &mut uis.single_particle.mass, 1e20, "Mass (kg)");
-    label_normal(ui, "Position (m)");
-    dragvalue_normal(ui, &mut uis.single_particle.position.x, 1e9, "X");
-    dragvalue_normal(ui, &mut uis.single_particle.position.y, 1e9, "Y");
-    dragvalue_normal(ui, &mut uis.single_particle.position.z, 1e9, "Z");
-    label_normal(ui, "Velocity (m/s)");
-    dragvalue_normal(ui, &mut uis.single_particle.velocity.x, 1e3, "X");
-    dragvalue_normal(ui, &mut uis.single_particle.velocity.y, 1e3, "Y");
-    dragvalue_normal(ui, &mut uis.single_particle.velocity.z, 1e3, "Z");
-    uis.clamp_velocity_inputs();
-    ui.horizontal(|ui| {
-        label_normal(ui, "Color");
-        let id = ui.make_persistent_id("single_particle_color_combobox");
-        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-            ComboBox::from_id_salt(id)
-                .selected_text(format!("{}", uis.single_particle.color))
-                .width(90.0)
-                .show_ui(ui, |ui| {
-                    for color in ParticleBasicColor::ALL {
-                        selectable_value(ui, &mut uis.single_particle.color, color);
+/// Renders the group table with clickable sort headers. Returns the row clicked, if any;
+/// a row's Paint button queues that group for recoloring instead.
+fn group_table(ui: &mut egui::Ui, uis: &mut UiState, mass_unit: f64) -> Option<usize> {
+    let mut clicked_group = None;
+    let mut painted_group = None;
+    egui::Grid::new("particle_groups_table")
+        .striped(true)
+        .show(ui, |ui| {
+            label_normal(ui, "#");
+            for key in GroupSortKey::ALL {
+                let arrow = match (uis.group_sort_key == key, uis.group_sort_descending) {
+                    (true, true) => " ⏷",
+                    (true, false) => " ⏶",
+                    (false, _) => "",
+                };
+                if ui.button(format!("{}{}", key, arrow)).clicked() {
+                    if uis.group_sort_key == key {
+                        uis.group_sort_descending = !uis.group_sort_descending;
+                    } else {
+                        uis.group_sort_key = key;
+                        uis.group_sort_descending = true;
                     }
-                });
+                    let descending = uis.group_sort_descending;
+                    sort_groups(&mut uis.particle_groups, key, descending);
+                }
+            }
+            ui.end_row();
+            for (number, group) in uis.particle_groups.iter().enumerate() {
+                if ui.link((number + 1).to_string()).clicked() {
+                    clicked_group = Some(number);
+                }
+                label_indicator(ui, &format_drag_value(group.mass * mass_unit));
+                label_indicator(ui, &format_particle_info_value(group.radius));
+                label_indicator(ui, &group.members.len().to_string());
+                if ui.small_button("Paint").clicked() {
+                    painted_group = Some(number);
+                }
+                ui.end_row();
+            }
+        });
+    if painted_group.is_some() {
+        uis.pending_group_recolor = painted_group;
+    }
+    clicked_group
+}
+
+/// Renders the trajectory export panel: tracked ID list, sample interval, and recording.
+fn trajectory_window(ctx: &egui::Context, uis: &mut UiState) {
+    uis.is_trajectory_panel_open = show_fixed_width_closable_window(
+        ctx,
+        "Trajectories",
+        uis.is_trajectory_panel_open,
+        INPUT_PANEL_WIDTH,
+        |window| window,
+        |ui| {
+            let recording = uis.trajectory_recorder.is_some();
+            ui.add_enabled_ui(!recording, |ui| {
+                ui.horizontal(|ui| {
+                    label_normal(ui, "ID");
+                    ui.add(egui::DragValue::new(&mut uis.trajectory_id_input).range(1..=u64::MAX));
+                    if ui.button("Add").clicked() {
+                        let id = uis.trajectory_id_input;
+                        uis.track_trajectory_id(id);
+                    }
+                });
+                let mut removed = None;
+                for (slot, id) in uis.trajectory_ids.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        label_indicator(ui, &id.to_string());
+                        if ui.small_button("×").clicked() {
+                            removed = Some(slot);
+                        }
+                    });
+                }
+                if let Some(slot) = removed {
+                    uis.trajectory_ids.remove(slot);
+                }
+                ui.horizontal(|ui| {
+                    label_normal(ui, "Every N Frames");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.add(
+                            egui::DragValue::new(&mut uis.trajectory_interval).range(1..=10_000),
+                        );
+                    });
+                });
+            });
+            ui.separator();
+            if recording {
+                ui.horizontal(|ui| {
+                    label_normal(ui, "Rows Written");
+                    let rows = uis.trajectory_recorder.as_ref().map_or(0, |r| r.rows());
+                    label_indicator(ui, &rows.to_string());
+                });
+                if button_normal(ui, "Stop Recording", true).clicked() {
+                    uis.stop_trajectory_recording();
+                }
+            } else {
+                ui.add_enabled_ui(!uis.trajectory_ids.is_empty(), |ui| {
+                    if button_normal(ui, "Record to CSV", false).clicked() {
+                        uis.trajectory_start_requested = true;
+                    }
+                });
+            }
+        },
+    );
+}
+
+const EVENT_LOG_MAX_HEIGHT: f32 = 120.0;
+
+/// Renders the event trigger list, the add-trigger row, and the event log.
+fn events_window(ctx: &egui::Context, uis: &mut UiState) {
+    uis.is_events_panel_open = show_fixed_width_closable_window(
+        ctx,
+        "Events",
+        uis.is_events_panel_open,
+        PHASE_SPACE_PANEL_WIDTH,
+        |window| window,
+        |ui| {
+            let mut removed = None;
+            for (slot, trigger) in uis.event_triggers.iter_mut().enumerate() {
+                ui.push_id(slot, |ui| {
+                    if event_trigger_editor(ui, trigger) {
+                        removed = Some(slot);
+                    }
+                });
+                ui.separator();
+            }
+            if let Some(slot) = removed {
+                uis.event_triggers.remove(slot);
+            }
+            ui.horizontal(|ui| {
+                combobox_compact(
+                    ui,
+                    "event_new_kind",
+                    &mut uis.event_new_kind,
+                    &EventConditionKind::ALL,
+                );
+                combobox_compact(
+                    ui,
+                    "event_new_action",
+                    &mut uis.event_new_action,
+                    &EventAction::ALL,
+                );
+            });
+            let (add, rearm) = button_row_pair(ui, "Add Trigger", "Re-arm All");
+            if add.clicked() {
+                let condition = uis.event_new_kind.default_condition();
+                let action = uis.event_new_action;
+                uis.event_triggers
+                    .push(EventTrigger::new(condition, action));
+            }
+            if rearm.clicked() {
+                uis.rearm_event_triggers();
+            }
+            ui.horizontal(|ui| {
+                label_normal(ui, "Snapshot Dir");
+                ui.text_edit_singleline(&mut uis.event_snapshot_dir);
+            });
+            ui.separator();
+            egui::ScrollArea::vertical()
+                .max_height(EVENT_LOG_MAX_HEIGHT)
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    for line in &uis.event_log {
+                        ui.monospace(line);
+                    }
+                });
+        },
+    );
+}
+
+/// Renders one trigger's enable toggle, parameters, and action. Returns true on delete.
+fn event_trigger_editor(ui: &mut egui::Ui, trigger: &mut EventTrigger) -> bool {
+    let mut delete = false;
+    ui.horizontal(|ui| {
+        ui.checkbox(
+            &mut trigger.enabled,
+            format!("{}", trigger.condition.kind()),
+        );
+        if trigger.fired {
+            label_indicator(ui, "fired");
+        }
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            delete = ui.small_button("×").clicked();
+        });
+    });
+    match &mut trigger.condition {
+        EventCondition::TimeReached { seconds } => {
+            dragvalue_normal(ui, seconds, 1.0, "Time (s)");
+        }
+        EventCondition::CloseApproach {
+            first_id,
+            second_id,
+            distance,
+        } => {
+            ui.horizontal(|ui| {
+                label_normal(ui, "IDs");
+                ui.add(egui::DragValue::new(first_id).range(1..=u64::MAX));
+                ui.add(egui::DragValue::new(second_id).range(1..=u64::MAX));
+            });
+            dragvalue_normal(ui, distance, 0.01, "Distance");
+            *distance = distance.max(0.0);
+        }
+        EventCondition::EnergyDrift { tolerance } => {
+            dragvalue_normal(ui, tolerance, 1e-4, "|ΔE/E₀| >");
+            *tolerance = tolerance.max(0.0);
+        }
+    }
+    combobox_compact(ui, "event_action", &mut trigger.action, &EventAction::ALL);
+    delete
+}
+
+/// Renders a compact combo box over `options`, keyed by `salt` within the current id scope.
+fn combobox_compact<T: PartialEq + Copy + std::fmt::Display>(
+    ui: &mut egui::Ui,
+    salt: &str,
+    value: &mut T,
+    options: &[T],
+) {
+    let id = ui.make_persistent_id(salt);
+    ComboBox::from_id_salt(id)
+        .selected_text(format!("{}", value))
+        .width(90.0)
+        .show_ui(ui, |ui| {
+            for &option in options {
+                selectable_value(ui, value, option);
+            }
+        });
+}
+
+const BATCH_RESULTS_MAX_HEIGHT: f32 = 160.0;
+
+/// Renders the batch sweep inputs, progress, and per-run drift results.
+fn batch_window(ctx: &egui::Context, uis: &mut UiState) {
+    uis.is_batch_panel_open = show_fixed_width_closable_window(
+        ctx,
+        "Batch",
+        uis.is_batch_panel_open,
+        PHASE_SPACE_PANEL_WIDTH,
+        |window| window,
+        |ui| {
+            let running = uis.batch_job.is_some();
+            ui.add_enabled_ui(!running, |ui| {
+                ui.horizontal(|ui| {
+                    label_normal(ui, "dt (s)");
+                    ui.text_edit_singleline(&mut uis.batch_time_steps);
+                });
+                dragvalue_normal(ui, &mut uis.batch_duration, 1.0, "Duration (s)");
+                uis.batch_duration = uis.batch_duration.max(0.0);
+                for simulation_type in SimulationType::ALL {
+                    let mut checked = uis.batch_simulation_types.contains(&simulation_type);
+                    if ui
+                        .checkbox(&mut checked, format!("{}", simulation_type))
+                        .changed()
+                    {
+                        if checked {
+                            uis.batch_simulation_types.push(simulation_type);
+                        } else {
+                            uis.batch_simulation_types.retain(|&t| t != simulation_type);
+                        }
+                    }
+                }
+            });
+            ui.horizontal(|ui| {
+                label_normal(ui, "Particles");
+                label_indicator(ui, &uis.add_particle_count.to_string());
+            });
+            ui.separator();
+            if let Some(job) = &uis.batch_job {
+                let (done, total) = job.progress();
+                let text = match job.eta() {
+                    Some(eta) => format!("{} / {}, ETA {}", done, total, format_wall_duration(eta)),
+                    None => format!("{} / {}", done, total),
+                };
+                ui.add(egui::ProgressBar::new(done as f32 / total.max(1) as f32).text(text));
+                if button_normal(ui, "Abort", true).clicked() {
+                    job.abort();
+                }
+            } else {
+                let (run, export) = button_row_pair(ui, "Run Batch", "Export CSV");
+                if run.clicked() {
+                    match uis.build_batch_config() {
+                        Ok(config) => {
+                            uis.batch_error = None;
+                            uis.batch_results.clear();
+                            uis.batch_job = Some(BatchJob::spawn(config));
+                        }
+                        Err(e) => uis.batch_error = Some(e),
+                    }
+                }
+                if export.clicked() && !uis.batch_results.is_empty() {
+                    uis.batch_export_requested = true;
+                }
+            }
+            if let Some(error) = &uis.batch_error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+            egui::ScrollArea::vertical()
+                .max_height(BATCH_RESULTS_MAX_HEIGHT)
+                .show(ui, |ui| {
+                    for run in &uis.batch_results {
+                        ui.monospace(format!(
+                            "{} dt={:e} |ΔE/E₀|={:.3e}",
+                            run.simulation_type, run.time_per_frame, run.relative_energy_drift
+                        ));
+                    }
+                });
+        },
+    );
+}
+
+/// Renders the reference-engine comparison controls and the last report.
+fn verification_window(ctx: &egui::Context, uis: &mut UiState) {
+    uis.is_verification_panel_open = show_fixed_width_closable_window(
+        ctx,
+        "Verification",
+        uis.is_verification_panel_open,
+        INPUT_PANEL_WIDTH,
+        |window| window,
+        |ui| {
+            ui.horizontal(|ui| {
+                label_normal(ui, "Steps");
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.add(
+                        egui::DragValue::new(&mut uis.verification_steps)
+                            .range(1..=MAX_VERIFICATION_STEPS),
+                    );
+                });
+            });
+            label_normal(
+                ui,
+                &format!(
+                    "Normal engine vs double-double reference, ≤ {} particles",
+                    MAX_VERIFICATION_PARTICLES
+                ),
+            );
+            if let Some(job) = &uis.verification_job {
+                verification_job_progress(ui, job);
+            } else if button_normal(ui, "Run Verification", false).clicked() {
+                uis.verification_requested = true;
+            }
+            if let Some(error) = &uis.verification_error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+            if let Some(report) = uis.verification_report {
+                ui.separator();
+                for (label, value) in [
+                    ("dt (s)", report.time_per_frame),
+                    ("Max |Δr|", report.max_position_error),
+                    ("Max |Δv|", report.max_velocity_error),
+                    ("|ΔE/E₀| Fast", report.fast_energy_drift),
+                    ("|ΔE/E₀| Reference", report.reference_energy_drift),
+                ] {
+                    ui.horizontal(|ui| {
+                        label_normal(ui, label);
+                        label_indicator(ui, &format_particle_info_value(value));
+                    });
+                }
+            }
+            ui.separator();
+            determinism_audit_section(ui, uis);
+        },
+    );
+}
+
+/// Renders the frames a running verification job has finished, and its Abort button.
+fn verification_job_progress<T>(ui: &mut egui::Ui, job: &VerificationJob<T>) {
+    let (done, total) = job.progress();
+    ui.add(
+        egui::ProgressBar::new(done as f32 / total.max(1) as f32)
+            .text(format!("{} / {}", done, total)),
+    );
+    if button_normal(ui, "Abort", true).clicked() {
+        job.abort();
+    }
+}
+
+/// Renders the determinism audit controls: two runs of the active engine from the live
+/// state, compared frame by frame.
+fn determinism_audit_section(ui: &mut egui::Ui, uis: &mut UiState) {
+    ui.strong("Determinism Audit");
+    ui.horizontal(|ui| {
+        label_normal(ui, "Steps");
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ui.add(
+                egui::DragValue::new(&mut uis.determinism_steps).range(1..=MAX_VERIFICATION_STEPS),
+            );
+        });
+    });
+    dragvalue_normal(ui, &mut uis.determinism_tolerance, 1e-12, "Tolerance");
+    uis.determinism_tolerance = uis.determinism_tolerance.max(0.0);
+    label_normal(
+        ui,
+        &format!(
+            "Runs the CPU engine twice from the live state, ≤ {} particles",
+            MAX_DETERMINISM_PARTICLES
+        ),
+    );
+    if let Some(job) = &uis.determinism_job {
+        verification_job_progress(ui, job);
+    } else if button_normal(ui, "Run Audit", false).clicked() {
+        uis.determinism_requested = true;
+    }
+    if let Some(error) = &uis.determinism_error {
+        ui.colored_label(ui.visuals().error_fg_color, error);
+    }
+    let Some(report) = uis.determinism_report else {
+        return;
+    };
+    ui.separator();
+    let Some(divergence) = report.divergence else {
+        label_normal(
+            ui,
+            &format!("Identical within tolerance for {} frames", report.steps),
+        );
+        return;
+    };
+    ui.colored_label(
+        ui.visuals().warn_fg_color,
+        format!("Runs diverge at frame {}", divergence.step),
+    );
+    ui.horizontal(|ui| {
+        label_normal(ui, "Particle");
+        label_indicator(ui, &format!("#{}", divergence.particle_id));
+    });
+    for (label, value) in [
+        ("Max |Δr|", divergence.position_difference),
+        ("Max |Δv|", divergence.velocity_difference),
+    ] {
+        ui.horizontal(|ui| {
+            label_normal(ui, label);
+            label_indicator(ui, &format_particle_info_value(value));
+        });
+    }
+}
+
+fn thomas_precession_window(ctx: &egui::Context, uis: &mut UiState) {
+    uis.is_thomas_panel_open = show_fixed_width_closable_window(
+        ctx,
+        "Thomas Precession",
+        uis.is_thomas_panel_open,
+        INPUT_PANEL_WIDTH,
+        |window| window,
+        |ui| {
+            ui.horizontal(|ui| {
+                label_normal(ui, "Speed β");
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.add(Slider::new(&mut uis.thomas_beta, 0.05..=0.99));
+                });
+            });
+            ui.horizontal(|ui| {
+                label_normal(ui, "Boosts per Revolution");
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.add(
+                        egui::DragValue::new(&mut uis.thomas_steps_per_revolution).range(12..=7200),
+                    );
+                });
+            });
+            let run_label = if uis.thomas_running { "Pause" } else { "Run" };
+            let (run, reset) = button_row_pair(ui, run_label, "Reset");
+            if run.clicked() {
+                uis.thomas_running = !uis.thomas_running;
+            }
+            if reset.clicked() {
+                uis.restart_thomas_precession();
+            }
+            label_normal(
+                ui,
+                "Each step boosts the particle in its own rest frame; the triad shows its axes",
+            );
+            ui.separator();
+            let precession = uis.thomas_precession;
+            let predicted = thomas_precession_per_revolution(precession.beta()).to_degrees();
+            let measured = if precession.revolutions() > 0.0 {
+                format!(
+                    "{:.3}°",
+                    precession.wigner_angle.to_degrees() / precession.revolutions()
+                )
+            } else {
+                "-".to_string()
+            };
+            for (label, value) in [
+                ("β", format!("{:.3}", precession.beta())),
+                ("γ", format!("{:.4}", precession.gamma())),
+                ("Revolutions", format!("{:.2}", precession.revolutions())),
+                (
+                    "Wigner Rotation",
+                    format!("{:.3}°", precession.wigner_angle.to_degrees()),
+                ),
+                ("Per Revolution", measured),
+                ("Predicted −360°(γ−1)", format!("{:.3}°", predicted)),
+            ] {
+                ui.horizontal(|ui| {
+                    label_normal(ui, label);
+                    label_indicator(ui, &value);
+                });
+            }
+        },
+    );
+}
+
+/// Renders the region shape combo box in the Region panel.
+fn combobox_region_shape(ui: &mut egui::Ui, uis: &mut UiState) {
+    ui.horizontal(|ui| {
+        label_normal(ui, "Shape");
+        let id = ui.make_persistent_id("region_shape_combobox");
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ComboBox::from_id_salt(id)
+                .selected_text(format!("{}", uis.region.shape))
+                .width(90.0)
+                .show_ui(ui, |ui| {
+                    for shape in RegionShape::ALL {
+                        selectable_value(ui, &mut uis.region.shape, shape);
+                    }
+                });
+        });
+    });
+}
+
+/// Screen area of the main camera's view: the whole window, or its left half in split view.
+fn primary_view_rect(ctx: &egui::Context, uis: &UiState) -> egui::Rect {
+    let viewport = ctx.viewport_rect();
+    if uis.split_view {
+        viewport.with_max_x(viewport.center().x)
+    } else {
+        viewport
+    }
+}
+
+/// Shows the camera mode with its controls, the grid point under the cursor, the selected
+/// particle, and the latest notification along the bottom of the window.
+fn status_bar(
+    ctx: &egui::Context,
+    uis: &UiState,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+    selected_id: Option<u64>,
+) {
+    let trace_active =
+        uis.is_trace_enabled && uis.is_particle_info_panel_open && uis.selected_particle.is_some();
+    let mode = ToolMode::current(uis.lock_camera_up, trace_active);
+    let viewport = primary_view_rect(ctx, uis);
+    let cursor = ctx
+        .pointer_hover_pos()
+        .filter(|pos| viewport.contains(*pos) && uis.display_space == DisplaySpace::Position)
+        .zip(render_pipeline)
+        .and_then(|(pos, pipeline)| {
+            let local = pos - viewport.min;
+            pipeline.unproject_grid_point(local.x, local.y, viewport.width(), viewport.height())
+        });
+    let meters_per_axes_unit = uis.scale / f64::from(particle_visual_scale_factor(uis.scale_gauge));
+    egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+        ui.horizontal(|ui| {
+            ui.strong(mode.label());
+            ui.label(mode.hint());
+            ui.separator();
+            match cursor {
+                Some(point) => ui.label(format!(
+                    "x {}  z {}",
+                    format_distance(f64::from(point.x) * meters_per_axes_unit),
+                    format_distance(f64::from(point.z) * meters_per_axes_unit)
+                )),
+                None => ui.label("x —  z —"),
+            };
+            if let Some(id) = selected_id {
+                ui.separator();
+                ui.label(format!("Selected #{}", id));
+            }
+            if let Some(message) = uis.status.message(Instant::now()) {
+                ui.separator();
+                ctx.request_repaint_after(STATUS_MESSAGE_DURATION);
+                if message.is_error {
+                    ui.colored_label(ui.visuals().error_fg_color, &message.text);
+                } else {
+                    ui.label(&message.text);
+                }
+            }
         });
     });
 }
 
-const ADD_CENTER_SLIDER_RANGE: std::ops::RangeInclusive<f64> = -10.0..=10.0;
-const ADD_CENTER_SLIDER_STEP: f64 = 0.01;
+/// Paints the grid's tick distances and axis names behind the windows, at their projected
+/// viewport positions.
+fn axis_label_overlay(
+    ctx: &egui::Context,
+    uis: &UiState,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+) {
+    let Some(pipeline) = render_pipeline else {
+        return;
+    };
+    let viewport = primary_view_rect(ctx, uis);
+    let meters_per_axes_unit = uis.scale / f64::from(particle_visual_scale_factor(uis.scale_gauge));
+    let painter = ctx
+        .layer_painter(egui::LayerId::background())
+        .with_clip_rect(viewport);
+    let font = egui::FontId::proportional(11.0);
+    for label in grid_labels(uis.display_space, meters_per_axes_unit) {
+        let Some([x, y]) =
+            pipeline.project_grid_point(label.position, viewport.width(), viewport.height())
+        else {
+            continue;
+        };
+        painter.text(
+            viewport.min + egui::vec2(x, y),
+            egui::Align2::CENTER_CENTER,
+            label.text,
+            font.clone(),
+            color32_from_rgba(label.color),
+        );
+    }
+}
+
+const ANNOTATION_FONT_SIZE: f32 = 13.0;
+const ANNOTATION_ANCHOR_RADIUS: f32 = 2.5;
+const ANNOTATION_LEADER_COLOR: egui::Color32 = egui::Color32::from_gray(180);
+
+/// Renders the annotation list with editable text and delete buttons, and the inputs
+/// pinning a new label to the selected particle or to a point.
+fn annotations_window(ctx: &egui::Context, uis: &mut UiState, selected: Option<Particle>) {
+    uis.is_annotations_panel_open = show_fixed_width_closable_window(
+        ctx,
+        "Annotations",
+        uis.is_annotations_panel_open,
+        INPUT_PANEL_WIDTH,
+        |window| window,
+        |ui| {
+            ui.horizontal(|ui| {
+                let mut v = uis.show_annotations;
+                if ui.add(Checkbox::new(&mut v, "Show Labels")).changed() {
+                    uis.show_annotations = v;
+                }
+            });
+            ui.separator();
+            let mut removed = None;
+            for (slot, annotation) in uis.annotations.iter_mut().enumerate() {
+                ui.push_id(slot, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.text_edit_singleline(&mut annotation.text);
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.small_button("×").clicked() {
+                                removed = Some(slot);
+                            }
+                        });
+                    });
+                    label_normal(ui, &annotation_target_text(annotation.target));
+                });
+            }
+            if let Some(slot) = removed {
+                uis.annotations.remove(slot);
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                label_normal(ui, "Text");
+                ui.text_edit_singleline(&mut uis.annotation_text);
+            });
+            ui.add_enabled_ui(selected.is_some(), |ui| {
+                if button_normal(ui, "Label Selected", false).clicked()
+                    && let Some(particle) = selected
+                {
+                    uis.add_annotation(AnnotationTarget::Particle(particle.id));
+                }
+            });
+            label_normal(ui, "Point (Base Scale Units)");
+            dragvalue_normal(ui, &mut uis.annotation_position.x, 0.01, "X");
+            dragvalue_normal(ui, &mut uis.annotation_position.y, 0.01, "Y");
+            dragvalue_normal(ui, &mut uis.annotation_position.z, 0.01, "Z");
+            ui.add_enabled_ui(selected.is_some(), |ui| {
+                if button_normal(ui, "Point at Selected", false).clicked()
+                    && let Some(particle) = selected
+                {
+                    uis.annotation_position = particle.position;
+                }
+            });
+            if button_normal(ui, "Label Point", false).clicked() {
+                uis.add_annotation(AnnotationTarget::Position(uis.annotation_position));
+            }
+        },
+    );
+}
+
+/// Renders the Render Still panel: output size, presets, supersampling, and the button
+/// that renders the main view to a PNG file.
+fn render_still_window(ctx: &egui::Context, uis: &mut UiState) {
+    uis.is_render_still_panel_open = show_fixed_width_closable_window(
+        ctx,
+        "Render Still",
+        uis.is_render_still_panel_open,
+        INPUT_PANEL_WIDTH,
+        |window| window,
+        |ui| {
+            let settings = &mut uis.still_settings;
+            ui.horizontal(|ui| {
+                label_normal(ui, "Preset");
+                let id = ui.make_persistent_id("still_preset_combobox");
+                let preset = STILL_RESOLUTION_PRESETS
+                    .iter()
+                    .find(|(_, width, height)| {
+                        (*width, *height) == (settings.width, settings.height)
+                    })
+                    .map_or("Custom", |(name, _, _)| *name);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ComboBox::from_id_salt(id)
+                        .selected_text(preset)
+                        .width(90.0)
+                        .show_ui(ui, |ui| {
+                            for (name, width, height) in STILL_RESOLUTION_PRESETS {
+                                if ui.selectable_label(preset == name, name).clicked() {
+                                    settings.width = width;
+                                    settings.height = height;
+                                }
+                            }
+                        });
+                });
+            });
+            dragvalue_normal(ui, &mut settings.width, 8.0, "Width");
+            dragvalue_normal(ui, &mut settings.height, 8.0, "Height");
+            settings.width = settings.width.clamp(1, MAX_STILL_SIZE);
+            settings.height = settings.height.clamp(1, MAX_STILL_SIZE);
+            ui.horizontal(|ui| {
+                label_normal(ui, "Supersampling");
+                let id = ui.make_persistent_id("still_supersampling_combobox");
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ComboBox::from_id_salt(id)
+                        .selected_text(format!("{}×", settings.supersampling))
+                        .width(90.0)
+                        .show_ui(ui, |ui| {
+                            for factor in STILL_SUPERSAMPLING {
+                                ui.selectable_value(
+                                    &mut settings.supersampling,
+                                    factor,
+                                    format!("{}×", factor),
+                                );
+                            }
+                        });
+                });
+            });
+            label_normal(
+                ui,
+                &format!(
+                    "Rendered at {} × {}",
+                    settings.width * settings.supersampling,
+                    settings.height * settings.supersampling
+                ),
+            );
+            if button_normal(ui, "Render…", false).clicked() {
+                uis.still_render_requested = true;
+            }
+            export_status(ui, &uis.export_writer.status());
+        },
+    );
+}
+
+/// Renders the settings of the running approximate force solver: the tree opening angle
+/// θ and its auto-tuning toward a target relative force error, or the FMM expansion
+/// order; then the error of the last sampled check.
+fn force_solver_controls(ui: &mut egui::Ui, uis: &mut UiState, dbl_click: Option<egui::Pos2>) {
+    if uis.uses_tree_forces() {
+        tree_force_controls(ui, uis, dbl_click);
+    } else if uis.uses_fast_multipoles() {
+        label_normal(ui, "Expansion Order");
+        let slider = ui.add(Slider::new(
+            &mut uis.expansion_order,
+            MIN_EXPANSION_ORDER..=MAX_EXPANSION_ORDER,
+        ));
+        apply_slider_double_click_reset_with_pos(&slider, dbl_click, || {
+            uis.expansion_order = DEFAULT_EXPANSION_ORDER;
+        });
+    } else {
+        return;
+    }
+    if let Some(error) = uis.force_error {
+        ui.horizontal(|ui| {
+            label_normal(ui, "Force Error");
+            label_indicator(ui, &format!("{:.2e}", error));
+        });
+    }
+}
+
+/// Renders the tree opening angle θ and its auto-tuning toward a target force error, the
+/// rebuild interval, and where the last step spent its time.
+fn tree_force_controls(ui: &mut egui::Ui, uis: &mut UiState, dbl_click: Option<egui::Pos2>) {
+    label_normal(ui, "Opening Angle θ");
+    let slider = ui.add_enabled(
+        !uis.auto_tune_opening_angle,
+        Slider::new(
+            &mut uis.opening_angle,
+            MIN_OPENING_ANGLE..=MAX_OPENING_ANGLE,
+        ),
+    );
+    apply_slider_double_click_reset_with_pos(&slider, dbl_click, || {
+        uis.opening_angle = DEFAULT_OPENING_ANGLE;
+    });
+    ui.horizontal(|ui| {
+        let mut v = uis.auto_tune_opening_angle;
+        if ui.add(Checkbox::new(&mut v, "Auto-tune θ")).changed() {
+            uis.auto_tune_opening_angle = v;
+        }
+    });
+    if uis.auto_tune_opening_angle {
+        label_normal(ui, "Target Force Error");
+        let slider = ui.add(
+            Slider::new(
+                &mut uis.target_force_error,
+                MIN_TARGET_FORCE_ERROR..=MAX_TARGET_FORCE_ERROR,
+            )
+            .logarithmic(true),
+        );
+        apply_slider_double_click_reset_with_pos(&slider, dbl_click, || {
+            uis.reset_target_force_error_to_default();
+        });
+    }
+    label_normal(ui, "Rebuild Tree Every (steps)");
+    let slider = ui.add(
+        Slider::new(
+            &mut uis.tree_rebuild_interval,
+            MIN_TREE_REBUILD_INTERVAL..=MAX_TREE_REBUILD_INTERVAL,
+        )
+        .logarithmic(true),
+    );
+    apply_slider_double_click_reset_with_pos(&slider, dbl_click, || {
+        uis.tree_rebuild_interval = DEFAULT_TREE_REBUILD_INTERVAL;
+    });
+    if let Some(timing) = uis.tree_timing {
+        let update = if timing.rebuilt {
+            format!("{:.2} ms (rebuilt)", timing.tree_seconds * 1e3)
+        } else {
+            format!(
+                "{:.2} ms ({} moved)",
+                timing.tree_seconds * 1e3,
+                timing.moved
+            )
+        };
+        ui.horizontal(|ui| {
+            label_normal(ui, "Tree Update");
+            label_indicator(ui, &update);
+        });
+        ui.horizontal(|ui| {
+            label_normal(ui, "Force Walk");
+            label_indicator(ui, &format!("{:.2} ms", timing.walk_seconds * 1e3));
+        });
+        ui.horizontal(|ui| {
+            label_normal(ui, "Tree Nodes");
+            label_indicator(ui, timing.node_count.to_string().as_str());
+        });
+    }
+}
+
+/// Renders the block time step toggle, the timestep factor η, and the finest step of the
+/// last frame. Only the CPU Newtonian engine in double precision is supported.
+fn block_step_controls(ui: &mut egui::Ui, uis: &mut UiState, dbl_click: Option<egui::Pos2>) {
+    ui.add_enabled_ui(uis.runs_direct_newtonian(), |ui| {
+        ui.horizontal(|ui| {
+            let mut v = uis.block_steps;
+            if ui.add(Checkbox::new(&mut v, "Block Time Steps")).changed() {
+                uis.block_steps = v;
+            }
+        });
+        if !uis.block_steps {
+            return;
+        }
+        label_normal(ui, "Timestep Factor η");
+        let slider = ui.add(
+            Slider::new(
+                &mut uis.block_step_accuracy,
+                MIN_BLOCK_STEP_ACCURACY..=MAX_BLOCK_STEP_ACCURACY,
+            )
+            .logarithmic(true),
+        );
+        apply_slider_double_click_reset_with_pos(&slider, dbl_click, || {
+            uis.reset_block_step_accuracy_to_default();
+        });
+        if let Some(level) = uis.block_level {
+            ui.horizontal(|ui| {
+                label_normal(ui, "Finest Step");
+                label_indicator(ui, &format!("1/{} frame", 1u32 << level));
+            });
+        }
+    });
+}
+
+/// Renders the close-encounter refinement toggle and the steps per encounter time. Only
+/// CPU engines with classical velocities are refined.
+fn encounter_refinement_controls(
+    ui: &mut egui::Ui,
+    uis: &mut UiState,
+    dbl_click: Option<egui::Pos2>,
+) {
+    let supported = uis.active_simulation_type.supports_drag()
+        && !uis.uses_gpu_simulation()
+        && uis.block_stepping().is_none();
+    ui.add_enabled_ui(supported, |ui| {
+        ui.horizontal(|ui| {
+            let mut v = uis.refine_encounters;
+            if ui
+                .add(Checkbox::new(&mut v, "Refine Close Encounters"))
+                .changed()
+            {
+                uis.refine_encounters = v;
+            }
+        });
+        if !uis.refine_encounters {
+            return;
+        }
+        label_normal(ui, "Steps per Encounter");
+        let slider = ui.add(Slider::new(
+            &mut uis.steps_per_encounter,
+            MIN_STEPS_PER_ENCOUNTER..=MAX_STEPS_PER_ENCOUNTER,
+        ));
+        apply_slider_double_click_reset_with_pos(&slider, dbl_click, || {
+            uis.reset_steps_per_encounter_to_default();
+        });
+    });
+}
+
+/// Renders the tight-binary regularization toggle, the steps per orbit below which a
+/// binary is regularized, and how many are. Only the CPU Newtonian engine in double
+/// precision is supported.
+fn binary_regularization_controls(
+    ui: &mut egui::Ui,
+    uis: &mut UiState,
+    dbl_click: Option<egui::Pos2>,
+) {
+    let supported = uis.runs_direct_newtonian() && uis.block_stepping().is_none();
+    ui.add_enabled_ui(supported, |ui| {
+        ui.horizontal(|ui| {
+            let mut v = uis.regularize_binaries;
+            if ui
+                .add(Checkbox::new(&mut v, "Regularize Tight Binaries"))
+                .changed()
+            {
+                uis.regularize_binaries = v;
+            }
+        });
+        if !uis.regularize_binaries {
+            return;
+        }
+        label_normal(ui, "Steps per Orbit");
+        let slider = ui.add(Slider::new(
+            &mut uis.steps_per_orbit,
+            MIN_STEPS_PER_ORBIT..=MAX_STEPS_PER_ORBIT,
+        ));
+        apply_slider_double_click_reset_with_pos(&slider, dbl_click, || {
+            uis.reset_steps_per_orbit_to_default();
+        });
+        ui.horizontal(|ui| {
+            label_normal(ui, "Binaries");
+            label_indicator(ui, uis.kepler_pairs.len().to_string().as_str());
+        });
+    });
+}
+
+/// Renders the energy drift warning toggle and its threshold.
+fn energy_warning_controls(ui: &mut egui::Ui, uis: &mut UiState) {
+    ui.horizontal(|ui| {
+        let mut v = uis.energy_monitor.enabled;
+        if ui
+            .add(Checkbox::new(&mut v, "Energy Drift Warning"))
+            .changed()
+        {
+            uis.energy_monitor.enabled = v;
+        }
+    });
+    ui.add_enabled(
+        uis.energy_monitor.enabled,
+        Slider::new(
+            &mut uis.energy_monitor.threshold,
+            MIN_ENERGY_DRIFT_THRESHOLD..=MAX_ENERGY_DRIFT_THRESHOLD,
+        )
+        .logarithmic(true)
+        .text("|ΔE/E₀|"),
+    );
+}
+
+/// Renders how much history the recorded diagnostics keep, and their CSV export.
+fn metrics_controls(ui: &mut egui::Ui, uis: &mut UiState) {
+    let mut retention = uis.metrics.retention();
+    dragvalue_normal(
+        ui,
+        &mut retention.capacity,
+        16.0,
+        "Metric History (samples)",
+    );
+    dragvalue_normal(
+        ui,
+        &mut retention.max_age,
+        1.0,
+        "Metric Max Age (s, 0 = all)",
+    );
+    retention.capacity = retention
+        .capacity
+        .clamp(MIN_METRIC_CAPACITY, MAX_METRIC_CAPACITY);
+    retention.max_age = retention.max_age.max(0.0);
+    if retention != uis.metrics.retention() {
+        uis.metrics.set_retention(retention);
+    }
+    if button_normal(ui, "Export Metrics CSV", false).clicked() && !uis.metrics.is_empty() {
+        uis.metrics_export_requested = true;
+    }
+}
+
+/// Renders the finite-run controls: the end time, the snapshot there, and the progress
+/// toward it.
+fn run_target_controls(ui: &mut egui::Ui, uis: &mut UiState) {
+    ui.horizontal(|ui| {
+        let mut v = uis.run_target.enabled;
+        if ui.add(Checkbox::new(&mut v, "Stop at")).changed() {
+            uis.run_target.enabled = v;
+        }
+        ui.add(
+            egui::DragValue::new(&mut uis.run_target.length)
+                .range(0.0..=f64::MAX)
+                .speed(0.1),
+        );
+        combobox_compact(
+            ui,
+            "run_length_unit",
+            &mut uis.run_target.unit,
+            &RunLengthUnit::ALL,
+        );
+    });
+    ui.horizontal(|ui| {
+        let mut v = uis.run_target.snapshot_at_end;
+        if ui.add(Checkbox::new(&mut v, "Snapshot at End")).changed() {
+            uis.run_target.snapshot_at_end = v;
+        }
+    });
+    if let Some(progress) = uis.run_progress() {
+        ui.add(egui::ProgressBar::new(progress).show_percentage());
+    }
+}
+
+/// Renders the Auto Speed toggle with its target render rate, and the measured step and
+/// render rates while it runs.
+fn auto_speed_controls(ui: &mut egui::Ui, uis: &mut UiState, dbl_click: Option<egui::Pos2>) {
+    ui.horizontal(|ui| {
+        let mut v = uis.auto_speed;
+        if ui.add(Checkbox::new(&mut v, "Auto Speed")).changed() {
+            uis.auto_speed = v;
+        }
+    });
+    if !uis.auto_speed {
+        return;
+    }
+    label_normal(ui, "Target render FPS");
+    let target_slider = ui.add(Slider::new(
+        &mut uis.target_render_fps,
+        MIN_TARGET_RENDER_FPS..=MAX_TARGET_RENDER_FPS,
+    ));
+    apply_slider_double_click_reset_with_pos(&target_slider, dbl_click, || {
+        uis.reset_target_render_fps_to_default();
+    });
+    let clock = uis.readout_clock();
+    ui.horizontal(|ui| {
+        label_normal(ui, "Steps/s");
+        label_indicator(ui, &clock.fps.to_string());
+    });
+    ui.horizontal(|ui| {
+        label_normal(ui, "Render FPS");
+        label_indicator(ui, &uis.draw_fps.to_string());
+    });
+}
+
+/// Renders the UI font list: font files in front of the default font, each removable, and
+/// the system CJK fallback. Changes apply from the next frame.
+fn fonts_controls(ui: &mut egui::Ui, uis: &mut UiState) {
+    label_normal(ui, "Fonts");
+    let mut removed = None;
+    for (slot, path) in uis.ui_fonts.iter().enumerate() {
+        ui.horizontal(|ui| {
+            let name = path.file_name().map_or_else(
+                || path.display().to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            );
+            label_indicator(ui, &name);
+            if ui.small_button("×").clicked() {
+                removed = Some(slot);
+            }
+        });
+    }
+    if let Some(slot) = removed {
+        uis.ui_fonts.remove(slot);
+        uis.fonts_changed = true;
+    }
+    ui.horizontal(|ui| {
+        let mut v = uis.system_cjk_font;
+        if ui.add(Checkbox::new(&mut v, "System CJK Font")).changed() {
+            uis.system_cjk_font = v;
+            uis.fonts_changed = true;
+        }
+    });
+    if button_normal(ui, "Add Font…", false).clicked() {
+        uis.font_dialog_requested = true;
+    }
+}
+
+/// Renders the fullscreen options: the monitor, borderless or exclusive, and the video
+/// mode used for exclusive fullscreen. Changes apply at once when fullscreen is on.
+fn display_mode_controls(ui: &mut egui::Ui, uis: &mut UiState) {
+    let before = uis.display_mode.clone();
+    ui.horizontal(|ui| {
+        let mut v = uis.display_mode.fullscreen;
+        if ui.add(Checkbox::new(&mut v, "Fullscreen (F11)")).changed() {
+            uis.display_mode.fullscreen = v;
+        }
+    });
+    let labels: Vec<String> = uis.monitors.iter().map(|m| m.label.clone()).collect();
+    ui.horizontal(|ui| {
+        label_normal(ui, "Monitor");
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            let mode = &mut uis.display_mode;
+            ComboBox::from_id_salt("fullscreen_monitor_combobox")
+                .selected_text(mode.monitor.as_deref().unwrap_or("Current"))
+                .width(150.0)
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut mode.monitor, None, "Current");
+                    for label in &labels {
+                        ui.selectable_value(&mut mode.monitor, Some(label.clone()), label);
+                    }
+                });
+        });
+    });
+    ui.horizontal(|ui| {
+        label_normal(ui, "Fullscreen Mode");
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            let kind = &mut uis.display_mode.kind;
+            ComboBox::from_id_salt("fullscreen_kind_combobox")
+                .selected_text(kind.to_string())
+                .width(150.0)
+                .show_ui(ui, |ui| {
+                    for option in FullscreenKind::ALL {
+                        ui.selectable_value(kind, option, option.to_string());
+                    }
+                });
+        });
+    });
+    if uis.display_mode.kind == FullscreenKind::Exclusive {
+        let monitor = match uis.display_mode.monitor.as_deref() {
+            Some(wanted) => uis.monitors.iter().find(|m| m.label == wanted),
+            None => uis
+                .current_monitor
+                .and_then(|index| uis.monitors.get(index)),
+        };
+        let resolutions = monitor.map(|m| m.resolutions.clone()).unwrap_or_default();
+        ui.horizontal(|ui| {
+            label_normal(ui, "Resolution");
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let resolution = &mut uis.display_mode.resolution;
+                let selected = resolution.map_or_else(|| "Largest".to_string(), |r| r.to_string());
+                ComboBox::from_id_salt("fullscreen_resolution_combobox")
+                    .selected_text(selected)
+                    .width(150.0)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(resolution, None, "Largest");
+                        for option in resolutions {
+                            ui.selectable_value(resolution, Some(option), option.to_string());
+                        }
+                    });
+            });
+        });
+    }
+    if uis.display_mode != before {
+        uis.display_mode_changed = true;
+    }
+}
+
+/// Renders the Palettes panel: the palettes assigned to generated particles and to groups,
+/// and an editor for custom palettes, which starts from a copy of any palette.
+fn palettes_window(ctx: &egui::Context, uis: &mut UiState) {
+    uis.is_palettes_panel_open = show_fixed_width_closable_window(
+        ctx,
+        "Palettes",
+        uis.is_palettes_panel_open,
+        INPUT_PANEL_WIDTH,
+        |window| window,
+        |ui| {
+            let names = uis.palettes.names();
+            let mut changed =
+                combobox_palette(ui, "Particles", &mut uis.palettes.particle_palette, &names);
+            changed |= combobox_palette(ui, "Groups", &mut uis.palettes.group_palette, &names);
+            label_normal(ui, "Used for particles added and groups found from now on.");
+            ui.separator();
+            combobox_palette(ui, "Edit", &mut uis.palette_editing, &names);
+            let editing = uis.palette_editing.clone();
+            let custom = uis.palettes.is_custom(&editing);
+            let mut colors = uis.palettes.colors(&editing).to_vec();
+            let removable = custom && colors.len() > 1;
+            let mut removed = None;
+            ui.add_enabled_ui(custom, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    for (slot, color) in colors.iter_mut().enumerate() {
+                        ui.push_id(slot, |ui| {
+                            ui.color_edit_button_rgb(color);
+                            if removable && ui.small_button("×").clicked() {
+                                removed = Some(slot);
+                            }
+                        });
+                    }
+                });
+            });
+            if custom {
+                if let Some(slot) = removed {
+                    colors.remove(slot);
+                }
+                let (add, delete) = button_row_pair(ui, "Add Color", "Delete");
+                if add.clicked() && colors.len() < MAX_PALETTE_COLORS {
+                    colors.push(colors.last().copied().unwrap_or([1.0; 3]));
+                }
+                if let Some(palette) = uis.palettes.custom_mut(&editing) {
+                    changed |= palette.colors != colors;
+                    palette.colors = colors;
+                }
+                if delete.clicked() {
+                    uis.palettes.remove(&editing);
+                    uis.palette_editing = DEFAULT_PARTICLE_PALETTE.to_string();
+                    changed = true;
+                }
+            }
+            if button_normal(ui, "Duplicate", false).clicked() {
+                uis.palette_editing = uis.palettes.duplicate(&editing);
+            }
+            if changed {
+                uis.palettes.apply();
+            }
+        },
+    );
+}
+
+/// Renders a palette picker row. Returns whether the selection changed.
+fn combobox_palette(
+    ui: &mut egui::Ui,
+    label: &str,
+    selected: &mut String,
+    names: &[String],
+) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        label_normal(ui, label);
+        let id = ui.make_persistent_id(format!("{}_palette_combobox", label));
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ComboBox::from_id_salt(id)
+                .selected_text(selected.as_str())
+                .width(90.0)
+                .show_ui(ui, |ui| {
+                    for name in names {
+                        changed |= ui.selectable_value(selected, name.clone(), name).changed();
+                    }
+                });
+        });
+    });
+    changed
+}
+
+const TOAST_WIDTH: f32 = 280.0;
+const TOAST_MARGIN: f32 = 12.0;
+
+/// Stacks the pending toasts in the top-right corner below the menu bar, oldest first,
+/// each with a button to dismiss it.
+fn toast_overlay(ctx: &egui::Context, toasts: &Toasts, menu_bar_height: f32) {
+    let visible = toasts.visible(Instant::now());
+    if visible.is_empty() {
+        return;
+    }
+    egui::Area::new(egui::Id::new("toasts"))
+        .order(egui::Order::Foreground)
+        .anchor(
+            egui::Align2::RIGHT_TOP,
+            egui::vec2(-TOAST_MARGIN, menu_bar_height + TOAST_MARGIN),
+        )
+        .show(ctx, |ui| {
+            ui.set_width(TOAST_WIDTH);
+            for toast in &visible {
+                let color = match toast.level {
+                    ToastLevel::Info => ui.visuals().text_color(),
+                    ToastLevel::Success => egui::Color32::from_rgb(120, 200, 120),
+                    ToastLevel::Warning => ui.visuals().warn_fg_color,
+                    ToastLevel::Error => ui.visuals().error_fg_color,
+                };
+                egui::Frame::popup(ui.style())
+                    .stroke(egui::Stroke::new(1.0, color))
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            if ui.small_button("✖").clicked() {
+                                toasts.dismiss(toast.id);
+                            }
+                            ui.colored_label(color, &toast.text);
+                        });
+                    });
+                ui.add_space(4.0);
+            }
+        });
+    ctx.request_repaint_after(TOAST_DURATION);
+}
+
+/// Names the kiosk entry on screen and tells visitors how to take over.
+fn kiosk_caption(ctx: &egui::Context, uis: &UiState) {
+    let Some((index, step)) = uis.kiosk.current() else {
+        return;
+    };
+    let count = uis.kiosk.playlist().len();
+    egui::Area::new(egui::Id::new("kiosk_caption"))
+        .order(egui::Order::Foreground)
+        .interactable(false)
+        .anchor(egui::Align2::CENTER_BOTTOM, [0.0, -24.0])
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.strong(format!("{} ({}/{})", step.label(), index + 1, count));
+                    ui.label("Press any key or click to take over");
+                });
+            });
+        });
+}
+
+/// Warns while the energy drift exceeds the threshold and offers a smaller time step.
+fn energy_drift_banner(ctx: &egui::Context, uis: &mut UiState, menu_bar_height: f32) {
+    let Some(drift) = uis.energy_monitor.warning() else {
+        return;
+    };
+    let threshold = uis.energy_monitor.threshold;
+    let suggested = suggested_time_per_frame(uis.time_per_frame, drift, threshold);
+    egui::Area::new(egui::Id::new("energy_drift_banner"))
+        .order(egui::Order::Foreground)
+        .anchor(
+            egui::Align2::CENTER_TOP,
+            egui::vec2(0.0, menu_bar_height + TOAST_MARGIN),
+        )
+        .show(ctx, |ui| {
+            let color = ui.visuals().warn_fg_color;
+            egui::Frame::popup(ui.style())
+                .stroke(egui::Stroke::new(2.0, color))
+                .show(ui, |ui| {
+                    ui.vertical_centered(|ui| {
+                        ui.colored_label(
+                            color,
+                            egui::RichText::new(format!(
+                                "⚠ Energy drift |ΔE/E₀| = {:.2e} exceeds {:.0e}",
+                                drift, threshold
+                            ))
+                            .strong(),
+                        );
+                        ui.label("The integration may have blown up; results are unreliable.");
+                        ui.horizontal(|ui| {
+                            let apply = format!(
+                                "Use Time/Frame {}",
+                                format_simulation_time(suggested, TimeDisplayUnit::Seconds, None)
+                            );
+                            if ui.button(apply).clicked() {
+                                uis.time_per_frame = suggested;
+                                uis.energy_monitor.forget();
+                            }
+                            if ui.button("Dismiss").clicked() {
+                                uis.energy_monitor.dismiss();
+                            }
+                        });
+                    });
+                });
+        });
+}
+
+const TUTORIAL_ANCHOR_ID: &str = "tutorial_anchor";
+const TUTORIAL_HIGHLIGHT_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 200, 40);
+const TUTORIAL_CARD_WIDTH: f32 = 320.0;
+
+/// Remembers `rect` as the element to highlight when the current tutorial step points at
+/// `target`.
+fn mark_tutorial_target(
+    ctx: &egui::Context,
+    uis: &UiState,
+    target: TutorialTarget,
+    rect: egui::Rect,
+) {
+    if uis.tutorial.step().and_then(|step| step.target()) == Some(target) {
+        ctx.data_mut(|data| data.insert_temp(egui::Id::new(TUTORIAL_ANCHOR_ID), Some(rect)));
+    }
+}
+
+/// Tells that the previous session crashed, shows its report, and offers to restore the
+/// last checkpoint.
+fn crash_report_window(ctx: &egui::Context, uis: &mut UiState) {
+    let Some(report) = uis.crash_report.clone() else {
+        return;
+    };
+    let mut restore = false;
+    let mut dismiss = false;
+    let open = show_fixed_width_closable_window(
+        ctx,
+        "Crash Report",
+        true,
+        HELP_WINDOW_WIDTH,
+        |window| window.anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO),
+        |ui| {
+            label_normal(ui, "The previous session ended with a crash.");
+            label_normal(ui, &format!("Report: {}", report.path.display()));
+            egui::ScrollArea::vertical()
+                .max_height(240.0)
+                .show(ui, |ui| {
+                    ui.monospace(&report.text);
+                });
+            ui.separator();
+            if report.checkpoint.is_none() {
+                label_normal(ui, "No checkpoint was written before the crash.");
+            }
+            ui.horizontal(|ui| {
+                restore = ui
+                    .add_enabled(
+                        report.checkpoint.is_some(),
+                        egui::Button::new("Restore Checkpoint"),
+                    )
+                    .clicked();
+                dismiss = ui.button("Dismiss").clicked();
+            });
+        },
+    );
+    if restore {
+        uis.pending_snapshot_dialog = Some(PendingSnapshotDialog::LoadCheckpoint);
+    }
+    if restore || dismiss || !open {
+        uis.crash_report = None;
+    }
+}
+
+const HELP_WINDOW_WIDTH: f32 = 420.0;
+
+/// Renders the Help window: the mouse and keyboard controls, the version, and project links.
+fn help_window(ctx: &egui::Context, uis: &mut UiState) {
+    uis.is_help_window_open = show_fixed_width_closable_window(
+        ctx,
+        "Help",
+        uis.is_help_window_open,
+        HELP_WINDOW_WIDTH,
+        |window| window,
+        |ui| {
+            for section in &CONTROL_SECTIONS {
+                ui.strong(section.title);
+                egui::Grid::new(section.title)
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (input, action) in section.controls {
+                            ui.monospace(*input);
+                            ui.label(*action);
+                            ui.end_row();
+                        }
+                    });
+                ui.add_space(6.0);
+            }
+            ui.separator();
+            label_normal(ui, &format!("Dual Spacetime Simulator {}", APP_VERSION));
+            for (label, url) in PROJECT_LINKS {
+                ui.hyperlink_to(label, url);
+            }
+        },
+    );
+}
+
+/// Advances the tutorial on what the user has done, then draws a pulsing frame around the
+/// element the current step points at and a card with its instructions. Elements in
+/// closed panels are not highlighted.
+fn tutorial_overlay(ctx: &egui::Context, uis: &mut UiState) {
+    if uis.is_object_input_panel_open {
+        uis.tutorial.notify(TutorialAction::OpenedObjectInput);
+    }
+    if uis.is_reset_requested {
+        uis.tutorial.notify(TutorialAction::Reset);
+    }
+    if uis.is_running {
+        uis.tutorial.notify(TutorialAction::Started);
+    }
+    let anchor_id = egui::Id::new(TUTORIAL_ANCHOR_ID);
+    let anchor = ctx
+        .data_mut(|data| data.remove_temp::<Option<egui::Rect>>(anchor_id))
+        .flatten();
+    let (Some(step), Some((number, count))) = (uis.tutorial.step(), uis.tutorial.progress()) else {
+        return;
+    };
+    if let Some(rect) = anchor.filter(|_| step.target().is_some()) {
+        let pulse = (ctx.input(|i| i.time) * 4.0).sin() as f32 * 0.5 + 0.5;
+        ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, anchor_id))
+            .rect_stroke(
+                rect.expand(2.0 + 2.0 * pulse),
+                4.0,
+                egui::Stroke::new(2.0, TUTORIAL_HIGHLIGHT_COLOR),
+                egui::StrokeKind::Outside,
+            );
+        ctx.request_repaint();
+    }
+    egui::Window::new("Tutorial")
+        .collapsible(false)
+        .resizable(false)
+        .default_width(TUTORIAL_CARD_WIDTH)
+        .anchor(egui::Align2::CENTER_BOTTOM, [0.0, -24.0])
+        .show(ctx, |ui| {
+            ui.strong(format!("{} ({}/{})", step.title(), number, count));
+            ui.label(step.instructions());
+            ui.horizontal(|ui| {
+                if step.completed_by() == TutorialAction::Next && ui.button("Next").clicked() {
+                    uis.tutorial.notify(TutorialAction::Next);
+                }
+                if ui.button("End Tutorial").clicked() {
+                    uis.tutorial.stop();
+                }
+            });
+        });
+}
+
+/// Describes what an annotation is pinned to for the annotation list.
+fn annotation_target_text(target: AnnotationTarget) -> String {
+    match target {
+        AnnotationTarget::Particle(id) => format!("Particle {id}"),
+        AnnotationTarget::Position(position) => format!(
+            "Point {}, {}, {}",
+            format_particle_info_value(position.x),
+            format_particle_info_value(position.y),
+            format_particle_info_value(position.z),
+        ),
+    }
+}
+
+/// Paints the shown annotations and body labels behind the windows, each joined by a leader
+/// line to the point it marks. Labels whose particle is gone or whose point is out of view
+/// are skipped.
+fn annotation_overlay(
+    ctx: &egui::Context,
+    uis: &mut UiState,
+    simulation_manager: &SimulationManager,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+) {
+    let Some(pipeline) = render_pipeline else {
+        return;
+    };
+    let viewport = primary_view_rect(ctx, uis);
+    let painter = ctx
+        .layer_painter(egui::LayerId::background())
+        .with_clip_rect(viewport);
+    let font = egui::FontId::proportional(ANNOTATION_FONT_SIZE);
+    let text_color = egui::Color32::WHITE;
+    let uses_gpu = uis.uses_gpu_simulation();
+    let simulation_type = uis.active_simulation_type();
+    let (scale, scale_gauge) = (uis.scale, uis.scale_gauge);
+    let annotations = if uis.show_annotations {
+        uis.annotations.as_mut_slice()
+    } else {
+        &mut []
+    };
+    let body_labels = if uis.show_body_labels {
+        uis.body_labels.as_mut_slice()
+    } else {
+        &mut []
+    };
+    let mut labels = Vec::new();
+    for annotation in annotations.iter_mut().chain(body_labels) {
+        let particle = match annotation.target {
+            AnnotationTarget::Particle(id) => {
+                let Some(index) = simulation_manager.find_particle_index(id, annotation.index_hint)
+                else {
+                    continue;
+                };
+                annotation.index_hint = index;
+                let particle = if uses_gpu {
+                    pipeline.read_particle_at(index, simulation_type, scale)
+                } else {
+                    simulation_manager.particle_at(index)
+                };
+                // Dead (S³-culled) particles are invisible, so their labels go too.
+                match particle.filter(|particle| particle.color[3] != 0.0) {
+                    Some(particle) => particle,
+                    None => continue,
+                }
+            }
+            AnnotationTarget::Position(position) => {
+                Particle::from_kinematics(position, glam::DVec3::ZERO, 0.0, [1.0; 4])
+            }
+        };
+        let Some([x, y]) = pipeline.project_seen_particle(
+            &particle,
+            viewport.width(),
+            viewport.height(),
+            scale_gauge,
+        ) else {
+            continue;
+        };
+        let galley = painter.layout_no_wrap(annotation.text.clone(), font.clone(), text_color);
+        labels.push((viewport.min + egui::vec2(x, y), galley));
+    }
+    let extents: Vec<([f32; 2], [f32; 2])> = labels
+        .iter()
+        .map(|(anchor, galley)| ([anchor.x, anchor.y], galley.size().into()))
+        .collect();
+    let stroke = egui::Stroke::new(1.0, ANNOTATION_LEADER_COLOR);
+    for ((anchor, galley), corner) in labels.into_iter().zip(place_labels(&extents)) {
+        let corner = egui::Pos2::from(corner);
+        painter.line_segment([anchor, corner + egui::vec2(0.0, galley.size().y)], stroke);
+        painter.circle_filled(anchor, ANNOTATION_ANCHOR_RADIUS, ANNOTATION_LEADER_COLOR);
+        painter.galley(corner, galley, text_color);
+    }
+}
+
+/// Converts a linear `[0, 1]` RGBA particle color into an egui color.
+fn color32_from_rgba(color: [f32; 4]) -> egui::Color32 {
+    egui::Color32::from_rgba_unmultiplied(
+        (color[0].clamp(0.0, 1.0) * 255.0) as u8,
+        (color[1].clamp(0.0, 1.0) * 255.0) as u8,
+        (color[2].clamp(0.0, 1.0) * 255.0) as u8,
+        (color[3].clamp(0.0, 1.0) * 255.0) as u8,
+    )
+}
+
+/// Draws a labeled color swatch matching the `label_indicator` row layout.
+fn draw_particle_color_swatch(ui: &mut egui::Ui, color: [f32; 4]) {
+    let color32 = color32_from_rgba(color);
+    ui.horizontal(|ui| {
+        label_normal(ui, "Color");
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(120.0, 16.0), egui::Sense::hover());
+            ui.painter().rect_filled(rect, 2.0, color32);
+        });
+    });
+}
+
+/// Minimum horizontal gap between labeled scale ticks; closer ticks are skipped.
+const SCALE_TICK_MIN_SPACING: f32 = 36.0;
+
+/// Draws power-of-ten zoom ticks under the scale slider, labeled with the physical
+/// length of one axes unit at each tick.
+fn scale_slider_ticks(
+    ui: &mut egui::Ui,
+    slider: &egui::Response,
+    mode: ScaleGaugeMode,
+    scale: f64,
+) {
+    let range = mode.slider_range();
+    let (lo, hi) = (*range.start(), *range.end());
+    let font = egui::FontId::proportional(9.0);
+    let (rect, _) = ui.allocate_exact_size(
+        egui::vec2(slider.rect.width(), font.size + 6.0),
+        egui::Sense::hover(),
+    );
+    let rail = slider.rect.x_range().shrink(slider.rect.height() / 2.5);
+    let color = ui.visuals().weak_text_color();
+    let painter = ui.painter();
+    let mut last_x = f32::NEG_INFINITY;
+    for (value, zoom) in mode.decade_ticks() {
+        let x = rail.min + ((value - lo) / (hi - lo)) as f32 * rail.span();
+        if x - last_x < SCALE_TICK_MIN_SPACING {
+            continue;
+        }
+        last_x = x;
+        painter.line_segment(
+            [egui::pos2(x, rect.top()), egui::pos2(x, rect.top() + 3.0)],
+            egui::Stroke::new(1.0, color),
+        );
+        painter.text(
+            egui::pos2(x, rect.top() + 3.0),
+            egui::Align2::CENTER_TOP,
+            format_scale_tick(scale / zoom),
+            font.clone(),
+            color,
+        );
+    }
+}
+
+/// Formats a length in meters compactly for scale slider tick labels.
+fn format_scale_tick(length: f64) -> String {
+    let (unit, name) = [
+        (MPC, "Mpc"),
+        (KPC, "kpc"),
+        (PC, "pc"),
+        (LY, "ly"),
+        (AU, "au"),
+        (1e3, "km"),
+    ]
+    .into_iter()
+    .find(|(unit, _)| length >= *unit)
+    .unwrap_or((1.0, "m"));
+    let value = length / unit;
+    if (1.0..1000.0).contains(&value) {
+        format!("{:.0} {}", value, name)
+    } else {
+        format!("{:.0e} {}", value, name)
+    }
+}
+
+/// Formats current scale and gauge ratio for display in the simulation panel.
+fn format_scale(scale_guage: f64, scale: f64) -> String {
+    let scale_inv = DEFAULT_SCALE_UI / scale_guage;
+    let pow10 = scale_inv.powi(4) * scale;
+    if pow10 >= MPC {
+        format!("{:.3e} Mpc", pow10 / MPC)
+    } else if pow10 >= KPC {
+        format!("{:.3e} kpc", pow10 / KPC)
+    } else if pow10 >= PC {
+        format!("{:.3e} pc", pow10 / PC)
+    } else if pow10 >= LY {
+        format!("{:.3e} ly", pow10 / LY)
+    } else if pow10 >= AU {
+        format!("{:.3} au", pow10 / AU)
+    } else if pow10 >= 1e9 {
+        format!("{:.3e} km", pow10 / 1e3)
+    } else if pow10 >= 1e3 {
+        format!("{:.3} km", pow10 / 1e3)
+    } else if pow10 < 1e-15 {
+        format!("{:.6} fm", pow10 * 1e15)
+    } else if pow10 < 1e-9 {
+        format!("{:.6} nm", pow10 * 1e9)
+    } else if pow10 < 1e-3 {
+        format!("{:.6} mm", pow10 * 1e3)
+    } else {
+        format!("{:.3} m", pow10)
+    }
+}
+
+/// Renders base-scale value input with selectable length units.
+fn base_scale_input(ui: &mut egui::Ui, uis: &mut UiState) {
+    label_normal(ui, "Base Scale");
+    let previous_unit = uis.base_scale_unit;
+    let unit = uis.base_scale_unit;
+    let mut display = uis.base_scale_display_value();
+    let format_display = |value: f64| unit.format_display(value);
+    ui.horizontal(|ui| {
+        dragvalue_positive_f64(
+            ui,
+            &mut display,
+            BASE_SCALE_DRAG_SPEED,
+            unit.min_display_value(),
+            110.0,
+            None,
+            Some(&format_display),
+        );
+        display = unit.sanitize_display(display);
+        let id = ui.make_persistent_id("base_scale_unit_combobox");
+        ComboBox::from_id_salt(id)
+            .selected_text(format!("{}", uis.base_scale_unit))
+            .width(60.0)
+            .show_ui(ui, |ui| {
+                for unit in BaseScaleUnit::ALL {
+                    selectable_value(ui, &mut uis.base_scale_unit, unit);
+                }
+            });
+    });
+    uis.apply_base_scale_edit(display, uis.base_scale_unit != previous_unit);
+}
+
+/// Renders the pedagogical speed-of-light slider and the c the engines run with. Only
+/// the engines that limit particles to light speed use it; it applies at the next reset.
+fn light_speed_slider(ui: &mut egui::Ui, uis: &mut UiState) {
+    ui.add_enabled_ui(uis.simulation_type.requires_subluminal_velocity(), |ui| {
+        ui.horizontal(|ui| {
+            label_normal(ui, "c Factor");
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.add(
+                    Slider::new(&mut uis.light_speed_factor, MIN_LIGHT_SPEED_FACTOR..=1.0)
+                        .logarithmic(true),
+                );
+            });
+        });
+    });
+    ui.horizontal(|ui| {
+        label_normal(ui, "c");
+        label_indicator(ui, &format!("{:.4e} m/s", uis.light_speed()));
+    });
+    if uis.simulation_type.requires_subluminal_velocity()
+        && uis.light_speed_factor != uis.active_light_speed_factor
+    {
+        let pending = uis.light_speed_factor * LIGHT_SPEED;
+        label_normal(ui, &format!("{:.4e} m/s after reset", pending));
+    }
+}
+
+/// Renders the angular velocity of the frame the classical engines simulate and display in.
+fn rotating_frame_input(ui: &mut egui::Ui, uis: &mut UiState) {
+    let supported = uis.simulation_type.supports_rotating_frame();
+    ui.add_enabled_ui(supported, |ui| {
+        dragvalue_normal(ui, &mut uis.frame_angular_velocity, 1e-9, "Frame Ω (rad/s)");
+    });
+    let omega = uis.frame_angular_velocity;
+    ui.horizontal(|ui| {
+        label_normal(ui, "Frame Period");
+        let period = if omega == 0.0 {
+            "Inertial".to_string()
+        } else {
+            let period = std::f64::consts::TAU / omega.abs();
+            format_simulation_time(period, TimeDisplayUnit::Days, None)
+        };
+        label_indicator(ui, &period);
+    });
+    if supported && omega != uis.active_frame_angular_velocity {
+        let pending = format_drag_value(omega);
+        label_normal(ui, &format!("{} rad/s after reset", pending));
+    }
+}
+
+/// Renders the Comoving Cosmology expansion history for the next reset and the running
+/// simulation's scale factor, redshift, and Hubble rate.
+fn cosmology_input(ui: &mut egui::Ui, uis: &mut UiState, cosmic_time: Option<(Cosmology, f64)>) {
+    ui.horizontal(|ui| {
+        label_normal(ui, "Expansion");
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            combobox_compact(
+                ui,
+                "expansion_history_combobox",
+                &mut uis.cosmology.history,
+                &ExpansionHistory::ALL,
+            );
+        });
+    });
+    let cosmology = &mut uis.cosmology;
+    dragvalue_normal(ui, &mut cosmology.hubble_constant, 0.1, "H₀ (km/s/Mpc)");
+    cosmology.hubble_constant = cosmology.hubble_constant.max(f64::MIN_POSITIVE);
+    if cosmology.history == ExpansionHistory::LambdaCdm {
+        dragvalue_normal(ui, &mut cosmology.matter_density, 0.01, "Ω_m");
+        cosmology.matter_density = cosmology
+            .matter_density
+            .clamp(MIN_MATTER_DENSITY, 1.0 - MIN_MATTER_DENSITY);
+    }
+    dragvalue_normal(ui, &mut cosmology.start_redshift, 0.1, "Start Redshift z");
+    cosmology.start_redshift = cosmology.start_redshift.max(0.0);
+    let Some((running, time)) = cosmic_time else {
+        return;
+    };
+    let scale_factor = running.scale_factor(time);
+    ui.horizontal(|ui| {
+        label_normal(ui, "Scale Factor a");
+        label_indicator(ui, &format_drag_value(scale_factor));
+    });
+    ui.horizontal(|ui| {
+        label_normal(ui, "Redshift z");
+        label_indicator(ui, &format_drag_value(scale_factor.recip() - 1.0));
+    });
+    ui.horizontal(|ui| {
+        label_normal(ui, "H (km/s/Mpc)");
+        let hubble = running.hubble_rate(time) * MPC / 1_000.0;
+        label_indicator(ui, &format_drag_value(hubble));
+    });
+}
+
+/// Renders the observer-view toggle for the Special engines.
+fn relativistic_view_checkbox(ui: &mut egui::Ui, uis: &mut UiState) {
+    ui.horizontal(|ui| {
+        let mut v = uis.relativistic_view;
+        if ui
+            .add(Checkbox::new(&mut v, "Aberration & Beaming"))
+            .changed()
+        {
+            uis.relativistic_view = v;
+        }
+    });
+}
+
+/// Renders the DST Galaxy S³-angle culling controls (enable toggle + angle threshold).
+fn galaxy_cull_controls(ui: &mut egui::Ui, uis: &mut UiState) {
+    ui.horizontal(|ui| {
+        let mut v = uis.galaxy_cull_enabled;
+        if ui
+            .add(Checkbox::new(&mut v, "Cull Beyond S³ Angle"))
+            .changed()
+        {
+            uis.galaxy_cull_enabled = v;
+        }
+    });
+    // Radial ratio |p|/R = 2α/π gives an intuitive sense of the cutoff distance.
+    let format_angle =
+        |a: f64| format!("{:.3} rad (|p|/R={:.2})", a, 2.0 * a / std::f64::consts::PI);
+    let mut angle = uis.galaxy_cull_max_angle;
+    dragvalue_positive_f64(
+        ui,
+        &mut angle,
+        0.01,
+        0.01,
+        110.0,
+        Some("Cull Angle"),
+        Some(&format_angle),
+    );
+    uis.galaxy_cull_max_angle = angle.min(std::f64::consts::PI);
+}
+
+/// Renders the reflective container toggle with its half-size and restitution.
+fn container_walls_controls(ui: &mut egui::Ui, uis: &mut UiState) {
+    ui.horizontal(|ui| {
+        let mut v = uis.walls_enabled;
+        if ui.add(Checkbox::new(&mut v, "Container Walls")).changed() {
+            uis.walls_enabled = v;
+        }
+    });
+    dragvalue_positive_f64(
+        ui,
+        &mut uis.walls.half_size,
+        0.01,
+        0.01,
+        110.0,
+        Some("Half Size"),
+        None,
+    );
+    dragvalue_normal(ui, &mut uis.walls.restitution, 0.01, "Restitution");
+    uis.walls.restitution = uis.walls.restitution.clamp(0.0, 1.0);
+}
+
+/// Renders the drag model selector and the coefficients of the selected model.
+fn drag_controls(ui: &mut egui::Ui, uis: &mut UiState) {
+    ui.horizontal(|ui| {
+        label_normal(ui, "Drag");
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            combobox_compact(
+                ui,
+                "drag_model_combobox",
+                &mut uis.drag.model,
+                &DragModel::ALL,
+            );
+        });
+    });
+    let drag = &mut uis.drag;
+    match drag.model {
+        DragModel::Off => {}
+        DragModel::Linear => {
+            dragvalue_normal(ui, &mut drag.damping_rate, 1e-10, "Damping γ (1/s)");
+            drag.damping_rate = drag.damping_rate.max(0.0);
+        }
+        DragModel::DynamicalFriction => {
+            dragvalue_normal(
+                ui,
+                &mut drag.background_density,
+                1e-22,
+                "Background ρ (kg/m³)",
+            );
+            drag.background_density = drag.background_density.max(0.0);
+            dragvalue_normal(
+                ui,
+                &mut drag.velocity_dispersion,
+                100.0,
+                "Dispersion σ (m/s)",
+            );
+            drag.velocity_dispersion = drag.velocity_dispersion.max(f64::MIN_POSITIVE);
+            dragvalue_normal(ui, &mut drag.coulomb_logarithm, 0.1, "Coulomb Log ln Λ");
+            drag.coulomb_logarithm = drag.coulomb_logarithm.max(0.0);
+        }
+    }
+}
+
+/// Renders the Langevin thermostat toggle, bath temperature, damping, and seed, with the
+/// thermal speed the bath gives a solar-mass particle.
+fn langevin_noise_controls(ui: &mut egui::Ui, uis: &mut UiState) {
+    ui.horizontal(|ui| {
+        let mut v = uis.noise_enabled;
+        if ui.add(Checkbox::new(&mut v, "Thermal Noise")).changed() {
+            uis.noise_enabled = v;
+        }
+    });
+    let noise = &mut uis.noise;
+    dragvalue_normal(ui, &mut noise.temperature, 1e57, "Temperature (K)");
+    noise.temperature = noise.temperature.max(0.0);
+    dragvalue_normal(ui, &mut noise.damping_rate, 1e-10, "Damping γ (1/s)");
+    noise.damping_rate = noise.damping_rate.max(0.0);
+    dragvalue_normal(ui, &mut noise.seed, 1.0, "Seed");
+    ui.horizontal(|ui| {
+        label_normal(ui, "σ at 1 M☉ (m/s)");
+        label_indicator(ui, &format_drag_value(noise.thermal_speed(MASS_SUN)));
+    });
+}
+
+/// Renders the simulation-type combo box and updates dependent UI state.
+fn combobox_simulation_type(ui: &mut egui::Ui, uis: &mut UiState) {
+    label_normal(ui, "Simulation Type");
+    let previous_type = uis.simulation_type;
+    let id = ui.make_persistent_id("simulation_type_combobox");
+    ComboBox::from_id_salt(id)
+        .selected_text(format!("{}", uis.simulation_type))
+        .width(ui.available_width())
+        .show_ui(ui, |ui| {
+            for ty in SimulationType::ALL {
+                selectable_value(ui, &mut uis.simulation_type, ty);
+            }
+        });
+    uis.apply_simulation_type_change(previous_type);
+}
+
+/// Renders the computing-unit GPU checkbox and updates dependent UI state.
+fn computing_unit_gpu_checkbox(ui: &mut egui::Ui, uis: &mut UiState) {
+    let previous_unit = uis.computing_unit;
+    ui.horizontal(|ui| {
+        label_normal(ui, "Computing Unit");
+        let mut gpu_enabled = uis.computing_unit == ComputingUnit::Gpu;
+        let available = uis.gpu_computing_available();
+        if ui
+            .add_enabled(available, egui::Checkbox::new(&mut gpu_enabled, "GPU"))
+            .changed()
+        {
+            uis.computing_unit = if gpu_enabled {
+                ComputingUnit::Gpu
+            } else {
+                ComputingUnit::Cpu
+            };
+        }
+    });
+    uis.apply_computing_unit_change(previous_unit);
+}
+
+/// Renders the CPU force-summation mode combo box; the GPU path always sums naively.
+fn combobox_force_summation(ui: &mut egui::Ui, uis: &mut UiState) {
+    ui.add_enabled_ui(!uis.uses_gpu_simulation(), |ui| {
+        ui.horizontal(|ui| {
+            label_normal(ui, "Force Summation");
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                combobox_compact(
+                    ui,
+                    "force_summation_combobox",
+                    &mut uis.force_summation,
+                    &Summation::ALL,
+                );
+            });
+        });
+    });
+}
+
+/// Renders the precision combo box; the f32 path exists only for CPU Normal simulations.
+fn combobox_precision(ui: &mut egui::Ui, uis: &mut UiState) {
+    let available =
+        uis.simulation_type == SimulationType::Normal && uis.computing_unit == ComputingUnit::Cpu;
+    ui.add_enabled_ui(available, |ui| {
+        ui.horizontal(|ui| {
+            label_normal(ui, "Precision");
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                combobox_compact(
+                    ui,
+                    "precision_combobox",
+                    &mut uis.precision,
+                    &Precision::ALL,
+                );
+            });
+        });
+    });
+}
+
+/// Renders the force solver combo box; the tree and FMM exist only for CPU Normal
+/// simulations in double precision.
+fn combobox_force_solver(ui: &mut egui::Ui, uis: &mut UiState) {
+    let available = uis.simulation_type == SimulationType::Normal
+        && uis.computing_unit == ComputingUnit::Cpu
+        && uis.precision == Precision::Double;
+    ui.add_enabled_ui(available, |ui| {
+        ui.horizontal(|ui| {
+            label_normal(ui, "Force Solver");
+            let mut solver = uis.force_solver;
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                combobox_compact(ui, "force_solver_combobox", &mut solver, &ForceSolver::ALL);
+            });
+            if solver != uis.force_solver {
+                uis.force_solver = solver;
+                uis.force_error = None;
+            }
+        });
+    });
+}
+
+/// Renders the CPU pipelined-stepping checkbox; the GPU path integrates in place.
+fn pipelined_stepping_checkbox(ui: &mut egui::Ui, uis: &mut UiState) {
+    ui.add_enabled_ui(!uis.uses_gpu_simulation(), |ui| {
+        ui.horizontal(|ui| {
+            label_normal(ui, "CPU Stepping");
+            ui.checkbox(&mut uis.pipelined_stepping, "Pipelined");
+        });
+    });
+}
+
+/// Renders the placement-mode combo box and updates dependent UI state.
+fn combobox_placement_mode(ui: &mut egui::Ui, uis: &mut UiState) {
+    label_normal(ui, "Placement Mode");
+    let previous_mode = uis.placement_mode;
+    let id = ui.make_persistent_id("placement_mode_combobox");
+    ComboBox::from_id_salt(id)
+        .selected_text(format!("{}", uis.placement_mode))
+        .width(ui.available_width())
+        .show_ui(ui, |ui| {
+            for mode in PlacementMode::ALL {
+                selectable_value(ui, &mut uis.placement_mode, mode);
+            }
+        });
+    uis.apply_placement_mode_change(previous_mode);
+}
+
+/// Renders the object-input type combo box and syncs scaled parameters on change.
+fn combobox_object_input_type(ui: &mut egui::Ui, uis: &mut UiState) {
+    label_normal(ui, "Object Input Type");
+    let previous_type = uis.object_input_type;
+    let id = ui.make_persistent_id("object_input_type_combobox");
+    ComboBox::from_id_salt(id)
+        .selected_text(format!("{}", uis.object_input_type))
+        .width(ui.available_width())
+        .show_ui(ui, |ui| {
+            for ty in ObjectInputType::ALL {
+                selectable_value(ui, &mut uis.object_input_type, ty);
+            }
+        });
+    uis.apply_object_input_type_change(previous_type);
+}
+
+/// Renders parameter controls for the active placement mode.
+fn placement_mode_conditions(ui: &mut egui::Ui, uis: &mut UiState) {
+    match uis.placement_mode {
+        PlacementMode::SolarSystem => condition_solar_system(ui, uis),
+        PlacementMode::SatelliteOrbit => condition_satellite_orbit(ui, uis),
+        PlacementMode::Manual => {}
+    }
+}
+
+/// Renders parameter controls for the active object-input type.
+fn object_input_type_conditions(ui: &mut egui::Ui, uis: &mut UiState) {
+    match uis.object_input_type {
+        ObjectInputType::RandomSphere => condition_random_sphere(ui, uis),
+        ObjectInputType::RandomCube => condition_random_cube(ui, uis),
+        ObjectInputType::SpiralDisk => condition_spiral_disk(ui, uis),
+        ObjectInputType::EllipticalOrbit => condition_elliptical_orbit(ui, uis),
+        ObjectInputType::SingleParticle => condition_single_particle(ui, uis),
+        ObjectInputType::KeplerianSystem => condition_keplerian_system(ui, uis),
+    }
+}
+
+/// Renders parameter controls for the random-sphere object input.
+fn condition_random_sphere(ui: &mut egui::Ui, uis: &mut UiState) {
+    dragvalue_normal(ui, &mut uis.random_sphere.radius, 1e9, "Sphere Radius (m)");
+    dragvalue_normal(
+        ui,
+        &mut uis.random_sphere.mass_range.0,
+        1e20,
+        "Mass Min (kg)",
+    );
+    dragvalue_normal(
+        ui,
+        &mut uis.random_sphere.mass_range.1,
+        1e20,
+        "Mass Max (kg)",
+    );
+    combobox_mass_function(ui, &mut uis.random_sphere.options.mass_function);
+    ui.add_enabled_ui(!uis.random_sphere.options.virial_equilibrium, |ui| {
+        dragvalue_normal(
+            ui,
+            &mut uis.random_sphere.velocity_std,
+            1e3,
+            "Velocity Std (m/s)",
+        );
+    });
+    if uis.show_advanced_controls {
+        cluster_options_controls(ui, &mut uis.random_sphere.options);
+    }
+    uis.clamp_velocity_inputs();
+}
+
+/// Renders parameter controls for the random-cube object input.
+fn condition_random_cube(ui: &mut egui::Ui, uis: &mut UiState) {
+    dragvalue_normal(ui, &mut uis.random_cube.cube_size, 1e3, "Cube Size (m)");
+    dragvalue_normal(ui, &mut uis.random_cube.mass_range.0, 1e20, "Mass Min (kg)");
+    dragvalue_normal(ui, &mut uis.random_cube.mass_range.1, 1e20, "Mass Max (kg)");
+    combobox_mass_function(ui, &mut uis.random_cube.options.mass_function);
+    ui.add_enabled_ui(!uis.random_cube.options.virial_equilibrium, |ui| {
+        dragvalue_normal(
+            ui,
+            &mut uis.random_cube.velocity_std,
+            1e3,
+            "Velocity Std (m/s)",
+        );
+    });
+    if uis.show_advanced_controls {
+        cluster_options_controls(ui, &mut uis.random_cube.options);
+    }
+    uis.clamp_velocity_inputs();
+}
+
+/// Renders the mass-function combo box used to sample random cluster masses.
+fn combobox_mass_function(ui: &mut egui::Ui, mass_function: &mut MassFunction) {
+    label_normal(ui, "Mass Function");
+    let id = ui.make_persistent_id("mass_function_combobox");
+    ComboBox::from_id_salt(id)
+        .selected_text(format!("{}", mass_function))
+        .width(ui.available_width())
+        .show_ui(ui, |ui| {
+            for function in MassFunction::ALL {
+                selectable_value(ui, mass_function, function);
+            }
+        });
+}
+
+/// Renders virial, spin, and bulk-velocity controls shared by the random cluster inputs.
+fn cluster_options_controls(ui: &mut egui::Ui, options: &mut RandomClusterOptions) {
+    ui.horizontal(|ui| {
+        ui.add(Checkbox::new(
+            &mut options.virial_equilibrium,
+            "Virial Equilibrium",
+        ));
+    });
+    dragvalue_normal(ui, &mut options.spin, 0.01, "Spin λ");
+    options.spin = options.spin.clamp(0.0, 1.0);
+    label_normal(ui, "Spin Axis");
+    dragvalue_normal(ui, &mut options.spin_axis.x, 0.1, "X");
+    dragvalue_normal(ui, &mut options.spin_axis.y, 0.1, "Y");
+    dragvalue_normal(ui, &mut options.spin_axis.z, 0.1, "Z");
+    label_normal(ui, "Bulk Velocity (m/s)");
+    dragvalue_normal(ui, &mut options.bulk_velocity.x, 1e3, "X");
+    dragvalue_normal(ui, &mut options.bulk_velocity.y, 1e3, "Y");
+    dragvalue_normal(ui, &mut options.bulk_velocity.z, 1e3, "Z");
+}
+
+/// Renders parameter controls for the spiral-disk object input.
+fn condition_spiral_disk(ui: &mut egui::Ui, uis: &mut UiState) {
+    dragvalue_normal(ui, &mut uis.spiral_disk.disk_radius, 1e7, "Disk Radius (m)");
+    dragvalue_normal(ui, &mut uis.spiral_disk.mass_fixed, 1e20, "Mass Fixed (kg)");
+}
+
+/// Renders start-date controls for the solar-system object input.
+fn condition_solar_system(ui: &mut egui::Ui, uis: &mut UiState) {
+    dragvalue_normal(ui, &mut uis.solar_system.start_year, 1, "Year");
+    dragvalue_normal(ui, &mut uis.solar_system.start_month, 1, "Month");
+    dragvalue_normal(ui, &mut uis.solar_system.start_day, 1, "Day");
+    dragvalue_normal(ui, &mut uis.solar_system.start_hour, 1, "Hour");
+}
+
+/// Renders parameter controls for the satellite-orbit object input.
+fn condition_satellite_orbit(ui: &mut egui::Ui, uis: &mut UiState) {
+    dragvalue_normal(
+        ui,
+        &mut uis.satellite_orbit.orbit_altitude_min,
+        1e3,
+        "Orbit Min (m)",
+    );
+    dragvalue_normal(
+        ui,
+        &mut uis.satellite_orbit.orbit_altitude_max,
+        1e3,
+        "Orbit Max (m)",
+    );
+    if let Some(range) = uis.satellite_count_slider() {
+        let response = slider_labeled_u32(
+            ui,
+            "Satellite Count",
+            &mut uis.satellite_orbit.satellite_count,
+            range,
+        );
+        apply_slider_double_click_reset(ui, &response, || {
+            uis.reset_satellite_count_to_default();
+        });
+    }
+}
+
+/// Renders parameter controls for the elliptical-orbit object input.
+fn condition_elliptical_orbit(ui: &mut egui::Ui, uis: &mut UiState) {
+    label_normal(ui, "Central Body");
+    dragvalue_normal(
+        ui,
+        &mut uis.elliptical_orbit.central_mass,
+        1e20,
+        "Mass (kg)",
+    );
+    label_normal(ui, "Planetary Body");
+    dragvalue_normal(
+        ui,
+        &mut uis.elliptical_orbit.planetary_mass,
+        1e20,
+        "Mass (kg)",
+    );
+    dragvalue_normal(
+        ui,
+        &mut uis.elliptical_orbit.planetary_speed,
+        1e3,
+        "Initial Speed (m/s)",
+    );
+    uis.clamp_velocity_inputs();
+    dragvalue_normal(
+        ui,
+        &mut uis.elliptical_orbit.planetary_distance,
+        1e7,
+        "Initial Distance (m)",
+    );
+}
+
+/// Renders parameter controls for the single-particle object input.
+fn condition_single_particle(ui: &mut egui::Ui, uis: &mut UiState) {
+    dragvalue_normal(ui, &mut uis.single_particle.mass, 1e20, "Mass (kg)");
+    label_normal(ui, "Position (m)");
+    dragvalue_normal(ui, &mut uis.single_particle.position.x, 1e9, "X");
+    dragvalue_normal(ui, &mut uis.single_particle.position.y, 1e9, "Y");
+    dragvalue_normal(ui, &mut uis.single_particle.position.z, 1e9, "Z");
+    label_normal(ui, "Velocity (m/s)");
+    dragvalue_normal(ui, &mut uis.single_particle.velocity.x, 1e3, "X");
+    dragvalue_normal(ui, &mut uis.single_particle.velocity.y, 1e3, "Y");
+    dragvalue_normal(ui, &mut uis.single_particle.velocity.z, 1e3, "Z");
+    uis.clamp_velocity_inputs();
+    ui.horizontal(|ui| {
+        label_normal(ui, "Color");
+        let id = ui.make_persistent_id("single_particle_color_combobox");
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ComboBox::from_id_salt(id)
+                .selected_text(format!("{}", uis.single_particle.color))
+                .width(90.0)
+                .show_ui(ui, |ui| {
+                    for color in ParticleBasicColor::ALL {
+                        selectable_value(ui, &mut uis.single_particle.color, color);
+                    }
+                });
+        });
+    });
+}
+
+/// Edits an angle stored in radians through a degree-valued drag field.
+fn dragvalue_degrees(ui: &mut egui::Ui, radians: &mut f64, prefix: &str) {
+    let mut degrees = radians.to_degrees();
+    dragvalue_normal(ui, &mut degrees, 1.0, prefix);
+    *radians = degrees.to_radians();
+}
+
+/// Renders primary mass plus per-body orbital elements for the Keplerian-system input.
+fn condition_keplerian_system(ui: &mut egui::Ui, uis: &mut UiState) {
+    label_normal(ui, "Primary Body");
+    dragvalue_normal(
+        ui,
+        &mut uis.keplerian_system.primary_mass,
+        1e20,
+        "Mass (kg)",
+    );
+    let mut remove_index = None;
+    for (index, body) in uis.keplerian_system.bodies.iter_mut().enumerate() {
+        ui.push_id(("keplerian_body", index), |ui| {
+            ui.separator();
+            label_normal(ui, &format!("Body {}", index + 1));
+            dragvalue_normal(ui, &mut body.mass, 1e20, "Mass (kg)");
+            let elements = &mut body.elements;
+            dragvalue_normal(ui, &mut elements.semi_major_axis, 1e9, "a (m)");
+            dragvalue_normal(ui, &mut elements.eccentricity, 0.01, "e");
+            elements.eccentricity = elements.eccentricity.max(0.0);
+            dragvalue_degrees(ui, &mut elements.inclination, "i (deg)");
+            dragvalue_degrees(ui, &mut elements.ascending_node, "Ω (deg)");
+            dragvalue_degrees(ui, &mut elements.argument_of_periapsis, "ω (deg)");
+            dragvalue_degrees(ui, &mut elements.true_anomaly, "ν (deg)");
+            if !elements.is_valid() {
+                label_normal(ui, "Invalid elements (body skipped)");
+            }
+            if button_normal(ui, "Remove Body", false).clicked() {
+                remove_index = Some(index);
+            }
+        });
+    }
+    if let Some(index) = remove_index {
+        uis.keplerian_system.remove_body(index);
+    }
+    ui.separator();
+    if button_normal(ui, "Add Body", false).clicked() {
+        uis.keplerian_system.push_body();
+    }
+}
+
+const ADD_CENTER_SLIDER_RANGE: std::ops::RangeInclusive<f64> = -10.0..=10.0;
+const ADD_CENTER_SLIDER_STEP: f64 = 0.01;
+
+fn add_center_axis_slider(value: &mut f64) -> Slider<'_> {
+    Slider::new(value, ADD_CENTER_SLIDER_RANGE.clone())
+        .step_by(ADD_CENTER_SLIDER_STEP)
+        .drag_value_speed(ADD_CENTER_SLIDER_STEP)
+}
+
+/// Renders X/Y/Z sliders as base-scale multipliers, converted via Correct.m like other inputs.
+fn slider_add_center(ui: &mut egui::Ui, uis: &mut UiState) {
+    label_normal(ui, "Add Center");
+    let row_width = ui.available_width();
+    let dbl_click = primary_double_click_pos(ui);
+    for (label, component) in [
+        ("X", &mut uis.add_center.x),
+        ("Y", &mut uis.add_center.y),
+        ("Z", &mut uis.add_center.z),
+    ] {
+        let response = slider_axis_row(ui, row_width, label, add_center_axis_slider(component));
+        apply_slider_double_click_reset_with_pos(&response, dbl_click, || {
+            *component = 0.0;
+        });
+    }
+}
+
+/// Draws add button and flags particle append when clicked.
+fn button_add_particles(ui: &mut egui::Ui, uis: &mut UiState, current_count: u32) {
+    let at_limit = uis.remaining_particle_capacity(current_count) == 0;
+    if at_limit {
+        label_normal(ui, "Particle limit reached");
+    }
+    ui.add_enabled_ui(
+        !at_limit && !uis.is_add_particles_requested && uis.is_add_particles_enabled,
+        |ui| {
+            if button_normal(ui, "Add", false).clicked() {
+                uis.base_scale = clamp_world_scale(uis.base_scale);
+                uis.object_input = uis.build_object_input();
+                uis.is_add_particles_requested = true;
+            }
+        },
+    );
+    if !uis.is_add_particles_enabled && !at_limit {
+        label_normal(ui, "Reset required");
+    }
+    if uis.is_add_particles_requested {
+        label_normal(ui, "Adding...");
+    }
+}
+
+/// Renders add-particle-count slider capped by remaining particle capacity.
+fn slider_add_particle_count(ui: &mut egui::Ui, uis: &mut UiState, current_count: u32) {
+    let remaining = uis.remaining_particle_capacity(current_count);
+    uis.clamp_add_particle_count_to_capacity(current_count);
+    if let Some(range) = UiState::add_particle_count_range(remaining) {
+        let response =
+            slider_labeled_u32(ui, "Add Particle Count", &mut uis.add_particle_count, range);
+        apply_slider_double_click_reset(ui, &response, || {
+            uis.reset_add_particle_count_to_default(current_count);
+        });
+    }
+}
+
+/// Renders the live particle-count slider; releasing it rescales the running simulation
+/// to the chosen count instead of resetting.
+fn slider_live_particle_count(ui: &mut egui::Ui, uis: &mut UiState, current_count: u32) {
+    if current_count == 0 {
+        return;
+    }
+    if uis.live_rescale_requested {
+        label_normal(ui, "Rescaling...");
+        return;
+    }
+    let max = current_count + uis.remaining_particle_capacity(current_count);
+    let mut target = uis.live_particle_target.unwrap_or(current_count);
+    let response = slider_labeled_u32(ui, "Live Particle Count", &mut target, 1..=max);
+    if response.changed() {
+        uis.live_particle_target = Some(target);
+    }
+    if response.drag_stopped() || (response.changed() && !response.dragged()) {
+        uis.live_rescale_requested = uis.live_particle_target.is_some();
+    }
+}
+
+/// Draws the hard and soft reset buttons and flags the chosen reset when clicked.
+fn button_reset(ui: &mut egui::Ui, uis: &mut UiState) {
+    ui.horizontal(|ui| {
+        let reset = button_normal(ui, "Reset", false);
+        mark_tutorial_target(ui.ctx(), uis, TutorialTarget::ResetButton, reset.rect);
+        if reset.clicked() {
+            uis.request_reset();
+        }
+        if button_normal(ui, "Soft Reset", false).clicked() {
+            uis.request_soft_reset();
+        }
+    });
+}
+
+/// Removes a particle scheduled for deletion from the Particle Info panel.
+pub(crate) fn process_pending_particle_delete(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+    gpu_particle_sync: &crate::GpuParticleSync,
+    need_redraw: &Arc<RwLock<bool>>,
+) {
+    let (index, uses_gpu) = {
+        let mut uis = ui_state.write().unwrap();
+        let Some(index) = uis.pending_delete_particle_index.take() else {
+            return;
+        };
+        if let Some(pipeline) = render_pipeline {
+            push_deletion_checkpoint(&mut uis, simulation_manager, pipeline, gpu_particle_sync);
+        }
+        (index, uis.uses_gpu_simulation())
+    };
+    if !simulation_manager.write().unwrap().remove_particle_at(index) {
+        return;
+    }
+    {
+        let mut uis = ui_state.write().unwrap();
+        uis.clear_selected_particle();
+        if uses_gpu {
+            gpu_particle_sync.request_remove_preserving(index);
+        }
+    }
+    *need_redraw.write().unwrap() = true;
+}
+
+/// Fits the scale gauge and camera to the live particle bounds when Fit View is pending.
+///
+/// Reads GPU particles when the buffer is authoritative; right after a reset the CPU copy
+/// is used instead because the full upload has not happened yet.
+pub(crate) fn process_pending_fit_view(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&mut ParticleRenderPipeline>,
+    gpu_particle_sync: &crate::GpuParticleSync,
+) {
+    let Some(pipeline) = render_pipeline else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    if !std::mem::take(&mut uis.fit_view_requested) {
+        return;
+    }
+    let mut particles = live_particles(&uis, simulation_manager, pipeline, gpu_particle_sync);
+    if uis.display_space != DisplaySpace::Position {
+        let kinematics = ViewKinematics::for_simulation(uis.active_simulation_type());
+        let light_speed = uis.light_speed() / uis.scale;
+        for particle in particles.iter_mut() {
+            let velocity = coordinate_velocity(particle, kinematics, light_speed);
+            particle.position = display_space_position(uis.display_space, velocity, light_speed)
+                .unwrap_or(particle.position);
+        }
+    }
+    let Some(sphere) = particle_bounding_sphere(&particles) else {
+        return;
+    };
+    uis.scale_gauge = fit_scale_gauge(sphere.radius);
+    let visual_scale = particle_visual_scale_factor(uis.scale_gauge);
+    pipeline.fit_camera_to_sphere(
+        sphere.center.as_vec3() * visual_scale,
+        sphere.radius as f32 * visual_scale,
+    );
+}
+
+/// Returns the authoritative particle list: GPU readback when the GPU buffer is current,
+/// otherwise the CPU copy (CPU mode, or a GPU upload still pending).
+fn live_particles(
+    uis: &UiState,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    pipeline: &ParticleRenderPipeline,
+    gpu_particle_sync: &crate::GpuParticleSync,
+) -> Vec<Particle> {
+    if uis.uses_gpu_simulation() && !gpu_particle_sync.has_pending_sync() {
+        pipeline.readback_particles(uis.active_simulation_type(), uis.scale)
+    } else {
+        simulation_manager.read().unwrap().particles()
+    }
+}
+
+/// Copies the live particles and the engine settings needed to restore them on undo.
+fn particle_checkpoint(
+    uis: &UiState,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    pipeline: &ParticleRenderPipeline,
+    gpu_particle_sync: &crate::GpuParticleSync,
+) -> ParticleCheckpoint {
+    ParticleCheckpoint {
+        particles: live_particles(uis, simulation_manager, pipeline, gpu_particle_sync),
+        simulation_type: uis.active_simulation_type(),
+        computing_unit: uis.active_computing_unit,
+        scale: uis.scale,
+        frame: uis.frame,
+        simulation_time: uis.simulation_time,
+        simulation_epoch: uis.simulation_epoch,
+        cosmic_time: simulation_manager.read().unwrap().cosmic_time(),
+    }
+}
+
+/// Records an undo step for a particle deletion that is about to happen.
+fn push_deletion_checkpoint(
+    uis: &mut UiState,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    pipeline: &ParticleRenderPipeline,
+    gpu_particle_sync: &crate::GpuParticleSync,
+) {
+    let checkpoint = particle_checkpoint(uis, simulation_manager, pipeline, gpu_particle_sync);
+    let parameters = uis.parameters();
+    uis.undo_history.push(UndoEntry {
+        parameters,
+        particles: Some(checkpoint),
+    });
+}
+
+/// Applies a pending undo or redo. The state being replaced is pushed onto the opposite
+/// stack; particle checkpoints are restored as-is and pushed with a full upload in GPU mode.
+pub(crate) fn process_pending_undo(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+    gpu_particle_sync: &crate::GpuParticleSync,
+    need_redraw: &Arc<RwLock<bool>>,
+) {
+    let Some(pipeline) = render_pipeline else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    let Some(direction) = uis.pending_undo.take() else {
+        return;
+    };
+    if uis.is_reset_requested || uis.is_add_particles_requested {
+        return;
+    }
+    let entry = match direction {
+        UndoDirection::Undo => uis.undo_history.take_undo(),
+        UndoDirection::Redo => uis.undo_history.take_redo(),
+    };
+    let Some(entry) = entry else {
+        return;
+    };
+    let replaced = UndoEntry {
+        parameters: uis.parameters(),
+        particles: entry
+            .particles
+            .as_ref()
+            .map(|_| particle_checkpoint(&uis, simulation_manager, pipeline, gpu_particle_sync)),
+    };
+    uis.apply_parameters(entry.parameters);
+    if let Some(checkpoint) = entry.particles {
+        simulation_manager.read().unwrap().restore_particles(
+            checkpoint.particles,
+            checkpoint.simulation_type,
+            checkpoint.scale,
+            checkpoint.cosmic_time,
+        );
+        uis.active_simulation_type = checkpoint.simulation_type;
+        uis.active_computing_unit = checkpoint.computing_unit;
+        uis.scale = checkpoint.scale;
+        uis.frame = checkpoint.frame;
+        uis.simulation_time = checkpoint.simulation_time;
+        uis.simulation_epoch = checkpoint.simulation_epoch;
+        if let Some((cosmology, _)) = checkpoint.cosmic_time {
+            uis.active_cosmology = cosmology;
+        }
+        uis.is_running = false;
+        uis.clear_selected_particle();
+        uis.region_statistics = None;
+        uis.invalidate_mass_profile();
+        uis.phase_space_frame = None;
+        uis.particle_groups.clear();
+        uis.request_particle_buffer_reload();
+    }
+    match direction {
+        UndoDirection::Undo => uis.undo_history.push_redo(replaced),
+        UndoDirection::Redo => uis.undo_history.push_after_redo(replaced),
+    }
+    *need_redraw.write().unwrap() = true;
+}
+
+/// Runs a Measure, Recolor, or Isolate request from the Region panel.
+///
+/// In GPU mode the edit is applied to a readback that replaces the CPU copy and is pushed
+/// back with a full upload; simulation time is left untouched.
+pub(crate) fn process_pending_region_action(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+    gpu_particle_sync: &crate::GpuParticleSync,
+    need_redraw: &Arc<RwLock<bool>>,
+) {
+    let Some(pipeline) = render_pipeline else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    let Some(action) = uis.pending_region_action.take() else {
+        return;
+    };
+    if action == RegionAction::Isolate {
+        push_deletion_checkpoint(&mut uis, simulation_manager, pipeline, gpu_particle_sync);
+    }
+    let region = uis.region;
+    let uses_gpu = uis.uses_gpu_simulation();
+    let manager = simulation_manager.read().unwrap();
+    let apply = |particles: &mut Vec<Particle>| {
+        let changed = apply_region_action(particles, &region, action);
+        (changed, region_statistics(particles, &region))
+    };
+    let (changed, statistics) = if uses_gpu && !gpu_particle_sync.has_pending_sync() {
+        let mut particles = pipeline.readback_particles(uis.active_simulation_type(), uis.scale);
+        let result = apply(&mut particles);
+        if result.0 {
+            manager.with_particles_mut(|current| *current = particles);
+        }
+        result
+    } else {
+        manager.with_particles_mut(apply)
+    };
+    drop(manager);
+    uis.region_statistics = Some(statistics);
+    if !changed {
+        return;
+    }
+    if let RegionAction::Dye(color) = action {
+        let frame = uis.frame;
+        uis.dye_injections.push(DyeInjection {
+            color,
+            count: statistics.count,
+            frame,
+        });
+    }
+    if action == RegionAction::Isolate {
+        uis.clear_selected_particle();
+    }
+    if uses_gpu {
+        gpu_particle_sync.request_full_upload();
+    }
+    *need_redraw.write().unwrap() = true;
+}
+
+/// Fires the scheduled burns whose time has come.
+///
+/// Burns land on the first frame at or after their time. In GPU mode they are applied to
+/// a readback that replaces the CPU copy and is pushed back with a full upload.
+pub(crate) fn process_due_maneuvers(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+    gpu_particle_sync: &crate::GpuParticleSync,
+    need_redraw: &Arc<RwLock<bool>>,
+) {
+    let Some(pipeline) = render_pipeline else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    let due = uis.take_due_maneuvers();
+    if due.is_empty() {
+        return;
+    }
+    let scale = uis.scale;
+    let uses_gpu = uis.uses_gpu_simulation();
+    let manager = simulation_manager.read().unwrap();
+    let apply = |particles: &mut Vec<Particle>| {
+        let mut applied = false;
+        for maneuver in &due {
+            applied |= apply_maneuver(particles, maneuver, scale);
+        }
+        applied
+    };
+    let applied = if uses_gpu && !gpu_particle_sync.has_pending_sync() {
+        let mut particles = pipeline.readback_particles(uis.active_simulation_type(), scale);
+        let applied = apply(&mut particles);
+        if applied {
+            manager.with_particles_mut(|current| *current = particles);
+        }
+        applied
+    } else {
+        manager.with_particles_mut(apply)
+    };
+    drop(manager);
+    if applied && uses_gpu {
+        gpu_particle_sync.request_full_upload();
+    }
+    *need_redraw.write().unwrap() = true;
+}
+
+/// Grows or shrinks the running simulation to the live slider's count, sampling new
+/// particles from the current ones or dropping the least-bound. Simulation time and the
+/// camera are kept; in GPU mode the edit goes through a readback and a full upload.
+pub(crate) fn process_pending_live_rescale(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+    gpu_particle_sync: &crate::GpuParticleSync,
+    need_redraw: &Arc<RwLock<bool>>,
+) {
+    let Some(pipeline) = render_pipeline else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    if !std::mem::take(&mut uis.live_rescale_requested) {
+        return;
+    }
+    let Some(target) = uis.live_particle_target.take() else {
+        return;
+    };
+    push_deletion_checkpoint(&mut uis, simulation_manager, pipeline, gpu_particle_sync);
+    let (simulation_type, scale) = (uis.active_simulation_type(), uis.scale);
+    let uses_gpu = uis.uses_gpu_simulation();
+    let manager = simulation_manager.read().unwrap();
+    let rescale = |particles: &mut Vec<Particle>| {
+        rescale_particle_count(particles, target as usize, simulation_type, scale)
+    };
+    let result = if uses_gpu && !gpu_particle_sync.has_pending_sync() {
+        let mut particles = pipeline.readback_particles(simulation_type, scale);
+        let result = rescale(&mut particles);
+        manager.with_particles_mut(|current| *current = particles);
+        result
+    } else {
+        manager.with_particles_mut(rescale)
+    };
+    drop(manager);
+    if result.added == 0 && result.removed == 0 {
+        return;
+    }
+    if result.removed > 0 {
+        uis.clear_selected_particle();
+    }
+    if uses_gpu {
+        gpu_particle_sync.request_full_upload();
+    }
+    *need_redraw.write().unwrap() = true;
+}
+
+/// Hands the running particles to the selected engine without a reset. The previous
+/// state is pushed as an undo step; in GPU mode the conversion runs on a readback that is
+/// pushed back with a full upload.
+pub(crate) fn process_pending_engine_switch(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+    gpu_particle_sync: &crate::GpuParticleSync,
+    need_redraw: &Arc<RwLock<bool>>,
+) {
+    let Some(pipeline) = render_pipeline else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    if !std::mem::take(&mut uis.engine_switch_requested) || !uis.can_switch_engine() {
+        return;
+    }
+    push_deletion_checkpoint(&mut uis, simulation_manager, pipeline, gpu_particle_sync);
+    let uses_gpu = uis.uses_gpu_simulation();
+    let manager = simulation_manager.read().unwrap();
+    if uses_gpu && !gpu_particle_sync.has_pending_sync() {
+        let particles = pipeline.readback_particles(uis.active_simulation_type(), uis.scale);
+        manager.with_particles_mut(|current| *current = particles);
+    }
+    manager.switch_simulation_type(uis.simulation_type, uis.scale);
+    drop(manager);
+    uis.active_simulation_type = uis.simulation_type;
+    uis.invalidate_mass_profile();
+    uis.phase_space_frame = None;
+    if uses_gpu {
+        gpu_particle_sync.request_full_upload();
+    }
+    *need_redraw.write().unwrap() = true;
+}
+
+/// Runs the friends-of-friends finder requested from the Groups panel and, when enabled,
+/// recolors particles by group (pushed to the GPU with a full upload in GPU mode).
+pub(crate) fn process_pending_group_finder(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+    gpu_particle_sync: &crate::GpuParticleSync,
+    need_redraw: &Arc<RwLock<bool>>,
+) {
+    let Some(pipeline) = render_pipeline else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    if !std::mem::take(&mut uis.group_finder_requested) {
+        return;
+    }
+    let (factor, min_members) = (uis.fof_linking_factor, uis.fof_min_members as usize);
+    let color_by_group = uis.fof_color_by_group;
+    let (sort_key, descending) = (uis.group_sort_key, uis.group_sort_descending);
+    let uses_gpu = uis.uses_gpu_simulation();
+    let manager = simulation_manager.read().unwrap();
+    let find = |particles: &mut Vec<Particle>| {
+        let Some(separation) = mean_interparticle_separation(particles) else {
+            return Vec::new();
+        };
+        let mut groups = friends_of_friends(particles, factor * separation, min_members);
+        if color_by_group {
+            color_particles_by_group(particles, &groups);
+        }
+        sort_groups(&mut groups, sort_key, descending);
+        groups
+    };
+    let groups = if uses_gpu && !gpu_particle_sync.has_pending_sync() {
+        let mut particles = pipeline.readback_particles(uis.active_simulation_type(), uis.scale);
+        let groups = find(&mut particles);
+        if color_by_group {
+            manager.with_particles_mut(|current| *current = particles);
+        }
+        groups
+    } else {
+        manager.with_particles_mut(find)
+    };
+    drop(manager);
+    uis.particle_groups = groups;
+    uis.particle_groups_frame = uis.frame;
+    if !color_by_group {
+        return;
+    }
+    if uses_gpu {
+        gpu_particle_sync.request_full_upload();
+    }
+    *need_redraw.write().unwrap() = true;
+}
+
+/// Paints the group queued by a Paint button in the Groups panel with the panel's paint
+/// color. In GPU mode the edit is applied to a readback and pushed back with a full upload.
+pub(crate) fn process_pending_group_recolor(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+    gpu_particle_sync: &crate::GpuParticleSync,
+    need_redraw: &Arc<RwLock<bool>>,
+) {
+    let Some(pipeline) = render_pipeline else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    let Some(number) = uis.pending_group_recolor.take() else {
+        return;
+    };
+    let Some(group) = uis.particle_groups.get(number) else {
+        return;
+    };
+    let [r, g, b] = uis.group_color;
+    let color = [r, g, b, 1.0];
+    let uses_gpu = uis.uses_gpu_simulation();
+    let manager = simulation_manager.read().unwrap();
+    let changed = if uses_gpu && !gpu_particle_sync.has_pending_sync() {
+        let mut particles = pipeline.readback_particles(uis.active_simulation_type(), uis.scale);
+        let changed = recolor_group(&mut particles, group, color);
+        if changed {
+            manager.with_particles_mut(|current| *current = particles);
+        }
+        changed
+    } else {
+        manager.with_particles_mut(|particles| recolor_group(particles, group, color))
+    };
+    drop(manager);
+    if !changed {
+        return;
+    }
+    if uses_gpu {
+        gpu_particle_sync.request_full_upload();
+    }
+    *need_redraw.write().unwrap() = true;
+}
+
+/// Recomputes the center of mass, Lagrangian radii, and escaping particles every
+/// `mass_profile_interval` frames while either overlay is shown.
+pub(crate) fn process_mass_profile_update(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+    gpu_particle_sync: &crate::GpuParticleSync,
+) {
+    let Some(pipeline) = render_pipeline else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    if !uis.mass_profile_update_due() {
+        return;
+    }
+    let particles = live_particles(&uis, simulation_manager, pipeline, gpu_particle_sync);
+    uis.mass_profile = mass_profile(&particles);
+    uis.escaper_positions = if uis.show_escaper_highlight {
+        escaping_particle_indices(&particles)
+            .into_iter()
+            .map(|index| particles[index].position)
+            .collect()
+    } else {
+        Vec::new()
+    };
+    uis.mass_profile_frame = Some(uis.frame);
+    uis.mass_profile_revision += 1;
+}
+
+/// Recomputes where particle worldlines cross the camera's past light cone when the camera
+/// moved or the update interval elapsed.
+pub(crate) fn process_light_cone_update(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+    gpu_particle_sync: &crate::GpuParticleSync,
+) {
+    let Some(pipeline) = render_pipeline else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    let observer = pipeline.camera_observer(uis.scale_gauge);
+    if !uis.light_cone_update_due(observer) {
+        return;
+    }
+    let particles = live_particles(&uis, simulation_manager, pipeline, gpu_particle_sync);
+    let light_speed = uis.light_speed() / uis.scale;
+    let kinematics = ViewKinematics::for_simulation(uis.active_simulation_type());
+    uis.light_cone = light_cone_crossings(&particles, kinematics, observer, light_speed);
+    uis.light_cone_depth = light_cone_depth(&uis.light_cone, light_speed);
+    uis.light_cone_observer = Some(observer);
+    uis.light_cone_frame = Some(uis.frame);
+    uis.light_cone_revision += 1;
+}
+
+/// Re-measures the bounds of the live particles for the minimap every few frames.
+pub(crate) fn process_minimap_update(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+    gpu_particle_sync: &crate::GpuParticleSync,
+) {
+    let Some(pipeline) = render_pipeline else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    if !uis.minimap_update_due() {
+        return;
+    }
+    let particles = live_particles(&uis, simulation_manager, pipeline, gpu_particle_sync);
+    uis.minimap_bounds = particle_bounding_sphere(&particles);
+    uis.minimap_frame = Some(uis.frame);
+}
+
+/// Turns the grid toward the selected plane, recomputing the invariant plane every few
+/// frames, and keeps frames coming while it turns.
+pub(crate) fn process_grid_alignment(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+    gpu_particle_sync: &crate::GpuParticleSync,
+    need_redraw: &Arc<RwLock<bool>>,
+) {
+    let Some(pipeline) = render_pipeline else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    if !uis.show_grid {
+        return;
+    }
+    let now = Instant::now();
+    if uis.grid_alignment_due() {
+        let angular_momentum = if uis.grid_alignment == GridAlignment::InvariantPlane {
+            let particles = live_particles(&uis, simulation_manager, pipeline, gpu_particle_sync);
+            total_angular_momentum(&particles)
+        } else {
+            glam::DVec3::ZERO
+        };
+        let normal = uis.grid_alignment.plane_normal(angular_momentum);
+        uis.grid_orientation.turn_to(grid_rotation(normal), now);
+        uis.grid_alignment_applied = Some((uis.grid_alignment, uis.frame));
+    }
+    uis.grid_orientation.update(now);
+    if uis.grid_orientation.is_turning() {
+        *need_redraw.write().unwrap() = true;
+    }
+}
+
+/// Re-predicts the selected particle's orbit around its primary every few frames, or at
+/// once when the selection or entered burn changes, and clears it when there is nothing
+/// to preview.
+pub(crate) fn process_orbit_preview_update(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+) {
+    let mut uis = ui_state.write().unwrap();
+    let selected = uis
+        .selected_particle
+        .filter(|_| uis.show_orbit_preview && uis.is_particle_info_panel_open)
+        .filter(|_| uis.active_simulation_type().supports_drag());
+    let Some(selected) = selected else {
+        uis.clear_orbit_preview();
+        return;
+    };
+    let burn = uis.orbit_preview_burn();
+    let inputs = (selected.index, burn);
+    if !uis.orbit_preview_update_due(inputs) {
+        return;
+    }
+    let bodies = {
+        let manager = simulation_manager.read().unwrap();
+        resolve_selected_particle_live(&mut uis, &manager, render_pipeline).and_then(
+            |(index, particle)| {
+                resolve_orbit_primary_live(&mut uis, &manager, render_pipeline, index)
+                    .map(|(_, primary)| (particle, primary))
+            },
+        )
+    };
+    let Some((particle, primary)) = bodies else {
+        uis.clear_orbit_preview();
+        return;
+    };
+    let scale = uis.scale;
+    uis.orbit_preview = predict_orbit(
+        (particle.position - primary.position) * scale,
+        (particle.velocity - primary.velocity) * scale,
+        gravitational_parameter(&particle, &primary, scale),
+        burn,
+    )
+    .into_iter()
+    .map(|offset| primary.position + offset / scale)
+    .collect();
+    uis.orbit_preview_inputs = Some(inputs);
+    uis.orbit_preview_frame = Some(uis.frame);
+    uis.orbit_preview_revision += 1;
+}
+
+/// Advances the Thomas precession demonstration by one rest-frame boost per frame while
+/// it runs and its panel is open.
+pub(crate) fn process_thomas_precession(ui_state: &Arc<RwLock<UiState>>) {
+    let mut uis = ui_state.write().unwrap();
+    if !uis.is_thomas_panel_open || !uis.thomas_running {
+        return;
+    }
+    uis.thomas_precession.step();
+    uis.thomas_revision += 1;
+}
+
+/// Samples the tracked particles' worldlines every
+/// [`WORLDLINE_INTERVAL`](crate::worldline::WORLDLINE_INTERVAL) frames while
+/// recording is on.
+pub(crate) fn process_worldline_recording(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+    gpu_particle_sync: &crate::GpuParticleSync,
+) {
+    let Some(pipeline) = render_pipeline else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    if !uis.worldline_sample_due() {
+        return;
+    }
+    let particles = live_particles(&uis, simulation_manager, pipeline, gpu_particle_sync);
+    let kinematics = ViewKinematics::for_simulation(uis.active_simulation_type());
+    let (time, light_speed) = (uis.simulation_time, uis.light_speed() / uis.scale);
+    let ids = uis.trajectory_ids.clone();
+    record_worldlines(
+        &mut uis.worldlines,
+        &ids,
+        &particles,
+        kinematics,
+        time,
+        light_speed,
+    );
+    uis.worldline_frame = Some(uis.frame);
+}
+
+/// Resamples the Phase Space panel scatter every few frames while the panel is open,
+/// always keeping the selected particle in the sample.
+pub(crate) fn process_phase_space_update(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+    gpu_particle_sync: &crate::GpuParticleSync,
+) {
+    let Some(pipeline) = render_pipeline else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    if !uis.phase_space_update_due() {
+        return;
+    }
+    let particles = live_particles(&uis, simulation_manager, pipeline, gpu_particle_sync);
+    uis.phase_space = sample_phase_space(
+        &particles,
+        uis.phase_space_x,
+        uis.phase_space_y,
+        uis.phase_space_max_points as usize,
+        uis.selected_particle.map(|s| s.index),
+    );
+    uis.phase_space_frame = Some(uis.frame);
+}
+
+/// Re-applies the memory budget to the particle count currently held in GPU buffers.
+pub(crate) fn process_memory_budget(
+    ui_state: &Arc<RwLock<UiState>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+) {
+    let Some(pipeline) = render_pipeline else {
+        return;
+    };
+    ui_state
+        .write()
+        .unwrap()
+        .apply_memory_budget(pipeline.gpu_particle_count());
+}
+
+/// Computes the power spectrum requested from the Power Spectrum panel.
+pub(crate) fn process_pending_power_spectrum(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+    gpu_particle_sync: &crate::GpuParticleSync,
+) {
+    let Some(pipeline) = render_pipeline else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    if !std::mem::take(&mut uis.power_spectrum_requested) {
+        return;
+    }
+    let particles = live_particles(&uis, simulation_manager, pipeline, gpu_particle_sync);
+    uis.power_spectrum = power_spectrum(&particles, uis.power_spectrum_grid);
+    uis.power_spectrum_frame = uis.frame;
+}
+
+/// Adds the font files picked in a native open dialog to the UI fonts.
+pub(crate) fn process_pending_font_dialog(window: &Window, ui_state: &Arc<RwLock<UiState>>) {
+    if !std::mem::take(&mut ui_state.write().unwrap().font_dialog_requested) {
+        return;
+    }
+    window.focus_window();
+    let Some(paths) = rfd::FileDialog::new()
+        .add_filter(FONT_FILTER_NAME, &FONT_FILTER_EXTS)
+        .set_parent(window)
+        .pick_files()
+    else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    for path in paths {
+        if !uis.ui_fonts.contains(&path) {
+            uis.ui_fonts.push(path);
+        }
+    }
+    uis.fonts_changed = true;
+}
+
+/// Applies the requested display mode to the window and refreshes the monitor list. The
+/// resize that follows recreates the swapchain.
+pub(crate) fn process_pending_display_mode(window: &Window, ui_state: &Arc<RwLock<UiState>>) {
+    let mode = {
+        let mut uis = ui_state.write().unwrap();
+        if !std::mem::take(&mut uis.display_mode_changed) && !uis.monitors.is_empty() {
+            return;
+        }
+        uis.display_mode.clone()
+    };
+    let (monitors, current_monitor) = list_monitors(window);
+    let fullscreen = fullscreen_for(window, &mode);
+    if fullscreen.is_some() || window.fullscreen().is_some() {
+        window.set_fullscreen(fullscreen);
+    }
+    let mut uis = ui_state.write().unwrap();
+    uis.monitors = monitors;
+    uis.current_monitor = current_monitor;
+}
+
+/// Writes the current power spectrum to a CSV file chosen in a native save dialog.
+pub(crate) fn process_pending_power_spectrum_export(
+    window: &Window,
+    ui_state: &Arc<RwLock<UiState>>,
+) {
+    let spectrum = {
+        let mut uis = ui_state.write().unwrap();
+        if !std::mem::take(&mut uis.power_spectrum_export_requested) {
+            return;
+        }
+        uis.power_spectrum.clone()
+    };
+    let Some(spectrum) = spectrum else {
+        return;
+    };
+    window.focus_window();
+    let Some(path) = rfd::FileDialog::new()
+        .add_filter(POWER_SPECTRUM_FILTER_NAME, &[POWER_SPECTRUM_FILTER_EXT])
+        .set_parent(window)
+        .set_file_name("power_spectrum.csv")
+        .save_file()
+    else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    if let Err(e) = uis
+        .export_writer
+        .submit(path, move |path| spectrum.save_csv(path))
+    {
+        uis.status
+            .error(format!("Failed to export power spectrum: {}", e));
+    }
+}
+
+/// Writes every recorded metric to a CSV file chosen in a native save dialog.
+pub(crate) fn process_pending_metrics_export(window: &Window, ui_state: &Arc<RwLock<UiState>>) {
+    let metrics = {
+        let mut uis = ui_state.write().unwrap();
+        if !std::mem::take(&mut uis.metrics_export_requested) {
+            return;
+        }
+        uis.metrics.clone()
+    };
+    window.focus_window();
+    let Some(path) = rfd::FileDialog::new()
+        .add_filter(METRICS_FILTER_NAME, &[METRICS_FILTER_EXT])
+        .set_parent(window)
+        .set_file_name("metrics.csv")
+        .save_file()
+    else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    if let Err(e) = uis
+        .export_writer
+        .submit(path, move |path| metrics.save_csv(path))
+    {
+        uis.status.error(format!("Failed to export metrics: {}", e));
+    }
+}
+
+/// Writes the chart of a pending chart export, as drawn (PNG) or as its samples (CSV), to
+/// a file chosen in a native save dialog.
+pub(crate) fn process_pending_plot_export(window: &Window, ui_state: &Arc<RwLock<UiState>>) {
+    let (chart, series, format) = {
+        let mut uis = ui_state.write().unwrap();
+        let Some((slot, format)) = uis.plot_export_request.take() else {
+            return;
+        };
+        let Some(chart) = uis.plot_charts.get(slot).cloned() else {
+            return;
+        };
+        let Some(series) = uis.metrics.series(&chart.metric).cloned() else {
+            return;
+        };
+        (chart, series, format)
+    };
+    let (filter_name, filter_ext) = match format {
+        PlotExportFormat::Png => (PLOT_PNG_FILTER_NAME, PLOT_PNG_FILTER_EXT),
+        PlotExportFormat::Csv => (PLOT_CSV_FILTER_NAME, PLOT_CSV_FILTER_EXT),
+    };
+    window.focus_window();
+    let Some(path) = rfd::FileDialog::new()
+        .add_filter(filter_name, &[filter_ext])
+        .set_parent(window)
+        .set_file_name(format!("{}.{}", chart.metric, filter_ext))
+        .save_file()
+    else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    let submitted = match format {
+        PlotExportFormat::Png => {
+            let rgb = render_plot_rgb(
+                &chart.axis_points(&series),
+                PLOT_IMAGE_WIDTH,
+                PLOT_IMAGE_HEIGHT,
+            );
+            uis.export_writer.submit(path, move |path| {
+                save_png(path, PLOT_IMAGE_WIDTH, PLOT_IMAGE_HEIGHT, &rgb)
+            })
+        }
+        PlotExportFormat::Csv => {
+            let csv = chart.to_csv(&series);
+            uis.export_writer
+                .submit(path, move |path| std::fs::write(path, csv))
+        }
+    };
+    if let Err(e) = submitted {
+        uis.status.error(format!("Failed to export chart: {}", e));
+    }
+}
+
+/// Starts a requested verification on a copy of the live particles (CPU Normal only).
+pub(crate) fn process_pending_verification(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+    gpu_particle_sync: &crate::GpuParticleSync,
+) {
+    let Some(pipeline) = render_pipeline else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    if !std::mem::take(&mut uis.verification_requested) || uis.verification_job.is_some() {
+        return;
+    }
+    if uis.active_simulation_type() != SimulationType::Normal {
+        uis.verification_error =
+            Some("The reference engine covers the Normal simulation type only".to_string());
+        return;
+    }
+    let particles = live_particles(&uis, simulation_manager, pipeline, gpu_particle_sync);
+    let config = uis.engine_config();
+    uis.verification_report = None;
+    uis.verification_error = None;
+    uis.verification_job = Some(VerificationJob::verify(
+        particles,
+        uis.time_per_frame,
+        uis.verification_steps,
+        config.summation,
+        config.precision,
+    ));
+}
+
+/// Collects the reports of finished verification and determinism audit jobs.
+pub(crate) fn process_verification_job(ui_state: &Arc<RwLock<UiState>>) {
+    let mut uis = ui_state.write().unwrap();
+    if let Some(job) = uis.verification_job.take_if(|job| job.is_finished()) {
+        match job.join() {
+            Ok(report) => uis.verification_report = Some(report),
+            Err(e) => uis.verification_error = Some(e),
+        }
+    }
+    if let Some(job) = uis.determinism_job.take_if(|job| job.is_finished()) {
+        match job.join() {
+            Ok(report) => uis.determinism_report = Some(report),
+            Err(e) => uis.determinism_error = Some(e),
+        }
+    }
+}
+
+/// Starts a requested determinism audit on a copy of the live particles.
+pub(crate) fn process_pending_determinism_audit(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+    gpu_particle_sync: &crate::GpuParticleSync,
+) {
+    let Some(pipeline) = render_pipeline else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    if !std::mem::take(&mut uis.determinism_requested) || uis.determinism_job.is_some() {
+        return;
+    }
+    let particles = live_particles(&uis, simulation_manager, pipeline, gpu_particle_sync);
+    let audit = DeterminismAudit {
+        simulation_type: uis.active_simulation_type(),
+        scale: uis.scale,
+        time_per_frame: uis.time_per_frame,
+        steps: uis.determinism_steps,
+        tolerance: uis.determinism_tolerance,
+        config: uis.engine_config(),
+    };
+    uis.determinism_report = None;
+    uis.determinism_error = None;
+    uis.determinism_job = Some(VerificationJob::audit(particles, audit));
+}
+
+/// Collects the results of a finished batch job.
+pub(crate) fn process_batch_job(ui_state: &Arc<RwLock<UiState>>) {
+    let mut uis = ui_state.write().unwrap();
+    if !uis.batch_job.as_ref().is_some_and(|job| job.is_finished()) {
+        return;
+    }
+    if let Some(job) = uis.batch_job.take() {
+        uis.batch_results = job.join();
+    }
+}
+
+/// Writes batch results to a CSV file chosen in a native save dialog.
+pub(crate) fn process_pending_batch_export(window: &Window, ui_state: &Arc<RwLock<UiState>>) {
+    let results = {
+        let mut uis = ui_state.write().unwrap();
+        if !std::mem::take(&mut uis.batch_export_requested) {
+            return;
+        }
+        uis.batch_results.clone()
+    };
+    window.focus_window();
+    let Some(path) = rfd::FileDialog::new()
+        .add_filter(BATCH_FILTER_NAME, &[BATCH_FILTER_EXT])
+        .set_parent(window)
+        .set_file_name("batch.csv")
+        .save_file()
+    else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    if let Err(e) = uis
+        .export_writer
+        .submit(path, move |path| save_batch_csv(path, &results))
+    {
+        uis.status
+            .error(format!("Failed to export batch results: {}", e));
+    }
+}
 
-fn add_center_axis_slider(value: &mut f64) -> Slider<'_> {
-    Slider::new(value, ADD_CENTER_SLIDER_RANGE.clone())
-        .step_by(ADD_CENTER_SLIDER_STEP)
-        .drag_value_speed(ADD_CENTER_SLIDER_STEP)
+/// Renders the requested still off screen and writes it to a PNG file chosen in a native
+/// save dialog.
+pub(crate) fn process_pending_still_render(
+    window: &Window,
+    ui_state: &Arc<RwLock<UiState>>,
+    vulkan_base: Option<&VulkanBase>,
+    render_pipeline: Option<&mut ParticleRenderPipeline>,
+) {
+    let (Some(base), Some(pipeline)) = (vulkan_base, render_pipeline) else {
+        return;
+    };
+    {
+        let mut uis = ui_state.write().unwrap();
+        if !std::mem::take(&mut uis.still_render_requested) {
+            return;
+        }
+    }
+    window.focus_window();
+    let Some(path) = rfd::FileDialog::new()
+        .add_filter(STILL_FILTER_NAME, &[STILL_FILTER_EXT])
+        .set_parent(window)
+        .set_file_name("still.png")
+        .save_file()
+    else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    let settings = uis.still_settings;
+    let rgb = match pipeline.render_still(
+        base,
+        settings,
+        uis.scale_gauge,
+        uis.link_point_size_to_scale,
+        uis.show_grid,
+        uis.particle_display_mode,
+    ) {
+        Ok(rgb) => rgb,
+        Err(e) => {
+            uis.status.error(format!("Failed to render still: {}", e));
+            return;
+        }
+    };
+    if let Err(e) = uis.export_writer.submit(path, move |path| {
+        save_png(path, settings.width, settings.height, &rgb)
+    }) {
+        uis.status.error(format!("Failed to export still: {}", e));
+    }
 }
 
-/// Renders X/Y/Z sliders as base-scale multipliers, converted via Correct.m like other inputs.
-fn slider_add_center(ui: &mut egui::Ui, uis: &mut UiState) {
-    label_normal(ui, "Add Center");
-    let row_width = ui.available_width();
-    let dbl_click = primary_double_click_pos(ui);
-    for (label, component) in [
-        ("X", &mut uis.add_center.x),
-        ("Y", &mut uis.add_center.y),
-        ("Z", &mut uis.add_center.z),
-    ] {
-        let response = slider_axis_row(ui, row_width, label, add_center_axis_slider(component));
-        apply_slider_double_click_reset_with_pos(&response, dbl_click, || {
-            *component = 0.0;
-        });
+/// Asks for a CSV path and starts recording the tracked trajectory IDs.
+pub(crate) fn process_pending_trajectory_start(window: &Window, ui_state: &Arc<RwLock<UiState>>) {
+    let ids = {
+        let mut uis = ui_state.write().unwrap();
+        if !std::mem::take(&mut uis.trajectory_start_requested) {
+            return;
+        }
+        uis.trajectory_ids.clone()
+    };
+    window.focus_window();
+    let Some(path) = rfd::FileDialog::new()
+        .add_filter(TRAJECTORY_FILTER_NAME, &[TRAJECTORY_FILTER_EXT])
+        .set_parent(window)
+        .set_file_name("trajectories.csv")
+        .save_file()
+    else {
+        return;
+    };
+    match TrajectoryRecorder::create(&path, &ids) {
+        Ok(recorder) => {
+            let mut uis = ui_state.write().unwrap();
+            uis.trajectory_recorder = Some(recorder);
+            uis.trajectory_record_frame = None;
+        }
+        Err(e) => ui_state
+            .write()
+            .unwrap()
+            .status
+            .error(format!("Failed to start trajectory export: {}", e)),
     }
 }
 
-/// Draws add button and flags particle append when clicked.
-fn button_add_particles(ui: &mut egui::Ui, uis: &mut UiState, current_count: u32) {
-    let at_limit = uis.remaining_particle_capacity(current_count) == 0;
-    if at_limit {
-        label_normal(ui, "Particle limit reached");
+/// Appends a trajectory sample every `trajectory_interval` frames while recording.
+///
+/// Samples are taken from the UI thread, so in CPU mode a fast worker may advance several
+/// frames between samples; each row carries its simulation time.
+pub(crate) fn process_trajectory_recording(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+    gpu_particle_sync: &crate::GpuParticleSync,
+) {
+    let Some(pipeline) = render_pipeline else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    if !uis.trajectory_sample_due() {
+        return;
     }
-    ui.add_enabled_ui(
-        !at_limit && !uis.is_add_particles_requested && uis.is_add_particles_enabled,
-        |ui| {
-            if button_normal(ui, "Add", false).clicked() {
-                uis.base_scale = clamp_world_scale(uis.base_scale);
-                uis.object_input = uis.build_object_input();
-                uis.is_add_particles_requested = true;
-            }
-        },
-    );
-    if !uis.is_add_particles_enabled && !at_limit {
-        label_normal(ui, "Reset required");
+    let particles = live_particles(&uis, simulation_manager, pipeline, gpu_particle_sync);
+    let time = uis.simulation_time;
+    let result = match uis.trajectory_recorder.as_mut() {
+        Some(recorder) => recorder.record(time, &particles),
+        None => return,
+    };
+    uis.trajectory_record_frame = Some(uis.frame);
+    if let Err(e) = result {
+        uis.status
+            .error(format!("Failed to write trajectory sample: {}", e));
+        uis.stop_trajectory_recording();
     }
-    if uis.is_add_particles_requested {
-        label_normal(ui, "Adding...");
+}
+
+/// Evaluates armed event triggers once per simulation frame and runs their actions.
+///
+/// Triggers are checked on the UI thread, so a fast CPU worker may run a few frames past
+/// the moment a condition first held before a Pause takes effect.
+pub(crate) fn process_event_triggers(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+    gpu_particle_sync: &crate::GpuParticleSync,
+) {
+    let Some(pipeline) = render_pipeline else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    let armed: Vec<EventCondition> = uis
+        .event_triggers
+        .iter()
+        .filter(|t| t.is_armed())
+        .map(|t| t.condition)
+        .collect();
+    if armed.is_empty() || uis.event_check_frame == Some(uis.frame) {
+        return;
+    }
+    uis.event_check_frame = Some(uis.frame);
+    let particles = if armed.iter().any(|c| c.needs_particles()) {
+        live_particles(&uis, simulation_manager, pipeline, gpu_particle_sync)
+    } else {
+        Vec::new()
+    };
+    let energy = armed
+        .iter()
+        .any(|c| c.kind() == EventConditionKind::EnergyDrift)
+        .then(|| total_energy(&particles));
+    if uis.event_reference_energy.is_none() {
+        uis.event_reference_energy = energy;
+    }
+    let context = EventContext {
+        time: uis.simulation_time,
+        particles: &particles,
+        reference_energy: uis.event_reference_energy,
+        energy,
+    };
+    let fired = fire_triggers(&mut uis.event_triggers, &context);
+    for index in fired {
+        let trigger = uis.event_triggers[index];
+        let frame = uis.frame;
+        let outcome = match trigger.action {
+            EventAction::Pause => {
+                uis.is_running = false;
+                "paused".to_string()
+            }
+            EventAction::Snapshot => {
+                let path = std::path::Path::new(&uis.event_snapshot_dir)
+                    .join(format!("event_frame_{}.zip", frame));
+                let particles = if particles.is_empty() {
+                    live_particles(&uis, simulation_manager, pipeline, gpu_particle_sync)
+                } else {
+                    particles.clone()
+                };
+                let snapshot =
+                    ParticleSnapshot::new(uis.active_simulation_type(), uis.scale, particles);
+                let queued = format!("queued {}", path.display());
+                match uis
+                    .export_writer
+                    .submit(path, move |path| snapshot.save(path))
+                {
+                    Ok(()) => queued,
+                    Err(e) => format!("snapshot failed: {}", e),
+                }
+            }
+            EventAction::Log => "logged".to_string(),
+        };
+        let line = format!("frame {}: {} → {}", frame, trigger.condition, outcome);
+        uis.push_event_log(line);
     }
 }
 
-/// Renders add-particle-count slider capped by remaining particle capacity.
-fn slider_add_particle_count(ui: &mut egui::Ui, uis: &mut UiState, current_count: u32) {
-    let remaining = uis.remaining_particle_capacity(current_count);
-    uis.clamp_add_particle_count_to_capacity(current_count);
-    if let Some(range) = UiState::add_particle_count_range(remaining) {
-        let response =
-            slider_labeled_u32(ui, "Add Particle Count", &mut uis.add_particle_count, range);
-        apply_slider_double_click_reset(ui, &response, || {
-            uis.reset_add_particle_count_to_default(current_count);
-        });
+/// Refreshes the scenario details a crash report would include, and while the simulation
+/// runs, writes a checkpoint snapshot every [`CHECKPOINT_INTERVAL`] for recovery after a
+/// crash.
+pub(crate) fn process_checkpoint(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+) {
+    let now = Instant::now();
+    let mut uis = ui_state.write().unwrap();
+    if uis
+        .crash_context_updated
+        .is_none_or(|updated| now.duration_since(updated) >= CRASH_CONTEXT_INTERVAL)
+    {
+        let particle_count = simulation_manager.read().unwrap().particle_count() as usize;
+        set_crash_context(uis.crash_context(particle_count));
+        uis.crash_context_updated = Some(now);
+    }
+    if !uis.is_running {
+        return;
+    }
+    let Some(last) = uis.last_checkpoint else {
+        // The first interval starts when the simulation first runs.
+        uis.last_checkpoint = Some(now);
+        return;
+    };
+    if now.duration_since(last) < CHECKPOINT_INTERVAL {
+        return;
+    }
+    uis.last_checkpoint = Some(now);
+    let path = match AppSettings::app_dir() {
+        Ok(dir) => dir.join(CHECKPOINT_FILE),
+        Err(e) => {
+            uis.status
+                .error(format!("Failed to locate the checkpoint: {}", e));
+            return;
+        }
+    };
+    let snapshot = current_snapshot(&uis, simulation_manager, render_pipeline);
+    if let Err(e) = uis
+        .export_writer
+        .submit(path, move |path| snapshot.save(path))
+    {
+        uis.status
+            .error(format!("Failed to write checkpoint: {}", e));
     }
 }
 
-/// Draws reset button and flags simulation reset when clicked.
-fn button_reset(ui: &mut egui::Ui, uis: &mut UiState) {
-    if button_normal(ui, "Reset", false).clicked() {
-        uis.request_reset();
+/// Measures the total energy every `ENERGY_CHECK_INTERVAL` frames for the drift warning,
+/// while the simulation should conserve it and is small enough for the O(N²) sum.
+pub(crate) fn process_energy_monitor(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+    gpu_particle_sync: &crate::GpuParticleSync,
+) {
+    let Some(pipeline) = render_pipeline else {
+        return;
+    };
+    let mut uis = ui_state.write().unwrap();
+    if !uis.energy_monitor.is_due(uis.frame) {
+        return;
+    }
+    if !uis.conserves_energy() {
+        uis.energy_monitor.forget();
+        return;
+    }
+    if simulation_manager.read().unwrap().particle_count() as usize > ENERGY_MONITOR_MAX_PARTICLES {
+        return;
+    }
+    let particles = live_particles(&uis, simulation_manager, pipeline, gpu_particle_sync);
+    let live_count = particles.iter().filter(|p| p.color[3] != 0.0).count();
+    let frame = uis.frame;
+    let time = uis.simulation_time;
+    let (kinetic, potential) = energy_components(&particles);
+    let energy = kinetic + potential;
+    uis.energy_monitor.record(frame, live_count, energy);
+    uis.metrics.record(KINETIC_ENERGY, time, kinetic);
+    uis.metrics.record(POTENTIAL_ENERGY, time, potential);
+    uis.metrics.record(TOTAL_ENERGY, time, energy);
+    if let Some(drift) = uis.energy_monitor.drift() {
+        uis.metrics.record(ENERGY_DRIFT, time, drift);
     }
 }
 
-/// Removes a particle scheduled for deletion from the Particle Info panel.
-pub(crate) fn process_pending_particle_delete(
+/// Samples the metrics charted in the Plots window every
+/// [`crate::metrics::METRIC_SAMPLE_INTERVAL`] frames: frame rate, step time, total
+/// momentum, and the selected particle's radius.
+/// Momentum is skipped above [`MOMENTUM_SAMPLE_MAX_PARTICLES`].
+pub(crate) fn process_metric_sampling(
     ui_state: &Arc<RwLock<UiState>>,
     simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
     gpu_particle_sync: &crate::GpuParticleSync,
-    need_redraw: &Arc<RwLock<bool>>,
 ) {
-    let (index, uses_gpu) = {
-        let mut uis = ui_state.write().unwrap();
-        let Some(index) = uis.pending_delete_particle_index.take() else {
-            return;
-        };
-        (index, uis.uses_gpu_simulation())
+    let Some(pipeline) = render_pipeline else {
+        return;
     };
-    if !simulation_manager.write().unwrap().remove_particle_at(index) {
+    let mut uis = ui_state.write().unwrap();
+    // One snapshot, so the step time never pairs the runtime of one step with another's
+    // frame.
+    let clock = uis.clock.snapshot();
+    if !uis.metric_sampler.is_due(clock.frame) {
         return;
     }
+    let time = clock.simulation_time;
+    let step_time = uis.metric_sampler.sample(clock.frame, clock.wall_runtime);
+    uis.metrics.record(FPS, time, clock.fps as f64);
+    if let Some(step_time) = step_time {
+        uis.metrics.record(STEP_TIME, time, step_time);
+    }
+    let particle_count = simulation_manager.read().unwrap().particle_count() as usize;
+    if particle_count <= MOMENTUM_SAMPLE_MAX_PARTICLES {
+        let particles = live_particles(&uis, simulation_manager, pipeline, gpu_particle_sync);
+        let momentum = total_momentum(&particles).length();
+        uis.metrics.record(TOTAL_MOMENTUM, time, momentum);
+    }
+    if let Some(index) = uis
+        .selected_particle
+        .as_ref()
+        .map(|selected| selected.index)
     {
-        let mut uis = ui_state.write().unwrap();
-        uis.clear_selected_particle();
-        if uses_gpu {
-            gpu_particle_sync.request_remove_preserving(index);
+        let particle = if uis.uses_gpu_simulation() {
+            pipeline.read_particle_at(index, uis.active_simulation_type(), uis.scale)
+        } else {
+            simulation_manager.read().unwrap().particle_at(index)
+        };
+        if let Some(particle) = particle.filter(|particle| particle.color[3] != 0.0) {
+            uis.metrics
+                .record(SELECTED_RADIUS, time, particle.position.length());
         }
     }
-    *need_redraw.write().unwrap() = true;
+}
+
+/// Queues the snapshot requested when a finite run reaches its end time, into the event
+/// snapshot directory. In GPU mode it waits until the steps queued before the stop have run.
+pub(crate) fn process_run_end_snapshot(
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&ParticleRenderPipeline>,
+    gpu_particle_sync: &crate::GpuParticleSync,
+) {
+    let mut uis = ui_state.write().unwrap();
+    if !uis.run_end_snapshot_requested || gpu_particle_sync.has_pending_advance_steps() {
+        return;
+    }
+    uis.run_end_snapshot_requested = false;
+    let path = Path::new(&uis.event_snapshot_dir).join(run_end_snapshot_file_name(uis.frame));
+    let snapshot = current_snapshot(&uis, simulation_manager, render_pipeline);
+    let queued = format!("Saving {}", path.display());
+    match uis
+        .export_writer
+        .submit(path, move |path| snapshot.save(path))
+    {
+        Ok(()) => uis.status.info(queued),
+        Err(e) => uis
+            .status
+            .error(format!("Failed to save the run end snapshot: {}", e)),
+    }
 }
 
 /// Opens a deferred save/load dialog after the UI frame completes.
@@ -1022,6 +6060,19 @@ pub fn process_pending_snapshot_dialog(
         PendingSnapshotDialog::Load => {
             load_particles(window, ui_state, simulation_manager, need_redraw);
         }
+        PendingSnapshotDialog::LoadCheckpoint => match AppSettings::app_dir() {
+            Ok(dir) => load_particles_from(
+                &dir.join(CHECKPOINT_FILE),
+                ui_state,
+                simulation_manager,
+                need_redraw,
+            ),
+            Err(e) => ui_state
+                .write()
+                .unwrap()
+                .status
+                .error(format!("Failed to locate the checkpoint: {}", e)),
+        },
     }
 }
 
@@ -1045,7 +6096,22 @@ fn save_particles(
     else {
         return;
     };
-    let uis = ui_state.read().unwrap();
+    let mut uis = ui_state.write().unwrap();
+    let snapshot = current_snapshot(&uis, simulation_manager, render_pipeline);
+    if let Err(e) = uis
+        .export_writer
+        .submit(path, move |path| snapshot.save(path))
+    {
+        uis.status.error(format!("Failed to save particles: {}", e));
+    }
+}
+
+/// Captures the live particles and the scenario state a snapshot restores.
+fn current_snapshot(
+    uis: &UiState,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    render_pipeline: Option<&crate::pipeline::ParticleRenderPipeline>,
+) -> ParticleSnapshot {
     let particles = if uis.uses_gpu_simulation() {
         render_pipeline
             .map(|pipeline| pipeline.readback_particles(uis.active_simulation_type(), uis.scale))
@@ -1053,10 +6119,11 @@ fn save_particles(
     } else {
         simulation_manager.read().unwrap().particles()
     };
-    let snapshot = ParticleSnapshot::new(uis.active_simulation_type(), uis.scale, particles);
-    if let Err(e) = snapshot.save(&path) {
-        eprintln!("Failed to save particles: {}", e);
-    }
+    let mut snapshot = ParticleSnapshot::new(uis.active_simulation_type(), uis.scale, particles);
+    snapshot.drag = uis.drag;
+    snapshot.mass_rules = uis.mass_rules.clone();
+    snapshot.annotations = uis.annotations.clone();
+    snapshot
 }
 
 /// Loads particles from a zip snapshot and restores them as the initial state.
@@ -1069,20 +6136,35 @@ fn load_particles(
     let Some(path) = snapshot_file_dialog(window).pick_file() else {
         return;
     };
-    let snapshot = match ParticleSnapshot::load(&path) {
+    load_particles_from(&path, ui_state, simulation_manager, need_redraw);
+}
+
+/// Loads particles from the zip snapshot at `path` and restores them as the initial state.
+fn load_particles_from(
+    path: &Path,
+    ui_state: &Arc<RwLock<UiState>>,
+    simulation_manager: &Arc<RwLock<SimulationManager>>,
+    need_redraw: &Arc<RwLock<bool>>,
+) {
+    let snapshot = match ParticleSnapshot::load(path) {
         Ok(snapshot) => snapshot,
         Err(e) => {
-            eprintln!("Failed to load particles: {}", e);
+            ui_state
+                .write()
+                .unwrap()
+                .status
+                .error(format!("Failed to load particles: {}", e));
             return;
         }
     };
     let mut uis = ui_state.write().unwrap();
     if snapshot.particles.len() > uis.max_particle_count as usize {
-        eprintln!(
+        let message = format!(
             "Particle count {} exceeds maximum {}",
             snapshot.particles.len(),
             uis.max_particle_count
         );
+        uis.status.error(message);
         return;
     }
     uis.simulation_type = snapshot.simulation_type;
@@ -1090,18 +6172,62 @@ fn load_particles(
     let scale = clamp_world_scale(snapshot.scale);
     uis.scale = scale;
     uis.apply_external_base_scale(scale);
+    uis.drag = snapshot.drag;
+    uis.mass_rules = snapshot.mass_rules.clone();
+    uis.annotations = snapshot.annotations.clone();
     uis.frame = 1;
     uis.simulation_time = 0.0;
+    uis.simulation_epoch = None;
     uis.is_running = false;
     uis.clear_selected_particle();
+    let particle_count = snapshot.particles.len();
     simulation_manager
         .write()
         .unwrap()
         .load_from_snapshot(snapshot);
     uis.request_particle_buffer_reload();
+    uis.status.info(format!(
+        "Loaded {} particles from {}",
+        particle_count,
+        path.display()
+    ));
     *need_redraw.write().unwrap() = true;
 }
 
+/// Shows the background export queue: the file being written and the last outcome.
+fn export_status(ui: &mut egui::Ui, status: &ExportStatus) {
+    if let Some(path) = &status.current {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        ui.horizontal(|ui| {
+            ui.spinner();
+            label_normal(ui, &format!("Writing {} ({} pending)", name, status.pending));
+        });
+    }
+    match &status.last_result {
+        Some(Ok(path)) if status.current.is_none() => {
+            label_normal(ui, &format!("Saved {}", path.display()));
+        }
+        Some(Err(error)) => {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        _ => {}
+    }
+}
+
+/// Shows the last memory-budget reduction or overrun with a button to dismiss it.
+fn memory_budget_warning(ui: &mut egui::Ui, uis: &mut UiState) {
+    let Some(warning) = &uis.memory_warning else {
+        return;
+    };
+    ui.colored_label(ui.visuals().warn_fg_color, warning);
+    if button_normal(ui, "Dismiss", false).clicked() {
+        uis.memory_warning = None;
+    }
+}
+
 /// Renders particle display mode combo box in the Settings panel.
 fn combobox_particle_display_mode(ui: &mut egui::Ui, uis: &mut UiState) {
     ui.horizontal(|ui| {
@@ -1119,3 +6245,62 @@ fn combobox_particle_display_mode(ui: &mut egui::Ui, uis: &mut UiState) {
         });
     });
 }
+
+/// Renders the display space combo box in the Settings panel; switching fits the view to
+/// the particles in the new space.
+fn combobox_display_space(ui: &mut egui::Ui, uis: &mut UiState) {
+    let previous = uis.display_space;
+    ui.horizontal(|ui| {
+        label_normal(ui, "Plot Particles At");
+        let id = ui.make_persistent_id("display_space_combobox");
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ComboBox::from_id_salt(id)
+                .selected_text(format!("{}", uis.display_space))
+                .width(90.0)
+                .show_ui(ui, |ui| {
+                    for space in DisplaySpace::ALL {
+                        selectable_value(ui, &mut uis.display_space, space);
+                    }
+                });
+        });
+    });
+    if uis.display_space != previous {
+        uis.fit_view_requested = true;
+    }
+}
+
+/// Renders the scale slider mode combo box in the Settings panel.
+fn combobox_scale_gauge_mode(ui: &mut egui::Ui, uis: &mut UiState) {
+    ui.horizontal(|ui| {
+        label_normal(ui, "Scale Slider");
+        let id = ui.make_persistent_id("scale_gauge_mode_combobox");
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ComboBox::from_id_salt(id)
+                .selected_text(format!("{}", uis.scale_gauge_mode))
+                .width(90.0)
+                .show_ui(ui, |ui| {
+                    for mode in ScaleGaugeMode::ALL {
+                        selectable_value(ui, &mut uis.scale_gauge_mode, mode);
+                    }
+                });
+        });
+    });
+}
+
+/// Renders the simulation-time display unit combo box in the Settings panel.
+fn combobox_time_display_unit(ui: &mut egui::Ui, uis: &mut UiState) {
+    ui.horizontal(|ui| {
+        label_normal(ui, "Time Display");
+        let id = ui.make_persistent_id("time_display_unit_combobox");
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ComboBox::from_id_salt(id)
+                .selected_text(format!("{}", uis.time_display_unit))
+                .width(90.0)
+                .show_ui(ui, |ui| {
+                    for unit in TimeDisplayUnit::ALL {
+                        selectable_value(ui, &mut uis.time_display_unit, unit);
+                    }
+                });
+        });
+    });
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+pub const BEGINNER_PROFILE: &str = "Beginner";
+pub const ADVANCED_PROFILE: &str = "Advanced";
+pub const DEFAULT_UI_PROFILE: &str = ADVANCED_PROFILE;
+
+/// A named UI configuration: whether expert controls are shown, which panels are open,
+/// and the view toggles.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct UiProfile {
+    pub name: String,
+    /// Show solver tuning (force summation, precision, pipelining, galaxy culling),
+    /// cluster generation options, and the analysis panels.
+    pub show_advanced: bool,
+    /// Labels of the open panels.
+    pub open_panels: Vec<String>,
+    pub show_grid: bool,
+    pub show_axis_labels: bool,
+    pub show_minimap: bool,
+    pub split_view: bool,
+    pub show_body_labels: bool,
+    pub lock_camera_up: bool,
+}
+
+impl Default for UiProfile {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            show_advanced: true,
+            open_panels: vec!["Simulation".to_string()],
+            show_grid: true,
+            show_axis_labels: false,
+            show_minimap: false,
+            split_view: false,
+            show_body_labels: true,
+            lock_camera_up: true,
+        }
+    }
+}
+
+/// Built-in profiles: an uncluttered one for classroom demos and one showing everything.
+pub fn builtin_ui_profiles() -> [UiProfile; 2] {
+    [
+        UiProfile {
+            name: BEGINNER_PROFILE.to_string(),
+            show_advanced: false,
+            open_panels: vec!["Simulation".to_string(), "Object Input".to_string()],
+            show_axis_labels: true,
+            ..UiProfile::default()
+        },
+        UiProfile {
+            name: ADVANCED_PROFILE.to_string(),
+            ..UiProfile::default()
+        },
+    ]
+}
+
+/// Returns the profile named `name`, looking at the saved profiles before the built-ins.
+pub fn find_ui_profile(saved: &[UiProfile], name: &str) -> Option<UiProfile> {
+    saved
+        .iter()
+        .cloned()
+        .chain(builtin_ui_profiles())
+        .find(|profile| profile.name == name)
+}
+
+/// Returns whether `name` belongs to a built-in profile, which cannot be overwritten.
+pub fn is_builtin_ui_profile(name: &str) -> bool {
+    builtin_ui_profiles()
+        .iter()
+        .any(|profile| profile.name == name)
+}
+
+/// Names of the built-in profiles followed by the saved ones.
+pub fn ui_profile_names(saved: &[UiProfile]) -> Vec<String> {
+    builtin_ui_profiles()
+        .into_iter()
+        .chain(saved.iter().cloned())
+        .map(|profile| profile.name)
+        .collect()
+}
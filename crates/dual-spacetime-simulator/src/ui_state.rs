@@ -1,16 +1,100 @@
+use crate::annotations::{Annotation, AnnotationTarget};
+use crate::batch_runner::{
+    BatchConfig, BatchJob, BatchRunSummary, DEFAULT_BATCH_DURATION, DEFAULT_BATCH_TIME_STEPS,
+    parse_value_list,
+};
+use crate::block_steps::DEFAULT_BLOCK_STEP_ACCURACY;
+use crate::close_encounter::{DEFAULT_STEPS_PER_ENCOUNTER, Encounter};
+use crate::container::ContainerWalls;
+use crate::cosmology::Cosmology;
+use crate::crash_report::CrashReport;
+use crate::display_mode::{DisplayMode, MonitorInfo};
+use crate::drag::{DragForce, DragModel};
+use crate::energy_monitor::EnergyMonitor;
+use crate::events::{
+    DEFAULT_EVENT_SNAPSHOT_DIR, EVENT_LOG_CAPACITY, EventAction, EventConditionKind, EventTrigger,
+};
+use crate::export_writer::ExportWriter;
+use crate::fast_multipole::DEFAULT_EXPANSION_ORDER;
+use crate::grid_alignment::{GRID_ALIGNMENT_INTERVAL, GridAlignment, GridOrientation};
+use crate::group_finder::{
+    DEFAULT_LINKING_LENGTH_FACTOR, DEFAULT_MIN_GROUP_MEMBERS, GroupSortKey, ParticleGroup,
+};
+use crate::kepler_binary::{DEFAULT_STEPS_PER_ORBIT, KeplerPair};
+use crate::kiosk::{CameraStep, Kiosk, KioskStep};
+use crate::langevin::LangevinNoise;
+use crate::light_cone::{LIGHT_CONE_INTERVAL, LightConeCrossing};
+use crate::maneuver::Maneuver;
+use crate::mass_evolution::{MassRule, ParticleMassRule};
+use crate::mass_profile::{DEFAULT_MASS_PROFILE_INTERVAL, MassProfile};
+use crate::memory_budget::{
+    DEFAULT_MEMORY_BUDGET_MB, MemoryDemand, MemoryUsage, budget_bytes, plan_within_budget,
+};
+use crate::metrics::{MetricSampler, MetricsStore, TOTAL_ENERGY};
+use crate::minimap::MINIMAP_INTERVAL;
 use crate::object_input::{
-    MIN_WORLD_SCALE, ObjectInput, ObjectInputType, ParticleBasicColor, SATELLITE_ORBIT_SCALE,
-    SOLAR_SYSTEM_SCALE, clamp_world_scale,
+    MIN_WORLD_SCALE, ObjectInput, ObjectInputType, ParticleBasicColor, RandomClusterOptions,
+    SATELLITE_ORBIT_SCALE, SOLAR_SYSTEM_SCALE, clamp_world_scale,
+};
+use crate::orbit_preview::{ORBIT_PREVIEW_INTERVAL, PreviewBurn};
+use crate::orbital_elements::{OrbitalElements, OrbitingBody};
+use crate::palette::{DEFAULT_PARTICLE_PALETTE, PaletteSettings};
+use crate::phase_space::{
+    DEFAULT_PHASE_SPACE_MAX_POINTS, PHASE_SPACE_REFRESH_FRAMES, PhaseSpacePoints,
+    PhaseSpaceQuantity,
 };
+use crate::plots::{PlotChart, PlotExportFormat};
+use crate::power_spectrum::{DEFAULT_POWER_SPECTRUM_GRID, PowerSpectrum};
+use crate::region_selection::{DyeInjection, RegionAction, RegionStatistics, SelectionRegion};
+use crate::repaint::{INDICATOR_REFRESH_INTERVAL, Throttled};
+use crate::rest_frame::FrameSwitcher;
+use crate::rotating_frame::orbital_angular_velocity;
+use crate::run_target::{RunTarget, run_progress};
 use crate::settings::AppSettings;
-use crate::simulation::{AU, KPC, LY, MPC, PC, clamp_scalar_speed_m_s, clamp_velocity_m_s};
+use crate::sim_clock::{ClockSnapshot, SimulationClock};
+use crate::simulation::{
+    AU, EngineConfig, KPC, LIGHT_SPEED, LY, MPC, PC, Summation, clamp_scalar_speed_m_s,
+    clamp_velocity_m_s,
+};
+use crate::speed_tuning::DEFAULT_TARGET_RENDER_FPS;
+use crate::status_bar::StatusBar;
+use crate::still_image::StillSettings;
+use crate::taskbar::{TaskbarProgress, taskbar_progress};
+use crate::thomas_precession::{
+    DEFAULT_THOMAS_BETA, DEFAULT_THOMAS_STEPS_PER_REVOLUTION, ThomasPrecession,
+};
+use crate::time_format::{CalendarEpoch, TimeDisplayUnit, format_simulation_time};
+use crate::toast::{ToastLevel, Toasts};
+use crate::trajectory_export::{DEFAULT_TRAJECTORY_INTERVAL, TrajectoryRecorder};
+use crate::tree_gravity::{
+    DEFAULT_OPENING_ANGLE, DEFAULT_TARGET_FORCE_ERROR, DEFAULT_TREE_REBUILD_INTERVAL, ForceSolver,
+    TreeTiming,
+};
+use crate::tutorial::Tutorial;
+use crate::ui_profile::{DEFAULT_UI_PROFILE, UiProfile, find_ui_profile, is_builtin_ui_profile};
+use crate::undo_history::{UiParameters, UndoDirection, UndoHistory};
+use crate::verification::{
+    DEFAULT_DETERMINISM_STEPS, DEFAULT_VERIFICATION_STEPS, DeterminismReport, VerificationJob,
+    VerificationReport,
+};
+use crate::view_fit::BoundingSphere;
+use crate::worldline::{WORLDLINE_INTERVAL, Worldline};
 use glam::DVec3;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
 
+/// Scale gauge at which particles render at their base size (zoom factor 1).
 pub const DEFAULT_SCALE_UI: f64 = 5000.0;
+/// Lower bound of the Simulation panel scale slider.
+pub const SCALE_GAUGE_MIN: f64 = DEFAULT_SCALE_UI * 0.2;
+/// Upper bound of the Simulation panel scale slider.
+pub const SCALE_GAUGE_MAX: f64 = DEFAULT_SCALE_UI * 3.0;
 
 /// Returns the model-matrix scale applied to particle rendering for a UI scale gauge.
+///
+/// The fourth power widens the zoom span of the legacy slider to about 1.6e-3..81.
 pub fn particle_visual_scale_factor(scale_gauge: f64) -> f32 {
     (scale_gauge / DEFAULT_SCALE_UI).powi(4) as f32
 }
@@ -160,6 +244,20 @@ pub enum PanelKind {
     Simulation,
     ObjectInput,
     Settings,
+    Region,
+    PhaseSpace,
+    Minkowski,
+    PowerSpectrum,
+    Plots,
+    Groups,
+    Trajectories,
+    Events,
+    Batch,
+    Verification,
+    ThomasPrecession,
+    Annotations,
+    RenderStill,
+    Palettes,
 }
 
 impl PanelKind {
@@ -169,8 +267,40 @@ impl PanelKind {
             PanelKind::Simulation => "Simulation",
             PanelKind::ObjectInput => "Object Input",
             PanelKind::Settings => "Settings",
+            PanelKind::Region => "Region",
+            PanelKind::PhaseSpace => "Phase Space",
+            PanelKind::Minkowski => "Minkowski",
+            PanelKind::PowerSpectrum => "Power Spectrum",
+            PanelKind::Plots => "Plots",
+            PanelKind::Groups => "Groups",
+            PanelKind::Trajectories => "Trajectories",
+            PanelKind::Events => "Events",
+            PanelKind::Batch => "Batch",
+            PanelKind::Verification => "Verification",
+            PanelKind::ThomasPrecession => "Thomas Precession",
+            PanelKind::Annotations => "Annotations",
+            PanelKind::RenderStill => "Render Still",
+            PanelKind::Palettes => "Palettes",
         }
     }
+
+    /// Returns whether the panel is an analysis or expert tool, left out of the Panel menu
+    /// while advanced controls are hidden.
+    pub const fn is_advanced(self) -> bool {
+        matches!(
+            self,
+            PanelKind::PhaseSpace
+                | PanelKind::Minkowski
+                | PanelKind::PowerSpectrum
+                | PanelKind::Plots
+                | PanelKind::Groups
+                | PanelKind::Trajectories
+                | PanelKind::Events
+                | PanelKind::Batch
+                | PanelKind::Verification
+                | PanelKind::ThomasPrecession
+        )
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -187,6 +317,7 @@ pub enum DragOwner {
 
 impl DragOwner {
     /// Promotes a pending scene drag to a concrete owner based on UI capture state.
+    #[cfg(feature = "gui")]
     pub(crate) fn promote_from_pending(self, ui_blocks_scene: bool) -> Option<Self> {
         Some(match self {
             Self::PendingSceneLeft => {
@@ -219,6 +350,20 @@ pub const PANELS: &[PanelKind] = &[
     PanelKind::Simulation,
     PanelKind::ObjectInput,
     PanelKind::Settings,
+    PanelKind::Region,
+    PanelKind::PhaseSpace,
+    PanelKind::Minkowski,
+    PanelKind::PowerSpectrum,
+    PanelKind::Plots,
+    PanelKind::Groups,
+    PanelKind::Trajectories,
+    PanelKind::Events,
+    PanelKind::Batch,
+    PanelKind::Verification,
+    PanelKind::ThomasPrecession,
+    PanelKind::Annotations,
+    PanelKind::RenderStill,
+    PanelKind::Palettes,
 ];
 
 #[repr(u32)]
@@ -229,16 +374,20 @@ pub enum SimulationType {
     LorentzTransformation = 2,
     DstGravity = 3,
     DstGalaxy = 4,
+    Gravitomagnetic = 5,
+    Comoving = 6,
 }
 
 impl SimulationType {
     /// All simulation types in UI display order (must match `repr(u32)` discriminants).
-    pub const ALL: [Self; 5] = [
+    pub const ALL: [Self; 7] = [
         Self::Normal,
         Self::SpeedOfLightLimit,
         Self::LorentzTransformation,
         Self::DstGravity,
         Self::DstGalaxy,
+        Self::Gravitomagnetic,
+        Self::Comoving,
     ];
 
     /// Returns the discriminant passed to the GPU compute shader push constants.
@@ -246,6 +395,13 @@ impl SimulationType {
         self as u32
     }
 
+    /// Whether the GPU compute shader implements this engine. The gravitomagnetic force
+    /// reads neighbor velocities, which the in-place GPU pass overwrites mid-step, and the
+    /// comoving engine tracks cosmic time on the CPU.
+    pub fn has_gpu_kernel(self) -> bool {
+        !matches!(self, Self::Gravitomagnetic | Self::Comoving)
+    }
+
     /// Whether generated particles need rapidity conversion before simulation.
     pub fn uses_rapidity_particles(self) -> bool {
         matches!(self, Self::LorentzTransformation)
@@ -256,9 +412,36 @@ impl SimulationType {
         matches!(self, Self::SpeedOfLightLimit)
     }
 
+    /// Whether this is one of the Special engines, whose stored kinematics carry a
+    /// light-speed limit the observer views and worldlines rely on.
+    pub fn is_special_relativistic(self) -> bool {
+        self.uses_momentum_particles() || self.uses_rapidity_particles()
+    }
+
+    /// Whether the engine can integrate in a rotating frame: the fictitious forces are
+    /// Newtonian, so only engines with classical coordinate velocities apply them.
+    pub fn supports_rotating_frame(self) -> bool {
+        matches!(
+            self,
+            Self::Normal | Self::DstGravity | Self::Gravitomagnetic
+        )
+    }
+
+    /// Whether the container walls can bounce this engine's particles; DST Galaxy positions
+    /// are derived from S³ orientations, which a wall cannot mirror.
+    pub fn supports_container_walls(self) -> bool {
+        self != Self::DstGalaxy
+    }
+
+    /// Whether drag and the Langevin thermostat can act on this engine's particles: like
+    /// the rotating frame, both act on classical coordinate velocities.
+    pub fn supports_drag(self) -> bool {
+        self.supports_rotating_frame()
+    }
+
     /// Whether particle velocities must stay below light speed.
     pub fn requires_subluminal_velocity(self) -> bool {
-        !matches!(self, Self::Normal | Self::DstGalaxy)
+        !matches!(self, Self::Normal | Self::DstGalaxy | Self::Comoving)
     }
 }
 
@@ -280,6 +463,30 @@ impl std::fmt::Display for ComputingUnit {
     }
 }
 
+/// Floating-point precision of the CPU Newtonian force loop.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Precision {
+    #[default]
+    Double,
+    /// Forces are summed in `f32`; positions and velocities stay `f64`.
+    Single,
+}
+
+impl Precision {
+    pub const ALL: [Self; 2] = [Self::Double, Self::Single];
+}
+
+impl std::fmt::Display for Precision {
+    /// Formats precision names for selection controls and the Simulation panel.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Precision::Double => "f64",
+            Precision::Single => "f32",
+        };
+        write!(f, "{}", text)
+    }
+}
+
 impl std::fmt::Display for SimulationType {
     /// Formats simulation type for combo-box and labels.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -289,11 +496,24 @@ impl std::fmt::Display for SimulationType {
             SimulationType::LorentzTransformation => "Lorentz Transformation",
             SimulationType::DstGravity => "DST Gravity",
             SimulationType::DstGalaxy => "DST Galaxy",
+            SimulationType::Gravitomagnetic => "Gravitomagnetic (GEM)",
+            SimulationType::Comoving => "Comoving Cosmology",
         };
         write!(f, "{}", text)
     }
 }
 
+/// How much of the current setup a reset keeps.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum ResetKind {
+    /// Regenerates particles but keeps the camera, time step, color options, and the
+    /// dye and event-log history.
+    Soft,
+    /// Also restores the camera, time step, and per-session display options to defaults.
+    #[default]
+    Hard,
+}
+
 #[derive(Clone, Copy, PartialEq, Debug, Default)]
 pub enum PlacementMode {
     #[default]
@@ -332,6 +552,8 @@ impl PlacementMode {
 pub enum PendingSnapshotDialog {
     Save,
     Load,
+    /// Loads the periodic checkpoint without a dialog, offered after a crash.
+    LoadCheckpoint,
 }
 
 /// Log panel state for Solar System reset (ephemeris data download progress).
@@ -359,10 +581,12 @@ pub enum ParticleDisplayMode {
     #[default]
     Glow = 0,
     Sphere = 1,
+    /// Alpha-blended soft discs, drawn back to front after a GPU depth sort.
+    Sprite = 2,
 }
 
 impl ParticleDisplayMode {
-    pub const ALL: [Self; 2] = [Self::Glow, Self::Sphere];
+    pub const ALL: [Self; 3] = [Self::Glow, Self::Sphere, Self::Sprite];
     const SPHERE_SIZE_SCALE: f32 = 0.7;
 
     /// Returns the particle pipeline slot for this display mode.
@@ -373,10 +597,21 @@ impl ParticleDisplayMode {
     /// Returns the multiplier applied to point sprite size for this mode.
     pub const fn size_scale_factor(self) -> f32 {
         match self {
-            Self::Glow => 1.0,
+            Self::Glow | Self::Sprite => 1.0,
             Self::Sphere => Self::SPHERE_SIZE_SCALE,
         }
     }
+
+    /// Returns whether this mode adds particles' light, so overlaps do not occlude.
+    pub const fn is_additive(self) -> bool {
+        matches!(self, Self::Glow)
+    }
+
+    /// Returns whether this mode blends in draw order, so particles must be sorted by
+    /// depth before drawing.
+    pub const fn is_depth_sorted(self) -> bool {
+        matches!(self, Self::Sprite)
+    }
 }
 
 impl std::fmt::Display for ParticleDisplayMode {
@@ -385,6 +620,112 @@ impl std::fmt::Display for ParticleDisplayMode {
         let text = match self {
             ParticleDisplayMode::Glow => "Glow",
             ParticleDisplayMode::Sphere => "Sphere",
+            ParticleDisplayMode::Sprite => "Sprite",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// Which vector particles are plotted at: their position, or their velocity or rapidity
+/// for a momentum-space view.
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum DisplaySpace {
+    #[default]
+    Position = 0,
+    Velocity = 1,
+    Rapidity = 2,
+}
+
+impl DisplaySpace {
+    pub const ALL: [Self; 3] = [Self::Position, Self::Velocity, Self::Rapidity];
+
+    /// Returns the labels of the x, y, and z axes in this space.
+    pub const fn axis_labels(self) -> [&'static str; 3] {
+        match self {
+            Self::Position => ["x", "y", "z"],
+            Self::Velocity => ["βx", "βy", "βz"],
+            Self::Rapidity => ["ηx", "ηy", "ηz"],
+        }
+    }
+}
+
+impl std::fmt::Display for DisplaySpace {
+    /// Formats display space names for UI selection controls.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            DisplaySpace::Position => "Position",
+            DisplaySpace::Velocity => "Velocity",
+            DisplaySpace::Rapidity => "Rapidity",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// How the Simulation panel scale slider maps to the particle zoom factor.
+///
+/// The stored `scale_gauge` keeps its legacy meaning in every mode (zoom factor
+/// `(scale_gauge / DEFAULT_SCALE_UI)^4`); modes only change what the slider moves along.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub enum ScaleGaugeMode {
+    /// Slider moves the raw gauge, as in earlier versions.
+    #[default]
+    Legacy,
+    /// Slider moves the zoom factor itself.
+    Linear,
+    /// Slider moves `log10` of the zoom factor.
+    Log,
+}
+
+impl ScaleGaugeMode {
+    pub const ALL: [Self; 3] = [Self::Legacy, Self::Linear, Self::Log];
+
+    /// Returns the slider range covering the same zoom span as the legacy gauge range.
+    pub fn slider_range(self) -> std::ops::RangeInclusive<f64> {
+        self.gauge_to_slider(SCALE_GAUGE_MIN)..=self.gauge_to_slider(SCALE_GAUGE_MAX)
+    }
+
+    /// Converts a stored scale gauge into this mode's slider value.
+    pub fn gauge_to_slider(self, scale_gauge: f64) -> f64 {
+        let zoom = (scale_gauge / DEFAULT_SCALE_UI).powi(4);
+        match self {
+            Self::Legacy => scale_gauge,
+            Self::Linear => zoom,
+            Self::Log => zoom.log10(),
+        }
+    }
+
+    /// Converts this mode's slider value back into a stored scale gauge.
+    pub fn slider_to_gauge(self, value: f64) -> f64 {
+        match self {
+            Self::Legacy => value,
+            Self::Linear => DEFAULT_SCALE_UI * value.max(0.0).powf(0.25),
+            Self::Log => DEFAULT_SCALE_UI * 10f64.powf(value * 0.25),
+        }
+    }
+
+    /// Returns slider positions of the power-of-ten zoom factors inside the slider range,
+    /// paired with the zoom factor at each tick.
+    pub fn decade_ticks(self) -> Vec<(f64, f64)> {
+        let min_zoom = particle_visual_scale_factor(SCALE_GAUGE_MIN) as f64;
+        let max_zoom = particle_visual_scale_factor(SCALE_GAUGE_MAX) as f64;
+        (min_zoom.log10().ceil() as i32..=max_zoom.log10().floor() as i32)
+            .map(|exponent| {
+                let zoom = 10f64.powi(exponent);
+                let gauge = DEFAULT_SCALE_UI * zoom.powf(0.25);
+                (self.gauge_to_slider(gauge), zoom)
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for ScaleGaugeMode {
+    /// Formats scale gauge mode names for UI selection controls.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            ScaleGaugeMode::Legacy => "Legacy",
+            ScaleGaugeMode::Linear => "Linear",
+            ScaleGaugeMode::Log => "Log",
         };
         write!(f, "{}", text)
     }
@@ -403,17 +744,121 @@ pub struct UiState {
     pub min_window_height: f32,
     pub add_particle_count: u32,
     pub max_particle_count: u32,
+    pub memory_budget_mb: u32,
+    /// Latest estimate from [`Self::apply_memory_budget`].
+    pub memory_usage: MemoryUsage,
+    /// Particle count the memory budget allows; caps adding on top of `max_particle_count`.
+    pub memory_particle_limit: Option<u32>,
+    /// Last budget reduction or overrun, shown until dismissed.
+    pub memory_warning: Option<String>,
+    /// Count picked on the live particle-count slider while it is being edited.
+    pub live_particle_target: Option<u32>,
+    /// Set when the live slider is released; handled by `process_pending_live_rescale`.
+    pub live_rescale_requested: bool,
+    /// Switch the running particles to the selected engine; handled by
+    /// `process_pending_engine_switch`.
+    pub engine_switch_requested: bool,
     pub fps: i64,
+    /// Frames handed to the render loop in the last second.
+    pub draw_fps: i64,
     pub frame: i64,
+    /// Consistent frame/time/FPS snapshot published by the simulation thread for readouts.
+    pub clock: SimulationClock,
+    /// `clock` as last shown, refreshed at [`INDICATOR_REFRESH_INTERVAL`].
+    pub clock_readout: Throttled<ClockSnapshot>,
     pub simulation_time: f64,
+    /// Unit used by the Simulation panel's time readout.
+    pub time_display_unit: TimeDisplayUnit,
+    /// UTC date at `simulation_time == 0` when the current state came from a dated preset.
+    pub simulation_epoch: Option<CalendarEpoch>,
     pub time_per_frame: f64,
+    /// Split frames into substeps while a close encounter needs them (CPU engines with
+    /// classical velocities).
+    pub refine_encounters: bool,
+    /// Steps per encounter time the refinement aims for.
+    pub steps_per_encounter: u32,
+    /// Pair of the encounter being refined, so each encounter is logged once.
+    pub encounter_pair: Option<(u64, u64)>,
+    /// Move tight binaries along their Kepler orbits (the CPU Newtonian engine in double
+    /// precision).
+    pub regularize_binaries: bool,
+    /// Steps per orbit below which a binary is regularized.
+    pub steps_per_orbit: u32,
+    /// IDs of the regularized binaries, lower first, so each is logged when it forms and
+    /// when it dissolves.
+    pub kepler_pairs: Vec<(u64, u64)>,
+    /// Advance each particle on its own power-of-two fraction of the frame (the CPU
+    /// Newtonian engine in double precision). Takes over from close-encounter refinement
+    /// and binary regularization, whose pairs it resolves itself.
+    pub block_steps: bool,
+    /// Timestep factor η of the block time steps.
+    pub block_step_accuracy: f64,
+    /// Finest block level of the last frame, shown in the Simulation panel.
+    pub block_level: Option<u32>,
+    /// How the CPU Newtonian engine in double precision finds forces; takes effect on the
+    /// next step.
+    pub force_solver: ForceSolver,
+    /// The tree solver's opening angle θ.
+    pub opening_angle: f64,
+    /// Steps the tree solver reuses its tree before rebuilding it.
+    pub tree_rebuild_interval: u32,
+    /// The FMM solver's expansion order.
+    pub expansion_order: u32,
+    /// Steer the tree solver's opening angle toward [`Self::target_force_error`].
+    pub auto_tune_opening_angle: bool,
+    /// Relative force error the opening-angle auto-tuning aims for.
+    pub target_force_error: f64,
+    /// Tree or FMM force error measured at the last check, shown in the Simulation panel.
+    pub force_error: Option<f64>,
+    /// Where the tree solver's last step spent its time, shown in the Simulation panel.
+    pub tree_timing: Option<TreeTiming>,
     pub scale: f64,
     pub scale_gauge: f64,
+    /// Mapping used by the scale slider; see [`ScaleGaugeMode`].
+    pub scale_gauge_mode: ScaleGaugeMode,
+    /// Fit View was requested; consumed by the main loop once particles are readable.
+    pub fit_view_requested: bool,
+    /// Request Fit View automatically after every completed reset.
+    pub auto_fit_on_reset: bool,
+    /// Cull off-screen particles in a compute pass and draw the rest indirectly.
+    pub gpu_frustum_culling: bool,
+    /// Points per frame beyond which each frame draws a random, brightness-compensated
+    /// subset of the particles; 0 draws them all.
+    pub point_budget: u32,
+    /// Frames a motion-blur trail of the main view lasts; 0 turns motion blur off.
+    pub motion_blur_frames: u32,
+    /// Font files from settings, in front of the default UI font.
+    pub ui_fonts: Vec<PathBuf>,
+    pub system_cjk_font: bool,
+    /// Reinstall the UI fonts on the next frame.
+    pub fonts_changed: bool,
+    /// Open the file dialog that adds a UI font.
+    pub font_dialog_requested: bool,
+    pub display_mode: DisplayMode,
+    /// Apply `display_mode` to the window on the next event-loop iteration.
+    pub display_mode_changed: bool,
+    /// Connected monitors, refreshed when the display mode is applied.
+    pub monitors: Vec<MonitorInfo>,
+    /// Index in `monitors` of the monitor the window is on.
+    pub current_monitor: Option<usize>,
     pub is_running: bool,
     pub max_fps: u32,
     pub max_fps_unlimited: bool,
+    /// Tune `skip` every second to draw `target_render_fps` while stepping as fast as
+    /// possible; Max FPS is ignored meanwhile.
+    pub auto_speed: bool,
+    pub target_render_fps: u32,
+    /// Simulation time the run stops at, when enabled.
+    pub run_target: RunTarget,
+    /// Set when a run ends with `snapshot_at_end`; cleared once the snapshot is queued.
+    pub run_end_snapshot_requested: bool,
     pub is_reset_requested: bool,
     pub is_resetting: bool,
+    /// Kind of the most recently requested reset.
+    pub reset_kind: ResetKind,
+    pub undo_history: UndoHistory,
+    /// Undo or redo requested from the Edit menu or Ctrl+Z / Ctrl+Y.
+    pub pending_undo: Option<UndoDirection>,
     pub add_center: DVec3,
     pub show_add_center_preview: bool,
     pub is_add_particles_requested: bool,
@@ -426,12 +871,68 @@ pub struct UiState {
     pub active_simulation_type: SimulationType,
     pub computing_unit: ComputingUnit,
     pub active_computing_unit: ComputingUnit,
+    /// Precision chosen for the next reset; only the CPU Normal engine has an f32 path.
+    pub precision: Precision,
+    pub active_precision: Precision,
+    /// Speed of light for the next reset as a fraction of the real one; only the engines
+    /// that limit particles to light speed use it.
+    pub light_speed_factor: f64,
+    pub active_light_speed_factor: f64,
+    /// Angular velocity (rad/s about +y) of the frame the next reset simulates and displays
+    /// in; zero is the inertial frame. Only the classical engines use it.
+    pub frame_angular_velocity: f64,
+    pub active_frame_angular_velocity: f64,
+    /// Expansion history the next Comoving Cosmology reset starts from.
+    pub cosmology: Cosmology,
+    pub active_cosmology: Cosmology,
+    /// CPU path: keep integrating while the render loop uploads the last finished frame.
+    pub pipelined_stepping: bool,
     pub base_scale: f64,
     pub base_scale_unit: BaseScaleUnit,
     /// DST Galaxy: remove particles whose S³ angle from the origin exceeds the threshold.
     pub galaxy_cull_enabled: bool,
     /// DST Galaxy: S³ geodesic-angle threshold α (radians) for culling, in (0, π].
     pub galaxy_cull_max_angle: f64,
+    /// Bounce particles off a reflective box around the origin.
+    pub walls_enabled: bool,
+    pub walls: ContainerWalls,
+    /// Velocity-dependent drag applied after each step; saved with particle snapshots.
+    pub drag: DragForce,
+    /// Langevin thermostat kicking particles after each step.
+    pub noise_enabled: bool,
+    pub noise: LangevinNoise,
+    /// Per-particle mass loss and accretion; saved with particle snapshots.
+    pub mass_rules: Vec<ParticleMassRule>,
+    /// Burns waiting for their time, in scheduling order.
+    pub maneuvers: Vec<Maneuver>,
+    /// Prograde, radial, and normal Δv in m/s entered for the next burn.
+    pub maneuver_delta_v: DVec3,
+    /// Delay in simulation seconds from now until the next scheduled burn.
+    pub maneuver_delay: f64,
+    /// ID of the particle orbit elements are measured around; `None` picks the selected
+    /// particle's dominant body.
+    pub orbit_primary_id: Option<u64>,
+    /// Buffer index the orbit primary was last found at.
+    pub orbit_primary_index: usize,
+    /// Draw the selected particle's predicted orbit, including the entered burn.
+    pub show_orbit_preview: bool,
+    /// Predicted path in particle space at the last preview update.
+    pub orbit_preview: Vec<DVec3>,
+    pub orbit_preview_frame: Option<i64>,
+    /// Selected index and burn the preview was computed for.
+    pub orbit_preview_inputs: Option<(usize, Option<PreviewBurn>)>,
+    /// Bumped on every preview update so the overlay knows to rebuild.
+    pub orbit_preview_revision: u64,
+    /// Text labels on particles and points; saved with particle snapshots.
+    pub annotations: Vec<Annotation>,
+    pub show_annotations: bool,
+    /// Text of the next annotation.
+    pub annotation_text: String,
+    /// Position in simulation units entered for the next point annotation.
+    pub annotation_position: DVec3,
+    /// Names of the Solar System preset's bodies, pinned to their particles at reset.
+    pub body_labels: Vec<Annotation>,
+    pub show_body_labels: bool,
     pub random_sphere: RandomSphereParameters,
     pub random_cube: RandomCubeParameters,
     pub spiral_disk: SpiralDiskParameters,
@@ -439,10 +940,25 @@ pub struct UiState {
     pub satellite_orbit: SatelliteOrbitParameters,
     pub elliptical_orbit: EllipticalOrbitParameters,
     pub single_particle: SingleParticleParameters,
+    pub keplerian_system: KeplerianSystemParameters,
     pub is_simulation_panel_open: bool,
     pub is_object_input_panel_open: bool,
     pub is_settings_panel_open: bool,
     pub is_particle_info_panel_open: bool,
+    pub is_region_panel_open: bool,
+    pub is_phase_space_panel_open: bool,
+    pub is_minkowski_panel_open: bool,
+    pub is_power_spectrum_panel_open: bool,
+    pub is_plots_panel_open: bool,
+    pub is_groups_panel_open: bool,
+    pub is_trajectory_panel_open: bool,
+    pub is_events_panel_open: bool,
+    pub is_batch_panel_open: bool,
+    pub is_verification_panel_open: bool,
+    pub is_thomas_panel_open: bool,
+    pub is_annotations_panel_open: bool,
+    pub is_render_still_panel_open: bool,
+    pub is_palettes_panel_open: bool,
     pub selected_particle: Option<SelectedParticleInfo>,
     /// When true, the camera follows the selected particle from behind each frame.
     pub is_trace_enabled: bool,
@@ -455,6 +971,46 @@ pub struct UiState {
     pub spacecraft_yaw_steer_anchor: Option<[f64; 2]>,
     pub mailbox_present_mode: bool,
     pub show_grid: bool,
+    pub grid_alignment: GridAlignment,
+    pub grid_orientation: GridOrientation,
+    /// Alignment and frame the grid plane was last computed for.
+    pub grid_alignment_applied: Option<(GridAlignment, i64)>,
+    /// Draw distance labels at the grid ticks and names at the axes.
+    pub show_axis_labels: bool,
+    /// Draw the top-down overview inset.
+    pub show_minimap: bool,
+    /// Bounds of the live particles the minimap frames, from the last refresh.
+    pub minimap_bounds: Option<BoundingSphere>,
+    pub minimap_frame: Option<i64>,
+    /// Show a second camera's view beside the main one.
+    pub split_view: bool,
+    /// Size and supersampling of the next offscreen still.
+    pub still_settings: StillSettings,
+    pub still_render_requested: bool,
+    /// Custom palettes and the palettes assigned to generated particles and groups.
+    pub palettes: PaletteSettings,
+    /// Palette shown in the Palettes panel editor.
+    pub palette_editing: String,
+    /// Show solver tuning, cluster options, and analysis panels.
+    pub show_advanced_controls: bool,
+    /// Name of the active UI profile.
+    pub ui_profile: String,
+    /// Saved UI profiles; the built-in ones are not listed.
+    pub ui_profiles: Vec<UiProfile>,
+    /// Name typed in the Profile menu for saving the current configuration.
+    pub ui_profile_name: String,
+    /// Guided tour started from the Help menu.
+    pub tutorial: Tutorial,
+    /// Attract mode started from the View menu or at launch.
+    pub kiosk: Kiosk,
+    pub start_in_kiosk_mode: bool,
+    pub is_help_window_open: bool,
+    /// Notifications for the status bar at the bottom of the window.
+    pub status: StatusBar,
+    /// Report left by a crashed earlier run, shown until dismissed.
+    pub crash_report: Option<CrashReport>,
+    pub last_checkpoint: Option<Instant>,
+    pub crash_context_updated: Option<Instant>,
     pub particle_display_mode: ParticleDisplayMode,
     pub request_exit: bool,
     pub pending_snapshot_dialog: Option<PendingSnapshotDialog>,
@@ -463,27 +1019,224 @@ pub struct UiState {
     /// Particle index scheduled for deletion from the Particle Info panel.
     pub pending_delete_particle_index: Option<usize>,
     pub reset_log: ResetLogPanelState,
+    pub region: SelectionRegion,
+    /// Draw the region outline while the Region panel is open.
+    pub show_region_outline: bool,
+    /// Color applied by the Region panel's Recolor action.
+    pub region_color: [f32; 3],
+    /// Result of the last Measure action; cleared when particles are replaced.
+    pub region_statistics: Option<RegionStatistics>,
+    /// Region operation scheduled from the Region panel.
+    pub pending_region_action: Option<RegionAction>,
+    /// Dye injections since the last reset, in injection order.
+    pub dye_injections: Vec<DyeInjection>,
+    /// Draw the center-of-mass marker and Lagrangian-radius spheres.
+    pub show_mass_profile_overlay: bool,
+    /// Frames between mass-profile recomputations while the overlay is shown.
+    pub mass_profile_interval: u32,
+    pub mass_profile: Option<MassProfile>,
+    /// Frame at which `mass_profile` was last computed.
+    pub mass_profile_frame: Option<i64>,
+    /// Mark particles faster than the local escape speed (refreshed with the mass profile).
+    pub show_escaper_highlight: bool,
+    /// Draw Special-engine particles as the camera sees them: light-travel delay,
+    /// aberration, and Doppler beaming.
+    pub relativistic_view: bool,
+    /// Plot particles at their positions, or at their velocities or rapidities.
+    pub display_space: DisplaySpace,
+    /// Positions of escaping particles at the last mass-profile update.
+    pub escaper_positions: Vec<DVec3>,
+    /// Bumped on every mass-profile update so overlays know to rebuild.
+    pub mass_profile_revision: u64,
+    /// Draw the camera's past light cone and where particle worldlines cross it.
+    pub show_light_cone: bool,
+    pub light_cone: Vec<LightConeCrossing>,
+    /// Height of the drawn cone, `c·τ` of the deepest crossing, in simulation units.
+    pub light_cone_depth: f64,
+    pub light_cone_frame: Option<i64>,
+    /// Camera position (particle space) the crossings were computed for.
+    pub light_cone_observer: Option<DVec3>,
+    /// Bumped on every light-cone update so the overlay knows to rebuild.
+    pub light_cone_revision: u64,
+    /// ID of the particle whose instantaneous rest frame particles are drawn in.
+    pub rest_frame_particle_id: Option<u64>,
+    /// Buffer index the rest-frame particle was last found at.
+    pub rest_frame_index: usize,
+    pub frame_switcher: FrameSwitcher,
+    pub phase_space_x: PhaseSpaceQuantity,
+    pub phase_space_y: PhaseSpaceQuantity,
+    pub phase_space_max_points: u32,
+    pub phase_space: PhaseSpacePoints,
+    /// Frame at which `phase_space` was last sampled; `None` forces a refresh.
+    pub phase_space_frame: Option<i64>,
+    /// Record worldlines of the tracked trajectory IDs for the Minkowski panel.
+    pub worldline_recording: bool,
+    pub worldlines: Vec<Worldline>,
+    /// Frame of the last worldline sample.
+    pub worldline_frame: Option<i64>,
+    /// Speed of the boosted frame along x, in units of c.
+    pub minkowski_beta: f64,
+    pub show_minkowski_boosted_axes: bool,
+    /// Cells per side of the power-spectrum deposition grid.
+    pub power_spectrum_grid: usize,
+    pub power_spectrum: Option<PowerSpectrum>,
+    /// Simulation frame the current `power_spectrum` was computed at.
+    pub power_spectrum_frame: i64,
+    pub power_spectrum_requested: bool,
+    pub power_spectrum_export_requested: bool,
+    /// Friends-of-friends linking length in units of the mean interparticle separation.
+    pub fof_linking_factor: f64,
+    pub fof_min_members: u32,
+    /// Recolor particles by group after each friends-of-friends run.
+    pub fof_color_by_group: bool,
+    /// Color the Groups panel's Paint buttons give a single group.
+    pub group_color: [f32; 3],
+    /// Row of `particle_groups` to paint with `group_color`.
+    pub pending_group_recolor: Option<usize>,
+    pub group_finder_requested: bool,
+    pub particle_groups: Vec<ParticleGroup>,
+    /// Simulation frame the current `particle_groups` were found at.
+    pub particle_groups_frame: i64,
+    pub group_sort_key: GroupSortKey,
+    pub group_sort_descending: bool,
+    /// Particle IDs whose trajectories are written while recording.
+    pub trajectory_ids: Vec<u64>,
+    /// ID typed into the Trajectories panel before it is added to `trajectory_ids`.
+    pub trajectory_id_input: u64,
+    pub trajectory_interval: u32,
+    pub trajectory_recorder: Option<TrajectoryRecorder>,
+    /// Frame of the last recorded trajectory sample.
+    pub trajectory_record_frame: Option<i64>,
+    pub trajectory_start_requested: bool,
+    pub event_triggers: Vec<EventTrigger>,
+    /// Condition kind and action preselected for the next trigger added in the Events panel.
+    pub event_new_kind: EventConditionKind,
+    pub event_new_action: EventAction,
+    pub event_log: Vec<String>,
+    /// Total energy captured when energy-drift triggers were first evaluated after a reset.
+    pub event_reference_energy: Option<f64>,
+    /// Frame at which triggers were last evaluated.
+    pub event_check_frame: Option<i64>,
+    pub event_snapshot_dir: String,
+    /// Relative energy drift since the last reset, behind the drift warning banner.
+    pub energy_monitor: EnergyMonitor,
+    /// Time series of the diagnostics, which plots and exports read.
+    pub metrics: MetricsStore,
+    pub metrics_export_requested: bool,
+    /// Paces the metrics sampled for the Plots window.
+    pub metric_sampler: MetricSampler,
+    pub plot_charts: Vec<PlotChart>,
+    /// Name and expression typed for the next derived metric.
+    pub derived_metric_name: String,
+    pub derived_metric_expression: String,
+    /// Chart index and format of a pending chart export.
+    pub plot_export_request: Option<(usize, PlotExportFormat)>,
+    /// Comma-separated time steps (seconds per frame) swept by the Batch panel.
+    pub batch_time_steps: String,
+    pub batch_simulation_types: Vec<SimulationType>,
+    /// Simulated seconds covered by each batch run.
+    pub batch_duration: f64,
+    pub batch_job: Option<BatchJob>,
+    /// Background writer for snapshots and CSV exports; drained when the state drops.
+    pub export_writer: ExportWriter,
+    /// Notifications from background tasks, shared with the threads that post them.
+    pub toasts: Toasts,
+    pub batch_results: Vec<BatchRunSummary>,
+    pub batch_error: Option<String>,
+    pub batch_export_requested: bool,
+    /// How the CPU engines accumulate pairwise forces; takes effect on the next step.
+    pub force_summation: Summation,
+    pub verification_steps: u32,
+    pub verification_report: Option<VerificationReport>,
+    pub verification_error: Option<String>,
+    pub verification_requested: bool,
+    pub verification_job: Option<VerificationJob<VerificationReport>>,
+    pub determinism_steps: u32,
+    /// Largest difference between the two audit runs still counted as agreement.
+    pub determinism_tolerance: f64,
+    pub determinism_report: Option<DeterminismReport>,
+    pub determinism_error: Option<String>,
+    pub determinism_requested: bool,
+    pub determinism_job: Option<VerificationJob<DeterminismReport>>,
+    /// Thomas precession demonstration; restarted from the settings below on Reset.
+    pub thomas_precession: ThomasPrecession,
+    pub thomas_running: bool,
+    pub thomas_beta: f64,
+    pub thomas_steps_per_revolution: u32,
+    /// Bumped whenever `thomas_precession` changes so the orbit overlay is rebuilt.
+    pub thomas_revision: u64,
 }
 
 impl Default for UiState {
     /// Initializes UI state with startup defaults for simulation and panels.
     fn default() -> Self {
+        let toasts = Toasts::default();
         Self {
             min_window_width: 400.0,
             min_window_height: 300.0,
             add_particle_count: DEFAULT_ADD_PARTICLE_COUNT,
             max_particle_count: 20000,
+            memory_budget_mb: DEFAULT_MEMORY_BUDGET_MB,
+            memory_usage: MemoryUsage::default(),
+            memory_particle_limit: None,
+            memory_warning: None,
+            live_particle_target: None,
+            live_rescale_requested: false,
+            engine_switch_requested: false,
             fps: 0,
+            draw_fps: 0,
             frame: 1,
+            clock: SimulationClock::new(),
+            clock_readout: Throttled::new(INDICATOR_REFRESH_INTERVAL),
             simulation_time: 0.0,
+            time_display_unit: TimeDisplayUnit::default(),
+            simulation_epoch: None,
             time_per_frame: 10.0,
+            refine_encounters: false,
+            steps_per_encounter: DEFAULT_STEPS_PER_ENCOUNTER,
+            encounter_pair: None,
+            regularize_binaries: false,
+            steps_per_orbit: DEFAULT_STEPS_PER_ORBIT,
+            kepler_pairs: Vec::new(),
+            block_steps: false,
+            block_step_accuracy: DEFAULT_BLOCK_STEP_ACCURACY,
+            block_level: None,
+            force_solver: ForceSolver::default(),
+            opening_angle: DEFAULT_OPENING_ANGLE,
+            tree_rebuild_interval: DEFAULT_TREE_REBUILD_INTERVAL,
+            expansion_order: DEFAULT_EXPANSION_ORDER,
+            auto_tune_opening_angle: false,
+            target_force_error: DEFAULT_TARGET_FORCE_ERROR,
+            force_error: None,
+            tree_timing: None,
             scale: 1e10,
             scale_gauge: DEFAULT_SCALE_UI,
+            scale_gauge_mode: ScaleGaugeMode::default(),
+            fit_view_requested: false,
+            auto_fit_on_reset: false,
+            gpu_frustum_culling: true,
+            point_budget: 0,
+            motion_blur_frames: 0,
+            ui_fonts: Vec::new(),
+            system_cjk_font: true,
+            fonts_changed: false,
+            font_dialog_requested: false,
+            display_mode: DisplayMode::default(),
+            display_mode_changed: false,
+            monitors: Vec::new(),
+            current_monitor: None,
             is_running: false,
             max_fps: DEFAULT_MAX_FPS,
             max_fps_unlimited: false,
+            auto_speed: false,
+            target_render_fps: DEFAULT_TARGET_RENDER_FPS,
+            run_target: RunTarget::default(),
+            run_end_snapshot_requested: false,
             is_reset_requested: false,
             is_resetting: false,
+            reset_kind: ResetKind::Hard,
+            undo_history: UndoHistory::new(),
+            pending_undo: None,
             add_center: DVec3::ZERO,
             show_add_center_preview: true,
             is_add_particles_requested: false,
@@ -496,10 +1249,41 @@ impl Default for UiState {
             active_simulation_type: SimulationType::Normal,
             computing_unit: ComputingUnit::default(),
             active_computing_unit: ComputingUnit::default(),
+            precision: Precision::default(),
+            active_precision: Precision::default(),
+            light_speed_factor: 1.0,
+            active_light_speed_factor: 1.0,
+            frame_angular_velocity: 0.0,
+            active_frame_angular_velocity: 0.0,
+            cosmology: Cosmology::default(),
+            active_cosmology: Cosmology::default(),
+            pipelined_stepping: false,
             base_scale: ObjectInputType::default().default_base_scale(),
             base_scale_unit: BaseScaleUnit::default(),
             galaxy_cull_enabled: true,
             galaxy_cull_max_angle: GALAXY_CULL_MAX_ANGLE_DEFAULT,
+            walls_enabled: false,
+            walls: ContainerWalls::default(),
+            drag: DragForce::default(),
+            noise_enabled: false,
+            noise: LangevinNoise::default(),
+            mass_rules: Vec::new(),
+            maneuvers: Vec::new(),
+            maneuver_delta_v: DVec3::ZERO,
+            maneuver_delay: 0.0,
+            orbit_primary_id: None,
+            orbit_primary_index: 0,
+            show_orbit_preview: true,
+            orbit_preview: Vec::new(),
+            orbit_preview_frame: None,
+            orbit_preview_inputs: None,
+            orbit_preview_revision: 0,
+            annotations: Vec::new(),
+            show_annotations: true,
+            annotation_text: String::new(),
+            annotation_position: DVec3::ZERO,
+            body_labels: Vec::new(),
+            show_body_labels: true,
             random_sphere: RandomSphereParameters::default(),
             random_cube: RandomCubeParameters::default(),
             spiral_disk: SpiralDiskParameters::default(),
@@ -507,10 +1291,25 @@ impl Default for UiState {
             satellite_orbit: SatelliteOrbitParameters::default(),
             elliptical_orbit: EllipticalOrbitParameters::default(),
             single_particle: SingleParticleParameters::default(),
+            keplerian_system: KeplerianSystemParameters::default(),
             is_simulation_panel_open: true,
             is_object_input_panel_open: false,
             is_settings_panel_open: false,
             is_particle_info_panel_open: false,
+            is_region_panel_open: false,
+            is_phase_space_panel_open: false,
+            is_minkowski_panel_open: false,
+            is_power_spectrum_panel_open: false,
+            is_plots_panel_open: false,
+            is_groups_panel_open: false,
+            is_trajectory_panel_open: false,
+            is_events_panel_open: false,
+            is_batch_panel_open: false,
+            is_verification_panel_open: false,
+            is_thomas_panel_open: false,
+            is_annotations_panel_open: false,
+            is_render_still_panel_open: false,
+            is_palettes_panel_open: false,
             selected_particle: None,
             is_trace_enabled: false,
             start_maximized: false,
@@ -520,12 +1319,135 @@ impl Default for UiState {
             spacecraft_yaw_steer_anchor: None,
             mailbox_present_mode: false,
             show_grid: true,
+            grid_alignment: GridAlignment::default(),
+            grid_orientation: GridOrientation::default(),
+            grid_alignment_applied: None,
+            show_axis_labels: false,
+            show_minimap: false,
+            minimap_bounds: None,
+            minimap_frame: None,
+            split_view: false,
+            still_settings: StillSettings::default(),
+            still_render_requested: false,
+            palettes: PaletteSettings::default(),
+            palette_editing: DEFAULT_PARTICLE_PALETTE.to_string(),
+            show_advanced_controls: true,
+            ui_profile: DEFAULT_UI_PROFILE.to_string(),
+            ui_profiles: Vec::new(),
+            ui_profile_name: String::new(),
+            tutorial: Tutorial::default(),
+            kiosk: Kiosk::default(),
+            start_in_kiosk_mode: false,
+            is_help_window_open: false,
+            status: StatusBar::default(),
+            crash_report: None,
+            last_checkpoint: None,
+            crash_context_updated: None,
             particle_display_mode: ParticleDisplayMode::default(),
             request_exit: false,
             pending_snapshot_dialog: None,
             particle_buffer_reload_requested: false,
             pending_delete_particle_index: None,
             reset_log: ResetLogPanelState::default(),
+            region: SelectionRegion::default(),
+            show_region_outline: true,
+            region_color: [1.0, 0.2, 0.8],
+            region_statistics: None,
+            pending_region_action: None,
+            dye_injections: Vec::new(),
+            show_mass_profile_overlay: false,
+            mass_profile_interval: DEFAULT_MASS_PROFILE_INTERVAL,
+            mass_profile: None,
+            mass_profile_frame: None,
+            show_escaper_highlight: false,
+            relativistic_view: false,
+            display_space: DisplaySpace::Position,
+            escaper_positions: Vec::new(),
+            mass_profile_revision: 0,
+            show_light_cone: false,
+            light_cone: Vec::new(),
+            light_cone_depth: 0.0,
+            light_cone_frame: None,
+            light_cone_observer: None,
+            light_cone_revision: 0,
+            rest_frame_particle_id: None,
+            rest_frame_index: 0,
+            frame_switcher: FrameSwitcher::default(),
+            phase_space_x: PhaseSpaceQuantity::Radius,
+            phase_space_y: PhaseSpaceQuantity::RadialVelocity,
+            phase_space_max_points: DEFAULT_PHASE_SPACE_MAX_POINTS,
+            phase_space: PhaseSpacePoints::default(),
+            phase_space_frame: None,
+            worldline_recording: false,
+            worldlines: Vec::new(),
+            worldline_frame: None,
+            minkowski_beta: 0.0,
+            show_minkowski_boosted_axes: false,
+            power_spectrum_grid: DEFAULT_POWER_SPECTRUM_GRID,
+            power_spectrum: None,
+            power_spectrum_frame: 0,
+            power_spectrum_requested: false,
+            power_spectrum_export_requested: false,
+            fof_linking_factor: DEFAULT_LINKING_LENGTH_FACTOR,
+            fof_min_members: DEFAULT_MIN_GROUP_MEMBERS,
+            fof_color_by_group: true,
+            group_color: [0.2, 0.8, 1.0],
+            pending_group_recolor: None,
+            group_finder_requested: false,
+            particle_groups: Vec::new(),
+            particle_groups_frame: 0,
+            group_sort_key: GroupSortKey::Mass,
+            group_sort_descending: true,
+            trajectory_ids: Vec::new(),
+            trajectory_id_input: 1,
+            trajectory_interval: DEFAULT_TRAJECTORY_INTERVAL,
+            trajectory_recorder: None,
+            trajectory_record_frame: None,
+            trajectory_start_requested: false,
+            event_triggers: Vec::new(),
+            event_new_kind: EventConditionKind::TimeReached,
+            event_new_action: EventAction::Pause,
+            event_log: Vec::new(),
+            event_reference_energy: None,
+            event_check_frame: None,
+            event_snapshot_dir: DEFAULT_EVENT_SNAPSHOT_DIR.to_string(),
+            energy_monitor: EnergyMonitor::default(),
+            metrics: MetricsStore::default(),
+            metrics_export_requested: false,
+            metric_sampler: MetricSampler::default(),
+            plot_charts: vec![PlotChart::new(TOTAL_ENERGY)],
+            derived_metric_name: String::new(),
+            derived_metric_expression: String::new(),
+            plot_export_request: None,
+            batch_time_steps: DEFAULT_BATCH_TIME_STEPS.to_string(),
+            batch_simulation_types: vec![SimulationType::Normal],
+            batch_duration: DEFAULT_BATCH_DURATION,
+            batch_job: None,
+            export_writer: ExportWriter::with_toasts(toasts.clone()),
+            toasts,
+            batch_results: Vec::new(),
+            batch_error: None,
+            batch_export_requested: false,
+            force_summation: Summation::default(),
+            verification_steps: DEFAULT_VERIFICATION_STEPS,
+            verification_report: None,
+            verification_error: None,
+            verification_requested: false,
+            verification_job: None,
+            determinism_steps: DEFAULT_DETERMINISM_STEPS,
+            determinism_tolerance: 0.0,
+            determinism_report: None,
+            determinism_error: None,
+            determinism_requested: false,
+            determinism_job: None,
+            thomas_precession: ThomasPrecession::new(
+                DEFAULT_THOMAS_BETA,
+                DEFAULT_THOMAS_STEPS_PER_REVOLUTION,
+            ),
+            thomas_running: false,
+            thomas_beta: DEFAULT_THOMAS_BETA,
+            thomas_steps_per_revolution: DEFAULT_THOMAS_STEPS_PER_REVOLUTION,
+            thomas_revision: 0,
         }
     }
 }
@@ -537,12 +1459,52 @@ impl UiState {
             PanelKind::Simulation => &mut self.is_simulation_panel_open,
             PanelKind::ObjectInput => &mut self.is_object_input_panel_open,
             PanelKind::Settings => &mut self.is_settings_panel_open,
+            PanelKind::Region => &mut self.is_region_panel_open,
+            PanelKind::PhaseSpace => &mut self.is_phase_space_panel_open,
+            PanelKind::Minkowski => &mut self.is_minkowski_panel_open,
+            PanelKind::PowerSpectrum => &mut self.is_power_spectrum_panel_open,
+            PanelKind::Plots => &mut self.is_plots_panel_open,
+            PanelKind::Groups => &mut self.is_groups_panel_open,
+            PanelKind::Trajectories => &mut self.is_trajectory_panel_open,
+            PanelKind::Events => &mut self.is_events_panel_open,
+            PanelKind::Batch => &mut self.is_batch_panel_open,
+            PanelKind::Verification => &mut self.is_verification_panel_open,
+            PanelKind::ThomasPrecession => &mut self.is_thomas_panel_open,
+            PanelKind::Annotations => &mut self.is_annotations_panel_open,
+            PanelKind::RenderStill => &mut self.is_render_still_panel_open,
+            PanelKind::Palettes => &mut self.is_palettes_panel_open,
         }
     }
 
-    /// Returns how many more particles can be added before hitting the configured maximum.
+    /// Returns how many more particles can be added before hitting the configured maximum
+    /// or the memory budget.
     pub fn remaining_particle_capacity(&self, current_count: u32) -> u32 {
-        self.max_particle_count.saturating_sub(current_count)
+        self.max_particle_count
+            .min(self.memory_particle_limit.unwrap_or(u32::MAX))
+            .saturating_sub(current_count)
+    }
+
+    /// Re-estimates memory for `particle_count` particles and lowers phase-space sampling
+    /// or pipelined stepping when the budget is exceeded.
+    pub fn apply_memory_budget(&mut self, particle_count: u32) {
+        let plan = plan_within_budget(
+            budget_bytes(self.memory_budget_mb),
+            MemoryDemand {
+                particle_count,
+                phase_space_max_points: self.phase_space_max_points,
+                pipelined_stepping: self.pipelined_stepping,
+            },
+        );
+        if plan.demand.phase_space_max_points != self.phase_space_max_points {
+            self.phase_space_max_points = plan.demand.phase_space_max_points;
+            self.phase_space_frame = None;
+        }
+        self.pipelined_stepping = plan.demand.pipelined_stepping;
+        self.memory_usage = plan.usage;
+        self.memory_particle_limit = Some(plan.particle_limit);
+        if plan.warning.is_some() {
+            self.memory_warning = plan.warning;
+        }
     }
 
     /// Returns the valid add-count slider range for the given remaining capacity.
@@ -585,12 +1547,40 @@ impl UiState {
     /// Applies persisted app settings and clamps runtime values to new limits.
     pub fn apply_settings(&mut self, settings: &AppSettings) {
         self.max_particle_count = settings.max_particle_count;
+        self.memory_budget_mb = settings.memory_budget_mb;
         self.min_window_width = settings.window_min_width;
         self.min_window_height = settings.window_min_height;
         self.start_maximized = settings.start_maximized;
         self.link_point_size_to_scale = settings.link_point_size_to_scale;
         self.mailbox_present_mode = settings.mailbox_present_mode;
         self.particle_display_mode = settings.particle_display_mode;
+        self.time_display_unit = settings.time_display_unit;
+        self.auto_fit_on_reset = settings.auto_fit_on_reset;
+        self.scale_gauge_mode = settings.scale_gauge_mode;
+        self.gpu_frustum_culling = settings.gpu_frustum_culling;
+        self.point_budget = settings.point_budget;
+        self.motion_blur_frames = settings.motion_blur_frames;
+        self.ui_fonts = settings.ui_fonts.clone();
+        self.system_cjk_font = settings.system_cjk_font;
+        self.fonts_changed = true;
+        self.display_mode = settings.display_mode.clone();
+        self.display_mode_changed = self.display_mode.fullscreen;
+        self.start_in_kiosk_mode = settings.start_in_kiosk_mode;
+        self.energy_monitor.enabled = settings.energy_warning_enabled;
+        self.energy_monitor.threshold = settings.energy_drift_threshold;
+        self.metrics.set_retention(settings.metrics_retention);
+        for e in self.metrics.set_derived(&settings.derived_metrics) {
+            self.status.error(format!("Skipped derived metric {}", e));
+        }
+        if self.start_in_kiosk_mode {
+            self.start_kiosk();
+        }
+        self.palettes = settings.palettes.clone();
+        self.palettes.apply();
+        self.ui_profiles = settings.ui_profiles.clone();
+        if let Some(profile) = find_ui_profile(&self.ui_profiles, &settings.ui_profile) {
+            self.apply_ui_profile(&profile);
+        }
         if self.add_particle_count > self.max_particle_count {
             self.add_particle_count = self.max_particle_count;
         }
@@ -614,12 +1604,284 @@ impl UiState {
         self.set_base_scale(unit.canonical_meters(unit.to_meters(display)));
     }
 
-    /// Returns whether GPU particle simulation is available for the current settings.
-    ///
-    /// All simulation types now have a GPU compute implementation, so GPU computing
-    /// is always offered.
+    /// Returns whether GPU particle simulation is available for the current settings:
+    /// only the selected engine's lack of a GPU compute implementation rules it out.
     pub fn gpu_computing_available(&self) -> bool {
-        true
+        self.simulation_type.has_gpu_kernel()
+    }
+
+    /// Returns the container walls the running simulation bounces particles off, if any.
+    pub fn container_walls(&self) -> Option<ContainerWalls> {
+        (self.walls_enabled && self.active_simulation_type.supports_container_walls())
+            .then_some(self.walls)
+    }
+
+    /// Returns the drag the running simulation slows particles with, if any.
+    pub fn drag_force(&self) -> Option<DragForce> {
+        (self.drag.model != DragModel::Off && self.active_simulation_type.supports_drag())
+            .then_some(self.drag)
+    }
+
+    /// Returns the Langevin thermostat the running simulation applies, if any.
+    pub fn langevin_noise(&self) -> Option<LangevinNoise> {
+        (self.noise_enabled && self.active_simulation_type.supports_drag()).then_some(self.noise)
+    }
+
+    /// Returns the mass rules the running simulation applies: all of them for engines with
+    /// classical velocities on the CPU, none otherwise.
+    pub fn mass_rules_in_effect(&self) -> &[ParticleMassRule] {
+        if self.active_simulation_type.supports_drag() && !self.uses_gpu_simulation() {
+            &self.mass_rules
+        } else {
+            &[]
+        }
+    }
+
+    /// Returns the steps per encounter time close encounters are refined to, when the
+    /// running engine integrates classical velocities on the CPU.
+    pub fn encounter_refinement(&self) -> Option<u32> {
+        (self.refine_encounters
+            && self.active_simulation_type.supports_drag()
+            && !self.uses_gpu_simulation()
+            && self.block_stepping().is_none())
+        .then_some(self.steps_per_encounter)
+    }
+
+    /// Logs `encounter` in the event log when it starts, and forgets the pair once no
+    /// encounter is refined.
+    pub fn note_encounter(&mut self, encounter: Option<Encounter>) {
+        let pair = encounter.map(|encounter| encounter.pair());
+        if let Some(encounter) = encounter
+            && self.encounter_pair != pair
+        {
+            let line = format!(
+                "frame {}: close encounter #{}–#{}, closest {:.3e} m, {} substeps",
+                self.frame,
+                encounter.first_id,
+                encounter.second_id,
+                encounter.min_separation * self.scale,
+                encounter.substeps
+            );
+            self.push_event_log(line);
+        }
+        self.encounter_pair = pair;
+    }
+
+    /// Resets the steps per encounter time to the default.
+    pub fn reset_steps_per_encounter_to_default(&mut self) {
+        self.steps_per_encounter = DEFAULT_STEPS_PER_ENCOUNTER;
+    }
+
+    /// Whether the running engine is the CPU Newtonian one in double precision with direct
+    /// summation, which binary regularization and block time steps build on.
+    pub fn runs_direct_newtonian(&self) -> bool {
+        self.runs_newtonian_double() && self.force_solver == ForceSolver::Direct
+    }
+
+    /// Whether the running engine finds forces through the Barnes–Hut tree.
+    pub fn uses_tree_forces(&self) -> bool {
+        self.runs_newtonian_double() && self.force_solver == ForceSolver::Tree
+    }
+
+    /// Whether the running engine finds forces through the fast multipole method.
+    pub fn uses_fast_multipoles(&self) -> bool {
+        self.runs_newtonian_double() && self.force_solver == ForceSolver::Fmm
+    }
+
+    fn runs_newtonian_double(&self) -> bool {
+        self.active_simulation_type == SimulationType::Normal
+            && self.effective_precision() == Precision::Double
+    }
+
+    /// Returns the steps per orbit below which tight binaries are regularized, when the
+    /// running engine supports it and block time steps are off.
+    pub fn binary_regularization(&self) -> Option<u32> {
+        (self.regularize_binaries
+            && self.runs_direct_newtonian()
+            && self.block_stepping().is_none())
+        .then_some(self.steps_per_orbit)
+    }
+
+    /// Returns the timestep factor of the block time steps, when they are on and the
+    /// running engine supports them.
+    pub fn block_stepping(&self) -> Option<f64> {
+        (self.block_steps && self.runs_direct_newtonian()).then_some(self.block_step_accuracy)
+    }
+
+    /// Resets the block timestep factor to the default.
+    pub fn reset_block_step_accuracy_to_default(&mut self) {
+        self.block_step_accuracy = DEFAULT_BLOCK_STEP_ACCURACY;
+    }
+
+    /// Resets the auto-tuning's target force error to the default.
+    pub fn reset_target_force_error_to_default(&mut self) {
+        self.target_force_error = DEFAULT_TARGET_FORCE_ERROR;
+    }
+
+    /// Logs the binaries in `pairs` that were not regularized last frame and those that
+    /// dissolved since, then holds `pairs` for the next frame.
+    pub fn note_kepler_pairs(&mut self, pairs: &[KeplerPair]) {
+        let held: Vec<(u64, u64)> = pairs.iter().map(KeplerPair::pair).collect();
+        let previous = std::mem::replace(&mut self.kepler_pairs, held);
+        let mut lines: Vec<String> = pairs
+            .iter()
+            .filter(|pair| !previous.contains(&pair.pair()))
+            .map(|pair| {
+                let (first, second) = pair.pair();
+                format!(
+                    "frame {}: binary #{}–#{} regularized, a = {:.3e} m, e = {:.3}, P = {:.3e} s",
+                    self.frame,
+                    first,
+                    second,
+                    pair.orbit.semi_major_axis * self.scale,
+                    pair.orbit.eccentricity,
+                    pair.orbit.period
+                )
+            })
+            .collect();
+        lines.extend(
+            previous
+                .iter()
+                .filter(|ids| !self.kepler_pairs.contains(ids))
+                .map(|(first, second)| {
+                    format!(
+                        "frame {}: binary #{}–#{} dissolved",
+                        self.frame, first, second
+                    )
+                }),
+        );
+        for line in lines {
+            self.push_event_log(line);
+        }
+    }
+
+    /// Resets the steps per orbit to the default.
+    pub fn reset_steps_per_orbit_to_default(&mut self) {
+        self.steps_per_orbit = DEFAULT_STEPS_PER_ORBIT;
+    }
+
+    /// Whether the running simulation should conserve the energy the drift monitor measures:
+    /// drag, the thermostat, mass rules, a rotating frame, and cosmic expansion change it
+    /// by design.
+    pub fn conserves_energy(&self) -> bool {
+        self.drag_force().is_none()
+            && self.langevin_noise().is_none()
+            && self.mass_rules_in_effect().is_empty()
+            && self.active_frame_angular_velocity == 0.0
+            && self.active_simulation_type() != SimulationType::Comoving
+    }
+
+    /// Returns the mass rule attached to the particle with `id`.
+    pub fn mass_rule(&self, id: u64) -> Option<MassRule> {
+        self.mass_rules
+            .iter()
+            .find(|entry| entry.particle_id == id)
+            .map(|entry| entry.rule)
+    }
+
+    /// Attaches `rule` to the particle with `id`, replacing any earlier one; `None` detaches.
+    pub fn set_mass_rule(&mut self, id: u64, rule: Option<MassRule>) {
+        self.mass_rules.retain(|entry| entry.particle_id != id);
+        if let Some(rule) = rule.filter(|_| id != 0) {
+            self.mass_rules.push(ParticleMassRule {
+                particle_id: id,
+                rule,
+            });
+        }
+    }
+
+    /// Returns whether the running engine can apply maneuvers: those with classical
+    /// velocities.
+    pub fn supports_maneuvers(&self) -> bool {
+        self.active_simulation_type.supports_drag()
+    }
+
+    /// Schedules a burn of the entered Δv for the particle with `id`, the entered delay
+    /// from now.
+    pub fn schedule_maneuver(&mut self, id: u64) {
+        if id == 0 {
+            return;
+        }
+        self.maneuvers.push(Maneuver {
+            particle_id: id,
+            delta_v: self.maneuver_delta_v,
+            burn_time: self.simulation_time + self.maneuver_delay.max(0.0),
+        });
+    }
+
+    /// Removes and returns the burns whose time has come, in scheduling order.
+    pub fn take_due_maneuvers(&mut self) -> Vec<Maneuver> {
+        if !self.supports_maneuvers() {
+            return Vec::new();
+        }
+        let now = self.simulation_time;
+        let (due, pending) = self
+            .maneuvers
+            .drain(..)
+            .partition(|maneuver| maneuver.burn_time <= now);
+        self.maneuvers = pending;
+        due
+    }
+
+    /// Returns the entered burn the orbit preview folds in: shown while the maneuver
+    /// planner is, and only once a Δv is entered.
+    pub fn orbit_preview_burn(&self) -> Option<PreviewBurn> {
+        (self.placement_mode == PlacementMode::SatelliteOrbit
+            && self.maneuver_delta_v != DVec3::ZERO)
+            .then_some(PreviewBurn {
+                delay: self.maneuver_delay.max(0.0),
+                delta_v: self.maneuver_delta_v,
+            })
+    }
+
+    /// Returns whether the orbit preview must be recomputed for `inputs`: the selection or
+    /// the entered burn changed, or [`ORBIT_PREVIEW_INTERVAL`] frames passed.
+    pub fn orbit_preview_update_due(&self, inputs: (usize, Option<PreviewBurn>)) -> bool {
+        if self.orbit_preview_inputs != Some(inputs) {
+            return true;
+        }
+        match self.orbit_preview_frame {
+            Some(last) => self.frame < last || self.frame - last >= ORBIT_PREVIEW_INTERVAL,
+            None => true,
+        }
+    }
+
+    /// Drops the predicted orbit so the overlay disappears.
+    pub fn clear_orbit_preview(&mut self) {
+        if self.orbit_preview_inputs.is_none() && self.orbit_preview.is_empty() {
+            return;
+        }
+        self.orbit_preview.clear();
+        self.orbit_preview_frame = None;
+        self.orbit_preview_inputs = None;
+        self.orbit_preview_revision += 1;
+    }
+
+    /// Pins the entered annotation text to `target`, ignoring blank text and unassigned
+    /// particle IDs, and clears the text for the next one.
+    pub fn add_annotation(&mut self, target: AnnotationTarget) {
+        let text = self.annotation_text.trim();
+        if text.is_empty() || target == AnnotationTarget::Particle(0) {
+            return;
+        }
+        self.annotations
+            .push(Annotation::new(text.to_string(), target));
+        self.annotation_text.clear();
+    }
+
+    /// Replaces the body labels with one per `(particle ID, name)` pair.
+    pub fn set_body_labels<'a>(&mut self, labels: impl IntoIterator<Item = (u64, &'a str)>) {
+        self.body_labels = labels
+            .into_iter()
+            .map(|(id, name)| Annotation::new(name.to_string(), AnnotationTarget::Particle(id)))
+            .collect();
+    }
+
+    /// Returns the clock snapshot the readouts show, refreshed at most once per
+    /// [`INDICATOR_REFRESH_INTERVAL`].
+    pub fn readout_clock(&mut self) -> ClockSnapshot {
+        let clock = &self.clock;
+        self.clock_readout.get(Instant::now(), || clock.snapshot())
     }
 
     /// Returns whether GPU compute should drive the active simulation.
@@ -627,16 +1889,66 @@ impl UiState {
         self.active_computing_unit == ComputingUnit::Gpu
     }
 
+    /// Returns the precision forces are actually computed in: the GPU shader is always
+    /// single precision, and the CPU f32 path exists only for the Normal engine.
+    pub fn effective_precision(&self) -> Precision {
+        if self.uses_gpu_simulation()
+            || (self.active_simulation_type == SimulationType::Normal
+                && self.active_precision == Precision::Single)
+        {
+            Precision::Single
+        } else {
+            Precision::Double
+        }
+    }
+
+    /// Returns whether the CPU worker hands frames to the render loop without waiting for
+    /// each upload; the GPU path never uploads per frame, so it is unaffected.
+    pub fn uses_pipelined_stepping(&self) -> bool {
+        self.pipelined_stepping && !self.uses_gpu_simulation()
+    }
+
     /// Returns the simulation type currently driving CPU/GPU integration.
     pub fn active_simulation_type(&self) -> SimulationType {
         self.active_simulation_type
     }
 
+    /// Whether the selected engine differs from the running one and can take over its
+    /// particles without a reset. A GPU run can only switch to engines the GPU implements.
+    pub fn can_switch_engine(&self) -> bool {
+        self.simulation_type != self.active_simulation_type
+            && !self.is_reset_requested
+            && (self.simulation_type.has_gpu_kernel() || !self.uses_gpu_simulation())
+    }
+
+    /// Flags a switch of the running simulation to the selected engine, keeping particles.
+    pub fn request_engine_switch(&mut self) {
+        if self.can_switch_engine() {
+            self.engine_switch_requested = true;
+        }
+    }
+
+    /// Selects, for the next reset, the frame rotating with a particle's orbit about the
+    /// origin. The particle's velocity is measured in the running frame, so that frame's own
+    /// rotation is added back.
+    pub fn co_rotate_with_orbit(&mut self, position: DVec3, velocity: DVec3) {
+        self.frame_angular_velocity =
+            orbital_angular_velocity(position, velocity) + self.active_frame_angular_velocity;
+    }
+
     /// Disables particle append when simulation type changes until the next reset.
     pub fn apply_simulation_type_change(&mut self, previous_type: SimulationType) {
         if self.simulation_type != previous_type {
-            if !self.gpu_computing_available() {
-                self.force_cpu_computing_units();
+            if !self.gpu_computing_available() && self.computing_unit == ComputingUnit::Gpu {
+                // Only the selection: a running GPU simulation keeps its unit until reset.
+                self.computing_unit = ComputingUnit::Cpu;
+                self.toasts.post(
+                    ToastLevel::Warning,
+                    format!(
+                        "{} has no GPU kernel — fell back to CPU",
+                        self.simulation_type
+                    ),
+                );
             }
             self.disable_add_until_reset();
             self.clamp_velocity_inputs();
@@ -655,7 +1967,11 @@ impl UiState {
         }
         self.random_sphere.velocity_std =
             clamp_scalar_speed_m_s(self.random_sphere.velocity_std);
+        self.random_sphere.options.bulk_velocity =
+            clamp_velocity_m_s(self.random_sphere.options.bulk_velocity);
         self.random_cube.velocity_std = clamp_scalar_speed_m_s(self.random_cube.velocity_std);
+        self.random_cube.options.bulk_velocity =
+            clamp_velocity_m_s(self.random_cube.options.bulk_velocity);
         self.elliptical_orbit.planetary_speed =
             clamp_scalar_speed_m_s(self.elliptical_orbit.planetary_speed);
         self.single_particle.velocity = clamp_velocity_m_s(self.single_particle.velocity);
@@ -679,6 +1995,93 @@ impl UiState {
         }
     }
 
+    /// Switches to the placement mode `step` places along [`PlacementMode::ALL`] (wrapping)
+    /// and resets into it, for controls without the Object Input panel such as a gamepad.
+    pub fn cycle_placement_preset(&mut self, step: isize) {
+        let modes = PlacementMode::ALL;
+        let current = modes
+            .iter()
+            .position(|&mode| mode == self.placement_mode)
+            .unwrap_or(0);
+        let previous_mode = self.placement_mode;
+        self.placement_mode =
+            modes[(current as isize + step).rem_euclid(modes.len() as isize) as usize];
+        self.apply_placement_mode_change(previous_mode);
+        self.request_reset();
+    }
+
+    /// Returns the progress for the taskbar button: the batch sweep, or pending exports.
+    pub fn taskbar_progress(&self) -> TaskbarProgress {
+        taskbar_progress(
+            self.batch_job.as_ref().map(BatchJob::progress),
+            self.export_writer.status().pending,
+        )
+    }
+
+    /// Returns the fraction of the finite run done, when a run end time is set.
+    pub fn run_progress(&self) -> Option<f32> {
+        let end = self.run_target.end_time()?;
+        Some(run_progress(self.simulation_time, end))
+    }
+
+    /// Pauses at the run end time and requests the end snapshot when one is wanted.
+    pub fn finish_run_target(&mut self) {
+        self.is_running = false;
+        self.run_end_snapshot_requested = self.run_target.snapshot_at_end;
+        self.status.info(format!(
+            "Run finished at {}",
+            format_simulation_time(
+                self.simulation_time,
+                self.time_display_unit,
+                self.simulation_epoch
+            )
+        ));
+    }
+
+    /// Switches between windowed and fullscreen (F11).
+    pub fn toggle_fullscreen(&mut self) {
+        self.display_mode.fullscreen ^= true;
+        self.display_mode_changed = true;
+    }
+
+    /// Starts the kiosk playlist from its first entry, ending the tutorial.
+    pub fn start_kiosk(&mut self) {
+        self.tutorial.stop();
+        self.kiosk.start();
+    }
+
+    /// Ends kiosk mode, leaving the current scene running for the visitor. Returns whether
+    /// kiosk mode was active, so the input that ended it can be swallowed.
+    pub fn stop_kiosk(&mut self) -> bool {
+        if !self.kiosk.stop() {
+            return false;
+        }
+        self.status.info("Kiosk mode ended");
+        true
+    }
+
+    /// Advances kiosk mode: resets into the next playlist entry when one is due, and keeps
+    /// the simulation running between resets. Returns the camera motion for this tick.
+    pub fn tick_kiosk(&mut self, now: Instant) -> Option<CameraStep> {
+        let tick = self.kiosk.tick(now)?;
+        match tick.load {
+            Some(step) => self.load_kiosk_step(step),
+            None if !self.is_reset_requested && !self.is_resetting => self.is_running = true,
+            None => {}
+        }
+        Some(tick.camera)
+    }
+
+    fn load_kiosk_step(&mut self, step: KioskStep) {
+        let previous_mode = self.placement_mode;
+        self.placement_mode = step.placement;
+        self.apply_placement_mode_change(previous_mode);
+        let previous_type = self.object_input_type;
+        self.object_input_type = step.object_type;
+        self.apply_object_input_type_change(previous_type);
+        self.request_reset();
+    }
+
     /// Syncs scaled object-input parameters when the add type changes.
     pub fn apply_object_input_type_change(&mut self, previous_type: ObjectInputType) {
         if self.object_input_type == previous_type {
@@ -705,6 +2108,11 @@ impl UiState {
         self.max_fps = DEFAULT_MAX_FPS;
     }
 
+    /// Resets the Auto Speed target to the default render rate.
+    pub fn reset_target_render_fps_to_default(&mut self) {
+        self.target_render_fps = DEFAULT_TARGET_RENDER_FPS;
+    }
+
     /// Resets skip-drawing-frames to the default value.
     pub fn reset_skip_to_default(&mut self) {
         self.skip = DEFAULT_SKIP_DRAWING_FRAMES;
@@ -756,6 +2164,344 @@ impl UiState {
         self.is_trace_enabled = false;
     }
 
+    /// Returns whether the mass-profile overlay is due for recomputation at the current frame.
+    pub fn mass_profile_update_due(&self) -> bool {
+        if !self.show_mass_profile_overlay && !self.show_escaper_highlight {
+            return false;
+        }
+        match self.mass_profile_frame {
+            Some(last) => {
+                self.frame < last || self.frame - last >= self.mass_profile_interval.max(1) as i64
+            }
+            None => true,
+        }
+    }
+
+    /// Re-arms every trigger and forgets the energy reference so the next evaluation
+    /// captures a fresh one.
+    pub fn rearm_event_triggers(&mut self) {
+        for trigger in &mut self.event_triggers {
+            trigger.fired = false;
+        }
+        self.event_reference_energy = None;
+        self.event_check_frame = None;
+    }
+
+    /// Clears state tied to the replaced particles once the worker has applied a reset.
+    /// A soft reset keeps the dye and event-log history and skips the automatic Fit View.
+    pub fn finish_applied_reset(&mut self, epoch: Option<CalendarEpoch>) {
+        self.frame = 1;
+        self.clock_readout.invalidate();
+        self.simulation_time = 0.0;
+        self.simulation_epoch = epoch;
+        self.clear_selected_particle();
+        self.region_statistics = None;
+        self.invalidate_mass_profile();
+        self.phase_space_frame = None;
+        self.power_spectrum = None;
+        self.particle_groups.clear();
+        self.pending_group_recolor = None;
+        self.clear_worldlines();
+        self.rest_frame_particle_id = None;
+        self.frame_switcher = FrameSwitcher::default();
+        self.maneuvers.clear();
+        self.orbit_primary_id = None;
+        self.grid_alignment_applied = None;
+        self.minimap_frame = None;
+        self.annotations
+            .retain(|annotation| !annotation.target.is_particle());
+        self.body_labels.clear();
+        // IDs restart with the new particles; stop so a recording never mixes
+        // unrelated particles under one ID.
+        self.stop_trajectory_recording();
+        self.rearm_event_triggers();
+        self.energy_monitor.forget();
+        self.metrics.clear();
+        self.metric_sampler.forget();
+        self.encounter_pair = None;
+        self.kepler_pairs.clear();
+        self.block_level = None;
+        self.force_error = None;
+        self.tree_timing = None;
+        if self.reset_kind == ResetKind::Hard {
+            self.dye_injections.clear();
+            self.event_log.clear();
+            if self.auto_fit_on_reset || self.kiosk.is_active() {
+                self.fit_view_requested = true;
+            }
+        }
+    }
+
+    /// Restores the display options that are not persisted in settings (region color,
+    /// group coloring, overlays, phase-space axes) to their startup values.
+    pub fn restore_display_defaults(&mut self) {
+        let defaults = Self::default();
+        self.region_color = defaults.region_color;
+        self.show_region_outline = defaults.show_region_outline;
+        self.fof_color_by_group = defaults.fof_color_by_group;
+        self.group_color = defaults.group_color;
+        self.show_mass_profile_overlay = defaults.show_mass_profile_overlay;
+        self.show_escaper_highlight = defaults.show_escaper_highlight;
+        self.show_light_cone = defaults.show_light_cone;
+        self.relativistic_view = defaults.relativistic_view;
+        self.display_space = defaults.display_space;
+        self.phase_space_x = defaults.phase_space_x;
+        self.phase_space_y = defaults.phase_space_y;
+    }
+
+    /// Captures the open panels and view toggles as a profile named `name`.
+    pub fn capture_ui_profile(&mut self, name: &str) -> UiProfile {
+        let open_panels = PANELS
+            .iter()
+            .filter(|panel| *self.panel_open_mut(**panel))
+            .map(|panel| panel.label().to_string())
+            .collect();
+        UiProfile {
+            name: name.to_string(),
+            show_advanced: self.show_advanced_controls,
+            open_panels,
+            show_grid: self.show_grid,
+            show_axis_labels: self.show_axis_labels,
+            show_minimap: self.show_minimap,
+            split_view: self.split_view,
+            show_body_labels: self.show_body_labels,
+            lock_camera_up: self.lock_camera_up,
+        }
+    }
+
+    /// Switches to `profile`: opens exactly its panels and applies its view toggles.
+    pub fn apply_ui_profile(&mut self, profile: &UiProfile) {
+        self.ui_profile = profile.name.clone();
+        self.show_advanced_controls = profile.show_advanced;
+        for panel in PANELS {
+            *self.panel_open_mut(*panel) = profile
+                .open_panels
+                .iter()
+                .any(|label| label == panel.label());
+        }
+        self.show_grid = profile.show_grid;
+        self.show_axis_labels = profile.show_axis_labels;
+        self.show_minimap = profile.show_minimap;
+        self.split_view = profile.split_view;
+        self.show_body_labels = profile.show_body_labels;
+        self.lock_camera_up = profile.lock_camera_up;
+    }
+
+    /// Stores `profile`, replacing a saved profile of the same name, and makes it active.
+    /// Built-in profiles cannot be overwritten.
+    pub fn save_ui_profile(&mut self, profile: UiProfile) {
+        if is_builtin_ui_profile(&profile.name) {
+            return;
+        }
+        self.ui_profile = profile.name.clone();
+        match self
+            .ui_profiles
+            .iter_mut()
+            .find(|saved| saved.name == profile.name)
+        {
+            Some(saved) => *saved = profile,
+            None => self.ui_profiles.push(profile),
+        }
+    }
+
+    /// Deletes the active saved profile and switches to the default one.
+    pub fn delete_ui_profile(&mut self) {
+        if is_builtin_ui_profile(&self.ui_profile) {
+            return;
+        }
+        let name = std::mem::take(&mut self.ui_profile);
+        self.ui_profiles.retain(|profile| profile.name != name);
+        if let Some(profile) = find_ui_profile(&self.ui_profiles, DEFAULT_UI_PROFILE) {
+            self.apply_ui_profile(&profile);
+        }
+    }
+
+    /// Appends a line to the event log, dropping the oldest beyond [`EVENT_LOG_CAPACITY`].
+    pub fn push_event_log(&mut self, line: String) {
+        self.event_log.push(line);
+        let excess = self.event_log.len().saturating_sub(EVENT_LOG_CAPACITY);
+        self.event_log.drain(..excess);
+    }
+
+    /// Adds `id` to the tracked trajectory set unless it is already present.
+    pub fn track_trajectory_id(&mut self, id: u64) {
+        if id != 0 && !self.trajectory_ids.contains(&id) {
+            self.trajectory_ids.push(id);
+        }
+    }
+
+    /// Returns the settings the running simulation steps with: the force settings as they
+    /// are now, and the precision, speed of light, cosmology, and frame rotation of the last
+    /// reset.
+    pub fn engine_config(&self) -> EngineConfig {
+        let mut config = EngineConfig {
+            summation: self.force_summation,
+            precision: self.active_precision,
+            force_solver: self.force_solver,
+            cosmology: self.active_cosmology,
+            frame_angular_velocity: self.active_frame_angular_velocity,
+            ..EngineConfig::default()
+        };
+        config.set_opening_angle(self.opening_angle);
+        config.set_tree_rebuild_interval(self.tree_rebuild_interval);
+        config.set_expansion_order(self.expansion_order);
+        config.set_light_speed_factor(self.active_light_speed_factor);
+        config
+    }
+
+    /// Returns the settings batch runs step with: those a simulation reset now would use,
+    /// but in the inertial frame.
+    fn batch_engine_config(&self) -> EngineConfig {
+        let mut config = EngineConfig {
+            precision: self.precision,
+            cosmology: self.cosmology,
+            frame_angular_velocity: 0.0,
+            ..self.engine_config()
+        };
+        config.set_light_speed_factor(self.light_speed_factor);
+        config
+    }
+
+    /// Returns the speed of light of the running simulation in meters per second.
+    pub fn light_speed(&self) -> f64 {
+        LIGHT_SPEED * self.active_light_speed_factor
+    }
+
+    /// Builds the batch parameter matrix from the Batch panel and the current placement,
+    /// particle count, and base scale.
+    pub fn build_batch_config(&self) -> Result<BatchConfig, String> {
+        let time_steps = parse_value_list(&self.batch_time_steps)?;
+        if self.batch_simulation_types.is_empty() {
+            return Err("No simulation types selected".to_string());
+        }
+        Ok(BatchConfig {
+            object_input: self.build_reset_object_input(),
+            particle_count: self.add_particle_count,
+            scale: self.base_scale,
+            duration: self.batch_duration,
+            time_steps,
+            simulation_types: self.batch_simulation_types.clone(),
+            engine: self.batch_engine_config(),
+        })
+    }
+
+    /// Flushes and closes the active trajectory recording, if any.
+    pub fn stop_trajectory_recording(&mut self) {
+        if let Some(recorder) = self.trajectory_recorder.take()
+            && let Err(e) = recorder.finish()
+        {
+            self.status
+                .error(format!("Failed to finish trajectory export: {}", e));
+        }
+        self.trajectory_record_frame = None;
+    }
+
+    /// Returns whether an active trajectory recording should write a sample this frame.
+    pub fn trajectory_sample_due(&self) -> bool {
+        if self.trajectory_recorder.is_none() {
+            return false;
+        }
+        match self.trajectory_record_frame {
+            Some(last) => self.frame - last >= self.trajectory_interval.max(1) as i64,
+            None => true,
+        }
+    }
+
+    /// Returns whether worldline recording should sample the tracked particles this frame.
+    /// Only the Special engines have a light-speed limit to draw a Minkowski diagram for.
+    pub fn worldline_sample_due(&self) -> bool {
+        if !self.worldline_recording
+            || self.trajectory_ids.is_empty()
+            || !self.active_simulation_type.is_special_relativistic()
+        {
+            return false;
+        }
+        match self.worldline_frame {
+            Some(last) => self.frame < last || self.frame - last >= WORLDLINE_INTERVAL,
+            None => true,
+        }
+    }
+
+    /// Draws particles in the rest frame of the particle with `id`, or in the simulation
+    /// frame with `None`, blending from the frame shown now.
+    pub fn set_rest_frame_particle(&mut self, id: Option<u64>) {
+        if self.rest_frame_particle_id != id {
+            self.rest_frame_particle_id = id;
+            self.frame_switcher.begin(Instant::now());
+        }
+    }
+
+    /// Discards the recorded worldlines; recording, if on, starts over at the next sample.
+    pub fn clear_worldlines(&mut self) {
+        self.worldlines.clear();
+        self.worldline_frame = None;
+    }
+
+    /// Restarts the Thomas precession demonstration from the current panel settings.
+    pub fn restart_thomas_precession(&mut self) {
+        self.thomas_precession =
+            ThomasPrecession::new(self.thomas_beta, self.thomas_steps_per_revolution);
+        self.thomas_revision += 1;
+    }
+
+    /// Returns whether the open Phase Space panel should resample particles.
+    pub fn phase_space_update_due(&self) -> bool {
+        if !self.is_phase_space_panel_open {
+            return false;
+        }
+        match self.phase_space_frame {
+            Some(last) => self.frame < last || self.frame - last >= PHASE_SPACE_REFRESH_FRAMES,
+            None => true,
+        }
+    }
+
+    /// Returns whether the light cone must be recomputed for a camera at `observer`: the
+    /// camera moved or [`LIGHT_CONE_INTERVAL`] frames passed. Never due for DstGalaxy,
+    /// whose display positions are not flat space.
+    pub fn light_cone_update_due(&self, observer: DVec3) -> bool {
+        if !self.show_light_cone || self.active_simulation_type == SimulationType::DstGalaxy {
+            return false;
+        }
+        if self.light_cone_observer != Some(observer) {
+            return true;
+        }
+        match self.light_cone_frame {
+            Some(last) => self.frame < last || self.frame - last >= LIGHT_CONE_INTERVAL,
+            None => true,
+        }
+    }
+
+    /// Returns whether the grid plane must be recomputed: the alignment changed, or
+    /// [`GRID_ALIGNMENT_INTERVAL`] frames passed while following the invariant plane.
+    pub fn grid_alignment_due(&self) -> bool {
+        match self.grid_alignment_applied {
+            Some((alignment, frame)) if alignment == self.grid_alignment => {
+                self.grid_alignment == GridAlignment::InvariantPlane
+                    && (self.frame < frame || self.frame - frame >= GRID_ALIGNMENT_INTERVAL)
+            }
+            _ => true,
+        }
+    }
+
+    /// Returns whether the shown minimap should re-measure the system bounds.
+    pub fn minimap_update_due(&self) -> bool {
+        if !self.show_minimap {
+            return false;
+        }
+        match self.minimap_frame {
+            Some(last) => self.frame < last || self.frame - last >= MINIMAP_INTERVAL,
+            None => true,
+        }
+    }
+
+    /// Drops the cached mass profile so the overlay is recomputed from fresh particles.
+    pub fn invalidate_mass_profile(&mut self) {
+        self.mass_profile = None;
+        self.mass_profile_frame = None;
+        self.escaper_positions.clear();
+        self.mass_profile_revision += 1;
+    }
+
     /// Applies the Escape shortcut: stop simulation, disable trace, clear ⊕ steer anchor.
     ///
     /// Returns `true` when the ⊕ steer anchor was cleared (caller may request redraw).
@@ -817,10 +2563,121 @@ impl UiState {
         self.reset_log.abort_requested.load(Ordering::Acquire)
     }
 
-    /// Flags a simulation reset and re-enables particle append.
+    /// Captures the panel parameters covered by undo.
+    pub fn parameters(&self) -> UiParameters {
+        UiParameters {
+            time_per_frame: self.time_per_frame,
+            scale_gauge: self.scale_gauge,
+            max_fps: self.max_fps,
+            max_fps_unlimited: self.max_fps_unlimited,
+            skip: self.skip,
+            add_particle_count: self.add_particle_count,
+            add_center: self.add_center,
+            is_add_particles_enabled: self.is_add_particles_enabled,
+            object_input_type: self.object_input_type,
+            placement_mode: self.placement_mode,
+            simulation_type: self.simulation_type,
+            computing_unit: self.computing_unit,
+            precision: self.precision,
+            pipelined_stepping: self.pipelined_stepping,
+            base_scale: self.base_scale,
+            base_scale_unit: self.base_scale_unit,
+            galaxy_cull_enabled: self.galaxy_cull_enabled,
+            galaxy_cull_max_angle: self.galaxy_cull_max_angle,
+            random_sphere: self.random_sphere.clone(),
+            random_cube: self.random_cube.clone(),
+            spiral_disk: self.spiral_disk.clone(),
+            solar_system: self.solar_system.clone(),
+            satellite_orbit: self.satellite_orbit.clone(),
+            elliptical_orbit: self.elliptical_orbit.clone(),
+            single_particle: self.single_particle.clone(),
+            keplerian_system: self.keplerian_system.clone(),
+            region: self.region,
+            region_color: self.region_color,
+        }
+    }
+
+    /// Describes the running scenario for crash reports: the engine, progress, and the
+    /// panel parameters.
+    pub fn crash_context(&self, particle_count: usize) -> String {
+        format!(
+            "Engine: {} on {}\nParticles: {}\nFrame: {}\nSimulation time: {} s\n\
+             Scale: {} m\nRunning: {}\n{:#?}",
+            self.active_simulation_type,
+            if self.uses_gpu_simulation() {
+                "GPU"
+            } else {
+                "CPU"
+            },
+            particle_count,
+            self.frame,
+            self.simulation_time,
+            self.scale,
+            self.is_running,
+            self.parameters()
+        )
+    }
+
+    /// Restores panel parameters captured by [`Self::parameters`].
+    pub fn apply_parameters(&mut self, parameters: UiParameters) {
+        self.time_per_frame = parameters.time_per_frame;
+        self.scale_gauge = parameters.scale_gauge;
+        self.max_fps = parameters.max_fps;
+        self.max_fps_unlimited = parameters.max_fps_unlimited;
+        self.skip = parameters.skip;
+        self.add_particle_count = parameters.add_particle_count;
+        self.add_center = parameters.add_center;
+        self.is_add_particles_enabled = parameters.is_add_particles_enabled;
+        self.object_input_type = parameters.object_input_type;
+        self.placement_mode = parameters.placement_mode;
+        self.simulation_type = parameters.simulation_type;
+        self.computing_unit = parameters.computing_unit;
+        self.precision = parameters.precision;
+        self.pipelined_stepping = parameters.pipelined_stepping;
+        self.base_scale = parameters.base_scale;
+        self.base_scale_unit = parameters.base_scale_unit;
+        self.galaxy_cull_enabled = parameters.galaxy_cull_enabled;
+        self.galaxy_cull_max_angle = parameters.galaxy_cull_max_angle;
+        self.random_sphere = parameters.random_sphere;
+        self.random_cube = parameters.random_cube;
+        self.spiral_disk = parameters.spiral_disk;
+        self.solar_system = parameters.solar_system;
+        self.satellite_orbit = parameters.satellite_orbit;
+        self.elliptical_orbit = parameters.elliptical_orbit;
+        self.single_particle = parameters.single_particle;
+        self.keplerian_system = parameters.keplerian_system;
+        self.region = parameters.region;
+        self.region_color = parameters.region_color;
+    }
+
+    /// Flags a hard simulation reset and re-enables particle append.
     pub fn request_reset(&mut self) {
+        self.request_reset_of(ResetKind::Hard);
+    }
+
+    /// Flags a soft reset that keeps the camera, color options, and diagnostics history.
+    pub fn request_soft_reset(&mut self) {
+        self.request_reset_of(ResetKind::Soft);
+    }
+
+    fn request_reset_of(&mut self, kind: ResetKind) {
+        self.reset_kind = kind;
         self.commit_active_computing_unit();
         self.commit_active_simulation_type();
+        self.active_precision = self.precision;
+        self.active_light_speed_factor =
+            if self.active_simulation_type.requires_subluminal_velocity() {
+                self.light_speed_factor
+            } else {
+                1.0
+            };
+        self.active_frame_angular_velocity =
+            if self.active_simulation_type.supports_rotating_frame() {
+                self.frame_angular_velocity
+            } else {
+                0.0
+            };
+        self.active_cosmology = self.cosmology;
         self.is_running = false;
         self.is_reset_requested = true;
         self.is_resetting = true;
@@ -880,6 +2737,7 @@ impl UiState {
                     radius,
                     mass_range,
                     velocity_std,
+                    options: self.random_sphere.options,
                 };
             }
             ObjectInput::RandomCube {
@@ -892,6 +2750,7 @@ impl UiState {
                     cube_size,
                     mass_range,
                     velocity_std,
+                    options: self.random_cube.options,
                 };
             }
             ObjectInput::SpiralDisk {
@@ -932,6 +2791,16 @@ impl UiState {
                     color,
                 };
             }
+            ObjectInput::KeplerianSystem {
+                primary_mass,
+                bodies,
+                ..
+            } => {
+                self.keplerian_system = KeplerianSystemParameters {
+                    primary_mass,
+                    bodies,
+                };
+            }
             ObjectInput::SolarSystem { .. } | ObjectInput::SatelliteOrbit { .. } => unreachable!(),
         }
     }
@@ -945,6 +2814,7 @@ impl UiState {
             ObjectInputType::SpiralDisk => self.spiral_disk.to_object_input(scale),
             ObjectInputType::EllipticalOrbit => self.elliptical_orbit.to_object_input(scale),
             ObjectInputType::SingleParticle => self.single_particle.to_object_input(scale),
+            ObjectInputType::KeplerianSystem => self.keplerian_system.to_object_input(scale),
         }
     }
 
@@ -953,6 +2823,14 @@ impl UiState {
         !matches!(self.placement_mode, PlacementMode::Manual)
     }
 
+    /// Returns the calendar epoch a reset with the current placement mode starts from.
+    pub fn reset_simulation_epoch(&self) -> Option<CalendarEpoch> {
+        (self.placement_mode == PlacementMode::SolarSystem).then(|| {
+            let ss = &self.solar_system;
+            CalendarEpoch::new(ss.start_year, ss.start_month, ss.start_day, ss.start_hour)
+        })
+    }
+
     /// Builds object input for simulation reset from the current placement mode.
     pub fn build_reset_object_input(&self) -> ObjectInput {
         let scale = self.base_scale;
@@ -970,7 +2848,10 @@ impl UiState {
             self.max_fps = 1000;
             self.skip = 10;
         } else if self.placement_mode == PlacementMode::Manual
-            && self.object_input_type == ObjectInputType::EllipticalOrbit
+            && matches!(
+                self.object_input_type,
+                ObjectInputType::EllipticalOrbit | ObjectInputType::KeplerianSystem
+            )
         {
             self.time_per_frame = 100_000.0;
             self.max_fps = 1000;
@@ -983,10 +2864,12 @@ impl UiState {
     }
 }
 
+#[derive(Clone, PartialEq, Debug)]
 pub struct RandomSphereParameters {
     pub radius: f64,
     pub mass_range: (f64, f64),
     pub velocity_std: f64,
+    pub options: RandomClusterOptions,
 }
 
 impl RandomSphereParameters {
@@ -997,6 +2880,7 @@ impl RandomSphereParameters {
             radius: self.radius,
             mass_range: self.mass_range,
             velocity_std: self.velocity_std,
+            options: self.options,
         }
     }
 }
@@ -1008,6 +2892,7 @@ impl Default for RandomSphereParameters {
             radius,
             mass_range,
             velocity_std,
+            options,
             ..
         } = ObjectInputType::RandomSphere.to_object_input(1e10)
         {
@@ -1015,6 +2900,7 @@ impl Default for RandomSphereParameters {
                 radius,
                 mass_range,
                 velocity_std,
+                options,
             }
         } else {
             panic!();
@@ -1022,10 +2908,12 @@ impl Default for RandomSphereParameters {
     }
 }
 
+#[derive(Clone, PartialEq, Debug)]
 pub struct RandomCubeParameters {
     pub cube_size: f64,
     pub mass_range: (f64, f64),
     pub velocity_std: f64,
+    pub options: RandomClusterOptions,
 }
 
 impl RandomCubeParameters {
@@ -1036,6 +2924,7 @@ impl RandomCubeParameters {
             cube_size: self.cube_size,
             mass_range: self.mass_range,
             velocity_std: self.velocity_std,
+            options: self.options,
         }
     }
 }
@@ -1047,6 +2936,7 @@ impl Default for RandomCubeParameters {
             cube_size,
             mass_range,
             velocity_std,
+            options,
             ..
         } = ObjectInputType::RandomCube.to_object_input(1e10)
         {
@@ -1054,6 +2944,7 @@ impl Default for RandomCubeParameters {
                 cube_size,
                 mass_range,
                 velocity_std,
+                options,
             }
         } else {
             panic!();
@@ -1061,6 +2952,7 @@ impl Default for RandomCubeParameters {
     }
 }
 
+#[derive(Clone, PartialEq, Debug)]
 pub struct SpiralDiskParameters {
     pub disk_radius: f64,
     pub mass_fixed: f64,
@@ -1096,6 +2988,7 @@ impl Default for SpiralDiskParameters {
     }
 }
 
+#[derive(Clone, PartialEq, Debug)]
 pub struct SolarSystemParameters {
     pub start_year: i32,
     pub start_month: i32,
@@ -1128,6 +3021,7 @@ impl Default for SolarSystemParameters {
     }
 }
 
+#[derive(Clone, PartialEq, Debug)]
 pub struct SatelliteOrbitParameters {
     pub orbit_altitude_min: f64,
     pub orbit_altitude_max: f64,
@@ -1162,6 +3056,7 @@ impl Default for SatelliteOrbitParameters {
     }
 }
 
+#[derive(Clone, PartialEq, Debug)]
 pub struct EllipticalOrbitParameters {
     pub central_mass: f64,
     pub planetary_mass: f64,
@@ -1205,6 +3100,7 @@ impl Default for EllipticalOrbitParameters {
     }
 }
 
+#[derive(Clone, PartialEq, Debug)]
 pub struct SingleParticleParameters {
     pub mass: f64,
     pub position: DVec3,
@@ -1247,3 +3143,62 @@ impl Default for SingleParticleParameters {
         }
     }
 }
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct KeplerianSystemParameters {
+    pub primary_mass: f64,
+    pub bodies: Vec<OrbitingBody>,
+}
+
+impl KeplerianSystemParameters {
+    /// Builds a Keplerian-system object input from panel parameters.
+    pub fn to_object_input(&self, scale: f64) -> ObjectInput {
+        ObjectInput::KeplerianSystem {
+            scale,
+            primary_mass: self.primary_mass,
+            bodies: self.bodies.clone(),
+        }
+    }
+
+    /// Appends a new body on a circular orbit just outside the outermost existing one.
+    pub fn push_body(&mut self) {
+        let outermost = self
+            .bodies
+            .iter()
+            .map(|body| body.elements.semi_major_axis.abs())
+            .fold(0.0, f64::max);
+        let radius = if outermost > 0.0 { outermost * 1.5 } else { AU };
+        let color_index = self.bodies.len() % ParticleBasicColor::ALL.len();
+        self.bodies.push(OrbitingBody {
+            mass: self.bodies.last().map_or(5.972e24, |body| body.mass),
+            elements: OrbitalElements::circular(radius, 0.0),
+            color: ParticleBasicColor::ALL[color_index],
+        });
+    }
+
+    /// Removes the body at `index`; out-of-range indices are ignored.
+    pub fn remove_body(&mut self, index: usize) {
+        if index < self.bodies.len() {
+            self.bodies.remove(index);
+        }
+    }
+}
+
+impl Default for KeplerianSystemParameters {
+    /// Loads default Keplerian-system parameter values from object-input presets.
+    fn default() -> Self {
+        if let ObjectInput::KeplerianSystem {
+            primary_mass,
+            bodies,
+            ..
+        } = ObjectInputType::KeplerianSystem.to_object_input(1.5e11)
+        {
+            Self {
+                primary_mass,
+                bodies,
+            }
+        } else {
+            panic!();
+        }
+    }
+}
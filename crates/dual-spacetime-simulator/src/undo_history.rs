@@ -0,0 +1,169 @@
+use crate::cosmology::Cosmology;
+use crate::object_input::ObjectInputType;
+use crate::region_selection::SelectionRegion;
+use crate::simulation::Particle;
+use crate::time_format::CalendarEpoch;
+use crate::ui_state::{
+    BaseScaleUnit, ComputingUnit, EllipticalOrbitParameters, KeplerianSystemParameters,
+    PlacementMode, Precision, RandomCubeParameters, RandomSphereParameters,
+    SatelliteOrbitParameters, SimulationType, SingleParticleParameters, SolarSystemParameters,
+    SpiralDiskParameters,
+};
+use glam::DVec3;
+use std::collections::VecDeque;
+
+/// Undo steps kept; the oldest is dropped beyond this. Each step may hold a particle copy.
+pub const UNDO_HISTORY_CAPACITY: usize = 32;
+
+/// Panel parameters covered by undo: everything a slider, combo box, or text field in the
+/// Simulation, Object Input, and Region panels edits.
+#[derive(Clone, PartialEq, Debug)]
+pub struct UiParameters {
+    pub time_per_frame: f64,
+    pub scale_gauge: f64,
+    pub max_fps: u32,
+    pub max_fps_unlimited: bool,
+    pub skip: u32,
+    pub add_particle_count: u32,
+    pub add_center: DVec3,
+    pub is_add_particles_enabled: bool,
+    pub object_input_type: ObjectInputType,
+    pub placement_mode: PlacementMode,
+    pub simulation_type: SimulationType,
+    pub computing_unit: ComputingUnit,
+    pub precision: Precision,
+    pub pipelined_stepping: bool,
+    pub base_scale: f64,
+    pub base_scale_unit: BaseScaleUnit,
+    pub galaxy_cull_enabled: bool,
+    pub galaxy_cull_max_angle: f64,
+    pub random_sphere: RandomSphereParameters,
+    pub random_cube: RandomCubeParameters,
+    pub spiral_disk: SpiralDiskParameters,
+    pub solar_system: SolarSystemParameters,
+    pub satellite_orbit: SatelliteOrbitParameters,
+    pub elliptical_orbit: EllipticalOrbitParameters,
+    pub single_particle: SingleParticleParameters,
+    pub keplerian_system: KeplerianSystemParameters,
+    pub region: SelectionRegion,
+    pub region_color: [f32; 3],
+}
+
+/// Particle state replaced by a reset or deletion, in the engine's own representation.
+#[derive(Clone, Debug)]
+pub struct ParticleCheckpoint {
+    pub particles: Vec<Particle>,
+    pub simulation_type: SimulationType,
+    pub computing_unit: ComputingUnit,
+    pub scale: f64,
+    pub frame: i64,
+    pub simulation_time: f64,
+    pub simulation_epoch: Option<CalendarEpoch>,
+    /// Expansion history and cosmic time of a comoving simulation, which fix its scale
+    /// factor.
+    pub cosmic_time: Option<(Cosmology, f64)>,
+}
+
+/// One undo step: the parameters before the edit and, for resets and deletions, the
+/// particles they replaced.
+#[derive(Clone, Debug)]
+pub struct UndoEntry {
+    pub parameters: UiParameters,
+    pub particles: Option<ParticleCheckpoint>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UndoDirection {
+    Undo,
+    Redo,
+}
+
+/// Undo and redo stacks. A slider drag becomes one step: the parameters from before the
+/// pointer went down are kept until it is released.
+#[derive(Default)]
+pub struct UndoHistory {
+    undo: VecDeque<UndoEntry>,
+    redo: Vec<UndoEntry>,
+    gesture_start: Option<UiParameters>,
+}
+
+impl UndoHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records one UI frame that went from `before` to `after`. `gesture_active` defers the
+    /// step until the pointer is released; a `checkpoint` (reset or deletion) is always
+    /// recorded immediately together with the parameters.
+    pub fn record_frame(
+        &mut self,
+        before: UiParameters,
+        after: &UiParameters,
+        gesture_active: bool,
+        checkpoint: Option<ParticleCheckpoint>,
+    ) {
+        if checkpoint.is_some() {
+            let parameters = self.gesture_start.take().unwrap_or(before);
+            self.push(UndoEntry {
+                parameters,
+                particles: checkpoint,
+            });
+            return;
+        }
+        if before != *after && self.gesture_start.is_none() {
+            self.gesture_start = Some(before);
+        }
+        if gesture_active {
+            return;
+        }
+        if let Some(start) = self.gesture_start.take()
+            && start != *after
+        {
+            self.push(UndoEntry {
+                parameters: start,
+                particles: None,
+            });
+        }
+    }
+
+    /// Adds a new step and discards the redo stack.
+    pub fn push(&mut self, entry: UndoEntry) {
+        self.redo.clear();
+        self.push_undo(entry);
+    }
+
+    fn push_undo(&mut self, entry: UndoEntry) {
+        if self.undo.len() == UNDO_HISTORY_CAPACITY {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(entry);
+    }
+
+    pub fn take_undo(&mut self) -> Option<UndoEntry> {
+        self.gesture_start = None;
+        self.undo.pop_back()
+    }
+
+    pub fn take_redo(&mut self) -> Option<UndoEntry> {
+        self.gesture_start = None;
+        self.redo.pop()
+    }
+
+    /// Stores the state an undo replaced so it can be redone.
+    pub fn push_redo(&mut self, entry: UndoEntry) {
+        self.redo.push(entry);
+    }
+
+    /// Stores the state a redo replaced, keeping the remaining redo steps.
+    pub fn push_after_redo(&mut self, entry: UndoEntry) {
+        self.push_undo(entry);
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
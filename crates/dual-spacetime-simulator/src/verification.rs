@@ -0,0 +1,525 @@
+use crate::events::total_energy;
+use crate::simulation::{EPSILON, EngineConfig, G, Particle, SimulationManager, Summation};
+use crate::tree_gravity::ForceSolver;
+use crate::ui_state::{Precision, SimulationType};
+use rayon::prelude::*;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::thread::JoinHandle;
+
+/// Default number of steps run by the Verification panel.
+pub const DEFAULT_VERIFICATION_STEPS: u32 = 200;
+/// Largest step count the Verification panel accepts.
+pub const MAX_VERIFICATION_STEPS: u32 = 1000;
+/// The reference engine is O(N²) in double-double arithmetic, so it refuses larger systems.
+pub const MAX_VERIFICATION_PARTICLES: usize = 512;
+
+/// Default number of frames the determinism audit runs.
+pub const DEFAULT_DETERMINISM_STEPS: u32 = 100;
+/// The audit holds and steps two copies of the engine, so it refuses larger systems.
+pub const MAX_DETERMINISM_PARTICLES: usize = 4096;
+
+/// Unevaluated sum `hi + lo` of two `f64`s (~106-bit significand).
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct DoubleDouble {
+    pub hi: f64,
+    pub lo: f64,
+}
+
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let bb = s - a;
+    (s, (a - (s - bb)) + (b - bb))
+}
+
+fn quick_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    (s, b - (s - a))
+}
+
+impl DoubleDouble {
+    pub const ZERO: Self = Self { hi: 0.0, lo: 0.0 };
+
+    fn from_pair((hi, lo): (f64, f64)) -> Self {
+        Self { hi, lo }
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.hi + self.lo
+    }
+
+    pub fn sqrt(self) -> Self {
+        if self.hi <= 0.0 {
+            return Self::ZERO;
+        }
+        let q = Self::from(self.hi.sqrt());
+        let r = self - q * q;
+        q + Self::from(r.hi / (2.0 * q.hi))
+    }
+}
+
+impl From<f64> for DoubleDouble {
+    fn from(value: f64) -> Self {
+        Self { hi: value, lo: 0.0 }
+    }
+}
+
+impl Add for DoubleDouble {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        let (s, e) = two_sum(self.hi, rhs.hi);
+        let (t, f) = two_sum(self.lo, rhs.lo);
+        let (s, e) = quick_two_sum(s, e + t);
+        Self::from_pair(quick_two_sum(s, e + f))
+    }
+}
+
+impl Neg for DoubleDouble {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self {
+            hi: -self.hi,
+            lo: -self.lo,
+        }
+    }
+}
+
+impl Sub for DoubleDouble {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Add::add(self, -rhs)
+    }
+}
+
+impl Mul for DoubleDouble {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        let p = self.hi * rhs.hi;
+        let e = self.hi.mul_add(rhs.hi, -p) + (self.hi * rhs.lo + self.lo * rhs.hi);
+        Self::from_pair(quick_two_sum(p, e))
+    }
+}
+
+impl Div for DoubleDouble {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        let q1 = self.hi / rhs.hi;
+        let r = self - rhs * Self::from(q1);
+        let q2 = r.hi / rhs.hi;
+        let r = r - rhs * Self::from(q2);
+        let q3 = r.hi / rhs.hi;
+        Self::from_pair(quick_two_sum(q1, q2)) + Self::from(q3)
+    }
+}
+
+/// Three double-double components.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct DdVec3 {
+    pub x: DoubleDouble,
+    pub y: DoubleDouble,
+    pub z: DoubleDouble,
+}
+
+impl DdVec3 {
+    pub const ZERO: Self = Self {
+        x: DoubleDouble::ZERO,
+        y: DoubleDouble::ZERO,
+        z: DoubleDouble::ZERO,
+    };
+
+    pub fn from_dvec3(v: glam::DVec3) -> Self {
+        Self {
+            x: v.x.into(),
+            y: v.y.into(),
+            z: v.z.into(),
+        }
+    }
+
+    pub fn to_dvec3(self) -> glam::DVec3 {
+        glam::DVec3::new(self.x.to_f64(), self.y.to_f64(), self.z.to_f64())
+    }
+
+    fn scale(self, s: DoubleDouble) -> Self {
+        Self {
+            x: self.x * s,
+            y: self.y * s,
+            z: self.z * s,
+        }
+    }
+
+    fn length_squared(self) -> DoubleDouble {
+        self.x * self.x + self.y * self.y + self.z * self.z
+    }
+}
+
+impl Add for DdVec3 {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+            z: self.z + rhs.z,
+        }
+    }
+}
+
+impl Sub for DdVec3 {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+            z: self.z - rhs.z,
+        }
+    }
+}
+
+/// Newtonian reference engine in double-double arithmetic. It mirrors the Normal engine
+/// step for step (drift, then kick) with the same `ε` cut-off, so any difference from it
+/// is rounding error of the fast path.
+pub struct ReferenceNewtonian {
+    pub positions: Vec<DdVec3>,
+    pub velocities: Vec<DdVec3>,
+    pub masses: Vec<DoubleDouble>,
+}
+
+impl ReferenceNewtonian {
+    pub fn new(particles: &[Particle]) -> Self {
+        Self {
+            positions: particles
+                .iter()
+                .map(|p| DdVec3::from_dvec3(p.position))
+                .collect(),
+            velocities: particles
+                .iter()
+                .map(|p| DdVec3::from_dvec3(p.velocity))
+                .collect(),
+            masses: particles.iter().map(|p| p.mass.into()).collect(),
+        }
+    }
+
+    pub fn step(&mut self, delta_seconds: f64) {
+        let dt = DoubleDouble::from(delta_seconds);
+        for (position, velocity) in self.positions.iter_mut().zip(&self.velocities) {
+            *position = *position + velocity.scale(dt);
+        }
+        let time_g = DoubleDouble::from(G) * dt;
+        let positions = &self.positions;
+        let masses = &self.masses;
+        self.velocities
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(i, velocity)| {
+                let mut acceleration = DdVec3::ZERO;
+                for (j, (&pos_j, &mass_j)) in positions.iter().zip(masses).enumerate() {
+                    let diff = pos_j - positions[i];
+                    let distance_sq = diff.length_squared();
+                    if j == i || distance_sq.hi < EPSILON {
+                        continue;
+                    }
+                    let factor = time_g * mass_j / (distance_sq * distance_sq.sqrt());
+                    acceleration = acceleration + diff.scale(factor);
+                }
+                *velocity = *velocity + acceleration;
+            });
+    }
+
+    /// Copies `particles` with positions and velocities replaced by the reference state.
+    pub fn to_particles(&self, particles: &[Particle]) -> Vec<Particle> {
+        particles
+            .iter()
+            .zip(self.positions.iter().zip(&self.velocities))
+            .map(|(p, (position, velocity))| Particle {
+                position: position.to_dvec3(),
+                velocity: velocity.to_dvec3(),
+                ..*p
+            })
+            .collect()
+    }
+}
+
+/// Deviation of the CPU Normal engine from the double-double reference after `steps`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct VerificationReport {
+    pub steps: u32,
+    pub time_per_frame: f64,
+    /// Largest per-particle position difference (base-scale units).
+    pub max_position_error: f64,
+    /// Largest per-particle velocity difference (base-scale units per second).
+    pub max_velocity_error: f64,
+    /// `|E - E₀| / |E₀|` of the fast engine.
+    pub fast_energy_drift: f64,
+    /// `|E - E₀| / |E₀|` of the reference engine (integrator error alone).
+    pub reference_energy_drift: f64,
+}
+
+fn relative_drift(initial: f64, current: f64) -> f64 {
+    if initial != 0.0 {
+        ((current - initial) / initial).abs()
+    } else {
+        f64::NAN
+    }
+}
+
+/// Returns the settings the fast run of a verification steps with: the Normal engine's
+/// direct force sum in the inertial frame, as the reference engine integrates, with the
+/// given summation and precision.
+pub fn verification_config(summation: Summation, precision: Precision) -> EngineConfig {
+    EngineConfig {
+        summation,
+        precision,
+        force_solver: ForceSolver::Direct,
+        frame_angular_velocity: 0.0,
+        ..EngineConfig::default()
+    }
+}
+
+/// Runs the CPU Normal engine with [`verification_config`] and the reference engine side
+/// by side from live `particles` for `steps` frames, bumping `completed` after each frame.
+/// Stops early when `abort` is set, reporting the frames run so far.
+pub fn verify_normal_engine(
+    particles: &[Particle],
+    time_per_frame: f64,
+    steps: u32,
+    summation: Summation,
+    precision: Precision,
+    completed: &AtomicU32,
+    abort: &AtomicBool,
+) -> Result<VerificationReport, String> {
+    let live: Vec<Particle> = particles
+        .iter()
+        .filter(|p| p.color[3] != 0.0)
+        .copied()
+        .collect();
+    if live.is_empty() {
+        return Err("No particles".to_string());
+    }
+    if live.len() > MAX_VERIFICATION_PARTICLES {
+        return Err(format!(
+            "Too many particles ({} > {})",
+            live.len(),
+            MAX_VERIFICATION_PARTICLES
+        ));
+    }
+    let fast = SimulationManager::with_config(verification_config(summation, precision));
+    fast.reset_from_particles(live.clone(), SimulationType::Normal, 1.0);
+    let mut reference = ReferenceNewtonian::new(&live);
+    let mut run = 0;
+    while run < steps && !abort.load(Ordering::Relaxed) {
+        fast.advance(time_per_frame);
+        reference.step(time_per_frame);
+        run += 1;
+        completed.fetch_add(1, Ordering::Relaxed);
+    }
+    let fast_particles = fast.particles();
+    let reference_particles = reference.to_particles(&live);
+    let max_error = |f: fn(&Particle) -> glam::DVec3| {
+        fast_particles
+            .iter()
+            .zip(&reference_particles)
+            .map(|(a, b)| f(a).distance(f(b)))
+            .fold(0.0, f64::max)
+    };
+    let initial_energy = total_energy(&live);
+    Ok(VerificationReport {
+        steps: run,
+        time_per_frame,
+        max_position_error: max_error(|p| p.position),
+        max_velocity_error: max_error(|p| p.velocity),
+        fast_energy_drift: relative_drift(initial_energy, total_energy(&fast_particles)),
+        reference_energy_drift: relative_drift(initial_energy, total_energy(&reference_particles)),
+    })
+}
+
+/// First frame at which two runs from the same state disagree.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Divergence {
+    pub step: u32,
+    /// ID of the particle with the largest position difference at that frame.
+    pub particle_id: u64,
+    /// Largest per-particle position difference (base-scale units).
+    pub position_difference: f64,
+    /// Largest per-particle velocity difference (base-scale units per second).
+    pub velocity_difference: f64,
+}
+
+/// Outcome of [`audit_determinism`]; `divergence` is `None` when the runs agreed to
+/// within the tolerance on every frame.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct DeterminismReport {
+    /// Frames requested, or the frames run when the audit was aborted without diverging.
+    pub steps: u32,
+    pub tolerance: f64,
+    pub divergence: Option<Divergence>,
+}
+
+/// Largest position and velocity differences between matching particles of two runs, and
+/// the ID of the particle with the largest position difference. NaNs count as infinite.
+fn largest_difference(a: &[Particle], b: &[Particle]) -> (f64, f64, u64) {
+    let distance = |x: glam::DVec3, y: glam::DVec3| {
+        let d = x.distance(y);
+        if d.is_nan() { f64::INFINITY } else { d }
+    };
+    let mut worst = (0.0, 0.0, a.first().map_or(0, |p| p.id));
+    for (p, q) in a.iter().zip(b) {
+        let position = distance(p.position, q.position);
+        if position > worst.0 {
+            worst.0 = position;
+            worst.2 = p.id;
+        }
+        worst.1 = f64::max(worst.1, distance(p.velocity, q.velocity));
+    }
+    worst
+}
+
+/// Engine, world scale, and frames a determinism audit runs.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct DeterminismAudit {
+    pub simulation_type: SimulationType,
+    pub scale: f64,
+    pub time_per_frame: f64,
+    pub steps: u32,
+    /// Largest difference between the two runs still counted as agreement.
+    pub tolerance: f64,
+    pub config: EngineConfig,
+}
+
+/// Runs the CPU engine of `audit` twice in lockstep from the same `particles`, and
+/// reports the first frame where positions or velocities differ by more than the
+/// tolerance. A tolerance of zero demands bit-identical runs. Walls, drag, and noise are
+/// not applied. Bumps `completed` after each frame and stops early when `abort` is set,
+/// reporting the frames run so far.
+pub fn audit_determinism(
+    particles: &[Particle],
+    audit: &DeterminismAudit,
+    completed: &AtomicU32,
+    abort: &AtomicBool,
+) -> Result<DeterminismReport, String> {
+    if particles.is_empty() {
+        return Err("No particles".to_string());
+    }
+    if particles.len() > MAX_DETERMINISM_PARTICLES {
+        return Err(format!(
+            "Too many particles ({} > {})",
+            particles.len(),
+            MAX_DETERMINISM_PARTICLES
+        ));
+    }
+    let first = SimulationManager::with_config(audit.config);
+    let second = SimulationManager::with_config(audit.config);
+    first.restore_particles(particles.to_vec(), audit.simulation_type, audit.scale, None);
+    second.restore_particles(particles.to_vec(), audit.simulation_type, audit.scale, None);
+    let mut step = 0;
+    while step < audit.steps && !abort.load(Ordering::Relaxed) {
+        step += 1;
+        first.advance(audit.time_per_frame);
+        second.advance(audit.time_per_frame);
+        completed.fetch_add(1, Ordering::Relaxed);
+        let (position, velocity, particle_id) =
+            largest_difference(&first.particles(), &second.particles());
+        if position > audit.tolerance || velocity > audit.tolerance {
+            return Ok(DeterminismReport {
+                steps: audit.steps,
+                tolerance: audit.tolerance,
+                divergence: Some(Divergence {
+                    step,
+                    particle_id,
+                    position_difference: position,
+                    velocity_difference: velocity,
+                }),
+            });
+        }
+    }
+    Ok(DeterminismReport {
+        steps: step,
+        tolerance: audit.tolerance,
+        divergence: None,
+    })
+}
+
+/// Verification or determinism audit running on a background thread so the window stays
+/// responsive.
+pub struct VerificationJob<T> {
+    completed: Arc<AtomicU32>,
+    abort: Arc<AtomicBool>,
+    total: u32,
+    handle: JoinHandle<Result<T, String>>,
+}
+
+impl VerificationJob<VerificationReport> {
+    /// Starts [`verify_normal_engine`] on `particles`.
+    pub fn verify(
+        particles: Vec<Particle>,
+        time_per_frame: f64,
+        steps: u32,
+        summation: Summation,
+        precision: Precision,
+    ) -> Self {
+        Self::spawn(steps, move |completed, abort| {
+            verify_normal_engine(
+                &particles,
+                time_per_frame,
+                steps,
+                summation,
+                precision,
+                completed,
+                abort,
+            )
+        })
+    }
+}
+
+impl VerificationJob<DeterminismReport> {
+    /// Starts [`audit_determinism`] on `particles`.
+    pub fn audit(particles: Vec<Particle>, audit: DeterminismAudit) -> Self {
+        Self::spawn(audit.steps, move |completed, abort| {
+            audit_determinism(&particles, &audit, completed, abort)
+        })
+    }
+}
+
+impl<T> VerificationJob<T> {
+    fn spawn(
+        total: u32,
+        run: impl FnOnce(&AtomicU32, &AtomicBool) -> Result<T, String> + Send + 'static,
+    ) -> Self
+    where
+        T: Send + 'static,
+    {
+        let completed = Arc::new(AtomicU32::new(0));
+        let abort = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let completed = Arc::clone(&completed);
+            let abort = Arc::clone(&abort);
+            std::thread::spawn(move || run(&completed, &abort))
+        };
+        Self {
+            completed,
+            abort,
+            total,
+            handle,
+        }
+    }
+
+    /// Returns `(finished frames, total frames)`.
+    pub fn progress(&self) -> (u32, u32) {
+        (self.completed.load(Ordering::Relaxed), self.total)
+    }
+
+    /// Asks the worker to stop after the current frame.
+    pub fn abort(&self) {
+        self.abort.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// Waits for the worker and returns its outcome.
+    pub fn join(self) -> Result<T, String> {
+        self.handle
+            .join()
+            .unwrap_or_else(|_| Err("Worker panicked".to_string()))
+    }
+}
@@ -0,0 +1,50 @@
+use crate::simulation::Particle;
+use crate::ui_state::{DEFAULT_SCALE_UI, SCALE_GAUGE_MAX, SCALE_GAUGE_MIN};
+use glam::DVec3;
+
+/// Radius in axes space (grid half-extent is 2.0) the fitted distribution is scaled to.
+pub const FIT_VIEW_RADIUS: f64 = 1.5;
+/// Extra distance factor so the fitted sphere does not touch the viewport edges.
+pub const FIT_VIEW_MARGIN: f32 = 1.15;
+
+/// Smallest sphere-like bound enclosing every live particle.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BoundingSphere {
+    pub center: DVec3,
+    pub radius: f64,
+}
+
+/// Returns a bounding sphere centered on the axis-aligned bounding box of live particles.
+///
+/// Dead (alpha 0) and non-finite particles are ignored; `None` when nothing remains.
+pub fn particle_bounding_sphere(particles: &[Particle]) -> Option<BoundingSphere> {
+    let live: Vec<DVec3> = particles
+        .iter()
+        .filter(|p| p.color[3] != 0.0 && p.position.is_finite())
+        .map(|p| p.position)
+        .collect();
+    let first = *live.first()?;
+    let (min, max) = live
+        .iter()
+        .fold((first, first), |(min, max), &p| (min.min(p), max.max(p)));
+    let center = (min + max) * 0.5;
+    let radius = live.iter().map(|p| p.distance(center)).fold(0.0, f64::max);
+    Some(BoundingSphere { center, radius })
+}
+
+/// Returns the scale gauge that maps `radius` (simulation units) to [`FIT_VIEW_RADIUS`].
+///
+/// Inverts `particle_visual_scale_factor` (`(gauge / DEFAULT_SCALE_UI)^4`) and clamps to
+/// the slider range; a degenerate radius leaves the default gauge.
+pub fn fit_scale_gauge(radius: f64) -> f64 {
+    if !radius.is_finite() || radius <= 0.0 {
+        return DEFAULT_SCALE_UI;
+    }
+    (DEFAULT_SCALE_UI * (FIT_VIEW_RADIUS / radius).powf(0.25))
+        .clamp(SCALE_GAUGE_MIN, SCALE_GAUGE_MAX)
+}
+
+/// Returns the camera distance at which a sphere of `radius` fills the vertical field of view.
+pub fn fit_camera_distance(radius: f32, fov_y: f32) -> f32 {
+    radius.max(f32::EPSILON) / (fov_y * 0.5).sin() * FIT_VIEW_MARGIN
+}
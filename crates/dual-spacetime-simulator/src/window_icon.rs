@@ -0,0 +1,54 @@
+use glam::Vec2;
+
+/// Edge length of the window and taskbar icon in pixels.
+pub const WINDOW_ICON_SIZE: u32 = 64;
+
+const BACKGROUND_INNER: [f32; 3] = [0.10, 0.14, 0.32];
+const BACKGROUND_OUTER: [f32; 3] = [0.02, 0.03, 0.10];
+const ORBIT_COLOR: [f32; 3] = [0.35, 0.85, 1.0];
+const CORE_COLOR: [f32; 3] = [1.0, 0.85, 0.45];
+const PLANET_COLOR: [f32; 3] = [0.95, 0.95, 1.0];
+
+/// Draws the application icon, a star with a tilted orbit and a planet on a dark disc, as
+/// `size`×`size` RGBA8 pixels. Drawn in code so the window needs no image asset.
+pub fn window_icon_rgba(size: u32) -> Vec<u8> {
+    let half = size as f32 * 0.5;
+    let planet = Vec2::new(0.545, 0.053);
+    let mut rgba = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            // Pixel center in [-1, 1], y up.
+            let p = Vec2::new(
+                (x as f32 + 0.5 - half) / half,
+                (half - y as f32 - 0.5) / half,
+            );
+            let r = p.length();
+            let disc = coverage(1.0 - r, half);
+            let mut color = mix(BACKGROUND_INNER, BACKGROUND_OUTER, r.min(1.0));
+            // Ellipse x² + (y / 0.35)² = 0.62², rotated by about 17°.
+            let tilted = Vec2::new(p.x * 0.956 + p.y * 0.292, -p.x * 0.292 + p.y * 0.956);
+            let orbit = (Vec2::new(tilted.x, tilted.y / 0.35).length() - 0.62).abs() * 0.35;
+            color = mix(color, ORBIT_COLOR, coverage(0.035 - orbit, half));
+            let glow = (1.0 - r / 0.45).max(0.0).powi(2);
+            color = mix(color, CORE_COLOR, glow.max(coverage(0.16 - r, half)));
+            color = mix(
+                color,
+                PLANET_COLOR,
+                coverage(0.09 - (p - planet).length(), half),
+            );
+            rgba.extend(color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8));
+            rgba.push((disc * 255.0).round() as u8);
+        }
+    }
+    rgba
+}
+
+/// Antialiased coverage of a shape whose signed distance inside is `inside` (in units of
+/// the icon radius), one pixel wide at the edge.
+fn coverage(inside: f32, half: f32) -> f32 {
+    (inside * half + 0.5).clamp(0.0, 1.0)
+}
+
+fn mix(a: [f32; 3], b: [f32; 3], t: f32) -> [f32; 3] {
+    [0, 1, 2].map(|i| a[i] + (b[i] - a[i]) * t)
+}
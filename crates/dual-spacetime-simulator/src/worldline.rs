@@ -0,0 +1,117 @@
+use crate::relativistic_view::{ViewKinematics, coordinate_velocity};
+use crate::simulation::Particle;
+use glam::DVec3;
+
+/// Frames between worldline samples while recording.
+pub const WORLDLINE_INTERVAL: i64 = 5;
+/// Events kept per worldline; the oldest are dropped beyond this.
+pub const WORLDLINE_MAX_EVENTS: usize = 4096;
+/// Largest boost speed offered by the Minkowski panel, in units of c.
+pub const MAX_MINKOWSKI_BETA: f64 = 0.99;
+
+/// One recorded event on a particle's worldline.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct WorldlineEvent {
+    /// Simulation time in seconds.
+    pub t: f64,
+    /// Position along the simulation x axis.
+    pub x: f64,
+    /// Proper time accumulated since recording began, in seconds.
+    pub proper_time: f64,
+    /// `dτ/dt = √(1 − v²/c²)` at this event.
+    pub dilation: f64,
+}
+
+/// Recorded (t, x) worldline of one tracked particle.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Worldline {
+    pub id: u64,
+    pub color: [f32; 4],
+    pub events: Vec<WorldlineEvent>,
+}
+
+impl Worldline {
+    pub fn new(id: u64, color: [f32; 4]) -> Self {
+        Self {
+            id,
+            color,
+            events: Vec::new(),
+        }
+    }
+
+    /// Appends an event at time `t`, integrating proper time with the trapezoid rule
+    /// between the previous event and this one.
+    pub fn record(&mut self, t: f64, position: DVec3, velocity: DVec3, light_speed: f64) {
+        let dilation = time_dilation(velocity, light_speed);
+        let proper_time = match self.events.last() {
+            Some(last) => last.proper_time + 0.5 * (last.dilation + dilation) * (t - last.t),
+            None => 0.0,
+        };
+        self.events.push(WorldlineEvent {
+            t,
+            x: position.x,
+            proper_time,
+            dilation,
+        });
+        let excess = self.events.len().saturating_sub(WORLDLINE_MAX_EVENTS);
+        self.events.drain(..excess);
+    }
+
+    /// Coordinate time between the first and last recorded events.
+    pub fn elapsed_time(&self) -> f64 {
+        match (self.events.first(), self.events.last()) {
+            (Some(first), Some(last)) => last.t - first.t,
+            _ => 0.0,
+        }
+    }
+
+    /// Proper time between the first and last recorded events.
+    pub fn elapsed_proper_time(&self) -> f64 {
+        match (self.events.first(), self.events.last()) {
+            (Some(first), Some(last)) => last.proper_time - first.proper_time,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Clock rate `dτ/dt` of a particle moving at `velocity`; zero at or above light speed.
+pub fn time_dilation(velocity: DVec3, light_speed: f64) -> f64 {
+    (1.0 - velocity.length_squared() / (light_speed * light_speed))
+        .max(0.0)
+        .sqrt()
+}
+
+/// Samples the particles with the given `ids` at simulation time `t`. Worldlines of IDs no
+/// longer tracked are dropped and newly tracked IDs start a fresh worldline; IDs missing
+/// from `particles` are skipped.
+pub fn record_worldlines(
+    worldlines: &mut Vec<Worldline>,
+    ids: &[u64],
+    particles: &[Particle],
+    kinematics: ViewKinematics,
+    t: f64,
+    light_speed: f64,
+) {
+    worldlines.retain(|worldline| ids.contains(&worldline.id));
+    for &id in ids {
+        let Some(particle) = particles.iter().find(|p| p.id == id) else {
+            continue;
+        };
+        let slot = match worldlines.iter().position(|w| w.id == id) {
+            Some(slot) => slot,
+            None => {
+                worldlines.push(Worldline::new(id, particle.color));
+                worldlines.len() - 1
+            }
+        };
+        let velocity = coordinate_velocity(particle, kinematics, light_speed);
+        worldlines[slot].record(t, particle.position, velocity, light_speed);
+    }
+}
+
+/// Lorentz boost of the event `(ct, x)` into a frame moving at `beta`·c along x.
+/// Returns `(ct', x')`.
+pub fn boost_event(ct: f64, x: f64, beta: f64) -> (f64, f64) {
+    let gamma = (1.0 - beta * beta).sqrt().recip();
+    (gamma * (ct - beta * x), gamma * (x - beta * ct))
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "gui")]
+
 use dual_spacetime_simulator::object_input::{ObjectInput, ObjectInputType};
 use dual_spacetime_simulator::pipeline::build_add_center_marker;
 use glam::DVec3;
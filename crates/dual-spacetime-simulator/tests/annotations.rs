@@ -0,0 +1,90 @@
+use dual_spacetime_simulator::annotations::{
+    Annotation, AnnotationTarget, LEADER_OFFSET, place_labels,
+};
+use dual_spacetime_simulator::particle_snapshot::ParticleSnapshot;
+use dual_spacetime_simulator::ui_state::{SimulationType, UiState};
+use glam::DVec3;
+
+const SIZE: [f32; 2] = [40.0, 10.0];
+
+#[test]
+fn labels_sit_up_and_right_of_their_anchors() {
+    let corners = place_labels(&[([100.0, 200.0], SIZE), ([300.0, 200.0], SIZE)]);
+    assert_eq!(
+        corners,
+        [
+            [100.0 + LEADER_OFFSET[0], 200.0 + LEADER_OFFSET[1] - SIZE[1]],
+            [300.0 + LEADER_OFFSET[0], 200.0 + LEADER_OFFSET[1] - SIZE[1]],
+        ]
+    );
+}
+
+#[test]
+fn crowded_labels_stack_upward() {
+    let corners = place_labels(&[
+        ([100.0, 200.0], SIZE),
+        ([105.0, 201.0], SIZE),
+        ([98.0, 199.0], SIZE),
+    ]);
+    assert!(corners[1][1] + SIZE[1] <= corners[0][1]);
+    assert!(corners[2][1] + SIZE[1] <= corners[1][1]);
+    assert_eq!(corners[1][0], 105.0 + LEADER_OFFSET[0]);
+}
+
+#[test]
+fn blank_text_and_unassigned_particles_are_not_annotated() {
+    let mut ui = UiState::default();
+    ui.annotation_text = "  ".to_string();
+    ui.add_annotation(AnnotationTarget::Particle(3));
+    ui.annotation_text = "Earth".to_string();
+    ui.add_annotation(AnnotationTarget::Particle(0));
+    assert!(ui.annotations.is_empty());
+
+    ui.add_annotation(AnnotationTarget::Particle(3));
+    ui.annotation_text = " L4 ".to_string();
+    ui.add_annotation(AnnotationTarget::Position(DVec3::X));
+    assert_eq!(ui.annotations.len(), 2);
+    assert_eq!(ui.annotations[1].text, "L4");
+    assert!(ui.annotation_text.is_empty());
+
+    ui.finish_applied_reset(None);
+    assert_eq!(
+        ui.annotations,
+        [Annotation::new(
+            "L4".to_string(),
+            AnnotationTarget::Position(DVec3::X)
+        )]
+    );
+}
+
+#[test]
+fn snapshot_keeps_annotations() {
+    let mut snapshot = ParticleSnapshot::new(SimulationType::Normal, 1e10, Vec::new());
+    snapshot.annotations = vec![
+        Annotation::new("Earth".to_string(), AnnotationTarget::Particle(2)),
+        Annotation::new(
+            "L4".to_string(),
+            AnnotationTarget::Position(DVec3::new(0.5, 0.8, 0.0)),
+        ),
+    ];
+    let json = serde_json::to_string(&snapshot).unwrap();
+    let back: ParticleSnapshot = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.annotations, snapshot.annotations);
+
+    let legacy = r#"{"version":2,"simulation_type":"Normal","scale":1e10,"particles":[]}"#;
+    let legacy: ParticleSnapshot = serde_json::from_str(legacy).unwrap();
+    assert!(legacy.annotations.is_empty());
+}
+
+#[test]
+fn body_labels_pin_names_to_ids_until_reset() {
+    let mut ui = UiState::default();
+    ui.set_body_labels([(1, "Sun"), (2, "Earth")]);
+    assert_eq!(
+        ui.body_labels[1],
+        Annotation::new("Earth".to_string(), AnnotationTarget::Particle(2))
+    );
+    assert!(ui.annotations.is_empty());
+    ui.finish_applied_reset(None);
+    assert!(ui.body_labels.is_empty());
+}
@@ -1,4 +1,4 @@
-use dual_spacetime_simulator::object_input::{ObjectInput, ObjectInputType};
+use dual_spacetime_simulator::object_input::{ObjectInput, ObjectInputType, RandomClusterOptions};
 use dual_spacetime_simulator::simulation::{
     Particle, SimulationManager, SimulationNormal, SimulationState,
 };
@@ -12,9 +12,10 @@ fn random_sphere_input(scale: f64) -> ObjectInput {
 
 fn manager_with_particles(particles: Vec<Particle>) -> SimulationManager {
     SimulationManager {
-        state: Arc::new(RwLock::new(SimulationState::Normal(SimulationNormal {
+        state: Arc::new(RwLock::new(SimulationState::Normal(SimulationNormal::new(
             particles,
-        }))),
+        )))),
+        ..Default::default()
     }
 }
 
@@ -74,6 +75,7 @@ fn append_particles_offsets_positions_by_base_scale() {
         radius: base_scale,
         mass_range: (1e20, 1e21),
         velocity_std: 1e3,
+        options: RandomClusterOptions::default(),
     };
     let center = DVec3::new(2.0, 3.0, 4.0);
     mgr.append_particles(
@@ -136,6 +138,7 @@ fn append_particles_lorentz_mode() {
                 },
             ),
         )),
+        ..Default::default()
     };
     let added = mgr.append_particles(
         random_sphere_input(scale),
@@ -153,3 +156,23 @@ fn append_particles_lorentz_mode() {
         assert!(particle.velocity.z.is_finite());
     }
 }
+
+#[test]
+fn append_particles_assigns_fresh_ids() {
+    let scale = 1e10;
+    let mut existing =
+        Particle::from_kinematics(DVec3::ZERO, DVec3::ZERO, 1.0, [1.0, 1.0, 1.0, 1.0]);
+    existing.id = 7;
+    let mgr = manager_with_particles(vec![existing]);
+    mgr.append_particles(
+        random_sphere_input(scale),
+        UiSimType::Normal,
+        4,
+        scale,
+        DVec3::ZERO,
+        scale,
+        20_000,
+    );
+    let ids: Vec<u64> = mgr.particles().iter().map(|p| p.id).collect();
+    assert_eq!(ids, vec![7, 8, 9, 10, 11]);
+}
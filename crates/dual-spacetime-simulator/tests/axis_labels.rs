@@ -0,0 +1,36 @@
+use dual_spacetime_simulator::axis_labels::{format_distance, grid_labels};
+use dual_spacetime_simulator::simulation::AU;
+use dual_spacetime_simulator::ui_state::DisplaySpace;
+
+#[test]
+fn distances_use_the_largest_fitting_unit() {
+    assert_eq!(format_distance(AU), "1 au");
+    assert_eq!(format_distance(1500.0), "1.5 km");
+    assert_eq!(format_distance(-750e3), "-750 km");
+    assert_eq!(format_distance(12.345), "12.3 m");
+    assert_eq!(format_distance(0.25), "250 mm");
+    assert_eq!(format_distance(0.0), "0");
+}
+
+#[test]
+fn grid_ticks_span_the_grid_edges() {
+    let labels = grid_labels(DisplaySpace::Position, 1000.0);
+    assert_eq!(labels.len(), 2 * 9 + 3);
+    assert_eq!(labels[0].text, "-2 km");
+    assert_eq!(labels[0].position.x, -2.0);
+    assert_eq!(labels[8].text, "0");
+    assert_eq!(labels[17].text, "2 km");
+    assert_eq!(labels[17].position.z, 2.0);
+    let names: Vec<_> = labels[18..]
+        .iter()
+        .map(|label| label.text.as_str())
+        .collect();
+    assert_eq!(names, ["x", "−y", "z"]);
+}
+
+#[test]
+fn velocity_space_shows_only_axis_names() {
+    let labels = grid_labels(DisplaySpace::Velocity, 1000.0);
+    let names: Vec<_> = labels.iter().map(|label| label.text.as_str()).collect();
+    assert_eq!(names, ["βx", "−βy", "βz"]);
+}
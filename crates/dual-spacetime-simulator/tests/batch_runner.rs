@@ -0,0 +1,118 @@
+use dual_spacetime_simulator::batch_runner::{
+    BATCH_CSV_HEADER, BatchConfig, batch_csv, frames_for_duration, parse_value_list, run_batch,
+    run_single,
+};
+use dual_spacetime_simulator::object_input::{ObjectInput, RandomClusterOptions};
+use dual_spacetime_simulator::simulation::{EngineConfig, G, Particle};
+use dual_spacetime_simulator::ui_state::SimulationType;
+use glam::DVec3;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+fn circular_binary() -> Vec<Particle> {
+    let central_mass = 1e10;
+    let speed = (G * central_mass).sqrt();
+    vec![
+        Particle::from_kinematics(DVec3::ZERO, DVec3::ZERO, central_mass, [1.0; 4]),
+        Particle::from_kinematics(DVec3::X, DVec3::new(0.0, speed, 0.0), 1.0, [1.0; 4]),
+    ]
+}
+
+fn config(time_steps: Vec<f64>, simulation_types: Vec<SimulationType>) -> BatchConfig {
+    BatchConfig {
+        object_input: ObjectInput::RandomSphere {
+            scale: 1e10,
+            radius: 1e9,
+            mass_range: (1e28, 1e29),
+            velocity_std: 1e3,
+            options: RandomClusterOptions::default(),
+        },
+        particle_count: 8,
+        scale: 1e10,
+        duration: 1.0,
+        time_steps,
+        simulation_types,
+        engine: EngineConfig::default(),
+    }
+}
+
+#[test]
+fn parses_comma_and_space_separated_positive_values() {
+    assert_eq!(
+        parse_value_list("0.1, 0.05 1e-3"),
+        Ok(vec![0.1, 0.05, 1e-3])
+    );
+    assert!(parse_value_list("0.1, -1").is_err());
+    assert!(parse_value_list("abc").is_err());
+    assert!(parse_value_list(" , ").is_err());
+}
+
+#[test]
+fn frames_cover_duration() {
+    assert_eq!(frames_for_duration(10.0, 0.1), 100);
+    assert_eq!(frames_for_duration(0.01, 1.0), 1);
+    assert_eq!(frames_for_duration(10.0, 0.0), 0);
+}
+
+#[test]
+fn runs_sweep_every_time_step_for_each_type() {
+    let config = config(
+        vec![0.5, 0.25],
+        vec![SimulationType::Normal, SimulationType::DstGravity],
+    );
+    assert_eq!(
+        config.runs(),
+        vec![
+            (SimulationType::Normal, 0.5),
+            (SimulationType::Normal, 0.25),
+            (SimulationType::DstGravity, 0.5),
+            (SimulationType::DstGravity, 0.25),
+        ]
+    );
+}
+
+#[test]
+fn smaller_time_step_drifts_less() {
+    let initial = circular_binary();
+    let abort = AtomicBool::new(false);
+    let config = EngineConfig::default();
+    let coarse = run_single(
+        &initial,
+        SimulationType::Normal,
+        1.0,
+        0.1,
+        80,
+        &config,
+        &abort,
+    );
+    let fine = run_single(
+        &initial,
+        SimulationType::Normal,
+        1.0,
+        0.01,
+        800,
+        &config,
+        &abort,
+    );
+    assert_eq!(coarse.frames, 80);
+    assert!((fine.simulated_time - 8.0).abs() < 1e-9);
+    assert_eq!(fine.live_particles, 2);
+    assert!(fine.relative_energy_drift < coarse.relative_energy_drift);
+}
+
+#[test]
+fn batch_reports_progress_and_stops_on_abort() {
+    let config = config(vec![0.5, 0.25], vec![SimulationType::Normal]);
+    let completed = AtomicUsize::new(0);
+    let summaries = run_batch(&config, &completed, &AtomicBool::new(false));
+    assert_eq!(summaries.len(), 2);
+    assert_eq!(completed.load(Ordering::Relaxed), 2);
+    assert_eq!(summaries[1].frames, 4);
+
+    let csv = batch_csv(&summaries);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], BATCH_CSV_HEADER);
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1].starts_with("Normal,5e-1,2,"));
+
+    assert!(run_batch(&config, &AtomicUsize::new(0), &AtomicBool::new(true)).is_empty());
+}
@@ -0,0 +1,99 @@
+use dual_spacetime_simulator::block_steps::{
+    DEFAULT_BLOCK_STEP_ACCURACY, MAX_BLOCK_LEVEL, advance_block_steps, block_level, block_levels,
+};
+use dual_spacetime_simulator::simulation::{EngineConfig, G, Particle, SimulationManager};
+use dual_spacetime_simulator::ui_state::{ComputingUnit, SimulationType, UiState};
+use glam::DVec3;
+
+const MASS: f64 = 1e10;
+
+/// Equal-mass circular binary of separation 1 around the origin, plus a light body far
+/// out on a slow orbit.
+fn binary_and_distant_body() -> Vec<Particle> {
+    let speed = (G * 2.0 * MASS).sqrt() * 0.5;
+    let distant_speed = (G * 2.0 * MASS / 1e4).sqrt();
+    vec![
+        Particle::from_kinematics(
+            DVec3::new(-0.5, 0.0, 0.0),
+            DVec3::new(0.0, -speed, 0.0),
+            MASS,
+            [1.0; 4],
+        ),
+        Particle::from_kinematics(
+            DVec3::new(0.5, 0.0, 0.0),
+            DVec3::new(0.0, speed, 0.0),
+            MASS,
+            [1.0; 4],
+        ),
+        Particle::from_kinematics(
+            DVec3::new(1e4, 0.0, 0.0),
+            DVec3::new(0.0, distant_speed, 0.0),
+            1.0,
+            [1.0; 4],
+        ),
+    ]
+}
+
+#[test]
+fn levels_halve_the_frame_until_the_step_fits() {
+    assert_eq!(block_level(1.0, 2.0), 0);
+    assert_eq!(block_level(1.0, 1.0), 0);
+    assert_eq!(block_level(1.0, 0.5), 1);
+    assert_eq!(block_level(1.0, 0.3), 2);
+    assert_eq!(block_level(1.0, 0.0), MAX_BLOCK_LEVEL);
+    assert_eq!(block_level(1.0, 1e-9), MAX_BLOCK_LEVEL);
+    assert_eq!(block_level(1.0, f64::INFINITY), 0);
+}
+
+#[test]
+fn tight_orbits_get_finer_levels_than_distant_bodies() {
+    let levels = block_levels(&binary_and_distant_body(), 1.0, DEFAULT_BLOCK_STEP_ACCURACY);
+    // η √(d / |a|) = 0.05 × √(1 / G M) ≈ 0.061 s for the binary.
+    assert_eq!(levels[0], 5);
+    assert_eq!(levels[1], 5);
+    assert_eq!(levels[2], 0);
+}
+
+#[test]
+fn single_level_matches_the_normal_engine() {
+    let manager = SimulationManager::new();
+    manager.reset_from_particles(binary_and_distant_body(), SimulationType::Normal, 1.0);
+    manager.advance(1e-3);
+    let mut particles = binary_and_distant_body();
+    assert_eq!(
+        advance_block_steps(&mut particles, 1e-3, 1.0, &EngineConfig::default()),
+        0
+    );
+    for (a, b) in manager.particles().iter().zip(&particles) {
+        assert_eq!(a.position, b.position);
+        assert_eq!(a.velocity, b.velocity);
+    }
+}
+
+#[test]
+fn block_steps_keep_a_binary_bound_over_long_frames() {
+    let manager = SimulationManager::new();
+    manager.reset_from_particles(binary_and_distant_body(), SimulationType::Normal, 1.0);
+    for _ in 0..10 {
+        let level = manager.advance_block_steps(100.0, DEFAULT_BLOCK_STEP_ACCURACY);
+        assert_eq!(level, MAX_BLOCK_LEVEL);
+    }
+    let particles = manager.particles();
+    let separation = particles[0].position.distance(particles[1].position);
+    assert!(separation > 0.8 && separation < 1.25, "{}", separation);
+}
+
+#[test]
+fn block_steps_take_over_from_other_refinements() {
+    let mut ui = UiState::default();
+    ui.active_computing_unit = ComputingUnit::Cpu;
+    ui.refine_encounters = true;
+    ui.regularize_binaries = true;
+    assert_eq!(ui.block_stepping(), None);
+    assert!(ui.encounter_refinement().is_some());
+    assert!(ui.binary_regularization().is_some());
+    ui.block_steps = true;
+    assert_eq!(ui.block_stepping(), Some(DEFAULT_BLOCK_STEP_ACCURACY));
+    assert_eq!(ui.encounter_refinement(), None);
+    assert_eq!(ui.binary_regularization(), None);
+}
@@ -0,0 +1,90 @@
+use dual_spacetime_simulator::close_encounter::{
+    DEFAULT_STEPS_PER_ENCOUNTER, Encounter, MAX_ENCOUNTER_SUBSTEPS, closest_approach,
+    encounter_substeps, find_close_encounter,
+};
+use dual_spacetime_simulator::simulation::{G, Particle};
+use dual_spacetime_simulator::ui_state::{ComputingUnit, UiState};
+use glam::DVec3;
+
+fn particle(id: u64, position: DVec3, velocity: DVec3) -> Particle {
+    let mut particle = Particle::from_kinematics(position, velocity, 1.0, [1.0; 4]);
+    particle.id = id;
+    particle
+}
+
+#[test]
+fn closest_approach_stays_within_the_step() {
+    let r = DVec3::new(10.0, 0.0, 0.0);
+    let v = DVec3::new(-1.0, 0.0, 0.0);
+    assert_eq!(closest_approach(r, v, 20.0), 0.0);
+    assert_eq!(closest_approach(r, v, 4.0), 6.0);
+    assert_eq!(closest_approach(r, -v, 4.0), 10.0);
+    assert_eq!(closest_approach(r, DVec3::ZERO, 4.0), 10.0);
+}
+
+#[test]
+fn substeps_follow_the_shorter_encounter_time() {
+    // Crossing time 10 s: 8 steps per encounter over a 100 s frame.
+    assert_eq!(encounter_substeps(10.0, 1.0, 0.0, 100.0, 8), 80);
+    // Free-fall time √(d³ / G M) = 1 s.
+    assert_eq!(encounter_substeps(1.0, 0.0, 1.0 / G, 1.99, 8), 16);
+    assert_eq!(encounter_substeps(10.0, 1.0, 0.0, 0.1, 8), 1);
+    assert_eq!(
+        encounter_substeps(0.0, 1.0, 1.0, 1.0, 8),
+        MAX_ENCOUNTER_SUBSTEPS
+    );
+}
+
+#[test]
+fn finds_the_pair_that_needs_the_most_substeps() {
+    let mut particles = vec![
+        particle(1, DVec3::ZERO, DVec3::ZERO),
+        particle(2, DVec3::new(1e6, 0.0, 0.0), DVec3::ZERO),
+        particle(3, DVec3::new(0.0, 1e3, 0.0), DVec3::new(0.0, -1e3, 0.0)),
+    ];
+    let encounter =
+        find_close_encounter(&particles, 10.0, DEFAULT_STEPS_PER_ENCOUNTER, &[]).unwrap();
+    assert_eq!(encounter.pair(), (1, 3));
+    assert!(encounter.substeps > 1);
+    // Regularized binaries follow their own orbits and are left out.
+    assert_eq!(
+        find_close_encounter(&particles, 10.0, DEFAULT_STEPS_PER_ENCOUNTER, &[(1, 3)]),
+        None
+    );
+    // Removed particles are skipped.
+    particles[2].color[3] = 0.0;
+    assert_eq!(
+        find_close_encounter(&particles, 10.0, DEFAULT_STEPS_PER_ENCOUNTER, &[]),
+        None
+    );
+}
+
+#[test]
+fn each_encounter_is_logged_once() {
+    let mut ui = UiState::default();
+    let encounter = Encounter {
+        first_id: 3,
+        second_id: 1,
+        min_separation: 1.0,
+        substeps: 4,
+    };
+    ui.note_encounter(Some(encounter));
+    ui.note_encounter(Some(encounter));
+    assert_eq!(ui.event_log.len(), 1);
+    assert_eq!(ui.encounter_pair, Some((1, 3)));
+    ui.note_encounter(None);
+    assert_eq!(ui.encounter_pair, None);
+    ui.note_encounter(Some(encounter));
+    assert_eq!(ui.event_log.len(), 2);
+}
+
+#[test]
+fn refinement_needs_a_classical_cpu_engine() {
+    let mut ui = UiState::default();
+    assert_eq!(ui.encounter_refinement(), None);
+    ui.refine_encounters = true;
+    // The GPU engine steps on its own queue and is not refined.
+    assert_eq!(ui.encounter_refinement(), None);
+    ui.active_computing_unit = ComputingUnit::Cpu;
+    assert_eq!(ui.encounter_refinement(), Some(DEFAULT_STEPS_PER_ENCOUNTER));
+}
@@ -1,6 +1,9 @@
 //! Headless Vulkan helpers for integration tests (no window / surface).
 
 use ash::{Device, Entry, Instance, vk};
+use dual_spacetime_simulator::pipeline::{OffscreenTarget, ParticleRenderPipeline};
+use dual_spacetime_simulator::still_image::StillSettings;
+use dual_spacetime_simulator::ui_state::{DEFAULT_SCALE_UI, ParticleDisplayMode};
 use gpu_allocator::vulkan::{Allocator, AllocatorCreateDesc};
 use std::sync::{Arc, Mutex};
 
@@ -104,3 +107,45 @@ pub fn try_create_headless_vulkan() -> Option<HeadlessVulkan> {
         allocator: Some(Arc::new(Mutex::new(allocator))),
     })
 }
+
+/// Color format of the headless render targets.
+#[allow(dead_code)]
+pub const OFFSCREEN_FORMAT: vk::Format = vk::Format::R8G8B8A8_UNORM;
+
+/// Creates a particle pipeline on `v` that draws into [`OFFSCREEN_FORMAT`] images.
+#[allow(dead_code)]
+pub fn headless_pipeline(v: &HeadlessVulkan) -> ParticleRenderPipeline {
+    ParticleRenderPipeline::new_headless(
+        &v.instance,
+        v.physical_device,
+        v.device.clone(),
+        v.allocator.clone().unwrap(),
+        OFFSCREEN_FORMAT,
+    )
+}
+
+/// Draws the pipeline's main camera view at the default scale and returns 8-bit RGB.
+#[allow(dead_code)]
+pub fn render_offscreen(
+    v: &HeadlessVulkan,
+    pipeline: &mut ParticleRenderPipeline,
+    settings: StillSettings,
+    show_grid: bool,
+    particle_display_mode: ParticleDisplayMode,
+) -> Vec<u8> {
+    let target = OffscreenTarget {
+        color_format: OFFSCREEN_FORMAT,
+        queue: v.graphics_queue,
+        queue_family: v.graphics_queue_family,
+    };
+    pipeline
+        .render_offscreen(
+            target,
+            settings,
+            DEFAULT_SCALE_UI,
+            false,
+            show_grid,
+            particle_display_mode,
+        )
+        .expect("offscreen render")
+}
@@ -0,0 +1,69 @@
+use dual_spacetime_simulator::container::{ContainerWalls, reflect_off_walls};
+use dual_spacetime_simulator::simulation::Particle;
+use dual_spacetime_simulator::ui_state::{SimulationType, UiState};
+use glam::DVec3;
+
+fn walls(restitution: f64) -> ContainerWalls {
+    ContainerWalls {
+        half_size: 1.0,
+        restitution,
+    }
+}
+
+#[test]
+fn elastic_bounce_mirrors_position_and_velocity() {
+    let mut particles = vec![Particle::from_kinematics(
+        DVec3::new(1.25, 0.5, -1.5),
+        DVec3::new(2.0, 1.0, -3.0),
+        1.0,
+        [1.0; 4],
+    )];
+    particles[0].momentum = particles[0].velocity;
+    reflect_off_walls(&mut particles, walls(1.0));
+    let particle = &particles[0];
+    assert!((particle.position - DVec3::new(0.75, 0.5, -0.5)).length() < 1e-15);
+    assert_eq!(particle.velocity, DVec3::new(-2.0, 1.0, 3.0));
+    assert_eq!(particle.momentum, DVec3::new(-2.0, 1.0, 3.0));
+}
+
+#[test]
+fn restitution_scales_normal_velocity() {
+    let mut particles = vec![Particle::from_kinematics(
+        DVec3::new(0.0, -1.2, 0.0),
+        DVec3::new(1.0, -4.0, 0.0),
+        1.0,
+        [1.0; 4],
+    )];
+    reflect_off_walls(&mut particles, walls(0.5));
+    let particle = &particles[0];
+    assert!((particle.position.y + 0.9).abs() < 1e-15);
+    assert_eq!(particle.velocity, DVec3::new(1.0, 2.0, 0.0));
+
+    reflect_off_walls(&mut particles, walls(0.0));
+    assert_eq!(particles[0].velocity, DVec3::new(1.0, 2.0, 0.0));
+}
+
+#[test]
+fn particle_already_heading_inward_keeps_velocity() {
+    let mut particles = vec![Particle::from_kinematics(
+        DVec3::new(1.1, 0.0, 0.0),
+        DVec3::new(-1.0, 0.0, 0.0),
+        1.0,
+        [1.0; 4],
+    )];
+    reflect_off_walls(&mut particles, walls(1.0));
+    assert!((particles[0].position.x - 0.9).abs() < 1e-15);
+    assert_eq!(particles[0].velocity, DVec3::new(-1.0, 0.0, 0.0));
+}
+
+#[test]
+fn walls_apply_only_when_enabled_and_supported() {
+    let mut ui = UiState::default();
+    assert_eq!(ui.container_walls(), None);
+    ui.walls_enabled = true;
+    assert_eq!(ui.container_walls(), Some(ContainerWalls::default()));
+
+    ui.simulation_type = SimulationType::DstGalaxy;
+    ui.request_reset();
+    assert_eq!(ui.container_walls(), None);
+}
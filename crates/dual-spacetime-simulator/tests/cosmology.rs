@@ -0,0 +1,100 @@
+use dual_spacetime_simulator::cosmology::{Cosmology, ExpansionHistory};
+use dual_spacetime_simulator::simulation::{
+    Particle, SimulationComoving, SimulationManager, SimulationState,
+};
+use dual_spacetime_simulator::ui_state::SimulationType;
+use glam::DVec3;
+
+fn lambda_cdm() -> Cosmology {
+    Cosmology {
+        history: ExpansionHistory::LambdaCdm,
+        ..Cosmology::DEFAULT
+    }
+}
+
+#[test]
+fn scale_factor_inverts_time_and_reaches_one_today() {
+    for cosmology in [Cosmology::DEFAULT, lambda_cdm()] {
+        for a in [0.02, 0.1, 0.5, 1.0] {
+            let time = cosmology.time_at_scale_factor(a);
+            assert!((cosmology.scale_factor(time) - a).abs() < 1e-12 * a.max(1.0));
+        }
+        let today = cosmology.time_at_scale_factor(1.0);
+        let h0 = cosmology.hubble_rate_today();
+        assert!((cosmology.hubble_rate(today) / h0 - 1.0).abs() < 1e-9);
+    }
+    let start = Cosmology::DEFAULT.start_time();
+    assert!((Cosmology::DEFAULT.scale_factor(start) - 1.0 / 51.0).abs() < 1e-12);
+}
+
+#[test]
+fn hubble_rate_matches_scale_factor_derivative() {
+    let cosmology = lambda_cdm();
+    let time = cosmology.time_at_scale_factor(0.5);
+    let dt = time * 1e-6;
+    let derivative =
+        (cosmology.scale_factor(time + dt) - cosmology.scale_factor(time - dt)) / (2.0 * dt);
+    let expected = derivative / cosmology.scale_factor(time);
+    assert!((cosmology.hubble_rate(time) / expected - 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn hubble_drag_decays_peculiar_velocity_as_inverse_square_of_scale_factor() {
+    let cosmology = Cosmology::DEFAULT;
+    let start = cosmology.time_at_scale_factor(0.1);
+    let end = cosmology.time_at_scale_factor(0.2);
+    let manager = SimulationManager {
+        state: std::sync::Arc::new(std::sync::RwLock::new(SimulationState::Comoving(
+            SimulationComoving {
+                particles: vec![Particle::from_kinematics(
+                    DVec3::ZERO,
+                    DVec3::X,
+                    1.0,
+                    [1.0; 4],
+                )],
+                cosmology,
+                time: start,
+            },
+        ))),
+        ..Default::default()
+    };
+    let steps = 10_000;
+    for _ in 0..steps {
+        manager.advance((end - start) / steps as f64);
+    }
+    let (_, time) = manager.cosmic_time().unwrap();
+    assert!((time / end - 1.0).abs() < 1e-9);
+    let speed = manager.particles()[0].velocity.length();
+    assert!((speed - 0.25).abs() < 1e-3, "{speed}");
+}
+
+#[test]
+fn restoring_particles_keeps_the_cosmic_time() {
+    let manager = SimulationManager::default();
+    manager.restore_particles(
+        vec![Particle::from_kinematics(
+            DVec3::ZERO,
+            DVec3::X,
+            1.0,
+            [1.0; 4],
+        )],
+        SimulationType::Comoving,
+        1.0,
+        None,
+    );
+    for _ in 0..100 {
+        manager.advance(1e15);
+    }
+    let cosmic_time = manager.cosmic_time();
+    let (cosmology, time) = cosmic_time.unwrap();
+    assert!(time > cosmology.start_time());
+    manager.restore_particles(
+        manager.particles(),
+        SimulationType::Comoving,
+        1.0,
+        cosmic_time,
+    );
+    assert_eq!(manager.cosmic_time(), cosmic_time);
+    manager.restore_particles(manager.particles(), SimulationType::Comoving, 1.0, None);
+    assert_eq!(manager.cosmic_time().unwrap().1, cosmology.start_time());
+}
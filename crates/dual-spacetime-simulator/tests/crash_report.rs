@@ -0,0 +1,53 @@
+use dual_spacetime_simulator::crash_report::{
+    CHECKPOINT_FILE, SEEN_CRASH_REPORT_FILE, format_crash_report, take_crash_report,
+    write_crash_report,
+};
+use std::path::PathBuf;
+
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir()
+        .join("dual-spacetime-simulator-test")
+        .join(format!("{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn report_names_the_thread_panic_and_scenario() {
+    let report = format_crash_report(
+        "simulation",
+        "index out of bounds",
+        "src/simulation.rs:10:5",
+        "0: main",
+        "Engine: Normal on CPU",
+    );
+    assert!(report.contains(env!("CARGO_PKG_VERSION")));
+    assert!(report.contains("Thread: simulation"));
+    assert!(report.contains("Panic: index out of bounds"));
+    assert!(report.contains("Location: src/simulation.rs:10:5"));
+    assert!(report.contains("Engine: Normal on CPU"));
+    assert!(report.contains("0: main"));
+}
+
+#[test]
+fn a_report_is_offered_once_with_the_checkpoint() {
+    let dir = test_dir("crash_report");
+    assert!(take_crash_report(&dir).is_none());
+    write_crash_report(&dir, "boom").unwrap();
+    std::fs::write(dir.join(CHECKPOINT_FILE), "zip").unwrap();
+
+    let report = take_crash_report(&dir).unwrap();
+    assert_eq!(report.text, "boom");
+    assert_eq!(report.path, dir.join(SEEN_CRASH_REPORT_FILE));
+    assert_eq!(report.checkpoint, Some(dir.join(CHECKPOINT_FILE)));
+    assert!(take_crash_report(&dir).is_none());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn a_report_without_a_checkpoint_offers_none() {
+    let dir = test_dir("crash_report_no_checkpoint");
+    write_crash_report(&dir, "boom").unwrap();
+    assert_eq!(take_crash_report(&dir).unwrap().checkpoint, None);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,59 @@
+use dual_spacetime_simulator::display_mode::{
+    Resolution, choose_resolution, monitor_label, sort_resolutions,
+};
+
+fn mode(width: u32, height: u32, hz: u32) -> Resolution {
+    Resolution {
+        width,
+        height,
+        refresh_millihertz: hz * 1000,
+    }
+}
+
+#[test]
+fn saved_resolution_is_used_when_the_monitor_offers_it() {
+    let modes = [
+        mode(1920, 1080, 60),
+        mode(2560, 1440, 60),
+        mode(1920, 1080, 144),
+    ];
+    assert_eq!(
+        choose_resolution(&modes, Some(mode(1920, 1080, 60))),
+        Some(0)
+    );
+    assert_eq!(
+        choose_resolution(&modes, Some(mode(1920, 1080, 75))),
+        Some(2)
+    );
+    assert_eq!(choose_resolution(&modes, Some(mode(800, 600, 60))), Some(1));
+    assert_eq!(choose_resolution(&modes, None), Some(1));
+    assert_eq!(choose_resolution(&[], None), None);
+}
+
+#[test]
+fn resolutions_list_largest_first_without_duplicates() {
+    let mut modes = vec![
+        mode(1280, 720, 60),
+        mode(1920, 1080, 60),
+        mode(1920, 1080, 144),
+        mode(1920, 1080, 60),
+    ];
+    sort_resolutions(&mut modes);
+    assert_eq!(
+        modes,
+        [
+            mode(1920, 1080, 144),
+            mode(1920, 1080, 60),
+            mode(1280, 720, 60)
+        ]
+    );
+}
+
+#[test]
+fn unnamed_monitors_are_numbered_from_one() {
+    assert_eq!(monitor_label(1, None), "Monitor 2");
+    assert_eq!(
+        monitor_label(0, Some("DELL U2720Q".to_string())),
+        "DELL U2720Q"
+    );
+}
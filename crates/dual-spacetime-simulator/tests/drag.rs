@@ -0,0 +1,99 @@
+use dual_spacetime_simulator::drag::{DragForce, DragModel, apply_drag, chandrasekhar_shape};
+use dual_spacetime_simulator::particle_snapshot::ParticleSnapshot;
+use dual_spacetime_simulator::simulation::{G, Particle};
+use dual_spacetime_simulator::ui_state::{SimulationType, UiState};
+use glam::DVec3;
+
+fn dynamical_friction() -> DragForce {
+    DragForce {
+        model: DragModel::DynamicalFriction,
+        ..DragForce::default()
+    }
+}
+
+#[test]
+fn linear_drag_decays_exponentially() {
+    let drag = DragForce {
+        model: DragModel::Linear,
+        damping_rate: 0.5,
+        ..DragForce::default()
+    };
+    let velocity = DVec3::new(3.0, -4.0, 0.0);
+    let mut particles = vec![Particle::from_kinematics(
+        DVec3::ZERO,
+        velocity,
+        1.0,
+        [1.0; 4],
+    )];
+    for _ in 0..100 {
+        apply_drag(&mut particles, drag, 1.0, 0.02);
+    }
+    let expected = velocity * (-1.0f64).exp();
+    assert!((particles[0].velocity - expected).length() < 1e-12);
+}
+
+#[test]
+fn chandrasekhar_shape_is_smooth_across_series_limit() {
+    let limit = 4.0 / (3.0 * std::f64::consts::PI.sqrt());
+    assert!((chandrasekhar_shape(1e-6) - limit).abs() < 1e-12);
+    let below = chandrasekhar_shape(2.0 - 1e-9);
+    let above = chandrasekhar_shape(2.0);
+    assert!((below - above).abs() < 1e-7);
+    // Fast particles feel the v⁻² tail: [erf − …] → 1.
+    let x = 20.0;
+    assert!((chandrasekhar_shape(x) * x * x * x - 1.0).abs() < 1e-6);
+}
+
+#[test]
+fn dynamical_friction_matches_chandrasekhar_formula() {
+    let drag = dynamical_friction();
+    let mass = 1e37;
+    let speed = drag.velocity_dispersion;
+    let x = std::f64::consts::FRAC_1_SQRT_2;
+    let bracket = chandrasekhar_shape(x) * x * x * x;
+    let deceleration = 4.0 * std::f64::consts::PI * G * G * mass * drag.background_density
+        / (speed * speed)
+        * drag.coulomb_logarithm
+        * bracket;
+    let rate = drag.deceleration_rate(speed, mass, 1.0);
+    assert!((rate * speed / deceleration - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn dynamical_friction_rate_is_independent_of_world_scale() {
+    let drag = dynamical_friction();
+    let (speed, mass) = (1.5e5, 2e38);
+    let scale = 1e10;
+    let si = drag.deceleration_rate(speed, mass, 1.0);
+    let scaled = drag.deceleration_rate(speed / scale, mass / scale.powi(3), scale);
+    assert!((scaled / si - 1.0).abs() < 1e-12);
+    assert!(drag.deceleration_rate(speed, 2.0 * mass, 1.0) > 1.99 * si);
+}
+
+#[test]
+fn drag_applies_only_when_selected_and_supported() {
+    let mut ui = UiState::default();
+    assert_eq!(ui.drag_force(), None);
+    ui.drag.model = DragModel::Linear;
+    assert_eq!(
+        ui.drag_force().map(|drag| drag.model),
+        Some(DragModel::Linear)
+    );
+
+    ui.simulation_type = SimulationType::LorentzTransformation;
+    ui.request_reset();
+    assert_eq!(ui.drag_force(), None);
+}
+
+#[test]
+fn snapshot_keeps_drag_coefficients() {
+    let mut snapshot = ParticleSnapshot::new(SimulationType::Normal, 1e10, Vec::new());
+    snapshot.drag = dynamical_friction();
+    let json = serde_json::to_string(&snapshot).unwrap();
+    let back: ParticleSnapshot = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.drag, snapshot.drag);
+
+    let legacy = r#"{"version":2,"simulation_type":"Normal","scale":1e10,"particles":[]}"#;
+    let legacy: ParticleSnapshot = serde_json::from_str(legacy).unwrap();
+    assert_eq!(legacy.drag.model, DragModel::Off);
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "gui")]
+
 use dual_spacetime_simulator::ui_styles::format_drag_value;
 
 #[test]
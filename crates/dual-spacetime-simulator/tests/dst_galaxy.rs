@@ -1,5 +1,5 @@
-use dual_spacetime_simulator::object_input::ObjectInput;
-use dual_spacetime_simulator::simulation::{G, LY, Particle, SimulationManager};
+use dual_spacetime_simulator::object_input::{ObjectInput, RandomClusterOptions};
+use dual_spacetime_simulator::simulation::{EngineConfig, G, LY, Particle, SimulationManager};
 use dual_spacetime_simulator::ui_state::SimulationType as UiSimType;
 use dst_math::s3_galaxy::{GALAXY_RADIUS_LY, galaxy_radius_sim};
 use glam::DVec3;
@@ -12,6 +12,7 @@ fn galaxy_sphere_input(scale: f64) -> ObjectInput {
         radius: GALAXY_RADIUS_LY * LY * 0.9,
         mass_range: (1e35, 1e36),
         velocity_std: 1.0,
+        options: RandomClusterOptions::default(),
     }
 }
 
@@ -21,8 +22,15 @@ fn dst_galaxy_particles_move_after_advance() {
     let ic = galaxy_sphere_input(scale);
     let mgr = SimulationManager {
         state: std::sync::Arc::new(std::sync::RwLock::new(
-            SimulationManager::create_simulation(ic, UiSimType::DstGalaxy, 32, scale),
+            SimulationManager::create_simulation(
+                ic,
+                UiSimType::DstGalaxy,
+                32,
+                scale,
+                &EngineConfig::default(),
+            ),
         )),
+        ..Default::default()
     };
     let before = mgr.particles();
     assert!(!before.is_empty());
@@ -44,7 +52,13 @@ fn dst_galaxy_sphere_input_derives_orientations() {
     // the 3D position when the DstGalaxy simulation is built.
     let scale = 1e20;
     let ic = galaxy_sphere_input(scale);
-    let state = SimulationManager::create_simulation(ic, UiSimType::DstGalaxy, 16, scale);
+    let state = SimulationManager::create_simulation(
+        ic,
+        UiSimType::DstGalaxy,
+        16,
+        scale,
+        &EngineConfig::default(),
+    );
     let particles = match state {
         dual_spacetime_simulator::simulation::SimulationState::DstGalaxy(s) => s.particles,
         _ => panic!("expected DstGalaxy"),
@@ -91,8 +105,15 @@ fn rotation_curve_smoke_after_short_evolution() {
     let ic = galaxy_sphere_input(scale);
     let mgr = SimulationManager {
         state: std::sync::Arc::new(std::sync::RwLock::new(
-            SimulationManager::create_simulation(ic, UiSimType::DstGalaxy, 64, scale),
+            SimulationManager::create_simulation(
+                ic,
+                UiSimType::DstGalaxy,
+                64,
+                scale,
+                &EngineConfig::default(),
+            ),
         )),
+        ..Default::default()
     };
     for _ in 0..20 {
         mgr.advance(86400.0 * 365.25 * 1e5);
@@ -189,8 +210,10 @@ fn dst_galaxy_zero_particles_advance_is_noop() {
                 UiSimType::DstGalaxy,
                 0,
                 1e20,
+                &EngineConfig::default(),
             ),
         )),
+        ..Default::default()
     };
     mgr.advance(100.0);
     assert_eq!(mgr.particle_count(), 0);
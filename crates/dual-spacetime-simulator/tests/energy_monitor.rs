@@ -0,0 +1,71 @@
+use dual_spacetime_simulator::energy_monitor::{
+    DEFAULT_ENERGY_DRIFT_THRESHOLD, ENERGY_CHECK_INTERVAL, EnergyMonitor, suggested_time_per_frame,
+};
+use dual_spacetime_simulator::ui_state::UiState;
+
+#[test]
+fn checks_every_interval_and_after_the_frame_goes_back() {
+    let mut monitor = EnergyMonitor::default();
+    assert!(monitor.is_due(1));
+    monitor.record(1, 10, -1.0);
+    assert!(!monitor.is_due(ENERGY_CHECK_INTERVAL));
+    assert!(monitor.is_due(1 + ENERGY_CHECK_INTERVAL));
+    monitor.record(31, 10, -1.0);
+    assert!(monitor.is_due(5));
+    monitor.enabled = false;
+    assert!(!monitor.is_due(100));
+}
+
+#[test]
+fn warns_above_the_threshold_until_dismissed() {
+    let mut monitor = EnergyMonitor::default();
+    monitor.record(1, 10, -2.0);
+    assert_eq!(monitor.drift(), None);
+    monitor.record(31, 10, -1.99);
+    assert!((monitor.drift().unwrap() - 0.005).abs() < 1e-12);
+    assert_eq!(monitor.warning(), None);
+    monitor.record(61, 10, -1.5);
+    assert_eq!(monitor.warning(), Some(0.25));
+    monitor.dismiss();
+    assert_eq!(monitor.warning(), None);
+    monitor.forget();
+    assert_eq!(monitor.drift(), None);
+    assert!(monitor.is_due(61));
+}
+
+#[test]
+fn changing_the_particle_count_starts_a_new_reference() {
+    let mut monitor = EnergyMonitor::default();
+    monitor.record(1, 10, -2.0);
+    monitor.record(31, 11, -1.0);
+    assert_eq!(monitor.drift(), None);
+    monitor.record(61, 11, -1.0);
+    assert_eq!(monitor.drift(), Some(0.0));
+}
+
+#[test]
+fn blown_up_energy_always_warns() {
+    let mut monitor = EnergyMonitor::default();
+    monitor.record(1, 10, -2.0);
+    monitor.record(31, 10, f64::NAN);
+    assert!(monitor.warning().is_some_and(f64::is_nan));
+}
+
+#[test]
+fn suggestion_scales_the_step_by_threshold_over_drift() {
+    let threshold = DEFAULT_ENERGY_DRIFT_THRESHOLD;
+    assert_eq!(suggested_time_per_frame(100.0, 0.005, threshold), 100.0);
+    assert!((suggested_time_per_frame(100.0, 0.1, threshold) - 5.0).abs() < 1e-12);
+    assert_eq!(
+        suggested_time_per_frame(100.0, f64::INFINITY, threshold),
+        50.0
+    );
+}
+
+#[test]
+fn dissipative_effects_pause_the_monitor() {
+    let mut ui = UiState::default();
+    assert!(ui.conserves_energy());
+    ui.active_frame_angular_velocity = 1e-6;
+    assert!(!ui.conserves_energy());
+}
@@ -0,0 +1,104 @@
+use dual_spacetime_simulator::events::{
+    EventAction, EventCondition, EventConditionKind, EventContext, EventTrigger, energy_components,
+    fire_triggers, total_energy, total_momentum,
+};
+use dual_spacetime_simulator::simulation::{EPSILON, G, Particle};
+use glam::DVec3;
+
+fn particle(id: u64, x: f64, vx: f64, mass: f64) -> Particle {
+    let mut p = Particle::from_kinematics(
+        DVec3::new(x, 0.0, 0.0),
+        DVec3::new(vx, 0.0, 0.0),
+        mass,
+        [1.0; 4],
+    );
+    p.id = id;
+    p
+}
+
+fn context(time: f64, particles: &[Particle]) -> EventContext<'_> {
+    EventContext {
+        time,
+        particles,
+        reference_energy: None,
+        energy: None,
+    }
+}
+
+#[test]
+fn triggers_fire_once_until_rearmed() {
+    let mut triggers = vec![
+        EventTrigger::new(
+            EventCondition::TimeReached { seconds: 10.0 },
+            EventAction::Pause,
+        ),
+        EventTrigger::new(
+            EventCondition::TimeReached { seconds: 20.0 },
+            EventAction::Log,
+        ),
+    ];
+    assert!(fire_triggers(&mut triggers, &context(5.0, &[])).is_empty());
+    assert_eq!(fire_triggers(&mut triggers, &context(10.0, &[])), vec![0]);
+    assert!(fire_triggers(&mut triggers, &context(15.0, &[])).is_empty());
+    triggers[1].enabled = false;
+    assert!(fire_triggers(&mut triggers, &context(25.0, &[])).is_empty());
+    triggers[0].fired = false;
+    assert_eq!(fire_triggers(&mut triggers, &context(25.0, &[])), vec![0]);
+}
+
+#[test]
+fn close_approach_compares_particles_by_id() {
+    let condition = EventCondition::CloseApproach {
+        first_id: 1,
+        second_id: 3,
+        distance: 1.0,
+    };
+    let far = [
+        particle(1, 0.0, 0.0, 1.0),
+        particle(2, 0.5, 0.0, 1.0),
+        particle(3, 2.0, 0.0, 1.0),
+    ];
+    let near = [particle(3, 0.5, 0.0, 1.0), particle(1, 0.0, 0.0, 1.0)];
+    assert!(!condition.is_met(&context(0.0, &far)));
+    assert!(condition.is_met(&context(0.0, &near)));
+    assert!(!condition.is_met(&context(0.0, &near[..1])));
+}
+
+#[test]
+fn energy_drift_needs_reference_and_tolerance() {
+    let condition = EventConditionKind::EnergyDrift.default_condition();
+    let mut ctx = context(0.0, &[]);
+    ctx.energy = Some(-1.01);
+    assert!(!condition.is_met(&ctx));
+    ctx.reference_energy = Some(-1.0);
+    assert!(condition.is_met(&ctx));
+    ctx.energy = Some(-1.0005);
+    assert!(!condition.is_met(&ctx));
+}
+
+#[test]
+fn total_energy_sums_kinetic_and_pair_potential() {
+    let particles = [particle(1, 0.0, 2.0, 3.0), particle(2, 4.0, 0.0, 5.0)];
+    let expected = 0.5 * 3.0 * 4.0 - G * 15.0 / (4.0 + EPSILON);
+    assert!((total_energy(&particles) - expected).abs() < 1e-12);
+}
+
+#[test]
+fn energy_components_split_the_total_energy() {
+    let particles = [particle(1, 0.0, 2.0, 3.0), particle(2, 4.0, 0.0, 5.0)];
+    let (kinetic, potential) = energy_components(&particles);
+    assert!((kinetic - 6.0).abs() < 1e-12);
+    assert!((potential + G * 15.0 / (4.0 + EPSILON)).abs() < 1e-12);
+}
+
+#[test]
+fn total_momentum_skips_dead_particles() {
+    let mut dead = particle(3, 1.0, 7.0, 2.0);
+    dead.color[3] = 0.0;
+    let particles = [
+        particle(1, 0.0, 2.0, 3.0),
+        particle(2, 4.0, -1.0, 5.0),
+        dead,
+    ];
+    assert_eq!(total_momentum(&particles), DVec3::new(1.0, 0.0, 0.0));
+}
@@ -0,0 +1,60 @@
+use dual_spacetime_simulator::export_writer::{EXPORT_QUEUE_CAPACITY, ExportWriter};
+use std::sync::mpsc::channel;
+
+#[test]
+fn queued_writes_land_on_disk_after_finish() {
+    let dir = std::env::temp_dir().join("dual-spacetime-simulator-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("export_writer_{}.txt", std::process::id()));
+    let mut writer = ExportWriter::new();
+    assert!(writer.is_idle());
+    writer
+        .submit(path.clone(), |path| std::fs::write(path, "written"))
+        .unwrap();
+    writer.finish();
+    let status = writer.status();
+    assert_eq!(status.pending, 0);
+    assert_eq!(status.completed, 1);
+    assert_eq!(status.last_result, Some(Ok(path.clone())));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "written");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn failed_write_is_reported_in_status() {
+    let mut writer = ExportWriter::new();
+    writer
+        .submit("unused.txt".into(), |_| {
+            Err(std::io::Error::other("disk full"))
+        })
+        .unwrap();
+    writer.finish();
+    let status = writer.status();
+    assert_eq!(status.failed, 1);
+    assert!(matches!(status.last_result, Some(Err(ref e)) if e.contains("disk full")));
+}
+
+#[test]
+fn full_queue_refuses_instead_of_blocking() {
+    let mut writer = ExportWriter::new();
+    let (release, hold) = channel::<()>();
+    let (started_tx, started) = channel::<()>();
+    writer
+        .submit("busy".into(), move |_| {
+            started_tx.send(()).unwrap();
+            hold.recv().unwrap();
+            Ok(())
+        })
+        .unwrap();
+    started.recv().unwrap();
+    for i in 0..EXPORT_QUEUE_CAPACITY {
+        writer
+            .submit(format!("queued_{i}").into(), |_| Ok(()))
+            .unwrap();
+    }
+    assert!(writer.submit("overflow".into(), |_| Ok(())).is_err());
+    assert_eq!(writer.status().pending, EXPORT_QUEUE_CAPACITY + 1);
+    release.send(()).unwrap();
+    writer.finish();
+    assert_eq!(writer.status().completed, EXPORT_QUEUE_CAPACITY + 1);
+}
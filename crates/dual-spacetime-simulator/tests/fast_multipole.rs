@@ -0,0 +1,96 @@
+use dual_spacetime_simulator::fast_multipole::{
+    DEFAULT_EXPANSION_ORDER, MAX_EXPANSION_ORDER, fmm_relative_force_error, fmm_velocity_deltas,
+    fmm_velocity_update,
+};
+use dual_spacetime_simulator::simulation::{
+    EngineConfig, G, Particle, SimulationManager, Summation,
+};
+use dual_spacetime_simulator::tree_gravity::ForceSolver;
+use dual_spacetime_simulator::ui_state::{ComputingUnit, SimulationType, UiState};
+use glam::DVec3;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Particles of random mass in a unit cube, the same on every run.
+fn cloud(count: usize) -> Vec<Particle> {
+    let mut rng = StdRng::seed_from_u64(11);
+    (0..count)
+        .map(|_| {
+            let position = DVec3::new(
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+            );
+            let mass = rng.random_range(1e8..1e10);
+            Particle::from_kinematics(position, DVec3::ZERO, mass, [1.0; 4])
+        })
+        .collect()
+}
+
+#[test]
+fn error_falls_with_the_expansion_order() {
+    let particles = cloud(400);
+    let sample: Vec<usize> = (0..particles.len()).collect();
+    let errors: Vec<f64> = [1, 2, 4, 8]
+        .into_iter()
+        .map(|order| fmm_relative_force_error(&particles, order, &sample))
+        .collect();
+    assert!(errors[0] < 0.1, "{:?}", errors);
+    assert!(errors[2] < 5e-3, "{:?}", errors);
+    assert!(errors[3] < 1e-4, "{:?}", errors);
+    assert!(errors.windows(2).all(|w| w[1] < w[0]), "{:?}", errors);
+}
+
+#[test]
+fn a_single_leaf_is_summed_directly() {
+    let particles = cloud(8);
+    let sample: Vec<usize> = (0..particles.len()).collect();
+    let error = fmm_relative_force_error(&particles, DEFAULT_EXPANSION_ORDER, &sample);
+    assert!(error < 1e-12, "{}", error);
+}
+
+#[test]
+fn unusable_positions_get_no_change() {
+    let positions = [DVec3::ZERO, DVec3::X, DVec3::NAN];
+    let deltas = fmm_velocity_deltas(&positions, &[1.0, 1.0, 1.0], 4, G, Summation::Naive);
+    assert!((deltas[0] - DVec3::X * G).length() < 1e-20);
+    assert!((deltas[1] + DVec3::X * G).length() < 1e-20);
+    assert_eq!(deltas[2], DVec3::ZERO);
+    assert!(fmm_velocity_deltas(&[], &[], 4, G, Summation::Naive).is_empty());
+}
+
+#[test]
+fn fmm_solver_drives_the_newtonian_engine() {
+    let mut ui = UiState::default();
+    ui.active_computing_unit = ComputingUnit::Cpu;
+    let mut config = EngineConfig::default();
+    config.set_expansion_order(99);
+    assert_eq!(config.expansion_order, MAX_EXPANSION_ORDER);
+    ui.expansion_order = 99;
+    assert_eq!(ui.engine_config().expansion_order, MAX_EXPANSION_ORDER);
+    ui.expansion_order = DEFAULT_EXPANSION_ORDER;
+
+    ui.force_solver = ForceSolver::Fmm;
+    assert!(!ui.runs_direct_newtonian());
+    assert!(!ui.uses_tree_forces());
+    assert!(ui.uses_fast_multipoles());
+    let manager = SimulationManager::with_config(ui.engine_config());
+    manager.reset_from_particles(cloud(200), SimulationType::Normal, 1.0);
+    manager.advance(1e-3);
+    let mut particles = cloud(200);
+    fmm_velocity_update(
+        &mut particles,
+        1e-3,
+        DEFAULT_EXPANSION_ORDER,
+        Summation::Naive,
+    );
+    let error = manager.sample_fmm_force_error(DEFAULT_EXPANSION_ORDER, 64, 1);
+
+    for (a, b) in manager.particles().iter().zip(&particles) {
+        assert_eq!(a.position, b.position);
+        assert_eq!(a.velocity, b.velocity);
+    }
+    assert!(error > 0.0 && error < 1e-2, "{}", error);
+    ui.force_solver = ForceSolver::Direct;
+    assert!(!ui.uses_fast_multipoles());
+}
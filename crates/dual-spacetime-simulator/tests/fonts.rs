@@ -0,0 +1,48 @@
+#![cfg(feature = "gui")]
+
+use dual_spacetime_simulator::fonts::font_definitions;
+use std::path::PathBuf;
+
+fn temp_font(name: &str, bytes: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("dss_fonts_{}_{}", std::process::id(), name));
+    std::fs::write(&path, bytes).unwrap();
+    path
+}
+
+#[test]
+fn no_fonts_keep_egui_defaults() {
+    let (definitions, errors) = font_definitions(&[], false);
+    assert!(errors.is_empty());
+    assert_eq!(
+        definitions.families,
+        egui::FontDefinitions::default().families
+    );
+}
+
+#[test]
+fn user_fonts_lead_proportional_and_follow_monospace() {
+    let first = temp_font("first.ttf", b"\x00\x01\x00\x00rest");
+    let second = temp_font("second.otf", b"OTTOrest");
+    let (definitions, errors) = font_definitions(&[first.clone(), second.clone()], false);
+    assert!(errors.is_empty());
+    let proportional = &definitions.families[&egui::FontFamily::Proportional];
+    assert_eq!(proportional[0], first.display().to_string());
+    assert_eq!(proportional[1], second.display().to_string());
+    let monospace = &definitions.families[&egui::FontFamily::Monospace];
+    assert_eq!(monospace.last(), Some(&second.display().to_string()));
+    std::fs::remove_file(first).unwrap();
+    std::fs::remove_file(second).unwrap();
+}
+
+#[test]
+fn files_that_are_not_fonts_are_reported_and_skipped() {
+    let image = temp_font("image.png", b"\x89PNG\r\n");
+    let missing = std::env::temp_dir().join("dss_fonts_missing.ttf");
+    let (definitions, errors) = font_definitions(&[image.clone(), missing], false);
+    assert_eq!(errors.len(), 2);
+    assert_eq!(
+        definitions.font_data.len(),
+        egui::FontDefinitions::default().font_data.len()
+    );
+    std::fs::remove_file(image).unwrap();
+}
@@ -0,0 +1,31 @@
+use dual_spacetime_simulator::simulation::{EngineConfig, Particle, SimulationManager};
+use dual_spacetime_simulator::ui_state::{Precision, SimulationType};
+use glam::DVec3;
+
+fn run(precision: Precision) -> Vec<Particle> {
+    let particles = vec![
+        Particle::from_kinematics(DVec3::ZERO, DVec3::ZERO, 1e12, [1.0; 4]),
+        Particle::from_kinematics(DVec3::X, DVec3::new(0.0, 8.0, 0.0), 1.0, [1.0; 4]),
+    ];
+    let manager = SimulationManager::with_config(EngineConfig {
+        precision,
+        ..EngineConfig::default()
+    });
+    manager.reset_from_particles(particles, SimulationType::Normal, 1.0);
+    for _ in 0..50 {
+        manager.advance(0.01);
+    }
+    manager.particles()
+}
+
+#[test]
+fn single_precision_forces_stay_close_to_double() {
+    assert_eq!(EngineConfig::default().precision, Precision::Double);
+    let double = run(Precision::Double);
+    let single = run(Precision::Single);
+    assert_ne!(double[1].velocity, single[1].velocity);
+    for (a, b) in double.iter().zip(&single) {
+        assert!(a.position.distance(b.position) < 1e-4);
+        assert!(a.velocity.distance(b.velocity) < 1e-3);
+    }
+}
@@ -0,0 +1,33 @@
+use dual_spacetime_simulator::simulation::{EngineConfig, Particle, SimulationManager, Summation};
+use dual_spacetime_simulator::ui_state::SimulationType;
+use glam::DVec3;
+
+fn run(summation: Summation, simulation_type: SimulationType) -> Vec<Particle> {
+    let particles = vec![
+        Particle::from_kinematics(DVec3::ZERO, DVec3::ZERO, 1e12, [1.0; 4]),
+        Particle::from_kinematics(DVec3::X, DVec3::new(0.0, 8.0, 0.0), 1.0, [1.0; 4]),
+        Particle::from_kinematics(DVec3::new(-2.0, 0.0, 0.0), DVec3::ZERO, 1e-3, [1.0; 4]),
+    ];
+    let manager = SimulationManager::with_config(EngineConfig {
+        summation,
+        ..EngineConfig::default()
+    });
+    manager.reset_from_particles(particles, simulation_type, 1.0);
+    for _ in 0..50 {
+        manager.advance(0.01);
+    }
+    manager.particles()
+}
+
+#[test]
+fn compensated_summation_tracks_naive_in_every_cpu_engine() {
+    assert_eq!(EngineConfig::default().summation, Summation::Naive);
+    for simulation_type in [SimulationType::Normal, SimulationType::DstGravity] {
+        let naive = run(Summation::Naive, simulation_type);
+        let compensated = run(Summation::Compensated, simulation_type);
+        for (a, b) in naive.iter().zip(&compensated) {
+            assert!(a.position.distance(b.position) < 1e-9);
+            assert!(a.velocity.distance(b.velocity) < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use dst_math::summation::Summation;
+use dual_spacetime_simulator::force_tiles::{
+    I_TILE, J_TILE, direct_velocity_deltas, force_thread_pool, numa_cpu_order, parse_cpu_list,
+    tiled_velocity_deltas, tiled_velocity_deltas_f32,
+};
+use dual_spacetime_simulator::simulation::{EPSILON, G};
+use glam::{DVec3, Vec3};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Enough particles for several tiles each way, with partial tiles at both ends.
+const COUNT: usize = 2 * J_TILE + I_TILE / 2 + 3;
+
+fn cloud() -> (Vec<DVec3>, Vec<f64>) {
+    let mut rng = StdRng::seed_from_u64(7);
+    let positions = (0..COUNT)
+        .map(|_| {
+            DVec3::new(
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+            )
+        })
+        .collect();
+    let masses = (0..COUNT).map(|_| rng.random_range(1e9..1e11)).collect();
+    (positions, masses)
+}
+
+#[test]
+fn tiles_sum_in_the_same_order_as_the_direct_loop() {
+    let (positions, masses) = cloud();
+    for summation in Summation::ALL {
+        let direct = direct_velocity_deltas(&positions, &masses, G, summation);
+        let tiled = tiled_velocity_deltas(&positions, &masses, G, summation);
+        assert_eq!(direct, tiled, "{}", summation);
+    }
+}
+
+#[test]
+fn single_precision_tiles_match_a_plain_loop() {
+    let (positions, masses) = cloud();
+    let positions: Vec<Vec3> = positions.iter().map(|p| p.as_vec3()).collect();
+    let masses: Vec<f32> = masses.iter().map(|&m| m as f32).collect();
+    let time_g = G as f32;
+    let tiled = tiled_velocity_deltas_f32(&positions, &masses, time_g);
+    for (i, &pos_i) in positions.iter().enumerate() {
+        let mut acceleration = Vec3::ZERO;
+        for (j, (&pos_j, &mass_j)) in positions.iter().zip(&masses).enumerate() {
+            let diff = pos_j - pos_i;
+            let distance_sq = diff.length_squared();
+            if j == i || distance_sq < EPSILON as f32 {
+                continue;
+            }
+            acceleration += diff * (time_g * mass_j / (distance_sq * distance_sq.sqrt()));
+        }
+        assert_eq!(tiled[i], acceleration, "particle {}", i);
+    }
+}
+
+#[test]
+fn cpu_lists_expand_ranges_and_skip_junk() {
+    assert_eq!(parse_cpu_list("0-3,8,10-11\n"), vec![0, 1, 2, 3, 8, 10, 11]);
+    assert_eq!(parse_cpu_list("5"), vec![5]);
+    assert_eq!(parse_cpu_list("x,2-y,4"), vec![4]);
+    assert!(parse_cpu_list("").is_empty());
+}
+
+#[test]
+fn pinned_pools_cover_each_cpu_once_and_compute_the_same() {
+    let order = numa_cpu_order();
+    let mut unique = order.clone();
+    unique.sort_unstable();
+    unique.dedup();
+    assert_eq!(unique.len(), order.len());
+
+    let (positions, masses) = cloud();
+    let expected = tiled_velocity_deltas(&positions, &masses, G, Summation::Naive);
+    let pool = force_thread_pool(3, true);
+    assert_eq!(pool.current_num_threads(), 3);
+    let pinned = pool.install(|| tiled_velocity_deltas(&positions, &masses, G, Summation::Naive));
+    assert_eq!(pinned, expected);
+}
@@ -0,0 +1,126 @@
+use dual_spacetime_simulator::frame_pipeline::{
+    FrameMailbox, STAGING_SLOTS, StagingRing, StagingState,
+};
+use dual_spacetime_simulator::simulation::{Particle, SimulationManager};
+use dual_spacetime_simulator::ui_state::SimulationType;
+use glam::DVec3;
+
+fn manager() -> SimulationManager {
+    let particles = vec![
+        Particle::from_kinematics(DVec3::ZERO, DVec3::ZERO, 1e10, [1.0; 4]),
+        Particle::from_kinematics(DVec3::X, DVec3::Y, 1.0, [1.0; 4]),
+    ];
+    let manager = SimulationManager::new();
+    manager.reset_from_particles(particles, SimulationType::Normal, 1.0);
+    manager
+}
+
+#[test]
+fn mailbox_keeps_only_the_newest_frame() {
+    let manager = manager();
+    let mailbox = FrameMailbox::new();
+    assert!(mailbox.take().is_none());
+    mailbox.publish_from(&manager);
+    manager.advance(0.1);
+    mailbox.publish_from(&manager);
+    let frame = mailbox.take().unwrap();
+    assert_eq!(frame, manager.particles());
+    assert!(!mailbox.has_frame());
+    mailbox.recycle(frame);
+}
+
+#[test]
+fn frame_is_a_copy_the_worker_can_advance_past() {
+    let manager = manager();
+    let mailbox = FrameMailbox::new();
+    mailbox.publish_from(&manager);
+    let published = manager.particles();
+    manager.advance(0.1);
+    assert_eq!(mailbox.take().unwrap(), published);
+    mailbox.publish_from(&manager);
+    mailbox.clear();
+    assert!(!mailbox.has_frame());
+}
+
+/// Ring whose slots are backed by `memory`, one `capacity`-sized block per slot, after a
+/// first frame of `capacity` values asked for room.
+fn staging_ring(memory: &mut [u32], capacity: usize) -> StagingRing<u32> {
+    let ring = StagingRing::new();
+    assert!(!ring.write(capacity, |_, _| panic!("no memory yet")));
+    for (slot, block) in memory.chunks_mut(capacity).enumerate() {
+        assert_eq!(ring.claim_undersized(), Some((slot, capacity)));
+        unsafe { ring.attach(slot, block.as_mut_ptr(), capacity) };
+    }
+    assert_eq!(ring.claim_undersized(), None);
+    ring
+}
+
+fn write_frame(ring: &StagingRing<u32>, values: &[u32]) -> bool {
+    ring.write(values.len(), |slot, ids| {
+        slot.copy_from_slice(values);
+        ids.clear();
+        ids.extend(values.iter().map(|&v| u64::from(v) + 100));
+    })
+}
+
+#[test]
+fn staged_frames_alternate_slots_until_their_copies_retire() {
+    let mut memory = vec![0; 4 * STAGING_SLOTS];
+    let ring = staging_ring(&mut memory, 4);
+    let mut ids = Vec::new();
+
+    assert!(write_frame(&ring, &[1, 2, 3]));
+    assert_eq!(ring.take(&mut ids), Some((0, 3)));
+    assert_eq!(ids, vec![101, 102, 103]);
+    ring.submitted(0, 0);
+    assert!(write_frame(&ring, &[4, 5, 6, 7]));
+    assert_eq!(ring.take(&mut ids), Some((1, 4)));
+    ring.submitted(1, 1);
+    // Both copies are in flight: the worker falls back to the mailbox.
+    assert!(!write_frame(&ring, &[8]));
+    ring.retire(0);
+    assert_eq!(ring.state(0), StagingState::Free);
+    assert_eq!(ring.state(1), StagingState::InFlight(1));
+    assert!(write_frame(&ring, &[8]));
+    assert_eq!(ring.take(&mut ids), Some((0, 1)));
+    drop(ring);
+    assert_eq!(memory, vec![8, 2, 3, 0, 4, 5, 6, 7]);
+}
+
+#[test]
+fn only_the_newest_staged_frame_is_kept() {
+    let mut memory = vec![0; 2 * STAGING_SLOTS];
+    let ring = staging_ring(&mut memory, 2);
+    let mut ids = Vec::new();
+    assert!(write_frame(&ring, &[1, 2]));
+    assert!(write_frame(&ring, &[3]));
+    assert_eq!(ring.state(1), StagingState::Free);
+    assert_eq!(ring.take(&mut ids), Some((0, 1)));
+    assert_eq!(ids, vec![103]);
+    assert_eq!(ring.take(&mut ids), None);
+
+    ring.release(0);
+    assert!(write_frame(&ring, &[4, 5]));
+    ring.clear();
+    assert_eq!(ring.take(&mut ids), None);
+}
+
+#[test]
+fn larger_frames_claim_free_slots_for_new_memory() {
+    let mut small = vec![0; 2 * STAGING_SLOTS];
+    let ring = staging_ring(&mut small, 2);
+    let mut ids = Vec::new();
+    assert!(write_frame(&ring, &[1, 2]));
+    assert_eq!(ring.take(&mut ids), Some((0, 2)));
+    assert!(!write_frame(&ring, &[1, 2, 3]));
+    // Slot 0 waits for its copy; only the free slot is resized.
+    let mut large = vec![0; 3];
+    assert_eq!(ring.claim_undersized(), Some((1, 3)));
+    assert_eq!(ring.claim_undersized(), None);
+    unsafe { ring.attach(1, large.as_mut_ptr(), 3) };
+    assert!(write_frame(&ring, &[7, 8, 9]));
+    assert_eq!(ring.take(&mut ids), Some((1, 3)));
+    ring.detach();
+    assert!(!write_frame(&ring, &[1]));
+    assert_eq!(large, vec![7, 8, 9]);
+}
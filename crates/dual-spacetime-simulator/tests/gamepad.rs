@@ -0,0 +1,44 @@
+#![cfg(feature = "gui")]
+
+use dual_spacetime_simulator::gamepad::{
+    GamepadAxes, STICK_DEAD_ZONE, apply_dead_zone, apply_gamepad_camera,
+};
+use glam::{Vec2, Vec3};
+use vulkanvil::OrbitCamera;
+
+#[test]
+fn dead_zone_ignores_small_deflection_and_keeps_full_range() {
+    assert_eq!(
+        apply_dead_zone(Vec2::new(STICK_DEAD_ZONE * 0.5, 0.0)),
+        Vec2::ZERO
+    );
+    let full = apply_dead_zone(Vec2::new(0.0, 1.0));
+    assert!((full - Vec2::new(0.0, 1.0)).length() < 1e-6);
+    let half = apply_dead_zone(Vec2::new(-0.6, 0.0));
+    assert!(half.x < 0.0 && half.x > -0.6);
+}
+
+#[test]
+fn sticks_and_triggers_move_the_camera() {
+    let camera = || OrbitCamera::new(Vec3::new(0.0, 0.0, 10.0), Vec3::ZERO);
+    let mut zoomed = camera();
+    let zoom_in = GamepadAxes {
+        zoom: 1.0,
+        ..Default::default()
+    };
+    apply_gamepad_camera(&mut zoomed, zoom_in, 0.1);
+    assert!(zoomed.orbit_distance() < 10.0);
+
+    let mut panned = camera();
+    let forward = GamepadAxes {
+        right: Vec2::new(0.0, 1.0),
+        ..Default::default()
+    };
+    apply_gamepad_camera(&mut panned, forward, 0.1);
+    assert!((panned.orbit_distance() - 10.0).abs() < 1e-4);
+    assert!(panned.target.z < 0.0);
+
+    let mut still = camera();
+    apply_gamepad_camera(&mut still, GamepadAxes::default(), 0.1);
+    assert_eq!(still.position, camera().position);
+}
@@ -0,0 +1,226 @@
+//! Requires Vulkan loader + compatible GPU (lavapipe is enough).
+//!
+//! Compares offscreen renders of a fixed scene against `tests/golden/*.png`. A missing
+//! golden image fails the test; set `UPDATE_GOLDEN=1` to write all of them from the current
+//! renders after an intended visual change, then review and commit the PNGs.
+
+#![cfg(feature = "gui")]
+
+mod common;
+
+use dual_spacetime_simulator::pipeline::ParticleRenderPipeline;
+use dual_spacetime_simulator::simulation::Particle;
+use dual_spacetime_simulator::still_image::{
+    StillSettings, VISIBLE_PIXEL_DIFFERENCE, compare_images, load_png, save_png,
+};
+use dual_spacetime_simulator::ui_state::{ParticleDisplayMode, SimulationType};
+use glam::DVec3;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::path::PathBuf;
+
+const GOLDEN_SEED: u64 = 2698;
+const GOLDEN_PARTICLES: usize = 300;
+/// Share of pixels allowed to differ visibly, for driver rounding along point edges.
+const MAX_DIFFERING_FRACTION: f64 = 0.002;
+/// Shift of the scene between the frames of a trail case.
+const TRAIL_STEP: DVec3 = DVec3::new(0.05, 0.0, 0.0);
+
+struct GoldenCase {
+    name: &'static str,
+    settings: StillSettings,
+    show_grid: bool,
+    particle_display_mode: ParticleDisplayMode,
+    /// Frames drawn with motion blur while the scene moves by [`TRAIL_STEP`]; 0 draws a
+    /// single still.
+    trail_frames: u32,
+}
+
+const CASES: [GoldenCase; 6] = [
+    GoldenCase {
+        name: "glow",
+        settings: StillSettings {
+            width: 160,
+            height: 120,
+            supersampling: 1,
+        },
+        show_grid: false,
+        particle_display_mode: ParticleDisplayMode::Glow,
+        trail_frames: 0,
+    },
+    GoldenCase {
+        name: "sphere",
+        settings: StillSettings {
+            width: 160,
+            height: 120,
+            supersampling: 1,
+        },
+        show_grid: false,
+        particle_display_mode: ParticleDisplayMode::Sphere,
+        trail_frames: 0,
+    },
+    GoldenCase {
+        name: "glow_grid",
+        settings: StillSettings {
+            width: 160,
+            height: 120,
+            supersampling: 1,
+        },
+        show_grid: true,
+        particle_display_mode: ParticleDisplayMode::Glow,
+        trail_frames: 0,
+    },
+    GoldenCase {
+        name: "sphere_supersampled",
+        settings: StillSettings {
+            width: 160,
+            height: 120,
+            supersampling: 4,
+        },
+        show_grid: true,
+        particle_display_mode: ParticleDisplayMode::Sphere,
+        trail_frames: 0,
+    },
+    GoldenCase {
+        name: "sprite",
+        settings: StillSettings {
+            width: 160,
+            height: 120,
+            supersampling: 1,
+        },
+        show_grid: false,
+        particle_display_mode: ParticleDisplayMode::Sprite,
+        trail_frames: 0,
+    },
+    GoldenCase {
+        name: "glow_trail",
+        settings: StillSettings {
+            width: 160,
+            height: 120,
+            supersampling: 1,
+        },
+        show_grid: false,
+        particle_display_mode: ParticleDisplayMode::Glow,
+        trail_frames: 8,
+    },
+];
+
+/// Particles scattered around the camera target with random colors, the same on every run.
+fn golden_scene() -> Vec<Particle> {
+    let mut rng = StdRng::seed_from_u64(GOLDEN_SEED);
+    (0..GOLDEN_PARTICLES)
+        .map(|_| {
+            let position = DVec3::new(
+                rng.random_range(-1.5..1.5),
+                rng.random_range(-0.5..0.5),
+                rng.random_range(-1.5..1.5),
+            );
+            let color = [
+                rng.random_range(0.2..1.0),
+                rng.random_range(0.2..1.0),
+                rng.random_range(0.2..1.0),
+                1.0,
+            ];
+            Particle::from_kinematics(position, DVec3::ZERO, 1.0, color)
+        })
+        .collect()
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(format!("{name}.png"))
+}
+
+/// Renders `case`: a still of the golden scene, or the last of its trail frames.
+fn render_case(
+    v: &common::HeadlessVulkan,
+    pipeline: &mut ParticleRenderPipeline,
+    case: &GoldenCase,
+) -> Vec<u8> {
+    let scene = golden_scene();
+    if case.trail_frames == 0 {
+        pipeline.upload_particles(&scene, SimulationType::Normal);
+        return common::render_offscreen(
+            v,
+            pipeline,
+            case.settings,
+            case.show_grid,
+            case.particle_display_mode,
+        );
+    }
+    pipeline.set_motion_blur_frames(case.trail_frames);
+    let mut rgb = Vec::new();
+    for frame in 0..case.trail_frames {
+        let shift = TRAIL_STEP * f64::from(frame);
+        let moved: Vec<Particle> = scene
+            .iter()
+            .map(|p| Particle {
+                position: p.position + shift,
+                ..*p
+            })
+            .collect();
+        pipeline.upload_particles(&moved, SimulationType::Normal);
+        rgb = common::render_offscreen(
+            v,
+            pipeline,
+            case.settings,
+            case.show_grid,
+            case.particle_display_mode,
+        );
+    }
+    pipeline.set_motion_blur_frames(0);
+    rgb
+}
+
+#[test]
+fn render_modes_match_golden_images() {
+    let v = common::try_create_headless_vulkan().expect("vulkan");
+    let mut pipeline = common::headless_pipeline(&v);
+    let update = std::env::var_os("UPDATE_GOLDEN").is_some();
+    let mut failures = Vec::new();
+    for case in &CASES {
+        let StillSettings { width, height, .. } = case.settings;
+        let rgb = render_case(&v, &mut pipeline, case);
+        let path = golden_path(case.name);
+        let actual = path.with_extension("actual.png");
+        if update {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            save_png(&path, width, height, &rgb).unwrap();
+            eprintln!("Wrote golden image {}", path.display());
+            continue;
+        }
+        if !path.is_file() {
+            save_png(&actual, width, height, &rgb).unwrap();
+            failures.push(format!(
+                "{}: no golden image at {}; render saved to {}, run with UPDATE_GOLDEN=1 to \
+                 write it",
+                case.name,
+                path.display(),
+                actual.display()
+            ));
+            continue;
+        }
+        let (golden_width, golden_height, golden) = load_png(&path).unwrap();
+        assert_eq!(
+            (golden_width, golden_height),
+            (width, height),
+            "{}",
+            case.name
+        );
+        let difference = compare_images(&golden, &rgb, VISIBLE_PIXEL_DIFFERENCE).unwrap();
+        if difference.differing_fraction() > MAX_DIFFERING_FRACTION {
+            save_png(&actual, width, height, &rgb).unwrap();
+            failures.push(format!(
+                "{}: {} of {} pixels differ (max {:.1}); render saved to {}",
+                case.name,
+                difference.differing_pixels,
+                difference.pixel_count,
+                difference.max_difference,
+                actual.display()
+            ));
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
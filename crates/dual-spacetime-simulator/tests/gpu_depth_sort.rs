@@ -0,0 +1,168 @@
+//! The GPU sort test requires Vulkan loader + compatible GPU (lavapipe is enough).
+
+#![cfg(feature = "gui")]
+
+mod common;
+
+use ash::vk;
+use dual_spacetime_simulator::gpu_culling::GpuParticleCulling;
+use dual_spacetime_simulator::gpu_depth_sort::{
+    GpuDepthSort, SORT_BLOCK_SIZE, depth_sort_key, sort_block_count,
+};
+use dual_spacetime_simulator::gpu_simulation::GpuParticle;
+use glam::{Mat4, Vec4};
+use gpu_allocator::MemoryLocation;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::Arc;
+use vulkanvil::AllocatedBuffer;
+
+#[test]
+fn depth_keys_ascend_from_far_to_near() {
+    let depths = [1e30, 1e6, 2.5, 1.0, 1e-20, 0.0, -1e-20, -1.0, -1e6];
+    let keys: Vec<u32> = depths.iter().map(|&w| depth_sort_key(w)).collect();
+    assert!(keys.windows(2).all(|pair| pair[0] < pair[1]), "{keys:?}");
+}
+
+#[test]
+fn blocks_cover_every_key() {
+    assert_eq!(sort_block_count(0), 1);
+    assert_eq!(sort_block_count(1), 1);
+    assert_eq!(sort_block_count(SORT_BLOCK_SIZE), 1);
+    assert_eq!(sort_block_count(SORT_BLOCK_SIZE + 1), 2);
+}
+
+#[test]
+fn sorted_indices_run_back_to_front_and_keep_ties_in_order() {
+    let v = common::try_create_headless_vulkan().expect("vulkan");
+    let allocator = v.allocator.clone().unwrap();
+    // Partial last block, repeated depths, points behind the camera, and dead slots.
+    let count = 3 * SORT_BLOCK_SIZE as usize + 17;
+    let mut rng = StdRng::seed_from_u64(3);
+    let particles: Vec<GpuParticle> = (0..count)
+        .map(|_| {
+            let z = rng.random_range(-20..200) as f32 * 0.5;
+            let alpha = if rng.random_range(0..50) == 0 {
+                0.0
+            } else {
+                1.0
+            };
+            GpuParticle::from_display([0.0, 0.0, z], [1.0, 1.0, 1.0, alpha])
+        })
+        .collect();
+    // Clip w equals z exactly, so the CPU keys match the shader's bit for bit.
+    let view_proj = Mat4::from_cols(Vec4::X, Vec4::Y, Vec4::new(0.0, 0.0, 1.0, 1.0), Vec4::ZERO);
+    let mut expected: Vec<u32> = (0..count as u32).collect();
+    expected.sort_by_key(|&i| {
+        let particle = &particles[i as usize];
+        if particle.color[3] == 0.0 {
+            u32::MAX
+        } else {
+            depth_sort_key(particle.position[2])
+        }
+    });
+
+    let particle_buffer = AllocatedBuffer::new(
+        &v.device,
+        &allocator,
+        std::mem::size_of_val(particles.as_slice()) as u64,
+        vk::BufferUsageFlags::STORAGE_BUFFER,
+        MemoryLocation::CpuToGpu,
+        "test_particles",
+    );
+    let mapped = particle_buffer
+        .allocation
+        .as_ref()
+        .and_then(|alloc| alloc.mapped_ptr())
+        .expect("mapped particle buffer");
+    unsafe {
+        std::ptr::copy_nonoverlapping(
+            particles.as_ptr(),
+            mapped.as_ptr() as *mut GpuParticle,
+            count,
+        );
+    }
+    let readback = AllocatedBuffer::new(
+        &v.device,
+        &allocator,
+        (std::mem::size_of::<u32>() * count) as u64,
+        vk::BufferUsageFlags::TRANSFER_DST,
+        MemoryLocation::GpuToCpu,
+        "test_sorted_indices",
+    );
+    let culling = GpuParticleCulling::new(v.device.clone(), Arc::clone(&allocator));
+    let mut sort = GpuDepthSort::new(
+        v.device.clone(),
+        Arc::clone(&allocator),
+        culling.descriptor_set_layout(),
+    );
+
+    let cb_ci = vk::CommandBufferAllocateInfo::default()
+        .command_pool(v.command_pool)
+        .level(vk::CommandBufferLevel::PRIMARY)
+        .command_buffer_count(1);
+    let cb = unsafe { v.device.allocate_command_buffers(&cb_ci) }.unwrap()[0];
+    unsafe {
+        v.device
+            .begin_command_buffer(cb, &vk::CommandBufferBeginInfo::default())
+            .unwrap();
+    }
+    sort.record(
+        cb,
+        particle_buffer.buffer,
+        count as u32,
+        view_proj.to_cols_array_2d(),
+    );
+    let to_transfer = vk::MemoryBarrier::default()
+        .src_access_mask(vk::AccessFlags::SHADER_WRITE)
+        .dst_access_mask(vk::AccessFlags::TRANSFER_READ);
+    let to_host = vk::MemoryBarrier::default()
+        .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+        .dst_access_mask(vk::AccessFlags::HOST_READ);
+    let region = vk::BufferCopy::default().size((std::mem::size_of::<u32>() * count) as u64);
+    unsafe {
+        v.device.cmd_pipeline_barrier(
+            cb,
+            vk::PipelineStageFlags::COMPUTE_SHADER,
+            vk::PipelineStageFlags::TRANSFER,
+            vk::DependencyFlags::empty(),
+            &[to_transfer],
+            &[],
+            &[],
+        );
+        v.device
+            .cmd_copy_buffer(cb, sort.sorted_indices(), readback.buffer, &[region]);
+        v.device.cmd_pipeline_barrier(
+            cb,
+            vk::PipelineStageFlags::TRANSFER,
+            vk::PipelineStageFlags::HOST,
+            vk::DependencyFlags::empty(),
+            &[to_host],
+            &[],
+            &[],
+        );
+        v.device.end_command_buffer(cb).unwrap();
+        let command_buffers = [cb];
+        let submit_info = vk::SubmitInfo::default().command_buffers(&command_buffers);
+        v.device
+            .queue_submit(v.graphics_queue, &[submit_info], vk::Fence::null())
+            .unwrap();
+        v.device.queue_wait_idle(v.graphics_queue).unwrap();
+        v.device
+            .free_command_buffers(v.command_pool, &command_buffers);
+    }
+    let sorted: Vec<u32> = bytemuck::cast_slice(
+        readback
+            .allocation
+            .as_ref()
+            .and_then(|alloc| alloc.mapped_slice())
+            .expect("mapped readback"),
+    )
+    .to_vec();
+    assert_eq!(sorted, expected);
+
+    drop(sort);
+    drop(culling);
+    readback.destroy(&v.device, &allocator);
+    particle_buffer.destroy(&v.device, &allocator);
+}
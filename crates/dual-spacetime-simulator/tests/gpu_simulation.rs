@@ -1,5 +1,7 @@
+#![cfg(feature = "gui")]
+
 use dual_spacetime_simulator::gpu_simulation::GpuParticle;
-use dual_spacetime_simulator::simulation::Particle;
+use dual_spacetime_simulator::simulation::{LIGHT_SPEED, Particle};
 use dual_spacetime_simulator::ui_state::SimulationType;
 use glam::{DQuat, DVec3};
 
@@ -17,7 +19,7 @@ fn gpu_particle_roundtrip_preserves_values() {
         [1.0, 0.5, 0.25, 1.0],
     );
     let gpu = GpuParticle::from_cpu(&particle, SimulationType::Normal);
-    let restored = gpu.to_cpu(SimulationType::Normal, 1e10);
+    let restored = gpu.to_cpu(SimulationType::Normal, 1e10, LIGHT_SPEED);
     assert!((particle.position - restored.position).length() < 1.0);
     assert!((particle.velocity - restored.velocity).length() < 1e-3);
     assert!((particle.mass - restored.mass).abs() < 1e-3);
@@ -35,10 +37,11 @@ fn gpu_particle_speed_of_light_limit_stores_momentum_in_velocity_slot() {
             [1.0, 1.0, 1.0, 1.0],
         )],
         scale,
+        LIGHT_SPEED,
     )[0];
     let gpu = GpuParticle::from_cpu(&particle, SimulationType::SpeedOfLightLimit);
     assert!((gpu.velocity[0] as f64 - particle.momentum.x).abs() < 1e-6);
-    let restored = gpu.to_cpu(SimulationType::SpeedOfLightLimit, scale);
+    let restored = gpu.to_cpu(SimulationType::SpeedOfLightLimit, scale, LIGHT_SPEED);
     assert!((restored.momentum - particle.momentum).length() < 1e-6);
     assert!((restored.velocity - particle.velocity).length() < 1e-9);
 }
@@ -87,7 +90,7 @@ fn gpu_particle_dst_galaxy_roundtrip_preserves_quaternion() {
     let gpu = GpuParticle::from_cpu(&particle, SimulationType::DstGalaxy);
     assert!((gpu.position[3] - particle.orientation.w as f32).abs() < 1e-6);
     assert!((gpu.attrs[1] - particle.orientation.x as f32).abs() < 1e-6);
-    let restored = gpu.to_cpu(SimulationType::DstGalaxy, 1e20);
+    let restored = gpu.to_cpu(SimulationType::DstGalaxy, 1e20, LIGHT_SPEED);
     assert!((restored.orientation.x - particle.orientation.x).abs() < 1e-6);
     assert!((restored.orientation.w - particle.orientation.w).abs() < 1e-6);
     assert!((restored.mass - particle.mass).abs() / particle.mass < 1e-6);
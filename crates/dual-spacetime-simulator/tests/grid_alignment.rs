@@ -0,0 +1,79 @@
+use dual_spacetime_simulator::grid_alignment::{
+    GRID_ALIGNMENT_INTERVAL, GridAlignment, GridOrientation, OBLIQUITY_J2000, grid_rotation,
+    total_angular_momentum,
+};
+use dual_spacetime_simulator::simulation::Particle;
+use dual_spacetime_simulator::ui_state::UiState;
+use glam::{DQuat, DVec3};
+use std::time::{Duration, Instant};
+
+fn particle(position: DVec3, velocity: DVec3, mass: f64) -> Particle {
+    Particle::from_kinematics(position, velocity, mass, [1.0; 4])
+}
+
+#[test]
+fn angular_momentum_is_taken_about_center_of_mass() {
+    let drift = DVec3::new(5.0, 7.0, -3.0);
+    let particles = [
+        particle(DVec3::X + drift, DVec3::Z + drift, 1.0),
+        particle(-DVec3::X + drift, -DVec3::Z + drift, 1.0),
+        particle(DVec3::splat(9.0), DVec3::splat(4.0), 0.0),
+    ];
+    // r × v = X × Z = −Y for each of the pair.
+    let angular_momentum = total_angular_momentum(&particles);
+    assert!((angular_momentum - DVec3::new(0.0, -2.0, 0.0)).length() < 1e-12);
+    assert_eq!(total_angular_momentum(&[]), DVec3::ZERO);
+}
+
+#[test]
+fn grid_normal_follows_the_chosen_plane() {
+    let spin = DVec3::new(0.0, 0.0, 3.0);
+    assert_eq!(GridAlignment::Fixed.plane_normal(spin), DVec3::Y);
+    assert_eq!(GridAlignment::InvariantPlane.plane_normal(spin), DVec3::Z);
+    assert_eq!(
+        GridAlignment::InvariantPlane.plane_normal(DVec3::ZERO),
+        DVec3::Y
+    );
+    let ecliptic = GridAlignment::Ecliptic.plane_normal(spin);
+    assert!((ecliptic.angle_between(DVec3::Z) - OBLIQUITY_J2000).abs() < 1e-12);
+}
+
+#[test]
+fn grid_rotation_takes_the_smaller_turn() {
+    assert!(grid_rotation(DVec3::Y).angle_between(DQuat::IDENTITY) < 1e-12);
+    let tilted = DVec3::new(0.0, -1.0, 1.0);
+    let rotation = grid_rotation(tilted);
+    assert!((rotation.angle_between(DQuat::IDENTITY) - std::f64::consts::FRAC_PI_4).abs() < 1e-12);
+    assert!((rotation * DVec3::Y).cross(tilted).length() < 1e-12);
+}
+
+#[test]
+fn orientation_eases_to_the_target() {
+    let start = Instant::now();
+    let target = grid_rotation(DVec3::Z);
+    let mut orientation = GridOrientation::default();
+    orientation.turn_to(target, start);
+    assert!(orientation.is_turning());
+    let halfway = orientation.update(start + Duration::from_millis(400));
+    let half_angle = halfway.angle_between(DQuat::IDENTITY);
+    assert!((half_angle - std::f64::consts::FRAC_PI_4).abs() < 1e-9);
+    assert_eq!(orientation.update(start + Duration::from_secs(5)), target);
+    assert!(!orientation.is_turning());
+    orientation.turn_to(target, start + Duration::from_secs(6));
+    assert!(!orientation.is_turning());
+}
+
+#[test]
+fn invariant_plane_is_recomputed_on_interval() {
+    let mut ui = UiState::default();
+    assert!(ui.grid_alignment_due());
+    ui.grid_alignment_applied = Some((ui.grid_alignment, ui.frame));
+    ui.frame += 10 * GRID_ALIGNMENT_INTERVAL;
+    assert!(!ui.grid_alignment_due());
+    ui.grid_alignment = GridAlignment::InvariantPlane;
+    assert!(ui.grid_alignment_due());
+    ui.grid_alignment_applied = Some((ui.grid_alignment, ui.frame));
+    assert!(!ui.grid_alignment_due());
+    ui.frame += GRID_ALIGNMENT_INTERVAL;
+    assert!(ui.grid_alignment_due());
+}
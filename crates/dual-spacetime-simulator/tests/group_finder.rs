@@ -0,0 +1,92 @@
+use dual_spacetime_simulator::group_finder::{
+    GroupSortKey, UNGROUPED_COLOR, color_particles_by_group, friends_of_friends,
+    mean_interparticle_separation, recolor_group, sort_groups,
+};
+use dual_spacetime_simulator::palette::group_palette_color;
+use dual_spacetime_simulator::simulation::Particle;
+use glam::DVec3;
+
+fn particle(x: f64, mass: f64) -> Particle {
+    Particle::from_kinematics(
+        DVec3::new(x, 0.0, 0.0),
+        DVec3::ZERO,
+        mass,
+        [1.0, 1.0, 1.0, 1.0],
+    )
+}
+
+#[test]
+fn links_chains_transitively_and_drops_small_groups() {
+    let particles = vec![
+        particle(0.0, 1.0),
+        particle(0.4, 1.0),
+        particle(0.8, 1.0),
+        particle(10.0, 5.0),
+        particle(10.3, 5.0),
+        particle(20.0, 1.0),
+    ];
+    let groups = friends_of_friends(&particles, 0.5, 2);
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0].members, vec![3, 4]);
+    assert_eq!(groups[0].mass, 10.0);
+    assert!((groups[0].center_of_mass.x - 10.15).abs() < 1e-12);
+    assert_eq!(groups[1].members, vec![0, 1, 2]);
+    assert!((groups[1].radius - 0.4).abs() < 1e-12);
+    assert_eq!(groups[1].central_member, 1);
+}
+
+#[test]
+fn dead_particles_do_not_bridge_groups() {
+    let mut particles = vec![particle(0.0, 1.0), particle(0.4, 1.0), particle(0.8, 1.0)];
+    particles[1].color[3] = 0.0;
+    assert!(friends_of_friends(&particles, 0.5, 2).is_empty());
+    assert_eq!(friends_of_friends(&particles, 0.5, 1).len(), 2);
+}
+
+#[test]
+fn coloring_marks_members_and_greys_the_rest() {
+    let mut particles = vec![particle(0.0, 1.0), particle(0.1, 1.0), particle(5.0, 1.0)];
+    let groups = friends_of_friends(&particles, 0.5, 2);
+    color_particles_by_group(&mut particles, &groups);
+    assert_eq!(particles[0].color, group_palette_color(0));
+    assert_eq!(particles[1].color, group_palette_color(0));
+    assert_eq!(particles[2].color, UNGROUPED_COLOR);
+}
+
+#[test]
+fn recoloring_a_group_leaves_other_particles_alone() {
+    let mut particles = vec![particle(0.0, 1.0), particle(0.1, 1.0), particle(5.0, 1.0)];
+    let groups = friends_of_friends(&particles, 0.5, 2);
+    particles[1].color[3] = 0.0;
+    let untouched = particles[2].color;
+    let green = [0.0, 1.0, 0.0, 1.0];
+    assert!(recolor_group(&mut particles, &groups[0], green));
+    assert_eq!(particles[0].color, green);
+    assert_eq!(particles[1].color[3], 0.0);
+    assert_eq!(particles[2].color, untouched);
+}
+
+#[test]
+fn sorting_by_member_count_ascending() {
+    let particles = vec![
+        particle(0.0, 10.0),
+        particle(0.1, 10.0),
+        particle(5.0, 1.0),
+        particle(5.1, 1.0),
+        particle(5.2, 1.0),
+    ];
+    let mut groups = friends_of_friends(&particles, 0.5, 2);
+    assert_eq!(groups[0].members, vec![0, 1]);
+    sort_groups(&mut groups, GroupSortKey::Members, false);
+    assert_eq!(groups[0].members, vec![0, 1]);
+    sort_groups(&mut groups, GroupSortKey::Members, true);
+    assert_eq!(groups[0].members, vec![2, 3, 4]);
+}
+
+#[test]
+fn mean_separation_uses_largest_extent() {
+    let particles: Vec<Particle> = (0..8).map(|i| particle(i as f64, 1.0)).collect();
+    let separation = mean_interparticle_separation(&particles).unwrap();
+    assert!((separation - 7.0 / 2.0).abs() < 1e-12);
+    assert_eq!(mean_interparticle_separation(&[particle(1.0, 1.0)]), None);
+}
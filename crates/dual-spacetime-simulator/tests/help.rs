@@ -0,0 +1,38 @@
+use dual_spacetime_simulator::help::{APP_VERSION, CONTROL_SECTIONS, PROJECT_LINKS};
+
+#[test]
+fn control_sections_list_each_input_once() {
+    for section in &CONTROL_SECTIONS {
+        assert!(!section.controls.is_empty(), "{}", section.title);
+        for (index, (input, _)) in section.controls.iter().enumerate() {
+            assert!(
+                section.controls[index + 1..]
+                    .iter()
+                    .all(|(other, _)| other != input),
+                "{} lists {} twice",
+                section.title,
+                input
+            );
+        }
+    }
+}
+
+#[test]
+fn help_mentions_double_click_shortcuts() {
+    let inputs: Vec<&str> = CONTROL_SECTIONS
+        .iter()
+        .flat_map(|section| section.controls.iter().map(|(input, _)| *input))
+        .collect();
+    assert!(inputs.contains(&"Right double-click"));
+    assert!(inputs.contains(&"Slider double-click"));
+}
+
+#[test]
+fn help_shows_crate_version_and_https_links() {
+    assert_eq!(APP_VERSION, env!("CARGO_PKG_VERSION"));
+    assert!(
+        PROJECT_LINKS
+            .iter()
+            .all(|(_, url)| url.starts_with("https://"))
+    );
+}
@@ -0,0 +1,128 @@
+use dual_spacetime_simulator::kepler_binary::{
+    DEFAULT_STEPS_PER_ORBIT, KeplerPair, bound_orbit, find_kepler_pairs, kepler_drift,
+};
+use dual_spacetime_simulator::simulation::{G, Particle, SimulationManager};
+use dual_spacetime_simulator::ui_state::{ComputingUnit, SimulationType, UiState};
+use glam::DVec3;
+use std::f64::consts::{PI, TAU};
+
+const MASS: f64 = 1e10;
+
+fn particle(id: u64, position: DVec3, velocity: DVec3) -> Particle {
+    let mut particle = Particle::from_kinematics(position, velocity, MASS, [1.0; 4]);
+    particle.id = id;
+    particle
+}
+
+/// Equal-mass circular binary of separation 1 around the origin, plus a third body at `x`.
+fn binary_with_third_at(x: f64) -> Vec<Particle> {
+    let speed = (G * 2.0 * MASS).sqrt() * 0.5;
+    vec![
+        particle(1, DVec3::new(-0.5, 0.0, 0.0), DVec3::new(0.0, -speed, 0.0)),
+        particle(2, DVec3::new(0.5, 0.0, 0.0), DVec3::new(0.0, speed, 0.0)),
+        particle(3, DVec3::new(x, 0.0, 0.0), DVec3::ZERO),
+    ]
+}
+
+fn assert_close(a: DVec3, b: DVec3, tolerance: f64) {
+    assert!(a.distance(b) < tolerance, "{} vs {}", a, b);
+}
+
+#[test]
+fn circular_orbit_turns_a_quarter_in_a_quarter_period() {
+    let (r, v) = kepler_drift(DVec3::X, DVec3::Y, 1.0, PI / 2.0);
+    assert_close(r, DVec3::Y, 1e-12);
+    assert_close(v, -DVec3::X, 1e-12);
+}
+
+#[test]
+fn eccentric_orbit_reaches_apocenter_and_composes() {
+    let (r0, v0) = (DVec3::X, DVec3::new(0.0, 1.2, 0.0));
+    let orbit = bound_orbit(r0, v0, 1.0).unwrap();
+    assert!((orbit.semi_major_axis - 1.0 / 0.56).abs() < 1e-12);
+    assert!((orbit.eccentricity - 0.44).abs() < 1e-12);
+    assert!((orbit.period - TAU * orbit.semi_major_axis.powf(1.5)).abs() < 1e-12);
+
+    let (r, _) = kepler_drift(r0, v0, 1.0, orbit.period / 2.0);
+    assert!((r.length() - orbit.apocenter()).abs() < 1e-9);
+
+    let (r1, v1) = kepler_drift(r0, v0, 1.0, 0.7);
+    let (r2, v2) = kepler_drift(r1, v1, 1.0, 1.9);
+    let (r, v) = kepler_drift(r0, v0, 1.0, 2.6);
+    assert_close(r2, r, 1e-10);
+    assert_close(v2, v, 1e-10);
+
+    // Whole orbits drop out.
+    let (r, v) = kepler_drift(r0, v0, 1.0, 1000.0 * orbit.period + 0.7);
+    assert_close(r, r1, 1e-8);
+    assert_close(v, v1, 1e-8);
+}
+
+#[test]
+fn unbound_pairs_have_no_orbit_and_drift_straight() {
+    let (r0, v0) = (DVec3::X, DVec3::new(0.0, 2.0, 0.0));
+    assert_eq!(bound_orbit(r0, v0, 1.0), None);
+    assert_eq!(kepler_drift(r0, v0, 1.0, 0.5), (r0 + v0 * 0.5, v0));
+}
+
+#[test]
+fn finds_isolated_unresolved_binaries() {
+    let particles = binary_with_third_at(1e3);
+    let pairs = find_kepler_pairs(&particles, 1.0, DEFAULT_STEPS_PER_ORBIT, &[]);
+    assert_eq!(pairs.len(), 1);
+    assert_eq!(pairs[0].pair(), (1, 2));
+    assert!(pairs[0].orbit.eccentricity < 1e-9);
+    // A step this short resolves the orbit on its own.
+    assert!(find_kepler_pairs(&particles, 0.01, DEFAULT_STEPS_PER_ORBIT, &[]).is_empty());
+}
+
+#[test]
+fn perturbed_pairs_are_kept_but_not_formed() {
+    // Perturbation 2 m r³ / (M d³) = 1 / 125, between the two limits.
+    let particles = binary_with_third_at(5.0);
+    assert!(find_kepler_pairs(&particles, 1.0, DEFAULT_STEPS_PER_ORBIT, &[]).is_empty());
+    let held = find_kepler_pairs(&particles, 1.0, DEFAULT_STEPS_PER_ORBIT, &[(1, 2)]);
+    assert_eq!(held.len(), 1);
+    assert!((held[0].perturbation - 1.0 / 125.0).abs() < 1e-12);
+    let particles = binary_with_third_at(3.0);
+    assert!(find_kepler_pairs(&particles, 1.0, DEFAULT_STEPS_PER_ORBIT, &[(1, 2)]).is_empty());
+}
+
+#[test]
+fn regularized_binary_survives_steps_longer_than_its_period() {
+    let manager = SimulationManager::new();
+    manager.reset_from_particles(binary_with_third_at(1e5), SimulationType::Normal, 1.0);
+    let mut held = Vec::new();
+    for _ in 0..200 {
+        let pairs = manager.find_kepler_pairs(100.0, DEFAULT_STEPS_PER_ORBIT, &held);
+        assert_eq!(pairs.len(), 1);
+        held = pairs.iter().map(KeplerPair::pair).collect();
+        manager.advance_with_kepler_pairs(100.0, &pairs);
+    }
+    let particles = manager.particles();
+    let separation = particles[0].position.distance(particles[1].position);
+    assert!((separation - 1.0).abs() < 1e-6, "{}", separation);
+}
+
+#[test]
+fn formation_and_dissolution_are_logged_once() {
+    let mut ui = UiState::default();
+    let pairs = find_kepler_pairs(&binary_with_third_at(1e3), 1.0, 50, &[]);
+    ui.note_kepler_pairs(&pairs);
+    ui.note_kepler_pairs(&pairs);
+    assert_eq!(ui.event_log.len(), 1);
+    assert_eq!(ui.kepler_pairs, vec![(1, 2)]);
+    ui.note_kepler_pairs(&[]);
+    assert_eq!(ui.event_log.len(), 2);
+    assert!(ui.event_log[1].contains("dissolved"));
+    assert!(ui.kepler_pairs.is_empty());
+}
+
+#[test]
+fn regularization_needs_the_cpu_newtonian_engine() {
+    let mut ui = UiState::default();
+    ui.regularize_binaries = true;
+    assert_eq!(ui.binary_regularization(), None);
+    ui.active_computing_unit = ComputingUnit::Cpu;
+    assert_eq!(ui.binary_regularization(), Some(DEFAULT_STEPS_PER_ORBIT));
+}
@@ -0,0 +1,84 @@
+use dual_spacetime_simulator::kiosk::{CameraMotion, Kiosk, KioskStep};
+use dual_spacetime_simulator::object_input::ObjectInputType;
+use dual_spacetime_simulator::ui_state::{PlacementMode, UiState};
+use std::time::{Duration, Instant};
+
+fn step(placement: PlacementMode, seconds: u64) -> KioskStep {
+    KioskStep {
+        placement,
+        object_type: ObjectInputType::SpiralDisk,
+        duration: Duration::from_secs(seconds),
+        camera: CameraMotion {
+            yaw_degrees_per_second: 90.0,
+            zoom_per_second: 0.0,
+        },
+    }
+}
+
+#[test]
+fn playlist_loads_each_entry_after_its_duration_and_wraps() {
+    let first = step(PlacementMode::Manual, 10);
+    let second = step(PlacementMode::SolarSystem, 5);
+    let mut kiosk = Kiosk::new(vec![first, second]);
+    let start = Instant::now();
+    assert_eq!(kiosk.tick(start), None);
+
+    kiosk.start();
+    assert_eq!(kiosk.tick(start).unwrap().load, Some(first));
+    assert_eq!(
+        kiosk.tick(start + Duration::from_secs(9)).unwrap().load,
+        None
+    );
+    assert_eq!(
+        kiosk.remaining(start + Duration::from_secs(9)),
+        Some(Duration::from_secs(1))
+    );
+    let at_second = start + Duration::from_secs(10);
+    assert_eq!(kiosk.tick(at_second).unwrap().load, Some(second));
+    let wrapped = kiosk.tick(at_second + Duration::from_secs(5)).unwrap();
+    assert_eq!(wrapped.load, Some(first));
+    assert_eq!(kiosk.current().map(|(index, _)| index), Some(0));
+
+    assert!(kiosk.stop());
+    assert!(!kiosk.stop());
+    assert_eq!(kiosk.tick(at_second), None);
+}
+
+#[test]
+fn camera_step_follows_the_script_and_caps_stalls() {
+    let mut kiosk = Kiosk::new(vec![step(PlacementMode::Manual, 60)]);
+    let start = Instant::now();
+    kiosk.start();
+    assert_eq!(kiosk.tick(start).unwrap().camera.yaw, 0.0);
+    let yaw = kiosk
+        .tick(start + Duration::from_millis(50))
+        .unwrap()
+        .camera
+        .yaw;
+    assert!((yaw - 0.05 * std::f32::consts::FRAC_PI_2).abs() < 1e-5);
+    let stalled = kiosk
+        .tick(start + Duration::from_secs(3))
+        .unwrap()
+        .camera
+        .yaw;
+    assert!((stalled - 0.1 * std::f32::consts::FRAC_PI_2).abs() < 1e-5);
+}
+
+#[test]
+fn ui_state_resets_into_the_entry_and_runs_after_the_reset() {
+    let mut ui = UiState::default();
+    ui.kiosk = Kiosk::new(vec![step(PlacementMode::SatelliteOrbit, 30)]);
+    ui.start_kiosk();
+    let now = Instant::now();
+    ui.tick_kiosk(now);
+    assert_eq!(ui.placement_mode, PlacementMode::SatelliteOrbit);
+    assert!(ui.is_reset_requested);
+    assert!(!ui.is_running);
+
+    ui.is_reset_requested = false;
+    ui.is_resetting = false;
+    ui.tick_kiosk(now + Duration::from_millis(10));
+    assert!(ui.is_running);
+    assert!(ui.stop_kiosk());
+    assert!(ui.is_running);
+}
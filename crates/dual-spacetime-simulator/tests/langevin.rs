@@ -0,0 +1,74 @@
+use dual_spacetime_simulator::langevin::{BOLTZMANN, LangevinNoise, apply_langevin_noise};
+use dual_spacetime_simulator::simulation::{Particle, assign_particle_ids};
+use dual_spacetime_simulator::ui_state::{SimulationType, UiState};
+use glam::DVec3;
+
+fn particles_at_rest(count: usize, mass: f64) -> Vec<Particle> {
+    let mut particles =
+        vec![Particle::from_kinematics(DVec3::ZERO, DVec3::ZERO, mass, [1.0; 4]); count];
+    assign_particle_ids(&mut particles);
+    particles
+}
+
+fn run(noise: LangevinNoise, steps: u64) -> Vec<DVec3> {
+    let mut particles = particles_at_rest(16, 1.0);
+    for step in 0..steps {
+        apply_langevin_noise(&mut particles, noise, 1.0, 0.1, step);
+    }
+    particles.iter().map(|p| p.velocity).collect()
+}
+
+#[test]
+fn same_seed_repeats_the_run() {
+    let noise = LangevinNoise {
+        temperature: 1.0 / BOLTZMANN,
+        damping_rate: 1.0,
+        seed: 42,
+    };
+    assert_eq!(run(noise, 20), run(noise, 20));
+    let other = LangevinNoise { seed: 43, ..noise };
+    assert_ne!(run(noise, 20), run(other, 20));
+}
+
+#[test]
+fn bath_thermalizes_to_equipartition() {
+    let mass = 4.0;
+    let noise = LangevinNoise {
+        temperature: mass / BOLTZMANN,
+        damping_rate: 1.0,
+        seed: 7,
+    };
+    assert!((noise.thermal_speed(mass) - 1.0).abs() < 1e-12);
+    let mut particles = particles_at_rest(20_000, mass);
+    // Many relaxation times, so the initial rest state is forgotten.
+    for step in 0..10 {
+        apply_langevin_noise(&mut particles, noise, 1.0, 1.0, step);
+    }
+    let mean_square = particles
+        .iter()
+        .map(|p| p.velocity.length_squared())
+        .sum::<f64>()
+        / particles.len() as f64;
+    assert!((mean_square - 3.0).abs() < 0.1, "⟨v²⟩ = {mean_square}");
+}
+
+#[test]
+fn kick_amplitude_scales_with_world_scale() {
+    let noise = LangevinNoise::default();
+    let (mass, scale, dt): (f64, f64, f64) = (2e30, 1e10, 100.0);
+    let si_kick = noise.kick_amplitude(1.0, dt) / mass.sqrt();
+    let sim_kick = noise.kick_amplitude(scale, dt) / (mass / scale.powi(3)).sqrt();
+    assert!((sim_kick * scale / si_kick - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn noise_applies_only_when_enabled_and_supported() {
+    let mut ui = UiState::default();
+    assert_eq!(ui.langevin_noise(), None);
+    ui.noise_enabled = true;
+    assert_eq!(ui.langevin_noise(), Some(LangevinNoise::default()));
+
+    ui.simulation_type = SimulationType::SpeedOfLightLimit;
+    ui.request_reset();
+    assert_eq!(ui.langevin_noise(), None);
+}
@@ -0,0 +1,63 @@
+use dual_spacetime_simulator::light_cone::{
+    LIGHT_CONE_INTERVAL, LIGHT_CONE_MAX_MARKERS, light_cone_crossing, light_cone_crossings,
+    light_cone_depth,
+};
+use dual_spacetime_simulator::relativistic_view::ViewKinematics;
+use dual_spacetime_simulator::simulation::Particle;
+use dual_spacetime_simulator::ui_state::{SimulationType, UiState};
+use glam::DVec3;
+
+const C: f64 = 10.0;
+
+#[test]
+fn crossing_events_lie_on_the_cone() {
+    let observer = DVec3::new(1.0, 4.0, -2.0);
+    let still = light_cone_crossing(DVec3::new(31.0, 9.0, 38.0), DVec3::ZERO, observer, C).unwrap();
+    assert_eq!(still.now, DVec3::new(31.0, 4.0, 38.0));
+    assert!((still.event - DVec3::new(31.0, 4.0 - 50.0, 38.0)).length() < 1e-9);
+    assert!((still.look_back - 5.0).abs() < 1e-12);
+
+    let moving = light_cone_crossing(
+        DVec3::new(-20.0, 0.0, 15.0),
+        DVec3::new(6.0, 100.0, -3.0),
+        observer,
+        C,
+    )
+    .unwrap();
+    let planar = DVec3::new(
+        moving.event.x - observer.x,
+        0.0,
+        moving.event.z - observer.z,
+    );
+    assert!((planar.length() - (observer.y - moving.event.y)).abs() < 1e-9);
+    assert!(light_cone_crossing(DVec3::X, DVec3::Z * C, observer, C).is_none());
+}
+
+#[test]
+fn crossings_are_sampled_and_skip_dead_particles() {
+    let mut particles: Vec<Particle> = (0..LIGHT_CONE_MAX_MARKERS * 2 + 1)
+        .map(|i| Particle::from_kinematics(DVec3::X * i as f64, DVec3::ZERO, 1.0, [1.0; 4]))
+        .collect();
+    particles[3].color[3] = 0.0;
+    let crossings = light_cone_crossings(&particles, ViewKinematics::Off, DVec3::ZERO, C);
+    assert!(crossings.len() <= LIGHT_CONE_MAX_MARKERS);
+    assert!(crossings.iter().all(|c| c.now != DVec3::X * 3.0));
+    let deepest = crossings.iter().map(|c| c.now.x).fold(0.0, f64::max);
+    assert!((light_cone_depth(&crossings, C) - deepest).abs() < 1e-9);
+}
+
+#[test]
+fn light_cone_updates_when_the_camera_moves_or_time_passes() {
+    let mut uis = UiState::default();
+    assert!(!uis.light_cone_update_due(DVec3::ZERO));
+    uis.show_light_cone = true;
+    assert!(uis.light_cone_update_due(DVec3::ZERO));
+    uis.light_cone_observer = Some(DVec3::ZERO);
+    uis.light_cone_frame = Some(uis.frame);
+    assert!(!uis.light_cone_update_due(DVec3::ZERO));
+    assert!(uis.light_cone_update_due(DVec3::X));
+    uis.frame += LIGHT_CONE_INTERVAL;
+    assert!(uis.light_cone_update_due(DVec3::ZERO));
+    uis.active_simulation_type = SimulationType::DstGalaxy;
+    assert!(!uis.light_cone_update_due(DVec3::X));
+}
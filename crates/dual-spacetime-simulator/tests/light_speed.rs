@@ -0,0 +1,50 @@
+use dual_spacetime_simulator::simulation::{
+    EngineConfig, LIGHT_SPEED, Particle, SimulationManager,
+};
+use dual_spacetime_simulator::ui_state::{SimulationType, UiState};
+use glam::DVec3;
+
+fn fastest_speed(simulation_type: SimulationType, config: EngineConfig) -> f64 {
+    let particles = vec![
+        Particle::from_kinematics(DVec3::ZERO, DVec3::new(1000.0, 0.0, 0.0), 1.0, [1.0; 4]),
+        Particle::from_kinematics(DVec3::X, DVec3::ZERO, 1e12, [1.0; 4]),
+    ];
+    let manager = SimulationManager::with_config(config);
+    manager.reset_from_particles(particles, simulation_type, 1.0);
+    for _ in 0..10 {
+        manager.advance(0.01);
+    }
+    manager
+        .particles()
+        .iter()
+        .map(|p| p.velocity.length())
+        .fold(0.0, f64::max)
+}
+
+#[test]
+fn lowered_light_speed_limits_the_special_engines() {
+    let mut config = EngineConfig::default();
+    assert_eq!(config.light_speed_factor, 1.0);
+    assert!(fastest_speed(SimulationType::SpeedOfLightLimit, config) > 999.0);
+
+    config.set_light_speed_factor(1e-6);
+    assert!((config.light_speed() - LIGHT_SPEED * 1e-6).abs() < 1e-9);
+    assert!(fastest_speed(SimulationType::SpeedOfLightLimit, config) < config.light_speed());
+
+    config.set_light_speed_factor(0.0);
+    assert!(config.light_speed_factor > 0.0);
+}
+
+#[test]
+fn reset_commits_the_light_speed_factor_of_subluminal_engines() {
+    let mut uis = UiState::default();
+    uis.light_speed_factor = 1e-3;
+    uis.simulation_type = SimulationType::Normal;
+    uis.request_reset();
+    assert_eq!(uis.active_light_speed_factor, 1.0);
+    uis.simulation_type = SimulationType::LorentzTransformation;
+    uis.request_reset();
+    assert_eq!(uis.active_light_speed_factor, 1e-3);
+    assert_eq!(uis.engine_config().light_speed_factor, 1e-3);
+    assert!((uis.light_speed() - LIGHT_SPEED * 1e-3).abs() < 1e-6);
+}
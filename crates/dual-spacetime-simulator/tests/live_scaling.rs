@@ -0,0 +1,66 @@
+use dual_spacetime_simulator::live_scaling::{
+    least_bound_indices, rescale_particle_count, sample_particles,
+};
+use dual_spacetime_simulator::simulation::{G, Particle, assign_particle_ids};
+use dual_spacetime_simulator::ui_state::SimulationType;
+use glam::DVec3;
+
+fn cluster_with_escaper() -> Vec<Particle> {
+    let central_mass = 1e10;
+    let circular = (G * central_mass).sqrt();
+    let mut particles = vec![
+        Particle::from_kinematics(DVec3::ZERO, DVec3::ZERO, central_mass, [1.0; 4]),
+        Particle::from_kinematics(DVec3::X, DVec3::Y * circular, 1.0, [1.0; 4]),
+        Particle::from_kinematics(DVec3::X * 50.0, DVec3::Y * circular * 10.0, 1.0, [1.0; 4]),
+        Particle::from_kinematics(-DVec3::X * 2.0, DVec3::Y * circular * 0.5, 1.0, [1.0; 4]),
+    ];
+    assign_particle_ids(&mut particles);
+    particles
+}
+
+#[test]
+fn least_bound_indices_pick_the_escaper_first() {
+    let particles = cluster_with_escaper();
+    assert_eq!(least_bound_indices(&particles, 1), vec![2]);
+    let two = least_bound_indices(&particles, 2);
+    assert_eq!(two.len(), 2);
+    assert!(two.contains(&2) && !two.contains(&0));
+    assert!(two.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn sampled_particles_copy_kinematics_and_clear_ids() {
+    let particles = cluster_with_escaper();
+    let sampled = sample_particles(&particles, 32, &mut rand::rng());
+    assert_eq!(sampled.len(), 32);
+    for particle in &sampled {
+        assert_eq!(particle.id, 0);
+        let source = particles
+            .iter()
+            .find(|p| p.velocity == particle.velocity && p.mass == particle.mass)
+            .expect("sample must come from a live particle");
+        assert!(particle.position.distance(source.position) <= 0.05 * 50.0 + 1e-9);
+    }
+    assert!(sample_particles(&[], 4, &mut rand::rng()).is_empty());
+}
+
+#[test]
+fn rescale_grows_and_shrinks_in_place() {
+    let mut particles = cluster_with_escaper();
+    let grown = rescale_particle_count(&mut particles, 10, SimulationType::Normal, 1.0);
+    assert_eq!((grown.added, grown.removed), (6, 0));
+    assert_eq!(particles.len(), 10);
+    let mut ids: Vec<u64> = particles.iter().map(|p| p.id).collect();
+    ids.sort_unstable();
+    ids.dedup();
+    assert_eq!(ids.len(), 10);
+    assert!(ids.iter().all(|&id| id != 0));
+
+    let shrunk = rescale_particle_count(&mut particles, 3, SimulationType::Normal, 1.0);
+    assert_eq!((shrunk.added, shrunk.removed), (0, 7));
+    assert_eq!(particles.len(), 3);
+    assert!(particles.iter().any(|p| p.mass == 1e10));
+
+    let unchanged = rescale_particle_count(&mut particles, 3, SimulationType::Normal, 1.0);
+    assert_eq!((unchanged.added, unchanged.removed), (0, 0));
+}
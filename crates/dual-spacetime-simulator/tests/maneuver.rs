@@ -0,0 +1,119 @@
+use dual_spacetime_simulator::maneuver::{Maneuver, OrbitalFrame, apply_maneuver, dominant_body};
+use dual_spacetime_simulator::simulation::{G, Particle, SimulationManager, assign_particle_ids};
+use dual_spacetime_simulator::ui_state::{SimulationType, UiState};
+use glam::DVec3;
+
+fn particle(position: DVec3, velocity: DVec3, mass: f64) -> Particle {
+    Particle::from_kinematics(position, velocity, mass, [1.0; 4])
+}
+
+#[test]
+fn orbital_frame_points_along_motion_and_away_from_primary() {
+    let frame = OrbitalFrame::from_relative_state(DVec3::X * 2.0, DVec3::Y * 3.0).unwrap();
+    assert_eq!(frame.prograde, DVec3::Y);
+    assert_eq!(frame.radial, DVec3::X);
+    assert_eq!(frame.normal, DVec3::Z);
+    assert_eq!(
+        frame.to_world(DVec3::new(1.0, 2.0, 3.0)),
+        DVec3::new(2.0, 1.0, 3.0)
+    );
+    assert!(OrbitalFrame::from_relative_state(DVec3::X, DVec3::X).is_none());
+}
+
+#[test]
+fn dominant_body_is_the_strongest_pull() {
+    let particles = [
+        particle(DVec3::ZERO, DVec3::ZERO, 1.0),
+        particle(DVec3::X * 10.0, DVec3::ZERO, 50.0),
+        particle(DVec3::X * -2.0, DVec3::ZERO, 3.0),
+    ];
+    // 50 / 100 < 3 / 4: the nearby light body wins.
+    assert_eq!(dominant_body(&particles, 0), Some(2));
+    assert_eq!(dominant_body(&particles[..1], 0), None);
+}
+
+#[test]
+fn burn_is_relative_to_primary_and_scaled() {
+    let mut particles = vec![
+        particle(DVec3::ZERO, DVec3::Z * 5.0, 1e6),
+        particle(DVec3::X, DVec3::new(0.0, 1.0, 5.0), 1.0),
+    ];
+    assign_particle_ids(&mut particles);
+    let maneuver = Maneuver {
+        particle_id: particles[1].id,
+        delta_v: DVec3::new(20.0, 0.0, 0.0),
+        burn_time: 0.0,
+    };
+    assert!(apply_maneuver(&mut particles, &maneuver, 10.0));
+    assert_eq!(particles[1].velocity, DVec3::new(0.0, 3.0, 5.0));
+    assert_eq!(particles[0].velocity, DVec3::Z * 5.0);
+
+    let missing = Maneuver {
+        particle_id: 999,
+        ..maneuver
+    };
+    assert!(!apply_maneuver(&mut particles, &missing, 10.0));
+}
+
+#[test]
+fn hohmann_burn_reaches_the_target_radius() {
+    let central_mass = 1.0e20;
+    let inner = 1.0e3;
+    let outer = 2.0e3;
+    let mu = G * central_mass;
+    let speed = (mu / inner).sqrt();
+    let mut particles = vec![
+        particle(DVec3::ZERO, DVec3::ZERO, central_mass),
+        particle(
+            DVec3::new(inner, 0.0, 0.0),
+            DVec3::new(0.0, 0.0, speed),
+            1.0,
+        ),
+    ];
+    assign_particle_ids(&mut particles);
+    let transfer_speed = (mu / inner * 2.0 * outer / (inner + outer)).sqrt();
+    let maneuver = Maneuver {
+        particle_id: particles[1].id,
+        delta_v: DVec3::X * (transfer_speed - speed),
+        burn_time: 0.0,
+    };
+    assert!(apply_maneuver(&mut particles, &maneuver, 1.0));
+
+    let manager = SimulationManager::new();
+    manager.reset_from_particles(particles, SimulationType::Normal, 1.0);
+    let semi_major_axis = 0.5 * (inner + outer);
+    let half_period = std::f64::consts::PI * (semi_major_axis.powi(3) / mu).sqrt();
+    let dt = half_period / 4000.0;
+    let mut apoapsis: f64 = 0.0;
+    for _ in 0..4000 {
+        manager.advance(dt);
+        let particles = manager.particles();
+        apoapsis = apoapsis.max((particles[1].position - particles[0].position).length());
+    }
+    assert!(
+        (apoapsis / outer - 1.0).abs() < 0.01,
+        "{apoapsis} vs {outer}"
+    );
+}
+
+#[test]
+fn due_maneuvers_are_taken_once() {
+    let mut ui = UiState::default();
+    ui.maneuver_delta_v = DVec3::X;
+    ui.maneuver_delay = 10.0;
+    ui.schedule_maneuver(3);
+    ui.schedule_maneuver(0);
+    assert_eq!(ui.maneuvers.len(), 1);
+    assert!(ui.take_due_maneuvers().is_empty());
+    ui.simulation_time = 10.0;
+    let due = ui.take_due_maneuvers();
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].particle_id, 3);
+    assert!(ui.maneuvers.is_empty());
+
+    ui.schedule_maneuver(3);
+    ui.simulation_type = SimulationType::LorentzTransformation;
+    ui.request_reset();
+    assert!(ui.take_due_maneuvers().is_empty());
+    assert_eq!(ui.maneuvers.len(), 1);
+}
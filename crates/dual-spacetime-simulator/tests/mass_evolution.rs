@@ -0,0 +1,125 @@
+use dual_spacetime_simulator::mass_evolution::{MassRule, ParticleMassRule, evolve_masses};
+use dual_spacetime_simulator::simulation::{G, Particle, SimulationManager, assign_particle_ids};
+use dual_spacetime_simulator::ui_state::{ComputingUnit, SimulationType, UiState};
+use glam::DVec3;
+
+fn particle(position: DVec3, velocity: DVec3, mass: f64) -> Particle {
+    Particle::from_kinematics(position, velocity, mass, [1.0; 4])
+}
+
+fn rule(particle_id: u64, rule: MassRule) -> ParticleMassRule {
+    ParticleMassRule { particle_id, rule }
+}
+
+#[test]
+fn mass_loss_keeps_velocity_and_stops_at_zero() {
+    let scale = 10.0;
+    let mut particles = vec![particle(DVec3::ZERO, DVec3::X, 4.0)];
+    particles[0].momentum = DVec3::X * 4.0;
+    assign_particle_ids(&mut particles);
+    // 1000 kg/s at scale 10 is 1 simulation mass unit per second.
+    let rules = [rule(particles[0].id, MassRule::MassLoss { rate: 1000.0 })];
+    assert!(evolve_masses(&mut particles, &rules, scale, 1.0).is_empty());
+    assert!((particles[0].mass - 3.0).abs() < 1e-12);
+    assert_eq!(particles[0].velocity, DVec3::X);
+    assert!((particles[0].momentum - DVec3::X * 3.0).length() < 1e-12);
+
+    evolve_masses(&mut particles, &rules, scale, 10.0);
+    assert_eq!(particles[0].mass, 0.0);
+}
+
+#[test]
+fn accretion_conserves_mass_and_momentum() {
+    let mut particles = vec![
+        particle(DVec3::ZERO, DVec3::ZERO, 3.0),
+        particle(DVec3::new(0.5, 0.0, 0.0), DVec3::new(0.0, 4.0, 0.0), 1.0),
+        particle(DVec3::new(2.0, 0.0, 0.0), DVec3::ZERO, 1.0),
+    ];
+    assign_particle_ids(&mut particles);
+    let far_id = particles[2].id;
+    let rules = [rule(particles[0].id, MassRule::Accretion { radius: 1.0 })];
+    let removed = evolve_masses(&mut particles, &rules, 1.0, 1.0);
+    assert_eq!(removed, vec![1]);
+    assert_eq!(particles.len(), 2);
+    assert_eq!(particles[0].mass, 4.0);
+    assert!((particles[0].position - DVec3::new(0.125, 0.0, 0.0)).length() < 1e-12);
+    assert!((particles[0].velocity - DVec3::new(0.0, 1.0, 0.0)).length() < 1e-12);
+    assert_eq!(particles[1].id, far_id);
+}
+
+#[test]
+fn heavier_accretor_swallows_lighter_one() {
+    let mut particles = vec![
+        particle(DVec3::ZERO, DVec3::ZERO, 1.0),
+        particle(DVec3::new(0.5, 0.0, 0.0), DVec3::ZERO, 2.0),
+    ];
+    assign_particle_ids(&mut particles);
+    let heavy_id = particles[1].id;
+    let rules = [
+        rule(particles[0].id, MassRule::Accretion { radius: 1.0 }),
+        rule(heavy_id, MassRule::Accretion { radius: 1.0 }),
+    ];
+    assert_eq!(evolve_masses(&mut particles, &rules, 1.0, 1.0), vec![0]);
+    assert_eq!(particles[0].id, heavy_id);
+    assert_eq!(particles[0].mass, 3.0);
+}
+
+#[test]
+fn central_mass_loss_widens_orbit() {
+    let central_mass = 1.0e20;
+    let radius = 1.0e3;
+    let speed = (G * central_mass / radius).sqrt();
+    let manager = SimulationManager::new();
+    manager.reset_from_particles(
+        vec![
+            particle(DVec3::ZERO, DVec3::ZERO, central_mass),
+            particle(
+                DVec3::new(radius, 0.0, 0.0),
+                DVec3::new(0.0, 0.0, speed),
+                1.0,
+            ),
+        ],
+        SimulationType::Normal,
+        1.0,
+    );
+    let sun_id = manager.particles()[0].id;
+    // Half the central mass goes over about twenty orbits: slow enough to be adiabatic,
+    // so the orbit radius grows as 1/M.
+    let period = std::f64::consts::TAU * radius / speed;
+    let duration = 20.0 * period;
+    let rate = 0.5 * central_mass / duration;
+    let rules = [rule(sun_id, MassRule::MassLoss { rate })];
+    let dt = period / 2000.0;
+    for _ in 0..(duration / dt) as usize {
+        manager.advance(dt);
+        manager.evolve_masses(&rules, 1.0, dt);
+    }
+    let particles = manager.particles();
+    let distance = (particles[1].position - particles[0].position).length();
+    let expected = radius * central_mass / particles[0].mass;
+    assert!(
+        (distance / expected - 1.0).abs() < 0.05,
+        "{distance} vs {expected}"
+    );
+}
+
+#[test]
+fn mass_rules_are_keyed_by_particle_id() {
+    let mut ui = UiState::default();
+    ui.set_mass_rule(7, Some(MassRule::MassLoss { rate: 1.0 }));
+    ui.set_mass_rule(7, Some(MassRule::Accretion { radius: 2.0 }));
+    ui.set_mass_rule(0, Some(MassRule::Accretion { radius: 2.0 }));
+    assert_eq!(ui.mass_rules.len(), 1);
+    assert_eq!(ui.mass_rule(7), Some(MassRule::Accretion { radius: 2.0 }));
+    // The GPU engine leaves masses alone.
+    assert!(ui.mass_rules_in_effect().is_empty());
+    ui.computing_unit = ComputingUnit::Cpu;
+    ui.request_reset();
+    assert_eq!(ui.mass_rules_in_effect().len(), 1);
+
+    ui.simulation_type = SimulationType::LorentzTransformation;
+    ui.request_reset();
+    assert!(ui.mass_rules_in_effect().is_empty());
+    ui.set_mass_rule(7, None);
+    assert!(ui.mass_rules.is_empty());
+}
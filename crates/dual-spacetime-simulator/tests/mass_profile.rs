@@ -0,0 +1,72 @@
+use dual_spacetime_simulator::mass_profile::{escaping_particle_indices, mass_profile};
+use dual_spacetime_simulator::simulation::{G, Particle};
+use glam::DVec3;
+
+fn particle(position: DVec3, mass: f64, alpha: f32) -> Particle {
+    Particle::from_kinematics(position, DVec3::ZERO, mass, [1.0, 1.0, 1.0, alpha])
+}
+
+#[test]
+fn center_of_mass_is_mass_weighted() {
+    let particles = vec![
+        particle(DVec3::new(0.0, 0.0, 0.0), 3.0, 1.0),
+        particle(DVec3::new(4.0, 0.0, 0.0), 1.0, 1.0),
+        particle(DVec3::new(100.0, 0.0, 0.0), 50.0, 0.0),
+    ];
+    let profile = mass_profile(&particles).unwrap();
+    assert!((profile.center_of_mass - DVec3::new(1.0, 0.0, 0.0)).length() < 1e-12);
+}
+
+#[test]
+fn lagrangian_radii_follow_enclosed_mass() {
+    // Equal masses at x = ±1..=±10: 2 of 20 lie within r = 1, 10 within 5, 18 within 9.
+    let particles: Vec<Particle> = (1..=10)
+        .flat_map(|i| {
+            let x = i as f64;
+            [
+                particle(DVec3::new(x, 0.0, 0.0), 1.0, 1.0),
+                particle(DVec3::new(-x, 0.0, 0.0), 1.0, 1.0),
+            ]
+        })
+        .collect();
+    let profile = mass_profile(&particles).unwrap();
+    assert!(profile.center_of_mass.length() < 1e-12);
+    assert_eq!(profile.lagrangian_radii, [1.0, 5.0, 9.0]);
+}
+
+#[test]
+fn no_live_mass_yields_none() {
+    assert!(mass_profile(&[]).is_none());
+    assert!(mass_profile(&[particle(DVec3::ONE, 1.0, 0.0)]).is_none());
+    assert!(mass_profile(&[particle(DVec3::ONE, 0.0, 1.0)]).is_none());
+}
+
+#[test]
+fn escapers_exceed_point_mass_escape_speed() {
+    let central_mass = 1e10;
+    let escape_speed = (2.0 * G * central_mass).sqrt();
+    let moving = |speed: f64| {
+        Particle::from_kinematics(
+            DVec3::new(1.0, 0.0, 0.0),
+            DVec3::new(0.0, speed, 0.0),
+            0.0,
+            [1.0, 1.0, 1.0, 1.0],
+        )
+    };
+    let particles = vec![
+        particle(DVec3::ZERO, central_mass, 1.0),
+        moving(escape_speed * 0.9),
+        moving(escape_speed * 1.1),
+    ];
+    assert_eq!(escaping_particle_indices(&particles), vec![2]);
+}
+
+#[test]
+fn bulk_motion_does_not_count_as_escape() {
+    let drift = DVec3::new(1e6, 0.0, 0.0);
+    let particles: Vec<Particle> = [DVec3::X, -DVec3::X, DVec3::Y, -DVec3::Y]
+        .into_iter()
+        .map(|position| Particle::from_kinematics(position, drift, 1e10, [1.0; 4]))
+        .collect();
+    assert!(escaping_particle_indices(&particles).is_empty());
+}
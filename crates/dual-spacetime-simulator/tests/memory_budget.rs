@@ -0,0 +1,57 @@
+use dual_spacetime_simulator::memory_budget::{
+    CPU_PARTICLE_BYTES, GPU_PARTICLE_BYTES, MIN_PHASE_SPACE_POINTS, MemoryDemand, budget_bytes,
+    plan_within_budget,
+};
+use dual_spacetime_simulator::ui_state::UiState;
+
+fn demand(particle_count: u32) -> MemoryDemand {
+    MemoryDemand {
+        particle_count,
+        phase_space_max_points: 100_000,
+        pipelined_stepping: true,
+    }
+}
+
+#[test]
+fn demand_within_budget_is_left_alone() {
+    let plan = plan_within_budget(budget_bytes(1024), demand(1000));
+    assert_eq!(plan.demand, demand(1000));
+    assert!(plan.warning.is_none());
+    assert!(plan.particle_limit > 1000);
+}
+
+#[test]
+fn phase_space_is_reduced_before_pipelining_is_disabled() {
+    let particles = 10_000;
+    let usage = demand(particles).usage();
+    let budget = usage.particle_state + usage.gpu_buffers + 1000 * 24;
+    let plan = plan_within_budget(budget, demand(particles));
+    assert!(plan.demand.pipelined_stepping);
+    assert!(plan.demand.phase_space_max_points < 100_000);
+    assert!(plan.demand.phase_space_max_points >= MIN_PHASE_SPACE_POINTS);
+    assert!(plan.usage.total() <= budget);
+    assert!(plan.warning.unwrap().contains("phase-space points"));
+}
+
+#[test]
+fn particles_over_budget_warn_and_cap_growth() {
+    let plan = plan_within_budget(budget_bytes(1), demand(100_000));
+    assert!(!plan.demand.pipelined_stepping);
+    assert_eq!(plan.demand.phase_space_max_points, MIN_PHASE_SPACE_POINTS);
+    assert!(plan.particle_limit < 100_000);
+    assert!(
+        plan.particle_limit as u64 * (CPU_PARTICLE_BYTES + GPU_PARTICLE_BYTES) <= budget_bytes(1)
+    );
+    assert!(plan.warning.unwrap().contains("adding is disabled"));
+}
+
+#[test]
+fn ui_state_caps_remaining_capacity_by_budget() {
+    let mut uis = UiState::default();
+    uis.max_particle_count = u32::MAX;
+    uis.memory_budget_mb = 1;
+    uis.apply_memory_budget(100);
+    let limit = uis.memory_particle_limit.unwrap();
+    assert_eq!(uis.remaining_particle_capacity(100), limit - 100);
+    assert!(uis.memory_warning.is_none());
+}
@@ -0,0 +1,120 @@
+use dual_spacetime_simulator::metric_expr::{
+    DerivedMetric, DerivedMetricDefinition, MetricExpr, is_metric_name,
+};
+use dual_spacetime_simulator::metrics::{KINETIC_ENERGY, MetricsStore, POTENTIAL_ENERGY};
+
+fn eval(text: &str) -> Option<f64> {
+    let metrics = |name: &str| match name {
+        "ke" => Some(3.0),
+        "pe" => Some(-6.0),
+        "p" => Some(-2.0),
+        _ => None,
+    };
+    MetricExpr::parse(text).unwrap().evaluate(&metrics)
+}
+
+fn definition(name: &str, expression: &str) -> DerivedMetricDefinition {
+    DerivedMetricDefinition {
+        name: name.to_string(),
+        expression: expression.to_string(),
+    }
+}
+
+#[test]
+fn arithmetic_follows_the_usual_precedence() {
+    assert_eq!(eval("ke/pe"), Some(-0.5));
+    assert_eq!(eval("|p|"), Some(2.0));
+    assert_eq!(eval("1 + 2 * 3 - 4 / 2"), Some(5.0));
+    assert_eq!(eval("(1 + 2) * 3"), Some(9.0));
+    assert_eq!(eval("-2^2"), Some(-4.0));
+    assert_eq!(eval("2^3^2"), Some(512.0));
+    assert_eq!(eval("2 ^ -1"), Some(0.5));
+    assert_eq!(eval("| |p| - 5 |"), Some(3.0));
+    assert_eq!(eval("1.5e3 + 2.5E-1"), Some(1500.25));
+}
+
+#[test]
+fn functions_apply_to_their_argument() {
+    assert_eq!(eval("sqrt(ke + 1)"), Some(2.0));
+    assert_eq!(eval("abs(pe)"), Some(6.0));
+    assert!((eval("log10(100)").unwrap() - 2.0).abs() < 1e-12);
+    assert!((eval("ln(exp(2))").unwrap() - 2.0).abs() < 1e-12);
+}
+
+#[test]
+fn unknown_metrics_leave_the_value_undefined() {
+    assert_eq!(eval("ke + missing"), None);
+    assert!(eval("ke / 0").unwrap().is_infinite());
+}
+
+#[test]
+fn malformed_expressions_are_rejected() {
+    for text in [
+        "", "1 +", "(ke", "|p", "ke pe", "2e", "1.2.3", "ke % 2", "foo(1)",
+    ] {
+        assert!(MetricExpr::parse(text).is_err(), "{text:?} parsed");
+    }
+}
+
+#[test]
+fn uses_lists_the_metrics_read() {
+    let expr = MetricExpr::parse("2 * ke / sqrt(|pe|)").unwrap();
+    assert!(expr.uses("ke"));
+    assert!(expr.uses("pe"));
+    assert!(!expr.uses("sqrt"));
+}
+
+#[test]
+fn derived_metric_names_are_identifiers() {
+    assert!(is_metric_name("virial_ratio2"));
+    assert!(!is_metric_name("2x"));
+    assert!(!is_metric_name("a-b"));
+    assert!(!is_metric_name("sqrt"));
+    assert!(DerivedMetric::new(definition("x", "x + 1")).is_err());
+    assert!(DerivedMetric::new(definition("", "1")).is_err());
+    assert!(DerivedMetric::new(definition("x", "ke")).is_ok());
+}
+
+#[test]
+fn derived_metrics_follow_their_inputs() {
+    let mut store = MetricsStore::default();
+    store
+        .define(definition(
+            "virial",
+            "2 * kinetic_energy / |potential_energy|",
+        ))
+        .unwrap();
+    store.define(definition("doubled", "virial * 2")).unwrap();
+    store.record(KINETIC_ENERGY, 0.0, 1.0);
+    assert!(store.series("virial").is_none());
+    store.record(POTENTIAL_ENERGY, 0.0, -4.0);
+    store.record(KINETIC_ENERGY, 1.0, 2.0);
+    store.record(POTENTIAL_ENERGY, 1.0, -2.0);
+    let virial: Vec<(f64, f64)> = store.series("virial").unwrap().iter().collect();
+    assert_eq!(virial, [(0.0, 0.5), (1.0, 2.0)]);
+    assert_eq!(store.series("doubled").unwrap().latest(), Some((1.0, 4.0)));
+}
+
+#[test]
+fn derived_metrics_cannot_shadow_other_metrics() {
+    let mut store = MetricsStore::default();
+    store.record("measured", 0.0, 1.0);
+    assert!(store.define(definition(KINETIC_ENERGY, "1")).is_err());
+    assert!(store.define(definition("measured", "1")).is_err());
+    store.define(definition("twice", "measured * 2")).unwrap();
+    assert!(store.define(definition("twice", "1")).is_err());
+    store.record("measured", 1.0, 3.0);
+    assert_eq!(store.series("twice").unwrap().latest(), Some((1.0, 6.0)));
+    store.undefine("twice");
+    assert!(store.derived().is_empty());
+    assert!(store.series("twice").is_none());
+}
+
+#[test]
+fn set_derived_replaces_the_definitions_and_reports_failures() {
+    let mut store = MetricsStore::default();
+    store.define(definition("old", "1")).unwrap();
+    let errors = store.set_derived(&[definition("a", "ke + 1"), definition("b", "ke +")]);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(store.derived_definitions(), [definition("a", "ke + 1")]);
+}
@@ -0,0 +1,113 @@
+use dual_spacetime_simulator::metrics::{
+    DEFAULT_METRIC_CAPACITY, METRIC_SAMPLE_INTERVAL, MetricSampler, MetricsStore, RetentionPolicy,
+    TOTAL_ENERGY,
+};
+use std::time::Duration;
+
+fn values(store: &MetricsStore, name: &str) -> Vec<(f64, f64)> {
+    store.series(name).unwrap().iter().collect()
+}
+
+#[test]
+fn record_creates_one_series_per_metric() {
+    let mut store = MetricsStore::default();
+    assert!(store.is_empty());
+    store.record(TOTAL_ENERGY, 0.0, -5.0);
+    store.record("virial_ratio", 0.0, 0.5);
+    store.record(TOTAL_ENERGY, 1.0, -4.9);
+    assert_eq!(
+        store.names().collect::<Vec<_>>(),
+        ["total_energy", "virial_ratio"]
+    );
+    assert_eq!(values(&store, TOTAL_ENERGY), [(0.0, -5.0), (1.0, -4.9)]);
+    assert_eq!(
+        store.series(TOTAL_ENERGY).unwrap().latest(),
+        Some((1.0, -4.9))
+    );
+    assert!(store.series("missing").is_none());
+    assert_eq!(store.retention().capacity, DEFAULT_METRIC_CAPACITY);
+}
+
+#[test]
+fn full_series_drop_their_oldest_samples() {
+    let mut store = MetricsStore::new(RetentionPolicy {
+        capacity: 3,
+        max_age: 0.0,
+    });
+    for step in 0..5 {
+        store.record("x", step as f64, step as f64 * 10.0);
+    }
+    assert_eq!(values(&store, "x"), [(2.0, 20.0), (3.0, 30.0), (4.0, 40.0)]);
+}
+
+#[test]
+fn samples_older_than_the_max_age_are_dropped() {
+    let mut store = MetricsStore::new(RetentionPolicy {
+        capacity: 100,
+        max_age: 2.5,
+    });
+    for step in 0..6 {
+        store.record("x", step as f64, 1.0);
+    }
+    let times: Vec<f64> = values(&store, "x").iter().map(|&(t, _)| t).collect();
+    assert_eq!(times, [3.0, 4.0, 5.0]);
+}
+
+#[test]
+fn going_back_in_time_overwrites_the_later_history() {
+    let mut store = MetricsStore::default();
+    for step in 0..5 {
+        store.record("x", step as f64, step as f64);
+    }
+    store.record("x", 2.0, -1.0);
+    assert_eq!(values(&store, "x"), [(0.0, 0.0), (1.0, 1.0), (2.0, -1.0)]);
+}
+
+#[test]
+fn a_tighter_retention_trims_the_kept_history() {
+    let mut store = MetricsStore::default();
+    for step in 0..10 {
+        store.record("x", step as f64, step as f64);
+    }
+    store.set_retention(RetentionPolicy {
+        capacity: 4,
+        max_age: 0.0,
+    });
+    assert_eq!(store.series("x").unwrap().len(), 4);
+    assert_eq!(store.series("x").unwrap().latest(), Some((9.0, 9.0)));
+    store.clear();
+    assert!(store.is_empty());
+}
+
+#[test]
+fn csv_lists_every_sample_with_its_metric() {
+    let mut store = MetricsStore::default();
+    store.record("b", 1.0, 2.0);
+    store.record("a", 0.5, f64::NAN);
+    assert_eq!(store.to_csv(), "metric,t,value\na,5e-1,NaN\nb,1e0,2e0\n");
+    assert!(store.series("a").unwrap().points().is_empty());
+    assert_eq!(store.series("b").unwrap().points(), [[1.0, 2.0]]);
+}
+
+#[test]
+fn sampler_paces_samples_and_measures_the_step_time() {
+    let mut sampler = MetricSampler::default();
+    assert!(sampler.is_due(0));
+    assert_eq!(sampler.sample(0, Duration::ZERO), None);
+    assert!(!sampler.is_due(METRIC_SAMPLE_INTERVAL - 1));
+    assert!(sampler.is_due(METRIC_SAMPLE_INTERVAL));
+    let step_time = sampler.sample(20, Duration::from_millis(50)).unwrap();
+    assert!((step_time - 2.5).abs() < 1e-9);
+}
+
+#[test]
+fn sampler_restarts_after_going_back() {
+    let mut sampler = MetricSampler::default();
+    sampler.sample(100, Duration::from_secs(1));
+    assert!(sampler.is_due(50));
+    assert_eq!(sampler.sample(50, Duration::from_secs(2)), None);
+    assert!(sampler.sample(60, Duration::from_secs(3)).is_some());
+    sampler.forget();
+    assert!(sampler.is_due(61));
+    assert_eq!(sampler.sample(61, Duration::from_secs(4)), None);
+}
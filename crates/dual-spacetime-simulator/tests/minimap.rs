@@ -0,0 +1,71 @@
+use dual_spacetime_simulator::minimap::{
+    MINIMAP_INTERVAL, MinimapRect, camera_frustum_lines, minimap_rect, minimap_view_proj,
+};
+use dual_spacetime_simulator::ui_state::UiState;
+use glam::{Vec3, Vec4Swizzles};
+
+#[test]
+fn inset_sits_in_the_bottom_right_corner() {
+    assert_eq!(
+        minimap_rect(1600, 900),
+        Some(MinimapRect {
+            x: 1359,
+            y: 659,
+            size: 225,
+        })
+    );
+    assert_eq!(minimap_rect(200, 900), None);
+}
+
+#[test]
+fn top_down_view_frames_the_system() {
+    let center = Vec3::new(1.0, -2.0, 3.0);
+    let view_proj = minimap_view_proj(center, 4.0);
+    let project = |point: Vec3| {
+        let clip = view_proj * point.extend(1.0);
+        clip.xyz() / clip.w
+    };
+    let middle = project(center);
+    assert!(middle.x.abs() < 1e-6 && middle.y.abs() < 1e-6);
+    assert!((middle.z - 0.5).abs() < 1e-6);
+    let edge_x = project(center + Vec3::X * 4.0);
+    let edge_z = project(center + Vec3::Z * 4.0);
+    assert!((edge_x.x - 1.0 / 1.1).abs() < 1e-5);
+    assert!((edge_z.y - 1.0 / 1.1).abs() < 1e-5);
+    // Height along the grid normal does not move a point on the map.
+    let lifted = project(center + Vec3::X * 4.0 - Vec3::Y);
+    assert!((lifted.x - edge_x.x).abs() < 1e-6);
+}
+
+#[test]
+fn frustum_reaches_the_view_rectangle_at_the_target() {
+    let position = Vec3::new(0.0, 0.0, 3.0);
+    let fov_y = std::f32::consts::FRAC_PI_2;
+    let lines = camera_frustum_lines(position, Vec3::ZERO, Vec3::Y, 2.0, fov_y);
+    assert_eq!(lines.len(), 8);
+    for [start, corner] in &lines[..4] {
+        assert_eq!(*start, position);
+        assert!(corner.z.abs() < 1e-6);
+        assert!((corner.x.abs() - 6.0).abs() < 1e-5);
+        assert!((corner.y.abs() - 3.0).abs() < 1e-5);
+    }
+    assert_eq!(lines[4][1], lines[5][0]);
+    assert_eq!(lines[7][1], lines[4][0]);
+    assert!(camera_frustum_lines(position, position, Vec3::Y, 1.0, fov_y).is_empty());
+    assert!(camera_frustum_lines(Vec3::Y, Vec3::ZERO, Vec3::Y, 1.0, fov_y).is_empty());
+}
+
+#[test]
+fn bounds_refresh_only_while_shown() {
+    let mut ui = UiState::default();
+    assert!(!ui.minimap_update_due());
+    ui.show_minimap = true;
+    assert!(ui.minimap_update_due());
+    ui.minimap_frame = Some(ui.frame);
+    assert!(!ui.minimap_update_due());
+    ui.frame += MINIMAP_INTERVAL;
+    assert!(ui.minimap_update_due());
+    ui.minimap_frame = Some(ui.frame);
+    ui.finish_applied_reset(None);
+    assert!(ui.minimap_update_due());
+}
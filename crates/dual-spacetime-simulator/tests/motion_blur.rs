@@ -0,0 +1,46 @@
+#![cfg(feature = "gui")]
+
+use dual_spacetime_simulator::motion_blur::{TRAIL_FLOOR, blur_retention, composite_gain};
+
+#[test]
+fn zero_frames_turn_motion_blur_off() {
+    assert_eq!(blur_retention(0), 0.0);
+    assert_eq!(composite_gain(blur_retention(0)), 1.0);
+}
+
+#[test]
+fn trails_fade_to_the_floor_after_the_configured_frames() {
+    for frames in [1, 2, 8, 30, 120] {
+        let retention = blur_retention(frames);
+        assert!(retention > 0.0 && retention < 1.0, "{frames}: {retention}");
+        let left = retention.powi(frames as i32);
+        assert!((left / TRAIL_FLOOR - 1.0).abs() < 1e-3, "{frames}: {left}");
+    }
+    assert!(blur_retention(30) > blur_retention(8));
+}
+
+#[test]
+fn a_particle_at_rest_keeps_its_brightness() {
+    // The same pixel lit every frame: fade, add 1, composite.
+    let retention = blur_retention(16);
+    let gain = composite_gain(retention);
+    let mut accumulated = 0.0f32;
+    for _ in 0..2000 {
+        accumulated = accumulated * retention + 1.0;
+    }
+    assert!(
+        (accumulated * gain - 1.0).abs() < 1e-4,
+        "{}",
+        accumulated * gain
+    );
+}
+
+#[test]
+fn a_moving_particle_spreads_its_light_along_the_streak() {
+    // Lit once, then left behind: the pixel's composited share decays by the retention.
+    let retention = blur_retention(10);
+    let gain = composite_gain(retention);
+    let shares: Vec<f32> = (0..10).map(|age| retention.powi(age) * gain).collect();
+    assert!(shares.windows(2).all(|pair| pair[0] > pair[1]));
+    assert!(shares[0] < 1.0);
+}
@@ -1,7 +1,12 @@
 use dual_spacetime_simulator::object_input::{
-    MIN_WORLD_SCALE, ObjectInput, ObjectInputType, ParticleBasicColor, SATELLITE_ORBIT_SCALE,
-    SOLAR_SYSTEM_SCALE, clamp_world_scale,
+    MASS_SUN, MIN_WORLD_SCALE, MassFunction, ObjectInput, ObjectInputType, ParticleBasicColor,
+    RandomClusterOptions, SATELLITE_ORBIT_SCALE, SOLAR_SYSTEM_SCALE, clamp_world_scale,
+    uniform_cube_potential_energy, virial_velocity_dispersion,
 };
+use dual_spacetime_simulator::simulation::G;
+use glam::DVec3;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 #[test]
 fn clamp_world_scale_rejects_non_positive_values() {
@@ -257,6 +262,7 @@ fn get_scale_clamps_negative_input() {
         radius: 1e10,
         mass_range: (1e29, 1e31),
         velocity_std: 1e6,
+        options: RandomClusterOptions::default(),
     };
     assert_eq!(ic.get_scale(), MIN_WORLD_SCALE);
 }
@@ -310,3 +316,172 @@ fn spiral_disk_initial_speed_scales_with_enclosed_mass() {
     assert!((min_speed - inner_expected).abs() < 0.05 * edge_speed);
     assert!((max_speed - outer_expected).abs() < 0.05 * edge_speed);
 }
+
+fn virial_sphere(options: RandomClusterOptions) -> ObjectInput {
+    ObjectInput::RandomSphere {
+        scale: 1e10,
+        radius: 1e10,
+        mass_range: (1e29, 1e31),
+        velocity_std: 1e6,
+        options,
+    }
+}
+
+#[test]
+fn virial_velocity_dispersion_matches_uniform_sphere_formula() {
+    let sigma = virial_velocity_dispersion(1e30, 1e10);
+    assert!((sigma * sigma - G * 1e30 / 5e10).abs() < 1e-9 * sigma * sigma);
+    assert_eq!(virial_velocity_dispersion(0.0, 1e10), 0.0);
+    assert_eq!(virial_velocity_dispersion(1e30, 0.0), 0.0);
+}
+
+#[test]
+fn virial_sphere_kinetic_energy_balances_uniform_sphere_potential() {
+    let options = RandomClusterOptions {
+        virial_equilibrium: true,
+        ..Default::default()
+    };
+    let particles = virial_sphere(options).generate_particles(4000).particles;
+    let total_mass: f64 = particles.iter().map(|p| p.mass).sum();
+    let kinetic: f64 = particles
+        .iter()
+        .map(|p| 0.5 * p.mass * p.velocity.length_squared())
+        .sum();
+    // Sampled radius in simulation units is 1.0 (radius == scale).
+    let potential = -0.6 * G * total_mass * total_mass;
+    let virial_ratio = 2.0 * kinetic / -potential;
+    assert!((virial_ratio - 1.0).abs() < 0.1, "2K/|W| = {virial_ratio}");
+
+    let momentum: DVec3 = particles.iter().map(|p| p.velocity * p.mass).sum();
+    let rms_speed = (2.0 * kinetic / total_mass).sqrt();
+    assert!(momentum.length() / total_mass < 1e-9 * rms_speed);
+}
+
+#[test]
+fn bulk_velocity_shifts_centre_of_mass_velocity() {
+    let bulk = DVec3::new(2e5, 0.0, -1e5);
+    let options = RandomClusterOptions {
+        virial_equilibrium: true,
+        bulk_velocity: bulk,
+        ..Default::default()
+    };
+    let particles = virial_sphere(options).generate_particles(500).particles;
+    let total_mass: f64 = particles.iter().map(|p| p.mass).sum();
+    let momentum: DVec3 = particles.iter().map(|p| p.velocity * p.mass).sum();
+    let com_velocity = momentum / total_mass;
+    let expected = bulk / 1e10;
+    assert!((com_velocity - expected).length() < 1e-9 * expected.length());
+}
+
+#[test]
+fn spin_adds_solid_body_rotation_with_requested_lambda() {
+    let lambda = 0.1;
+    let cold_spinning_sphere = ObjectInput::RandomSphere {
+        scale: 1e10,
+        radius: 1e10,
+        mass_range: (1e29, 1e31),
+        velocity_std: 1e-3,
+        options: RandomClusterOptions {
+            spin: lambda,
+            spin_axis: DVec3::new(0.0, 0.0, 2.0),
+            ..Default::default()
+        },
+    };
+    let particles = cold_spinning_sphere.generate_particles(2000).particles;
+    let total_mass: f64 = particles.iter().map(|p| p.mass).sum();
+    let center: DVec3 = particles.iter().map(|p| p.position * p.mass).sum::<DVec3>() / total_mass;
+    let angular_momentum: DVec3 = particles
+        .iter()
+        .map(|p| p.mass * (p.position - center).cross(p.velocity))
+        .sum();
+    // Off-axis products of inertia of a random sample tilt J only slightly.
+    assert!(angular_momentum.truncate().length() < 0.1 * angular_momentum.z);
+
+    // The cloud is effectively cold, so E ≈ W before spin-up.
+    let energy = -0.6 * G * total_mass * total_mass;
+    let measured = angular_momentum.z * energy.abs().sqrt() / (G * total_mass.powf(2.5));
+    assert!((measured - lambda).abs() < 1e-6, "lambda = {measured}");
+}
+
+#[test]
+fn virial_cube_kinetic_energy_balances_uniform_cube_potential() {
+    let cube = ObjectInput::RandomCube {
+        scale: 1e10,
+        cube_size: 2e10,
+        mass_range: (1e29, 1e31),
+        velocity_std: 1e6,
+        options: RandomClusterOptions {
+            virial_equilibrium: true,
+            ..Default::default()
+        },
+    };
+    let particles = cube.generate_particles(4000).particles;
+    let total_mass: f64 = particles.iter().map(|p| p.mass).sum();
+    let kinetic: f64 = particles
+        .iter()
+        .map(|p| 0.5 * p.mass * p.velocity.length_squared())
+        .sum();
+    let virial_ratio = 2.0 * kinetic / -uniform_cube_potential_energy(total_mass, 2.0);
+    assert!((virial_ratio - 1.0).abs() < 0.1, "2K/|W| = {virial_ratio}");
+}
+
+fn sample_masses(function: MassFunction, lower: f64, upper: f64, count: usize) -> Vec<f64> {
+    let mut rng = rand::rng();
+    (0..count)
+        .map(|_| function.sample(lower, upper, MASS_SUN, &mut rng))
+        .collect()
+}
+
+#[test]
+fn mass_functions_stay_within_range() {
+    let (lower, upper) = (0.08 * MASS_SUN, 50.0 * MASS_SUN);
+    for function in MassFunction::ALL {
+        for mass in sample_masses(function, lower, upper, 2000) {
+            assert!((lower..=upper).contains(&mass), "{function}: {mass}");
+        }
+    }
+}
+
+#[test]
+fn uniform_mass_function_draws_like_a_flat_range() {
+    let (lower, upper) = (0.1 * MASS_SUN, 10.0 * MASS_SUN);
+    let mut sampled = StdRng::seed_from_u64(11);
+    let mut flat = StdRng::seed_from_u64(11);
+    for _ in 0..100 {
+        let mass = MassFunction::Uniform.sample(lower, upper, MASS_SUN, &mut sampled);
+        assert_eq!(mass, flat.random_range(lower..upper));
+    }
+}
+
+#[test]
+fn salpeter_favours_low_masses_more_than_log_uniform() {
+    let (lower, upper) = (0.1 * MASS_SUN, 10.0 * MASS_SUN);
+    let below_one = |function| {
+        sample_masses(function, lower, upper, 20_000)
+            .into_iter()
+            .filter(|&m| m < MASS_SUN)
+            .count() as f64
+            / 20_000.0
+    };
+    // Log-uniform puts half the draws below the geometric midpoint (1 M☉).
+    assert!((below_one(MassFunction::LogUniform) - 0.5).abs() < 0.03);
+    // Salpeter: (1 - 0.1^-1.35) / (10^-1.35 - 0.1^-1.35) ≈ 0.957.
+    assert!((below_one(MassFunction::Salpeter) - 0.957).abs() < 0.02);
+}
+
+#[test]
+fn kroupa_matches_broken_power_law_fraction_below_break() {
+    let (lower, upper) = (0.1 * MASS_SUN, 10.0 * MASS_SUN);
+    let below_break = sample_masses(MassFunction::Kroupa, lower, upper, 20_000)
+        .into_iter()
+        .filter(|&m| m < 0.5 * MASS_SUN)
+        .count() as f64
+        / 20_000.0;
+    let low = (0.5_f64.powf(-0.3) - 0.1_f64.powf(-0.3)) / -0.3;
+    let high = 0.5 * (10.0_f64.powf(-1.3) - 0.5_f64.powf(-1.3)) / -1.3;
+    let expected = low / (low + high);
+    assert!(
+        (below_break - expected).abs() < 0.02,
+        "{below_break} vs {expected}"
+    );
+}
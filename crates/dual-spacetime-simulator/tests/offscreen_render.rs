@@ -0,0 +1,98 @@
+//! Requires Vulkan loader + compatible GPU (lavapipe is enough).
+
+#![cfg(feature = "gui")]
+
+mod common;
+
+use dual_spacetime_simulator::pipeline::ParticleRenderPipeline;
+use dual_spacetime_simulator::simulation::Particle;
+use dual_spacetime_simulator::still_image::StillSettings;
+use dual_spacetime_simulator::ui_state::{
+    DEFAULT_SCALE_UI, ParticleDisplayMode, SimulationType, particle_visual_scale_factor,
+};
+use glam::DVec3;
+
+const SIZE: u32 = 64;
+
+/// Draws the default camera's view at `SIZE` × `SIZE` without supersampling.
+fn render(
+    v: &common::HeadlessVulkan,
+    pipeline: &mut ParticleRenderPipeline,
+    show_grid: bool,
+    particle_display_mode: ParticleDisplayMode,
+) -> Vec<u8> {
+    let settings = StillSettings {
+        width: SIZE,
+        height: SIZE,
+        supersampling: 1,
+    };
+    common::render_offscreen(v, pipeline, settings, show_grid, particle_display_mode)
+}
+
+fn pixel(rgb: &[u8], x: u32, y: u32) -> [u8; 3] {
+    let i = (y * SIZE + x) as usize * 3;
+    [rgb[i], rgb[i + 1], rgb[i + 2]]
+}
+
+/// Brightest channel value within `radius` pixels of the image center.
+fn center_brightness(rgb: &[u8], radius: u32) -> u8 {
+    let c = SIZE / 2;
+    (c - radius..=c + radius)
+        .flat_map(|y| (c - radius..=c + radius).map(move |x| (x, y)))
+        .flat_map(|(x, y)| pixel(rgb, x, y))
+        .max()
+        .unwrap()
+}
+
+#[test]
+fn empty_scene_without_grid_is_clear_color() {
+    let v = common::try_create_headless_vulkan().expect("vulkan");
+    let mut pipeline = common::headless_pipeline(&v);
+    let rgb = render(&v, &mut pipeline, false, ParticleDisplayMode::Glow);
+    assert_eq!(rgb.len(), (SIZE * SIZE * 3) as usize);
+    assert!(rgb.iter().all(|&c| c == 0));
+}
+
+#[test]
+fn grid_draws_lines() {
+    let v = common::try_create_headless_vulkan().expect("vulkan");
+    let mut pipeline = common::headless_pipeline(&v);
+    let rgb = render(&v, &mut pipeline, true, ParticleDisplayMode::Glow);
+    assert!(rgb.iter().any(|&c| c > 0));
+}
+
+#[test]
+fn particle_at_target_lights_the_center() {
+    let v = common::try_create_headless_vulkan().expect("vulkan");
+    let mut pipeline = common::headless_pipeline(&v);
+    let particles = [Particle::from_kinematics(
+        DVec3::ZERO,
+        DVec3::ZERO,
+        1.0,
+        [1.0; 4],
+    )];
+    pipeline.upload_particles(&particles, SimulationType::Normal);
+    for mode in ParticleDisplayMode::ALL {
+        let rgb = render(&v, &mut pipeline, false, mode);
+        assert!(center_brightness(&rgb, 2) > 64, "{mode:?}");
+        assert_eq!(pixel(&rgb, 0, 0), [0, 0, 0], "{mode:?}");
+        assert_eq!(pixel(&rgb, SIZE - 1, SIZE - 1), [0, 0, 0], "{mode:?}");
+    }
+}
+
+#[test]
+fn sprites_draw_the_nearer_particle_over_the_farther_in_either_buffer_order() {
+    let v = common::try_create_headless_vulkan().expect("vulkan");
+    let mut pipeline = common::headless_pipeline(&v);
+    // Halfway to the default camera, on its line of sight through the target.
+    let toward_camera = DVec3::new(1.6, -1.6, 3.0) * 0.5
+        / f64::from(particle_visual_scale_factor(DEFAULT_SCALE_UI));
+    let near = Particle::from_kinematics(toward_camera, DVec3::ZERO, 1.0, [1.0, 0.0, 0.0, 1.0]);
+    let far = Particle::from_kinematics(DVec3::ZERO, DVec3::ZERO, 1.0, [0.0, 0.0, 1.0, 1.0]);
+    for particles in [[near, far], [far, near]] {
+        pipeline.upload_particles(&particles, SimulationType::Normal);
+        let rgb = render(&v, &mut pipeline, false, ParticleDisplayMode::Sprite);
+        let [red, _, blue] = pixel(&rgb, SIZE / 2, SIZE / 2);
+        assert!(red > 128 && blue < 64, "{:?}", [red, blue]);
+    }
+}
@@ -0,0 +1,90 @@
+use dual_spacetime_simulator::orbit_preview::{
+    ORBIT_PREVIEW_INTERVAL, ORBIT_PREVIEW_MAX_STEPS, PreviewBurn, predict_orbit,
+};
+use dual_spacetime_simulator::ui_state::{PlacementMode, UiState};
+use glam::DVec3;
+
+const MU: f64 = 4.0e14;
+const RADIUS: f64 = 7.0e6;
+
+fn circular_speed(radius: f64) -> f64 {
+    (MU / radius).sqrt()
+}
+
+fn farthest(points: &[DVec3]) -> f64 {
+    points.iter().map(|p| p.length()).fold(0.0, f64::max)
+}
+
+#[test]
+fn circular_orbit_closes_after_one_period() {
+    let position = DVec3::new(RADIUS, 0.0, 0.0);
+    let velocity = DVec3::new(0.0, circular_speed(RADIUS), 0.0);
+    let points = predict_orbit(position, velocity, MU, None);
+    assert!(points.len() > 100 && points.len() < ORBIT_PREVIEW_MAX_STEPS);
+    assert_eq!(points[0], position);
+    assert!((*points.last().unwrap() - position).length() < 5e-3 * RADIUS);
+    assert!(
+        points
+            .iter()
+            .all(|p| (p.length() / RADIUS - 1.0).abs() < 1e-3)
+    );
+}
+
+#[test]
+fn delayed_prograde_burn_raises_the_far_side() {
+    let target = 2.0 * RADIUS;
+    let speed = circular_speed(RADIUS);
+    let transfer_speed = (MU / RADIUS * 2.0 * target / (RADIUS + target)).sqrt();
+    let position = DVec3::new(RADIUS, 0.0, 0.0);
+    let velocity = DVec3::new(0.0, speed, 0.0);
+    let period = std::f64::consts::TAU * RADIUS / speed;
+    let burn = PreviewBurn {
+        delay: 0.25 * period,
+        delta_v: DVec3::X * (transfer_speed - speed),
+    };
+    let points = predict_orbit(position, velocity, MU, Some(burn));
+    assert!((farthest(&points) / target - 1.0).abs() < 0.01);
+    // A quarter orbit later the burn happens at +Y, so apoapsis lands at −Y.
+    let apoapsis = points
+        .iter()
+        .max_by(|a, b| a.length().total_cmp(&b.length()))
+        .unwrap();
+    assert!(apoapsis.y < -0.99 * target);
+}
+
+#[test]
+fn escape_path_stops_well_away() {
+    let position = DVec3::new(RADIUS, 0.0, 0.0);
+    let velocity = DVec3::new(0.0, 1.5 * circular_speed(RADIUS), 0.0);
+    let points = predict_orbit(position, velocity, MU, None);
+    assert!(points.len() < ORBIT_PREVIEW_MAX_STEPS);
+    assert!(farthest(&points) >= 10.0 * RADIUS);
+    assert!(predict_orbit(DVec3::ZERO, velocity, MU, None).is_empty());
+}
+
+#[test]
+fn preview_refreshes_on_interval_and_input_changes() {
+    let mut ui = UiState::default();
+    assert_eq!(ui.orbit_preview_burn(), None);
+    ui.placement_mode = PlacementMode::SatelliteOrbit;
+    ui.maneuver_delta_v = DVec3::X;
+    let burn = ui.orbit_preview_burn();
+    assert!(burn.is_some());
+
+    assert!(ui.orbit_preview_update_due((3, burn)));
+    ui.orbit_preview_inputs = Some((3, burn));
+    ui.orbit_preview_frame = Some(ui.frame);
+    assert!(!ui.orbit_preview_update_due((3, burn)));
+    assert!(ui.orbit_preview_update_due((4, burn)));
+    assert!(ui.orbit_preview_update_due((3, None)));
+    ui.frame += ORBIT_PREVIEW_INTERVAL;
+    assert!(ui.orbit_preview_update_due((3, burn)));
+
+    ui.orbit_preview = vec![DVec3::ZERO; 2];
+    let revision = ui.orbit_preview_revision;
+    ui.clear_orbit_preview();
+    assert!(ui.orbit_preview.is_empty());
+    assert_eq!(ui.orbit_preview_revision, revision + 1);
+    ui.clear_orbit_preview();
+    assert_eq!(ui.orbit_preview_revision, revision + 1);
+}
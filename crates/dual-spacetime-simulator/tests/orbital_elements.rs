@@ -0,0 +1,238 @@
+use dual_spacetime_simulator::object_input::{ObjectInput, ObjectInputType};
+use dual_spacetime_simulator::orbital_elements::{OrbitalElements, osculating_elements};
+use dual_spacetime_simulator::simulation::{AU, G, Particle};
+use glam::DVec3;
+
+const MU_SUN: f64 = G * 1.988475e30;
+
+fn assert_close(actual: f64, expected: f64, rel: f64) {
+    let tolerance = expected.abs().max(1.0) * rel;
+    assert!(
+        (actual - expected).abs() <= tolerance,
+        "actual={actual} expected={expected}"
+    );
+}
+
+#[test]
+fn circular_orbit_has_constant_radius_and_circular_speed() {
+    let elements = OrbitalElements::circular(AU, 1.0);
+    let (position, velocity) = elements.to_state_vectors(MU_SUN).unwrap();
+    assert_close(position.length(), AU, 1e-12);
+    assert_close(velocity.length(), (MU_SUN / AU).sqrt(), 1e-12);
+    assert!(position.dot(velocity).abs() < 1e-3 * AU);
+    assert!(position.z.abs() < 1e-6);
+}
+
+#[test]
+fn periapsis_and_apoapsis_match_eccentricity() {
+    let a = 2.0 * AU;
+    let e = 0.5;
+    let at_periapsis = OrbitalElements {
+        semi_major_axis: a,
+        eccentricity: e,
+        ..OrbitalElements::circular(a, 0.0)
+    };
+    let at_apoapsis = OrbitalElements {
+        true_anomaly: std::f64::consts::PI,
+        ..at_periapsis
+    };
+    let (r_peri, _) = at_periapsis.to_state_vectors(MU_SUN).unwrap();
+    let (r_apo, _) = at_apoapsis.to_state_vectors(MU_SUN).unwrap();
+    assert_close(r_peri.length(), a * (1.0 - e), 1e-12);
+    assert_close(r_apo.length(), a * (1.0 + e), 1e-12);
+    assert_close(at_periapsis.periapsis_distance(), a * (1.0 - e), 1e-12);
+}
+
+#[test]
+fn state_vectors_satisfy_vis_viva() {
+    let elements = OrbitalElements {
+        semi_major_axis: 1.3 * AU,
+        eccentricity: 0.3,
+        inclination: 0.4,
+        ascending_node: 1.1,
+        argument_of_periapsis: 2.2,
+        true_anomaly: 0.7,
+    };
+    let (position, velocity) = elements.to_state_vectors(MU_SUN).unwrap();
+    let expected_speed_sq = MU_SUN * (2.0 / position.length() - 1.0 / elements.semi_major_axis);
+    assert_close(velocity.length_squared(), expected_speed_sq, 1e-10);
+}
+
+#[test]
+fn angular_momentum_direction_follows_inclination_and_node() {
+    let inclination = 0.5;
+    let node = 0.8;
+    let elements = OrbitalElements {
+        inclination,
+        ascending_node: node,
+        ..OrbitalElements::circular(AU, 0.3)
+    };
+    let (position, velocity) = elements.to_state_vectors(MU_SUN).unwrap();
+    let h = position.cross(velocity).normalize();
+    let expected = DVec3::new(
+        node.sin() * inclination.sin(),
+        -node.cos() * inclination.sin(),
+        inclination.cos(),
+    );
+    assert!((h - expected).length() < 1e-12, "h={h:?}");
+}
+
+#[test]
+fn hyperbolic_orbit_exceeds_escape_speed() {
+    let elements = OrbitalElements {
+        semi_major_axis: -AU,
+        eccentricity: 1.5,
+        ..OrbitalElements::circular(AU, 0.2)
+    };
+    let (position, velocity) = elements.to_state_vectors(MU_SUN).unwrap();
+    let escape_speed_sq = 2.0 * MU_SUN / position.length();
+    assert!(velocity.length_squared() > escape_speed_sq);
+}
+
+#[test]
+fn state_vectors_round_trip_to_elements() {
+    let elements = OrbitalElements {
+        semi_major_axis: 1.3 * AU,
+        eccentricity: 0.3,
+        inclination: 0.4,
+        ascending_node: 1.1,
+        argument_of_periapsis: 2.2,
+        true_anomaly: 0.7,
+    };
+    let hyperbolic = OrbitalElements {
+        semi_major_axis: -AU,
+        eccentricity: 1.5,
+        true_anomaly: 5.9,
+        ..elements
+    };
+    for elements in [elements, hyperbolic] {
+        let (position, velocity) = elements.to_state_vectors(MU_SUN).unwrap();
+        let back = OrbitalElements::from_state_vectors(position, velocity, MU_SUN).unwrap();
+        assert_close(back.semi_major_axis, elements.semi_major_axis, 1e-9);
+        assert_close(back.eccentricity, elements.eccentricity, 1e-9);
+        for (actual, expected) in [
+            (back.inclination, elements.inclination),
+            (back.ascending_node, elements.ascending_node),
+            (back.argument_of_periapsis, elements.argument_of_periapsis),
+            (back.true_anomaly, elements.true_anomaly),
+        ] {
+            assert!((actual - expected).abs() < 1e-9, "{back:?}");
+        }
+    }
+}
+
+#[test]
+fn circular_equatorial_orbit_measures_angles_from_x() {
+    let elements = OrbitalElements::circular(AU, 1.0);
+    let (position, velocity) = elements.to_state_vectors(MU_SUN).unwrap();
+    let back = OrbitalElements::from_state_vectors(position, velocity, MU_SUN).unwrap();
+    assert!(back.eccentricity < 1e-9);
+    assert_eq!(back.ascending_node, 0.0);
+    assert!((back.argument_of_periapsis + back.true_anomaly - 1.0).abs() < 1e-6);
+    assert_close(back.period(MU_SUN).unwrap(), 3.15581e7, 1e-4);
+    assert!(OrbitalElements::from_state_vectors(position, position, MU_SUN).is_none());
+}
+
+#[test]
+fn osculating_elements_use_relative_state_in_si_units() {
+    let scale: f64 = 1e9;
+    let (position, velocity) = OrbitalElements {
+        semi_major_axis: 2.0 * AU,
+        eccentricity: 0.5,
+        ..OrbitalElements::circular(AU, 0.0)
+    }
+    .to_state_vectors(MU_SUN)
+    .unwrap();
+    let drift = DVec3::new(3.0, -1.0, 2.0);
+    let sun = Particle::from_kinematics(drift, drift, 1.988475e30 / scale.powi(3), [1.0; 4]);
+    let planet = Particle::from_kinematics(
+        drift + position / scale,
+        drift + velocity / scale,
+        0.0,
+        [1.0; 4],
+    );
+    let elements = osculating_elements(&planet, &sun, scale).unwrap();
+    assert_close(elements.periapsis_distance(), AU, 1e-9);
+    assert_close(elements.apoapsis_distance().unwrap(), 3.0 * AU, 1e-9);
+}
+
+#[test]
+fn invalid_element_sets_are_rejected() {
+    let parabolic = OrbitalElements {
+        eccentricity: 1.0,
+        ..OrbitalElements::circular(AU, 0.0)
+    };
+    let bound_with_negative_axis = OrbitalElements::circular(-AU, 0.0);
+    let hyperbolic_beyond_asymptote = OrbitalElements {
+        semi_major_axis: -AU,
+        eccentricity: 2.0,
+        ..OrbitalElements::circular(AU, 3.0)
+    };
+    for elements in [
+        parabolic,
+        bound_with_negative_axis,
+        hyperbolic_beyond_asymptote,
+    ] {
+        assert!(!elements.is_valid(), "{elements:?}");
+        assert!(elements.to_state_vectors(MU_SUN).is_none());
+    }
+    assert!(OrbitalElements::default().to_state_vectors(0.0).is_none());
+}
+
+#[test]
+fn keplerian_system_generates_primary_plus_valid_bodies() {
+    let ic = ObjectInputType::KeplerianSystem.to_object_input(1.5e11);
+    let ObjectInput::KeplerianSystem { mut bodies, .. } = ic.clone() else {
+        panic!("expected KeplerianSystem");
+    };
+    let sim = ic.generate_particles(999);
+    assert_eq!(sim.particles.len(), 1 + bodies.len());
+    assert_eq!(sim.particles[0].position, DVec3::ZERO);
+
+    bodies[0].elements.eccentricity = 1.0;
+    let skipped = ObjectInput::KeplerianSystem {
+        scale: 1.5e11,
+        primary_mass: 1.988475e30,
+        bodies,
+    };
+    assert_eq!(skipped.generate_particles(1).particles.len(), 2);
+}
+
+#[test]
+fn keplerian_system_positions_are_in_simulation_units() {
+    let scale = 1.5e11;
+    let ic = ObjectInputType::KeplerianSystem.to_object_input(scale);
+    let ObjectInput::KeplerianSystem { bodies, .. } = &ic else {
+        panic!("expected KeplerianSystem");
+    };
+    let sim = ic.generate_particles(1);
+    let first = bodies[0].elements;
+    let expected = first.semi_major_axis * (1.0 - first.eccentricity) / scale;
+    assert_close(sim.particles[1].position.length(), expected, 1e-9);
+}
+
+#[test]
+fn keplerian_system_starts_at_rest_in_the_centre_of_mass_frame() {
+    let scale = 1.5e11;
+    let ic = ObjectInputType::KeplerianSystem.to_object_input(scale);
+    let ObjectInput::KeplerianSystem {
+        primary_mass,
+        bodies,
+        ..
+    } = &ic
+    else {
+        panic!("expected KeplerianSystem");
+    };
+    let sim = ic.generate_particles(1);
+    let momentum: DVec3 = sim.particles.iter().map(|p| p.velocity * p.mass).sum();
+    let primary = &sim.particles[0];
+    assert!(momentum.length() < 1e-12 * primary.mass * primary.velocity.length());
+    assert!(primary.velocity.length() > 0.0);
+
+    let mu = G * (primary_mass + bodies[0].mass);
+    let (_, relative_velocity) = bodies[0].elements.to_state_vectors(mu).unwrap();
+    let body_velocity = sim.particles[1].velocity - primary.velocity;
+    assert!(
+        (body_velocity * scale - relative_velocity).length() < 1e-9 * relative_velocity.length()
+    );
+}
@@ -0,0 +1,51 @@
+use dual_spacetime_simulator::palette::{
+    BUILTIN_PALETTES, DEFAULT_GROUP_PALETTE, DEFAULT_PARTICLE_PALETTE, PaletteSettings,
+    builtin_palette, palette_color,
+};
+
+#[test]
+fn colors_wrap_around_and_are_opaque() {
+    let colors = [[0.1, 0.2, 0.3], [0.4, 0.5, 0.6]];
+    assert_eq!(palette_color(&colors, 0), [0.1, 0.2, 0.3, 1.0]);
+    assert_eq!(palette_color(&colors, 3), [0.4, 0.5, 0.6, 1.0]);
+    assert_eq!(palette_color(&[], 2), [1.0; 4]);
+    for (name, colors) in BUILTIN_PALETTES {
+        assert!(!colors.is_empty(), "{name}");
+    }
+}
+
+#[test]
+fn custom_palettes_copy_and_replace_built_ins() {
+    let mut palettes = PaletteSettings::default();
+    let copy = palettes.duplicate("Okabe-Ito");
+    assert_eq!(copy, "Custom 1");
+    assert!(palettes.is_custom(&copy));
+    assert_eq!(
+        palettes.colors(&copy),
+        builtin_palette("Okabe-Ito").unwrap()
+    );
+    palettes.custom_mut(&copy).unwrap().colors = vec![[0.0, 1.0, 0.0]];
+    assert_eq!(palettes.duplicate(&copy), "Custom 2");
+    assert_eq!(palettes.colors("Custom 2"), [[0.0, 1.0, 0.0]]);
+    assert_eq!(palettes.names().len(), BUILTIN_PALETTES.len() + 2);
+}
+
+#[test]
+fn removing_a_palette_restores_default_assignments() {
+    let mut palettes = PaletteSettings::default();
+    let copy = palettes.duplicate("Tol Bright");
+    palettes.particle_palette = copy.clone();
+    palettes.group_palette = copy.clone();
+    palettes.remove(&copy);
+    assert!(!palettes.is_custom(&copy));
+    assert_eq!(palettes.particle_palette, DEFAULT_PARTICLE_PALETTE);
+    assert_eq!(palettes.group_palette, DEFAULT_GROUP_PALETTE);
+    // Built-ins stay, and a missing name falls back to the default particle palette.
+    palettes.particle_palette = "Tol Vibrant".to_string();
+    palettes.remove("Tol Vibrant");
+    assert_eq!(palettes.particle_palette, "Tol Vibrant");
+    assert_eq!(
+        palettes.colors(&copy),
+        builtin_palette(DEFAULT_PARTICLE_PALETTE).unwrap()
+    );
+}
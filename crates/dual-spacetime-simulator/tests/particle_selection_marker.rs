@@ -1,3 +1,5 @@
+#![cfg(feature = "gui")]
+
 use dual_spacetime_simulator::particle_selection_marker::{
     compute_bracket_half_size, BRACKET_RADIUS_RATIO, MIN_HALF_SIZE_PX,
 };
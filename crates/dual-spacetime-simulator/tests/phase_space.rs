@@ -0,0 +1,75 @@
+use dual_spacetime_simulator::phase_space::{
+    PhaseSpacePoints, PhaseSpaceQuantity, sample_phase_space,
+};
+use dual_spacetime_simulator::simulation::Particle;
+use glam::DVec3;
+
+fn particle(position: DVec3, velocity: DVec3, mass: f64) -> Particle {
+    Particle::from_kinematics(position, velocity, mass, [1.0, 1.0, 1.0, 1.0])
+}
+
+#[test]
+fn radial_and_tangential_velocity_split_the_speed() {
+    let position = DVec3::new(2.0, 0.0, 0.0);
+    let velocity = DVec3::new(-3.0, 4.0, 0.0);
+    assert_eq!(PhaseSpaceQuantity::Radius.evaluate(position, velocity), 2.0);
+    assert_eq!(
+        PhaseSpaceQuantity::RadialVelocity.evaluate(position, velocity),
+        -3.0
+    );
+    assert_eq!(
+        PhaseSpaceQuantity::TangentialVelocity.evaluate(position, velocity),
+        4.0
+    );
+    assert_eq!(PhaseSpaceQuantity::Speed.evaluate(position, velocity), 5.0);
+    assert_eq!(
+        PhaseSpaceQuantity::RadialVelocity.evaluate(DVec3::ZERO, velocity),
+        0.0
+    );
+}
+
+#[test]
+fn sample_is_relative_to_center_of_mass_and_mean_velocity() {
+    let drift = DVec3::new(10.0, 0.0, 0.0);
+    let particles = vec![
+        particle(DVec3::new(4.0, 0.0, 0.0), drift + DVec3::X, 1.0),
+        particle(DVec3::new(6.0, 0.0, 0.0), drift - DVec3::X, 1.0),
+    ];
+    let sample = sample_phase_space(
+        &particles,
+        PhaseSpaceQuantity::X,
+        PhaseSpaceQuantity::Vx,
+        100,
+        None,
+    );
+    assert_eq!(sample.indices, vec![0, 1]);
+    assert_eq!(sample.points, vec![[-1.0, 1.0], [1.0, -1.0]]);
+}
+
+#[test]
+fn stride_caps_points_but_keeps_the_selected_particle() {
+    let mut particles: Vec<Particle> = (0..10)
+        .map(|i| particle(DVec3::new(i as f64, 0.0, 0.0), DVec3::ZERO, 1.0))
+        .collect();
+    particles[4].color[3] = 0.0;
+    let sample = sample_phase_space(
+        &particles,
+        PhaseSpaceQuantity::Radius,
+        PhaseSpaceQuantity::Speed,
+        3,
+        Some(8),
+    );
+    // Nine live particles with stride 3 keep particles 0, 3, 7 plus the selected 8.
+    assert_eq!(sample.indices, vec![0, 3, 7, 8]);
+    assert_eq!(sample.points.len(), sample.indices.len());
+}
+
+#[test]
+fn bounds_cover_every_point() {
+    assert_eq!(PhaseSpacePoints::default().bounds(), None);
+    let sample = PhaseSpacePoints {
+        points: vec![[1.0, -2.0], [-3.0, 5.0], [0.5, 0.0]],
+        indices: vec![0, 1, 2],
+    };
+    assert_eq!(sample.bounds(), Some([[-3.0, 1.0], [-2.0, 5.0]]));
+}
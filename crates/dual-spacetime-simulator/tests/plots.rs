@@ -0,0 +1,84 @@
+use dual_spacetime_simulator::metrics::{BUILT_IN_METRICS, MetricsStore, TOTAL_ENERGY};
+use dual_spacetime_simulator::plots::{
+    AxisScale, PlotChart, metric_label, plot_bounds, render_plot_rgb,
+};
+
+fn store(samples: &[(f64, f64)]) -> MetricsStore {
+    let mut store = MetricsStore::default();
+    for &(t, v) in samples {
+        store.record("x", t, v);
+    }
+    store
+}
+
+fn pixel(rgb: &[u8], width: u32, x: u32, y: u32) -> [u8; 3] {
+    let i = ((y * width + x) * 3) as usize;
+    [rgb[i], rgb[i + 1], rgb[i + 2]]
+}
+
+#[test]
+fn log_axes_map_positive_values_and_back() {
+    assert_eq!(AxisScale::Linear.to_axis(-2.0), Some(-2.0));
+    assert_eq!(AxisScale::Log.to_axis(100.0), Some(2.0));
+    assert_eq!(AxisScale::Log.to_axis(0.0), None);
+    assert_eq!(AxisScale::Log.to_axis(-1.0), None);
+    assert!((AxisScale::Log.from_axis(-3.0) - 1e-3).abs() < 1e-15);
+    assert_eq!(AxisScale::Linear.from_axis(4.0), 4.0);
+}
+
+#[test]
+fn axis_points_skip_samples_a_log_axis_cannot_show() {
+    let store = store(&[(0.0, 10.0), (1.0, -1.0), (10.0, 1000.0)]);
+    let series = store.series("x").unwrap();
+    let mut chart = PlotChart::new("x");
+    assert_eq!(chart.axis_points(series).len(), 3);
+    chart.value_scale = AxisScale::Log;
+    assert_eq!(chart.axis_points(series), [[0.0, 1.0], [10.0, 3.0]]);
+    chart.time_scale = AxisScale::Log;
+    assert_eq!(chart.axis_points(series), [[1.0, 3.0]]);
+}
+
+#[test]
+fn bounds_widen_a_flat_series() {
+    assert_eq!(plot_bounds(&[]), None);
+    assert_eq!(
+        plot_bounds(&[[0.0, 2.0], [4.0, -1.0]]),
+        Some([[0.0, 4.0], [-1.0, 2.0]])
+    );
+    assert_eq!(plot_bounds(&[[1.0, 5.0]]), Some([[0.5, 1.5], [4.5, 5.5]]));
+}
+
+#[test]
+fn chart_csv_lists_the_unscaled_samples() {
+    let store = store(&[(0.0, -1.0), (0.5, 2.0)]);
+    let mut chart = PlotChart::new("x");
+    chart.value_scale = AxisScale::Log;
+    assert_eq!(
+        chart.to_csv(store.series("x").unwrap()),
+        "t,x\n0e0,-1e0\n5e-1,2e0\n"
+    );
+}
+
+#[test]
+fn rendered_chart_runs_corner_to_corner() {
+    let (width, height) = (100, 60);
+    let rgb = render_plot_rgb(&[[0.0, 0.0], [1.0, 1.0]], width, height);
+    assert_eq!(rgb.len(), (width * height * 3) as usize);
+    let line = pixel(&rgb, width, 16, 43);
+    assert_eq!(pixel(&rgb, width, 83, 16), line);
+    assert_ne!(pixel(&rgb, width, 83, 43), line);
+    assert_ne!(pixel(&rgb, width, 0, 0), pixel(&rgb, width, 5, 5));
+    let empty = render_plot_rgb(&[], width, height);
+    assert_eq!(pixel(&empty, width, 50, 30), pixel(&rgb, width, 5, 5));
+}
+
+#[test]
+fn built_in_metrics_have_labels() {
+    assert!(
+        BUILT_IN_METRICS
+            .iter()
+            .all(|&name| metric_label(name) != name)
+    );
+    assert_eq!(metric_label(TOTAL_ENERGY), "Total Energy");
+    assert_eq!(metric_label("virial_ratio"), "virial_ratio");
+}
@@ -0,0 +1,68 @@
+use dual_spacetime_simulator::point_budget::{
+    area_compensation, is_sampled, sample_hash, sampled_fraction, sampling_phase,
+};
+
+const COUNT: u32 = 200_000;
+
+#[test]
+fn the_fraction_only_drops_below_one_past_the_budget() {
+    assert_eq!(sampled_fraction(1_000_000, 0), 1.0);
+    assert_eq!(sampled_fraction(1_000, 1_000), 1.0);
+    assert_eq!(sampled_fraction(500, 1_000), 1.0);
+    assert_eq!(sampled_fraction(4_000, 1_000), 0.25);
+    assert_eq!(area_compensation(0.25), 2.0);
+}
+
+#[test]
+fn hashes_and_phases_stay_in_24_bits() {
+    assert!((0..COUNT).all(|index| sample_hash(index) < 1 << 24));
+    assert!((0..1000).all(|frame| sampling_phase(frame) < 1 << 24));
+    assert!(sampling_phase(u32::MAX) < 1 << 24);
+}
+
+#[test]
+fn each_frame_draws_about_the_budget() {
+    let budget = 20_000;
+    let fraction = sampled_fraction(COUNT, budget);
+    for frame in 0..8 {
+        let phase = sampling_phase(frame);
+        let drawn = (0..COUNT)
+            .filter(|&index| is_sampled(index, fraction, phase))
+            .count() as f64;
+        assert!(
+            (drawn / f64::from(budget) - 1.0).abs() < 0.03,
+            "{frame}: {drawn}"
+        );
+    }
+}
+
+#[test]
+fn compensated_brightness_averages_to_the_full_draw_over_frames() {
+    let fraction = 0.1;
+    let frames = 400;
+    let particles = 64;
+    for index in 0..particles {
+        let kept = (0..frames)
+            .filter(|&frame| is_sampled(index, fraction, sampling_phase(frame)))
+            .count();
+        // Each particle shows in about a tenth of the frames at ten times the brightness.
+        let mean = kept as f32 / fraction / frames as f32;
+        assert!((mean - 1.0).abs() < 0.1, "particle {index}: {mean}");
+    }
+}
+
+#[test]
+fn successive_frames_draw_different_subsets() {
+    let fraction = 0.5;
+    let (first, second) = (sampling_phase(0), sampling_phase(1));
+    let both = (0..COUNT)
+        .filter(|&index| is_sampled(index, fraction, first) && is_sampled(index, fraction, second))
+        .count() as f64;
+    // Independent halves would share a quarter; identical ones would share half.
+    assert!(both / f64::from(COUNT) < 0.4, "{both}");
+}
+
+#[test]
+fn everything_is_drawn_at_full_fraction() {
+    assert!((0..COUNT).all(|index| is_sampled(index, 1.0, sampling_phase(index))));
+}
@@ -0,0 +1,62 @@
+use dual_spacetime_simulator::power_spectrum::{fft_in_place, power_spectrum};
+use dual_spacetime_simulator::simulation::Particle;
+use glam::DVec3;
+use std::f64::consts::TAU;
+
+fn particle(position: DVec3) -> Particle {
+    Particle::from_kinematics(position, DVec3::ZERO, 1.0, [1.0, 1.0, 1.0, 1.0])
+}
+
+#[test]
+fn fft_of_impulse_is_flat() {
+    let mut data = vec![[0.0; 2]; 8];
+    data[0] = [1.0, 0.0];
+    fft_in_place(&mut data);
+    assert!(data.iter().all(|&[re, im]| re == 1.0 && im == 0.0));
+}
+
+#[test]
+fn fft_of_cosine_peaks_at_its_frequency() {
+    let mut data: Vec<[f64; 2]> = (0..8)
+        .map(|i| [(TAU * 2.0 * i as f64 / 8.0).cos(), 0.0])
+        .collect();
+    fft_in_place(&mut data);
+    for (k, [re, im]) in data.into_iter().enumerate() {
+        let expected = if k == 2 || k == 6 { 4.0 } else { 0.0 };
+        assert!((re - expected).abs() < 1e-12 && im.abs() < 1e-12, "bin {k}");
+    }
+}
+
+#[test]
+fn spectrum_bins_ascend_to_nyquist_and_export_as_csv() {
+    let particles: Vec<Particle> = (0..64)
+        .map(|i| {
+            let t = i as f64;
+            particle(DVec3::new(t.sin() * 3.0, (t * 1.7).cos(), t * 0.05))
+        })
+        .collect();
+    let spectrum = power_spectrum(&particles, 16).unwrap();
+    assert_eq!(spectrum.grid, 16);
+    assert_eq!(spectrum.bins.len(), 8);
+    assert!(spectrum.bins.windows(2).all(|w| w[0].k < w[1].k));
+    assert!(
+        spectrum
+            .bins
+            .iter()
+            .all(|bin| bin.power.is_finite() && bin.power >= 0.0 && bin.modes > 0)
+    );
+    let csv = spectrum.to_csv();
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("k,power,modes"));
+    assert_eq!(lines.count(), spectrum.bins.len());
+}
+
+#[test]
+fn degenerate_inputs_yield_none() {
+    let particles = vec![particle(DVec3::ZERO), particle(DVec3::X)];
+    assert!(power_spectrum(&[], 16).is_none());
+    assert!(power_spectrum(&[particle(DVec3::ONE)], 16).is_none());
+    assert!(power_spectrum(&particles, 12).is_none());
+    assert!(power_spectrum(&particles, 2).is_none());
+    assert!(power_spectrum(&particles, 16).is_some());
+}
@@ -0,0 +1,102 @@
+use dual_spacetime_simulator::region_selection::{
+    DYE_PALETTE, RegionAction, RegionShape, SelectionRegion, apply_region_action, dye_color,
+    region_statistics,
+};
+use dual_spacetime_simulator::simulation::Particle;
+use glam::DVec3;
+
+fn particle(position: DVec3, velocity: DVec3, mass: f64) -> Particle {
+    Particle::from_kinematics(position, velocity, mass, [1.0, 1.0, 1.0, 1.0])
+}
+
+fn sample_particles() -> Vec<Particle> {
+    vec![
+        particle(DVec3::new(0.5, 0.0, 0.0), DVec3::new(1.0, 0.0, 0.0), 1.0),
+        particle(DVec3::new(-0.5, 0.0, 0.0), DVec3::new(-1.0, 0.0, 0.0), 1.0),
+        particle(DVec3::new(0.0, 0.9, 0.0), DVec3::new(0.0, 0.0, 3.0), 2.0),
+        particle(DVec3::new(5.0, 0.0, 0.0), DVec3::new(9.0, 9.0, 9.0), 7.0),
+    ]
+}
+
+#[test]
+fn sphere_and_box_membership() {
+    let mut region = SelectionRegion {
+        shape: RegionShape::Sphere,
+        center: DVec3::ZERO,
+        radius: 1.0,
+        half_size: DVec3::new(0.6, 0.1, 0.1),
+    };
+    let particles = sample_particles();
+    assert_eq!(region.member_indices(&particles), vec![0, 1, 2]);
+    region.shape = RegionShape::Box;
+    assert_eq!(region.member_indices(&particles), vec![0, 1]);
+}
+
+#[test]
+fn statistics_are_mass_weighted() {
+    let stats = region_statistics(&sample_particles(), &SelectionRegion::default());
+    assert_eq!(stats.count, 3);
+    assert!((stats.total_mass - 4.0).abs() < 1e-12);
+    assert!((stats.mean_velocity - DVec3::new(0.0, 0.0, 1.5)).length() < 1e-12);
+    // Σ m |v - v̄|² = 1·3.25 + 1·3.25 + 2·2.25 = 11; σ² = 11 / (3·4).
+    assert!((stats.velocity_dispersion - (11.0f64 / 12.0).sqrt()).abs() < 1e-12);
+}
+
+#[test]
+fn empty_region_reports_zero() {
+    let region = SelectionRegion {
+        center: DVec3::splat(100.0),
+        ..SelectionRegion::default()
+    };
+    let stats = region_statistics(&sample_particles(), &region);
+    assert_eq!(stats.count, 0);
+    assert_eq!(stats.total_mass, 0.0);
+}
+
+#[test]
+fn recolor_and_isolate_only_touch_members() {
+    let region = SelectionRegion::default();
+    let red = [1.0, 0.0, 0.0, 1.0];
+    let mut particles = sample_particles();
+    assert!(apply_region_action(
+        &mut particles,
+        &region,
+        RegionAction::Recolor(red)
+    ));
+    assert_eq!(particles[2].color, red);
+    assert_eq!(particles[3].color, [1.0, 1.0, 1.0, 1.0]);
+    assert!(apply_region_action(
+        &mut particles,
+        &region,
+        RegionAction::Isolate
+    ));
+    assert_eq!(particles.len(), 3);
+    assert!(!apply_region_action(
+        &mut particles,
+        &region,
+        RegionAction::Isolate
+    ));
+    assert!(!apply_region_action(
+        &mut particles,
+        &region,
+        RegionAction::Measure
+    ));
+}
+
+#[test]
+fn dye_tags_members_with_cycling_palette() {
+    assert_eq!(dye_color(0), DYE_PALETTE[0]);
+    assert_eq!(dye_color(DYE_PALETTE.len() + 1), DYE_PALETTE[1]);
+    let mut particles = sample_particles();
+    let color = dye_color(2);
+    let region = SelectionRegion::default();
+    assert!(apply_region_action(
+        &mut particles,
+        &region,
+        RegionAction::Dye(color)
+    ));
+    let dyed: Vec<usize> = (0..particles.len())
+        .filter(|&i| particles[i].color == color)
+        .collect();
+    assert_eq!(dyed, vec![0, 1, 2]);
+}
@@ -0,0 +1,111 @@
+use dual_spacetime_simulator::relativistic_view::{
+    MAX_BEAMING_INTENSITY, ObserverView, VELOCITY_SPACE_RADIUS, VIEW_FLAG_RAPIDITY_SPACE,
+    VIEW_FLAG_VELOCITY_SPACE, ViewKinematics, beaming_intensity, coordinate_velocity,
+    display_space_position, doppler_factor, observe, rapidity, retarded_position,
+};
+use dual_spacetime_simulator::simulation::{LIGHT_SPEED, Particle, SimulationManager};
+use dual_spacetime_simulator::ui_state::{DisplaySpace, SimulationType};
+use glam::DVec3;
+
+const C: f64 = 10.0;
+
+#[test]
+fn kinematics_follow_the_special_engines() {
+    assert_eq!(
+        ViewKinematics::for_simulation(SimulationType::SpeedOfLightLimit),
+        ViewKinematics::Momentum
+    );
+    assert_eq!(
+        ViewKinematics::for_simulation(SimulationType::LorentzTransformation),
+        ViewKinematics::Rapidity
+    );
+    assert_eq!(
+        ViewKinematics::for_simulation(SimulationType::Normal),
+        ViewKinematics::Off
+    );
+}
+
+#[test]
+fn retarded_position_lies_on_the_past_light_cone() {
+    let observer = DVec3::new(0.0, 0.0, 5.0);
+    let (still, tau) = retarded_position(DVec3::X * 30.0, DVec3::ZERO, observer, C);
+    assert_eq!(still, DVec3::X * 30.0);
+    assert!((tau * C - still.distance(observer)).abs() < 1e-12);
+
+    let position = DVec3::new(40.0, -10.0, 0.0);
+    let velocity = DVec3::new(-6.0, 3.0, 1.0);
+    let (apparent, tau) = retarded_position(position, velocity, observer, C);
+    assert!(tau > 0.0);
+    assert!((apparent - (position - velocity * tau)).length() < 1e-12);
+    assert!((apparent.distance(observer) - C * tau).abs() < 1e-9);
+}
+
+#[test]
+fn doppler_factor_brightens_approach_and_dims_recession() {
+    let velocity = DVec3::X * 0.6 * C;
+    let approaching = doppler_factor(velocity, DVec3::ZERO, DVec3::X * 100.0, C);
+    let receding = doppler_factor(velocity, DVec3::ZERO, -DVec3::X * 100.0, C);
+    assert!((approaching - 2.0).abs() < 1e-12);
+    assert!((receding - 0.5).abs() < 1e-12);
+    let transverse = doppler_factor(velocity, DVec3::ZERO, DVec3::Y * 100.0, C);
+    assert!((transverse - 0.8).abs() < 1e-12);
+    assert_eq!(beaming_intensity(approaching), MAX_BEAMING_INTENSITY);
+    assert!((beaming_intensity(receding) - 0.0625).abs() < 1e-12);
+}
+
+#[test]
+fn observe_recovers_velocity_from_the_engine_representation() {
+    let velocity = DVec3::new(0.5, 0.2, 0.0) * C;
+    let particle = Particle::from_kinematics(DVec3::X * 20.0, velocity, 3.0, [1.0; 4]);
+    let scale = LIGHT_SPEED / C;
+    let momentum = SimulationManager::convert_to_momentum(vec![particle], scale, LIGHT_SPEED)[0];
+    let recovered = coordinate_velocity(&momentum, ViewKinematics::Momentum, C);
+    assert!((recovered - velocity).length() < C * 1e-12);
+
+    let observer = DVec3::Z * 15.0;
+    let off = observe(&particle, ViewKinematics::Off, observer, C);
+    assert_eq!(off.position, particle.position);
+    assert_eq!(off.intensity, 1.0);
+    let seen = observe(&momentum, ViewKinematics::Momentum, observer, C);
+    let (expected, _) = retarded_position(particle.position, recovered, observer, C);
+    assert_eq!(seen.position, expected);
+    assert!(seen.intensity > 0.0);
+}
+
+#[test]
+fn velocity_and_rapidity_displays_plot_the_velocity_vector() {
+    let velocity = DVec3::new(0.0, 0.0, -0.6 * C);
+    assert_eq!(
+        display_space_position(DisplaySpace::Position, velocity, C),
+        None
+    );
+    let plotted = display_space_position(DisplaySpace::Velocity, velocity, C).unwrap();
+    assert!((plotted - DVec3::Z * -0.6 * VELOCITY_SPACE_RADIUS).length() < 1e-12);
+    let eta = rapidity(velocity, C);
+    assert!((eta.length() - 0.6f64.atanh()).abs() < 1e-12);
+    assert!(eta.z < 0.0);
+    assert!(rapidity(DVec3::X * 2.0 * C, C).length().is_finite());
+    assert_eq!(
+        display_space_position(DisplaySpace::Rapidity, velocity, C),
+        Some(eta * VELOCITY_SPACE_RADIUS)
+    );
+}
+
+#[test]
+fn display_space_works_without_a_light_speed_limit() {
+    let particle = Particle::from_kinematics(DVec3::ONE, DVec3::X * 2.0, 1.0, [1.0; 4]);
+    let mut view = ObserverView {
+        kinematics: ViewKinematics::Off,
+        aberration: true,
+        reference_frame: None,
+        display_space: DisplaySpace::Velocity,
+        light_speed: C,
+    };
+    assert_eq!(view.shader_flags(), VIEW_FLAG_VELOCITY_SPACE);
+    let seen = view.observe(&particle, DVec3::ZERO);
+    assert!((seen.position - DVec3::X * 0.2 * VELOCITY_SPACE_RADIUS).length() < 1e-12);
+    assert_eq!(seen.intensity, 1.0);
+    view.kinematics = ViewKinematics::Momentum;
+    view.display_space = DisplaySpace::Rapidity;
+    assert_eq!(view.shader_flags(), VIEW_FLAG_RAPIDITY_SPACE);
+}
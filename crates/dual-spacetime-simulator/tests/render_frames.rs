@@ -0,0 +1,62 @@
+#![cfg(feature = "gui")]
+
+use dual_spacetime_simulator::render_frames::{
+    RenderFramesOptions, frame_path, parse_render_frames_args,
+};
+use dual_spacetime_simulator::ui_state::ParticleDisplayMode;
+use std::path::{Path, PathBuf};
+
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(str::to_string).collect()
+}
+
+#[test]
+fn without_the_flag_the_window_starts() {
+    assert_eq!(parse_render_frames_args(&[]), Ok(None));
+    assert_eq!(parse_render_frames_args(&args("--grid")), Ok(None));
+}
+
+#[test]
+fn options_override_defaults() {
+    let options = parse_render_frames_args(&args(
+        "--frames 10 --render-frames orbit.zip --out out --steps-per-frame 4 \
+         --time-per-frame 0.5 --width 640 --height 480 --supersampling 2 --display sphere --grid",
+    ))
+    .unwrap()
+    .unwrap();
+    let mut expected = RenderFramesOptions::new(PathBuf::from("orbit.zip"));
+    expected.output_dir = PathBuf::from("out");
+    expected.frames = 10;
+    expected.steps_per_frame = 4;
+    expected.time_per_frame = 0.5;
+    expected.still.width = 640;
+    expected.still.height = 480;
+    expected.still.supersampling = 2;
+    expected.particle_display_mode = ParticleDisplayMode::Sphere;
+    expected.show_grid = true;
+    assert_eq!(options, expected);
+}
+
+#[test]
+fn invalid_arguments_are_reported() {
+    for line in [
+        "--render-frames",
+        "--render-frames --grid",
+        "--render-frames a.zip --frames",
+        "--render-frames a.zip --frames ten",
+        "--render-frames a.zip --frames 0",
+        "--render-frames a.zip --supersampling 3",
+        "--render-frames a.zip --display heatmap",
+        "--render-frames a.zip --unknown",
+    ] {
+        assert!(parse_render_frames_args(&args(line)).is_err(), "{line}");
+    }
+}
+
+#[test]
+fn frames_are_numbered_in_order() {
+    assert_eq!(
+        frame_path(Path::new("out"), 7),
+        Path::new("out").join("frame_00007.png")
+    );
+}
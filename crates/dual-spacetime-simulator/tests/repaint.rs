@@ -0,0 +1,53 @@
+use dual_spacetime_simulator::repaint::{
+    IDLE_REPAINT_INTERVAL, RepaintSchedule, Throttled, schedule_repaint,
+};
+use std::time::{Duration, Instant};
+
+#[test]
+fn changing_scene_and_first_frame_redraw_now() {
+    let now = Instant::now();
+    assert_eq!(
+        schedule_repaint(true, None, Some(now), now),
+        RepaintSchedule::Now
+    );
+    assert_eq!(
+        schedule_repaint(false, None, None, now),
+        RepaintSchedule::Now
+    );
+}
+
+#[test]
+fn idle_window_waits_for_the_earlier_deadline() {
+    let now = Instant::now();
+    let idle = now + IDLE_REPAINT_INTERVAL;
+    assert_eq!(
+        schedule_repaint(false, None, Some(now), now),
+        RepaintSchedule::At(idle)
+    );
+    let soon = now + Duration::from_millis(10);
+    assert_eq!(
+        schedule_repaint(false, Some(soon), Some(now), now),
+        RepaintSchedule::At(soon)
+    );
+    let late = now + Duration::from_secs(5);
+    assert_eq!(
+        schedule_repaint(false, Some(late), Some(now), now),
+        RepaintSchedule::At(idle)
+    );
+    assert_eq!(
+        schedule_repaint(false, None, Some(now), idle),
+        RepaintSchedule::Now
+    );
+}
+
+#[test]
+fn throttled_value_refreshes_once_per_interval() {
+    let start = Instant::now();
+    let interval = Duration::from_millis(100);
+    let mut readout = Throttled::new(interval);
+    assert_eq!(readout.get(start, || 1), 1);
+    assert_eq!(readout.get(start + Duration::from_millis(50), || 2), 1);
+    assert_eq!(readout.get(start + interval, || 3), 3);
+    readout.invalidate();
+    assert_eq!(readout.get(start + interval, || 4), 4);
+}
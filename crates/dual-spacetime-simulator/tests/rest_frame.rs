@@ -0,0 +1,106 @@
+use dual_spacetime_simulator::relativistic_view::{
+    ObserverView, VIEW_FLAG_ABERRATION, VIEW_FLAG_REST_FRAME, ViewKinematics,
+};
+use dual_spacetime_simulator::rest_frame::{
+    FRAME_TRANSITION_SECONDS, FrameSwitcher, ReferenceFrame,
+};
+use dual_spacetime_simulator::simulation::Particle;
+use dual_spacetime_simulator::ui_state::DisplaySpace;
+use glam::DVec3;
+use std::time::{Duration, Instant};
+
+const C: f64 = 10.0;
+
+fn frame(velocity: DVec3) -> ReferenceFrame {
+    ReferenceFrame {
+        origin: DVec3::new(5.0, -2.0, 1.0),
+        velocity,
+    }
+}
+
+#[test]
+fn reference_particle_rests_at_its_own_position() {
+    let frame = frame(DVec3::new(6.0, 0.0, 3.0));
+    let (position, velocity) = frame.transform(frame.origin, frame.velocity, C);
+    assert!((position - frame.origin).length() < 1e-9);
+    assert!(velocity.length() < 1e-9);
+}
+
+#[test]
+fn comoving_rod_shows_its_rest_length() {
+    let velocity = DVec3::X * 0.6 * C;
+    let frame = frame(velocity);
+    let (end, end_velocity) = frame.transform(frame.origin + DVec3::X * 4.0, velocity, C);
+    assert!((end - frame.origin - DVec3::X * 5.0).length() < 1e-9);
+    assert!(end_velocity.length() < 1e-9);
+    let (side, _) = frame.transform(frame.origin + DVec3::Y * 4.0, velocity, C);
+    assert!((side - frame.origin - DVec3::Y * 4.0).length() < 1e-9);
+}
+
+#[test]
+fn velocities_compose_relativistically() {
+    let frame = frame(DVec3::X * 0.6 * C);
+    let (_, still) = frame.transform(DVec3::ZERO, DVec3::ZERO, C);
+    assert!((still - DVec3::X * -0.6 * C).length() < 1e-9);
+    let (_, oncoming) = frame.transform(DVec3::ZERO, DVec3::X * -0.6 * C, C);
+    assert!((oncoming.x + 0.6 * 2.0 / 1.36 * C).abs() < 1e-9);
+    assert!(oncoming.length() < C);
+}
+
+#[test]
+fn blends_stay_below_light_speed() {
+    let from = frame(DVec3::X * 0.99 * C);
+    let to = ReferenceFrame {
+        origin: DVec3::ZERO,
+        velocity: DVec3::Y * 0.99 * C,
+    };
+    assert!((from.lerp(to, 0.0, C).velocity - from.velocity).length() < 1e-9);
+    assert!((from.lerp(to, 1.0, C).velocity - to.velocity).length() < 1e-9);
+    let half = from.lerp(to, 0.5, C);
+    assert!(half.velocity.length() < C);
+    assert_eq!(half.origin, from.origin * 0.5);
+}
+
+#[test]
+fn switcher_blends_in_and_out_of_a_rest_frame() {
+    let target = Some(frame(DVec3::X * 0.8 * C));
+    let start = Instant::now();
+    let mut switcher = FrameSwitcher::default();
+    assert_eq!(switcher.update(None, start, C), None);
+
+    switcher.begin(start);
+    let first = switcher.update(target, start, C).unwrap();
+    assert_eq!(first.origin, target.unwrap().origin);
+    assert_eq!(first.velocity, DVec3::ZERO);
+    let done = start + Duration::from_secs_f64(FRAME_TRANSITION_SECONDS);
+    assert_eq!(switcher.update(target, done, C), target);
+
+    switcher.begin(done);
+    let leaving = switcher.update(None, done, C).unwrap();
+    assert!((leaving.velocity - target.unwrap().velocity).length() < 1e-9);
+    let later = done + Duration::from_secs_f64(FRAME_TRANSITION_SECONDS);
+    assert_eq!(switcher.update(None, later, C), None);
+}
+
+#[test]
+fn rest_frame_view_moves_particles_only_in_special_engines() {
+    let particle = Particle::from_kinematics(DVec3::X, DVec3::ZERO, 1.0, [1.0; 4]);
+    let mut view = ObserverView {
+        kinematics: ViewKinematics::Off,
+        aberration: true,
+        reference_frame: Some(frame(DVec3::Y * 0.5 * C)),
+        display_space: DisplaySpace::Position,
+        light_speed: C,
+    };
+    assert_eq!(view.shader_flags(), 0);
+    assert_eq!(view.observe(&particle, DVec3::ZERO).position, DVec3::X);
+    view.kinematics = ViewKinematics::Momentum;
+    view.aberration = false;
+    assert_eq!(view.shader_flags(), VIEW_FLAG_REST_FRAME);
+    assert_ne!(view.observe(&particle, DVec3::ZERO).position, DVec3::X);
+    view.aberration = true;
+    assert_eq!(
+        view.shader_flags(),
+        VIEW_FLAG_ABERRATION | VIEW_FLAG_REST_FRAME
+    );
+}
@@ -0,0 +1,82 @@
+use dual_spacetime_simulator::rotating_frame::{
+    enter_rotating_frame, orbital_angular_velocity, rotating_frame_velocity_update,
+    velocity_in_rotating_frame,
+};
+use dual_spacetime_simulator::simulation::{EngineConfig, G, Particle, SimulationManager};
+use dual_spacetime_simulator::ui_state::{SimulationType, UiState};
+use glam::DVec3;
+
+#[test]
+fn point_at_rest_moves_backwards_in_rotating_frame() {
+    let velocity = velocity_in_rotating_frame(DVec3::X, DVec3::ZERO, 1.0);
+    assert!((velocity - DVec3::Z).length() < 1e-15);
+    assert!((orbital_angular_velocity(DVec3::X, velocity) + 1.0).abs() < 1e-15);
+    assert_eq!(orbital_angular_velocity(DVec3::Y, DVec3::X), 0.0);
+}
+
+#[test]
+fn free_particle_traces_rotated_straight_line() {
+    let omega = 1.0;
+    let dt = 1e-4;
+    let mut particles = vec![Particle::from_kinematics(
+        DVec3::X,
+        DVec3::ZERO,
+        1.0,
+        [1.0; 4],
+    )];
+    enter_rotating_frame(&mut particles, omega);
+    let steps = (std::f64::consts::FRAC_PI_2 / dt).round() as usize;
+    for _ in 0..steps {
+        let velocity = particles[0].velocity;
+        particles[0].position += velocity * dt;
+        rotating_frame_velocity_update(&mut particles, omega, dt);
+    }
+    // At rest in the inertial frame, so a quarter turn of the frame leaves it at +z.
+    let position = particles[0].position;
+    assert!((position - DVec3::Z).length() < 1e-3, "{position:?}");
+    assert!((position.length() - 1.0).abs() < 1e-3);
+}
+
+#[test]
+fn co_rotate_adds_back_running_frame_rotation() {
+    let mut ui = UiState::default();
+    ui.active_frame_angular_velocity = 0.5;
+    ui.co_rotate_with_orbit(DVec3::new(2.0, 0.0, 0.0), DVec3::new(0.0, 0.0, -2.0));
+    assert!((ui.frame_angular_velocity - 1.5).abs() < 1e-15);
+
+    ui.simulation_type = SimulationType::LorentzTransformation;
+    ui.request_reset();
+    assert_eq!(ui.active_frame_angular_velocity, 0.0);
+}
+
+#[test]
+fn circular_orbit_stays_put_in_co_rotating_frame() {
+    let central_mass = 1.0e20;
+    let radius = 1.0e3;
+    let speed = (G * central_mass / radius).sqrt();
+    let start = DVec3::new(radius, 0.0, 0.0);
+    let orbit_velocity = DVec3::new(0.0, 0.0, speed);
+    let omega = orbital_angular_velocity(start, orbit_velocity);
+
+    let manager = SimulationManager::with_config(EngineConfig {
+        frame_angular_velocity: omega,
+        ..Default::default()
+    });
+    manager.reset_from_particles(
+        vec![
+            Particle::from_kinematics(DVec3::ZERO, DVec3::ZERO, central_mass, [1.0; 4]),
+            Particle::from_kinematics(start, orbit_velocity, 1.0, [1.0; 4]),
+        ],
+        SimulationType::Normal,
+        1.0,
+    );
+    assert!(manager.particles()[1].velocity.length() < 1e-9 * speed);
+
+    let dt = 1e-4;
+    let period = std::f64::consts::TAU / omega.abs();
+    for _ in 0..(period / dt) as usize {
+        manager.advance(dt);
+    }
+    let drift = (manager.particles()[1].position - start).length();
+    assert!(drift < 1e-3 * radius, "drift {drift}");
+}
@@ -0,0 +1,48 @@
+use dual_spacetime_simulator::run_target::{
+    RunLengthUnit, RunTarget, reaches_run_end, run_end_snapshot_file_name, run_progress,
+};
+use dual_spacetime_simulator::time_format::SECONDS_PER_YEAR;
+use dual_spacetime_simulator::ui_state::UiState;
+
+#[test]
+fn end_time_needs_enabled_positive_length() {
+    let mut target = RunTarget::default();
+    assert_eq!(target.end_time(), None);
+    target.enabled = true;
+    assert_eq!(target.end_time(), Some(10.0 * SECONDS_PER_YEAR));
+    target.unit = RunLengthUnit::Seconds;
+    target.length = 0.0;
+    assert_eq!(target.end_time(), None);
+}
+
+#[test]
+fn progress_is_clamped_to_the_run() {
+    assert_eq!(run_progress(25.0, 100.0), 0.25);
+    assert_eq!(run_progress(-1.0, 100.0), 0.0);
+    assert_eq!(run_progress(150.0, 100.0), 1.0);
+    assert_eq!(run_progress(0.0, 0.0), 1.0);
+}
+
+#[test]
+fn only_the_step_crossing_the_end_finishes_the_run() {
+    // Ten steps of 0.1 sum to just under 1.0.
+    let time = (0..10).fold(0.0, |time: f64, _| time + 0.1);
+    assert!(time < 1.0);
+    assert!(reaches_run_end(time - 0.1, time, 1.0, 0.1));
+    assert!(!reaches_run_end(0.8, 0.9, 1.0, 0.1));
+    // A run restarted past its end keeps going.
+    assert!(!reaches_run_end(1.0, 1.1, 1.0, 0.1));
+}
+
+#[test]
+fn finishing_pauses_and_requests_the_snapshot_when_wanted() {
+    let mut ui = UiState::default();
+    ui.is_running = true;
+    ui.finish_run_target();
+    assert!(!ui.is_running);
+    assert!(!ui.run_end_snapshot_requested);
+    ui.run_target.snapshot_at_end = true;
+    ui.finish_run_target();
+    assert!(ui.run_end_snapshot_requested);
+    assert_eq!(run_end_snapshot_file_name(42), "run_end_frame_42.zip");
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "gui")]
+
 use glam::Vec3;
 use vulkanvil::OrbitCamera;
 
@@ -1,5 +1,10 @@
+use dual_spacetime_simulator::display_mode::{DisplayMode, FullscreenKind, Resolution};
+use dual_spacetime_simulator::energy_monitor::DEFAULT_ENERGY_DRIFT_THRESHOLD;
+use dual_spacetime_simulator::metric_expr::DerivedMetricDefinition;
+use dual_spacetime_simulator::metrics::RetentionPolicy;
 use dual_spacetime_simulator::settings::AppSettings;
-use dual_spacetime_simulator::ui_state::ParticleDisplayMode;
+use dual_spacetime_simulator::time_format::TimeDisplayUnit;
+use dual_spacetime_simulator::ui_state::{ParticleDisplayMode, ScaleGaugeMode};
 
 #[test]
 fn app_settings_json_roundtrip() {
@@ -11,6 +16,32 @@ fn app_settings_json_roundtrip() {
         link_point_size_to_scale: false,
         mailbox_present_mode: true,
         particle_display_mode: ParticleDisplayMode::Sphere,
+        time_display_unit: TimeDisplayUnit::Years,
+        auto_fit_on_reset: true,
+        scale_gauge_mode: ScaleGaugeMode::Log,
+        gpu_frustum_culling: false,
+        point_budget: 250_000,
+        motion_blur_frames: 12,
+        metrics_retention: RetentionPolicy {
+            capacity: 500,
+            max_age: 1e6,
+        },
+        derived_metrics: vec![DerivedMetricDefinition {
+            name: "virial".to_string(),
+            expression: "2 * kinetic_energy / |potential_energy|".to_string(),
+        }],
+        memory_budget_mb: 512,
+        display_mode: DisplayMode {
+            fullscreen: true,
+            kind: FullscreenKind::Exclusive,
+            monitor: Some("Monitor 2".to_string()),
+            resolution: Some(Resolution {
+                width: 2560,
+                height: 1440,
+                refresh_millihertz: 144_000,
+            }),
+        },
+        ..AppSettings::default()
     };
     let json = serde_json::to_string_pretty(&s).unwrap();
     let back: AppSettings = serde_json::from_str(&json).unwrap();
@@ -18,4 +49,46 @@ fn app_settings_json_roundtrip() {
     assert!((s.window_min_width - back.window_min_width).abs() < f32::EPSILON);
     assert_eq!(s.start_maximized, back.start_maximized);
     assert_eq!(s.particle_display_mode, back.particle_display_mode);
+    assert_eq!(s.time_display_unit, back.time_display_unit);
+    assert_eq!(s.auto_fit_on_reset, back.auto_fit_on_reset);
+    assert_eq!(s.scale_gauge_mode, back.scale_gauge_mode);
+    assert_eq!(s.gpu_frustum_culling, back.gpu_frustum_culling);
+    assert_eq!(s.point_budget, back.point_budget);
+    assert_eq!(s.motion_blur_frames, back.motion_blur_frames);
+    assert_eq!(s.metrics_retention, back.metrics_retention);
+    assert_eq!(s.derived_metrics, back.derived_metrics);
+    assert_eq!(s.memory_budget_mb, back.memory_budget_mb);
+    assert_eq!(s.display_mode, back.display_mode);
+}
+
+#[test]
+fn gpu_frustum_culling_defaults_on_for_older_settings() {
+    let back: AppSettings = serde_json::from_str(r#"{"max_particle_count": 10}"#).unwrap();
+    assert_eq!(back.max_particle_count, 10);
+    assert!(back.gpu_frustum_culling);
+    assert_eq!(back.point_budget, 0);
+    assert_eq!(back.motion_blur_frames, 0);
+    assert_eq!(back.metrics_retention, RetentionPolicy::default());
+    assert!(back.derived_metrics.is_empty());
+}
+
+#[test]
+fn older_settings_use_the_default_font_with_the_cjk_fallback() {
+    let back: AppSettings = serde_json::from_str(r#"{"max_particle_count": 10}"#).unwrap();
+    assert!(back.ui_fonts.is_empty());
+    assert!(back.system_cjk_font);
+}
+
+#[test]
+fn older_settings_start_windowed() {
+    let back: AppSettings = serde_json::from_str(r#"{"max_particle_count": 10}"#).unwrap();
+    assert_eq!(back.display_mode, DisplayMode::default());
+    assert!(!back.display_mode.fullscreen);
+}
+
+#[test]
+fn older_settings_warn_on_energy_drift() {
+    let back: AppSettings = serde_json::from_str(r#"{"max_particle_count": 10}"#).unwrap();
+    assert!(back.energy_warning_enabled);
+    assert_eq!(back.energy_drift_threshold, DEFAULT_ENERGY_DRIFT_THRESHOLD);
 }
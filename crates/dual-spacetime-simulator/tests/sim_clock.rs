@@ -0,0 +1,63 @@
+use dual_spacetime_simulator::sim_clock::{SimulationClock, estimate_eta, estimate_eta_at_rate};
+use std::time::Duration;
+
+#[test]
+fn publish_replaces_the_whole_snapshot() {
+    let clock = SimulationClock::new();
+    let reader = clock.clone();
+    clock.publish(42, 420.0, 60, Duration::from_secs(7));
+    let snapshot = reader.snapshot();
+    assert_eq!(snapshot.frame, 42);
+    assert_eq!(snapshot.simulation_time, 420.0);
+    assert_eq!(snapshot.fps, 60);
+    assert_eq!(snapshot.wall_runtime, Duration::from_secs(7));
+    assert_eq!(snapshot.eta, None);
+}
+
+#[test]
+fn eta_follows_the_target_time() {
+    let clock = SimulationClock::new();
+    clock.set_target_time(Some(1000.0));
+    clock.publish(10, 250.0, 60, Duration::from_secs(10));
+    assert_eq!(clock.snapshot().eta, Some(Duration::from_secs(30)));
+    clock.set_target_time(None);
+    assert_eq!(clock.snapshot().eta, None);
+    assert_eq!(clock.target_time(), None);
+}
+
+#[test]
+fn eta_prefers_the_recent_rate() {
+    let clock = SimulationClock::new();
+    clock.set_target_time(Some(1000.0));
+    clock.set_time_rate(Some(50.0));
+    clock.publish(10, 250.0, 60, Duration::from_secs(10));
+    assert_eq!(clock.snapshot().eta, Some(Duration::from_secs(15)));
+    assert_eq!(clock.snapshot().time_rate, Some(50.0));
+    clock.set_time_rate(Some(0.0));
+    clock.publish(10, 250.0, 60, Duration::from_secs(10));
+    assert_eq!(clock.snapshot().eta, Some(Duration::from_secs(30)));
+}
+
+#[test]
+fn estimate_eta_needs_progress_and_stops_at_zero() {
+    assert_eq!(estimate_eta(0.0, 10.0, Duration::from_secs(5)), None);
+    assert_eq!(estimate_eta(5.0, 10.0, Duration::ZERO), None);
+    assert_eq!(
+        estimate_eta(2.0, 10.0, Duration::from_secs(4)),
+        Some(Duration::from_secs(16))
+    );
+    assert_eq!(
+        estimate_eta(12.0, 10.0, Duration::from_secs(4)),
+        Some(Duration::ZERO)
+    );
+}
+
+#[test]
+fn estimate_eta_at_rate_needs_a_positive_rate() {
+    assert_eq!(estimate_eta_at_rate(2.0, 10.0, 0.0), None);
+    assert_eq!(
+        estimate_eta_at_rate(2.0, 10.0, 4.0),
+        Some(Duration::from_secs(2))
+    );
+    assert_eq!(estimate_eta_at_rate(12.0, 10.0, 0.0), Some(Duration::ZERO));
+}
@@ -0,0 +1,76 @@
+use dual_spacetime_simulator::particle_snapshot::ParticleSnapshot;
+use dual_spacetime_simulator::simulate::{
+    DEFAULT_FRAME_TIME, SimulateOptions, parse_simulate_args, simulate,
+};
+use dual_spacetime_simulator::simulation::{G, Particle};
+use dual_spacetime_simulator::ui_state::SimulationType;
+use glam::DVec3;
+use std::path::PathBuf;
+
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(str::to_string).collect()
+}
+
+#[test]
+fn simulate_options_override_defaults() {
+    assert_eq!(parse_simulate_args(&args("--grid")), Ok(None));
+    let options = parse_simulate_args(&args(
+        "--frames 20 --simulate binary.zip --time-per-frame 0.5 --out final.zip",
+    ))
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+        options,
+        SimulateOptions {
+            scenario: PathBuf::from("binary.zip"),
+            frames: 20,
+            time_per_frame: 0.5,
+            output: Some(PathBuf::from("final.zip")),
+        }
+    );
+    let defaults = parse_simulate_args(&args("--simulate a.zip"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(defaults.time_per_frame, DEFAULT_FRAME_TIME);
+    for line in [
+        "--simulate",
+        "--simulate a.zip --frames",
+        "--simulate a.zip --frames -1",
+        "--simulate a.zip --display glow",
+    ] {
+        assert!(parse_simulate_args(&args(line)).is_err(), "{line}");
+    }
+}
+
+#[test]
+fn simulate_saves_the_advanced_scenario() {
+    let central_mass = 1e10;
+    let particles = vec![
+        Particle::from_kinematics(DVec3::ZERO, DVec3::ZERO, central_mass, [1.0; 4]),
+        Particle::from_kinematics(
+            DVec3::X,
+            DVec3::new(0.0, (G * central_mass).sqrt(), 0.0),
+            1.0,
+            [1.0; 4],
+        ),
+    ];
+    let dir = std::env::temp_dir().join("dual-spacetime-simulator-test");
+    let scenario = dir.join("simulate_input.zip");
+    let output = dir.join("simulate_output.zip");
+    ParticleSnapshot::new(SimulationType::Normal, 1.0, particles.clone())
+        .save(&scenario)
+        .unwrap();
+    let options = SimulateOptions {
+        frames: 10,
+        time_per_frame: 0.01,
+        output: Some(output.clone()),
+        ..SimulateOptions::new(scenario.clone())
+    };
+    simulate(&options).unwrap();
+    let result = ParticleSnapshot::load(&output).unwrap();
+    assert_eq!(result.simulation_type, SimulationType::Normal);
+    assert_eq!(result.particles.len(), 2);
+    assert_ne!(result.particles[1].position, particles[1].position);
+    let _ = std::fs::remove_file(&scenario);
+    let _ = std::fs::remove_file(&output);
+}
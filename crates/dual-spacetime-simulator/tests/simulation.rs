@@ -1,13 +1,14 @@
-use dual_spacetime_simulator::object_input::ObjectInput;
+use dual_spacetime_simulator::object_input::{ObjectInput, RandomClusterOptions};
 use dual_spacetime_simulator::simulation::{
-    EPSILON, G, LIGHT_SPEED, Particle, SimulationManager, clamp_scalar_speed_m_s, clamp_velocity_m_s,
-    max_subluminal_speed_m_s,
+    EPSILON, EngineConfig, G, LIGHT_SPEED, Particle, SimulationManager, clamp_scalar_speed_m_s,
+    clamp_velocity_m_s, max_subluminal_speed_m_s,
 };
 use dual_spacetime_simulator::ui_state::SimulationType as UiSimType;
 use dst_math::gravity::{
     gravitational_potential_at, k_scale_from_light_speed, time_dilation,
 };
-use glam::DVec3;
+use dst_math::spacetime::momentum_from_velocity;
+use glam::{DQuat, DVec3};
 
 fn total_energy(particles: &[Particle]) -> f64 {
     let mut ke = 0.0f64;
@@ -58,8 +59,15 @@ fn create_simulation_lorentz_with_superluminal_velocity_std_stays_finite() {
         radius: 1e9,
         mass_range: (1e28, 1e29),
         velocity_std: LIGHT_SPEED,
+        options: RandomClusterOptions::default(),
     };
-    let state = SimulationManager::create_simulation(ic, UiSimType::LorentzTransformation, 16, 1e10);
+    let state = SimulationManager::create_simulation(
+        ic,
+        UiSimType::LorentzTransformation,
+        16,
+        1e10,
+        &EngineConfig::default(),
+    );
     let particles = match state {
         dual_spacetime_simulator::simulation::SimulationState::LorentzTransformation(s) => {
             s.particles
@@ -82,9 +90,16 @@ fn elliptical_two_body_energy_approximately_conserved_short_run() {
         planetary_speed: 2.0e5,
         planetary_distance: 2.0e11,
     };
-    let state = SimulationManager::create_simulation(ic, UiSimType::Normal, 2, 1e10);
+    let state = SimulationManager::create_simulation(
+        ic,
+        UiSimType::Normal,
+        2,
+        1e10,
+        &EngineConfig::default(),
+    );
     let mgr = SimulationManager {
         state: std::sync::Arc::new(std::sync::RwLock::new(state)),
+        ..Default::default()
     };
     let e0 = {
         let g = mgr.state.read().unwrap();
@@ -117,7 +132,7 @@ fn convert_to_lorentz_finite() {
         [1.0, 1.0, 1.0, 1.0],
     );
     let scale = 1e3;
-    let out = SimulationManager::convert_to_lorentz(vec![p], scale);
+    let out = SimulationManager::convert_to_lorentz(vec![p], scale, LIGHT_SPEED);
     assert!(out[0].velocity.x.is_finite());
     assert!(out[0].velocity.y.is_finite());
     assert!(out[0].velocity.z.is_finite());
@@ -130,10 +145,18 @@ fn speed_of_light_limit_advance_stays_finite() {
         radius: 1e9,
         mass_range: (1e28, 1e29),
         velocity_std: 1e5,
+        options: RandomClusterOptions::default(),
     };
-    let state = SimulationManager::create_simulation(ic, UiSimType::SpeedOfLightLimit, 8, 1e10);
+    let state = SimulationManager::create_simulation(
+        ic,
+        UiSimType::SpeedOfLightLimit,
+        8,
+        1e10,
+        &EngineConfig::default(),
+    );
     let mgr = SimulationManager {
         state: std::sync::Arc::new(std::sync::RwLock::new(state)),
+        ..Default::default()
     };
     for _ in 0..20 {
         mgr.advance(1e3);
@@ -156,6 +179,7 @@ fn speed_of_light_limit_huge_momentum_stays_finite() {
             [1.0, 1.0, 1.0, 1.0],
         )],
         scale,
+        LIGHT_SPEED,
     )[0];
     particle.momentum = DVec3::new(1e30, 0.0, 0.0);
     let state = dual_spacetime_simulator::simulation::SimulationState::SpeedOfLightLimit(
@@ -166,6 +190,7 @@ fn speed_of_light_limit_huge_momentum_stays_finite() {
     );
     let mgr = SimulationManager {
         state: std::sync::Arc::new(std::sync::RwLock::new(state)),
+        ..Default::default()
     };
     for _ in 0..10 {
         mgr.advance(1e3);
@@ -185,10 +210,18 @@ fn clear_removes_all_particles() {
         radius: 1e9,
         mass_range: (1e28, 1e29),
         velocity_std: 1e5,
+        options: RandomClusterOptions::default(),
     };
-    let state = SimulationManager::create_simulation(ic, UiSimType::Normal, 10, 1e10);
+    let state = SimulationManager::create_simulation(
+        ic,
+        UiSimType::Normal,
+        10,
+        1e10,
+        &EngineConfig::default(),
+    );
     let mgr = SimulationManager {
         state: std::sync::Arc::new(std::sync::RwLock::new(state)),
+        ..Default::default()
     };
     assert_eq!(mgr.particle_count(), 10);
     mgr.clear(UiSimType::Normal, 1e10);
@@ -202,10 +235,18 @@ fn remove_particle_at_deletes_index_and_shifts_remaining() {
         radius: 1e9,
         mass_range: (1e28, 1e29),
         velocity_std: 1e5,
+        options: RandomClusterOptions::default(),
     };
-    let state = SimulationManager::create_simulation(ic, UiSimType::Normal, 3, 1e10);
+    let state = SimulationManager::create_simulation(
+        ic,
+        UiSimType::Normal,
+        3,
+        1e10,
+        &EngineConfig::default(),
+    );
     let mgr = SimulationManager {
         state: std::sync::Arc::new(std::sync::RwLock::new(state)),
+        ..Default::default()
     };
     assert_eq!(mgr.particle_count(), 3);
     assert!(mgr.remove_particle_at(1));
@@ -224,6 +265,7 @@ fn dst_gravity_manager(particles: Vec<Particle>, scale: f64) -> SimulationManage
                 dual_spacetime_simulator::simulation::SimulationDstGravity { particles, scale },
             ),
         )),
+        ..Default::default()
     }
 }
 
@@ -239,6 +281,7 @@ fn dst_galaxy_manager(particles: Vec<Particle>, scale: f64) -> SimulationManager
                 },
             ),
         )),
+        ..Default::default()
     }
 }
 
@@ -283,10 +326,18 @@ fn cull_galaxy_by_angle_is_noop_for_other_types() {
         radius: 1e9,
         mass_range: (1e28, 1e29),
         velocity_std: 1e5,
+        options: RandomClusterOptions::default(),
     };
-    let state = SimulationManager::create_simulation(ic, UiSimType::Normal, 4, 1e10);
+    let state = SimulationManager::create_simulation(
+        ic,
+        UiSimType::Normal,
+        4,
+        1e10,
+        &EngineConfig::default(),
+    );
     let mgr = SimulationManager {
         state: std::sync::Arc::new(std::sync::RwLock::new(state)),
+        ..Default::default()
     };
     assert!(mgr.cull_galaxy_by_angle(0.0).is_empty());
     assert_eq!(mgr.particle_count(), 4);
@@ -315,6 +366,43 @@ fn dst_gravity_weak_field_attracts_via_update() {
     assert!(dv.is_finite());
 }
 
+fn gravitomagnetic_pull(velocity_0: DVec3, velocity_1: DVec3) -> f64 {
+    let mass = 1.0e20;
+    let mgr = SimulationManager {
+        state: std::sync::Arc::new(std::sync::RwLock::new(
+            dual_spacetime_simulator::simulation::SimulationState::Gravitomagnetic(
+                dual_spacetime_simulator::simulation::SimulationGravitomagnetic {
+                    particles: vec![
+                        Particle::from_kinematics(DVec3::ZERO, velocity_0, mass, [1.0; 4]),
+                        Particle::from_kinematics(
+                            DVec3::new(0.0, 1.0e3, 0.0),
+                            velocity_1,
+                            mass,
+                            [1.0; 4],
+                        ),
+                    ],
+                    scale: 1.0,
+                },
+            ),
+        )),
+        ..Default::default()
+    };
+    mgr.advance(1.0e-9);
+    mgr.particles()[0].velocity.y - velocity_0.y
+}
+
+#[test]
+fn gravitomagnetic_pull_depends_on_relative_motion() {
+    let v = DVec3::new(0.1 * LIGHT_SPEED, 0.0, 0.0);
+    let at_rest = gravitomagnetic_pull(DVec3::ZERO, DVec3::ZERO);
+    let co_moving = gravitomagnetic_pull(v, v);
+    let counter_moving = gravitomagnetic_pull(v, -v);
+
+    assert!(at_rest > 0.0);
+    assert!((co_moving / at_rest - 0.96).abs() < 1e-6);
+    assert!((counter_moving / at_rest - 1.04).abs() < 1e-6);
+}
+
 #[test]
 fn dst_gravity_updates_proper_time_and_lambda_eff() {
     let scale = 1e10_f64;
@@ -405,10 +493,18 @@ fn dst_gravity_random_sphere_stays_finite_short_run() {
         radius: 1e10,
         mass_range: (1e29, 1e31),
         velocity_std: 1e6,
+        options: RandomClusterOptions::default(),
     };
-    let state = SimulationManager::create_simulation(ic, UiSimType::DstGravity, 16, scale);
+    let state = SimulationManager::create_simulation(
+        ic,
+        UiSimType::DstGravity,
+        16,
+        scale,
+        &EngineConfig::default(),
+    );
     let mgr = SimulationManager {
         state: std::sync::Arc::new(std::sync::RwLock::new(state)),
+        ..Default::default()
     };
     for frame in 1..=25 {
         mgr.advance(10.0);
@@ -432,3 +528,73 @@ fn advance_with_zero_particles_is_noop_for_all_simulation_types() {
         assert_eq!(mgr.particle_count(), 0);
     }
 }
+
+#[test]
+fn reset_assigns_unique_ids_and_keeps_existing_ones() {
+    let mut particles: Vec<Particle> = (0..4)
+        .map(|i| {
+            Particle::from_kinematics(DVec3::new(i as f64, 0.0, 0.0), DVec3::ZERO, 1.0, [1.0; 4])
+        })
+        .collect();
+    particles[2].id = 10;
+    let mgr = SimulationManager::new();
+    mgr.reset_from_particles(particles, UiSimType::Normal, 1.0);
+    let ids: Vec<u64> = mgr.particles().iter().map(|p| p.id).collect();
+    assert_eq!(ids, vec![11, 12, 10, 13]);
+}
+#[test]
+fn switch_simulation_type_keeps_the_lorentz_coordinate_velocity() {
+    let scale = 1e10;
+    let c = LIGHT_SPEED / scale;
+    let velocity = DVec3::new(0.6, 0.2, 0.0) * c;
+    let mgr = SimulationManager::new();
+    mgr.reset_from_particles(
+        vec![Particle::from_kinematics(
+            DVec3::ZERO,
+            velocity,
+            1.0,
+            [1.0; 4],
+        )],
+        UiSimType::LorentzTransformation,
+        scale,
+    );
+    let id = mgr.particles()[0].id;
+    let dt = 1e-3;
+    mgr.advance(dt);
+    let moved = mgr.particles()[0].position;
+    mgr.switch_simulation_type(UiSimType::Normal, scale);
+    let switched = mgr.particles()[0];
+    assert_eq!(switched.id, id);
+    assert_eq!(switched.position, moved);
+    assert!((switched.velocity - moved / dt).length() < c * 1e-9);
+    assert!(switched.velocity.length() < c);
+}
+
+#[test]
+fn switch_simulation_type_rebuilds_momentum_and_s3_orientation() {
+    let scale = 1.0;
+    let position = DVec3::new(1e3, -2e3, 5e2);
+    let velocity = DVec3::new(0.3, 0.0, 0.0) * LIGHT_SPEED;
+    let mgr = SimulationManager::new();
+    mgr.reset_from_particles(
+        vec![Particle::from_kinematics(position, velocity, 2.0, [1.0; 4])],
+        UiSimType::Normal,
+        scale,
+    );
+
+    mgr.switch_simulation_type(UiSimType::SpeedOfLightLimit, scale);
+    let p = mgr.particles()[0];
+    let expected = momentum_from_velocity(velocity, 2.0, LIGHT_SPEED);
+    assert!((p.momentum - expected).length() < expected.length() * 1e-12);
+    assert!((p.velocity - velocity).length() < velocity.length() * 1e-12);
+
+    mgr.switch_simulation_type(UiSimType::DstGalaxy, scale);
+    let p = mgr.particles()[0];
+    assert_ne!(p.orientation, DQuat::IDENTITY);
+    assert!((p.position - position).length() < position.length() * 1e-9);
+
+    mgr.switch_simulation_type(UiSimType::Normal, scale);
+    let p = mgr.particles()[0];
+    assert_eq!(p.momentum, DVec3::ZERO);
+    assert_eq!(p.orientation, DQuat::IDENTITY);
+}
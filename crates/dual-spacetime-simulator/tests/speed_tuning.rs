@@ -0,0 +1,18 @@
+use dual_spacetime_simulator::speed_tuning::{MAX_AUTO_SKIP, tune_skip};
+
+#[test]
+fn skip_grows_when_drawing_faster_than_the_target() {
+    assert_eq!(tune_skip(9, 45.0, 30.0), 14);
+    // At most doubles the steps per drawn frame at a time.
+    assert_eq!(tune_skip(9, 300.0, 30.0), 19);
+    assert_eq!(tune_skip(800, 300.0, 30.0), MAX_AUTO_SKIP);
+}
+
+#[test]
+fn skip_shrinks_when_drawing_too_slowly_and_holds_near_the_target() {
+    assert_eq!(tune_skip(9, 20.0, 30.0), 6);
+    assert_eq!(tune_skip(9, 1.0, 30.0), 4);
+    assert_eq!(tune_skip(0, 1.0, 30.0), 0);
+    assert_eq!(tune_skip(9, 31.0, 30.0), 9);
+    assert_eq!(tune_skip(9, 0.0, 30.0), 9);
+}
@@ -0,0 +1,46 @@
+use dual_spacetime_simulator::split_view::{ViewRect, ViewSide, view_rect, view_side_at};
+
+#[test]
+fn split_divides_the_window_vertically() {
+    let left = view_rect(ViewSide::Primary, 1601, 900, true);
+    let right = view_rect(ViewSide::Secondary, 1601, 900, true);
+    assert_eq!(
+        left,
+        ViewRect {
+            x: 0,
+            y: 0,
+            width: 801,
+            height: 900,
+        }
+    );
+    assert_eq!(
+        right,
+        ViewRect {
+            x: 801,
+            y: 0,
+            width: 800,
+            height: 900,
+        }
+    );
+    assert_eq!(right.center(), (1201.0, 450.0));
+    assert!((right.aspect_ratio() - 800.0 / 900.0).abs() < 1e-6);
+}
+
+#[test]
+fn unsplit_views_span_the_window() {
+    let full = ViewRect {
+        x: 0,
+        y: 0,
+        width: 1600,
+        height: 900,
+    };
+    assert_eq!(view_rect(ViewSide::Primary, 1600, 900, false), full);
+    assert_eq!(view_rect(ViewSide::Secondary, 1600, 900, false), full);
+}
+
+#[test]
+fn cursor_picks_the_view_under_it() {
+    assert_eq!(view_side_at(799.5, 1600, true), ViewSide::Primary);
+    assert_eq!(view_side_at(800.0, 1600, true), ViewSide::Secondary);
+    assert_eq!(view_side_at(1500.0, 1600, false), ViewSide::Primary);
+}
@@ -0,0 +1,38 @@
+use dual_spacetime_simulator::status_bar::{
+    STATUS_MESSAGE_DURATION, StatusBar, ToolMode, intersect_grid_plane,
+};
+use glam::Vec3;
+use std::time::Instant;
+
+#[test]
+fn trace_takes_precedence_over_the_camera_lock() {
+    assert_eq!(ToolMode::current(true, false), ToolMode::Orbit);
+    assert_eq!(ToolMode::current(false, false), ToolMode::Spacecraft);
+    assert_eq!(ToolMode::current(true, true), ToolMode::Trace);
+    assert_eq!(ToolMode::current(false, true), ToolMode::Trace);
+}
+
+#[test]
+fn notifications_expire_and_are_replaced() {
+    let mut status = StatusBar::default();
+    assert!(status.message(Instant::now()).is_none());
+    status.info("Settings saved");
+    let message = status.message(Instant::now()).unwrap();
+    assert_eq!(message.text, "Settings saved");
+    assert!(!message.is_error);
+    let expired = message.posted_at + STATUS_MESSAGE_DURATION;
+    assert!(status.message(expired).is_none());
+
+    status.error("Failed to load particles: bad zip");
+    let message = status.message(Instant::now()).unwrap();
+    assert!(message.is_error);
+    assert!(message.text.starts_with("Failed to load"));
+}
+
+#[test]
+fn grid_plane_intersection_follows_the_ray() {
+    let point = intersect_grid_plane(Vec3::new(1.0, 2.0, 0.0), Vec3::new(1.0, -2.0, 4.0)).unwrap();
+    assert!((point - Vec3::new(1.0, 0.0, 2.0)).length() < 1e-6);
+    assert!(intersect_grid_plane(Vec3::new(0.0, 1.0, 0.0), Vec3::new(5.0, 1.0, 0.0)).is_none());
+    assert!(intersect_grid_plane(Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 2.0, 0.0)).is_none());
+}
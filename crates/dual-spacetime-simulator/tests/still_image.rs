@@ -0,0 +1,108 @@
+use dual_spacetime_simulator::still_image::{
+    STILL_TILE_SIZE, StillSettings, StillTile, VISIBLE_PIXEL_DIFFERENCE, compare_images, load_png,
+    resolve_tile, save_png, still_tiles, tile_clip_transform,
+};
+use glam::Vec4;
+
+#[test]
+fn tiles_cover_the_still_once_supersampled() {
+    let settings = StillSettings {
+        width: 7680,
+        height: 4320,
+        supersampling: 2,
+    };
+    let tiles = still_tiles(settings);
+    assert_eq!(tiles.len(), 8 * 5);
+    assert_eq!(
+        tiles[7],
+        StillTile {
+            x: 7168,
+            y: 0,
+            width: 512,
+            height: 1024,
+        }
+    );
+    assert_eq!(tiles[39].height, 4320 - 4 * 1024);
+    let area: u32 = tiles.iter().map(|tile| tile.width * tile.height).sum();
+    assert_eq!(area, 7680 * 4320);
+    assert!(
+        tiles
+            .iter()
+            .all(|tile| tile.width * 2 <= STILL_TILE_SIZE && tile.height * 2 <= STILL_TILE_SIZE)
+    );
+    let small = StillSettings {
+        width: 640,
+        height: 480,
+        supersampling: 2,
+    };
+    assert_eq!(still_tiles(small).len(), 1);
+}
+
+#[test]
+fn tile_transform_stretches_the_tile_to_the_viewport() {
+    let tile = StillTile {
+        x: 1000,
+        y: 500,
+        width: 500,
+        height: 250,
+    };
+    let transform = tile_clip_transform(tile, 2000, 1000);
+    // Clip-space x grows rightwards and y downwards, as pixel columns and rows do.
+    let to_clip = |px: f32, py: f32| Vec4::new(px / 1000.0 - 1.0, py / 500.0 - 1.0, 0.25, 1.0);
+    let top_left = transform * to_clip(1000.0, 500.0);
+    let bottom_right = transform * to_clip(1500.0, 750.0);
+    assert!((top_left - Vec4::new(-1.0, -1.0, 0.25, 1.0)).length() < 1e-5);
+    assert!((bottom_right - Vec4::new(1.0, 1.0, 0.25, 1.0)).length() < 1e-5);
+    let whole = StillTile {
+        x: 0,
+        y: 0,
+        width: 2000,
+        height: 1000,
+    };
+    assert!(tile_clip_transform(whole, 2000, 1000).abs_diff_eq(glam::Mat4::IDENTITY, 1e-6));
+}
+
+#[test]
+fn resolve_averages_samples_and_drops_alpha() {
+    let tile = StillTile {
+        x: 1,
+        y: 0,
+        width: 1,
+        height: 1,
+    };
+    // A 2×2 block of BGRA samples.
+    let pixels = [
+        10, 20, 30, 255, 20, 20, 30, 255, //
+        30, 20, 31, 255, 40, 21, 30, 0,
+    ];
+    let mut output = vec![0; 2 * 3];
+    resolve_tile(&mut output, 2, tile, &pixels, 2, true);
+    assert_eq!(output, [0, 0, 0, 30, 20, 25]);
+    resolve_tile(&mut output, 2, tile, &pixels, 2, false);
+    assert_eq!(output[3..], [25, 20, 30]);
+}
+
+#[test]
+fn png_round_trips() {
+    let dir = std::env::temp_dir().join("dual-spacetime-simulator-test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("still_round_trip.png");
+    let rgb: Vec<u8> = (0..3 * 2 * 3).collect();
+    save_png(&path, 3, 2, &rgb).unwrap();
+    assert_eq!(load_png(&path).unwrap(), (3, 2, rgb));
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn comparison_weights_channels_by_luma() {
+    let black = [0; 6];
+    let blue = [0, 0, 0, 0, 0, 20];
+    let green = [0, 0, 0, 0, 20, 0];
+    let faint_blue = compare_images(&black, &blue, VISIBLE_PIXEL_DIFFERENCE).unwrap();
+    assert_eq!(faint_blue.differing_pixels, 0);
+    assert!(faint_blue.max_difference > 0.0);
+    let visible_green = compare_images(&black, &green, VISIBLE_PIXEL_DIFFERENCE).unwrap();
+    assert_eq!(visible_green.differing_pixels, 1);
+    assert_eq!(visible_green.differing_fraction(), 0.5);
+    assert!(compare_images(&black, &black[..3], VISIBLE_PIXEL_DIFFERENCE).is_err());
+}
@@ -0,0 +1,12 @@
+use dual_spacetime_simulator::taskbar::{TaskbarProgress, taskbar_progress};
+
+#[test]
+fn batch_sweep_shows_its_fraction_before_pending_exports() {
+    assert_eq!(
+        taskbar_progress(Some((3, 12)), 2),
+        TaskbarProgress::Normal(0.25)
+    );
+    assert_eq!(taskbar_progress(None, 2), TaskbarProgress::Indeterminate);
+    assert_eq!(taskbar_progress(Some((0, 0)), 0), TaskbarProgress::None);
+    assert_eq!(taskbar_progress(None, 0), TaskbarProgress::None);
+}
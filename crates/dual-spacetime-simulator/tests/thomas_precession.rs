@@ -0,0 +1,45 @@
+use dual_spacetime_simulator::thomas_precession::{
+    THOMAS_ORBIT_RADIUS, ThomasPrecession, thomas_precession_per_revolution,
+};
+use glam::DVec3;
+
+#[test]
+fn one_revolution_matches_thomas_precession() {
+    for beta in [0.1, 0.5, 0.8] {
+        let mut precession = ThomasPrecession::new(beta, 3600);
+        for _ in 0..3600 {
+            precession.step();
+        }
+        assert!((precession.revolutions() - 1.0).abs() < 1e-12);
+        let expected = thomas_precession_per_revolution(beta);
+        assert!(
+            (precession.wigner_angle - expected).abs() < 1e-3 * expected.abs(),
+            "beta {beta}: {} vs {expected}",
+            precession.wigner_angle
+        );
+    }
+}
+
+#[test]
+fn speed_stays_constant_and_velocity_follows_the_orbit() {
+    let mut precession = ThomasPrecession::new(0.6, 360);
+    for _ in 0..90 {
+        precession.step();
+    }
+    let velocity = precession.velocity();
+    assert!((velocity.length() - 0.6).abs() < 1e-12);
+    let tangent = DVec3::Y.cross(precession.position()) / THOMAS_ORBIT_RADIUS;
+    assert!((velocity.normalize() - tangent).length() < 1e-9);
+}
+
+#[test]
+fn orientation_turns_retrograde_about_the_orbit_axis() {
+    let mut precession = ThomasPrecession::new(0.5, 720);
+    for _ in 0..100 {
+        precession.step();
+    }
+    let (axis, angle) = precession.orientation().to_axis_angle();
+    assert!(precession.wigner_angle < 0.0);
+    assert!((axis.y.abs() - 1.0).abs() < 1e-9);
+    assert!((axis.y * angle - precession.wigner_angle).abs() < 1e-9);
+}
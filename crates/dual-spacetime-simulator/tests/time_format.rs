@@ -0,0 +1,97 @@
+use dual_spacetime_simulator::time_format::{
+    CalendarEpoch, SECONDS_PER_DAY, SECONDS_PER_MYR, SECONDS_PER_YEAR, TimeDisplayUnit,
+    format_simulation_time, format_time_rate, format_wall_duration,
+};
+use std::time::Duration;
+
+#[test]
+fn day_clock_matches_legacy_format() {
+    let t = 2.0 * SECONDS_PER_DAY + 3.0 * 3600.0 + 4.0 * 60.0 + 5.0;
+    assert_eq!(
+        format_simulation_time(t, TimeDisplayUnit::DayClock, None),
+        "2 03:04:05"
+    );
+    assert_eq!(
+        format_simulation_time(-t, TimeDisplayUnit::DayClock, None),
+        "-2 03:04:05"
+    );
+}
+
+#[test]
+fn fixed_units_scale_seconds() {
+    assert_eq!(
+        format_simulation_time(90.0, TimeDisplayUnit::Seconds, None),
+        "90.000 s"
+    );
+    assert_eq!(
+        format_simulation_time(1.5 * SECONDS_PER_DAY, TimeDisplayUnit::Days, None),
+        "1.500 d"
+    );
+    assert_eq!(
+        format_simulation_time(2.0 * SECONDS_PER_YEAR, TimeDisplayUnit::Years, None),
+        "2.000 yr"
+    );
+    assert_eq!(
+        format_simulation_time(250.0 * SECONDS_PER_MYR, TimeDisplayUnit::Myr, None),
+        "250.000 Myr"
+    );
+    assert!(format_simulation_time(1.0, TimeDisplayUnit::Myr, None).contains('e'));
+}
+
+#[test]
+fn calendar_advances_from_epoch_across_leap_day() {
+    let epoch = CalendarEpoch::new(2024, 2, 28, 12);
+    assert_eq!(
+        format_simulation_time(SECONDS_PER_DAY, TimeDisplayUnit::Calendar, Some(epoch)),
+        "2024-02-29 12:00:00"
+    );
+    assert_eq!(
+        format_simulation_time(
+            2.0 * SECONDS_PER_DAY,
+            TimeDisplayUnit::Calendar,
+            Some(epoch)
+        ),
+        "2024-03-01 12:00:00"
+    );
+    assert_eq!(
+        format_simulation_time(-13.0 * 3600.0, TimeDisplayUnit::Calendar, Some(epoch)),
+        "2024-02-27 23:00:00"
+    );
+}
+
+#[test]
+fn calendar_without_epoch_falls_back_to_day_clock() {
+    assert_eq!(
+        format_simulation_time(SECONDS_PER_DAY, TimeDisplayUnit::Calendar, None),
+        "1 00:00:00"
+    );
+}
+
+#[test]
+fn invalid_epoch_falls_back_to_j2000() {
+    assert_eq!(CalendarEpoch::new(2023, 2, 29, 0), CalendarEpoch::J2000);
+    assert_eq!(CalendarEpoch::new(2024, 13, 1, 0), CalendarEpoch::J2000);
+    assert_eq!(
+        format_simulation_time(0.0, TimeDisplayUnit::Calendar, Some(CalendarEpoch::J2000)),
+        "2000-01-01 12:00:00"
+    );
+}
+
+#[test]
+fn wall_duration_uses_hours_minutes_seconds() {
+    assert_eq!(format_wall_duration(Duration::from_secs(59)), "0:00:59");
+    assert_eq!(format_wall_duration(Duration::from_secs(3_725)), "1:02:05");
+    assert_eq!(
+        format_wall_duration(Duration::from_millis(100_999)),
+        "0:01:40"
+    );
+}
+
+#[test]
+fn time_rate_picks_the_largest_unit_at_least_one() {
+    assert_eq!(format_time_rate(0.0), "0.000 s/s");
+    assert_eq!(format_time_rate(60.0), "60.000 s/s");
+    assert_eq!(format_time_rate(3.5 * SECONDS_PER_DAY), "3.500 d/s");
+    assert_eq!(format_time_rate(2.0 * SECONDS_PER_YEAR), "2.000 yr/s");
+    assert_eq!(format_time_rate(1.5 * SECONDS_PER_MYR), "1.500 Myr/s");
+}
@@ -0,0 +1,78 @@
+use dual_spacetime_simulator::export_writer::ExportWriter;
+use dual_spacetime_simulator::toast::{MAX_TOASTS, TOAST_DURATION, ToastLevel, Toasts};
+use std::time::Instant;
+
+#[test]
+fn posting_beyond_the_limit_drops_the_oldest() {
+    let toasts = Toasts::default();
+    for i in 0..MAX_TOASTS + 2 {
+        toasts.post(ToastLevel::Info, format!("toast {}", i));
+    }
+    let visible = toasts.visible(Instant::now());
+    assert_eq!(visible.len(), MAX_TOASTS);
+    assert_eq!(visible[0].text, "toast 2");
+}
+
+#[test]
+fn toasts_expire_and_can_be_dismissed() {
+    let toasts = Toasts::default();
+    toasts.post(ToastLevel::Warning, "first");
+    toasts.post(ToastLevel::Error, "second");
+    let first = toasts.visible(Instant::now())[0].id;
+    toasts.dismiss(first);
+    let visible = toasts.visible(Instant::now());
+    assert_eq!(visible.len(), 1);
+    assert_eq!(visible[0].level, ToastLevel::Error);
+    assert!(
+        toasts
+            .visible(visible[0].posted_at + TOAST_DURATION)
+            .is_empty()
+    );
+}
+
+#[test]
+fn dismissing_targets_the_toast_not_its_position() {
+    let toasts = Toasts::default();
+    for i in 0..MAX_TOASTS {
+        toasts.post(ToastLevel::Info, format!("toast {}", i));
+    }
+    let shown = toasts.visible(Instant::now());
+    // A background post pushes the oldest out before the click is handled.
+    toasts.post(ToastLevel::Info, "late");
+    toasts.dismiss(shown[1].id);
+    toasts.dismiss(shown[0].id);
+    let texts: Vec<String> = toasts
+        .visible(Instant::now())
+        .into_iter()
+        .map(|toast| toast.text)
+        .collect();
+    assert_eq!(texts, ["toast 2", "toast 3", "late"]);
+}
+
+#[test]
+fn background_threads_post_to_the_shared_queue() {
+    let toasts = Toasts::default();
+    let poster = toasts.clone();
+    std::thread::spawn(move || poster.post(ToastLevel::Success, "checkpoint written"))
+        .join()
+        .unwrap();
+    assert_eq!(toasts.visible(Instant::now())[0].text, "checkpoint written");
+}
+
+#[test]
+fn export_writer_posts_a_toast_per_finished_job() {
+    let toasts = Toasts::default();
+    let mut writer = ExportWriter::with_toasts(toasts.clone());
+    writer.submit("ok.txt".into(), |_| Ok(())).unwrap();
+    writer
+        .submit("bad.txt".into(), |_| {
+            Err(std::io::Error::other("disk full"))
+        })
+        .unwrap();
+    writer.finish();
+    let visible = toasts.visible(Instant::now());
+    assert_eq!(visible.len(), 2);
+    assert_eq!(visible[0].level, ToastLevel::Success);
+    assert_eq!(visible[1].level, ToastLevel::Error);
+    assert!(visible[1].text.contains("disk full"));
+}
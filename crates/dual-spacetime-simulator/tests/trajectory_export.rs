@@ -0,0 +1,36 @@
+use dual_spacetime_simulator::simulation::Particle;
+use dual_spacetime_simulator::trajectory_export::{TRAJECTORY_CSV_HEADER, TrajectoryRecorder};
+use glam::DVec3;
+
+fn particle(id: u64, x: f64) -> Particle {
+    let mut p = Particle::from_kinematics(
+        DVec3::new(x, 0.0, 0.0),
+        DVec3::new(0.0, 2.0, 0.0),
+        1.0,
+        [1.0; 4],
+    );
+    p.id = id;
+    p
+}
+
+#[test]
+fn records_only_tracked_ids_with_time() {
+    let path = std::env::temp_dir()
+        .join("dual-spacetime-simulator-test")
+        .join("trajectories.csv");
+    let mut recorder = TrajectoryRecorder::create(&path, &[3, 1, 3]).unwrap();
+    let particles = vec![particle(1, 0.5), particle(2, 1.0), particle(3, 1.5)];
+    recorder.record(0.0, &particles).unwrap();
+    recorder.record(10.0, &particles[..2]).unwrap();
+    assert_eq!(recorder.rows(), 3);
+    recorder.finish().unwrap();
+
+    let text = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[0], TRAJECTORY_CSV_HEADER);
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[1], "0e0,1,5e-1,0e0,0e0,0e0,2e0,0e0");
+    assert!(lines[2].starts_with("0e0,3,1.5e0,"));
+    assert!(lines[3].starts_with("1e1,1,"));
+    let _ = std::fs::remove_file(&path);
+}
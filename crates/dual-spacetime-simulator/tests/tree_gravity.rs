@@ -0,0 +1,186 @@
+use dual_spacetime_simulator::simulation::{G, Particle, SimulationManager, Summation};
+use dual_spacetime_simulator::tree_gravity::{
+    DEFAULT_OPENING_ANGLE, ForceSolver, MAX_OPENING_ANGLE, MIN_OPENING_ANGLE, Octree, TreeCache,
+    relative_force_error, sample_indices, tree_velocity_update, tune_opening_angle,
+};
+use dual_spacetime_simulator::ui_state::{ComputingUnit, SimulationType, UiState};
+use glam::DVec3;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Particles of random mass in a unit cube, the same on every run.
+fn cloud(count: usize) -> Vec<Particle> {
+    let mut rng = StdRng::seed_from_u64(7);
+    (0..count)
+        .map(|_| {
+            let position = DVec3::new(
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+                rng.random_range(-1.0..1.0),
+            );
+            let mass = rng.random_range(1e8..1e10);
+            Particle::from_kinematics(position, DVec3::ZERO, mass, [1.0; 4])
+        })
+        .collect()
+}
+
+#[test]
+fn tree_converges_to_direct_summation() {
+    let particles = cloud(400);
+    let sample: Vec<usize> = (0..particles.len()).collect();
+    let exact = relative_force_error(&particles, 0.0, &sample);
+    let fine = relative_force_error(&particles, MIN_OPENING_ANGLE, &sample);
+    let coarse = relative_force_error(&particles, MAX_OPENING_ANGLE, &sample);
+    assert!(exact < 1e-12, "{}", exact);
+    assert!(fine < 1e-3, "{}", fine);
+    assert!(fine < coarse, "{} vs {}", fine, coarse);
+}
+
+#[test]
+fn octree_splits_crowded_cells_only() {
+    let particles = cloud(400);
+    let positions: Vec<DVec3> = particles.iter().map(|p| p.position).collect();
+    let masses: Vec<f64> = particles.iter().map(|p| p.mass).collect();
+    assert_eq!(Octree::build(&positions[..8], &masses[..8]).node_count(), 1);
+    assert!(Octree::build(&positions, &masses).node_count() > 8);
+    assert_eq!(Octree::build(&[], &[]).node_count(), 0);
+    // Coincident particles stop splitting at the depth limit.
+    let stacked = vec![DVec3::ONE; 20];
+    assert!(Octree::build(&stacked, &[1.0; 20]).node_count() <= 49);
+}
+
+fn positions_and_masses(particles: &[Particle]) -> (Vec<DVec3>, Vec<f64>) {
+    (
+        particles.iter().map(|p| p.position).collect(),
+        particles.iter().map(|p| p.mass).collect(),
+    )
+}
+
+#[test]
+fn in_place_updates_keep_every_particle_in_one_leaf() {
+    let mut particles = cloud(400);
+    let (positions, masses) = positions_and_masses(&particles);
+    let mut tree = Octree::build(&positions, &masses);
+    let mut rng = StdRng::seed_from_u64(3);
+    for particle in &mut particles {
+        let jitter = DVec3::new(
+            rng.random_range(-0.1..0.1),
+            rng.random_range(-0.1..0.1),
+            rng.random_range(-0.1..0.1),
+        );
+        particle.position = particle.position * 0.8 + jitter;
+    }
+    let (positions, masses) = positions_and_masses(&particles);
+    let moved = tree.update(&positions, &masses).unwrap();
+    assert!(moved > 0 && moved < particles.len(), "{}", moved);
+    // With θ = 0 every cell is opened, so both trees sum every pair exactly once.
+    let fresh = Octree::build(&positions, &masses);
+    for i in 0..particles.len() {
+        let reused = tree.velocity_delta(&positions, &masses, i, 0.0, G, Summation::Naive);
+        let rebuilt = fresh.velocity_delta(&positions, &masses, i, 0.0, G, Summation::Naive);
+        assert!(
+            (reused - rebuilt).length() <= 1e-9 * rebuilt.length(),
+            "{} vs {}",
+            reused,
+            rebuilt
+        );
+    }
+}
+
+#[test]
+fn escapes_and_count_changes_need_a_rebuild() {
+    let particles = cloud(100);
+    let (mut positions, masses) = positions_and_masses(&particles);
+    let mut tree = Octree::build(&positions, &masses);
+    assert_eq!(tree.update(&positions, &masses), Some(0));
+    assert_eq!(tree.update(&positions[..99], &masses[..99]), None);
+    positions[5] = DVec3::X * 10.0;
+    assert_eq!(tree.update(&positions, &masses), None);
+}
+
+#[test]
+fn cache_rebuilds_on_its_interval() {
+    let mut particles = cloud(100);
+    let mut cache = TreeCache::default();
+    assert_eq!(cache.timing(), None);
+    let mut rebuilt = Vec::new();
+    for _ in 0..5 {
+        let mut fresh = particles.clone();
+        tree_velocity_update(&mut fresh, 1e-3, DEFAULT_OPENING_ANGLE, Summation::Naive);
+        cache.velocity_update(
+            &mut particles,
+            1e-3,
+            DEFAULT_OPENING_ANGLE,
+            2,
+            Summation::Naive,
+        );
+        let timing = cache.timing().unwrap();
+        rebuilt.push(timing.rebuilt);
+        assert_eq!(timing.moved, 0);
+        assert!(timing.node_count > 1);
+        for (a, b) in particles.iter().zip(&fresh) {
+            assert_eq!(a.velocity, b.velocity);
+        }
+    }
+    assert_eq!(rebuilt, [true, false, true, false, true]);
+}
+
+#[test]
+fn samples_are_distinct_and_bounded() {
+    let mut sample = sample_indices(100, 64, 3);
+    sample.sort_unstable();
+    sample.dedup();
+    assert_eq!(sample.len(), 64);
+    assert!(sample.iter().all(|&i| i < 100));
+    assert_eq!(sample_indices(10, 64, 3).len(), 10);
+    assert_ne!(sample_indices(100, 8, 1), sample_indices(100, 8, 2));
+}
+
+#[test]
+fn opening_angle_follows_the_target_error() {
+    // Within the dead band.
+    assert_eq!(tune_opening_angle(0.5, 1.05e-3, 1e-3), 0.5);
+    // Error four times too large: θ halves, limited to a factor 1.5 per step.
+    assert!((tune_opening_angle(0.5, 4e-3, 1e-3) - 0.5 / 1.5).abs() < 1e-12);
+    // Error four times too small: θ grows by √4, limited the same way.
+    assert!((tune_opening_angle(0.5, 2.5e-4, 1e-3) - 0.75).abs() < 1e-12);
+    assert!((tune_opening_angle(0.5, 8e-4, 1e-3) - 0.5 * 1.25f64.sqrt()).abs() < 1e-12);
+    assert_eq!(tune_opening_angle(0.5, 0.0, 1e-3), 0.75);
+    assert_eq!(tune_opening_angle(1.0, 0.0, 1e-3), MAX_OPENING_ANGLE);
+    assert_eq!(tune_opening_angle(0.12, 1.0, 1e-3), MIN_OPENING_ANGLE);
+    assert_eq!(tune_opening_angle(0.5, f64::NAN, 1e-3), 0.5);
+}
+
+#[test]
+fn tree_solver_drives_the_newtonian_engine() {
+    let mut ui = UiState::default();
+    ui.active_computing_unit = ComputingUnit::Cpu;
+    assert!(ui.runs_direct_newtonian());
+    assert!(!ui.uses_tree_forces());
+
+    ui.force_solver = ForceSolver::Tree;
+    assert!(!ui.runs_direct_newtonian());
+    assert!(ui.uses_tree_forces());
+    let manager = SimulationManager::with_config(ui.engine_config());
+    manager.reset_from_particles(cloud(200), SimulationType::Normal, 1.0);
+    manager.advance(1e-3);
+    let mut particles = cloud(200);
+    for particle in &mut particles {
+        particle.position += particle.velocity * 1e-3;
+    }
+    tree_velocity_update(
+        &mut particles,
+        1e-3,
+        DEFAULT_OPENING_ANGLE,
+        Summation::Naive,
+    );
+    let error = manager.sample_force_error(DEFAULT_OPENING_ANGLE, 64, 1);
+
+    for (a, b) in manager.particles().iter().zip(&particles) {
+        assert_eq!(a.position, b.position);
+        assert_eq!(a.velocity, b.velocity);
+    }
+    assert!(error > 0.0 && error < 1e-2, "{}", error);
+    ui.force_solver = ForceSolver::Direct;
+    assert!(ui.runs_direct_newtonian());
+}
@@ -0,0 +1,40 @@
+use dual_spacetime_simulator::tutorial::{Tutorial, TutorialAction, TutorialStep};
+
+#[test]
+fn tour_advances_only_on_the_awaited_action() {
+    let mut tutorial = Tutorial::default();
+    assert!(!tutorial.notify(TutorialAction::Next));
+    tutorial.start();
+    assert_eq!(tutorial.progress(), Some((1, TutorialStep::ALL.len())));
+    assert!(!tutorial.notify(TutorialAction::Started));
+    assert!(tutorial.notify(TutorialAction::Next));
+    assert_eq!(tutorial.step(), Some(TutorialStep::OpenObjectInput));
+    assert!(!tutorial.notify(TutorialAction::Next));
+}
+
+#[test]
+fn completing_every_step_ends_the_tour() {
+    let mut tutorial = Tutorial::default();
+    tutorial.start();
+    for step in TutorialStep::ALL {
+        assert_eq!(tutorial.step(), Some(step));
+        assert!(tutorial.notify(step.completed_by()));
+    }
+    assert!(!tutorial.is_active());
+    assert_eq!(tutorial.progress(), None);
+}
+
+#[test]
+fn steps_that_wait_for_buttons_point_at_them() {
+    for step in TutorialStep::ALL {
+        let needs_target = !matches!(
+            step.completed_by(),
+            TutorialAction::Next | TutorialAction::CameraMoved
+        );
+        assert!(!needs_target || step.target().is_some(), "{step:?}");
+    }
+    let mut tutorial = Tutorial::default();
+    tutorial.start();
+    tutorial.stop();
+    assert_eq!(tutorial.step(), None);
+}
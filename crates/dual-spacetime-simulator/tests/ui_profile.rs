@@ -0,0 +1,60 @@
+use dual_spacetime_simulator::ui_profile::{
+    ADVANCED_PROFILE, BEGINNER_PROFILE, DEFAULT_UI_PROFILE, UiProfile, find_ui_profile,
+    is_builtin_ui_profile, ui_profile_names,
+};
+use dual_spacetime_simulator::ui_state::UiState;
+
+#[test]
+fn beginner_profile_hides_advanced_controls() {
+    let mut ui = UiState::default();
+    let beginner = find_ui_profile(&[], BEGINNER_PROFILE).unwrap();
+    ui.is_batch_panel_open = true;
+    ui.apply_ui_profile(&beginner);
+    assert_eq!(ui.ui_profile, BEGINNER_PROFILE);
+    assert!(!ui.show_advanced_controls);
+    assert!(ui.is_simulation_panel_open && ui.is_object_input_panel_open);
+    assert!(!ui.is_batch_panel_open);
+}
+
+#[test]
+fn captured_profile_restores_panels_and_view() {
+    let mut ui = UiState::default();
+    ui.is_groups_panel_open = true;
+    ui.show_minimap = true;
+    ui.lock_camera_up = false;
+    let profile = ui.capture_ui_profile("Lecture");
+    assert!(profile.open_panels.contains(&"Groups".to_string()));
+    ui.save_ui_profile(profile.clone());
+    assert_eq!(ui.ui_profile, "Lecture");
+    assert_eq!(
+        find_ui_profile(&ui.ui_profiles, "Lecture"),
+        Some(profile.clone())
+    );
+
+    let mut other = UiState::default();
+    other.apply_ui_profile(&profile);
+    assert!(other.is_groups_panel_open && other.show_minimap && !other.lock_camera_up);
+    assert_eq!(
+        ui_profile_names(&ui.ui_profiles),
+        [BEGINNER_PROFILE, ADVANCED_PROFILE, "Lecture"]
+    );
+}
+
+#[test]
+fn built_in_profiles_cannot_be_replaced_or_deleted() {
+    let mut ui = UiState::default();
+    ui.save_ui_profile(UiProfile {
+        name: ADVANCED_PROFILE.to_string(),
+        show_advanced: false,
+        ..UiProfile::default()
+    });
+    assert!(ui.ui_profiles.is_empty());
+    assert!(is_builtin_ui_profile(DEFAULT_UI_PROFILE));
+
+    let profile = ui.capture_ui_profile("Demo");
+    ui.save_ui_profile(profile);
+    ui.delete_ui_profile();
+    assert!(ui.ui_profiles.is_empty());
+    assert_eq!(ui.ui_profile, DEFAULT_UI_PROFILE);
+    assert!(ui.show_advanced_controls);
+}
@@ -1,10 +1,11 @@
 use dual_spacetime_simulator::object_input::ObjectInputType;
+use dual_spacetime_simulator::region_selection::DyeInjection;
 use dual_spacetime_simulator::settings::AppSettings;
 use dual_spacetime_simulator::simulation::{LIGHT_SPEED, max_subluminal_speed_m_s};
 use dual_spacetime_simulator::ui_state::{
     ComputingUnit, DEFAULT_ADD_PARTICLE_COUNT, DEFAULT_MAX_FPS, DEFAULT_SATELLITE_COUNT,
-    DEFAULT_SCALE_UI, DEFAULT_SKIP_DRAWING_FRAMES, ParticleDisplayMode, PlacementMode,
-    SimulationType, UiState,
+    DEFAULT_SCALE_UI, DEFAULT_SKIP_DRAWING_FRAMES, ParticleDisplayMode, PlacementMode, Precision,
+    ResetKind, SCALE_GAUGE_MAX, SCALE_GAUGE_MIN, ScaleGaugeMode, SimulationType, UiState,
 };
 use glam::DVec3;
 
@@ -180,14 +181,17 @@ fn simulation_type_gpu_code_matches_all_order() {
 }
 
 #[test]
-fn gpu_computing_available_for_all_types() {
+fn gpu_computing_available_for_types_with_gpu_kernel() {
     let mut ui = UiState::default();
     ui.computing_unit = ComputingUnit::Gpu;
 
     for sim_type in SimulationType::ALL {
         ui.simulation_type = sim_type;
+        assert_eq!(ui.gpu_computing_available(), sim_type.has_gpu_kernel());
+        if !sim_type.has_gpu_kernel() {
+            continue;
+        }
         ui.active_computing_unit = ComputingUnit::Gpu;
-        assert!(ui.gpu_computing_available());
         assert!(ui.uses_gpu_simulation());
 
         ui.active_computing_unit = ComputingUnit::Cpu;
@@ -195,6 +199,25 @@ fn gpu_computing_available_for_all_types() {
     }
 }
 
+#[test]
+fn gravitomagnetic_selection_falls_back_to_cpu() {
+    let mut ui = UiState::default();
+    ui.computing_unit = ComputingUnit::Gpu;
+    ui.active_computing_unit = ComputingUnit::Gpu;
+
+    let previous = ui.simulation_type;
+    ui.simulation_type = SimulationType::Gravitomagnetic;
+    ui.apply_simulation_type_change(previous);
+    assert_eq!(ui.computing_unit, ComputingUnit::Cpu);
+    // The running GPU simulation keeps its unit until the reset.
+    assert!(ui.uses_gpu_simulation());
+    assert!(!ui.can_switch_engine());
+
+    ui.request_reset();
+    assert!(!ui.uses_gpu_simulation());
+    assert_eq!(ui.active_simulation_type(), SimulationType::Gravitomagnetic);
+}
+
 #[test]
 fn simulation_type_change_disables_add_until_reset() {
     let mut ui = UiState::default();
@@ -393,3 +416,110 @@ fn panel_slider_double_click_resets_to_defaults() {
     assert_eq!(ui.add_particle_count, DEFAULT_ADD_PARTICLE_COUNT);
     assert_eq!(ui.satellite_orbit.satellite_count, DEFAULT_SATELLITE_COUNT);
 }
+
+#[test]
+fn scale_gauge_modes_round_trip_and_share_zoom_span() {
+    for mode in ScaleGaugeMode::ALL {
+        for gauge in [SCALE_GAUGE_MIN, DEFAULT_SCALE_UI, 7_500.0, SCALE_GAUGE_MAX] {
+            let back = mode.slider_to_gauge(mode.gauge_to_slider(gauge));
+            assert!((back - gauge).abs() < 1e-6, "{mode} {gauge}");
+        }
+        let range = mode.slider_range();
+        assert!((mode.slider_to_gauge(*range.start()) - SCALE_GAUGE_MIN).abs() < 1e-6);
+        assert!((mode.slider_to_gauge(*range.end()) - SCALE_GAUGE_MAX).abs() < 1e-6);
+    }
+    assert!(ScaleGaugeMode::Log.gauge_to_slider(DEFAULT_SCALE_UI).abs() < 1e-12);
+    assert!((ScaleGaugeMode::Linear.gauge_to_slider(DEFAULT_SCALE_UI) - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn scale_gauge_decade_ticks_lie_inside_slider_range() {
+    for mode in ScaleGaugeMode::ALL {
+        let ticks = mode.decade_ticks();
+        let zooms: Vec<f64> = ticks.iter().map(|&(_, zoom)| zoom).collect();
+        assert_eq!(zooms.len(), 4);
+        assert!((zooms[0] - 0.01).abs() < 1e-12 && (zooms[3] - 10.0).abs() < 1e-9);
+        let range = mode.slider_range();
+        assert!(ticks.iter().all(|(value, _)| range.contains(value)));
+    }
+}
+
+#[test]
+fn effective_precision_is_single_on_gpu_or_cpu_normal_f32() {
+    let mut uis = UiState::default();
+    uis.active_computing_unit = ComputingUnit::Gpu;
+    assert_eq!(uis.effective_precision(), Precision::Single);
+    uis.computing_unit = ComputingUnit::Cpu;
+    uis.active_computing_unit = ComputingUnit::Cpu;
+    assert_eq!(uis.effective_precision(), Precision::Double);
+    uis.precision = Precision::Single;
+    uis.request_reset();
+    assert_eq!(uis.active_precision, Precision::Single);
+    assert_eq!(uis.effective_precision(), Precision::Single);
+    uis.active_simulation_type = SimulationType::DstGravity;
+    assert_eq!(uis.effective_precision(), Precision::Double);
+}
+
+fn state_with_history() -> UiState {
+    let mut uis = UiState::default();
+    uis.frame = 500;
+    uis.auto_fit_on_reset = true;
+    uis.dye_injections.push(DyeInjection {
+        color: [1.0, 0.0, 0.0, 1.0],
+        count: 3,
+        frame: 10,
+    });
+    uis.push_event_log("frame 10: fired".to_string());
+    uis.select_particle(2);
+    uis
+}
+
+#[test]
+fn soft_reset_keeps_diagnostics_history() {
+    let mut uis = state_with_history();
+    uis.request_soft_reset();
+    assert_eq!(uis.reset_kind, ResetKind::Soft);
+    assert!(uis.is_reset_requested);
+    uis.finish_applied_reset(None);
+    assert_eq!(uis.frame, 1);
+    assert!(uis.selected_particle.is_none());
+    assert_eq!(uis.dye_injections.len(), 1);
+    assert_eq!(uis.event_log.len(), 1);
+    assert!(!uis.fit_view_requested);
+}
+
+#[test]
+fn hard_reset_clears_history_and_display_options() {
+    let mut uis = state_with_history();
+    uis.region_color = [0.1, 0.2, 0.3];
+    uis.show_mass_profile_overlay = !UiState::default().show_mass_profile_overlay;
+    uis.request_reset();
+    assert_eq!(uis.reset_kind, ResetKind::Hard);
+    uis.finish_applied_reset(None);
+    assert!(uis.dye_injections.is_empty());
+    assert!(uis.event_log.is_empty());
+    assert!(uis.fit_view_requested);
+    uis.restore_display_defaults();
+    let defaults = UiState::default();
+    assert_eq!(uis.region_color, defaults.region_color);
+    assert_eq!(
+        uis.show_mass_profile_overlay,
+        defaults.show_mass_profile_overlay
+    );
+}
+
+#[test]
+fn cycling_placement_presets_wraps_and_requests_a_reset() {
+    let mut ui = UiState::default();
+    let modes = PlacementMode::ALL;
+    let start = modes.iter().position(|&m| m == ui.placement_mode).unwrap();
+    ui.cycle_placement_preset(1);
+    assert_eq!(ui.placement_mode, modes[(start + 1) % modes.len()]);
+    assert!(ui.is_reset_requested);
+    ui.cycle_placement_preset(-1);
+    ui.cycle_placement_preset(-1);
+    assert_eq!(
+        ui.placement_mode,
+        modes[(start + modes.len() - 1) % modes.len()]
+    );
+}
@@ -0,0 +1,99 @@
+use dual_spacetime_simulator::simulation::Particle;
+use dual_spacetime_simulator::ui_state::{ComputingUnit, SimulationType, UiState};
+use dual_spacetime_simulator::undo_history::{
+    ParticleCheckpoint, UNDO_HISTORY_CAPACITY, UiParameters, UndoEntry, UndoHistory,
+};
+use glam::DVec3;
+
+fn with_time_step(time_per_frame: f64) -> UiParameters {
+    let mut uis = UiState::default();
+    uis.time_per_frame = time_per_frame;
+    uis.parameters()
+}
+
+fn checkpoint() -> ParticleCheckpoint {
+    ParticleCheckpoint {
+        particles: vec![Particle::from_kinematics(
+            DVec3::X,
+            DVec3::ZERO,
+            1.0,
+            [1.0; 4],
+        )],
+        simulation_type: SimulationType::Normal,
+        computing_unit: ComputingUnit::Cpu,
+        scale: 1.0,
+        frame: 42,
+        simulation_time: 420.0,
+        simulation_epoch: None,
+        cosmic_time: None,
+    }
+}
+
+#[test]
+fn drag_is_recorded_as_one_step_on_release() {
+    let mut history = UndoHistory::new();
+    history.record_frame(with_time_step(10.0), &with_time_step(11.0), true, None);
+    history.record_frame(with_time_step(11.0), &with_time_step(12.0), true, None);
+    assert!(!history.can_undo());
+    history.record_frame(with_time_step(12.0), &with_time_step(12.0), false, None);
+    let entry = history.take_undo().unwrap();
+    assert_eq!(entry.parameters.time_per_frame, 10.0);
+    assert!(entry.particles.is_none());
+    assert!(!history.can_undo());
+}
+
+#[test]
+fn unchanged_frames_and_round_trips_record_nothing() {
+    let mut history = UndoHistory::new();
+    history.record_frame(with_time_step(10.0), &with_time_step(10.0), false, None);
+    history.record_frame(with_time_step(10.0), &with_time_step(11.0), true, None);
+    history.record_frame(with_time_step(11.0), &with_time_step(10.0), false, None);
+    assert!(!history.can_undo());
+}
+
+#[test]
+fn checkpoint_is_recorded_immediately_and_new_steps_clear_redo() {
+    let mut history = UndoHistory::new();
+    let parameters = with_time_step(10.0);
+    history.record_frame(parameters.clone(), &parameters, true, Some(checkpoint()));
+    let entry = history.take_undo().unwrap();
+    assert_eq!(entry.particles.as_ref().unwrap().frame, 42);
+    history.push_redo(entry);
+    assert!(history.can_redo());
+    history.push(UndoEntry {
+        parameters,
+        particles: None,
+    });
+    assert!(!history.can_redo());
+}
+
+#[test]
+fn history_drops_oldest_beyond_capacity() {
+    let mut history = UndoHistory::new();
+    for i in 0..UNDO_HISTORY_CAPACITY + 5 {
+        history.push(UndoEntry {
+            parameters: with_time_step(i as f64),
+            particles: None,
+        });
+    }
+    let mut count = 0;
+    let mut oldest = None;
+    while let Some(entry) = history.take_undo() {
+        oldest = Some(entry.parameters.time_per_frame);
+        count += 1;
+    }
+    assert_eq!(count, UNDO_HISTORY_CAPACITY);
+    assert_eq!(oldest, Some(5.0));
+}
+
+#[test]
+fn parameters_round_trip_through_ui_state() {
+    let mut uis = UiState::default();
+    let saved = uis.parameters();
+    uis.time_per_frame = 1234.0;
+    uis.add_center = DVec3::ONE;
+    uis.random_sphere.radius *= 2.0;
+    assert_ne!(uis.parameters(), saved);
+    uis.apply_parameters(saved.clone());
+    assert_eq!(uis.parameters(), saved);
+}
@@ -0,0 +1,119 @@
+use dual_spacetime_simulator::simulation::{EngineConfig, G, Particle, Summation};
+use dual_spacetime_simulator::ui_state::{Precision, SimulationType};
+use dual_spacetime_simulator::verification::{
+    DeterminismAudit, DoubleDouble, MAX_VERIFICATION_PARTICLES, ReferenceNewtonian,
+    VerificationJob, audit_determinism, verify_normal_engine,
+};
+use glam::DVec3;
+use std::sync::atomic::{AtomicBool, AtomicU32};
+
+fn binary() -> Vec<Particle> {
+    let central_mass = 1e10;
+    vec![
+        Particle::from_kinematics(DVec3::ZERO, DVec3::ZERO, central_mass, [1.0; 4]),
+        Particle::from_kinematics(
+            DVec3::X,
+            DVec3::new(0.0, (G * central_mass).sqrt(), 0.0),
+            1.0,
+            [1.0; 4],
+        ),
+    ]
+}
+
+#[test]
+fn double_double_keeps_bits_lost_in_f64() {
+    let one = DoubleDouble::from(1.0);
+    let tiny = DoubleDouble::from(1e-20);
+    assert_eq!((one + tiny - one).to_f64(), 1e-20);
+    let two = DoubleDouble::from(2.0);
+    let root = two.sqrt();
+    assert!((root * root - two).to_f64().abs() < 1e-30);
+    let third = one / DoubleDouble::from(3.0);
+    assert!((third * DoubleDouble::from(3.0) - one).to_f64().abs() < 1e-30);
+}
+
+#[test]
+fn reference_engine_matches_normal_engine_to_rounding() {
+    let report = VerificationJob::verify(binary(), 0.01, 200, Summation::Naive, Precision::Double)
+        .join()
+        .unwrap();
+    assert_eq!(report.steps, 200);
+    assert!(report.max_position_error < 1e-12);
+    assert!(report.max_velocity_error < 1e-12);
+    assert!((report.fast_energy_drift - report.reference_energy_drift).abs() < 1e-9);
+}
+
+#[test]
+fn reference_step_moves_the_orbiter() {
+    let particles = binary();
+    let mut reference = ReferenceNewtonian::new(&particles);
+    reference.step(0.1);
+    let stepped = reference.to_particles(&particles);
+    assert!((stepped[1].position.y - particles[1].velocity.y * 0.1).abs() < 1e-15);
+    assert!(stepped[1].velocity.x < 0.0);
+    assert_eq!(stepped[1].id, particles[1].id);
+}
+
+#[test]
+fn verification_rejects_empty_and_oversized_systems() {
+    let verify = |particles: &[Particle]| {
+        let completed = AtomicU32::new(0);
+        let abort = AtomicBool::new(false);
+        verify_normal_engine(
+            particles,
+            0.01,
+            1,
+            Summation::Naive,
+            Precision::Double,
+            &completed,
+            &abort,
+        )
+    };
+    assert!(verify(&[]).is_err());
+    let crowd: Vec<Particle> = (0..=MAX_VERIFICATION_PARTICLES)
+        .map(|i| Particle::from_kinematics(DVec3::X * i as f64, DVec3::ZERO, 1.0, [1.0; 4]))
+        .collect();
+    assert!(verify(&crowd).is_err());
+}
+
+#[test]
+fn aborted_verification_reports_frames_run() {
+    let completed = AtomicU32::new(0);
+    let report = verify_normal_engine(
+        &binary(),
+        0.01,
+        200,
+        Summation::Naive,
+        Precision::Double,
+        &completed,
+        &AtomicBool::new(true),
+    )
+    .unwrap();
+    assert_eq!(report.steps, 0);
+    assert_eq!(report.max_position_error, 0.0);
+}
+
+fn audit(steps: u32) -> DeterminismAudit {
+    DeterminismAudit {
+        simulation_type: SimulationType::Normal,
+        scale: 1.0,
+        time_per_frame: 0.01,
+        steps,
+        tolerance: 0.0,
+        config: EngineConfig::default(),
+    }
+}
+
+#[test]
+fn identical_runs_do_not_diverge() {
+    let report = VerificationJob::audit(binary(), audit(50)).join().unwrap();
+    assert_eq!(report.steps, 50);
+    assert_eq!(report.divergence, None);
+}
+
+#[test]
+fn determinism_audit_rejects_empty_systems() {
+    let completed = AtomicU32::new(0);
+    let abort = AtomicBool::new(false);
+    assert!(audit_determinism(&[], &audit(1), &completed, &abort).is_err());
+}
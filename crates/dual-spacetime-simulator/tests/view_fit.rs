@@ -0,0 +1,51 @@
+use dual_spacetime_simulator::simulation::Particle;
+use dual_spacetime_simulator::ui_state::{
+    DEFAULT_SCALE_UI, SCALE_GAUGE_MAX, SCALE_GAUGE_MIN, particle_visual_scale_factor,
+};
+use dual_spacetime_simulator::view_fit::{
+    FIT_VIEW_MARGIN, FIT_VIEW_RADIUS, fit_camera_distance, fit_scale_gauge,
+    particle_bounding_sphere,
+};
+use glam::DVec3;
+
+fn particle_at(position: DVec3, alpha: f32) -> Particle {
+    Particle::from_kinematics(position, DVec3::ZERO, 1.0, [1.0, 1.0, 1.0, alpha])
+}
+
+#[test]
+fn bounding_sphere_ignores_dead_particles() {
+    let particles = vec![
+        particle_at(DVec3::new(-1.0, 0.0, 0.0), 1.0),
+        particle_at(DVec3::new(3.0, 0.0, 0.0), 1.0),
+        particle_at(DVec3::new(100.0, 100.0, 100.0), 0.0),
+    ];
+    let sphere = particle_bounding_sphere(&particles).unwrap();
+    assert!((sphere.center - DVec3::new(1.0, 0.0, 0.0)).length() < 1e-12);
+    assert!((sphere.radius - 2.0).abs() < 1e-12);
+    assert!(particle_bounding_sphere(&[particle_at(DVec3::ONE, 0.0)]).is_none());
+}
+
+#[test]
+fn fitted_gauge_maps_radius_to_fit_view_radius() {
+    for radius in [0.5, 1.5, 4.0, 20.0] {
+        let gauge = fit_scale_gauge(radius);
+        let visual = radius * particle_visual_scale_factor(gauge) as f64;
+        assert!((visual - FIT_VIEW_RADIUS).abs() < 1e-4, "radius {radius}");
+    }
+}
+
+#[test]
+fn fitted_gauge_stays_within_slider_range() {
+    assert_eq!(fit_scale_gauge(1e-12), SCALE_GAUGE_MAX);
+    assert_eq!(fit_scale_gauge(1e12), SCALE_GAUGE_MIN);
+    assert_eq!(fit_scale_gauge(0.0), DEFAULT_SCALE_UI);
+    assert_eq!(fit_scale_gauge(f64::NAN), DEFAULT_SCALE_UI);
+}
+
+#[test]
+fn camera_distance_fits_sphere_in_field_of_view() {
+    let fov = std::f32::consts::FRAC_PI_2;
+    let distance = fit_camera_distance(1.0, fov);
+    let expected = std::f32::consts::SQRT_2 * FIT_VIEW_MARGIN;
+    assert!((distance - expected).abs() < 1e-5);
+}
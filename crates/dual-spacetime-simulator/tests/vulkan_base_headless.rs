@@ -1,5 +1,7 @@
 //! Requires Vulkan loader + compatible GPU.
 
+#![cfg(feature = "gui")]
+
 mod common;
 
 use ash::vk;
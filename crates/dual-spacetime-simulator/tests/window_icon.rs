@@ -0,0 +1,13 @@
+use dual_spacetime_simulator::window_icon::{WINDOW_ICON_SIZE, window_icon_rgba};
+
+#[test]
+fn icon_is_an_opaque_disc_with_a_bright_center() {
+    let size = WINDOW_ICON_SIZE as usize;
+    let rgba = window_icon_rgba(WINDOW_ICON_SIZE);
+    assert_eq!(rgba.len(), size * size * 4);
+    let pixel = |x: usize, y: usize| &rgba[(y * size + x) * 4..][..4];
+    assert_eq!(pixel(0, 0)[3], 0);
+    let center = pixel(size / 2, size / 2);
+    assert_eq!(center[3], 255);
+    assert!(center[0] > 200 && center[1] > 180);
+}
@@ -0,0 +1,102 @@
+use dual_spacetime_simulator::relativistic_view::ViewKinematics;
+use dual_spacetime_simulator::simulation::Particle;
+use dual_spacetime_simulator::ui_state::{SimulationType, UiState};
+use dual_spacetime_simulator::worldline::{
+    WORLDLINE_INTERVAL, WORLDLINE_MAX_EVENTS, Worldline, boost_event, record_worldlines,
+};
+use glam::DVec3;
+
+const C: f64 = 10.0;
+
+fn particle(id: u64, position: DVec3, velocity: DVec3) -> Particle {
+    let mut particle = Particle::from_kinematics(position, velocity, 1.0, [1.0; 4]);
+    particle.id = id;
+    particle
+}
+
+#[test]
+fn moving_clock_accumulates_less_proper_time() {
+    let velocity = DVec3::X * 0.6 * C;
+    let mut traveller = Worldline::new(1, [1.0; 4]);
+    for step in 0..=10 {
+        let t = step as f64;
+        traveller.record(t, velocity * t, velocity, C);
+    }
+    assert_eq!(traveller.elapsed_time(), 10.0);
+    assert!((traveller.elapsed_proper_time() - 8.0).abs() < 1e-12);
+    assert_eq!(traveller.events.last().unwrap().x, 60.0);
+
+    let mut long = Worldline::new(2, [1.0; 4]);
+    for step in 0..WORLDLINE_MAX_EVENTS + 3 {
+        long.record(step as f64, DVec3::ZERO, DVec3::ZERO, C);
+    }
+    assert_eq!(long.events.len(), WORLDLINE_MAX_EVENTS);
+    assert_eq!(long.events[0].t, 3.0);
+}
+
+#[test]
+fn recording_follows_the_tracked_ids() {
+    let particles = [
+        particle(1, DVec3::X, DVec3::ZERO),
+        particle(2, DVec3::Y, DVec3::X),
+    ];
+    let mut worldlines = Vec::new();
+    record_worldlines(
+        &mut worldlines,
+        &[2, 7],
+        &particles,
+        ViewKinematics::Off,
+        0.0,
+        C,
+    );
+    assert_eq!(worldlines.len(), 1);
+    assert_eq!(worldlines[0].id, 2);
+    record_worldlines(
+        &mut worldlines,
+        &[1, 2],
+        &particles,
+        ViewKinematics::Off,
+        1.0,
+        C,
+    );
+    assert_eq!(worldlines.len(), 2);
+    assert_eq!(worldlines[0].events.len(), 2);
+    assert_eq!(worldlines[1].events.len(), 1);
+    record_worldlines(
+        &mut worldlines,
+        &[1],
+        &particles,
+        ViewKinematics::Off,
+        2.0,
+        C,
+    );
+    assert_eq!(worldlines.len(), 1);
+    assert_eq!(worldlines[0].id, 1);
+}
+
+#[test]
+fn boost_preserves_the_interval_and_rests_the_moving_frame() {
+    let (ct, x, beta) = (5.0, 3.0, 0.6);
+    let (ct_boosted, x_boosted) = boost_event(ct, x, beta);
+    let interval = ct * ct - x * x;
+    assert!((ct_boosted * ct_boosted - x_boosted * x_boosted - interval).abs() < 1e-12);
+    let (_, x_at_rest) = boost_event(10.0, 10.0 * beta, beta);
+    assert!(x_at_rest.abs() < 1e-12);
+}
+
+#[test]
+fn worldlines_sample_only_in_special_engines() {
+    let mut uis = UiState::default();
+    uis.worldline_recording = true;
+    uis.track_trajectory_id(1);
+    uis.active_simulation_type = SimulationType::Normal;
+    assert!(!uis.worldline_sample_due());
+    uis.active_simulation_type = SimulationType::LorentzTransformation;
+    assert!(uis.worldline_sample_due());
+    uis.worldline_frame = Some(uis.frame);
+    assert!(!uis.worldline_sample_due());
+    uis.frame += WORLDLINE_INTERVAL;
+    assert!(uis.worldline_sample_due());
+    uis.clear_worldlines();
+    assert!(uis.worldline_frame.is_none());
+}
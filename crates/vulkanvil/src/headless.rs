@@ -0,0 +1,109 @@
+use ash::{Device, Entry, Instance, vk};
+use gpu_allocator::vulkan::{Allocator, AllocatorCreateDesc};
+use std::ffi::CStr;
+use std::sync::{Arc, Mutex};
+
+/// Instance, device and allocator without a window, surface or swapchain, for rendering
+/// into offscreen images on machines without a display server.
+pub struct HeadlessBase {
+    pub entry: Entry,
+    pub instance: Instance,
+    pub physical_device: vk::PhysicalDevice,
+    pub device: Device,
+    pub graphics_queue: vk::Queue,
+    pub graphics_queue_family: u32,
+    pub allocator: Option<Arc<Mutex<Allocator>>>,
+}
+
+impl HeadlessBase {
+    /// Creates a device on the first physical device with a graphics queue. Unlike
+    /// [`crate::VulkanBase::new`] this reports failures instead of panicking, so callers
+    /// can explain a missing loader or driver.
+    pub fn new(app_name: &CStr, app_version: u32) -> Result<Self, String> {
+        let entry = unsafe { Entry::load() }.map_err(|e| format!("Failed to load Vulkan: {e}"))?;
+
+        let app_info = vk::ApplicationInfo::default()
+            .application_name(app_name)
+            .application_version(app_version)
+            .engine_name(c"No Engine")
+            .engine_version(vk::make_api_version(0, 1, 0, 0))
+            .api_version(vk::API_VERSION_1_2);
+        let instance_ci = vk::InstanceCreateInfo::default().application_info(&app_info);
+        let instance = unsafe { entry.create_instance(&instance_ci, None) }
+            .map_err(|e| format!("Failed to create Vulkan instance: {e}"))?;
+
+        let Some((physical_device, queue_family)) = pick_graphics_device(&instance) else {
+            unsafe { instance.destroy_instance(None) };
+            return Err("No Vulkan device with a graphics queue found".to_string());
+        };
+
+        let queue_priorities = [1.0f32];
+        let queue_ci = vk::DeviceQueueCreateInfo::default()
+            .queue_family_index(queue_family)
+            .queue_priorities(&queue_priorities);
+        let queue_cis = [queue_ci];
+        let device_ci = vk::DeviceCreateInfo::default().queue_create_infos(&queue_cis);
+        let device = match unsafe { instance.create_device(physical_device, &device_ci, None) } {
+            Ok(device) => device,
+            Err(e) => {
+                unsafe { instance.destroy_instance(None) };
+                return Err(format!("Failed to create logical device: {e}"));
+            }
+        };
+        let graphics_queue = unsafe { device.get_device_queue(queue_family, 0) };
+
+        let allocator = match Allocator::new(&AllocatorCreateDesc {
+            instance: instance.clone(),
+            device: device.clone(),
+            physical_device,
+            debug_settings: Default::default(),
+            buffer_device_address: false,
+            allocation_sizes: Default::default(),
+        }) {
+            Ok(allocator) => allocator,
+            Err(e) => {
+                unsafe {
+                    device.destroy_device(None);
+                    instance.destroy_instance(None);
+                }
+                return Err(format!("Failed to create GPU allocator: {e}"));
+            }
+        };
+
+        Ok(Self {
+            entry,
+            instance,
+            physical_device,
+            device,
+            graphics_queue,
+            graphics_queue_family: queue_family,
+            allocator: Some(Arc::new(Mutex::new(allocator))),
+        })
+    }
+}
+
+impl Drop for HeadlessBase {
+    /// Releases the allocator, device and instance in reverse creation order.
+    fn drop(&mut self) {
+        unsafe {
+            if let Err(err) = self.device.device_wait_idle() {
+                eprintln!("HeadlessBase::drop device_wait_idle failed: {err:?}");
+            }
+            drop(self.allocator.take());
+            self.device.destroy_device(None);
+            self.instance.destroy_instance(None);
+        }
+    }
+}
+
+/// Returns the first physical device and queue family that supports graphics.
+fn pick_graphics_device(instance: &Instance) -> Option<(vk::PhysicalDevice, u32)> {
+    let devices = unsafe { instance.enumerate_physical_devices() }.ok()?;
+    devices.into_iter().find_map(|pd| {
+        let queue_families = unsafe { instance.get_physical_device_queue_family_properties(pd) };
+        queue_families
+            .iter()
+            .position(|qf| qf.queue_flags.contains(vk::QueueFlags::GRAPHICS))
+            .map(|i| (pd, i as u32))
+    })
+}
@@ -31,6 +31,12 @@ impl InputState {
         self.held.contains(&code)
     }
 
+    /// True while any key is held, e.g. to keep redrawing during keyboard camera motion.
+    #[inline]
+    pub fn any_held(&self) -> bool {
+        !self.held.is_empty()
+    }
+
     /// True once on the frame a key was first pressed.
     #[inline]
     pub fn just_pressed(&self, code: KeyCode) -> bool {
@@ -1,10 +1,11 @@
 //! Shared Vulkan foundation for the workspace's renderers: instance/device/swapchain
-//! setup, GPU buffer/image allocation helpers, shader module creation, orbit camera,
-//! and keyboard camera controls.
+//! setup (or a surface-less device for offscreen rendering), GPU buffer/image allocation
+//! helpers, shader module creation, orbit camera, and keyboard camera controls.
 
 pub mod base;
 pub mod buffer;
 pub mod camera;
+pub mod headless;
 pub mod input;
 pub mod shader;
 
@@ -17,6 +18,7 @@ pub use buffer::{
     select_depth_format,
 };
 pub use camera::*;
+pub use headless::HeadlessBase;
 #[cfg(feature = "egui")]
 pub use spacecraft_markers::{
     draw_spacecraft_steer_marker, draw_spacecraft_yaw_steer_marker,